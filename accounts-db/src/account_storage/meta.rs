@@ -3,7 +3,10 @@ use {
         account_info::AccountInfo,
         accounts_hash::AccountHash,
         append_vec::AppendVecStoredAccountMeta,
-        tiered_storage::hot::{HotAccount, HotAccountMeta},
+        tiered_storage::{
+            cold::ColdAccount,
+            hot::{HotAccount, HotAccountMeta},
+        },
     },
     solana_sdk::{account::ReadableAccount, hash::Hash, pubkey::Pubkey, stake_history::Epoch},
 };
@@ -20,6 +23,7 @@ lazy_static! {
 pub enum StoredAccountMeta<'storage> {
     AppendVec(AppendVecStoredAccountMeta<'storage>),
     Hot(HotAccount<'storage, HotAccountMeta>),
+    Cold(ColdAccount<'storage>),
 }
 
 impl<'storage> StoredAccountMeta<'storage> {
@@ -27,6 +31,7 @@ impl<'storage> StoredAccountMeta<'storage> {
         match self {
             Self::AppendVec(av) => av.pubkey(),
             Self::Hot(hot) => hot.address(),
+            Self::Cold(cold) => cold.address(),
         }
     }
 
@@ -34,7 +39,7 @@ impl<'storage> StoredAccountMeta<'storage> {
         match self {
             Self::AppendVec(av) => av.hash(),
             // tiered-storage has deprecated the use of AccountHash
-            Self::Hot(_) => &DEFAULT_ACCOUNT_HASH,
+            Self::Hot(_) | Self::Cold(_) => &DEFAULT_ACCOUNT_HASH,
         }
     }
 
@@ -42,6 +47,7 @@ impl<'storage> StoredAccountMeta<'storage> {
         match self {
             Self::AppendVec(av) => av.stored_size(),
             Self::Hot(hot) => hot.stored_size(),
+            Self::Cold(cold) => cold.stored_size(),
         }
     }
 
@@ -49,6 +55,7 @@ impl<'storage> StoredAccountMeta<'storage> {
         match self {
             Self::AppendVec(av) => av.offset(),
             Self::Hot(hot) => AccountInfo::reduced_offset_to_offset(hot.index().0),
+            Self::Cold(cold) => AccountInfo::reduced_offset_to_offset(cold.index().0),
         }
     }
 
@@ -56,6 +63,7 @@ impl<'storage> StoredAccountMeta<'storage> {
         match self {
             Self::AppendVec(av) => av.data(),
             Self::Hot(hot) => hot.data(),
+            Self::Cold(cold) => cold.data(),
         }
     }
 
@@ -63,23 +71,24 @@ impl<'storage> StoredAccountMeta<'storage> {
         match self {
             Self::AppendVec(av) => av.data_len() as usize,
             Self::Hot(hot) => hot.data().len(),
+            Self::Cold(cold) => cold.data().len(),
         }
     }
 
     pub fn meta(&self) -> &StoredMeta {
         match self {
             Self::AppendVec(av) => av.meta(),
-            // Hot account does not support this API as it does not
+            // Tiered accounts do not support this API as they do not
             // use the same in-memory layout as StoredMeta.
-            Self::Hot(_) => unreachable!(),
+            Self::Hot(_) | Self::Cold(_) => unreachable!(),
         }
     }
 
     pub(crate) fn sanitize(&self) -> bool {
         match self {
             Self::AppendVec(av) => av.sanitize(),
-            // Hot account currently doesn't have the concept of sanitization.
-            Self::Hot(_) => unimplemented!(),
+            // Tiered accounts currently don't have the concept of sanitization.
+            Self::Hot(_) | Self::Cold(_) => unimplemented!(),
         }
     }
 }
@@ -89,30 +98,35 @@ impl<'storage> ReadableAccount for StoredAccountMeta<'storage> {
         match self {
             Self::AppendVec(av) => av.lamports(),
             Self::Hot(hot) => hot.lamports(),
+            Self::Cold(cold) => cold.lamports(),
         }
     }
     fn data(&self) -> &[u8] {
         match self {
             Self::AppendVec(av) => av.data(),
             Self::Hot(hot) => hot.data(),
+            Self::Cold(cold) => cold.data(),
         }
     }
     fn owner(&self) -> &Pubkey {
         match self {
             Self::AppendVec(av) => av.owner(),
             Self::Hot(hot) => hot.owner(),
+            Self::Cold(cold) => cold.owner(),
         }
     }
     fn executable(&self) -> bool {
         match self {
             Self::AppendVec(av) => av.executable(),
             Self::Hot(hot) => hot.executable(),
+            Self::Cold(cold) => cold.executable(),
         }
     }
     fn rent_epoch(&self) -> Epoch {
         match self {
             Self::AppendVec(av) => av.rent_epoch(),
             Self::Hot(hot) => hot.rent_epoch(),
+            Self::Cold(cold) => cold.rent_epoch(),
         }
     }
 }
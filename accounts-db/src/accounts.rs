@@ -26,8 +26,11 @@ use {
     },
     std::{
         cmp::Reverse,
-        collections::{BinaryHeap, HashSet},
-        ops::RangeBounds,
+        collections::{BinaryHeap, HashMap, HashSet},
+        ops::{
+            Bound::{Excluded, Unbounded},
+            RangeBounds,
+        },
         sync::{
             atomic::{AtomicUsize, Ordering},
             Arc, Mutex,
@@ -474,6 +477,62 @@ impl Accounts {
         Self::maybe_abort_scan(result, &config)
     }
 
+    /// Loads up to `limit` accounts passing `filter` in pubkey order, starting after `after`.
+    /// If `index_key` is Some, only the accounts in that secondary index are considered.
+    ///
+    /// Also returns whether more matching accounts follow the returned page.
+    pub fn load_page_with_filter<F: Fn(&AccountSharedData) -> bool>(
+        &self,
+        ancestors: &Ancestors,
+        bank_id: BankId,
+        index_key: Option<&IndexKey>,
+        filter: F,
+        after: Option<&Pubkey>,
+        limit: usize,
+        config: &ScanConfig,
+        byte_limit_for_scan: Option<usize>,
+    ) -> ScanResult<(Vec<TransactionAccount>, bool)> {
+        let sum = AtomicUsize::default();
+        let config = config.recreate_with_abort();
+        let range = (after.map_or(Unbounded, |after| Excluded(*after)), Unbounded);
+        let mut collector = Vec::new();
+        self.accounts_db.ordered_scan_accounts(
+            ancestors,
+            bank_id,
+            index_key.copied(),
+            range,
+            |some_account_tuple| {
+                Self::load_while_filtering(&mut collector, some_account_tuple, |account| {
+                    let use_account = filter(account);
+                    if use_account
+                        && Self::accumulate_and_check_scan_result_size(
+                            &sum,
+                            account,
+                            &byte_limit_for_scan,
+                        )
+                    {
+                        // total size of results exceeds size limit, so abort scan
+                        config.abort();
+                    }
+                    use_account
+                });
+                if collector.len() > limit {
+                    // one account past the page is enough to know another page follows
+                    config.abort();
+                }
+            },
+            &config,
+        )?;
+        if byte_limit_for_scan.is_some_and(|limit| sum.load(Ordering::Relaxed) > limit) {
+            return Err(ScanError::Aborted(
+                "The accumulated scan results exceeded the limit".to_string(),
+            ));
+        }
+        let has_more = collector.len() > limit;
+        collector.truncate(limit);
+        Ok((collector, has_more))
+    }
+
     pub fn account_indexes_include_key(&self, key: &Pubkey) -> bool {
         self.accounts_db.account_indexes.include_key(key)
     }
@@ -628,6 +687,52 @@ impl Accounts {
         }
     }
 
+    /// Lock the accounts of a bundle of transactions atomically. The bundle's transactions are
+    /// executed sequentially, so they may conflict with each other: the union of their accounts
+    /// is locked once, with an account write-locked if any of the transactions writes to it.
+    /// Locks must be released with `unlock_bundle_accounts`.
+    pub fn lock_bundle_accounts<'a, Tx: SVMMessage + 'a>(
+        &self,
+        txs: impl Iterator<Item = &'a Tx> + Clone,
+        tx_account_lock_limit: usize,
+    ) -> Result<()> {
+        for tx in txs.clone() {
+            validate_account_locks(tx.account_keys(), tx_account_lock_limit)?;
+        }
+
+        let bundle_account_locks = Self::bundle_account_locks(txs);
+        self.account_locks
+            .lock()
+            .unwrap()
+            .try_lock_accounts(bundle_account_locks.into_iter())
+    }
+
+    /// Unlock the accounts of a bundle locked with `lock_bundle_accounts`.
+    pub fn unlock_bundle_accounts<'a, Tx: SVMMessage + 'a>(
+        &self,
+        txs: impl Iterator<Item = &'a Tx>,
+    ) {
+        let bundle_account_locks = Self::bundle_account_locks(txs);
+        self.account_locks
+            .lock()
+            .unwrap()
+            .unlock_accounts(bundle_account_locks.into_iter());
+    }
+
+    /// Each account of `txs` once, writable if any of the transactions writes to it.
+    fn bundle_account_locks<'a, Tx: SVMMessage + 'a>(
+        txs: impl Iterator<Item = &'a Tx>,
+    ) -> Vec<(&'a Pubkey, bool)> {
+        let mut bundle_account_locks: HashMap<&Pubkey, bool> = HashMap::new();
+        for tx in txs {
+            let tx_account_locks = TransactionAccountLocksIterator::new(tx);
+            for (key, is_writable) in tx_account_locks.accounts_with_is_writable() {
+                *bundle_account_locks.entry(key).or_default() |= is_writable;
+            }
+        }
+        bundle_account_locks.into_iter().collect()
+    }
+
     /// Store the accounts into the DB
     pub fn store_cached<'a>(
         &self,
@@ -884,6 +989,78 @@ mod tests {
         assert_eq!(loaded, vec![]);
     }
 
+    #[test]
+    fn test_load_page_with_filter() {
+        let accounts_db = AccountsDb::new_single_for_tests();
+        let accounts = Accounts::new(Arc::new(accounts_db));
+        let ancestors = Ancestors::from(vec![0]);
+
+        let program_id = Pubkey::from([2; 32]);
+        let mut expected: Vec<_> = (0..5)
+            .map(|_| {
+                let pubkey = solana_sdk::pubkey::new_rand();
+                let account = AccountSharedData::new(1, 0, &program_id);
+                accounts.store_slow_uncached(0, &pubkey, &account);
+                (pubkey, account)
+            })
+            .collect();
+        expected.sort_unstable_by_key(|(pubkey, _)| *pubkey);
+        // accounts owned by other programs are skipped without ending a page early
+        let other_account = AccountSharedData::new(1, 0, &Pubkey::from([3; 32]));
+        accounts.store_slow_uncached(0, &solana_sdk::pubkey::new_rand(), &other_account);
+
+        let load_page = |after: Option<&Pubkey>, limit| {
+            accounts
+                .load_page_with_filter(
+                    &ancestors,
+                    0,
+                    None,
+                    |account| account.owner() == &program_id,
+                    after,
+                    limit,
+                    &ScanConfig::default(),
+                    None,
+                )
+                .unwrap()
+        };
+
+        let (page, has_more) = load_page(None, 2);
+        assert_eq!(page, expected[..2]);
+        assert!(has_more);
+        let (page, has_more) = load_page(Some(&expected[1].0), 2);
+        assert_eq!(page, expected[2..4]);
+        assert!(has_more);
+        let (page, has_more) = load_page(Some(&expected[3].0), 2);
+        assert_eq!(page, expected[4..]);
+        assert!(!has_more);
+        let (page, has_more) = load_page(None, expected.len());
+        assert_eq!(page, expected);
+        assert!(!has_more);
+
+        // the byte limit applies to the accounts collected for a page
+        let load_page_with_byte_limit = |byte_limit| {
+            accounts.load_page_with_filter(
+                &ancestors,
+                0,
+                None,
+                |account| account.owner() == &program_id,
+                None,
+                2,
+                &ScanConfig::default(),
+                Some(byte_limit),
+            )
+        };
+        let account_size = Accounts::calc_scan_result_size(&expected[0].1);
+        // the page is loaded along with the first account of the next page
+        let (page, has_more) = load_page_with_byte_limit(3 * account_size).unwrap();
+        assert_eq!(page, expected[..2]);
+        assert!(has_more);
+        assert!(matches!(
+            load_page_with_byte_limit(3 * account_size - 1),
+            Err(ScanError::Aborted(_))
+        ));
+    }
+
     #[test]
     fn test_lock_accounts_with_duplicates() {
         let accounts_db = AccountsDb::new_single_for_tests();
@@ -1283,6 +1460,72 @@ mod tests {
             .is_locked_write(&keypair2.pubkey()));
     }
 
+    #[test]
+    fn test_lock_bundle_accounts() {
+        let keypair0 = Keypair::new();
+        let keypair1 = Keypair::new();
+        let keypair2 = Keypair::new();
+
+        let accounts_db = AccountsDb::new_single_for_tests();
+        let accounts = Accounts::new(Arc::new(accounts_db));
+
+        let new_tx = |keypair: &Keypair, other: Pubkey| {
+            let instructions = vec![CompiledInstruction::new(2, &(), vec![0, 1])];
+            let message = Message::new_with_compiled_instructions(
+                1,
+                0,
+                1,
+                vec![keypair.pubkey(), other, native_loader::id()],
+                Hash::default(),
+                instructions,
+            );
+            new_sanitized_tx(&[keypair], message, Hash::default())
+        };
+        let tx0 = new_tx(&keypair0, Pubkey::new_unique());
+        let tx1 = new_tx(&keypair1, Pubkey::new_unique());
+        // Writes to the fee payer of `tx0`
+        let tx2 = new_tx(&keypair2, keypair0.pubkey());
+
+        // The transactions of a bundle may conflict with each other
+        let bundle = vec![tx0.clone(), tx1.clone(), tx2.clone()];
+        assert_eq!(
+            accounts.lock_bundle_accounts(bundle.iter(), MAX_TX_ACCOUNT_LOCKS),
+            Ok(())
+        );
+        {
+            let account_locks = accounts.account_locks.lock().unwrap();
+            assert!(account_locks.is_locked_write(&keypair0.pubkey()));
+            assert!(account_locks.is_locked_write(&keypair1.pubkey()));
+            assert!(account_locks.is_locked_write(&keypair2.pubkey()));
+            assert!(account_locks.is_locked_readonly(&native_loader::id()));
+        }
+
+        // But not with transactions outside of the bundle, which fails without locking anything
+        let other_bundle = vec![new_tx(&Keypair::new(), Pubkey::new_unique()), tx2];
+        assert_eq!(
+            accounts.lock_bundle_accounts(other_bundle.iter(), MAX_TX_ACCOUNT_LOCKS),
+            Err(TransactionError::AccountInUse)
+        );
+        assert_eq!(
+            accounts.lock_accounts([tx0.clone()].iter(), MAX_TX_ACCOUNT_LOCKS),
+            vec![Err(TransactionError::AccountInUse)]
+        );
+
+        // Each account is only unlocked once
+        accounts.unlock_bundle_accounts(bundle.iter());
+        {
+            let account_locks = accounts.account_locks.lock().unwrap();
+            assert!(!account_locks.is_locked_write(&keypair0.pubkey()));
+            assert!(!account_locks.is_locked_write(&keypair1.pubkey()));
+            assert!(!account_locks.is_locked_write(&keypair2.pubkey()));
+            assert!(!account_locks.is_locked_readonly(&native_loader::id()));
+        }
+        assert_eq!(
+            accounts.lock_accounts([tx0, tx1].iter(), MAX_TX_ACCOUNT_LOCKS),
+            vec![Ok(()), Ok(())]
+        );
+    }
+
     #[test]
     fn huge_clean() {
         solana_logger::setup();
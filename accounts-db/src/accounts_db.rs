@@ -506,6 +506,7 @@ pub const ACCOUNTS_DB_CONFIG_FOR_TESTING: AccountsDbConfig = AccountsDbConfig {
     skip_initial_hash_calc: false,
     exhaustively_verify_refcounts: false,
     create_ancient_storage: CreateAncientStorage::Pack,
+    ancient_storage_format: None,
     test_partitioned_epoch_rewards: TestPartitionedEpochRewards::CompareResults,
     test_skip_rewrites_but_include_in_bank_hash: false,
     storage_access: StorageAccess::Mmap,
@@ -532,6 +533,7 @@ pub const ACCOUNTS_DB_CONFIG_FOR_BENCHMARKS: AccountsDbConfig = AccountsDbConfig
     skip_initial_hash_calc: false,
     exhaustively_verify_refcounts: false,
     create_ancient_storage: CreateAncientStorage::Pack,
+    ancient_storage_format: None,
     test_partitioned_epoch_rewards: TestPartitionedEpochRewards::None,
     test_skip_rewrites_but_include_in_bank_hash: false,
     storage_access: StorageAccess::Mmap,
@@ -661,6 +663,9 @@ pub struct AccountsDbConfig {
     pub exhaustively_verify_refcounts: bool,
     /// how to create ancient storages
     pub create_ancient_storage: CreateAncientStorage,
+    /// storage format of the ancient storages created by packing.
+    /// None means the format of all other new storages is used.
+    pub ancient_storage_format: Option<AccountsFileProvider>,
     pub test_partitioned_epoch_rewards: TestPartitionedEpochRewards,
     pub storage_access: StorageAccess,
     pub scan_filter_for_shrinking: ScanFilter,
@@ -1598,6 +1603,10 @@ pub struct AccountsDb {
     /// storage format to use for new storages
     accounts_file_provider: AccountsFileProvider,
 
+    /// storage format to use for new ancient storages created by packing.
+    /// None means `accounts_file_provider` is used.
+    ancient_accounts_file_provider: Option<AccountsFileProvider>,
+
     /// method to use for accessing storages
     storage_access: StorageAccess,
 
@@ -2077,6 +2086,7 @@ impl AccountsDb {
             zero_lamport_accounts_to_purge_after_full_snapshot: DashSet::default(),
             log_dead_slots: AtomicBool::new(true),
             accounts_file_provider: AccountsFileProvider::default(),
+            ancient_accounts_file_provider: accounts_db_config.ancient_storage_format,
             epoch_accounts_hash_manager: EpochAccountsHashManager::new_invalid(),
             latest_full_snapshot_slot: SeqLock::new(None),
             best_ancient_slots_to_shrink: RwLock::default(),
@@ -2114,14 +2124,22 @@ impl AccountsDb {
         next_id
     }
 
-    fn new_storage_entry(&self, slot: Slot, path: &Path, size: u64) -> AccountStorageEntry {
-        AccountStorageEntry::new(
-            path,
-            slot,
-            self.next_id(),
-            size,
-            self.accounts_file_provider,
-        )
+    fn new_storage_entry(
+        &self,
+        slot: Slot,
+        path: &Path,
+        size: u64,
+        provider: AccountsFileProvider,
+    ) -> AccountStorageEntry {
+        AccountStorageEntry::new(path, slot, self.next_id(), size, provider)
+    }
+
+    /// Sets the storage format to use for new ancient storages created by packing.
+    ///
+    /// Ancient storages are written once, so a write-once format such as
+    /// `AccountsFileProvider::ColdStorage` can be used to reduce their disk usage.
+    pub fn set_ancient_accounts_file_provider(&mut self, provider: AccountsFileProvider) {
+        self.ancient_accounts_file_provider = Some(provider);
     }
 
     /// Returns if the experimental accounts lattice hash is enabled
@@ -4099,6 +4117,21 @@ impl AccountsDb {
         self.storage.shrinking_in_progress(slot, shrunken_store)
     }
 
+    /// return a store that can contain 'size' bytes of packed ancient accounts
+    pub(crate) fn get_store_for_ancient(&self, slot: Slot, size: u64) -> ShrinkInProgress<'_> {
+        let provider = self
+            .ancient_accounts_file_provider
+            .unwrap_or(self.accounts_file_provider);
+        let ancient_store = self.create_store_with_provider(
+            slot,
+            size,
+            "ancient",
+            self.shrink_paths.as_slice(),
+            provider,
+        );
+        self.storage.shrinking_in_progress(slot, ancient_store)
+    }
+
     // Reads all accounts in given slot's AppendVecs and filter only to alive,
     // then create a minimum AppendVec filled with the alive.
     fn shrink_slot_forced(&self, slot: Slot) {
@@ -4862,6 +4895,7 @@ impl AccountsDb {
             IndexKey::ProgramId(key) => key,
             IndexKey::SplTokenMint(key) => key,
             IndexKey::SplTokenOwner(key) => key,
+            IndexKey::Custom(_, key) => key,
         };
         if !self.account_indexes.include_key(key) {
             // the requested key was not indexed in the secondary index, so do a normal scan
@@ -4888,6 +4922,51 @@ impl AccountsDb {
         Ok(used_index)
     }
 
+    /// Scans the accounts within `range` in pubkey order. If `index_key` is Some and the key is
+    /// included in the secondary indexes, only the accounts in that secondary index are scanned.
+    pub fn ordered_scan_accounts<F, R>(
+        &self,
+        ancestors: &Ancestors,
+        bank_id: BankId,
+        index_key: Option<IndexKey>,
+        range: R,
+        mut scan_func: F,
+        config: &ScanConfig,
+    ) -> ScanResult<()>
+    where
+        F: FnMut(Option<(&Pubkey, AccountSharedData, Slot)>),
+        R: RangeBounds<Pubkey> + std::fmt::Debug,
+    {
+        // the requested key was not indexed in the secondary index, so do a normal scan
+        let index_key = index_key.filter(|index_key| {
+            let key = match index_key {
+                IndexKey::ProgramId(key) => key,
+                IndexKey::SplTokenMint(key) => key,
+                IndexKey::SplTokenOwner(key) => key,
+                IndexKey::Custom(_, key) => key,
+            };
+            self.account_indexes.include_key(key)
+        });
+
+        self.accounts_index.ordered_scan_accounts(
+            ancestors,
+            bank_id,
+            index_key,
+            range,
+            |pubkey, (account_info, slot)| {
+                let account_slot = self
+                    .get_account_accessor(slot, pubkey, &account_info.storage_location())
+                    .get_loaded_account(|loaded_account| {
+                        (pubkey, loaded_account.take_account(), slot)
+                    });
+                scan_func(account_slot)
+            },
+            config,
+        )?;
+
+        Ok(())
+    }
+
     /// Scan a specific slot through all the account storage
     pub(crate) fn scan_account_storage<R, B>(
         &self,
@@ -5586,12 +5665,24 @@ impl AccountsDb {
         size: u64,
         from: &str,
         paths: &[PathBuf],
+    ) -> Arc<AccountStorageEntry> {
+        self.create_store_with_provider(slot, size, from, paths, self.accounts_file_provider)
+    }
+
+    fn create_store_with_provider(
+        &self,
+        slot: Slot,
+        size: u64,
+        from: &str,
+        paths: &[PathBuf],
+        provider: AccountsFileProvider,
     ) -> Arc<AccountStorageEntry> {
         self.stats
             .create_store_count
             .fetch_add(1, Ordering::Relaxed);
         let path_index = thread_rng().gen_range(0..paths.len());
-        let store = Arc::new(self.new_storage_entry(slot, Path::new(&paths[path_index]), size));
+        let store =
+            Arc::new(self.new_storage_entry(slot, Path::new(&paths[path_index]), size, provider));
 
         debug!(
             "creating store: {} slot: {} len: {} size: {} from: {} path: {}",
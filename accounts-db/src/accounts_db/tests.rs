@@ -4240,7 +4240,9 @@ define_accounts_db_test!(
                     0
                 );
             }
-            AccountsFileProvider::HotStorage => {
+            AccountsFileProvider::HotStorage
+            | AccountsFileProvider::ColdStorage
+            | AccountsFileProvider::ColdStorageZstd => {
                 // For tired-storage, alive bytes are only an approximation.
                 // Therefore, it won't be zero.
                 assert!(
@@ -6168,6 +6170,31 @@ fn test_mark_dirty_dead_stores() {
     }
 }
 
+#[test]
+fn test_get_store_for_ancient() {
+    let mut db = AccountsDb::new_single_for_tests();
+    let size = 1000;
+
+    // without an ancient provider, ancient stores use the default provider
+    let slot = 0;
+    db.create_and_insert_store(slot, size, "test");
+    let shrink_in_progress = db.get_store_for_ancient(slot, size);
+    assert_matches!(
+        shrink_in_progress.new_storage().accounts,
+        AccountsFile::AppendVec(_)
+    );
+    drop(shrink_in_progress);
+
+    db.set_ancient_accounts_file_provider(AccountsFileProvider::ColdStorage);
+    let slot = 1;
+    db.create_and_insert_store(slot, size, "test");
+    let shrink_in_progress = db.get_store_for_ancient(slot, size);
+    assert_matches!(
+        shrink_in_progress.new_storage().accounts,
+        AccountsFile::TieredStorage(_)
+    );
+}
+
 #[test]
 fn test_split_storages_ancient_chunks() {
     let storages = SortedStorages::empty();
@@ -6,7 +6,10 @@ use {
         append_vec::{AppendVec, AppendVecError, IndexInfo},
         storable_accounts::StorableAccounts,
         tiered_storage::{
-            error::TieredStorageError, hot::HOT_FORMAT, index::IndexOffset, TieredStorage,
+            cold::{COLD_FORMAT, COLD_ZSTD_FORMAT},
+            error::TieredStorageError,
+            index::IndexOffset,
+            TieredStorage,
         },
     },
     solana_sdk::{account::AccountSharedData, clock::Slot, pubkey::Pubkey},
@@ -70,6 +73,11 @@ pub enum AccountsFile {
 impl AccountsFile {
     /// Create an AccountsFile instance from the specified path.
     ///
+    /// Tiered accounts files, such as the ancient storages written in the cold
+    /// formats, are detected by the magic number at their end.  They are always
+    /// read in full, so `current_len` and `storage_access` only apply to
+    /// append vecs.
+    ///
     /// The second element of the returned tuple is the number of accounts in the
     /// accounts file.
     pub fn new_from_file(
@@ -77,6 +85,14 @@ impl AccountsFile {
         current_len: usize,
         storage_access: StorageAccess,
     ) -> Result<(Self, usize)> {
+        let path = path.into();
+        if TieredStorage::is_tiered_storage_file(&path)? {
+            let tiered_storage = TieredStorage::new_readonly(path)?;
+            let num_accounts = tiered_storage
+                .reader()
+                .map_or(0, |reader| reader.num_accounts());
+            return Ok((Self::TieredStorage(tiered_storage), num_accounts));
+        }
         let (av, num_accounts) = AppendVec::new_from_file(path, current_len, storage_access)?;
         Ok((Self::AppendVec(av), num_accounts))
     }
@@ -283,7 +299,7 @@ impl AccountsFile {
             // assumes all offsets are multiple of 8 while TieredStorage uses
             // IndexOffset that is equivalent to AccountInfo::reduced_offset.
             Self::TieredStorage(ts) => ts
-                .write_accounts(accounts, skip, &HOT_FORMAT)
+                .write_accounts_with_default_format(accounts, skip)
                 .map(|mut stored_accounts_info| {
                     stored_accounts_info.offsets.iter_mut().for_each(|offset| {
                         *offset = AccountInfo::reduced_offset_to_offset(*offset as u32);
@@ -313,6 +329,13 @@ pub enum AccountsFileProvider {
     #[default]
     AppendVec,
     HotStorage,
+    /// Tiered storage with compressed account blocks.  As a cold accounts
+    /// file can only be written once, it is intended for ancient storages.
+    ColdStorage,
+    /// Same as ColdStorage, but compresses the account blocks with zstd and
+    /// a dictionary trained from the accounts of each file.  It trades more
+    /// CPU for a better compression ratio on rarely-read storages.
+    ColdStorageZstd,
 }
 
 impl AccountsFileProvider {
@@ -322,6 +345,12 @@ impl AccountsFileProvider {
                 AccountsFile::AppendVec(AppendVec::new(path, true, file_size as usize))
             }
             Self::HotStorage => AccountsFile::TieredStorage(TieredStorage::new_writable(path)),
+            Self::ColdStorage => AccountsFile::TieredStorage(
+                TieredStorage::new_writable_with_format(path, COLD_FORMAT),
+            ),
+            Self::ColdStorageZstd => AccountsFile::TieredStorage(
+                TieredStorage::new_writable_with_format(path, COLD_ZSTD_FORMAT),
+            ),
         }
     }
 }
@@ -346,7 +375,16 @@ pub struct StoredAccountsInfo {
 
 #[cfg(test)]
 pub mod tests {
-    use crate::accounts_file::AccountsFile;
+    use {
+        crate::accounts_file::{AccountsFile, AccountsFileProvider, StorageAccess},
+        solana_sdk::{
+            account::{AccountSharedData, ReadableAccount},
+            pubkey::Pubkey,
+        },
+        std::mem::ManuallyDrop,
+        tempfile::tempdir,
+        test_case::test_case,
+    };
     impl AccountsFile {
         pub(crate) fn set_current_len_for_tests(&self, len: usize) {
             match self {
@@ -355,4 +393,58 @@ pub mod tests {
             }
         }
     }
+
+    #[test_case(AccountsFileProvider::AppendVec)]
+    #[test_case(AccountsFileProvider::HotStorage)]
+    #[test_case(AccountsFileProvider::ColdStorage)]
+    #[test_case(AccountsFileProvider::ColdStorageZstd)]
+    fn test_new_from_file(provider: AccountsFileProvider) {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("test_new_from_file");
+        let accounts: Vec<_> = (0..10)
+            .map(|i| {
+                (
+                    Pubkey::new_unique(),
+                    AccountSharedData::new(i + 1, i as usize * 10, &Pubkey::new_unique()),
+                )
+            })
+            .collect();
+
+        // The file must outlive the writer, as would the file of a storage
+        // that is reopened on restart or unpacked from a snapshot
+        let account_refs: Vec<_> = accounts
+            .iter()
+            .map(|(address, account)| (address, account))
+            .collect();
+        let accounts_file = ManuallyDrop::new(provider.new_writable(&path, 1024 * 1024));
+        accounts_file
+            .append_accounts(&(0, account_refs.as_slice()), 0)
+            .unwrap();
+        accounts_file.flush().unwrap();
+        let current_len = accounts_file.len();
+
+        let (accounts_file, num_accounts) =
+            AccountsFile::new_from_file(&path, current_len, StorageAccess::default()).unwrap();
+        assert_eq!(
+            matches!(accounts_file, AccountsFile::TieredStorage(_)),
+            provider != AccountsFileProvider::AppendVec
+        );
+        assert_eq!(num_accounts, accounts.len());
+        let mut loaded_accounts = Vec::new();
+        accounts_file.scan_accounts(|stored_account| {
+            loaded_accounts.push((
+                *stored_account.pubkey(),
+                stored_account.to_account_shared_data(),
+            ));
+        });
+        assert_eq!(loaded_accounts.len(), accounts.len());
+        for ((address, account), (loaded_address, loaded_account)) in
+            accounts.iter().zip(&loaded_accounts)
+        {
+            assert_eq!(address, loaded_address);
+            assert_eq!(account.lamports(), loaded_account.lamports());
+            assert_eq!(account.data(), loaded_account.data());
+            assert_eq!(account.owner(), loaded_account.owner());
+        }
+    }
 }
@@ -183,7 +183,7 @@ pub enum ScanError {
 
 enum ScanTypes<R: RangeBounds<Pubkey>> {
     Unindexed(Option<R>),
-    Indexed(IndexKey),
+    Indexed(IndexKey, Option<R>),
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -191,6 +191,7 @@ pub enum IndexKey {
     ProgramId(Pubkey),
     SplTokenMint(Pubkey),
     SplTokenOwner(Pubkey),
+    Custom(CustomIndexSpec, Pubkey),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -198,6 +199,7 @@ pub enum AccountIndex {
     ProgramId,
     SplTokenMint,
     SplTokenOwner,
+    Custom(CustomIndexSpec),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -669,6 +671,7 @@ pub struct AccountsIndex<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> {
     program_id_index: SecondaryIndex<RwLockSecondaryIndexEntry>,
     spl_token_mint_index: SecondaryIndex<RwLockSecondaryIndexEntry>,
     spl_token_owner_index: SecondaryIndex<RwLockSecondaryIndexEntry>,
+    custom_indexes: CustomSecondaryIndexes,
     pub roots_tracker: RwLock<RootsTracker>,
     ongoing_scan_roots: RwLock<BTreeMap<Slot, u64>>,
     // Each scan has some latest slot `S` that is the tip of the fork the scan
@@ -728,6 +731,7 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
             spl_token_owner_index: SecondaryIndex::<RwLockSecondaryIndexEntry>::new(
                 "spl_token_owner_index_stats",
             ),
+            custom_indexes: CustomSecondaryIndexes::default(),
             roots_tracker: RwLock::<RootsTracker>::default(),
             ongoing_scan_roots: RwLock::<BTreeMap<Slot, u64>>::default(),
             removed_bank_ids: Mutex::<HashSet<BankId>>::default(),
@@ -951,36 +955,53 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
                 // Pass "" not to log metrics, so RPC doesn't get spammy
                 self.do_scan_accounts(metric_name, ancestors, func, range, Some(max_root), config);
             }
-            ScanTypes::Indexed(IndexKey::ProgramId(program_id)) => {
+            ScanTypes::Indexed(IndexKey::ProgramId(program_id), range) => {
                 self.do_scan_secondary_index(
                     ancestors,
                     func,
                     &self.program_id_index,
                     &program_id,
+                    range,
                     Some(max_root),
                     config,
                 );
             }
-            ScanTypes::Indexed(IndexKey::SplTokenMint(mint_key)) => {
+            ScanTypes::Indexed(IndexKey::SplTokenMint(mint_key), range) => {
                 self.do_scan_secondary_index(
                     ancestors,
                     func,
                     &self.spl_token_mint_index,
                     &mint_key,
+                    range,
                     Some(max_root),
                     config,
                 );
             }
-            ScanTypes::Indexed(IndexKey::SplTokenOwner(owner_key)) => {
+            ScanTypes::Indexed(IndexKey::SplTokenOwner(owner_key), range) => {
                 self.do_scan_secondary_index(
                     ancestors,
                     func,
                     &self.spl_token_owner_index,
                     &owner_key,
+                    range,
                     Some(max_root),
                     config,
                 );
             }
+            ScanTypes::Indexed(IndexKey::Custom(spec, index_key), range) => {
+                // nothing was indexed by this spec yet if it has no index
+                if let Some(index) = self.custom_indexes.get(&spec) {
+                    self.do_scan_secondary_index(
+                        ancestors,
+                        func,
+                        &*index,
+                        &index_key,
+                        range,
+                        Some(max_root),
+                        config,
+                    );
+                }
+            }
         }
 
         {
@@ -1088,8 +1109,10 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
         }
     }
 
+    // If `range` is Some, only the pubkeys within it are visited, in pubkey order
     fn do_scan_secondary_index<
         F,
+        R,
         SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send,
     >(
         &self,
@@ -1097,23 +1120,50 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
         mut func: F,
         index: &SecondaryIndex<SecondaryIndexEntryType>,
         index_key: &Pubkey,
+        range: Option<R>,
         max_root: Option<Slot>,
         config: &ScanConfig,
     ) where
         F: FnMut(&Pubkey, (&T, Slot)),
+        R: RangeBounds<Pubkey>,
     {
-        for pubkey in index.get(index_key) {
-            if config.is_aborted() {
-                break;
-            }
-            if let Some(entry) = self.get_cloned(&pubkey) {
+        let mut scan_pubkey = |pubkey: &Pubkey| {
+            if let Some(entry) = self.get_cloned(pubkey) {
                 self.get_account_info_with_and_then(
                     &entry,
                     Some(ancestors),
                     max_root,
-                    |(slot, account_info)| func(&pubkey, (&account_info, slot)),
+                    |(slot, account_info)| func(pubkey, (&account_info, slot)),
                 );
             };
+        };
+
+        let Some(range) = range else {
+            for pubkey in index.get(index_key) {
+                if config.is_aborted() {
+                    break;
+                }
+                scan_pubkey(&pubkey);
+            }
+            return;
+        };
+
+        // Ordered scans usually stop early, e.g. once a page is full, so collect and visit the
+        // keys one batch at a time instead of collecting and sorting all of them upfront
+        let end_bound = range.end_bound().cloned();
+        let mut start_bound = range.start_bound().cloned();
+        loop {
+            let pubkeys = index.get_ordered(index_key, &(start_bound, end_bound), ITER_BATCH_SIZE);
+            for pubkey in &pubkeys {
+                if config.is_aborted() {
+                    return;
+                }
+                scan_pubkey(pubkey);
+            }
+            match pubkeys.last() {
+                Some(last) if pubkeys.len() == ITER_BATCH_SIZE => start_bound = Excluded(*last),
+                _ => break,
+            }
         }
     }
 
@@ -1293,11 +1343,40 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
             ancestors,
             scan_bank_id,
             func,
-            ScanTypes::<Range<Pubkey>>::Indexed(index_key),
+            ScanTypes::Indexed(index_key, None::<Range<Pubkey>>),
             config,
         )
     }
 
+    /// call func with every pubkey and index visible from a given set of ancestors within
+    /// `range`, in pubkey order. If `index_key` is Some, only the pubkeys in that secondary
+    /// index are visited.
+    pub(crate) fn ordered_scan_accounts<F, R>(
+        &self,
+        ancestors: &Ancestors,
+        scan_bank_id: BankId,
+        index_key: Option<IndexKey>,
+        range: R,
+        func: F,
+        config: &ScanConfig,
+    ) -> Result<(), ScanError>
+    where
+        F: FnMut(&Pubkey, (&T, Slot)),
+        R: RangeBounds<Pubkey> + std::fmt::Debug,
+    {
+        // the index iterator only yields pubkeys in order when collecting sorted
+        let config = ScanConfig {
+            abort: config.abort.clone(),
+            collect_all_unsorted: false,
+        };
+        let scan_type = match index_key {
+            Some(index_key) => ScanTypes::Indexed(index_key, Some(range)),
+            None => ScanTypes::Unindexed(Some(range)),
+        };
+        // Pass "" not to log metrics, so RPC doesn't get spammy
+        self.do_checked_scan_accounts("", ancestors, scan_bank_id, func, scan_type, &config)
+    }
+
     pub fn get_rooted_entries(
         &self,
         slice: SlotSlice<T>,
@@ -1594,6 +1673,10 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
                 .index
                 .get(index_key)
                 .map(|x| x.len()),
+            AccountIndex::Custom(spec) => self
+                .custom_indexes
+                .get(spec)
+                .and_then(|index| index.index.get(index_key).map(|x| x.len())),
         }
     }
 
@@ -1611,6 +1694,7 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
             info!("secondary index: {:?}", AccountIndex::SplTokenOwner);
             self.spl_token_owner_index.log_contents();
         }
+        self.custom_indexes.log_contents();
     }
 
     pub(crate) fn update_secondary_indexes(
@@ -1659,6 +1743,16 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
             account_data,
             account_indexes,
         );
+
+        for index in &account_indexes.indexes {
+            if let AccountIndex::Custom(spec) = index {
+                if let Some(index_key) = spec.index_key(account_owner, account_data) {
+                    if account_indexes.include_key(index_key) {
+                        self.custom_indexes.insert(spec, index_key, pubkey);
+                    }
+                }
+            }
+        }
     }
 
     pub(crate) fn get_bin(&self, pubkey: &Pubkey) -> AccountMaps<T, U> {
@@ -1888,6 +1982,14 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
         if account_indexes.contains(&AccountIndex::SplTokenMint) {
             self.spl_token_mint_index.remove_by_inner_key(inner_key);
         }
+
+        if account_indexes
+            .indexes
+            .iter()
+            .any(|index| matches!(index, AccountIndex::Custom(_)))
+        {
+            self.custom_indexes.remove_by_inner_key(inner_key);
+        }
     }
 
     fn purge_older_root_entries(
@@ -3032,6 +3134,101 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_ordered_scan_accounts() {
+        let (index, mut pubkeys) = setup_accounts_index_keys(3 * ITER_BATCH_SIZE);
+        pubkeys.sort();
+        let ancestors = Ancestors::default();
+
+        // resume after each page's last key until the index is exhausted
+        let page_size = ITER_BATCH_SIZE / 2 + 1;
+        let mut scanned_keys = vec![];
+        let mut start_bound = Unbounded;
+        loop {
+            let config = ScanConfig::default().recreate_with_abort();
+            let mut page = vec![];
+            index
+                .ordered_scan_accounts(
+                    &ancestors,
+                    0,
+                    None,
+                    (start_bound, Unbounded),
+                    |pubkey, _index| {
+                        page.push(*pubkey);
+                        if page.len() == page_size {
+                            config.abort();
+                        }
+                    },
+                    &config,
+                )
+                .unwrap();
+            let Some(last) = page.last().copied() else {
+                break;
+            };
+            scanned_keys.extend(page);
+            start_bound = Excluded(last);
+        }
+        assert_eq!(scanned_keys, pubkeys);
+    }
+
+    #[test]
+    fn test_ordered_scan_accounts_secondary_index() {
+        let index = AccountsIndex::<bool, bool>::default_for_tests();
+        let secondary_indexes = AccountSecondaryIndexes {
+            keys: None,
+            indexes: HashSet::from([AccountIndex::ProgramId]),
+        };
+        let program_id = Pubkey::new_unique();
+        let account = AccountSharedData::new(1, 0, &program_id);
+        let other_account = AccountSharedData::new(1, 0, &Pubkey::new_unique());
+        let mut pubkeys: Vec<_> = (0..2 * ITER_BATCH_SIZE + 1)
+            .map(|i| {
+                let pubkey = solana_sdk::pubkey::new_rand();
+                index.upsert(
+                    0,
+                    0,
+                    &pubkey,
+                    &account,
+                    &secondary_indexes,
+                    true,
+                    &mut vec![],
+                    UPSERT_POPULATE_RECLAIMS,
+                );
+                // accounts of other programs are not visited
+                if i % 2 == 0 {
+                    index.upsert(
+                        0,
+                        0,
+                        &solana_sdk::pubkey::new_rand(),
+                        &other_account,
+                        &secondary_indexes,
+                        true,
+                        &mut vec![],
+                        UPSERT_POPULATE_RECLAIMS,
+                    );
+                }
+                pubkey
+            })
+            .collect();
+        pubkeys.sort();
+        index.add_root(0);
+
+        // the visited keys span several batches of the secondary index
+        let start = pubkeys[ITER_BATCH_SIZE / 2];
+        let mut scanned_keys = vec![];
+        index
+            .ordered_scan_accounts(
+                &Ancestors::default(),
+                0,
+                Some(IndexKey::ProgramId(program_id)),
+                (Excluded(start), Unbounded),
+                |pubkey, _index| scanned_keys.push(*pubkey),
+                &ScanConfig::default(),
+            )
+            .unwrap();
+        assert_eq!(scanned_keys, pubkeys[ITER_BATCH_SIZE / 2 + 1..]);
+    }
+
     fn run_test_scan_accounts(num_pubkeys: usize) {
         let (index, _) = setup_accounts_index_keys(num_pubkeys);
         let ancestors = Ancestors::default();
@@ -3762,6 +3959,59 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_custom_secondary_index() {
+        let program_id = Pubkey::new_unique();
+        let spec = CustomIndexSpec {
+            owner: program_id,
+            offset: 8,
+        };
+        assert_eq!(spec.to_string().parse::<CustomIndexSpec>(), Ok(spec));
+        assert!("no-offset".parse::<CustomIndexSpec>().is_err());
+        let secondary_indexes = AccountSecondaryIndexes {
+            keys: None,
+            indexes: HashSet::from([AccountIndex::Custom(spec)]),
+        };
+        let index = AccountsIndex::<bool, bool>::default_for_tests();
+        let account_key = Pubkey::new_unique();
+        let index_key = Pubkey::new_unique();
+        let mut account_data = vec![0; 40];
+        account_data[8..40].copy_from_slice(index_key.as_ref());
+
+        // Wrong program id, and account data too short to hold the key
+        for account in [
+            AccountSharedData::create(1, account_data.clone(), Pubkey::default(), false, 0),
+            AccountSharedData::create(1, account_data[1..].to_vec(), program_id, false, 0),
+        ] {
+            index.update_secondary_indexes(&account_key, &account, &secondary_indexes);
+            assert!(index.custom_indexes.get(&spec).is_none());
+        }
+
+        index.upsert(
+            0,
+            0,
+            &account_key,
+            &AccountSharedData::create(1, account_data, program_id, false, 0),
+            &secondary_indexes,
+            true,
+            &mut vec![],
+            UPSERT_POPULATE_RECLAIMS,
+        );
+        let secondary_index = index.custom_indexes.get(&spec).unwrap();
+        check_secondary_index_mapping_correct(&secondary_index, &[index_key], &account_key);
+        assert_eq!(
+            index.get_index_key_size(&AccountIndex::Custom(spec), &index_key),
+            Some(1)
+        );
+
+        index.slot_list_mut(&account_key, |slot_list| slot_list.clear());
+
+        // Everything should be deleted
+        let _ = index.handle_dead_keys(&[&account_key], &secondary_indexes);
+        assert!(secondary_index.index.is_empty());
+        assert!(secondary_index.reverse_index.is_empty());
+    }
+
     #[test]
     fn test_spl_token_owner_secondary_index() {
         let (key_start, key_end, secondary_indexes) =
@@ -544,7 +544,7 @@ impl AccountsDb {
     ) {
         let target_slot = accounts_to_write.target_slot();
         let (shrink_in_progress, create_and_insert_store_elapsed_us) =
-            measure_us!(self.get_store_for_shrink(target_slot, bytes));
+            measure_us!(self.get_store_for_ancient(target_slot, bytes));
         let (store_accounts_timing, rewrite_elapsed_us) = measure_us!(
             self.store_accounts_frozen(accounts_to_write, shrink_in_progress.new_storage(),)
         );
@@ -1236,7 +1236,7 @@ pub mod tests {
             match self {
                 Self::AppendVec(av) => av.ref_executable_byte(),
                 // Tests currently only cover AppendVec.
-                Self::Hot(_) => unreachable!(),
+                Self::Hot(_) | Self::Cold(_) => unreachable!(),
             }
         }
     }
@@ -1,13 +1,18 @@
 use {
     dashmap::{mapref::entry::Entry::Occupied, DashMap},
     log::*,
-    solana_sdk::{pubkey::Pubkey, timing::AtomicInterval},
+    solana_sdk::{
+        pubkey::{Pubkey, PUBKEY_BYTES},
+        timing::AtomicInterval,
+    },
     std::{
-        collections::HashSet,
-        fmt::Debug,
+        collections::{BinaryHeap, HashSet},
+        fmt::{self, Debug},
+        ops::RangeBounds,
+        str::FromStr,
         sync::{
             atomic::{AtomicU64, Ordering},
-            RwLock,
+            Arc, RwLock,
         },
     },
 };
@@ -24,9 +29,31 @@ pub trait SecondaryIndexEntry: Debug {
     fn remove_inner_key(&self, key: &Pubkey) -> bool;
     fn is_empty(&self) -> bool;
     fn keys(&self) -> Vec<Pubkey>;
+    // Returns up to `limit` of the smallest keys within `range`, in order.
+    fn ordered_keys(&self, range: &impl RangeBounds<Pubkey>, limit: usize) -> Vec<Pubkey>;
     fn len(&self) -> usize;
 }
 
+/// Returns up to `limit` of the smallest `keys` within `range`, in order, without collecting or
+/// sorting all of them
+fn smallest_keys_in_range(
+    keys: impl Iterator<Item = Pubkey>,
+    range: &impl RangeBounds<Pubkey>,
+    limit: usize,
+) -> Vec<Pubkey> {
+    // a max-heap of the smallest keys seen so far
+    let mut smallest = BinaryHeap::with_capacity(limit.saturating_add(1));
+    for key in keys.filter(|key| range.contains(key)) {
+        if smallest.len() < limit {
+            smallest.push(key);
+        } else if smallest.peek().is_some_and(|largest| key < *largest) {
+            smallest.pop();
+            smallest.push(key);
+        }
+    }
+    smallest.into_sorted_vec()
+}
+
 #[derive(Debug, Default)]
 pub struct SecondaryIndexStats {
     last_report: AtomicInterval,
@@ -62,6 +89,14 @@ impl SecondaryIndexEntry for DashMapSecondaryIndexEntry {
             .collect()
     }
 
+    fn ordered_keys(&self, range: &impl RangeBounds<Pubkey>, limit: usize) -> Vec<Pubkey> {
+        smallest_keys_in_range(
+            self.account_keys.iter().map(|entry_ref| *entry_ref.key()),
+            range,
+            limit,
+        )
+    }
+
     fn len(&self) -> usize {
         self.account_keys.len()
     }
@@ -97,6 +132,14 @@ impl SecondaryIndexEntry for RwLockSecondaryIndexEntry {
         self.account_keys.read().unwrap().iter().cloned().collect()
     }
 
+    fn ordered_keys(&self, range: &impl RangeBounds<Pubkey>, limit: usize) -> Vec<Pubkey> {
+        smallest_keys_in_range(
+            self.account_keys.read().unwrap().iter().copied(),
+            range,
+            limit,
+        )
+    }
+
     fn len(&self) -> usize {
         self.account_keys.read().unwrap().len()
     }
@@ -226,6 +269,20 @@ impl<SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send>
         }
     }
 
+    /// Returns up to `limit` of the smallest inner keys of `key` within `range`, in order
+    pub fn get_ordered(
+        &self,
+        key: &Pubkey,
+        range: &impl RangeBounds<Pubkey>,
+        limit: usize,
+    ) -> Vec<Pubkey> {
+        if let Some(inner_keys_map) = self.index.get(key) {
+            inner_keys_map.ordered_keys(range, limit)
+        } else {
+            vec![]
+        }
+    }
+
     /// log top 20 (owner, # accounts) in descending order of # accounts
     pub fn log_contents(&self) {
         let mut entries = self
@@ -241,3 +298,93 @@ impl<SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send>
             .for_each(|(v, k)| info!("owner: {}, accounts: {}", k, v));
     }
 }
+
+/// A user-defined secondary index: accounts owned by `owner` are indexed by the 32-byte key
+/// stored at `offset` in their data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CustomIndexSpec {
+    pub owner: Pubkey,
+    pub offset: usize,
+}
+
+impl CustomIndexSpec {
+    /// Returns the key to index an account by, if the account is covered by this spec
+    pub fn index_key<'a>(
+        &self,
+        account_owner: &Pubkey,
+        account_data: &'a [u8],
+    ) -> Option<&'a Pubkey> {
+        if *account_owner != self.owner {
+            return None;
+        }
+        let end = self.offset.checked_add(PUBKEY_BYTES)?;
+        account_data.get(self.offset..end).map(bytemuck::from_bytes)
+    }
+}
+
+impl fmt::Display for CustomIndexSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.owner, self.offset)
+    }
+}
+
+/// Parses `<OWNER>:<OFFSET>`
+impl FromStr for CustomIndexSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (owner, offset) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected <OWNER>:<OFFSET>, got {s}"))?;
+        let owner = owner
+            .parse()
+            .map_err(|err| format!("invalid owner {owner}: {err}"))?;
+        let offset = offset
+            .parse()
+            .map_err(|err| format!("invalid offset {offset}: {err}"))?;
+        Ok(Self { owner, offset })
+    }
+}
+
+/// The secondary indexes of every `CustomIndexSpec`, each created when its first key is inserted
+#[derive(Debug, Default)]
+pub struct CustomSecondaryIndexes {
+    indexes: DashMap<CustomIndexSpec, Arc<SecondaryIndex<RwLockSecondaryIndexEntry>>>,
+}
+
+impl CustomSecondaryIndexes {
+    pub fn insert(&self, spec: &CustomIndexSpec, key: &Pubkey, inner_key: &Pubkey) {
+        let index = self.get(spec).unwrap_or_else(|| {
+            self.indexes
+                .entry(*spec)
+                .or_insert_with(|| Arc::new(SecondaryIndex::new("custom_secondary_index_stats")))
+                .clone()
+        });
+        index.insert(key, inner_key);
+    }
+
+    pub fn get(
+        &self,
+        spec: &CustomIndexSpec,
+    ) -> Option<Arc<SecondaryIndex<RwLockSecondaryIndexEntry>>> {
+        self.indexes
+            .get(spec)
+            .map(|index| Arc::clone(index.value()))
+    }
+
+    pub fn remove_by_inner_key(&self, inner_key: &Pubkey) {
+        self.indexes
+            .iter()
+            .for_each(|index| index.value().remove_by_inner_key(inner_key));
+    }
+
+    /// log the contents of each custom index
+    pub fn log_contents(&self) {
+        self.indexes.iter().for_each(|index| {
+            if !index.value().index.is_empty() {
+                info!("secondary index: {}", index.key());
+                index.value().log_contents();
+            }
+        });
+    }
+}
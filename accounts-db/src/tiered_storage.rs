@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 
 pub mod byte_block;
+pub mod cold;
 pub mod error;
 pub mod file;
 pub mod footer;
@@ -14,14 +15,17 @@ mod test_utils;
 
 use {
     crate::{accounts_file::StoredAccountsInfo, storable_accounts::StorableAccounts},
+    bytemuck::Zeroable,
+    cold::ColdStorageWriter,
     error::TieredStorageError,
+    file::{TieredReadableFile, TieredStorageMagicNumber},
     footer::{AccountBlockFormat, AccountMetaFormat},
     hot::{HotStorageWriter, HOT_FORMAT},
     index::IndexBlockFormat,
     owners::OwnersBlockFormat,
     readable::TieredStorageReader,
     std::{
-        fs, io,
+        fs, io, mem,
         path::{Path, PathBuf},
         sync::{
             atomic::{AtomicBool, Ordering},
@@ -43,6 +47,13 @@ pub struct TieredStorageFormat {
     pub owners_block_format: OwnersBlockFormat,
     pub index_block_format: IndexBlockFormat,
     pub account_block_format: AccountBlockFormat,
+    /// The compression level of the account blocks, where 0 means the
+    /// default level of the account block format.
+    pub compression_level: i32,
+    /// The maximum size of the compression dictionary that is trained from
+    /// the accounts and stored in the file, or 0 to compress the account
+    /// blocks without a dictionary.  Only applies to AccountBlockFormat::Zstd.
+    pub max_dictionary_size: usize,
 }
 
 /// The implementation of AccountsFile for tiered-storage.
@@ -54,6 +65,9 @@ pub struct TieredStorage {
     already_written: AtomicBool,
     /// The path to the file that stores accounts.
     path: PathBuf,
+    /// The format used by write_accounts_with_default_format() when writing
+    /// accounts into this TieredStorage.
+    default_format: TieredStorageFormat,
 }
 
 impl Drop for TieredStorage {
@@ -78,10 +92,24 @@ impl TieredStorage {
     /// Note that the actual file will not be created until write_accounts
     /// is called.
     pub fn new_writable(path: impl Into<PathBuf>) -> Self {
+        Self::new_writable_with_format(path, HOT_FORMAT)
+    }
+
+    /// Creates a new writable instance of TieredStorage based on the
+    /// specified path, and the specified TieredStorageFormat as the
+    /// default format of the accounts file.
+    ///
+    /// Note that the actual file will not be created until write_accounts
+    /// is called.
+    pub fn new_writable_with_format(
+        path: impl Into<PathBuf>,
+        default_format: TieredStorageFormat,
+    ) -> Self {
         Self {
             reader: OnceLock::<TieredStorageReader>::new(),
             already_written: false.into(),
             path: path.into(),
+            default_format,
         }
     }
 
@@ -93,9 +121,24 @@ impl TieredStorage {
             reader: TieredStorageReader::new_from_path(&path).map(OnceLock::from)?,
             already_written: true.into(),
             path,
+            default_format: HOT_FORMAT,
         })
     }
 
+    /// Returns whether the file at the specified path is a tiered accounts
+    /// file, i.e. whether it ends with the tiered storage magic number.
+    pub fn is_tiered_storage_file(path: impl AsRef<Path>) -> io::Result<bool> {
+        let file = TieredReadableFile(fs::File::open(path)?);
+        let magic_number_size = mem::size_of::<TieredStorageMagicNumber>();
+        if file.0.metadata()?.len() < magic_number_size as u64 {
+            return Ok(false);
+        }
+        file.seek_from_end(-(magic_number_size as i64))?;
+        let mut magic_number = TieredStorageMagicNumber::zeroed();
+        file.read_pod(&mut magic_number)?;
+        Ok(magic_number == TieredStorageMagicNumber::default())
+    }
+
     /// Returns the path to this TieredStorage.
     pub fn path(&self) -> &Path {
         self.path.as_path()
@@ -117,26 +160,42 @@ impl TieredStorage {
             panic!("cannot write same tiered storage file more than once");
         }
 
-        if format == &HOT_FORMAT {
-            let stored_accounts_info = {
-                let mut writer = HotStorageWriter::new(&self.path)?;
-                let stored_accounts_info = writer.write_accounts(accounts, skip)?;
-                writer.flush()?;
-                stored_accounts_info
-            };
-
-            // panic here if self.reader.get() is not None as self.reader can only be
-            // None since a false-value `was_written` indicates the accounts file has
-            // not been written previously, implying is_read_only() was also false.
-            debug_assert!(!self.is_read_only());
-            self.reader
-                .set(TieredStorageReader::new_from_path(&self.path)?)
-                .unwrap();
-
-            Ok(stored_accounts_info)
+        let stored_accounts_info = if format == &HOT_FORMAT {
+            let mut writer = HotStorageWriter::new(&self.path)?;
+            let stored_accounts_info = writer.write_accounts(accounts, skip)?;
+            writer.flush()?;
+            stored_accounts_info
+        } else if format.account_meta_format == AccountMetaFormat::Cold {
+            let mut writer = ColdStorageWriter::new_with_format(&self.path, format)?;
+            let stored_accounts_info = writer.write_accounts(accounts, skip)?;
+            writer.flush()?;
+            stored_accounts_info
         } else {
-            Err(TieredStorageError::UnknownFormat(self.path.to_path_buf()))
-        }
+            return Err(TieredStorageError::UnknownFormat(self.path.to_path_buf()));
+        };
+
+        // panic here if self.reader.get() is not None as self.reader can only be
+        // None since a false-value `was_written` indicates the accounts file has
+        // not been written previously, implying is_read_only() was also false.
+        debug_assert!(!self.is_read_only());
+        self.reader
+            .set(TieredStorageReader::new_from_path(&self.path)?)
+            .unwrap();
+
+        Ok(stored_accounts_info)
+    }
+
+    /// Writes the specified accounts into this TieredStorage using the
+    /// default format specified when this TieredStorage was created.
+    ///
+    /// Note that this function can only be called once per a TieredStorage
+    /// instance.  Otherwise, it will trigger panic.
+    pub fn write_accounts_with_default_format<'a>(
+        &self,
+        accounts: &impl StorableAccounts<'a>,
+        skip: usize,
+    ) -> TieredStorageResult<StoredAccountsInfo> {
+        self.write_accounts(accounts, skip, &self.default_format)
     }
 
     /// Returns the underlying reader of the TieredStorage.  None will be
@@ -175,6 +234,7 @@ impl TieredStorage {
 mod tests {
     use {
         super::*,
+        cold::{COLD_FORMAT, COLD_ZSTD_FORMAT},
         file::TieredStorageMagicNumber,
         footer::TieredStorageFooter,
         hot::HOT_FORMAT,
@@ -269,6 +329,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_is_tiered_storage_file() {
+        let temp_dir = tempdir().unwrap();
+        let tiered_storage_path = temp_dir.path().join("test_is_tiered_storage_file");
+        let tiered_storage = TieredStorage::new_writable(&tiered_storage_path);
+        write_zero_accounts(
+            &tiered_storage,
+            Ok(StoredAccountsInfo {
+                offsets: vec![],
+                size: 0,
+            }),
+        );
+        assert!(TieredStorage::is_tiered_storage_file(&tiered_storage_path).unwrap());
+
+        let other_path = temp_dir.path().join("test_is_not_tiered_storage_file");
+        fs::write(&other_path, [0u8; 64]).unwrap();
+        assert!(!TieredStorage::is_tiered_storage_file(&other_path).unwrap());
+
+        let short_path = temp_dir.path().join("test_short_file");
+        fs::write(&short_path, [0u8; 4]).unwrap();
+        assert!(!TieredStorage::is_tiered_storage_file(&short_path).unwrap());
+    }
+
     #[test]
     #[should_panic(expected = "cannot write same tiered storage file more than once")]
     fn test_write_accounts_twice() {
@@ -342,7 +425,6 @@ mod tests {
     }
 
     /// The helper function for all write_accounts tests.
-    /// Currently only supports hot accounts.
     fn do_test_write_accounts(
         path_suffix: &str,
         account_data_sizes: &[u64],
@@ -439,4 +521,71 @@ mod tests {
             HOT_FORMAT.clone(),
         );
     }
+
+    #[test]
+    fn test_write_cold_accounts_small_accounts() {
+        do_test_write_accounts(
+            "test_write_cold_accounts_small_accounts",
+            &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
+            COLD_FORMAT.clone(),
+        );
+    }
+
+    #[test]
+    fn test_write_cold_accounts_one_max_len() {
+        do_test_write_accounts(
+            "test_write_cold_accounts_one_max_len",
+            &[MAX_PERMITTED_DATA_LENGTH],
+            COLD_FORMAT.clone(),
+        );
+    }
+
+    #[test]
+    fn test_write_cold_accounts_mixed_size() {
+        do_test_write_accounts(
+            "test_write_cold_accounts_mixed_size",
+            &[
+                1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 1000, 2000, 3000, 4000, 9, 8, 7, 6, 5, 4, 3, 2, 1,
+            ],
+            COLD_FORMAT.clone(),
+        );
+    }
+
+    #[test]
+    fn test_write_cold_zstd_accounts_mixed_size() {
+        do_test_write_accounts(
+            "test_write_cold_zstd_accounts_mixed_size",
+            &[
+                1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 1000, 2000, 3000, 4000, 9, 8, 7, 6, 5, 4, 3, 2, 1,
+            ],
+            COLD_ZSTD_FORMAT.clone(),
+        );
+    }
+
+    #[test]
+    fn test_write_accounts_with_default_format() {
+        let temp_dir = tempdir().unwrap();
+        let tiered_storage_path = temp_dir
+            .path()
+            .join("test_write_accounts_with_default_format");
+        let tiered_storage =
+            TieredStorage::new_writable_with_format(&tiered_storage_path, COLD_FORMAT);
+
+        let account_refs = Vec::<(&Pubkey, &AccountSharedData)>::new();
+        let storable_accounts = (Slot::MAX, account_refs.as_slice());
+        tiered_storage
+            .write_accounts_with_default_format(&storable_accounts, 0)
+            .unwrap();
+
+        let footer = tiered_storage.footer().unwrap();
+        assert_eq!(footer.account_meta_format, COLD_FORMAT.account_meta_format);
+        assert_eq!(
+            footer.account_block_format,
+            COLD_FORMAT.account_block_format
+        );
+        assert!(matches!(
+            tiered_storage.reader().unwrap(),
+            TieredStorageReader::Cold(_)
+        ));
+    }
 }
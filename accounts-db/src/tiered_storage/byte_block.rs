@@ -4,16 +4,29 @@
 use {
     crate::tiered_storage::{footer::AccountBlockFormat, meta::AccountMetaOptionalFields},
     std::{
+        fmt,
         io::{Cursor, Read, Result as IoResult, Write},
         mem, ptr,
     },
+    zstd::dict::{DecoderDictionary, EncoderDictionary},
 };
 
 /// The encoder for the byte-block.
-#[derive(Debug)]
-pub enum ByteBlockEncoder {
+pub enum ByteBlockEncoder<'a> {
     Raw(Cursor<Vec<u8>>),
     Lz4(lz4::Encoder<Vec<u8>>),
+    Zstd(zstd::stream::write::Encoder<'a, Vec<u8>>),
+}
+
+impl fmt::Debug for ByteBlockEncoder<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Raw(cursor) => f.debug_tuple("Raw").field(cursor).finish(),
+            Self::Lz4(lz4_encoder) => f.debug_tuple("Lz4").field(lz4_encoder).finish(),
+            // zstd encoders do not implement Debug
+            Self::Zstd(_) => f.debug_tuple("Zstd").finish(),
+        }
+    }
 }
 
 /// The byte block writer.
@@ -24,28 +37,45 @@ pub enum ByteBlockEncoder {
 /// To finalize all the writes, invoke `finish` to obtain the encoded byte
 /// block.
 #[derive(Debug)]
-pub struct ByteBlockWriter {
+pub struct ByteBlockWriter<'a> {
     /// the encoder for the byte-block
-    encoder: ByteBlockEncoder,
+    encoder: ByteBlockEncoder<'a>,
     /// the length of the raw data
     len: usize,
 }
 
-impl ByteBlockWriter {
+impl<'a> ByteBlockWriter<'a> {
     /// Create a ByteBlockWriter from the specified AccountBlockFormat.
     pub fn new(encoding: AccountBlockFormat) -> Self {
-        Self {
-            encoder: match encoding {
-                AccountBlockFormat::AlignedRaw => ByteBlockEncoder::Raw(Cursor::new(Vec::new())),
-                AccountBlockFormat::Lz4 => ByteBlockEncoder::Lz4(
-                    lz4::EncoderBuilder::new()
-                        .level(0)
-                        .build(Vec::new())
-                        .unwrap(),
-                ),
-            },
-            len: 0,
-        }
+        Self::new_with_compression(encoding, 0, None).unwrap()
+    }
+
+    /// Create a ByteBlockWriter from the specified AccountBlockFormat,
+    /// compression level, and prepared compression dictionary.
+    ///
+    /// A compression level of 0 means the default level of the specified
+    /// format.  The dictionary is only used by AccountBlockFormat::Zstd, in
+    /// which case the compression level it was prepared with is used instead.
+    pub fn new_with_compression(
+        encoding: AccountBlockFormat,
+        compression_level: i32,
+        dictionary: Option<&'a EncoderDictionary<'static>>,
+    ) -> IoResult<Self> {
+        let encoder = match encoding {
+            AccountBlockFormat::AlignedRaw => ByteBlockEncoder::Raw(Cursor::new(Vec::new())),
+            AccountBlockFormat::Lz4 => ByteBlockEncoder::Lz4(
+                lz4::EncoderBuilder::new()
+                    .level(compression_level.max(0) as u32)
+                    .build(Vec::new())?,
+            ),
+            AccountBlockFormat::Zstd => ByteBlockEncoder::Zstd(match dictionary {
+                Some(dictionary) => {
+                    zstd::stream::write::Encoder::with_prepared_dictionary(Vec::new(), dictionary)?
+                }
+                None => zstd::stream::write::Encoder::new(Vec::new(), compression_level)?,
+            }),
+        };
+        Ok(Self { encoder, len: 0 })
     }
 
     /// Return the length of the raw data (i.e. after decoding).
@@ -107,6 +137,7 @@ impl ByteBlockWriter {
         match &mut self.encoder {
             ByteBlockEncoder::Raw(cursor) => cursor.write_all(buf)?,
             ByteBlockEncoder::Lz4(lz4_encoder) => lz4_encoder.write_all(buf)?,
+            ByteBlockEncoder::Zstd(zstd_encoder) => zstd_encoder.write_all(buf)?,
         };
         self.len += buf.len();
         Ok(())
@@ -122,6 +153,7 @@ impl ByteBlockWriter {
                 result?;
                 Ok(compressed_block)
             }
+            ByteBlockEncoder::Zstd(zstd_encoder) => zstd_encoder.finish(),
         }
     }
 }
@@ -129,6 +161,60 @@ impl ByteBlockWriter {
 /// The util struct for reading byte blocks.
 pub struct ByteBlockReader;
 
+/// The streaming decoder for the byte-block.
+///
+/// Unlike `ByteBlockReader::decode`, it decodes the input lazily, so that a
+/// caller that only needs a prefix of the decoded byte block does not pay for
+/// decoding the rest of it.
+pub enum ByteBlockDecoder<'a> {
+    Raw(&'a [u8]),
+    Lz4(lz4::Decoder<&'a [u8]>),
+    Zstd(zstd::stream::read::Decoder<'a, &'a [u8]>),
+}
+
+impl<'a> ByteBlockDecoder<'a> {
+    /// Create a ByteBlockDecoder that decodes the input byte array using the
+    /// specified format and the prepared dictionary that was used to encode it.
+    ///
+    /// Unlike `ByteBlockReader::decode`, an input of AccountBlockFormat::AlignedRaw
+    /// encoding is read as is.
+    pub fn new(
+        encoding: AccountBlockFormat,
+        input: &'a [u8],
+        dictionary: Option<&'a DecoderDictionary<'static>>,
+    ) -> IoResult<Self> {
+        Ok(match encoding {
+            AccountBlockFormat::AlignedRaw => Self::Raw(input),
+            AccountBlockFormat::Lz4 => Self::Lz4(lz4::Decoder::new(input)?),
+            AccountBlockFormat::Zstd => Self::Zstd(new_zstd_decoder(input, dictionary)?),
+        })
+    }
+}
+
+/// Create a zstd decoder of the input byte array that uses the specified
+/// prepared dictionary, if any.
+fn new_zstd_decoder<'a>(
+    input: &'a [u8],
+    dictionary: Option<&'a DecoderDictionary<'static>>,
+) -> IoResult<zstd::stream::read::Decoder<'a, &'a [u8]>> {
+    match dictionary {
+        Some(dictionary) => {
+            zstd::stream::read::Decoder::with_prepared_dictionary(input, dictionary)
+        }
+        None => zstd::stream::read::Decoder::with_buffer(input),
+    }
+}
+
+impl Read for ByteBlockDecoder<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        match self {
+            Self::Raw(input) => input.read(buf),
+            Self::Lz4(decoder) => decoder.read(buf),
+            Self::Zstd(decoder) => decoder.read(buf),
+        }
+    }
+}
+
 /// Reads the raw part of the input byte_block, at the specified offset, as type T.
 ///
 /// Returns None if `offset` + size_of::<T>() exceeds the size of the input byte_block.
@@ -174,6 +260,21 @@ impl ByteBlockReader {
     /// Note that calling this function with AccountBlockFormat::AlignedRaw encoding
     /// will result in panic as the input is already decoded.
     pub fn decode(encoding: AccountBlockFormat, input: &[u8]) -> IoResult<Vec<u8>> {
+        Self::decode_with_dictionary(encoding, input, None)
+    }
+
+    /// Decode the input byte array using the specified format and the
+    /// prepared dictionary that was used to encode it.
+    ///
+    /// The dictionary is only used by AccountBlockFormat::Zstd.
+    ///
+    /// Note that calling this function with AccountBlockFormat::AlignedRaw encoding
+    /// will result in panic as the input is already decoded.
+    pub fn decode_with_dictionary(
+        encoding: AccountBlockFormat,
+        input: &[u8],
+        dictionary: Option<&DecoderDictionary<'static>>,
+    ) -> IoResult<Vec<u8>> {
         match encoding {
             AccountBlockFormat::Lz4 => {
                 let mut decoder = lz4::Decoder::new(input).unwrap();
@@ -181,6 +282,12 @@ impl ByteBlockReader {
                 decoder.read_to_end(&mut output)?;
                 Ok(output)
             }
+            AccountBlockFormat::Zstd => {
+                let mut decoder = new_zstd_decoder(input, dictionary)?;
+                let mut output = vec![];
+                decoder.read_to_end(&mut output)?;
+                Ok(output)
+            }
             AccountBlockFormat::AlignedRaw => panic!("the input buffer is already decoded"),
         }
     }
@@ -235,6 +342,11 @@ mod tests {
         write_single(AccountBlockFormat::Lz4);
     }
 
+    #[test]
+    fn test_write_single_zstd_format() {
+        write_single(AccountBlockFormat::Zstd);
+    }
+
     #[derive(Debug, PartialEq)]
     struct TestMetaStruct {
         lamports: u64,
@@ -339,6 +451,11 @@ mod tests {
         write_multiple(AccountBlockFormat::Lz4);
     }
 
+    #[test]
+    fn test_write_multiple_zstd_format() {
+        write_multiple(AccountBlockFormat::Zstd);
+    }
+
     fn write_optional_fields(format: AccountBlockFormat) {
         let mut test_epoch = 5432312;
 
@@ -399,4 +516,89 @@ mod tests {
     fn test_write_optional_fields_lz4_format() {
         write_optional_fields(AccountBlockFormat::Lz4);
     }
+
+    #[test]
+    fn test_write_optional_fields_zstd_format() {
+        write_optional_fields(AccountBlockFormat::Zstd);
+    }
+
+    fn decode_prefix(format: AccountBlockFormat) {
+        let data: Vec<u8> = (0..4096u32).map(|i| (i % 251) as u8).collect();
+        let mut writer = ByteBlockWriter::new(format);
+        writer.write(&data).unwrap();
+        let buffer = writer.finish().unwrap();
+
+        let mut decoder = ByteBlockDecoder::new(format, &buffer, None).unwrap();
+        let mut prefix = vec![0u8; 100];
+        decoder.read_exact(&mut prefix).unwrap();
+        assert_eq!(prefix, data[..100]);
+
+        // the rest of the byte block can still be read
+        let mut rest = vec![];
+        decoder.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, data[100..]);
+    }
+
+    #[test]
+    fn test_decode_prefix_raw_format() {
+        decode_prefix(AccountBlockFormat::AlignedRaw);
+    }
+
+    #[test]
+    fn test_decode_prefix_lz4_format() {
+        decode_prefix(AccountBlockFormat::Lz4);
+    }
+
+    #[test]
+    fn test_decode_prefix_zstd_format() {
+        decode_prefix(AccountBlockFormat::Zstd);
+    }
+
+    #[test]
+    fn test_zstd_with_dictionary() {
+        // bytes that do not compress well by themselves
+        let data: Vec<u8> = (0..1024u32)
+            .map(|i| (i.wrapping_mul(2654435761) >> 13) as u8)
+            .collect();
+        // a raw-content dictionary that contains the data
+        let encoder_dictionary = EncoderDictionary::copy(&data, 3);
+        let decoder_dictionary = DecoderDictionary::copy(&data);
+
+        let mut writer =
+            ByteBlockWriter::new_with_compression(AccountBlockFormat::Zstd, 3, None).unwrap();
+        writer.write(&data).unwrap();
+        let buffer_without_dictionary = writer.finish().unwrap();
+
+        // the same prepared dictionary can be shared by many writers
+        for _ in 0..2 {
+            let mut writer = ByteBlockWriter::new_with_compression(
+                AccountBlockFormat::Zstd,
+                3,
+                Some(&encoder_dictionary),
+            )
+            .unwrap();
+            writer.write(&data).unwrap();
+            assert_eq!(writer.raw_len(), data.len());
+            let buffer = writer.finish().unwrap();
+            assert!(buffer.len() < buffer_without_dictionary.len());
+
+            let decoded_buffer = ByteBlockReader::decode_with_dictionary(
+                AccountBlockFormat::Zstd,
+                &buffer,
+                Some(&decoder_dictionary),
+            )
+            .unwrap();
+            assert_eq!(decoded_buffer, data);
+
+            let mut decoder =
+                ByteBlockDecoder::new(AccountBlockFormat::Zstd, &buffer, Some(&decoder_dictionary))
+                    .unwrap();
+            let mut decoded_buffer = vec![];
+            decoder.read_to_end(&mut decoded_buffer).unwrap();
+            assert_eq!(decoded_buffer, data);
+
+            // the block cannot be decoded without its dictionary
+            assert!(ByteBlockReader::decode(AccountBlockFormat::Zstd, &buffer).is_err());
+        }
+    }
 }
@@ -0,0 +1,1421 @@
+//! The account meta and related structs for cold accounts.
+//!
+//! Unlike hot accounts, cold accounts are packed into compressed account
+//! blocks.  Each account block holds one or more account entries, and an
+//! account entry consists of the following elements before compression:
+//!
+//! * ColdAccountMeta
+//! * [u8] account data
+//! * optional fields
+//!
+//! Accounts whose entry is larger than the account block size of the file
+//! are blob accounts, and each of them occupies its own account block.
+//!
+//! When the account blocks are compressed by zstd, a dictionary trained from
+//! the account entries of the file can be stored in the dictionary block that
+//! follows the account blocks.
+
+use {
+    crate::{
+        account_info::AccountInfo,
+        account_storage::meta::StoredAccountMeta,
+        accounts_file::{MatchAccountOwnerError, StoredAccountsInfo},
+        append_vec::{IndexInfo, IndexInfoInner},
+        tiered_storage::{
+            byte_block::{ByteBlockDecoder, ByteBlockReader, ByteBlockWriter},
+            file::{TieredReadableFile, TieredWritableFile},
+            footer::{AccountBlockFormat, AccountMetaFormat, TieredStorageFooter},
+            hot::HotAccount,
+            index::{AccountIndexWriterEntry, AccountOffset, IndexBlockFormat, IndexOffset},
+            meta::{
+                AccountAddressRange, AccountMetaFlags, AccountMetaOptionalFields, TieredAccountMeta,
+            },
+            mmap_utils::{get_pod, get_slice},
+            owners::{OwnerOffset, OwnersBlockFormat, OwnersTable},
+            StorableAccounts, TieredStorageError, TieredStorageFormat, TieredStorageResult,
+        },
+    },
+    bytemuck_derive::{Pod, Zeroable},
+    memmap2::{Mmap, MmapOptions},
+    solana_sdk::{
+        account::{AccountSharedData, ReadableAccount, WritableAccount},
+        pubkey::Pubkey,
+        rent_collector::RENT_EXEMPT_RENT_EPOCH,
+        stake_history::Epoch,
+    },
+    std::{
+        fmt,
+        io::{self, Read, Write},
+        option::Option,
+        path::Path,
+    },
+    zstd::dict::{DecoderDictionary, EncoderDictionary},
+};
+
+pub const COLD_FORMAT: TieredStorageFormat = TieredStorageFormat {
+    meta_entry_size: std::mem::size_of::<ColdAccountMeta>(),
+    account_meta_format: AccountMetaFormat::Cold,
+    owners_block_format: OwnersBlockFormat::AddressesOnly,
+    index_block_format: IndexBlockFormat::AddressesThenOffsets,
+    account_block_format: AccountBlockFormat::Lz4,
+    compression_level: 0,
+    max_dictionary_size: 0,
+};
+
+/// The cold format whose account blocks are compressed by zstd with a
+/// dictionary trained from the accounts of each file.  It costs more CPU
+/// than COLD_FORMAT in exchange for a better compression ratio.
+pub const COLD_ZSTD_FORMAT: TieredStorageFormat = TieredStorageFormat {
+    account_block_format: AccountBlockFormat::Zstd,
+    compression_level: COLD_ZSTD_COMPRESSION_LEVEL,
+    max_dictionary_size: COLD_ZSTD_MAX_DICTIONARY_SIZE,
+    ..COLD_FORMAT
+};
+
+/// The zstd compression level of COLD_ZSTD_FORMAT.
+const COLD_ZSTD_COMPRESSION_LEVEL: i32 = 9;
+
+/// The maximum size of the trained dictionary of COLD_ZSTD_FORMAT.
+const COLD_ZSTD_MAX_DICTIONARY_SIZE: usize = 16 * 1024;
+
+/// The maximum number of account entries that are sampled to train the
+/// compression dictionary of a cold accounts file.
+const MAX_DICTIONARY_SAMPLES: usize = 4096;
+
+/// The default size of a cold account block before compression.
+pub(crate) const COLD_ACCOUNT_BLOCK_SIZE: usize = 4096;
+
+/// The alignment for the blocks inside a cold accounts file.  A cold accounts
+/// file consists of accounts blocks, index block, owners block, and footer.
+/// This requirement allows the offset of each block properly aligned so
+/// that they can be readable under mmap.
+pub(crate) const COLD_BLOCK_ALIGNMENT: usize = 8;
+
+/// The maximum supported offset for a cold account block.
+const MAX_COLD_BLOCK_OFFSET: usize = u32::MAX as usize * COLD_BLOCK_ALIGNMENT;
+
+/// The buffer that is used for padding.
+const PADDING_BUFFER: [u8; COLD_BLOCK_ALIGNMENT] = [0u8; COLD_BLOCK_ALIGNMENT];
+
+// returns the required number of padding
+fn padding_bytes(len: usize) -> usize {
+    (COLD_BLOCK_ALIGNMENT - (len % COLD_BLOCK_ALIGNMENT)) % COLD_BLOCK_ALIGNMENT
+}
+
+/// A helper function that creates a new default footer for cold
+/// accounts storage of the specified format.
+fn new_cold_footer(format: &TieredStorageFormat) -> TieredStorageFooter {
+    TieredStorageFooter {
+        account_meta_format: format.account_meta_format,
+        account_meta_entry_size: format.meta_entry_size as u32,
+        account_block_format: format.account_block_format,
+        account_block_size: COLD_ACCOUNT_BLOCK_SIZE as u64,
+        index_block_format: format.index_block_format,
+        owners_block_format: format.owners_block_format,
+        compression_level: format.compression_level,
+        ..TieredStorageFooter::default()
+    }
+}
+
+/// The offset to access a cold account.
+///
+/// As multiple cold accounts can share the same compressed account block,
+/// the offset consists of the offset to the account block inside the file
+/// and the offset to the account entry inside the decoded account block.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Pod, Zeroable)]
+pub struct ColdAccountOffset {
+    /// The offset to the account block in the unit of COLD_BLOCK_ALIGNMENT.
+    block_offset: u32,
+    /// The offset to the account entry inside the decoded account block.
+    intra_block_offset: u32,
+}
+
+// Ensure there are no implicit padding bytes
+const _: () = assert!(std::mem::size_of::<ColdAccountOffset>() == 8);
+
+impl AccountOffset for ColdAccountOffset {}
+
+impl ColdAccountOffset {
+    /// Creates a new ColdAccountOffset instance
+    pub fn new(block_offset: usize, intra_block_offset: usize) -> TieredStorageResult<Self> {
+        if block_offset > MAX_COLD_BLOCK_OFFSET {
+            return Err(TieredStorageError::OffsetOutOfBounds(
+                block_offset,
+                MAX_COLD_BLOCK_OFFSET,
+            ));
+        }
+
+        // Cold account blocks are aligned based on COLD_BLOCK_ALIGNMENT.
+        if block_offset % COLD_BLOCK_ALIGNMENT != 0 {
+            return Err(TieredStorageError::OffsetAlignmentError(
+                block_offset,
+                COLD_BLOCK_ALIGNMENT,
+            ));
+        }
+
+        let intra_block_offset = u32::try_from(intra_block_offset).map_err(|_| {
+            TieredStorageError::OffsetOutOfBounds(intra_block_offset, u32::MAX as usize)
+        })?;
+
+        Ok(Self {
+            block_offset: (block_offset / COLD_BLOCK_ALIGNMENT) as u32,
+            intra_block_offset,
+        })
+    }
+
+    /// Returns the offset to the account block that contains the account.
+    fn block_offset(&self) -> usize {
+        self.block_offset as usize * COLD_BLOCK_ALIGNMENT
+    }
+
+    /// Returns the offset to the account entry inside its decoded account block.
+    fn intra_block_offset(&self) -> usize {
+        self.intra_block_offset as usize
+    }
+}
+
+/// The storage and in-memory representation of the metadata entry for a
+/// cold account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Pod, Zeroable)]
+#[repr(C)]
+pub struct ColdAccountMeta {
+    /// The balance of this account.
+    lamports: u64,
+    /// The size of the account data before compression.
+    account_data_size: u64,
+    /// The index to the owner of a cold account inside an AccountsFile.
+    owner_offset: u32,
+    /// Stores boolean flags and existence of each optional field.
+    flags: AccountMetaFlags,
+}
+
+// Ensure there are no implicit padding bytes
+const _: () = assert!(std::mem::size_of::<ColdAccountMeta>() == 8 + 8 + 4 + 4);
+
+impl TieredAccountMeta for ColdAccountMeta {
+    /// Construct a ColdAccountMeta instance.
+    fn new() -> Self {
+        ColdAccountMeta {
+            lamports: 0,
+            account_data_size: 0,
+            owner_offset: 0,
+            flags: AccountMetaFlags::new(),
+        }
+    }
+
+    /// A builder function that initializes lamports.
+    fn with_lamports(mut self, lamports: u64) -> Self {
+        self.lamports = lamports;
+        self
+    }
+
+    /// A builder function that initializes the number of padding bytes
+    /// for the account data associated with the current meta.
+    fn with_account_data_padding(self, _padding: u8) -> Self {
+        // Cold account entries are not padded as the account data is
+        // always accessed from a decoded account block.
+        self
+    }
+
+    /// A builder function that initializes the owner's index.
+    fn with_owner_offset(mut self, owner_offset: OwnerOffset) -> Self {
+        self.owner_offset = owner_offset.0;
+        self
+    }
+
+    /// A builder function that initializes the account data size.
+    fn with_account_data_size(mut self, account_data_size: u64) -> Self {
+        self.account_data_size = account_data_size;
+        self
+    }
+
+    /// A builder function that initializes the AccountMetaFlags of the current
+    /// meta.
+    fn with_flags(mut self, flags: &AccountMetaFlags) -> Self {
+        self.flags = *flags;
+        self
+    }
+
+    /// Returns the balance of the lamports associated with the account.
+    fn lamports(&self) -> u64 {
+        self.lamports
+    }
+
+    /// Always returns 0 as cold account entries are not padded.
+    fn account_data_padding(&self) -> u8 {
+        0
+    }
+
+    /// Returns the index to the accounts' owner in the current AccountsFile.
+    fn owner_offset(&self) -> OwnerOffset {
+        OwnerOffset(self.owner_offset)
+    }
+
+    /// Returns the AccountMetaFlags of the current meta.
+    fn flags(&self) -> &AccountMetaFlags {
+        &self.flags
+    }
+
+    /// Always returns true as multiple cold accounts can share the same
+    /// account block.
+    fn supports_shared_account_block() -> bool {
+        true
+    }
+
+    /// Returns the epoch that this account will next owe rent by parsing
+    /// the specified account block.  None will be returned if this account
+    /// does not persist this optional field.
+    fn rent_epoch(&self, account_block: &[u8]) -> Option<Epoch> {
+        self.flags()
+            .has_rent_epoch()
+            .then(|| {
+                let offset = self.optional_fields_offset(account_block)
+                    + AccountMetaOptionalFields::rent_epoch_offset(self.flags());
+                // A decoded account block does not guarantee any alignment,
+                // so the field is read unaligned.
+                account_block
+                    .get(offset..offset.saturating_add(std::mem::size_of::<Epoch>()))
+                    .map(bytemuck::pod_read_unaligned::<Epoch>)
+            })
+            .flatten()
+    }
+
+    /// Returns the epoch that this account will next owe rent by parsing
+    /// the specified account block.  RENT_EXEMPT_RENT_EPOCH will be returned
+    /// if the account is rent-exempt.
+    ///
+    /// For a zero-lamport account, Epoch::default() will be returned to
+    /// default states of an AccountSharedData.
+    fn final_rent_epoch(&self, account_block: &[u8]) -> Epoch {
+        self.rent_epoch(account_block)
+            .unwrap_or(if self.lamports() != 0 {
+                RENT_EXEMPT_RENT_EPOCH
+            } else {
+                // Same as hot accounts, return Epoch::default() to match
+                // the default states of AccountSharedData.
+                Epoch::default()
+            })
+    }
+
+    /// Returns the offset of the optional fields based on the specified account
+    /// block.
+    fn optional_fields_offset(&self, account_block: &[u8]) -> usize {
+        self.account_data_size(account_block)
+    }
+
+    /// Returns the length of the data associated to this account based on the
+    /// specified account block.
+    fn account_data_size(&self, account_block: &[u8]) -> usize {
+        (self.account_data_size as usize).min(account_block.len())
+    }
+
+    /// Returns the data associated to this account based on the specified
+    /// account block.
+    fn account_data<'a>(&self, account_block: &'a [u8]) -> &'a [u8] {
+        &account_block[..self.account_data_size(account_block)]
+    }
+}
+
+/// The struct that offers read APIs for accessing a cold account.
+///
+/// The account block of a cold account refers to the account data and
+/// optional fields of the account inside its decoded account block.
+pub type ColdAccount<'accounts_file> = HotAccount<'accounts_file, ColdAccountMeta>;
+
+/// Returns the size of a cold account entry before compression.
+fn account_entry_size(meta: &ColdAccountMeta) -> usize {
+    std::mem::size_of::<ColdAccountMeta>()
+        .saturating_add(meta.account_data_size as usize)
+        .saturating_add(AccountMetaOptionalFields::size_from_flags(meta.flags()))
+}
+
+/// Parses the account entry at the specified offset of the decoded account
+/// block, and returns its meta together with the part of the account block
+/// that holds its account data and optional fields.
+fn parse_account_entry(
+    decoded_block: &[u8],
+    intra_block_offset: usize,
+) -> TieredStorageResult<(ColdAccountMeta, &[u8])> {
+    let meta_end = intra_block_offset.saturating_add(std::mem::size_of::<ColdAccountMeta>());
+    let meta = decoded_block
+        .get(intra_block_offset..meta_end)
+        .map(bytemuck::pod_read_unaligned::<ColdAccountMeta>)
+        .ok_or(TieredStorageError::InvalidAccountEntry(intra_block_offset))?;
+
+    let entry_end = intra_block_offset.saturating_add(account_entry_size(&meta));
+    let account_block = decoded_block
+        .get(meta_end..entry_end)
+        .ok_or(TieredStorageError::InvalidAccountEntry(intra_block_offset))?;
+
+    Ok((meta, account_block))
+}
+
+/// The reader to a cold accounts file.
+pub struct ColdStorageReader {
+    mmap: Mmap,
+    footer: TieredStorageFooter,
+    /// The compression dictionary of the account blocks, prepared once
+    /// for all the reads of this file.
+    dictionary: Option<DecoderDictionary<'static>>,
+}
+
+impl fmt::Debug for ColdStorageReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ColdStorageReader")
+            .field("mmap", &self.mmap)
+            .field("footer", &self.footer)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ColdStorageReader {
+    pub fn new(file: TieredReadableFile) -> TieredStorageResult<Self> {
+        let mmap = unsafe { MmapOptions::new().map(&file.0)? };
+        // Same as HotStorageReader, the footer is copied to avoid the
+        // overhead of indirection associated with memory-mapped accesses.
+        let footer = TieredStorageFooter::new_from_mmap(&mmap)?;
+        let dictionary = if footer.dictionary_size > 0 {
+            let (dictionary, _) = get_slice(
+                &mmap,
+                footer.dictionary_block_offset as usize,
+                footer.dictionary_size as usize,
+            )?;
+            Some(DecoderDictionary::copy(dictionary))
+        } else {
+            None
+        };
+
+        Ok(Self {
+            mmap,
+            footer,
+            dictionary,
+        })
+    }
+
+    /// Returns the size of the underlying storage.
+    pub fn len(&self) -> usize {
+        self.mmap.len()
+    }
+
+    /// Returns whether the underlying storage is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.len() as u64
+    }
+
+    /// Returns the footer of the underlying tiered-storage accounts file.
+    pub fn footer(&self) -> &TieredStorageFooter {
+        &self.footer
+    }
+
+    /// Returns the number of files inside the underlying tiered-storage
+    /// accounts file.
+    pub fn num_accounts(&self) -> usize {
+        self.footer.account_entry_count as usize
+    }
+
+    /// Returns the offset to the account given the specified index.
+    pub(super) fn get_account_offset(
+        &self,
+        index_offset: IndexOffset,
+    ) -> TieredStorageResult<ColdAccountOffset> {
+        self.footer
+            .index_block_format
+            .get_account_offset::<ColdAccountOffset>(&self.mmap, &self.footer, index_offset)
+    }
+
+    /// Returns the address of the account associated with the specified index.
+    fn get_account_address(&self, index: IndexOffset) -> TieredStorageResult<&Pubkey> {
+        self.footer
+            .index_block_format
+            .get_account_address(&self.mmap, &self.footer, index)
+    }
+
+    /// Returns the address of the account owner given the specified
+    /// owner_offset.
+    fn get_owner_address(&self, owner_offset: OwnerOffset) -> TieredStorageResult<&Pubkey> {
+        self.footer
+            .owners_block_format
+            .get_owner_address(&self.mmap, &self.footer, owner_offset)
+    }
+
+    /// Returns the offset that is right after the last account block.
+    fn account_blocks_end_offset(&self) -> usize {
+        if self.footer.dictionary_size > 0 {
+            self.footer.dictionary_block_offset as usize
+        } else {
+            self.footer.index_block_offset as usize
+        }
+    }
+
+    /// Returns the encoded account block that starts at the specified offset.
+    ///
+    /// Each account block is persisted as its encoded size followed by the
+    /// encoded bytes.
+    fn get_encoded_account_block(&self, block_offset: usize) -> TieredStorageResult<&[u8]> {
+        assert!(
+            block_offset.saturating_add(std::mem::size_of::<u64>())
+                <= self.account_blocks_end_offset(),
+            "reading account block ({}) would exceed accounts blocks offset boundary ({}).",
+            block_offset,
+            self.account_blocks_end_offset(),
+        );
+        let (&encoded_len, encoded_offset) = get_pod::<u64>(&self.mmap, block_offset)?;
+        let (encoded_block, _) = get_slice(&self.mmap, encoded_offset, encoded_len as usize)?;
+        Ok(encoded_block)
+    }
+
+    /// Returns the decoded account block that starts at the specified offset.
+    fn get_decoded_account_block(&self, block_offset: usize) -> TieredStorageResult<Vec<u8>> {
+        let encoded_block = self.get_encoded_account_block(block_offset)?;
+        match self.footer.account_block_format {
+            AccountBlockFormat::AlignedRaw => Ok(encoded_block.to_vec()),
+            format => Ok(ByteBlockReader::decode_with_dictionary(
+                format,
+                encoded_block,
+                self.dictionary.as_ref(),
+            )?),
+        }
+    }
+
+    /// Returns the decoded account entry at the specified account offset.
+    ///
+    /// An account block can hold many accounts, so only the part of the
+    /// block up to the end of the requested entry is decoded, and only the
+    /// entry itself is kept.
+    fn get_decoded_account_entry(
+        &self,
+        account_offset: ColdAccountOffset,
+    ) -> TieredStorageResult<Vec<u8>> {
+        let encoded_block = self.get_encoded_account_block(account_offset.block_offset())?;
+        let mut decoder = ByteBlockDecoder::new(
+            self.footer.account_block_format,
+            encoded_block,
+            self.dictionary.as_ref(),
+        )?;
+
+        let intra_block_offset = account_offset.intra_block_offset();
+        let invalid_entry = || TieredStorageError::InvalidAccountEntry(intra_block_offset);
+        let skipped = io::copy(
+            &mut (&mut decoder).take(intra_block_offset as u64),
+            &mut io::sink(),
+        )?;
+        if skipped != intra_block_offset as u64 {
+            return Err(invalid_entry());
+        }
+
+        let mut entry = vec![0u8; std::mem::size_of::<ColdAccountMeta>()];
+        decoder
+            .read_exact(&mut entry)
+            .map_err(|_| invalid_entry())?;
+        let meta = bytemuck::pod_read_unaligned::<ColdAccountMeta>(&entry);
+        let entry_size = account_entry_size(&meta);
+        // The rest of the entry is read without allocating its size upfront,
+        // as a corrupted meta could claim any size.
+        (&mut decoder)
+            .take(entry_size.saturating_sub(entry.len()) as u64)
+            .read_to_end(&mut entry)?;
+        if entry.len() != entry_size {
+            return Err(invalid_entry());
+        }
+        Ok(entry)
+    }
+
+    /// Returns Ok(index_of_matching_owner) if the account owner at
+    /// `index_offset` is one of the pubkeys in `owners`.
+    ///
+    /// Returns Err(MatchAccountOwnerError::NoMatch) if the account has 0
+    /// lamports or the owner is not one of the pubkeys in `owners`.
+    ///
+    /// Returns Err(MatchAccountOwnerError::UnableToLoad) if there is any internal
+    /// error that causes the data unable to load, including `index_offset`
+    /// causes a data overrun.
+    pub fn account_matches_owners(
+        &self,
+        index_offset: IndexOffset,
+        owners: &[Pubkey],
+    ) -> Result<usize, MatchAccountOwnerError> {
+        let account_offset = self
+            .get_account_offset(index_offset)
+            .map_err(|_| MatchAccountOwnerError::UnableToLoad)?;
+        let entry = self
+            .get_decoded_account_entry(account_offset)
+            .map_err(|_| MatchAccountOwnerError::UnableToLoad)?;
+        let (account_meta, _) =
+            parse_account_entry(&entry, 0).map_err(|_| MatchAccountOwnerError::UnableToLoad)?;
+
+        if account_meta.lamports() == 0 {
+            Err(MatchAccountOwnerError::NoMatch)
+        } else {
+            let account_owner = self
+                .get_owner_address(account_meta.owner_offset())
+                .map_err(|_| MatchAccountOwnerError::UnableToLoad)?;
+
+            owners
+                .iter()
+                .position(|candidate| account_owner == candidate)
+                .ok_or(MatchAccountOwnerError::NoMatch)
+        }
+    }
+
+    /// calls `callback` with the account located at the specified index offset.
+    pub fn get_stored_account_meta_callback<Ret>(
+        &self,
+        index_offset: IndexOffset,
+        mut callback: impl for<'local> FnMut(StoredAccountMeta<'local>) -> Ret,
+    ) -> TieredStorageResult<Option<Ret>> {
+        if index_offset.0 >= self.footer.account_entry_count {
+            return Ok(None);
+        }
+
+        let account_offset = self.get_account_offset(index_offset)?;
+        let entry = self.get_decoded_account_entry(account_offset)?;
+        let (meta, account_block) = parse_account_entry(&entry, 0)?;
+        let address = self.get_account_address(index_offset)?;
+        let owner = self.get_owner_address(meta.owner_offset())?;
+
+        Ok(Some(callback(StoredAccountMeta::Cold(ColdAccount {
+            meta: &meta,
+            address,
+            owner,
+            index: index_offset,
+            account_block,
+        }))))
+    }
+
+    /// Returns the account located at the specified index offset.
+    pub fn get_account_shared_data(
+        &self,
+        index_offset: IndexOffset,
+    ) -> TieredStorageResult<Option<AccountSharedData>> {
+        if index_offset.0 >= self.footer.account_entry_count {
+            return Ok(None);
+        }
+
+        let account_offset = self.get_account_offset(index_offset)?;
+        let entry = self.get_decoded_account_entry(account_offset)?;
+        let (meta, account_block) = parse_account_entry(&entry, 0)?;
+
+        let lamports = meta.lamports();
+        let data = meta.account_data(account_block).to_vec();
+        let owner = *self.get_owner_address(meta.owner_offset())?;
+        let executable = meta.flags().executable();
+        let rent_epoch = meta.final_rent_epoch(account_block);
+        Ok(Some(AccountSharedData::create(
+            lamports, data, owner, executable, rent_epoch,
+        )))
+    }
+
+    /// Iterates over all accounts in the order of their index offsets and
+    /// calls `callback` with each account's index offset, meta and account
+    /// block.
+    ///
+    /// As accounts sharing the same account block are stored consecutively,
+    /// each account block is only decoded once.
+    fn scan_account_entries(
+        &self,
+        mut callback: impl FnMut(IndexOffset, &ColdAccountMeta, &[u8]) -> TieredStorageResult<()>,
+    ) -> TieredStorageResult<()> {
+        let mut decoded_block: Option<(usize, Vec<u8>)> = None;
+        for i in 0..self.footer.account_entry_count {
+            let index_offset = IndexOffset(i);
+            let account_offset = self.get_account_offset(index_offset)?;
+            let block_offset = account_offset.block_offset();
+            if decoded_block
+                .as_ref()
+                .map(|(cached_offset, _)| *cached_offset)
+                != Some(block_offset)
+            {
+                let block = self.get_decoded_account_block(block_offset)?;
+                decoded_block = Some((block_offset, block));
+            }
+            let (_, block) = decoded_block.as_ref().unwrap();
+            let (meta, account_block) =
+                parse_account_entry(block, account_offset.intra_block_offset())?;
+            callback(index_offset, &meta, account_block)?;
+        }
+        Ok(())
+    }
+
+    /// iterate over all pubkeys
+    pub fn scan_pubkeys(&self, mut callback: impl FnMut(&Pubkey)) -> TieredStorageResult<()> {
+        for i in 0..self.footer.account_entry_count {
+            let address = self.get_account_address(IndexOffset(i))?;
+            callback(address);
+        }
+        Ok(())
+    }
+
+    /// for each offset in `sorted_offsets`, return the account size
+    pub(crate) fn get_account_sizes(
+        &self,
+        sorted_offsets: &[usize],
+    ) -> TieredStorageResult<Vec<usize>> {
+        let mut result = Vec::with_capacity(sorted_offsets.len());
+        let mut decoded_block: Option<(usize, Vec<u8>)> = None;
+        for &offset in sorted_offsets {
+            let index_offset = IndexOffset(AccountInfo::get_reduced_offset(offset));
+            let account_offset = self.get_account_offset(index_offset)?;
+            let block_offset = account_offset.block_offset();
+            if decoded_block
+                .as_ref()
+                .map(|(cached_offset, _)| *cached_offset)
+                != Some(block_offset)
+            {
+                let block = self.get_decoded_account_block(block_offset)?;
+                decoded_block = Some((block_offset, block));
+            }
+            let (_, block) = decoded_block.as_ref().unwrap();
+            let (meta, account_block) =
+                parse_account_entry(block, account_offset.intra_block_offset())?;
+            result.push(stored_size(meta.account_data_size(account_block)));
+        }
+        Ok(result)
+    }
+
+    /// Iterate over all accounts and call `callback` with each account.
+    pub(crate) fn scan_accounts(
+        &self,
+        mut callback: impl for<'local> FnMut(StoredAccountMeta<'local>),
+    ) -> TieredStorageResult<()> {
+        self.scan_account_entries(|index_offset, meta, account_block| {
+            let address = self.get_account_address(index_offset)?;
+            let owner = self.get_owner_address(meta.owner_offset())?;
+            callback(StoredAccountMeta::Cold(ColdAccount {
+                meta,
+                address,
+                owner,
+                index: index_offset,
+                account_block,
+            }));
+            Ok(())
+        })
+    }
+
+    /// iterate over all entries to put in index
+    pub(crate) fn scan_index(
+        &self,
+        mut callback: impl FnMut(IndexInfo),
+    ) -> TieredStorageResult<()> {
+        self.scan_account_entries(|index_offset, meta, account_block| {
+            let pubkey = self.get_account_address(index_offset)?;
+            let data_len = meta.account_data_size(account_block);
+            callback(IndexInfo {
+                index_info: {
+                    IndexInfoInner {
+                        pubkey: *pubkey,
+                        lamports: meta.lamports(),
+                        offset: AccountInfo::reduced_offset_to_offset(index_offset.0),
+                        data_len: data_len as u64,
+                        executable: meta.flags().executable(),
+                        rent_epoch: meta.final_rent_epoch(account_block),
+                    }
+                },
+                stored_size_aligned: stored_size(data_len),
+            });
+            Ok(())
+        })
+    }
+
+    /// Returns a slice suitable for use when archiving cold storages
+    pub fn data_for_archive(&self) -> &[u8] {
+        self.mmap.as_ref()
+    }
+}
+
+/// return an approximation of the cost to store an account.
+/// Some fields like owner are shared across multiple accounts.
+fn stored_size(data_len: usize) -> usize {
+    data_len + std::mem::size_of::<Pubkey>()
+}
+
+/// The writer that creates a cold accounts file.
+#[derive(Debug)]
+pub struct ColdStorageWriter {
+    storage: TieredWritableFile,
+    format: TieredStorageFormat,
+}
+
+impl ColdStorageWriter {
+    /// Create a new ColdStorageWriter with the specified path.
+    pub fn new(file_path: impl AsRef<Path>) -> TieredStorageResult<Self> {
+        Self::new_with_format(file_path, &COLD_FORMAT)
+    }
+
+    /// Create a new ColdStorageWriter with the specified path and format.
+    ///
+    /// The format must be a cold format, while its account block format,
+    /// compression level, and dictionary size can be chosen freely.
+    pub fn new_with_format(
+        file_path: impl AsRef<Path>,
+        format: &TieredStorageFormat,
+    ) -> TieredStorageResult<Self> {
+        if format.account_meta_format != COLD_FORMAT.account_meta_format
+            || format.meta_entry_size != COLD_FORMAT.meta_entry_size
+            || format.owners_block_format != COLD_FORMAT.owners_block_format
+            || format.index_block_format != COLD_FORMAT.index_block_format
+        {
+            return Err(TieredStorageError::UnknownFormat(
+                file_path.as_ref().to_path_buf(),
+            ));
+        }
+
+        Ok(Self {
+            storage: TieredWritableFile::new(file_path)?,
+            format: format.clone(),
+        })
+    }
+
+    /// Writes an account entry with the specified information into the
+    /// specified account block writer and returns the offset of the entry
+    /// inside the account block.
+    fn write_account_entry(
+        block_writer: &mut ByteBlockWriter,
+        lamports: u64,
+        owner_offset: OwnerOffset,
+        account_data: &[u8],
+        executable: bool,
+        rent_epoch: Option<Epoch>,
+    ) -> TieredStorageResult<usize> {
+        let optional_fields = AccountMetaOptionalFields { rent_epoch };
+
+        let mut flags = AccountMetaFlags::new_from(&optional_fields);
+        flags.set_executable(executable);
+
+        let meta = ColdAccountMeta::new()
+            .with_lamports(lamports)
+            .with_owner_offset(owner_offset)
+            .with_account_data_size(account_data.len() as u64)
+            .with_flags(&flags);
+
+        let intra_block_offset = block_writer.raw_len();
+        block_writer.write_pod(&meta)?;
+        block_writer.write(account_data)?;
+        block_writer.write_optional_fields(&optional_fields)?;
+
+        Ok(intra_block_offset)
+    }
+
+    /// Encodes and persists the specified account block, and returns the
+    /// number of bytes written including the padding that keeps the next
+    /// block aligned.
+    fn write_account_block(&mut self, block_writer: ByteBlockWriter) -> TieredStorageResult<usize> {
+        let encoded_block = block_writer.finish()?;
+        let mut bytes_written = 0;
+        bytes_written += self.storage.write_pod(&(encoded_block.len() as u64))?;
+        bytes_written += self.storage.write_bytes(&encoded_block)?;
+        bytes_written += self
+            .storage
+            .write_bytes(&PADDING_BUFFER[..padding_bytes(encoded_block.len())])?;
+
+        Ok(bytes_written)
+    }
+
+    /// Trains a compression dictionary from a sample of the account entries
+    /// in `accounts`, skipping the first `skip` number of accounts.
+    ///
+    /// Returns an empty dictionary if the format of this writer does not use
+    /// a dictionary, or if there are not enough samples to train one.
+    fn train_dictionary<'a>(
+        &self,
+        accounts: &impl StorableAccounts<'a>,
+        skip: usize,
+    ) -> TieredStorageResult<Vec<u8>> {
+        if self.format.account_block_format != AccountBlockFormat::Zstd
+            || self.format.max_dictionary_size == 0
+        {
+            return Ok(vec![]);
+        }
+
+        let len = accounts.len();
+        let step = (len.saturating_sub(skip) / MAX_DICTIONARY_SAMPLES).max(1);
+        // the owners are only used to build the samples
+        let mut owners_table = OwnersTable::default();
+        let mut samples = vec![];
+        for i in (skip..len).step_by(step) {
+            let sample = accounts.account_default_if_zero_lamport(i, |account| {
+                let rent_epoch = (account.rent_epoch() != RENT_EXEMPT_RENT_EPOCH)
+                    .then_some(account.rent_epoch());
+                let mut sample_writer = ByteBlockWriter::new(AccountBlockFormat::AlignedRaw);
+                Self::write_account_entry(
+                    &mut sample_writer,
+                    account.lamports(),
+                    owners_table.insert(account.owner()),
+                    account.data(),
+                    account.executable(),
+                    rent_epoch,
+                )?;
+                let mut sample = sample_writer.finish()?;
+                // blob accounts are truncated so that they do not dominate
+                // the samples.
+                sample.truncate(COLD_ACCOUNT_BLOCK_SIZE);
+                TieredStorageResult::Ok(sample)
+            })?;
+            samples.push(sample);
+        }
+
+        // Training fails when the samples are too few or too small, in which
+        // case the account blocks are compressed without a dictionary.
+        Ok(zstd::dict::from_samples(&samples, self.format.max_dictionary_size).unwrap_or_default())
+    }
+
+    /// Persists `accounts` into the underlying cold accounts file associated
+    /// with this ColdStorageWriter.  The first `skip` number of accounts are
+    /// *not* persisted.
+    pub fn write_accounts<'a>(
+        &mut self,
+        accounts: &impl StorableAccounts<'a>,
+        skip: usize,
+    ) -> TieredStorageResult<StoredAccountsInfo> {
+        let mut footer = new_cold_footer(&self.format);
+        let dictionary = self.train_dictionary(accounts, skip)?;
+        // the dictionary is prepared once and shared by all the account blocks
+        let encoder_dictionary = (!dictionary.is_empty())
+            .then(|| EncoderDictionary::copy(&dictionary, footer.compression_level));
+        let new_block_writer = || {
+            ByteBlockWriter::new_with_compression(
+                footer.account_block_format,
+                footer.compression_level,
+                encoder_dictionary.as_ref(),
+            )
+        };
+        let account_block_size = footer.account_block_size as usize;
+        let mut index = vec![];
+        let mut owners_table = OwnersTable::default();
+        let mut cursor = 0;
+        let mut address_range = AccountAddressRange::default();
+
+        let len = accounts.len();
+        let total_input_accounts = len.saturating_sub(skip);
+        let mut offsets = Vec::with_capacity(total_input_accounts);
+
+        // writing accounts blocks
+        let mut block_writer = new_block_writer()?;
+        for i in skip..len {
+            accounts.account_default_if_zero_lamport::<TieredStorageResult<()>>(i, |account| {
+                address_range.update(account.pubkey());
+
+                // Obtain necessary fields from the account, or default fields
+                // for a zero-lamport account in the None case.
+                let (lamports, owner, data, executable, rent_epoch) = {
+                    (
+                        account.lamports(),
+                        account.owner(),
+                        account.data(),
+                        account.executable(),
+                        // only persist rent_epoch for those rent-paying accounts
+                        (account.rent_epoch() != RENT_EXEMPT_RENT_EPOCH)
+                            .then_some(account.rent_epoch()),
+                    )
+                };
+
+                // Close the current account block first if the new account
+                // entry does not fit.  An account entry that is larger than
+                // the account block size makes a blob account that has its
+                // own account block.
+                let entry_size = std::mem::size_of::<ColdAccountMeta>()
+                    + data.len()
+                    + AccountMetaOptionalFields { rent_epoch }.size();
+                if block_writer.raw_len() > 0
+                    && block_writer.raw_len() + entry_size > account_block_size
+                {
+                    let full_block = std::mem::replace(&mut block_writer, new_block_writer()?);
+                    cursor += self.write_account_block(full_block)?;
+                }
+
+                let owner_offset = owners_table.insert(owner);
+                let intra_block_offset = Self::write_account_entry(
+                    &mut block_writer,
+                    lamports,
+                    owner_offset,
+                    data,
+                    executable,
+                    rent_epoch,
+                )?;
+
+                // Same as hot storage, IndexOffset is returned as the offset
+                // of each account.
+                offsets.push(index.len());
+                index.push(AccountIndexWriterEntry {
+                    address: *account.pubkey(),
+                    offset: ColdAccountOffset::new(cursor, intra_block_offset)?,
+                });
+                Ok(())
+            })?;
+        }
+        if block_writer.raw_len() > 0 {
+            cursor += self.write_account_block(block_writer)?;
+        }
+        footer.account_entry_count = total_input_accounts as u32;
+
+        // writing dictionary block
+        if !dictionary.is_empty() {
+            footer.dictionary_block_offset = cursor as u64;
+            footer.dictionary_size = dictionary.len() as u32;
+            cursor += self.storage.write_bytes(&dictionary)?;
+            cursor += self
+                .storage
+                .write_bytes(&PADDING_BUFFER[..padding_bytes(dictionary.len())])?;
+        }
+
+        // writing index block
+        // expect the offset of each block aligned.
+        assert!(cursor % COLD_BLOCK_ALIGNMENT == 0);
+        footer.index_block_offset = cursor as u64;
+        cursor += footer
+            .index_block_format
+            .write_index_block(&mut self.storage, &index)?;
+
+        // writing owners block
+        // as each index entry of a cold accounts file is a multiple of 8
+        // bytes, the owners block is always aligned.
+        assert!(cursor % COLD_BLOCK_ALIGNMENT == 0);
+        footer.owners_block_offset = cursor as u64;
+        footer.owner_count = owners_table.len() as u32;
+        cursor += footer
+            .owners_block_format
+            .write_owners_block(&mut self.storage, &owners_table)?;
+
+        // writing footer
+        footer.min_account_address = address_range.min;
+        footer.max_account_address = address_range.max;
+        cursor += footer.write_footer_block(&mut self.storage)?;
+
+        Ok(StoredAccountsInfo {
+            offsets,
+            size: cursor,
+        })
+    }
+
+    /// Flushes any buffered data to the file
+    pub fn flush(&mut self) -> TieredStorageResult<()> {
+        self.storage
+            .0
+            .flush()
+            .map_err(TieredStorageError::FlushColdWriter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::tiered_storage::{
+            file::TieredStorageMagicNumber,
+            test_utils::{create_test_account, verify_test_account},
+        },
+        assert_matches::assert_matches,
+        memoffset::offset_of,
+        rand::seq::SliceRandom,
+        solana_sdk::{account::ReadableAccount, slot_history::Slot},
+        tempfile::TempDir,
+    };
+
+    /// Writes the specified accounts into a cold storage file and returns
+    /// the reader of the file together with the written accounts.
+    fn write_test_accounts(
+        temp_dir: &TempDir,
+        account_data_sizes: &[u64],
+    ) -> (
+        ColdStorageReader,
+        StoredAccountsInfo,
+        Vec<(Pubkey, AccountSharedData)>,
+    ) {
+        write_test_accounts_with_format(temp_dir, account_data_sizes, &COLD_FORMAT)
+    }
+
+    /// Same as write_test_accounts() but writes the accounts in the
+    /// specified cold format.
+    fn write_test_accounts_with_format(
+        temp_dir: &TempDir,
+        account_data_sizes: &[u64],
+        format: &TieredStorageFormat,
+    ) -> (
+        ColdStorageReader,
+        StoredAccountsInfo,
+        Vec<(Pubkey, AccountSharedData)>,
+    ) {
+        let accounts: Vec<_> = account_data_sizes
+            .iter()
+            .map(|size| create_test_account(*size))
+            .map(|(stored_meta, account)| (stored_meta.pubkey, account))
+            .collect();
+        let account_refs: Vec<_> = accounts
+            .iter()
+            .map(|(address, account)| (address, account))
+            .collect();
+
+        // Slot information is not used here
+        let storable_accounts = (Slot::MAX, &account_refs[..]);
+
+        let path = temp_dir.path().join("test_cold_storage");
+        let stored_accounts_info = {
+            let mut writer = ColdStorageWriter::new_with_format(&path, format).unwrap();
+            let stored_accounts_info = writer.write_accounts(&storable_accounts, 0).unwrap();
+            writer.flush().unwrap();
+            stored_accounts_info
+        };
+
+        let file = TieredReadableFile::new(&path).unwrap();
+        let cold_storage = ColdStorageReader::new(file).unwrap();
+
+        let expected_accounts = (0..accounts.len())
+            .map(|i| {
+                storable_accounts.account_default_if_zero_lamport(i, |account| {
+                    (*account.pubkey(), account.to_account_shared_data())
+                })
+            })
+            .collect();
+
+        (cold_storage, stored_accounts_info, expected_accounts)
+    }
+
+    #[test]
+    fn test_cold_account_meta_layout() {
+        assert_eq!(offset_of!(ColdAccountMeta, lamports), 0x00);
+        assert_eq!(offset_of!(ColdAccountMeta, account_data_size), 0x08);
+        assert_eq!(offset_of!(ColdAccountMeta, owner_offset), 0x10);
+        assert_eq!(offset_of!(ColdAccountMeta, flags), 0x14);
+        assert_eq!(std::mem::size_of::<ColdAccountMeta>(), 24);
+    }
+
+    #[test]
+    fn test_cold_account_meta() {
+        const TEST_LAMPORTS: u64 = 2314232137;
+        const TEST_DATA_SIZE: u64 = 4321;
+        const TEST_OWNER_OFFSET: OwnerOffset = OwnerOffset(0xffff_1234);
+        const TEST_RENT_EPOCH: Epoch = 7;
+
+        let optional_fields = AccountMetaOptionalFields {
+            rent_epoch: Some(TEST_RENT_EPOCH),
+        };
+
+        let flags = AccountMetaFlags::new_from(&optional_fields);
+        let meta = ColdAccountMeta::new()
+            .with_lamports(TEST_LAMPORTS)
+            .with_account_data_size(TEST_DATA_SIZE)
+            .with_account_data_padding(5)
+            .with_owner_offset(TEST_OWNER_OFFSET)
+            .with_flags(&flags);
+
+        assert_eq!(meta.lamports(), TEST_LAMPORTS);
+        assert_eq!(meta.account_data_size, TEST_DATA_SIZE);
+        assert_eq!(meta.account_data_padding(), 0);
+        assert_eq!(meta.owner_offset(), TEST_OWNER_OFFSET);
+        assert_eq!(*meta.flags(), flags);
+    }
+
+    #[test]
+    fn test_cold_account_entry() {
+        let account_data = [11u8; 83];
+
+        const TEST_LAMPORTS: u64 = 2314232137;
+        const TEST_OWNER_OFFSET: OwnerOffset = OwnerOffset(0x1fef_1234);
+        const TEST_RENT_EPOCH: Epoch = 7;
+
+        let mut writer = ByteBlockWriter::new(AccountBlockFormat::Lz4);
+        // write a dummy byte first so that the entry is not aligned
+        writer.write(&[0u8]).unwrap();
+        let intra_block_offset = ColdStorageWriter::write_account_entry(
+            &mut writer,
+            TEST_LAMPORTS,
+            TEST_OWNER_OFFSET,
+            &account_data,
+            true,
+            Some(TEST_RENT_EPOCH),
+        )
+        .unwrap();
+        assert_eq!(intra_block_offset, 1);
+        let buffer = writer.finish().unwrap();
+        let decoded = ByteBlockReader::decode(AccountBlockFormat::Lz4, &buffer).unwrap();
+
+        let (meta, account_block) = parse_account_entry(&decoded, intra_block_offset).unwrap();
+        assert_eq!(meta.lamports(), TEST_LAMPORTS);
+        assert_eq!(meta.owner_offset(), TEST_OWNER_OFFSET);
+        assert!(meta.flags().executable());
+        assert!(meta.flags().has_rent_epoch());
+        assert_eq!(meta.account_data_size(account_block), account_data.len());
+        assert_eq!(meta.account_data(account_block), account_data);
+        assert_eq!(meta.rent_epoch(account_block), Some(TEST_RENT_EPOCH));
+        assert_eq!(meta.final_rent_epoch(account_block), TEST_RENT_EPOCH);
+
+        // an offset that does not point to a complete entry
+        assert_matches!(
+            parse_account_entry(&decoded, decoded.len() - 1),
+            Err(TieredStorageError::InvalidAccountEntry(_))
+        );
+    }
+
+    #[test]
+    fn test_cold_account_offset() {
+        assert_matches!(ColdAccountOffset::new(0, 0), Ok(_));
+        assert_matches!(
+            ColdAccountOffset::new(MAX_COLD_BLOCK_OFFSET, u32::MAX as usize),
+            Ok(_)
+        );
+        assert_matches!(
+            ColdAccountOffset::new(MAX_COLD_BLOCK_OFFSET + COLD_BLOCK_ALIGNMENT, 0),
+            Err(TieredStorageError::OffsetOutOfBounds(_, _))
+        );
+        assert_matches!(
+            ColdAccountOffset::new(0, u32::MAX as usize + 1),
+            Err(TieredStorageError::OffsetOutOfBounds(_, _))
+        );
+        assert_matches!(
+            ColdAccountOffset::new(COLD_BLOCK_ALIGNMENT - 1, 0),
+            Err(TieredStorageError::OffsetAlignmentError(_, _))
+        );
+
+        let offset = ColdAccountOffset::new(COLD_BLOCK_ALIGNMENT * 3, 77).unwrap();
+        assert_eq!(offset.block_offset(), COLD_BLOCK_ALIGNMENT * 3);
+        assert_eq!(offset.intra_block_offset(), 77);
+    }
+
+    #[test]
+    fn test_cold_storage_writer_twice_on_same_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir
+            .path()
+            .join("test_cold_storage_writer_twice_on_same_path");
+
+        // Expect the first returns Ok
+        assert_matches!(ColdStorageWriter::new(&path), Ok(_));
+        // Expect the second call on the same path returns Err, as the
+        // ColdStorageWriter only writes once.
+        assert_matches!(ColdStorageWriter::new(&path), Err(_));
+    }
+
+    #[test]
+    fn test_write_account_and_index_blocks() {
+        let account_data_sizes = &[
+            1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 1000, 2000, 3000, 4000, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0,
+            10_000, 3,
+        ];
+        let temp_dir = TempDir::new().unwrap();
+        let (cold_storage, stored_accounts_info, expected_accounts) =
+            write_test_accounts(&temp_dir, account_data_sizes);
+
+        let footer = cold_storage.footer();
+        assert_eq!(footer.account_meta_format, AccountMetaFormat::Cold);
+        assert_eq!(footer.account_block_format, AccountBlockFormat::Lz4);
+        assert_eq!(footer.account_block_size, COLD_ACCOUNT_BLOCK_SIZE as u64);
+        assert_eq!(cold_storage.num_accounts(), account_data_sizes.len());
+
+        for (i, (address, account)) in expected_accounts.iter().enumerate() {
+            cold_storage
+                .get_stored_account_meta_callback(IndexOffset(i as u32), |stored_account_meta| {
+                    verify_test_account(&stored_account_meta, account, address);
+                })
+                .unwrap()
+                .unwrap();
+
+            let account_shared_data = cold_storage
+                .get_account_shared_data(IndexOffset(i as u32))
+                .unwrap()
+                .unwrap();
+            assert_eq!(&account_shared_data, account);
+        }
+        // Make sure it returns None on num_accounts to allow termination on
+        // while loop in actual accounts-db read case.
+        assert_matches!(
+            cold_storage.get_stored_account_meta_callback(
+                IndexOffset(expected_accounts.len() as u32),
+                |_| {
+                    panic!("unexpected");
+                }
+            ),
+            Ok(None)
+        );
+        assert_matches!(
+            cold_storage.get_account_shared_data(IndexOffset(expected_accounts.len() as u32)),
+            Ok(None)
+        );
+
+        assert_eq!(stored_accounts_info.offsets.len(), expected_accounts.len());
+
+        // verify everything
+        let mut i = 0;
+        cold_storage
+            .scan_accounts(|stored_meta| {
+                let (address, account) = &expected_accounts[i];
+                verify_test_account(&stored_meta, account, address);
+                i += 1;
+            })
+            .unwrap();
+        assert_eq!(i, expected_accounts.len());
+
+        let mut i = 0;
+        cold_storage
+            .scan_index(|index_info| {
+                let (address, account) = &expected_accounts[i];
+                assert_eq!(index_info.index_info.pubkey, *address);
+                assert_eq!(index_info.index_info.lamports, account.lamports());
+                assert_eq!(index_info.index_info.data_len, account.data().len() as u64);
+                assert_eq!(index_info.index_info.executable, account.executable());
+                assert_eq!(index_info.index_info.rent_epoch, account.rent_epoch());
+                i += 1;
+            })
+            .unwrap();
+        assert_eq!(i, expected_accounts.len());
+
+        let sorted_offsets: Vec<_> = (0..expected_accounts.len() as u32)
+            .map(AccountInfo::reduced_offset_to_offset)
+            .collect();
+        let sizes = cold_storage.get_account_sizes(&sorted_offsets).unwrap();
+        for (size, (_, account)) in sizes.iter().zip(expected_accounts.iter()) {
+            assert_eq!(*size, stored_size(account.data().len()));
+        }
+
+        let expected_size = footer.owners_block_offset as usize
+            + std::mem::size_of::<Pubkey>() * footer.owner_count as usize
+            + std::mem::size_of::<TieredStorageFooter>()
+            + std::mem::size_of::<TieredStorageMagicNumber>();
+
+        assert!(!cold_storage.is_empty());
+        assert_eq!(expected_size, cold_storage.len());
+    }
+
+    #[test]
+    fn test_compressed_account_blocks() {
+        // accounts with repeated data bytes are highly compressible
+        let account_data_sizes: Vec<u64> = std::iter::repeat(1000).take(20).collect();
+        let temp_dir = TempDir::new().unwrap();
+        let (cold_storage, _, expected_accounts) =
+            write_test_accounts(&temp_dir, &account_data_sizes);
+
+        let total_data_size: usize = expected_accounts
+            .iter()
+            .map(|(_, account)| account.data().len())
+            .sum();
+        assert!((cold_storage.footer().index_block_offset as usize) < total_data_size);
+
+        // accounts that share the same account block have the same block
+        // offset but different intra-block offsets.
+        let offsets: Vec<_> = (0..expected_accounts.len() as u32)
+            .map(|i| cold_storage.get_account_offset(IndexOffset(i)).unwrap())
+            .collect();
+        assert!(offsets
+            .windows(2)
+            .any(|pair| pair[0].block_offset() == pair[1].block_offset()));
+        offsets
+            .iter()
+            .for_each(|offset| assert_eq!(offset.block_offset() % COLD_BLOCK_ALIGNMENT, 0));
+    }
+
+    #[test]
+    fn test_zstd_account_blocks_with_dictionary() {
+        let account_data_sizes: Vec<u64> = (0..2000).map(|i| 100 + i % 200).collect();
+        let temp_dir = TempDir::new().unwrap();
+        let (cold_storage, _, expected_accounts) =
+            write_test_accounts_with_format(&temp_dir, &account_data_sizes, &COLD_ZSTD_FORMAT);
+
+        let footer = cold_storage.footer();
+        assert_eq!(footer.account_block_format, AccountBlockFormat::Zstd);
+        assert_eq!(footer.compression_level, COLD_ZSTD_COMPRESSION_LEVEL);
+        assert!(footer.dictionary_size > 0);
+        assert!(footer.dictionary_size as usize <= COLD_ZSTD_MAX_DICTIONARY_SIZE);
+        assert_eq!(
+            footer.dictionary_block_offset % COLD_BLOCK_ALIGNMENT as u64,
+            0
+        );
+        assert!(footer.dictionary_block_offset < footer.index_block_offset);
+
+        for (i, (address, account)) in expected_accounts.iter().enumerate() {
+            cold_storage
+                .get_stored_account_meta_callback(IndexOffset(i as u32), |stored_account_meta| {
+                    verify_test_account(&stored_account_meta, account, address);
+                })
+                .unwrap()
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_zstd_account_blocks_without_dictionary() {
+        let format = TieredStorageFormat {
+            max_dictionary_size: 0,
+            ..COLD_ZSTD_FORMAT
+        };
+        let account_data_sizes: Vec<u64> = (1..30).collect();
+        let temp_dir = TempDir::new().unwrap();
+        let (cold_storage, _, expected_accounts) =
+            write_test_accounts_with_format(&temp_dir, &account_data_sizes, &format);
+
+        let footer = cold_storage.footer();
+        assert_eq!(footer.account_block_format, AccountBlockFormat::Zstd);
+        assert_eq!(footer.dictionary_size, 0);
+
+        for (i, (_, account)) in expected_accounts.iter().enumerate() {
+            let account_shared_data = cold_storage
+                .get_account_shared_data(IndexOffset(i as u32))
+                .unwrap()
+                .unwrap();
+            assert_eq!(&account_shared_data, account);
+        }
+    }
+
+    #[test]
+    fn test_cold_storage_writer_rejects_non_cold_format() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir
+            .path()
+            .join("test_cold_storage_writer_rejects_non_cold_format");
+        let format = TieredStorageFormat {
+            account_meta_format: AccountMetaFormat::Hot,
+            ..COLD_FORMAT
+        };
+        assert_matches!(
+            ColdStorageWriter::new_with_format(&path, &format),
+            Err(TieredStorageError::UnknownFormat(_))
+        );
+    }
+
+    #[test]
+    fn test_account_matches_owners() {
+        let account_data_sizes: Vec<u64> = (1..30).collect();
+        let temp_dir = TempDir::new().unwrap();
+        let (cold_storage, _, expected_accounts) =
+            write_test_accounts(&temp_dir, &account_data_sizes);
+        let mut rng = rand::thread_rng();
+
+        let mut owner_candidates: Vec<_> = expected_accounts
+            .iter()
+            .map(|(_, account)| *account.owner())
+            .collect();
+        owner_candidates.dedup();
+        owner_candidates.shuffle(&mut rng);
+
+        for (i, (_, account)) in expected_accounts.iter().enumerate() {
+            let index = cold_storage
+                .account_matches_owners(IndexOffset(i as u32), &owner_candidates)
+                .unwrap();
+            assert_eq!(owner_candidates[index], *account.owner());
+        }
+
+        let unmatched_candidates: Vec<_> = std::iter::repeat_with(Pubkey::new_unique)
+            .take(20)
+            .collect();
+        for i in 0..expected_accounts.len() {
+            assert_eq!(
+                cold_storage.account_matches_owners(IndexOffset(i as u32), &unmatched_candidates),
+                Err(MatchAccountOwnerError::NoMatch)
+            );
+        }
+    }
+
+    #[test]
+    fn test_zero_lamport_account_matches_no_owner() {
+        let temp_dir = TempDir::new().unwrap();
+        // a test account created with seed 0 has zero lamports
+        let (cold_storage, _, expected_accounts) = write_test_accounts(&temp_dir, &[0]);
+        let owners = [*expected_accounts[0].1.owner()];
+        assert_eq!(
+            cold_storage.account_matches_owners(IndexOffset(0), &owners),
+            Err(MatchAccountOwnerError::NoMatch)
+        );
+    }
+}
@@ -15,12 +15,21 @@ use {
     thiserror::Error,
 };
 
-pub const FOOTER_FORMAT_VERSION: u64 = 1;
+pub const FOOTER_FORMAT_VERSION: u64 = 2;
+
+/// The format version of the footers written before the compression-related
+/// fields were added.  Such footers are still readable.
+const FOOTER_FORMAT_VERSION_V1: u64 = 1;
 
 /// The size of the footer struct + the magic number at the end.
 pub const FOOTER_SIZE: usize =
     mem::size_of::<TieredStorageFooter>() + mem::size_of::<TieredStorageMagicNumber>();
-static_assertions::const_assert_eq!(mem::size_of::<TieredStorageFooter>(), 160);
+static_assertions::const_assert_eq!(mem::size_of::<TieredStorageFooter>(), 176);
+
+/// The size of the version 1 footer struct + the magic number at the end.
+const FOOTER_V1_SIZE: usize =
+    mem::size_of::<TieredStorageFooterV1>() + mem::size_of::<TieredStorageMagicNumber>();
+static_assertions::const_assert_eq!(mem::size_of::<TieredStorageFooterV1>(), 160);
 
 /// The size of the ending part of the footer.  This size should remain unchanged
 /// even when the footer's format changes.
@@ -41,8 +50,7 @@ pub const FOOTER_TAIL_SIZE: usize = 24;
 pub enum AccountMetaFormat {
     #[default]
     Hot = 0,
-    // Temporarily comment out to avoid unimplemented!() block
-    // Cold = 1,
+    Cold = 1,
 }
 
 #[repr(u16)]
@@ -61,6 +69,7 @@ pub enum AccountBlockFormat {
     #[default]
     AlignedRaw = 0,
     Lz4 = 1,
+    Zstd = 2,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -101,6 +110,17 @@ pub struct TieredStorageFooter {
     /// The offset pointing to the first byte of the owners block.
     pub owners_block_offset: u64,
 
+    // Compression-related
+    /// The offset pointing to the first byte of the compression dictionary
+    /// block.  Only valid when `dictionary_size` is non-zero.
+    pub dictionary_block_offset: u64,
+    /// The size of the compression dictionary in bytes, or 0 if the account
+    /// blocks are compressed without a dictionary.
+    pub dictionary_size: u32,
+    /// The compression level used to encode the account blocks, where 0
+    /// means the default level of the account block format.
+    pub compression_level: i32,
+
     // account range
     /// The smallest account address in this file.
     pub min_account_address: Pubkey,
@@ -121,6 +141,57 @@ pub struct TieredStorageFooter {
     // pub magic_number: u64,
 }
 
+/// The footer of the tiered accounts files of FOOTER_FORMAT_VERSION_V1.
+///
+/// It is the same as TieredStorageFooter without the compression-related
+/// fields, and is only used to read the footers of older files.
+#[derive(Debug, Default, Clone, Copy)]
+#[repr(C)]
+struct TieredStorageFooterV1 {
+    account_meta_format: AccountMetaFormat,
+    owners_block_format: OwnersBlockFormat,
+    index_block_format: IndexBlockFormat,
+    account_block_format: AccountBlockFormat,
+    account_entry_count: u32,
+    account_meta_entry_size: u32,
+    account_block_size: u64,
+    owner_count: u32,
+    owner_entry_size: u32,
+    index_block_offset: u64,
+    owners_block_offset: u64,
+    min_account_address: Pubkey,
+    max_account_address: Pubkey,
+    hash: Hash,
+    format_version: u64,
+    footer_size: u64,
+}
+
+impl From<TieredStorageFooterV1> for TieredStorageFooter {
+    fn from(footer: TieredStorageFooterV1) -> Self {
+        Self {
+            account_meta_format: footer.account_meta_format,
+            owners_block_format: footer.owners_block_format,
+            index_block_format: footer.index_block_format,
+            account_block_format: footer.account_block_format,
+            account_entry_count: footer.account_entry_count,
+            account_meta_entry_size: footer.account_meta_entry_size,
+            account_block_size: footer.account_block_size,
+            owner_count: footer.owner_count,
+            owner_entry_size: footer.owner_entry_size,
+            index_block_offset: footer.index_block_offset,
+            owners_block_offset: footer.owners_block_offset,
+            dictionary_block_offset: 0,
+            dictionary_size: 0,
+            compression_level: 0,
+            min_account_address: footer.min_account_address,
+            max_account_address: footer.max_account_address,
+            hash: footer.hash,
+            format_version: footer.format_version,
+            footer_size: footer.footer_size,
+        }
+    }
+}
+
 // It is undefined behavior to read/write uninitialized bytes.
 // The `Pod` marker trait indicates there are no uninitialized bytes.
 // In order to safely guarantee a type is POD, it cannot have any padding.
@@ -137,6 +208,9 @@ const _: () = assert!(
          + std::mem::size_of::<u32>() // owner_entry_size
          + std::mem::size_of::<u64>() // index_block_offset
          + std::mem::size_of::<u64>() // owners_block_offset
+         + std::mem::size_of::<u64>() // dictionary_block_offset
+         + std::mem::size_of::<u32>() // dictionary_size
+         + std::mem::size_of::<i32>() // compression_level
          + std::mem::size_of::<Pubkey>() // min_account_address
          + std::mem::size_of::<Pubkey>() // max_account_address
          + std::mem::size_of::<Hash>() // hash
@@ -159,6 +233,9 @@ impl Default for TieredStorageFooter {
             owner_entry_size: 0,
             index_block_offset: 0,
             owners_block_offset: 0,
+            dictionary_block_offset: 0,
+            dictionary_size: 0,
+            compression_level: 0,
             hash: Hash::new_unique(),
             min_account_address: Pubkey::default(),
             max_account_address: Pubkey::default(),
@@ -189,16 +266,14 @@ impl TieredStorageFooter {
 
         let mut footer_version: u64 = 0;
         file.read_pod(&mut footer_version)?;
-        if footer_version != FOOTER_FORMAT_VERSION {
-            return Err(TieredStorageError::InvalidFooterVersion(footer_version));
-        }
+        let expected_footer_size = Self::footer_size_of_version(footer_version)?;
 
         let mut footer_size: u64 = 0;
         file.read_pod(&mut footer_size)?;
-        if footer_size != FOOTER_SIZE as u64 {
+        if footer_size != expected_footer_size {
             return Err(TieredStorageError::InvalidFooterSize(
                 footer_size,
-                FOOTER_SIZE as u64,
+                expected_footer_size,
             ));
         }
 
@@ -211,29 +286,34 @@ impl TieredStorageFooter {
             ));
         }
 
-        let mut footer = Self::default();
         file.seek_from_end(-(footer_size as i64))?;
         // SAFETY: We sanitize the footer to ensure all the bytes are
         // actually safe to interpret as a TieredStorageFooter.
-        unsafe { file.read_type(&mut footer)? };
+        let footer = if footer_version == FOOTER_FORMAT_VERSION_V1 {
+            let mut footer = TieredStorageFooterV1::default();
+            unsafe { file.read_type(&mut footer)? };
+            Self::from(footer)
+        } else {
+            let mut footer = Self::default();
+            unsafe { file.read_type(&mut footer)? };
+            footer
+        };
         Self::sanitize(&footer)?;
 
         Ok(footer)
     }
 
-    pub fn new_from_mmap(mmap: &Mmap) -> TieredStorageResult<&TieredStorageFooter> {
+    pub fn new_from_mmap(mmap: &Mmap) -> TieredStorageResult<TieredStorageFooter> {
         let offset = mmap.len().saturating_sub(FOOTER_TAIL_SIZE);
 
-        let (footer_version, offset) = get_pod::<u64>(mmap, offset)?;
-        if *footer_version != FOOTER_FORMAT_VERSION {
-            return Err(TieredStorageError::InvalidFooterVersion(*footer_version));
-        }
+        let (&footer_version, offset) = get_pod::<u64>(mmap, offset)?;
+        let expected_footer_size = Self::footer_size_of_version(footer_version)?;
 
         let (&footer_size, offset) = get_pod::<u64>(mmap, offset)?;
-        if footer_size != FOOTER_SIZE as u64 {
+        if footer_size != expected_footer_size {
             return Err(TieredStorageError::InvalidFooterSize(
                 footer_size,
-                FOOTER_SIZE as u64,
+                expected_footer_size,
             ));
         }
 
@@ -248,12 +328,30 @@ impl TieredStorageFooter {
         let footer_offset = mmap.len().saturating_sub(footer_size as usize);
         // SAFETY: We sanitize the footer to ensure all the bytes are
         // actually safe to interpret as a TieredStorageFooter.
-        let (footer, _offset) = unsafe { get_type::<TieredStorageFooter>(mmap, footer_offset)? };
-        Self::sanitize(footer)?;
+        let footer = if footer_version == FOOTER_FORMAT_VERSION_V1 {
+            let (footer, _offset) =
+                unsafe { get_type::<TieredStorageFooterV1>(mmap, footer_offset)? };
+            Self::from(*footer)
+        } else {
+            let (footer, _offset) =
+                unsafe { get_type::<TieredStorageFooter>(mmap, footer_offset)? };
+            *footer
+        };
+        Self::sanitize(&footer)?;
 
         Ok(footer)
     }
 
+    /// Returns the expected footer size of the specified footer format
+    /// version, or an error if the version is not supported.
+    fn footer_size_of_version(footer_version: u64) -> TieredStorageResult<u64> {
+        match footer_version {
+            FOOTER_FORMAT_VERSION => Ok(FOOTER_SIZE as u64),
+            FOOTER_FORMAT_VERSION_V1 => Ok(FOOTER_V1_SIZE as u64),
+            _ => Err(TieredStorageError::InvalidFooterVersion(footer_version)),
+        }
+    }
+
     /// Sanitizes the footer
     ///
     /// Since the various formats only have specific valid values, they must be sanitized
@@ -333,6 +431,9 @@ mod tests {
             owner_entry_size: 32,
             index_block_offset: 1069600,
             owners_block_offset: 1081200,
+            dictionary_block_offset: 0,
+            dictionary_size: 0,
+            compression_level: 0,
             hash: Hash::new_unique(),
             min_account_address: Pubkey::default(),
             max_account_address: Pubkey::new_unique(),
@@ -354,6 +455,68 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_footer_v1() {
+        let path = get_append_vec_path("test_file_footer_v1");
+        let footer_v1 = TieredStorageFooterV1 {
+            account_meta_format: AccountMetaFormat::Hot,
+            owners_block_format: OwnersBlockFormat::AddressesOnly,
+            index_block_format: IndexBlockFormat::AddressesThenOffsets,
+            account_block_format: AccountBlockFormat::AlignedRaw,
+            account_entry_count: 300,
+            account_meta_entry_size: 24,
+            account_block_size: 4096,
+            owner_count: 250,
+            owner_entry_size: 32,
+            index_block_offset: 1069600,
+            owners_block_offset: 1081200,
+            hash: Hash::new_unique(),
+            min_account_address: Pubkey::default(),
+            max_account_address: Pubkey::new_unique(),
+            format_version: FOOTER_FORMAT_VERSION_V1,
+            footer_size: FOOTER_V1_SIZE as u64,
+        };
+
+        // Persist the footer in the version 1 format.
+        {
+            let mut file = TieredWritableFile::new(&path.path).unwrap();
+            // SAFETY: The footer does not contain any uninitialized bytes.
+            unsafe { file.write_type(&footer_v1).unwrap() };
+            file.write_pod(&TieredStorageMagicNumber::default())
+                .unwrap();
+        }
+
+        // A version 1 footer is read without any compression-related fields.
+        let expected_footer = TieredStorageFooter {
+            account_meta_format: AccountMetaFormat::Hot,
+            owners_block_format: OwnersBlockFormat::AddressesOnly,
+            index_block_format: IndexBlockFormat::AddressesThenOffsets,
+            account_block_format: AccountBlockFormat::AlignedRaw,
+            account_entry_count: 300,
+            account_meta_entry_size: 24,
+            account_block_size: 4096,
+            owner_count: 250,
+            owner_entry_size: 32,
+            index_block_offset: 1069600,
+            owners_block_offset: 1081200,
+            dictionary_block_offset: 0,
+            dictionary_size: 0,
+            compression_level: 0,
+            hash: footer_v1.hash,
+            min_account_address: Pubkey::default(),
+            max_account_address: footer_v1.max_account_address,
+            format_version: FOOTER_FORMAT_VERSION_V1,
+            footer_size: FOOTER_V1_SIZE as u64,
+        };
+        let footer = TieredStorageFooter::new_from_path(&path.path).unwrap();
+        assert_eq!(expected_footer, footer);
+
+        let file = std::fs::File::open(&path.path).unwrap();
+        let mmap = unsafe { Mmap::map(&file).unwrap() };
+        let footer = TieredStorageFooter::new_from_mmap(&mmap).unwrap();
+        assert_eq!(expected_footer, footer);
+    }
+
     #[test]
     fn test_footer_layout() {
         assert_eq!(offset_of!(TieredStorageFooter, account_meta_format), 0x00);
@@ -370,11 +533,17 @@ mod tests {
         assert_eq!(offset_of!(TieredStorageFooter, owner_entry_size), 0x1C);
         assert_eq!(offset_of!(TieredStorageFooter, index_block_offset), 0x20);
         assert_eq!(offset_of!(TieredStorageFooter, owners_block_offset), 0x28);
-        assert_eq!(offset_of!(TieredStorageFooter, min_account_address), 0x30);
-        assert_eq!(offset_of!(TieredStorageFooter, max_account_address), 0x50);
-        assert_eq!(offset_of!(TieredStorageFooter, hash), 0x70);
-        assert_eq!(offset_of!(TieredStorageFooter, format_version), 0x90);
-        assert_eq!(offset_of!(TieredStorageFooter, footer_size), 0x98);
+        assert_eq!(
+            offset_of!(TieredStorageFooter, dictionary_block_offset),
+            0x30
+        );
+        assert_eq!(offset_of!(TieredStorageFooter, dictionary_size), 0x38);
+        assert_eq!(offset_of!(TieredStorageFooter, compression_level), 0x3C);
+        assert_eq!(offset_of!(TieredStorageFooter, min_account_address), 0x40);
+        assert_eq!(offset_of!(TieredStorageFooter, max_account_address), 0x60);
+        assert_eq!(offset_of!(TieredStorageFooter, hash), 0x80);
+        assert_eq!(offset_of!(TieredStorageFooter, format_version), 0xA0);
+        assert_eq!(offset_of!(TieredStorageFooter, footer_size), 0xA8);
     }
 
     #[test]
@@ -37,6 +37,8 @@ pub const HOT_FORMAT: TieredStorageFormat = TieredStorageFormat {
     owners_block_format: OwnersBlockFormat::AddressesOnly,
     index_block_format: IndexBlockFormat::AddressesThenOffsets,
     account_block_format: AccountBlockFormat::AlignedRaw,
+    compression_level: 0,
+    max_dictionary_size: 0,
 };
 
 /// A helper function that creates a new default footer for hot
@@ -370,7 +372,7 @@ impl HotStorageReader {
         // TieredStorage instance requires accessing its Footer.
         // This can help improve cache locality and reduce the overhead
         // of indirection associated with memory-mapped accesses.
-        let footer = *TieredStorageFooter::new_from_mmap(&mmap)?;
+        let footer = TieredStorageFooter::new_from_mmap(&mmap)?;
 
         Ok(Self { mmap, footer })
     }
@@ -823,7 +825,10 @@ mod tests {
         crate::tiered_storage::{
             byte_block::ByteBlockWriter,
             file::{TieredStorageMagicNumber, TieredWritableFile},
-            footer::{AccountBlockFormat, AccountMetaFormat, TieredStorageFooter, FOOTER_SIZE},
+            footer::{
+                AccountBlockFormat, AccountMetaFormat, TieredStorageFooter, FOOTER_FORMAT_VERSION,
+                FOOTER_SIZE,
+            },
             hot::{HotAccountMeta, HotStorageReader},
             index::{AccountIndexWriterEntry, IndexBlockFormat, IndexOffset},
             meta::{AccountMetaFlags, AccountMetaOptionalFields, TieredAccountMeta},
@@ -1113,11 +1118,14 @@ mod tests {
             owner_entry_size: 32,
             index_block_offset: 1069600,
             owners_block_offset: 1081200,
+            dictionary_block_offset: 0,
+            dictionary_size: 0,
+            compression_level: 0,
             hash: Hash::new_unique(),
             min_account_address: Pubkey::default(),
             max_account_address: Pubkey::new_unique(),
             footer_size: FOOTER_SIZE as u64,
-            format_version: 1,
+            format_version: FOOTER_FORMAT_VERSION,
         };
 
         {
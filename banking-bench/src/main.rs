@@ -1,4 +1,7 @@
 #![allow(clippy::arithmetic_side_effects)]
+#[cfg(feature = "trace-replay")]
+mod trace_replay;
+
 use {
     clap::{crate_description, crate_name, Arg, ArgEnum, Command},
     crossbeam_channel::{unbounded, Receiver},
@@ -232,7 +235,7 @@ impl PacketsPerIteration {
 fn main() {
     solana_logger::setup();
 
-    let matches = Command::new(crate_name!())
+    let command = Command::new(crate_name!())
         .about(crate_description!())
         .version(solana_version::version!())
         .arg(
@@ -312,8 +315,10 @@ fn main() {
                 .takes_value(true)
                 .requires("simulate_mint")
                 .help("In simulating mint, number of mint transactions out of 100."),
-        )
-        .get_matches();
+        );
+    #[cfg(feature = "trace-replay")]
+    let command = trace_replay::add_args(command);
+    let matches = command.get_matches();
 
     let block_production_method = matches
         .value_of_t::<BlockProductionMethod>("block_production_method")
@@ -321,6 +326,17 @@ fn main() {
     let num_banking_threads = matches
         .value_of_t::<u32>("num_banking_threads")
         .unwrap_or_else(|_| BankingStage::num_threads());
+
+    #[cfg(feature = "trace-replay")]
+    if matches.is_present("from_trace") {
+        trace_replay::replay(trace_replay::TraceReplayConfig::from_matches(
+            &matches,
+            block_production_method,
+            num_banking_threads,
+        ));
+        return;
+    }
+
     //   a multiple of packet chunk duplicates to avoid races
     let num_chunks = matches.value_of_t::<usize>("num_chunks").unwrap_or(16);
     let packets_per_batch = matches
@@ -475,6 +491,7 @@ fn main() {
         bank_forks.clone(),
         &Arc::new(PrioritizationFeeCache::new(0u64)),
         false,
+        None,
     );
 
     // This is so that the signal_receiver does not go out of scope after the closure.
@@ -0,0 +1,682 @@
+//! Replays a recorded banking trace through a real `BankingStage`, on top of a bank loaded from a
+//! snapshot archive, without running a validator or replaying the ledger.
+//!
+//! Slot boundaries are taken from the trace rather than from the wall clock: every replayed slot
+//! is fed exactly the packet batches which were traced while the original slot was being
+//! produced, each at the same offset from the start of the slot at which it was originally sent,
+//! and every replayed slot lasts exactly `ns_per_slot`. Batches traced during the holding phase
+//! prior to the first slot are delivered as soon as it starts. So repeated runs over the same
+//! trace feed the banking stage the same load at the same times, regardless of how long the
+//! previous slots took to set up.
+//!
+//! For each slot, the replay reports the compute unit fill of the block, how many of the
+//! received transactions landed (and how many of those were carried over from a previous slot),
+//! and, if the ledger contains the original block, how the ordering of the replayed block
+//! compares with it.
+//!
+//! Built only with the `trace-replay` feature, e.g.
+//! `cargo run --release -p solana-banking-bench --features trace-replay -- --from-trace <DIR>`.
+
+use {
+    clap::{Arg, ArgMatches, Command},
+    crossbeam_channel::unbounded,
+    log::*,
+    solana_client::connection_cache::ConnectionCache,
+    solana_core::{
+        banking_simulation::{BankingSimulator, BankingTraceEvents},
+        banking_stage::BankingStage,
+        banking_trace::{BankingTracer, ChannelLabel},
+        validator::BlockProductionMethod,
+    },
+    solana_gossip::cluster_info::{ClusterInfo, Node},
+    solana_ledger::{
+        blockstore::Blockstore,
+        blockstore_options::{AccessType, BlockstoreOptions},
+        get_tmp_ledger_path_auto_delete,
+        leader_schedule_cache::LeaderScheduleCache,
+    },
+    solana_perf::packet::Packet,
+    solana_poh::poh_recorder::create_test_recorder,
+    solana_runtime::{
+        bank::Bank, bank_forks::BankForks, prioritization_fee_cache::PrioritizationFeeCache,
+        runtime_config::RuntimeConfig, snapshot_bank_utils::bank_from_latest_snapshot_archives,
+    },
+    solana_sdk::{
+        borsh1::try_from_slice_unchecked,
+        clock::{
+            Slot, DEFAULT_MS_PER_SLOT, HOLD_TRANSACTIONS_SLOT_OFFSET, NUM_CONSECUTIVE_LEADER_SLOTS,
+        },
+        compute_budget::{self, ComputeBudgetInstruction},
+        genesis_config::GenesisConfig,
+        poh_config::PohConfig,
+        signature::{Keypair, Signature, Signer},
+        transaction::VersionedTransaction,
+    },
+    solana_streamer::socket::SocketAddrSpace,
+    solana_tpu_client::tpu_client::DEFAULT_TPU_CONNECTION_POOL_SIZE,
+    std::{
+        cmp::Reverse,
+        collections::HashMap,
+        fs,
+        path::{Path, PathBuf},
+        sync::{atomic::Ordering, Arc},
+        thread::sleep,
+        time::{Duration, Instant, SystemTime},
+    },
+};
+
+// Covers the holding phase prior to the leader slots, as in `BankingSimulator`
+const WARMUP_DURATION: Duration =
+    Duration::from_millis(HOLD_TRANSACTIONS_SLOT_OFFSET * DEFAULT_MS_PER_SLOT);
+
+pub struct TraceReplayConfig {
+    /// Directory containing the banking trace event files
+    pub trace_path: PathBuf,
+    /// Ledger directory containing the genesis config, the snapshot archive to start from, and
+    /// optionally the original blocks
+    pub ledger_path: PathBuf,
+    /// First slot to replay, defaults to the slot after the snapshot
+    pub first_slot: Option<Slot>,
+    /// Maximum number of traced slots to replay
+    pub num_slots: usize,
+    pub block_production_method: BlockProductionMethod,
+    pub num_banking_threads: u32,
+}
+
+impl TraceReplayConfig {
+    /// Builds the config from the arguments added by `add_args()`. `--from-trace` must be present.
+    pub fn from_matches(
+        matches: &ArgMatches,
+        block_production_method: BlockProductionMethod,
+        num_banking_threads: u32,
+    ) -> Self {
+        Self {
+            trace_path: PathBuf::from(matches.value_of("from_trace").unwrap()),
+            ledger_path: PathBuf::from(matches.value_of("ledger").unwrap()),
+            first_slot: matches.value_of_t("first_slot").ok(),
+            num_slots: matches
+                .value_of_t::<usize>("num_slots")
+                .unwrap_or(NUM_CONSECUTIVE_LEADER_SLOTS as usize),
+            block_production_method,
+            num_banking_threads,
+        }
+    }
+}
+
+/// Adds the trace replay arguments to the banking-bench command line
+pub fn add_args(command: Command) -> Command {
+    command
+        .arg(
+            Arg::new("from_trace")
+                .long("from-trace")
+                .takes_value(true)
+                .value_name("DIR")
+                .requires("ledger")
+                .help(
+                    "Replay the banking trace in DIR through the banking stage, instead of \
+                     generating transactions",
+                ),
+        )
+        .arg(
+            Arg::new("ledger")
+                .long("ledger")
+                .takes_value(true)
+                .value_name("DIR")
+                .help(
+                    "With --from-trace, ledger containing the genesis config and the snapshot \
+                     to start from. The replayed blocks are compared with the original blocks \
+                     if the ledger contains them",
+                ),
+        )
+        .arg(
+            Arg::new("first_slot")
+                .long("first-slot")
+                .takes_value(true)
+                .value_name("SLOT")
+                .requires("from_trace")
+                .help("First slot to replay, defaults to the slot after the snapshot"),
+        )
+        .arg(
+            Arg::new("num_slots")
+                .long("num-slots")
+                .takes_value(true)
+                .value_name("NUM")
+                .requires("from_trace")
+                .help("Maximum number of traced slots to replay"),
+        )
+}
+
+/// A transaction received by the replayed banking stage
+struct ReceivedTransaction {
+    slot: Slot,
+    landed: bool,
+}
+
+struct SlotReport {
+    slot: Slot,
+    received_count: usize,
+    landed_count: usize,
+    /// Landed transactions which were received during an earlier slot
+    carried_over_count: usize,
+    /// Received transactions which have not landed yet, either buffered or dropped
+    unlanded_count: usize,
+    block_cost: u64,
+    block_cost_limit: u64,
+    /// Fraction of transaction pairs in the replayed block which are ordered by increasing
+    /// priority
+    priority_inversions: f64,
+    original: Option<OriginalBlockComparison>,
+}
+
+struct OriginalBlockComparison {
+    transaction_count: usize,
+    /// Transactions which are in both the original and the replayed block
+    common_count: usize,
+    /// Fraction of pairs of common transactions which are in the same order in both blocks
+    order_agreement: f64,
+    priority_inversions: f64,
+}
+
+pub fn replay(config: TraceReplayConfig) {
+    let TraceReplayConfig {
+        trace_path,
+        ledger_path,
+        first_slot,
+        num_slots,
+        block_production_method,
+        num_banking_threads,
+    } = config;
+
+    let events = BankingTraceEvents::load(&trace_event_file_paths(&trace_path))
+        .expect("Expected to be able to load banking trace events");
+    let genesis_config =
+        GenesisConfig::load(&ledger_path).expect("Expected to be able to load genesis config");
+    // The original blocks are optional, the ledger may only contain the genesis and snapshots
+    let original_blockstore = Blockstore::open_with_options(
+        &ledger_path,
+        BlockstoreOptions {
+            access_type: AccessType::Secondary,
+            ..BlockstoreOptions::default()
+        },
+    )
+    .map_err(|err| warn!("Not comparing with the original blocks: {err}"))
+    .ok();
+
+    let tmp_path = get_tmp_ledger_path_auto_delete!();
+    let bank = load_snapshot_bank(&ledger_path, tmp_path.path(), &genesis_config);
+    bank.set_hash_overrides(events.hash_overrides().clone());
+    let snapshot_slot = bank.slot();
+    let slots: Vec<Slot> = events
+        .freeze_time_by_slot()
+        .range(first_slot.unwrap_or_default().max(snapshot_slot + 1)..)
+        .map(|(slot, _)| *slot)
+        .take(num_slots)
+        .collect();
+    assert!(
+        !slots.is_empty(),
+        "no traced slots to replay after the snapshot slot {snapshot_slot}"
+    );
+    info!("Replaying slots {slots:?} on top of snapshot slot {snapshot_slot}");
+
+    let bank_forks = BankForks::new_rw_arc(bank);
+    let mut bank = bank_forks.read().unwrap().working_bank();
+    let leader_schedule_cache = Arc::new(LeaderScheduleCache::new_from_bank(&bank));
+    let blockstore = Arc::new(
+        Blockstore::open(tmp_path.path()).expect("Expected to be able to open database ledger"),
+    );
+    // Slots are ended by the replay, so only tick often enough to keep PoH going
+    let poh_config = PohConfig {
+        target_tick_duration: Duration::from_millis(DEFAULT_MS_PER_SLOT),
+        ..PohConfig::default()
+    };
+    let (exit, poh_recorder, poh_service, entry_receiver) = create_test_recorder(
+        bank.clone(),
+        blockstore,
+        Some(poh_config),
+        Some(leader_schedule_cache.clone()),
+    );
+    // The snapshot bank is frozen, hold the packets until the first replayed slot
+    poh_recorder
+        .write()
+        .unwrap()
+        .reset(bank.clone(), Some((slots[0], slots[0])));
+
+    let (banking_tracer, _) = BankingTracer::new(None).unwrap();
+    let (non_vote_sender, non_vote_receiver) = banking_tracer.create_channel_non_vote();
+    let (tpu_vote_sender, tpu_vote_receiver) = banking_tracer.create_channel_tpu_vote();
+    let (gossip_vote_sender, gossip_vote_receiver) = banking_tracer.create_channel_gossip_vote();
+    let cluster_info = {
+        let keypair = Arc::new(Keypair::new());
+        let node = Node::new_localhost_with_pubkey(&keypair.pubkey());
+        Arc::new(ClusterInfo::new(
+            node.info,
+            keypair,
+            SocketAddrSpace::Unspecified,
+        ))
+    };
+    let (replay_vote_sender, _replay_vote_receiver) = unbounded();
+    let banking_stage = BankingStage::new_num_threads(
+        block_production_method,
+        &cluster_info,
+        &poh_recorder,
+        non_vote_receiver,
+        tpu_vote_receiver,
+        gossip_vote_receiver,
+        num_banking_threads,
+        None,
+        replay_vote_sender,
+        None,
+        Arc::new(ConnectionCache::with_udp(
+            "connection_cache_banking_bench_udp",
+            DEFAULT_TPU_CONNECTION_POOL_SIZE,
+        )),
+        bank_forks.clone(),
+        &Arc::new(PrioritizationFeeCache::new(0u64)),
+        false,
+        None,
+    );
+
+    let freeze_time_by_slot = events.freeze_time_by_slot();
+    let packet_batches_by_time = events.packet_batches_by_time();
+    let mut received: HashMap<Signature, ReceivedTransaction> = HashMap::new();
+    let mut reports = Vec::with_capacity(slots.len());
+    for (index, &slot) in slots.iter().enumerate() {
+        let slot_start_event_time = freeze_time_by_slot
+            .range(..slot)
+            .next_back()
+            .map(|(_, time)| *time)
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        let window_start = if index == 0 {
+            slot_start_event_time
+                .checked_sub(WARMUP_DURATION)
+                .unwrap_or(SystemTime::UNIX_EPOCH)
+        } else {
+            slot_start_event_time
+        };
+        let window_end = freeze_time_by_slot[&slot];
+
+        let leader = leader_schedule_cache
+            .slot_leader_at(slot, Some(&bank))
+            .unwrap_or_else(|| *bank.collector_id());
+        let new_bank = Bank::new_from_parent(bank.clone(), &leader, slot);
+        bank = bank_forks
+            .write()
+            .unwrap()
+            .insert(new_bank)
+            .clone_without_scheduler();
+        poh_recorder
+            .write()
+            .unwrap()
+            .set_bank_for_test(bank.clone());
+        let slot_start = Instant::now();
+
+        let mut received_count = 0;
+        for (event_time, (label, batch)) in packet_batches_by_time.range(window_start..window_end) {
+            let offset = event_time
+                .duration_since(slot_start_event_time)
+                .unwrap_or_default();
+            if let Some(remaining) = offset.checked_sub(slot_start.elapsed()) {
+                sleep(remaining);
+            }
+            for transaction in deserialize_transactions(batch.0.iter().flatten()) {
+                received_count += 1;
+                received
+                    .entry(transaction.signatures[0])
+                    .or_insert(ReceivedTransaction {
+                        slot,
+                        landed: false,
+                    });
+            }
+            let sender = match label {
+                ChannelLabel::NonVote => &non_vote_sender,
+                ChannelLabel::TpuVote => &tpu_vote_sender,
+                ChannelLabel::GossipVote => &gossip_vote_sender,
+                ChannelLabel::Dummy => unreachable!(),
+            };
+            sender.send(batch.clone()).unwrap();
+        }
+
+        let slot_duration = Duration::from_nanos(bank.ns_per_slot as u64);
+        if let Some(remaining) = slot_duration.checked_sub(slot_start.elapsed()) {
+            sleep(remaining);
+        }
+        let next_slot = slots.get(index + 1).copied().unwrap_or(slot + 1);
+        poh_recorder
+            .write()
+            .unwrap()
+            .reset(bank.clone(), Some((next_slot, next_slot)));
+        bank.freeze();
+
+        let replayed_transactions: Vec<VersionedTransaction> = entry_receiver
+            .try_iter()
+            .filter(|(entry_bank, _)| entry_bank.slot() == slot)
+            .flat_map(|(_, (entry, _tick_height))| entry.transactions)
+            .collect();
+        let mut carried_over_count = 0;
+        for transaction in &replayed_transactions {
+            if let Some(received_transaction) = received.get_mut(&transaction.signatures[0]) {
+                received_transaction.landed = true;
+                if received_transaction.slot != slot {
+                    carried_over_count += 1;
+                }
+            }
+        }
+        let (block_cost, block_cost_limit) = {
+            let cost_tracker = bank.read_cost_tracker().unwrap();
+            (cost_tracker.block_cost(), cost_tracker.block_cost_limit())
+        };
+        let original = original_blockstore
+            .as_ref()
+            .and_then(|blockstore| blockstore.get_slot_entries(slot, 0).ok())
+            .filter(|entries| !entries.is_empty())
+            .map(|entries| {
+                let original_transactions: Vec<_> = entries
+                    .into_iter()
+                    .flat_map(|entry| entry.transactions)
+                    .collect();
+                compare_with_original(&original_transactions, &replayed_transactions)
+            });
+        let report = SlotReport {
+            slot,
+            received_count,
+            landed_count: replayed_transactions.len(),
+            carried_over_count,
+            unlanded_count: received.values().filter(|tx| !tx.landed).count(),
+            block_cost,
+            block_cost_limit,
+            priority_inversions: priority_inversions(&replayed_transactions),
+            original,
+        };
+        print_slot_report(&report);
+        reports.push(report);
+    }
+
+    let total_received = received.len();
+    let total_landed = received.values().filter(|tx| tx.landed).count();
+    let total_carried_over: usize = reports.iter().map(|report| report.carried_over_count).sum();
+    eprintln!(
+        "[replayed slots: {}, received: {}, landed: {}, carried over: {}, unlanded: {}]",
+        reports.len(),
+        total_received,
+        total_landed,
+        total_carried_over,
+        total_received - total_landed,
+    );
+
+    drop(non_vote_sender);
+    drop(tpu_vote_sender);
+    drop(gossip_vote_sender);
+    exit.store(true, Ordering::Relaxed);
+    banking_stage.join().unwrap();
+    poh_service.join().unwrap();
+}
+
+fn trace_event_file_paths(trace_path: &Path) -> Vec<PathBuf> {
+    (0..)
+        .map(|index| trace_path.join(BankingSimulator::event_file_name(index)))
+        .take_while(|path| path.exists())
+        .collect()
+}
+
+fn load_snapshot_bank(ledger_path: &Path, tmp_path: &Path, genesis_config: &GenesisConfig) -> Bank {
+    let bank_snapshots_dir = tmp_path.join("bank_snapshots");
+    let accounts_dir = tmp_path.join("accounts");
+    fs::create_dir_all(&bank_snapshots_dir).unwrap();
+    fs::create_dir_all(&accounts_dir).unwrap();
+
+    let (bank, full_snapshot_archive_info, incremental_snapshot_archive_info) =
+        bank_from_latest_snapshot_archives(
+            bank_snapshots_dir,
+            ledger_path,
+            ledger_path,
+            &[accounts_dir],
+            genesis_config,
+            &RuntimeConfig::default(),
+            None,
+            None,
+            None,
+            false,
+            false,
+            false,
+            false,
+            None,
+            None,
+            Arc::default(),
+        )
+        .expect("Expected to be able to load a bank from the snapshot archives");
+    info!(
+        "Loaded bank at slot {} from {:?} and {:?}",
+        bank.slot(),
+        full_snapshot_archive_info.path(),
+        incremental_snapshot_archive_info.map(|info| info.path().clone()),
+    );
+    bank
+}
+
+fn deserialize_transactions<'a>(
+    packets: impl Iterator<Item = &'a Packet> + 'a,
+) -> impl Iterator<Item = VersionedTransaction> + 'a {
+    packets
+        .filter(|packet| !packet.meta().discard())
+        .filter_map(|packet| packet.deserialize_slice(..).ok())
+}
+
+fn compute_unit_price(transaction: &VersionedTransaction) -> u64 {
+    let message = &transaction.message;
+    message
+        .instructions()
+        .iter()
+        .find_map(|instruction| {
+            let program_id = message
+                .static_account_keys()
+                .get(usize::from(instruction.program_id_index))?;
+            if program_id != &compute_budget::id() {
+                return None;
+            }
+            match try_from_slice_unchecked(&instruction.data) {
+                Ok(ComputeBudgetInstruction::SetComputeUnitPrice(price)) => Some(price),
+                _ => None,
+            }
+        })
+        .unwrap_or_default()
+}
+
+/// Fraction of transaction pairs in which the lower priority transaction comes first.
+fn priority_inversions(transactions: &[VersionedTransaction]) -> f64 {
+    let priorities: Vec<_> = transactions
+        .iter()
+        .map(|transaction| Reverse(compute_unit_price(transaction)))
+        .collect();
+    inversion_fraction(&priorities)
+}
+
+fn compare_with_original(
+    original_transactions: &[VersionedTransaction],
+    replayed_transactions: &[VersionedTransaction],
+) -> OriginalBlockComparison {
+    let original_positions: HashMap<&Signature, usize> = original_transactions
+        .iter()
+        .enumerate()
+        .map(|(position, transaction)| (&transaction.signatures[0], position))
+        .collect();
+    // Original positions of the common transactions, in the replayed order
+    let common_positions: Vec<usize> = replayed_transactions
+        .iter()
+        .filter_map(|transaction| original_positions.get(&transaction.signatures[0]))
+        .copied()
+        .collect();
+
+    OriginalBlockComparison {
+        transaction_count: original_transactions.len(),
+        common_count: common_positions.len(),
+        order_agreement: 1.0 - inversion_fraction(&common_positions),
+        priority_inversions: priority_inversions(original_transactions),
+    }
+}
+
+/// Fraction of pairs of `values` which are out of order.
+fn inversion_fraction<T: Ord + Copy>(values: &[T]) -> f64 {
+    let len = values.len() as u64;
+    if len < 2 {
+        return 0.0;
+    }
+    let mut values = values.to_vec();
+    let inversions = count_inversions(&mut values);
+    inversions as f64 / (len * (len - 1) / 2) as f64
+}
+
+/// Counts the pairs `i < j` with `values[i] > values[j]` by merge sorting `values`.
+fn count_inversions<T: Ord + Copy>(values: &mut [T]) -> u64 {
+    if values.len() < 2 {
+        return 0;
+    }
+    let middle = values.len() / 2;
+    let mut inversions = count_inversions(&mut values[..middle]);
+    inversions += count_inversions(&mut values[middle..]);
+
+    let (left, right) = values.split_at(middle);
+    let mut merged = Vec::with_capacity(values.len());
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() && j < right.len() {
+        if right[j] < left[i] {
+            inversions += (left.len() - i) as u64;
+            merged.push(right[j]);
+            j += 1;
+        } else {
+            merged.push(left[i]);
+            i += 1;
+        }
+    }
+    merged.extend_from_slice(&left[i..]);
+    merged.extend_from_slice(&right[j..]);
+    values.copy_from_slice(&merged);
+    inversions
+}
+
+fn print_slot_report(report: &SlotReport) {
+    eprintln!(
+        "[slot {}, received: {}, landed: {} (carried over: {}), unlanded: {}, cu fill: {:.1}% \
+         ({}/{}), priority inversions: {:.3}]",
+        report.slot,
+        report.received_count,
+        report.landed_count,
+        report.carried_over_count,
+        report.unlanded_count,
+        100.0 * report.block_cost as f64 / report.block_cost_limit as f64,
+        report.block_cost,
+        report.block_cost_limit,
+        report.priority_inversions,
+    );
+    if let Some(original) = &report.original {
+        eprintln!(
+            "[slot {} original, transactions: {}, in common: {}, order agreement: {:.3}, \
+             priority inversions: {:.3}]",
+            report.slot,
+            original.transaction_count,
+            original.common_count,
+            original.order_agreement,
+            original.priority_inversions,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{hash::Hash, transaction::Transaction},
+    };
+
+    fn transaction_with_price(compute_unit_price: u64) -> VersionedTransaction {
+        let payer = Keypair::new();
+        VersionedTransaction::from(Transaction::new_signed_with_payer(
+            &[ComputeBudgetInstruction::set_compute_unit_price(
+                compute_unit_price,
+            )],
+            Some(&payer.pubkey()),
+            &[&payer],
+            Hash::default(),
+        ))
+    }
+
+    #[test]
+    fn test_count_inversions() {
+        for (values, expected_inversions) in [
+            (vec![], 0),
+            (vec![1], 0),
+            (vec![1, 2, 3, 4], 0),
+            (vec![4, 3, 2, 1], 6),
+            (vec![2, 1, 4, 3], 2),
+            (vec![3, 1, 2], 2),
+            (vec![1, 3, 2, 5, 4], 2),
+            (vec![2, 2, 1], 2),
+        ] {
+            let mut sorted = values.clone();
+            sorted.sort();
+            let mut values = values;
+            assert_eq!(count_inversions(&mut values), expected_inversions);
+            // The values are left sorted
+            assert_eq!(values, sorted);
+        }
+    }
+
+    #[test]
+    fn test_inversion_fraction() {
+        assert_eq!(inversion_fraction::<u64>(&[]), 0.0);
+        assert_eq!(inversion_fraction(&[1]), 0.0);
+        assert_eq!(inversion_fraction(&[1, 2, 3, 4]), 0.0);
+        assert_eq!(inversion_fraction(&[4, 3, 2, 1]), 1.0);
+        assert_eq!(inversion_fraction(&[2, 1, 4, 3]), 2.0 / 6.0);
+        // Equal values are not out of order
+        assert_eq!(inversion_fraction(&[1, 1]), 0.0);
+    }
+
+    #[test]
+    fn test_compare_with_original() {
+        let original: Vec<_> = [1, 2, 3, 4]
+            .into_iter()
+            .map(transaction_with_price)
+            .collect();
+
+        // Nothing in common
+        let comparison = compare_with_original(&original, &[transaction_with_price(5)]);
+        assert_eq!(comparison.transaction_count, 4);
+        assert_eq!(comparison.common_count, 0);
+        assert_eq!(comparison.order_agreement, 1.0);
+        // The original block is in increasing priority order
+        assert_eq!(comparison.priority_inversions, 1.0);
+
+        // The common transactions keep their order
+        let replayed = vec![
+            original[0].clone(),
+            transaction_with_price(5),
+            original[2].clone(),
+        ];
+        let comparison = compare_with_original(&original, &replayed);
+        assert_eq!(comparison.common_count, 2);
+        assert_eq!(comparison.order_agreement, 1.0);
+
+        // One of the three pairs of common transactions is swapped
+        let replayed = vec![
+            original[1].clone(),
+            original[0].clone(),
+            original[3].clone(),
+        ];
+        let comparison = compare_with_original(&original, &replayed);
+        assert_eq!(comparison.common_count, 3);
+        assert_eq!(comparison.order_agreement, 1.0 - 1.0 / 3.0);
+
+        // The reverse order
+        let replayed: Vec<_> = original.iter().rev().cloned().collect();
+        let comparison = compare_with_original(&original, &replayed);
+        assert_eq!(comparison.common_count, 4);
+        assert_eq!(comparison.order_agreement, 0.0);
+
+        // An empty original block
+        let comparison = compare_with_original(&[], &replayed);
+        assert_eq!(comparison.transaction_count, 0);
+        assert_eq!(comparison.common_count, 0);
+        assert_eq!(comparison.order_agreement, 1.0);
+        assert_eq!(comparison.priority_inversions, 0.0);
+    }
+}
@@ -304,6 +304,7 @@ fn bench_banking(bencher: &mut Bencher, tx_type: TransactionType) {
         bank_forks,
         &Arc::new(PrioritizationFeeCache::new(0u64)),
         false,
+        None,
     );
 
     let chunk_len = verified.len() / CHUNKS;
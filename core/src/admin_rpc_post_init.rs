@@ -2,6 +2,7 @@ use {
     crate::{
         cluster_slots_service::cluster_slots::ClusterSlots,
         repair::{outstanding_requests::OutstandingRequests, serve_repair::ShredRepairType},
+        time_control::TimeControl,
     },
     solana_gossip::cluster_info::ClusterInfo,
     solana_runtime::bank_forks::BankForks,
@@ -23,4 +24,5 @@ pub struct AdminRpcRequestMetadataPostInit {
     pub repair_socket: Arc<UdpSocket>,
     pub outstanding_repair_requests: Arc<RwLock<OutstandingRequests<ShredRepairType>>>,
     pub cluster_slots: Arc<ClusterSlots>,
+    pub time_control: Option<Arc<TimeControl>>,
 }
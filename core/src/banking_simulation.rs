@@ -131,9 +131,9 @@ const WARMUP_DURATION: Duration =
     Duration::from_millis(HOLD_TRANSACTIONS_SLOT_OFFSET * DEFAULT_MS_PER_SLOT + 5000);
 
 /// BTreeMap is intentional because events could be unordered slightly due to tracing jitter.
-type PacketBatchesByTime = BTreeMap<SystemTime, (ChannelLabel, BankingPacketBatch)>;
+pub type PacketBatchesByTime = BTreeMap<SystemTime, (ChannelLabel, BankingPacketBatch)>;
 
-type FreezeTimeBySlot = BTreeMap<Slot, SystemTime>;
+pub type FreezeTimeBySlot = BTreeMap<Slot, SystemTime>;
 
 type TimedBatchesToSend = Vec<(
     (Duration, (ChannelLabel, BankingPacketBatch)),
@@ -231,6 +231,14 @@ impl BankingTraceEvents {
     pub fn hash_overrides(&self) -> &HashOverrides {
         &self.hash_overrides
     }
+
+    pub fn packet_batches_by_time(&self) -> &PacketBatchesByTime {
+        &self.packet_batches_by_time
+    }
+
+    pub fn freeze_time_by_slot(&self) -> &FreezeTimeBySlot {
+        &self.freeze_time_by_slot
+    }
 }
 
 struct DummyClusterInfo {
@@ -815,6 +823,7 @@ impl BankingSimulator {
             bank_forks.clone(),
             prioritization_fee_cache,
             false,
+            None,
         );
 
         let (&_slot, &raw_base_event_time) = freeze_time_by_slot
@@ -20,7 +20,8 @@ use {
             consume_worker::ConsumeWorker,
             packet_deserializer::PacketDeserializer,
             transaction_scheduler::{
-                prio_graph_scheduler::PrioGraphScheduler,
+                greedy_scheduler::GreedyScheduler, prio_graph_scheduler::PrioGraphScheduler,
+                receive_and_buffer::ReceiveAndBuffer, scheduler::Scheduler,
                 scheduler_controller::SchedulerController, scheduler_error::SchedulerError,
             },
         },
@@ -34,7 +35,10 @@ use {
     solana_gossip::{cluster_info::ClusterInfo, contact_info::ContactInfo},
     solana_ledger::blockstore_processor::TransactionStatusSender,
     solana_measure::measure_us,
-    solana_perf::{data_budget::DataBudget, packet::PACKETS_PER_BATCH},
+    solana_perf::{
+        data_budget::DataBudget,
+        packet::{PacketBatch, PACKETS_PER_BATCH},
+    },
     solana_poh::poh_recorder::{PohRecorder, TransactionRecorder},
     solana_runtime::{
         bank_forks::BankForks, prioritization_fee_cache::PrioritizationFeeCache,
@@ -91,6 +95,15 @@ const MIN_TOTAL_THREADS: u32 = NUM_VOTE_PROCESSING_THREADS + MIN_THREADS_BANKING
 
 const SLOT_BOUNDARY_CHECK_PERIOD: Duration = Duration::from_millis(10);
 
+/// Receiver of transaction bundles for the central scheduler. Each
+/// `PacketBatch` is one bundle, whose packets must already be signature
+/// verified.
+pub type BundleReceiver = Receiver<PacketBatch>;
+
+/// Capacity of the channel of bundles sent to banking stage. Senders should
+/// not block when it is full, but reject the bundle instead.
+pub const BUNDLE_CHANNEL_CAPACITY: usize = 1_000;
+
 #[derive(Debug, Default)]
 pub struct BankingStageStats {
     last_report: AtomicInterval,
@@ -364,6 +377,7 @@ impl BankingStage {
         bank_forks: Arc<RwLock<BankForks>>,
         prioritization_fee_cache: &Arc<PrioritizationFeeCache>,
         enable_forwarding: bool,
+        bundle_receiver: Option<BundleReceiver>,
     ) -> Self {
         Self::new_num_threads(
             block_production_method,
@@ -380,6 +394,7 @@ impl BankingStage {
             bank_forks,
             prioritization_fee_cache,
             enable_forwarding,
+            bundle_receiver,
         )
     }
 
@@ -399,9 +414,15 @@ impl BankingStage {
         bank_forks: Arc<RwLock<BankForks>>,
         prioritization_fee_cache: &Arc<PrioritizationFeeCache>,
         enable_forwarding: bool,
+        bundle_receiver: Option<BundleReceiver>,
     ) -> Self {
         match block_production_method {
-            BlockProductionMethod::CentralScheduler => Self::new_central_scheduler(
+            BlockProductionMethod::CentralScheduler
+            | BlockProductionMethod::CentralSchedulerGreedy => Self::new_central_scheduler(
+                matches!(
+                    block_production_method,
+                    BlockProductionMethod::CentralSchedulerGreedy
+                ),
                 cluster_info,
                 poh_recorder,
                 non_vote_receiver,
@@ -415,6 +436,7 @@ impl BankingStage {
                 bank_forks,
                 prioritization_fee_cache,
                 enable_forwarding,
+                bundle_receiver,
             ),
         }
     }
@@ -507,6 +529,7 @@ impl BankingStage {
 
     #[allow(clippy::too_many_arguments)]
     pub fn new_central_scheduler(
+        use_greedy_scheduler: bool,
         cluster_info: &impl LikeClusterInfo,
         poh_recorder: &Arc<RwLock<PohRecorder>>,
         non_vote_receiver: BankingPacketReceiver,
@@ -520,6 +543,7 @@ impl BankingStage {
         bank_forks: Arc<RwLock<BankForks>>,
         prioritization_fee_cache: &Arc<PrioritizationFeeCache>,
         enable_forwarding: bool,
+        bundle_receiver: Option<BundleReceiver>,
     ) -> Self {
         assert!(num_threads >= MIN_TOTAL_THREADS);
         // Single thread to generate entries from many banks.
@@ -621,30 +645,53 @@ impl BankingStage {
                 bank_forks.clone(),
                 forwarder.is_some(),
             );
-            let scheduler = PrioGraphScheduler::new(work_senders, finished_work_receiver);
-            let scheduler_controller = SchedulerController::new(
-                decision_maker.clone(),
-                receive_and_buffer,
-                bank_forks,
-                scheduler,
-                worker_metrics,
-                forwarder,
-            );
-            Builder::new()
-                .name("solBnkTxSched".to_string())
-                .spawn(move || match scheduler_controller.run() {
-                    Ok(_) => {}
-                    Err(SchedulerError::DisconnectedRecvChannel(_)) => {}
-                    Err(SchedulerError::DisconnectedSendChannel(_)) => {
-                        warn!("Unexpected worker disconnect from scheduler")
-                    }
-                })
-                .unwrap()
+            if use_greedy_scheduler {
+                Self::spawn_scheduler_thread(SchedulerController::new(
+                    decision_maker.clone(),
+                    receive_and_buffer,
+                    bank_forks,
+                    GreedyScheduler::new(work_senders, finished_work_receiver),
+                    bundle_receiver,
+                    worker_metrics,
+                    forwarder,
+                ))
+            } else {
+                Self::spawn_scheduler_thread(SchedulerController::new(
+                    decision_maker.clone(),
+                    receive_and_buffer,
+                    bank_forks,
+                    PrioGraphScheduler::new(work_senders, finished_work_receiver),
+                    bundle_receiver,
+                    worker_metrics,
+                    forwarder,
+                ))
+            }
         });
 
         Self { bank_thread_hdls }
     }
 
+    fn spawn_scheduler_thread<C, R, S>(
+        scheduler_controller: SchedulerController<C, R, S>,
+    ) -> JoinHandle<()>
+    where
+        C: LikeClusterInfo,
+        R: ReceiveAndBuffer + Send + 'static,
+        R::Container: Send,
+        S: Scheduler<R::Transaction> + Send + 'static,
+    {
+        Builder::new()
+            .name("solBnkTxSched".to_string())
+            .spawn(move || match scheduler_controller.run() {
+                Ok(_) => {}
+                Err(SchedulerError::DisconnectedRecvChannel(_)) => {}
+                Err(SchedulerError::DisconnectedSendChannel(_)) => {
+                    warn!("Unexpected worker disconnect from scheduler")
+                }
+            })
+            .unwrap()
+    }
+
     fn spawn_thread_local_multi_iterator_thread<T: LikeClusterInfo>(
         id: u32,
         packet_receiver: BankingPacketReceiver,
@@ -904,6 +951,7 @@ mod tests {
                 bank_forks,
                 &Arc::new(PrioritizationFeeCache::new(0u64)),
                 false,
+                None,
             );
             drop(non_vote_sender);
             drop(tpu_vote_sender);
@@ -960,6 +1008,7 @@ mod tests {
                 bank_forks,
                 &Arc::new(PrioritizationFeeCache::new(0u64)),
                 false,
+                None,
             );
             trace!("sending bank");
             drop(non_vote_sender);
@@ -1040,6 +1089,7 @@ mod tests {
                 bank_forks.clone(), // keep a local-copy of bank-forks so worker threads do not lose weak access to bank-forks
                 &Arc::new(PrioritizationFeeCache::new(0u64)),
                 false,
+                None,
             );
 
             // fund another account so we can send 2 good transactions in a single batch.
@@ -1126,6 +1176,11 @@ mod tests {
         test_banking_stage_entries_only(BlockProductionMethod::CentralScheduler);
     }
 
+    #[test]
+    fn test_banking_stage_entries_only_central_scheduler_greedy() {
+        test_banking_stage_entries_only(BlockProductionMethod::CentralSchedulerGreedy);
+    }
+
     #[test]
     fn test_banking_stage_entryfication() {
         solana_logger::setup();
@@ -1397,6 +1452,7 @@ mod tests {
                 bank_forks,
                 &Arc::new(PrioritizationFeeCache::new(0u64)),
                 false,
+                None,
             );
 
             let keypairs = (0..100).map(|_| Keypair::new()).collect_vec();
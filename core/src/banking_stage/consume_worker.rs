@@ -1,12 +1,16 @@
 use {
     super::{
-        consumer::{Consumer, ExecuteAndCommitTransactionsOutput, ProcessTransactionBatchOutput},
+        consumer::{
+            BundleOutcome, Consumer, ExecuteAndCommitTransactionsOutput,
+            ProcessTransactionBatchOutput,
+        },
+        leader_slot_metrics::LeaderSlotMetricsTracker,
         leader_slot_timing_metrics::LeaderExecuteAndCommitTimings,
         scheduler_messages::{ConsumeWork, FinishedConsumeWork},
     },
     crossbeam_channel::{Receiver, RecvError, SendError, Sender},
     solana_measure::measure_us,
-    solana_poh::leader_bank_notifier::LeaderBankNotifier,
+    solana_poh::{leader_bank_notifier::LeaderBankNotifier, poh_recorder::BankStart},
     solana_runtime::bank::Bank,
     solana_runtime_transaction::transaction_with_meta::TransactionWithMeta,
     solana_sdk::clock::Slot,
@@ -16,7 +20,7 @@ use {
             atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
             Arc,
         },
-        time::Duration,
+        time::{Duration, Instant},
     },
     thiserror::Error,
 };
@@ -36,6 +40,7 @@ pub(crate) struct ConsumeWorker<Tx> {
 
     leader_bank_notifier: Arc<LeaderBankNotifier>,
     metrics: Arc<ConsumeWorkerMetrics>,
+    id: u32,
 }
 
 impl<Tx: TransactionWithMeta> ConsumeWorker<Tx> {
@@ -52,6 +57,7 @@ impl<Tx: TransactionWithMeta> ConsumeWorker<Tx> {
             consumed_sender,
             leader_bank_notifier,
             metrics: Arc::new(ConsumeWorkerMetrics::new(id)),
+            id,
         }
     }
 
@@ -60,14 +66,20 @@ impl<Tx: TransactionWithMeta> ConsumeWorker<Tx> {
     }
 
     pub fn run(self) -> Result<(), ConsumeWorkerError<Tx>> {
+        let mut slot_metrics_tracker = LeaderSlotMetricsTracker::new(self.id);
         loop {
             let work = self.consume_receiver.recv()?;
-            self.consume_loop(work)?;
+            self.consume_loop(work, &mut slot_metrics_tracker)?;
         }
     }
 
-    fn consume_loop(&self, work: ConsumeWork<Tx>) -> Result<(), ConsumeWorkerError<Tx>> {
+    fn consume_loop(
+        &self,
+        work: ConsumeWork<Tx>,
+        slot_metrics_tracker: &mut LeaderSlotMetricsTracker,
+    ) -> Result<(), ConsumeWorkerError<Tx>> {
         let (maybe_consume_bank, get_bank_us) = measure_us!(self.get_consume_bank());
+        update_slot_metrics_tracker(slot_metrics_tracker, maybe_consume_bank.as_ref());
         let Some(mut bank) = maybe_consume_bank else {
             self.metrics
                 .timing_metrics
@@ -83,6 +95,7 @@ impl<Tx: TransactionWithMeta> ConsumeWorker<Tx> {
         for work in try_drain_iter(work, &self.consume_receiver) {
             if bank.is_complete() {
                 let (maybe_new_bank, get_bank_us) = measure_us!(self.get_consume_bank());
+                update_slot_metrics_tracker(slot_metrics_tracker, maybe_new_bank.as_ref());
                 if let Some(new_bank) = maybe_new_bank {
                     self.metrics
                         .timing_metrics
@@ -97,7 +110,7 @@ impl<Tx: TransactionWithMeta> ConsumeWorker<Tx> {
                     return self.retry_drain(work);
                 }
             }
-            self.consume(&bank, work)?;
+            self.consume(&bank, work, slot_metrics_tracker)?;
         }
 
         Ok(())
@@ -108,12 +121,22 @@ impl<Tx: TransactionWithMeta> ConsumeWorker<Tx> {
         &self,
         bank: &Arc<Bank>,
         work: ConsumeWork<Tx>,
+        slot_metrics_tracker: &mut LeaderSlotMetricsTracker,
     ) -> Result<(), ConsumeWorkerError<Tx>> {
-        let output = self.consumer.process_and_record_aged_transactions(
-            bank,
-            &work.transactions,
-            &work.max_ages,
-        );
+        let output = if work.is_bundle {
+            let bundle_output =
+                self.consumer
+                    .process_and_record_bundle(bank, &work.transactions, &work.max_ages);
+            self.metrics.update_for_bundle(bundle_output.outcome);
+            slot_metrics_tracker.increment_bundle_outcome_count(bundle_output.outcome);
+            bundle_output.process_transaction_batch_output
+        } else {
+            self.consumer.process_and_record_aged_transactions(
+                bank,
+                &work.transactions,
+                &work.max_ages,
+            )
+        };
 
         self.metrics.update_for_consume(&output);
         self.metrics.has_data.store(true, Ordering::Relaxed);
@@ -163,6 +186,21 @@ impl<Tx: TransactionWithMeta> ConsumeWorker<Tx> {
     }
 }
 
+/// Start, report or keep the leader slot metrics depending on the bank being
+/// consumed into. The metrics of a slot start when the worker first sees its
+/// bank, rather than when the bank was created.
+fn update_slot_metrics_tracker(
+    slot_metrics_tracker: &mut LeaderSlotMetricsTracker,
+    bank: Option<&Arc<Bank>>,
+) {
+    let bank_start = bank.map(|bank| BankStart {
+        working_bank: bank.clone(),
+        bank_creation_time: Arc::new(Instant::now()),
+    });
+    let action = slot_metrics_tracker.check_leader_slot_boundary(bank_start.as_ref(), None);
+    slot_metrics_tracker.apply_action(action);
+}
+
 /// Helper function to create an non-blocking iterator over work in the receiver,
 /// starting with the given work item.
 fn try_drain_iter<T>(work: T, receiver: &Receiver<T>) -> impl Iterator<Item = T> + '_ {
@@ -218,6 +256,15 @@ impl ConsumeWorkerMetrics {
         }
     }
 
+    fn update_for_bundle(&self, outcome: BundleOutcome) {
+        let count = match outcome {
+            BundleOutcome::Committed => &self.count_metrics.bundles_committed_count,
+            BundleOutcome::Retryable => &self.count_metrics.bundles_retryable_count,
+            BundleOutcome::Failed => &self.count_metrics.bundles_failed_count,
+        };
+        count.fetch_add(1, Ordering::Relaxed);
+    }
+
     fn update_for_consume(
         &self,
         ProcessTransactionBatchOutput {
@@ -437,6 +484,9 @@ struct ConsumeWorkerCountMetrics {
     retryable_transaction_count: AtomicUsize,
     retryable_expired_bank_count: AtomicUsize,
     cost_model_throttled_transactions_count: AtomicU64,
+    bundles_committed_count: AtomicU64,
+    bundles_retryable_count: AtomicU64,
+    bundles_failed_count: AtomicU64,
     min_prioritization_fees: AtomicU64,
     max_prioritization_fees: AtomicU64,
 }
@@ -450,6 +500,9 @@ impl Default for ConsumeWorkerCountMetrics {
             retryable_transaction_count: AtomicUsize::default(),
             retryable_expired_bank_count: AtomicUsize::default(),
             cost_model_throttled_transactions_count: AtomicU64::default(),
+            bundles_committed_count: AtomicU64::default(),
+            bundles_retryable_count: AtomicU64::default(),
+            bundles_failed_count: AtomicU64::default(),
             min_prioritization_fees: AtomicU64::new(u64::MAX),
             max_prioritization_fees: AtomicU64::default(),
         }
@@ -494,6 +547,21 @@ impl ConsumeWorkerCountMetrics {
                     .swap(0, Ordering::Relaxed),
                 i64
             ),
+            (
+                "bundles_committed_count",
+                self.bundles_committed_count.swap(0, Ordering::Relaxed),
+                i64
+            ),
+            (
+                "bundles_retryable_count",
+                self.bundles_retryable_count.swap(0, Ordering::Relaxed),
+                i64
+            ),
+            (
+                "bundles_failed_count",
+                self.bundles_failed_count.swap(0, Ordering::Relaxed),
+                i64
+            ),
             (
                 "min_prioritization_fees",
                 self.min_prioritization_fees
@@ -912,6 +980,7 @@ mod tests {
             ids: vec![id],
             transactions,
             max_ages: vec![max_age],
+            is_bundle: false,
         };
         consume_sender.send(work).unwrap();
         let consumed = consumed_receiver.recv().unwrap();
@@ -961,6 +1030,7 @@ mod tests {
             ids: vec![id],
             transactions,
             max_ages: vec![max_age],
+            is_bundle: false,
         };
         consume_sender.send(work).unwrap();
         let consumed = consumed_receiver.recv().unwrap();
@@ -1012,6 +1082,7 @@ mod tests {
                 ids: vec![id1, id2],
                 transactions: txs,
                 max_ages: vec![max_age, max_age],
+                is_bundle: false,
             })
             .unwrap();
 
@@ -1073,6 +1144,7 @@ mod tests {
                 ids: vec![id1],
                 transactions: txs1,
                 max_ages: vec![max_age],
+                is_bundle: false,
             })
             .unwrap();
 
@@ -1082,6 +1154,7 @@ mod tests {
                 ids: vec![id2],
                 transactions: txs2,
                 max_ages: vec![max_age],
+                is_bundle: false,
             })
             .unwrap();
         let consumed = consumed_receiver.recv().unwrap();
@@ -1100,6 +1173,138 @@ mod tests {
         let _ = worker_thread.join().unwrap();
     }
 
+    #[test]
+    fn test_worker_consume_bundle() {
+        let (test_frame, worker) = setup_test_frame();
+        let TestFrame {
+            mint_keypair,
+            genesis_config,
+            bank,
+            poh_recorder,
+            consume_sender,
+            consumed_receiver,
+            ..
+        } = &test_frame;
+        let worker_thread = std::thread::spawn(move || worker.run());
+        poh_recorder
+            .write()
+            .unwrap()
+            .set_bank_for_test(bank.clone());
+
+        let payer1 = Keypair::new();
+        let payer2 = Keypair::new();
+        bank.transfer(1_000, mint_keypair, &payer1.pubkey())
+            .unwrap();
+        bank.transfer(1_000, mint_keypair, &payer2.pubkey())
+            .unwrap();
+        let pubkey1 = Pubkey::new_unique();
+        let pubkey2 = Pubkey::new_unique();
+
+        let max_age = MaxAge {
+            sanitized_epoch: bank.epoch(),
+            alt_invalidation_slot: bank.slot(),
+        };
+
+        // The second transfer fails, so neither transaction is committed.
+        consume_sender
+            .send(ConsumeWork {
+                batch_id: TransactionBatchId::new(0),
+                ids: vec![],
+                transactions: sanitize_transactions(vec![
+                    system_transaction::transfer(&payer1, &pubkey1, 1, genesis_config.hash()),
+                    system_transaction::transfer(&payer2, &pubkey2, 10_000, genesis_config.hash()),
+                ]),
+                max_ages: vec![max_age, max_age],
+                is_bundle: true,
+            })
+            .unwrap();
+        let consumed = consumed_receiver.recv().unwrap();
+        assert_eq!(consumed.retryable_indexes, Vec::<usize>::new());
+        assert_eq!(bank.get_balance(&pubkey1), 0);
+        assert_eq!(bank.get_balance(&payer2.pubkey()), 1_000);
+
+        // Both transfers succeed, so the bundle is committed.
+        consume_sender
+            .send(ConsumeWork {
+                batch_id: TransactionBatchId::new(1),
+                ids: vec![],
+                transactions: sanitize_transactions(vec![
+                    system_transaction::transfer(&payer1, &pubkey1, 1, genesis_config.hash()),
+                    system_transaction::transfer(&payer2, &pubkey2, 1, genesis_config.hash()),
+                ]),
+                max_ages: vec![max_age, max_age],
+                is_bundle: true,
+            })
+            .unwrap();
+        let consumed = consumed_receiver.recv().unwrap();
+        assert_eq!(consumed.retryable_indexes, Vec::<usize>::new());
+        assert_eq!(bank.get_balance(&pubkey1), 1);
+        assert_eq!(bank.get_balance(&pubkey2), 1);
+
+        drop(test_frame);
+        let _ = worker_thread.join().unwrap();
+    }
+
+    #[test]
+    fn test_worker_consume_conflicting_bundle() {
+        let (test_frame, worker) = setup_test_frame();
+        let TestFrame {
+            mint_keypair,
+            genesis_config,
+            bank,
+            _entry_receiver: entry_receiver,
+            poh_recorder,
+            consume_sender,
+            consumed_receiver,
+            ..
+        } = &test_frame;
+        let worker_thread = std::thread::spawn(move || worker.run());
+        poh_recorder
+            .write()
+            .unwrap()
+            .set_bank_for_test(bank.clone());
+
+        // The second transfer spends from the account funded by the first
+        let payer = Keypair::new();
+        let pubkey = Pubkey::new_unique();
+        let max_age = MaxAge {
+            sanitized_epoch: bank.epoch(),
+            alt_invalidation_slot: bank.slot(),
+        };
+        let transactions = sanitize_transactions(vec![
+            system_transaction::transfer(
+                mint_keypair,
+                &payer.pubkey(),
+                1_000_000,
+                genesis_config.hash(),
+            ),
+            system_transaction::transfer(&payer, &pubkey, 1_000, genesis_config.hash()),
+        ]);
+        let signatures: Vec<_> = transactions.iter().map(|tx| *tx.signature()).collect();
+        consume_sender
+            .send(ConsumeWork {
+                batch_id: TransactionBatchId::new(0),
+                ids: vec![],
+                transactions,
+                max_ages: vec![max_age, max_age],
+                is_bundle: true,
+            })
+            .unwrap();
+        let consumed = consumed_receiver.recv().unwrap();
+        assert_eq!(consumed.retryable_indexes, Vec::<usize>::new());
+        assert_eq!(bank.get_balance(&pubkey), 1_000);
+
+        // Each transaction is recorded as its own entry, in order
+        for signature in signatures {
+            let (_bank, (entry, _tick_height)) = entry_receiver.recv().unwrap();
+            assert_eq!(entry.transactions.len(), 1);
+            assert_eq!(entry.transactions[0].signatures[0], signature);
+        }
+
+        drop(test_frame);
+        let _ = worker_thread.join().unwrap();
+    }
+
     #[test]
     fn test_worker_ttl() {
         let (test_frame, worker) = setup_test_frame();
@@ -1220,6 +1425,7 @@ mod tests {
                         alt_invalidation_slot: bank.slot() + 1,
                     },
                 ],
+                is_bundle: false,
             })
             .unwrap();
 
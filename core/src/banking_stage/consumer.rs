@@ -15,6 +15,7 @@ use {
     solana_feature_set as feature_set,
     solana_ledger::token_balances::collect_token_balances,
     solana_measure::{measure::Measure, measure_us},
+    solana_metrics::transaction_tracing::{self, TransactionSpan},
     solana_poh::poh_recorder::{
         BankStart, PohRecorderError, RecordTransactionsSummary, RecordTransactionsTimings,
         TransactionRecorder,
@@ -60,6 +61,24 @@ pub struct ProcessTransactionBatchOutput {
     pub execute_and_commit_transactions_output: ExecuteAndCommitTransactionsOutput,
 }
 
+/// The outcome of processing a bundle with `Consumer::process_and_record_bundle`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BundleOutcome {
+    /// All of the bundle's transactions were recorded and committed.
+    Committed,
+    /// None of the bundle's transactions were committed, but the bundle can
+    /// be retried, e.g. its accounts were in use or the block was full.
+    Retryable,
+    /// None of the bundle's transactions were committed, because one of them
+    /// failed the checks or failed to execute.
+    Failed,
+}
+
+pub struct ProcessBundleOutput {
+    pub(crate) outcome: BundleOutcome,
+    pub process_transaction_batch_output: ProcessTransactionBatchOutput,
+}
+
 pub struct ExecuteAndCommitTransactionsOutput {
     // Transactions counts reported to `ConsumeWorkerMetrics` and then
     // accumulated later for `LeaderSlotMetrics`
@@ -434,6 +453,100 @@ impl Consumer {
         txs: &[impl TransactionWithMeta],
         max_ages: &[MaxAge],
     ) -> ProcessTransactionBatchOutput {
+        let pre_results = Self::check_aged_transactions(bank, txs, max_ages);
+        self.process_and_record_transactions_with_pre_results(bank, txs, 0, pre_results)
+    }
+
+    /// Process a bundle of transactions atomically. The accounts of all the
+    /// transactions are locked together and the transactions are executed
+    /// sequentially, each seeing the state left by the ones before it. Only
+    /// if every transaction executes successfully is the bundle recorded, as
+    /// consecutive entries of one transaction each, and committed; otherwise
+    /// none of its transactions are.
+    pub fn process_and_record_bundle(
+        &self,
+        bank: &Arc<Bank>,
+        txs: &[impl TransactionWithMeta],
+        max_ages: &[MaxAge],
+    ) -> ProcessBundleOutput {
+        let pre_results = Self::check_aged_transactions(bank, txs, max_ages);
+        let (
+            (transaction_qos_cost_results, cost_model_throttled_transactions_count),
+            cost_model_us,
+        ) = measure_us!(self.qos_service.select_and_accumulate_transaction_costs(
+            bank,
+            txs,
+            pre_results
+        ));
+
+        // The bundle can only be executed if all of its transactions passed
+        // the checks and were selected for the block.
+        let bundle_result = transaction_qos_cost_results
+            .iter()
+            .find_map(|result| result.as_ref().err())
+            .map_or(Ok(()), |err| Err(err.clone()));
+        let (batch, lock_us) = measure_us!(bank.prepare_locked_bundle_batch(txs, bundle_result));
+
+        let execute_and_commit_transactions_output =
+            self.execute_and_commit_transactions_locked(bank, &batch, true);
+
+        let (_, unlock_us) = measure_us!(drop(batch));
+
+        let ExecuteAndCommitTransactionsOutput {
+            ref retryable_transaction_indexes,
+            ref execute_and_commit_timings,
+            ref commit_transactions_result,
+            ..
+        } = execute_and_commit_transactions_output;
+
+        QosService::remove_or_update_costs(
+            transaction_qos_cost_results.iter(),
+            commit_transactions_result.as_ref().ok(),
+            bank,
+        );
+
+        let outcome = match commit_transactions_result {
+            Ok(commit_transaction_details)
+                if commit_transaction_details.iter().all(|details| {
+                    matches!(details, CommitTransactionDetails::Committed { .. })
+                }) =>
+            {
+                BundleOutcome::Committed
+            }
+            _ if !retryable_transaction_indexes.is_empty() => BundleOutcome::Retryable,
+            _ => BundleOutcome::Failed,
+        };
+
+        let (cu, us) =
+            Self::accumulate_execute_units_and_time(&execute_and_commit_timings.execute_timings);
+        self.qos_service.accumulate_actual_execute_cu(cu);
+        self.qos_service.accumulate_actual_execute_time(us);
+        self.qos_service.report_metrics(bank.slot());
+
+        debug!(
+            "bank: {} lock: {}us unlock: {}us bundle_len: {} outcome: {:?}",
+            bank.slot(),
+            lock_us,
+            unlock_us,
+            txs.len(),
+            outcome,
+        );
+
+        ProcessBundleOutput {
+            outcome,
+            process_transaction_batch_output: ProcessTransactionBatchOutput {
+                cost_model_throttled_transactions_count,
+                cost_model_us,
+                execute_and_commit_transactions_output,
+            },
+        }
+    }
+
+    fn check_aged_transactions<'a>(
+        bank: &'a Bank,
+        txs: &'a [impl TransactionWithMeta],
+        max_ages: &'a [MaxAge],
+    ) -> impl Iterator<Item = Result<(), TransactionError>> + 'a {
         let move_precompile_verification_to_svm = bank
             .feature_set
             .is_active(&feature_set::move_precompile_verification_to_svm::id());
@@ -441,7 +554,7 @@ impl Consumer {
         // Need to filter out transactions since they were sanitized earlier.
         // This means that the transaction may cross and epoch boundary (not allowed),
         //  or account lookup tables may have been closed.
-        let pre_results = txs.iter().zip(max_ages).map(|(tx, max_age)| {
+        txs.iter().zip(max_ages).map(move |(tx, max_age)| {
             // If the transaction was sanitized before this bank's epoch,
             // additional checks are necessary.
             if bank.epoch() != max_age.sanitized_epoch {
@@ -468,8 +581,7 @@ impl Consumer {
             }
 
             Ok(())
-        });
-        self.process_and_record_transactions_with_pre_results(bank, txs, 0, pre_results)
+        })
     }
 
     fn process_and_record_transactions_with_pre_results(
@@ -503,7 +615,7 @@ impl Consumer {
         // WouldExceedMaxAccountCostLimit, WouldExceedMaxVoteCostLimit
         // and WouldExceedMaxAccountDataCostLimit
         let mut execute_and_commit_transactions_output =
-            self.execute_and_commit_transactions_locked(bank, &batch);
+            self.execute_and_commit_transactions_locked(bank, &batch, false);
 
         // Once the accounts are new transactions can enter the pipeline to process them
         let (_, unlock_us) = measure_us!(drop(batch));
@@ -552,10 +664,61 @@ impl Consumer {
         }
     }
 
+    /// If `is_bundle` is set, the batch's transactions are only recorded and
+    /// committed if all of them were processed successfully.
     fn execute_and_commit_transactions_locked(
         &self,
         bank: &Arc<Bank>,
         batch: &TransactionBatch<impl TransactionWithMeta>,
+        is_bundle: bool,
+    ) -> ExecuteAndCommitTransactionsOutput {
+        if !transaction_tracing::is_enabled() {
+            return self.do_execute_and_commit_transactions_locked(bank, batch, is_bundle);
+        }
+
+        let spans: Vec<_> = batch
+            .sanitized_transactions()
+            .iter()
+            .map(|tx| {
+                TransactionSpan::start(tx.signature().as_ref(), "banking_stage").map(|mut span| {
+                    span.add_attribute("slot", bank.slot());
+                    span
+                })
+            })
+            .collect();
+        let output = self.do_execute_and_commit_transactions_locked(bank, batch, is_bundle);
+        for (index, span) in spans.into_iter().enumerate() {
+            let Some(mut span) = span else {
+                continue;
+            };
+            let committed = match &output.commit_transactions_result {
+                Ok(details) => matches!(
+                    details.get(index),
+                    Some(CommitTransactionDetails::Committed { .. })
+                ),
+                Err(_) => false,
+            };
+            if committed {
+                span.add_attribute("result", "committed");
+            } else if output.retryable_transaction_indexes.contains(&index) {
+                span.add_attribute("result", "retryable");
+            } else {
+                span.add_attribute("result", "error");
+                match &batch.lock_results()[index] {
+                    Err(err) => span.set_error(err),
+                    Ok(()) => span.set_error("not committed"),
+                }
+            }
+            span.end();
+        }
+        output
+    }
+
+    fn do_execute_and_commit_transactions_locked(
+        &self,
+        bank: &Arc<Bank>,
+        batch: &TransactionBatch<impl TransactionWithMeta>,
+        is_bundle: bool,
     ) -> ExecuteAndCommitTransactionsOutput {
         let transaction_status_sender_enabled = self.committer.transaction_status_sender_enabled();
         let mut execute_and_commit_timings = LeaderExecuteAndCommitTimings::default();
@@ -639,6 +802,8 @@ impl Consumer {
                         transaction_status_sender_enabled
                     ),
                     transaction_account_lock_limit: Some(bank.get_transaction_account_lock_limit()),
+                    record_state_diffs: false,
+                    record_instruction_traces: false,
                 }
             ));
         execute_and_commit_timings.load_execute_us = load_execute_us;
@@ -646,6 +811,7 @@ impl Consumer {
         let LoadAndExecuteTransactionsOutput {
             processing_results,
             processed_counts,
+            ..
         } = load_and_execute_transactions_output;
 
         let transaction_counts = LeaderProcessedTransactionCounts {
@@ -655,6 +821,24 @@ impl Consumer {
             attempted_processing_count: processing_results.len() as u64,
         };
 
+        if is_bundle
+            && processed_counts.processed_with_successful_result_count
+                != processing_results.len() as u64
+        {
+            return ExecuteAndCommitTransactionsOutput {
+                transaction_counts,
+                retryable_transaction_indexes,
+                commit_transactions_result: Ok(vec![
+                    CommitTransactionDetails::NotCommitted;
+                    processing_results.len()
+                ]),
+                execute_and_commit_timings,
+                error_counters,
+                min_prioritization_fees,
+                max_prioritization_fees,
+            };
+        }
+
         let (processed_transactions, processing_results_to_transactions_us) =
             measure_us!(processing_results
                 .iter()
@@ -671,10 +855,38 @@ impl Consumer {
         let (freeze_lock, freeze_lock_us) = measure_us!(bank.freeze_lock());
         execute_and_commit_timings.freeze_lock_us = freeze_lock_us;
 
-        let (record_transactions_summary, record_us) = measure_us!(self
-            .transaction_recorder
-            .record_transactions(bank.slot(), processed_transactions));
+        let record_spans: Vec<_> = if transaction_tracing::is_enabled() {
+            processing_results
+                .iter()
+                .zip(batch.sanitized_transactions())
+                .filter(|(processing_result, _)| processing_result.was_processed())
+                .filter_map(|(_, tx)| TransactionSpan::start(tx.signature().as_ref(), "poh_record"))
+                .collect()
+        } else {
+            vec![]
+        };
+        let (record_transactions_summary, record_us) = measure_us!(if is_bundle {
+            // The bundle's transactions may conflict with each other, so each one is recorded
+            // as its own entry, in order
+            self.transaction_recorder.record_transaction_batches(
+                bank.slot(),
+                processed_transactions
+                    .into_iter()
+                    .map(|transaction| vec![transaction])
+                    .collect(),
+            )
+        } else {
+            self.transaction_recorder
+                .record_transactions(bank.slot(), processed_transactions)
+        });
         execute_and_commit_timings.record_us = record_us;
+        for mut span in record_spans {
+            span.add_attribute("slot", bank.slot());
+            if let Err(err) = &record_transactions_summary.result {
+                span.set_error(err);
+            }
+            span.end();
+        }
 
         let RecordTransactionsSummary {
             result: record_transactions_result,
@@ -1269,10 +1481,10 @@ mod tests {
                         let timeout = Duration::from_millis(10);
                         let record = record_receiver.recv_timeout(timeout);
                         if let Ok(record) = record {
-                            let record_response = poh_recorder.write().unwrap().record(
+                            let record_response = poh_recorder.write().unwrap().record_batches(
                                 record.slot,
-                                record.mixin,
-                                record.transactions,
+                                record.mixins,
+                                record.transaction_batches,
                             );
                             poh_recorder.write().unwrap().tick();
                             if record.sender.send(record_response).is_err() {
@@ -1,6 +1,6 @@
 use {
     super::{
-        consumer::LeaderProcessedTransactionCounts,
+        consumer::{BundleOutcome, LeaderProcessedTransactionCounts},
         leader_slot_timing_metrics::{LeaderExecuteAndCommitTimings, LeaderSlotTimingMetrics},
         packet_deserializer::PacketReceiverStats,
         unprocessed_transaction_storage::{
@@ -238,6 +238,17 @@ struct LeaderSlotPacketCountMetrics {
     // is defined in `ForwardPacketBatchesByAccounts` in `forward_packet_batches_by_accounts.rs`
     forwardable_batches_count: u64,
 
+    // total number of bundles whose transactions were all recorded and committed into the block
+    bundles_committed_count: u64,
+
+    // total number of bundles that were not committed, but were sent back to the scheduler to be
+    // retried, e.g. because their accounts were in use or the block was full
+    bundles_retryable_count: u64,
+
+    // total number of bundles that were dropped because one of their transactions failed the
+    // checks or failed to execute
+    bundles_failed_count: u64,
+
     // min prioritization fees for scheduled transactions
     min_prioritization_fees: u64,
     // max prioritization fees for scheduled transactions
@@ -384,6 +395,17 @@ impl LeaderSlotPacketCountMetrics {
                 self.end_of_slot_unprocessed_buffer_len,
                 i64
             ),
+            (
+                "bundles_committed_count",
+                self.bundles_committed_count,
+                i64
+            ),
+            (
+                "bundles_retryable_count",
+                self.bundles_retryable_count,
+                i64
+            ),
+            ("bundles_failed_count", self.bundles_failed_count, i64),
             (
                 "min_prioritization_fees",
                 self.min_prioritization_fees,
@@ -1090,6 +1112,18 @@ impl LeaderSlotMetricsTracker {
         }
     }
 
+    pub(crate) fn increment_bundle_outcome_count(&mut self, outcome: BundleOutcome) {
+        if let Some(leader_slot_metrics) = &mut self.leader_slot_metrics {
+            let packet_count_metrics = &mut leader_slot_metrics.packet_count_metrics;
+            let count = match outcome {
+                BundleOutcome::Committed => &mut packet_count_metrics.bundles_committed_count,
+                BundleOutcome::Retryable => &mut packet_count_metrics.bundles_retryable_count,
+                BundleOutcome::Failed => &mut packet_count_metrics.bundles_failed_count,
+            };
+            saturating_add_assign!(*count, 1);
+        }
+    }
+
     pub(crate) fn increment_dropped_gossip_vote_count(&mut self, count: u64) {
         if let Some(leader_slot_metrics) = &mut self.leader_slot_metrics {
             saturating_add_assign!(
@@ -1375,4 +1409,38 @@ mod tests {
             assert!(leader_slot_metrics_tracker.leader_slot_metrics.is_none());
         }
     }
+
+    #[test]
+    pub fn test_increment_bundle_outcome_count() {
+        let TestSlotBoundaryComponents {
+            first_poh_recorder_bank,
+            mut leader_slot_metrics_tracker,
+            ..
+        } = setup_test_slot_boundary_banks();
+
+        // Outcomes outside of a leader slot are not counted
+        leader_slot_metrics_tracker.increment_bundle_outcome_count(BundleOutcome::Committed);
+        assert!(leader_slot_metrics_tracker.leader_slot_metrics.is_none());
+
+        let action = leader_slot_metrics_tracker
+            .check_leader_slot_boundary(Some(&first_poh_recorder_bank), None);
+        leader_slot_metrics_tracker.apply_action(action);
+        for outcome in [
+            BundleOutcome::Committed,
+            BundleOutcome::Committed,
+            BundleOutcome::Retryable,
+            BundleOutcome::Failed,
+        ] {
+            leader_slot_metrics_tracker.increment_bundle_outcome_count(outcome);
+        }
+
+        let packet_count_metrics = &leader_slot_metrics_tracker
+            .leader_slot_metrics
+            .as_ref()
+            .unwrap()
+            .packet_count_metrics;
+        assert_eq!(packet_count_metrics.bundles_committed_count, 2);
+        assert_eq!(packet_count_metrics.bundles_retryable_count, 1);
+        assert_eq!(packet_count_metrics.bundles_failed_count, 1);
+    }
 }
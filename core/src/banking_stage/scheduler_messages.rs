@@ -41,6 +41,9 @@ pub struct ConsumeWork<Tx> {
     pub ids: Vec<TransactionId>,
     pub transactions: Vec<Tx>,
     pub max_ages: Vec<MaxAge>,
+    /// The transactions form a bundle, and must be committed atomically and
+    /// contiguously. Bundles do not have `ids`.
+    pub is_bundle: bool,
 }
 
 /// Message: [Worker -> Scheduler]
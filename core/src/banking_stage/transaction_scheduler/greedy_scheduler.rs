@@ -0,0 +1,451 @@
+use {
+    super::{
+        scheduler::{Scheduler, SchedulingSummary},
+        scheduler_common::{
+            select_thread, try_schedule_transaction, Batches, SchedulingCommon,
+            TransactionSchedulingError,
+        },
+        scheduler_error::SchedulerError,
+        transaction_state::TransactionBundle,
+        transaction_state_container::StateContainer,
+    },
+    crate::banking_stage::{
+        consumer::TARGET_NUM_TRANSACTIONS_PER_BATCH,
+        read_write_account_set::ReadWriteAccountSet,
+        scheduler_messages::{ConsumeWork, FinishedConsumeWork},
+    },
+    crossbeam_channel::{Receiver, Sender},
+    solana_cost_model::block_cost_limits::MAX_BLOCK_UNITS,
+    solana_measure::measure_us,
+    solana_runtime_transaction::transaction_with_meta::TransactionWithMeta,
+    solana_sdk::saturating_add_assign,
+};
+
+/// Schedules transactions strictly in the priority order of the container,
+/// without the look-ahead of the `PrioGraphScheduler`. The batches being
+/// built are sent as soon as the next transaction conflicts with any of them,
+/// so that conflicting transactions are never executed in the same batch.
+pub(crate) struct GreedyScheduler<Tx> {
+    common: SchedulingCommon<Tx>,
+    /// Accounts locked by the transactions in the batches being built.
+    working_account_set: ReadWriteAccountSet,
+}
+
+impl<Tx: TransactionWithMeta> GreedyScheduler<Tx> {
+    pub(crate) fn new(
+        consume_work_senders: Vec<Sender<ConsumeWork<Tx>>>,
+        finished_consume_work_receiver: Receiver<FinishedConsumeWork<Tx>>,
+    ) -> Self {
+        Self {
+            common: SchedulingCommon::new(consume_work_senders, finished_consume_work_receiver),
+            working_account_set: ReadWriteAccountSet::default(),
+        }
+    }
+}
+
+impl<Tx: TransactionWithMeta> Scheduler<Tx> for GreedyScheduler<Tx> {
+    fn schedule<S: StateContainer<Tx>>(
+        &mut self,
+        container: &mut S,
+        pre_graph_filter: impl Fn(&[&Tx], &mut [bool]),
+        pre_lock_filter: impl Fn(&Tx) -> bool,
+    ) -> Result<SchedulingSummary, SchedulerError> {
+        let num_threads = self.common.num_threads();
+        let max_cu_per_thread = MAX_BLOCK_UNITS / num_threads as u64;
+
+        let mut schedulable_threads = self.common.schedulable_threads(max_cu_per_thread);
+        if schedulable_threads.is_empty() {
+            return Ok(SchedulingSummary {
+                num_scheduled: 0,
+                num_unschedulable: 0,
+                num_filtered_out: 0,
+                filter_time_us: 0,
+            });
+        }
+
+        let mut batches = Batches::new(num_threads);
+        // Transactions which conflict with unschedulable transactions must not
+        // be scheduled before them.
+        let mut blocking_locks = ReadWriteAccountSet::default();
+        // Transactions popped from the container but not scheduled. These are
+        // pushed back into the container at the end of the pass.
+        let mut unscheduled_ids = Vec::new();
+
+        const MAX_TRANSACTIONS_PER_SCHEDULING_PASS: usize = 100_000;
+        const MAX_FILTER_CHUNK_SIZE: usize = 128;
+        let mut num_scanned: usize = 0;
+        let mut num_scheduled: usize = 0;
+        let mut num_sent: usize = 0;
+        let mut num_unschedulable: usize = 0;
+        let mut num_filtered_out: usize = 0;
+        let mut total_filter_time_us: u64 = 0;
+
+        while num_scanned < MAX_TRANSACTIONS_PER_SCHEDULING_PASS
+            && !schedulable_threads.is_empty()
+            && !container.is_empty()
+        {
+            let mut filter_array = [true; MAX_FILTER_CHUNK_SIZE];
+            let mut ids = Vec::with_capacity(MAX_FILTER_CHUNK_SIZE);
+            while ids.len() < MAX_FILTER_CHUNK_SIZE {
+                let Some(id) = container.pop() else {
+                    break;
+                };
+                ids.push(id);
+            }
+            saturating_add_assign!(num_scanned, ids.len());
+
+            let txs: Vec<_> = ids
+                .iter()
+                .map(|id| &container.get_transaction_ttl(id.id).unwrap().transaction)
+                .collect();
+            let (_, filter_us) =
+                measure_us!(pre_graph_filter(&txs, &mut filter_array[..ids.len()]));
+            saturating_add_assign!(total_filter_time_us, filter_us);
+
+            for (id, filter_result) in ids.into_iter().zip(filter_array) {
+                if !filter_result {
+                    saturating_add_assign!(num_filtered_out, 1);
+                    container.remove_by_id(id.id);
+                    continue;
+                }
+                if schedulable_threads.is_empty() {
+                    unscheduled_ids.push(id);
+                    continue;
+                }
+
+                let Some(transaction_state) = container.get_mut_transaction_state(id.id) else {
+                    panic!("transaction state must exist")
+                };
+
+                // Send the batches being built if the transaction conflicts with them,
+                // so that it is executed after them.
+                if !self
+                    .working_account_set
+                    .check_locks(&transaction_state.transaction_ttl().transaction)
+                {
+                    self.working_account_set.clear();
+                    saturating_add_assign!(num_sent, self.common.send_batches(&mut batches)?);
+                }
+
+                let maybe_schedule_info = try_schedule_transaction(
+                    transaction_state,
+                    &pre_lock_filter,
+                    &mut blocking_locks,
+                    &mut self.common.account_locks,
+                    schedulable_threads,
+                    |thread_set| {
+                        select_thread(
+                            thread_set,
+                            &batches.total_cus,
+                            self.common.in_flight_tracker.cus_in_flight_per_thread(),
+                            &batches.transactions,
+                            self.common.in_flight_tracker.num_in_flight_per_thread(),
+                        )
+                    },
+                );
+
+                match maybe_schedule_info {
+                    Err(TransactionSchedulingError::Filtered) => {
+                        container.remove_by_id(id.id);
+                    }
+                    Err(TransactionSchedulingError::UnschedulableConflicts) => {
+                        unscheduled_ids.push(id);
+                        saturating_add_assign!(num_unschedulable, 1);
+                    }
+                    Ok(transaction_scheduling_info) => {
+                        let thread_id = transaction_scheduling_info.thread_id;
+                        self.working_account_set
+                            .take_locks(&transaction_scheduling_info.transaction);
+                        saturating_add_assign!(num_scheduled, 1);
+                        batches.push(id.id, transaction_scheduling_info);
+
+                        // If target batch size is reached, send only this batch.
+                        if batches.ids[thread_id].len() >= TARGET_NUM_TRANSACTIONS_PER_BATCH {
+                            saturating_add_assign!(
+                                num_sent,
+                                self.common.send_batch(&mut batches, thread_id)?
+                            );
+                        }
+
+                        // if the thread is at max_cu_per_thread, remove it from the schedulable threads
+                        if self.common.in_flight_tracker.cus_in_flight_per_thread()[thread_id]
+                            + batches.total_cus[thread_id]
+                            >= max_cu_per_thread
+                        {
+                            schedulable_threads.remove(thread_id);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Send batches for any remaining transactions
+        self.working_account_set.clear();
+        saturating_add_assign!(num_sent, self.common.send_batches(&mut batches)?);
+
+        for id in unscheduled_ids {
+            container.push_id_into_queue(id);
+        }
+
+        assert_eq!(
+            num_scheduled, num_sent,
+            "number of scheduled and sent transactions must match"
+        );
+
+        Ok(SchedulingSummary {
+            num_scheduled,
+            num_unschedulable,
+            num_filtered_out,
+            filter_time_us: total_filter_time_us,
+        })
+    }
+
+    fn receive_completed(
+        &mut self,
+        container: &mut impl StateContainer<Tx>,
+    ) -> Result<(usize, usize), SchedulerError> {
+        self.common.receive_completed(container)
+    }
+
+    fn schedule_bundle(
+        &mut self,
+        bundle: TransactionBundle<Tx>,
+    ) -> Result<Option<TransactionBundle<Tx>>, SchedulerError> {
+        self.common.schedule_bundle(bundle)
+    }
+
+    fn take_retryable_bundles(&mut self) -> Vec<TransactionBundle<Tx>> {
+        self.common.take_retryable_bundles()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::banking_stage::{
+            immutable_deserialized_packet::ImmutableDeserializedPacket,
+            scheduler_messages::{MaxAge, TransactionId},
+            transaction_scheduler::{
+                transaction_state::SanitizedTransactionTTL,
+                transaction_state_container::TransactionStateContainer,
+            },
+        },
+        crossbeam_channel::unbounded,
+        itertools::Itertools,
+        solana_runtime_transaction::runtime_transaction::RuntimeTransaction,
+        solana_sdk::{
+            compute_budget::ComputeBudgetInstruction,
+            hash::Hash,
+            message::Message,
+            packet::Packet,
+            pubkey::Pubkey,
+            signature::Keypair,
+            signer::Signer,
+            system_instruction,
+            transaction::{SanitizedTransaction, Transaction},
+        },
+        std::{borrow::Borrow, sync::Arc},
+    };
+
+    #[allow(clippy::type_complexity)]
+    fn create_test_frame(
+        num_threads: usize,
+    ) -> (
+        GreedyScheduler<RuntimeTransaction<SanitizedTransaction>>,
+        Vec<Receiver<ConsumeWork<RuntimeTransaction<SanitizedTransaction>>>>,
+        Sender<FinishedConsumeWork<RuntimeTransaction<SanitizedTransaction>>>,
+    ) {
+        let (consume_work_senders, consume_work_receivers) =
+            (0..num_threads).map(|_| unbounded()).unzip();
+        let (finished_consume_work_sender, finished_consume_work_receiver) = unbounded();
+        let scheduler = GreedyScheduler::new(consume_work_senders, finished_consume_work_receiver);
+        (
+            scheduler,
+            consume_work_receivers,
+            finished_consume_work_sender,
+        )
+    }
+
+    fn prioritized_tranfers(
+        from_keypair: &Keypair,
+        to_pubkeys: impl IntoIterator<Item = impl Borrow<Pubkey>>,
+        lamports: u64,
+        priority: u64,
+    ) -> RuntimeTransaction<SanitizedTransaction> {
+        let to_pubkeys_lamports = to_pubkeys
+            .into_iter()
+            .map(|pubkey| *pubkey.borrow())
+            .zip(std::iter::repeat(lamports))
+            .collect_vec();
+        let mut ixs =
+            system_instruction::transfer_many(&from_keypair.pubkey(), &to_pubkeys_lamports);
+        let prioritization = ComputeBudgetInstruction::set_compute_unit_price(priority);
+        ixs.push(prioritization);
+        let message = Message::new(&ixs, Some(&from_keypair.pubkey()));
+        let tx = Transaction::new(&[from_keypair], message, Hash::default());
+        RuntimeTransaction::from_transaction_for_tests(tx)
+    }
+
+    fn create_container(
+        tx_infos: impl IntoIterator<
+            Item = (
+                impl Borrow<Keypair>,
+                impl IntoIterator<Item = impl Borrow<Pubkey>>,
+                u64,
+                u64,
+            ),
+        >,
+    ) -> TransactionStateContainer<RuntimeTransaction<SanitizedTransaction>> {
+        let mut container = TransactionStateContainer::with_capacity(10 * 1024);
+        for (from_keypair, to_pubkeys, lamports, compute_unit_price) in tx_infos.into_iter() {
+            let transaction = prioritized_tranfers(
+                from_keypair.borrow(),
+                to_pubkeys,
+                lamports,
+                compute_unit_price,
+            );
+            let packet = Arc::new(
+                ImmutableDeserializedPacket::new(
+                    Packet::from_data(None, transaction.to_versioned_transaction()).unwrap(),
+                )
+                .unwrap(),
+            );
+            let transaction_ttl = SanitizedTransactionTTL {
+                transaction,
+                max_age: MaxAge::MAX,
+            };
+            const TEST_TRANSACTION_COST: u64 = 5000;
+            container.insert_new_transaction(
+                transaction_ttl,
+                packet,
+                compute_unit_price,
+                TEST_TRANSACTION_COST,
+            );
+        }
+
+        container
+    }
+
+    fn collect_work(
+        receiver: &Receiver<ConsumeWork<RuntimeTransaction<SanitizedTransaction>>>,
+    ) -> (
+        Vec<ConsumeWork<RuntimeTransaction<SanitizedTransaction>>>,
+        Vec<Vec<TransactionId>>,
+    ) {
+        receiver
+            .try_iter()
+            .map(|work| {
+                let ids = work.ids.clone();
+                (work, ids)
+            })
+            .unzip()
+    }
+
+    fn test_pre_graph_filter(
+        _txs: &[&RuntimeTransaction<SanitizedTransaction>],
+        results: &mut [bool],
+    ) {
+        results.fill(true);
+    }
+
+    fn test_pre_lock_filter(_tx: &RuntimeTransaction<SanitizedTransaction>) -> bool {
+        true
+    }
+
+    #[test]
+    fn test_schedule_single_threaded_no_conflicts() {
+        let (mut scheduler, work_receivers, _finished_work_sender) = create_test_frame(1);
+        let mut container = create_container([
+            (&Keypair::new(), &[Pubkey::new_unique()], 1, 1),
+            (&Keypair::new(), &[Pubkey::new_unique()], 2, 2),
+        ]);
+
+        let scheduling_summary = scheduler
+            .schedule(&mut container, test_pre_graph_filter, test_pre_lock_filter)
+            .unwrap();
+        assert_eq!(scheduling_summary.num_scheduled, 2);
+        assert_eq!(scheduling_summary.num_unschedulable, 0);
+        assert_eq!(collect_work(&work_receivers[0]).1, vec![vec![1, 0]]);
+    }
+
+    #[test]
+    fn test_schedule_single_threaded_conflict() {
+        let (mut scheduler, work_receivers, _finished_work_sender) = create_test_frame(1);
+        let pubkey = Pubkey::new_unique();
+        let mut container = create_container([
+            (&Keypair::new(), &[pubkey], 1, 1),
+            (&Keypair::new(), &[pubkey], 1, 2),
+        ]);
+
+        // Conflicting transactions are sent in separate batches.
+        let scheduling_summary = scheduler
+            .schedule(&mut container, test_pre_graph_filter, test_pre_lock_filter)
+            .unwrap();
+        assert_eq!(scheduling_summary.num_scheduled, 2);
+        assert_eq!(scheduling_summary.num_unschedulable, 0);
+        assert_eq!(collect_work(&work_receivers[0]).1, vec![vec![1], vec![0]]);
+    }
+
+    #[test]
+    fn test_schedule_multi_threaded_conflict() {
+        let (mut scheduler, work_receivers, finished_work_sender) = create_test_frame(2);
+        let accounts = (0..4).map(|_| Keypair::new()).collect_vec();
+        let mut container = create_container([
+            (&accounts[0], &[accounts[1].pubkey()], 1, 4),
+            (&accounts[2], &[accounts[3].pubkey()], 1, 3),
+            (&accounts[1], &[accounts[2].pubkey()], 1, 2),
+            (&accounts[3], &[accounts[1].pubkey()], 1, 1),
+        ]);
+
+        // [0] and [1] are scheduled onto different threads, so [2] which conflicts
+        // with both is unschedulable. [3] only conflicts with [1], but is blocked by
+        // the higher priority [2].
+        let scheduling_summary = scheduler
+            .schedule(&mut container, test_pre_graph_filter, test_pre_lock_filter)
+            .unwrap();
+        assert_eq!(scheduling_summary.num_scheduled, 2);
+        assert_eq!(scheduling_summary.num_unschedulable, 2);
+        let (thread_0_work, thread_0_ids) = collect_work(&work_receivers[0]);
+        assert_eq!(thread_0_ids, [vec![0]]);
+        assert_eq!(collect_work(&work_receivers[1]).1, [vec![1]]);
+
+        // Once thread 0 completes, [2] can be scheduled onto thread 1, followed by [3].
+        finished_work_sender
+            .send(FinishedConsumeWork {
+                work: thread_0_work.into_iter().next().unwrap(),
+                retryable_indexes: vec![],
+            })
+            .unwrap();
+        scheduler.receive_completed(&mut container).unwrap();
+        let scheduling_summary = scheduler
+            .schedule(&mut container, test_pre_graph_filter, test_pre_lock_filter)
+            .unwrap();
+        assert_eq!(scheduling_summary.num_scheduled, 2);
+        assert_eq!(scheduling_summary.num_unschedulable, 0);
+        assert_eq!(collect_work(&work_receivers[1]).1, [vec![2], vec![3]]);
+    }
+
+    #[test]
+    fn test_schedule_pre_graph_filter() {
+        let (mut scheduler, work_receivers, _finished_work_sender) = create_test_frame(1);
+        let mut container = create_container(
+            (0..4).map(|priority| (Keypair::new(), [Pubkey::new_unique()], 1, priority)),
+        );
+
+        // Drop every other transaction before scheduling.
+        let pre_graph_filter = |_txs: &[&RuntimeTransaction<SanitizedTransaction>],
+                                results: &mut [bool]| {
+            for (index, result) in results.iter_mut().enumerate() {
+                *result = index % 2 == 0;
+            }
+        };
+        let scheduling_summary = scheduler
+            .schedule(&mut container, pre_graph_filter, test_pre_lock_filter)
+            .unwrap();
+        assert_eq!(scheduling_summary.num_scheduled, 2);
+        assert_eq!(scheduling_summary.num_filtered_out, 2);
+        assert_eq!(collect_work(&work_receivers[0]).1, vec![vec![3, 1]]);
+        assert!(container.is_empty());
+    }
+}
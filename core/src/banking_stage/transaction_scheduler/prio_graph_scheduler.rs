@@ -1,23 +1,24 @@
 use {
     super::{
-        in_flight_tracker::InFlightTracker,
+        scheduler::{Scheduler, SchedulingSummary},
+        scheduler_common::{
+            select_thread, try_schedule_transaction, Batches, SchedulingCommon,
+            TransactionSchedulingError,
+        },
         scheduler_error::SchedulerError,
-        thread_aware_account_locks::{ThreadAwareAccountLocks, ThreadId, ThreadSet},
-        transaction_state::SanitizedTransactionTTL,
+        thread_aware_account_locks::ThreadSet,
+        transaction_state::{SanitizedTransactionTTL, TransactionBundle},
     },
     crate::banking_stage::{
         consumer::TARGET_NUM_TRANSACTIONS_PER_BATCH,
         read_write_account_set::ReadWriteAccountSet,
-        scheduler_messages::{
-            ConsumeWork, FinishedConsumeWork, MaxAge, TransactionBatchId, TransactionId,
-        },
+        scheduler_messages::{ConsumeWork, FinishedConsumeWork},
         transaction_scheduler::{
-            transaction_priority_id::TransactionPriorityId, transaction_state::TransactionState,
+            transaction_priority_id::TransactionPriorityId,
             transaction_state_container::StateContainer,
         },
     },
-    crossbeam_channel::{Receiver, Sender, TryRecvError},
-    itertools::izip,
+    crossbeam_channel::{Receiver, Sender},
     prio_graph::{AccessKind, GraphNode, PrioGraph},
     solana_cost_model::block_cost_limits::MAX_BLOCK_UNITS,
     solana_measure::measure_us,
@@ -42,10 +43,7 @@ type SchedulerPrioGraph = PrioGraph<
 >;
 
 pub(crate) struct PrioGraphScheduler<Tx> {
-    in_flight_tracker: InFlightTracker,
-    account_locks: ThreadAwareAccountLocks,
-    consume_work_senders: Vec<Sender<ConsumeWork<Tx>>>,
-    finished_consume_work_receiver: Receiver<FinishedConsumeWork<Tx>>,
+    common: SchedulingCommon<Tx>,
     look_ahead_window_size: usize,
     prio_graph: SchedulerPrioGraph,
 }
@@ -55,48 +53,47 @@ impl<Tx: TransactionWithMeta> PrioGraphScheduler<Tx> {
         consume_work_senders: Vec<Sender<ConsumeWork<Tx>>>,
         finished_consume_work_receiver: Receiver<FinishedConsumeWork<Tx>>,
     ) -> Self {
-        let num_threads = consume_work_senders.len();
         Self {
-            in_flight_tracker: InFlightTracker::new(num_threads),
-            account_locks: ThreadAwareAccountLocks::new(num_threads),
-            consume_work_senders,
-            finished_consume_work_receiver,
+            common: SchedulingCommon::new(consume_work_senders, finished_consume_work_receiver),
             look_ahead_window_size: 2048,
             prio_graph: PrioGraph::new(passthrough_priority),
         }
     }
 
-    /// Schedule transactions from the given `StateContainer` to be
-    /// consumed by the worker threads. Returns summary of scheduling, or an
-    /// error.
-    /// `pre_graph_filter` is used to filter out transactions that should be
-    /// skipped and dropped before insertion to the prio-graph. This fn should
-    /// set `false` for transactions that should be dropped, and `true`
-    /// otherwise.
-    /// `pre_lock_filter` is used to filter out transactions after they have
-    /// made it to the top of the prio-graph, and immediately before locks are
-    /// checked and taken. This fn should return `true` for transactions that
-    /// should be scheduled, and `false` otherwise.
-    ///
+    /// Gets accessed accounts (resources) for use in `PrioGraph`.
+    fn get_transaction_account_access(
+        transaction: &SanitizedTransactionTTL<impl SVMMessage>,
+    ) -> impl Iterator<Item = (Pubkey, AccessKind)> + '_ {
+        let message = &transaction.transaction;
+        message
+            .account_keys()
+            .iter()
+            .enumerate()
+            .map(|(index, key)| {
+                if message.is_writable(index) {
+                    (*key, AccessKind::Write)
+                } else {
+                    (*key, AccessKind::Read)
+                }
+            })
+    }
+}
+
+impl<Tx: TransactionWithMeta> Scheduler<Tx> for PrioGraphScheduler<Tx> {
     /// Uses a `PrioGraph` to perform look-ahead during the scheduling of transactions.
     /// This, combined with internal tracking of threads' in-flight transactions, allows
     /// for load-balancing while prioritizing scheduling transactions onto threads that will
     /// not cause conflicts in the near future.
-    pub(crate) fn schedule<S: StateContainer<Tx>>(
+    fn schedule<S: StateContainer<Tx>>(
         &mut self,
         container: &mut S,
         pre_graph_filter: impl Fn(&[&Tx], &mut [bool]),
         pre_lock_filter: impl Fn(&Tx) -> bool,
     ) -> Result<SchedulingSummary, SchedulerError> {
-        let num_threads = self.consume_work_senders.len();
+        let num_threads = self.common.num_threads();
         let max_cu_per_thread = MAX_BLOCK_UNITS / num_threads as u64;
 
-        let mut schedulable_threads = ThreadSet::any(num_threads);
-        for thread_id in 0..num_threads {
-            if self.in_flight_tracker.cus_in_flight_per_thread()[thread_id] >= max_cu_per_thread {
-                schedulable_threads.remove(thread_id);
-            }
-        }
+        let mut schedulable_threads = self.common.schedulable_threads(max_cu_per_thread);
         if schedulable_threads.is_empty() {
             return Ok(SchedulingSummary {
                 num_scheduled: 0,
@@ -171,7 +168,7 @@ impl<Tx: TransactionWithMeta> PrioGraphScheduler<Tx> {
         chunked_pops(container, &mut self.prio_graph, &mut window_budget);
 
         let mut unblock_this_batch =
-            Vec::with_capacity(self.consume_work_senders.len() * TARGET_NUM_TRANSACTIONS_PER_BATCH);
+            Vec::with_capacity(num_threads * TARGET_NUM_TRANSACTIONS_PER_BATCH);
         const MAX_TRANSACTIONS_PER_SCHEDULING_PASS: usize = 100_000;
         let mut num_scheduled: usize = 0;
         let mut num_sent: usize = 0;
@@ -195,15 +192,15 @@ impl<Tx: TransactionWithMeta> PrioGraphScheduler<Tx> {
                     transaction_state,
                     &pre_lock_filter,
                     &mut blocking_locks,
-                    &mut self.account_locks,
-                    num_threads,
+                    &mut self.common.account_locks,
+                    ThreadSet::any(num_threads),
                     |thread_set| {
-                        Self::select_thread(
+                        select_thread(
                             thread_set,
                             &batches.total_cus,
-                            self.in_flight_tracker.cus_in_flight_per_thread(),
+                            self.common.in_flight_tracker.cus_in_flight_per_thread(),
                             &batches.transactions,
-                            self.in_flight_tracker.num_in_flight_per_thread(),
+                            self.common.in_flight_tracker.num_in_flight_per_thread(),
                         )
                     },
                 );
@@ -216,29 +213,22 @@ impl<Tx: TransactionWithMeta> PrioGraphScheduler<Tx> {
                         unschedulable_ids.push(id);
                         saturating_add_assign!(num_unschedulable, 1);
                     }
-                    Ok(TransactionSchedulingInfo {
-                        thread_id,
-                        transaction,
-                        max_age,
-                        cost,
-                    }) => {
+                    Ok(transaction_scheduling_info) => {
+                        let thread_id = transaction_scheduling_info.thread_id;
                         saturating_add_assign!(num_scheduled, 1);
-                        batches.transactions[thread_id].push(transaction);
-                        batches.ids[thread_id].push(id.id);
-                        batches.max_ages[thread_id].push(max_age);
-                        saturating_add_assign!(batches.total_cus[thread_id], cost);
+                        batches.push(id.id, transaction_scheduling_info);
 
                         // If target batch size is reached, send only this batch.
                         if batches.ids[thread_id].len() >= TARGET_NUM_TRANSACTIONS_PER_BATCH {
                             saturating_add_assign!(
                                 num_sent,
-                                self.send_batch(&mut batches, thread_id)?
+                                self.common.send_batch(&mut batches, thread_id)?
                             );
                         }
 
                         // if the thread is at max_cu_per_thread, remove it from the schedulable threads
                         // if there are no more schedulable threads, stop scheduling.
-                        if self.in_flight_tracker.cus_in_flight_per_thread()[thread_id]
+                        if self.common.in_flight_tracker.cus_in_flight_per_thread()[thread_id]
                             + batches.total_cus[thread_id]
                             >= max_cu_per_thread
                         {
@@ -256,7 +246,7 @@ impl<Tx: TransactionWithMeta> PrioGraphScheduler<Tx> {
             }
 
             // Send all non-empty batches
-            saturating_add_assign!(num_sent, self.send_batches(&mut batches)?);
+            saturating_add_assign!(num_sent, self.common.send_batches(&mut batches)?);
 
             // Refresh window budget and do chunked pops
             saturating_add_assign!(window_budget, unblock_this_batch.len());
@@ -269,7 +259,7 @@ impl<Tx: TransactionWithMeta> PrioGraphScheduler<Tx> {
         }
 
         // Send batches for any remaining transactions
-        saturating_add_assign!(num_sent, self.send_batches(&mut batches)?);
+        saturating_add_assign!(num_sent, self.common.send_batches(&mut batches)?);
 
         // Push unschedulable ids back into the container
         for id in unschedulable_ids {
@@ -300,304 +290,23 @@ impl<Tx: TransactionWithMeta> PrioGraphScheduler<Tx> {
 
     /// Receive completed batches of transactions without blocking.
     /// Returns (num_transactions, num_retryable_transactions) on success.
-    pub fn receive_completed(
+    fn receive_completed(
         &mut self,
         container: &mut impl StateContainer<Tx>,
     ) -> Result<(usize, usize), SchedulerError> {
-        let mut total_num_transactions: usize = 0;
-        let mut total_num_retryable: usize = 0;
-        loop {
-            let (num_transactions, num_retryable) = self.try_receive_completed(container)?;
-            if num_transactions == 0 {
-                break;
-            }
-            saturating_add_assign!(total_num_transactions, num_transactions);
-            saturating_add_assign!(total_num_retryable, num_retryable);
-        }
-        Ok((total_num_transactions, total_num_retryable))
+        self.common.receive_completed(container)
     }
 
-    /// Receive completed batches of transactions.
-    /// Returns `Ok((num_transactions, num_retryable))` if a batch was received, `Ok((0, 0))` if no batch was received.
-    fn try_receive_completed(
+    fn schedule_bundle(
         &mut self,
-        container: &mut impl StateContainer<Tx>,
-    ) -> Result<(usize, usize), SchedulerError> {
-        match self.finished_consume_work_receiver.try_recv() {
-            Ok(FinishedConsumeWork {
-                work:
-                    ConsumeWork {
-                        batch_id,
-                        ids,
-                        transactions,
-                        max_ages,
-                    },
-                retryable_indexes,
-            }) => {
-                let num_transactions = ids.len();
-                let num_retryable = retryable_indexes.len();
-
-                // Free the locks
-                self.complete_batch(batch_id, &transactions);
-
-                // Retryable transactions should be inserted back into the container
-                let mut retryable_iter = retryable_indexes.into_iter().peekable();
-                for (index, (id, transaction, max_age)) in
-                    izip!(ids, transactions, max_ages).enumerate()
-                {
-                    if let Some(retryable_index) = retryable_iter.peek() {
-                        if *retryable_index == index {
-                            container.retry_transaction(
-                                id,
-                                SanitizedTransactionTTL {
-                                    transaction,
-                                    max_age,
-                                },
-                            );
-                            retryable_iter.next();
-                            continue;
-                        }
-                    }
-                    container.remove_by_id(id);
-                }
-
-                Ok((num_transactions, num_retryable))
-            }
-            Err(TryRecvError::Empty) => Ok((0, 0)),
-            Err(TryRecvError::Disconnected) => Err(SchedulerError::DisconnectedRecvChannel(
-                "finished consume work",
-            )),
-        }
-    }
-
-    /// Mark a given `TransactionBatchId` as completed.
-    /// This will update the internal tracking, including account locks.
-    fn complete_batch(&mut self, batch_id: TransactionBatchId, transactions: &[Tx]) {
-        let thread_id = self.in_flight_tracker.complete_batch(batch_id);
-        for transaction in transactions {
-            let account_keys = transaction.account_keys();
-            let write_account_locks = account_keys
-                .iter()
-                .enumerate()
-                .filter_map(|(index, key)| transaction.is_writable(index).then_some(key));
-            let read_account_locks = account_keys
-                .iter()
-                .enumerate()
-                .filter_map(|(index, key)| (!transaction.is_writable(index)).then_some(key));
-            self.account_locks
-                .unlock_accounts(write_account_locks, read_account_locks, thread_id);
-        }
+        bundle: TransactionBundle<Tx>,
+    ) -> Result<Option<TransactionBundle<Tx>>, SchedulerError> {
+        self.common.schedule_bundle(bundle)
     }
 
-    /// Send all batches of transactions to the worker threads.
-    /// Returns the number of transactions sent.
-    fn send_batches(&mut self, batches: &mut Batches<Tx>) -> Result<usize, SchedulerError> {
-        (0..self.consume_work_senders.len())
-            .map(|thread_index| self.send_batch(batches, thread_index))
-            .sum()
+    fn take_retryable_bundles(&mut self) -> Vec<TransactionBundle<Tx>> {
+        self.common.take_retryable_bundles()
     }
-
-    /// Send a batch of transactions to the given thread's `ConsumeWork` channel.
-    /// Returns the number of transactions sent.
-    fn send_batch(
-        &mut self,
-        batches: &mut Batches<Tx>,
-        thread_index: usize,
-    ) -> Result<usize, SchedulerError> {
-        if batches.ids[thread_index].is_empty() {
-            return Ok(0);
-        }
-
-        let (ids, transactions, max_ages, total_cus) = batches.take_batch(thread_index);
-
-        let batch_id = self
-            .in_flight_tracker
-            .track_batch(ids.len(), total_cus, thread_index);
-
-        let num_scheduled = ids.len();
-        let work = ConsumeWork {
-            batch_id,
-            ids,
-            transactions,
-            max_ages,
-        };
-        self.consume_work_senders[thread_index]
-            .send(work)
-            .map_err(|_| SchedulerError::DisconnectedSendChannel("consume work sender"))?;
-
-        Ok(num_scheduled)
-    }
-
-    /// Given the schedulable `thread_set`, select the thread with the least amount
-    /// of work queued up.
-    /// Currently, "work" is just defined as the number of transactions.
-    ///
-    /// If the `chain_thread` is available, this thread will be selected, regardless of
-    /// load-balancing.
-    ///
-    /// Panics if the `thread_set` is empty. This should never happen, see comment
-    /// on `ThreadAwareAccountLocks::try_lock_accounts`.
-    fn select_thread(
-        thread_set: ThreadSet,
-        batch_cus_per_thread: &[u64],
-        in_flight_cus_per_thread: &[u64],
-        batches_per_thread: &[Vec<Tx>],
-        in_flight_per_thread: &[usize],
-    ) -> ThreadId {
-        thread_set
-            .contained_threads_iter()
-            .map(|thread_id| {
-                (
-                    thread_id,
-                    batch_cus_per_thread[thread_id] + in_flight_cus_per_thread[thread_id],
-                    batches_per_thread[thread_id].len() + in_flight_per_thread[thread_id],
-                )
-            })
-            .min_by(|a, b| a.1.cmp(&b.1).then_with(|| a.2.cmp(&b.2)))
-            .map(|(thread_id, _, _)| thread_id)
-            .unwrap()
-    }
-
-    /// Gets accessed accounts (resources) for use in `PrioGraph`.
-    fn get_transaction_account_access(
-        transaction: &SanitizedTransactionTTL<impl SVMMessage>,
-    ) -> impl Iterator<Item = (Pubkey, AccessKind)> + '_ {
-        let message = &transaction.transaction;
-        message
-            .account_keys()
-            .iter()
-            .enumerate()
-            .map(|(index, key)| {
-                if message.is_writable(index) {
-                    (*key, AccessKind::Write)
-                } else {
-                    (*key, AccessKind::Read)
-                }
-            })
-    }
-}
-
-/// Metrics from scheduling transactions.
-#[derive(Debug, PartialEq, Eq)]
-pub(crate) struct SchedulingSummary {
-    /// Number of transactions scheduled.
-    pub num_scheduled: usize,
-    /// Number of transactions that were not scheduled due to conflicts.
-    pub num_unschedulable: usize,
-    /// Number of transactions that were dropped due to filter.
-    pub num_filtered_out: usize,
-    /// Time spent filtering transactions
-    pub filter_time_us: u64,
-}
-
-struct Batches<Tx> {
-    ids: Vec<Vec<TransactionId>>,
-    transactions: Vec<Vec<Tx>>,
-    max_ages: Vec<Vec<MaxAge>>,
-    total_cus: Vec<u64>,
-}
-
-impl<Tx> Batches<Tx> {
-    fn new(num_threads: usize) -> Self {
-        Self {
-            ids: vec![Vec::with_capacity(TARGET_NUM_TRANSACTIONS_PER_BATCH); num_threads],
-
-            transactions: (0..num_threads)
-                .map(|_| Vec::with_capacity(TARGET_NUM_TRANSACTIONS_PER_BATCH))
-                .collect(),
-            max_ages: vec![Vec::with_capacity(TARGET_NUM_TRANSACTIONS_PER_BATCH); num_threads],
-            total_cus: vec![0; num_threads],
-        }
-    }
-
-    fn take_batch(
-        &mut self,
-        thread_id: ThreadId,
-    ) -> (Vec<TransactionId>, Vec<Tx>, Vec<MaxAge>, u64) {
-        (
-            core::mem::replace(
-                &mut self.ids[thread_id],
-                Vec::with_capacity(TARGET_NUM_TRANSACTIONS_PER_BATCH),
-            ),
-            core::mem::replace(
-                &mut self.transactions[thread_id],
-                Vec::with_capacity(TARGET_NUM_TRANSACTIONS_PER_BATCH),
-            ),
-            core::mem::replace(
-                &mut self.max_ages[thread_id],
-                Vec::with_capacity(TARGET_NUM_TRANSACTIONS_PER_BATCH),
-            ),
-            core::mem::replace(&mut self.total_cus[thread_id], 0),
-        )
-    }
-}
-
-/// A transaction has been scheduled to a thread.
-struct TransactionSchedulingInfo<Tx> {
-    thread_id: ThreadId,
-    transaction: Tx,
-    max_age: MaxAge,
-    cost: u64,
-}
-
-/// Error type for reasons a transaction could not be scheduled.
-enum TransactionSchedulingError {
-    /// Transaction was filtered out before locking.
-    Filtered,
-    /// Transaction cannot be scheduled due to conflicts, or
-    /// higher priority conflicting transactions are unschedulable.
-    UnschedulableConflicts,
-}
-
-fn try_schedule_transaction<Tx: TransactionWithMeta>(
-    transaction_state: &mut TransactionState<Tx>,
-    pre_lock_filter: impl Fn(&Tx) -> bool,
-    blocking_locks: &mut ReadWriteAccountSet,
-    account_locks: &mut ThreadAwareAccountLocks,
-    num_threads: usize,
-    thread_selector: impl Fn(ThreadSet) -> ThreadId,
-) -> Result<TransactionSchedulingInfo<Tx>, TransactionSchedulingError> {
-    let transaction = &transaction_state.transaction_ttl().transaction;
-    if !pre_lock_filter(transaction) {
-        return Err(TransactionSchedulingError::Filtered);
-    }
-
-    // Check if this transaction conflicts with any blocked transactions
-    if !blocking_locks.check_locks(transaction) {
-        blocking_locks.take_locks(transaction);
-        return Err(TransactionSchedulingError::UnschedulableConflicts);
-    }
-
-    // Schedule the transaction if it can be.
-    let account_keys = transaction.account_keys();
-    let write_account_locks = account_keys
-        .iter()
-        .enumerate()
-        .filter_map(|(index, key)| transaction.is_writable(index).then_some(key));
-    let read_account_locks = account_keys
-        .iter()
-        .enumerate()
-        .filter_map(|(index, key)| (!transaction.is_writable(index)).then_some(key));
-
-    let Some(thread_id) = account_locks.try_lock_accounts(
-        write_account_locks,
-        read_account_locks,
-        ThreadSet::any(num_threads),
-        thread_selector,
-    ) else {
-        blocking_locks.take_locks(transaction);
-        return Err(TransactionSchedulingError::UnschedulableConflicts);
-    };
-
-    let sanitized_transaction_ttl = transaction_state.transition_to_pending();
-    let cost = transaction_state.cost();
-
-    Ok(TransactionSchedulingInfo {
-        thread_id,
-        transaction: sanitized_transaction_ttl.transaction,
-        max_age: sanitized_transaction_ttl.max_age,
-        cost,
-    })
 }
 
 #[cfg(test)]
@@ -607,6 +316,7 @@ mod tests {
         crate::banking_stage::{
             consumer::TARGET_NUM_TRANSACTIONS_PER_BATCH,
             immutable_deserialized_packet::ImmutableDeserializedPacket,
+            scheduler_messages::{MaxAge, TransactionId},
             transaction_scheduler::transaction_state_container::TransactionStateContainer,
         },
         crossbeam_channel::{unbounded, Receiver},
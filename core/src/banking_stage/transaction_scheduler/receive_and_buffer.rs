@@ -1,6 +1,7 @@
 use {
     super::{
         scheduler_metrics::{SchedulerCountMetrics, SchedulerTimingMetrics},
+        transaction_state::TransactionBundle,
         transaction_state_container::StateContainer,
     },
     crate::banking_stage::{
@@ -16,6 +17,8 @@ use {
     solana_accounts_db::account_locks::validate_account_locks,
     solana_cost_model::cost_model::CostModel,
     solana_measure::measure_us,
+    solana_metrics::transaction_tracing,
+    solana_perf::packet::PacketBatch,
     solana_runtime::{bank::Bank, bank_forks::BankForks},
     solana_runtime_transaction::{
         runtime_transaction::RuntimeTransaction, transaction_meta::StaticMeta,
@@ -29,7 +32,10 @@ use {
         transaction::SanitizedTransaction,
     },
     solana_svm::transaction_error_metrics::TransactionErrorMetrics,
-    std::sync::{Arc, RwLock},
+    std::{
+        collections::HashSet,
+        sync::{Arc, RwLock},
+    },
 };
 
 pub(crate) trait ReceiveAndBuffer {
@@ -44,6 +50,15 @@ pub(crate) trait ReceiveAndBuffer {
         count_metrics: &mut SchedulerCountMetrics,
         decision: &BufferedPacketsDecision,
     ) -> bool;
+
+    /// Deserialize and sanitize the packets of a bundle. `None` is returned
+    /// if any of its transactions fail sanitization, or if a transaction is
+    /// repeated. The transactions may conflict with each other, as they are
+    /// executed sequentially.
+    fn sanitize_bundle(
+        &self,
+        packet_batch: &PacketBatch,
+    ) -> Option<TransactionBundle<Self::Transaction>>;
 }
 
 pub(crate) struct SanitizedTransactionReceiveAndBuffer {
@@ -128,6 +143,72 @@ impl ReceiveAndBuffer for SanitizedTransactionReceiveAndBuffer {
 
         true
     }
+
+    fn sanitize_bundle(
+        &self,
+        packet_batch: &PacketBatch,
+    ) -> Option<TransactionBundle<Self::Transaction>> {
+        let (root_bank, working_bank) = {
+            let bank_forks = self.bank_forks.read().unwrap();
+            let root_bank = bank_forks.root_bank();
+            let working_bank = bank_forks.working_bank();
+            (root_bank, working_bank)
+        };
+        let alt_resolved_slot = root_bank.slot();
+        let sanitized_epoch = root_bank.epoch();
+        let transaction_account_lock_limit = working_bank.get_transaction_account_lock_limit();
+        let vote_only = working_bank.vote_only_bank();
+
+        let mut transactions = Vec::with_capacity(packet_batch.len());
+        let mut max_ages = Vec::with_capacity(packet_batch.len());
+        let mut signatures = HashSet::with_capacity(packet_batch.len());
+        let mut message_hashes = HashSet::with_capacity(packet_batch.len());
+        let mut cost: u64 = 0;
+        for packet in packet_batch.iter() {
+            if packet.meta().discard() {
+                return None;
+            }
+            let packet = ImmutableDeserializedPacket::new(packet.clone()).ok()?;
+            packet.check_excessive_precompiles().ok()?;
+            let (transaction, deactivation_slot) = packet.build_sanitized_transaction(
+                vote_only,
+                root_bank.as_ref(),
+                root_bank.get_reserved_account_keys(),
+            )?;
+            // A transaction repeated within the bundle would be committed twice
+            if !signatures.insert(*transaction.signature())
+                || !message_hashes.insert(*transaction.message_hash())
+            {
+                return None;
+            }
+            validate_account_locks(
+                transaction.message().account_keys(),
+                transaction_account_lock_limit,
+            )
+            .ok()?;
+            let fee_budget_limits: FeeBudgetLimits = transaction
+                .compute_budget_instruction_details()
+                .sanitize_and_convert_to_compute_budget_limits(&working_bank.feature_set)
+                .ok()?
+                .into();
+
+            let (_priority, transaction_cost) =
+                calculate_priority_and_cost(&transaction, &fee_budget_limits, &working_bank);
+            saturating_add_assign!(cost, transaction_cost);
+            max_ages.push(calculate_max_age(
+                sanitized_epoch,
+                deactivation_slot,
+                alt_resolved_slot,
+            ));
+            transactions.push(transaction);
+        }
+
+        (!transactions.is_empty()).then_some(TransactionBundle {
+            transactions,
+            max_ages,
+            cost,
+        })
+    }
 }
 
 impl SanitizedTransactionReceiveAndBuffer {
@@ -220,6 +301,17 @@ impl SanitizedTransactionReceiveAndBuffer {
                 &mut error_counts,
             );
             let post_lock_validation_count = transactions.len();
+            if transaction_tracing::is_enabled() {
+                for (transaction, check_result) in transactions.iter().zip(&check_results) {
+                    if let Err(err) = check_result {
+                        transaction_tracing::record_event(
+                            transaction.signature().as_ref(),
+                            "receive_and_buffer",
+                            Some(&err.to_string()),
+                        );
+                    }
+                }
+            }
 
             let mut post_transaction_check_count: usize = 0;
             let mut num_dropped_on_capacity: usize = 0;
@@ -0,0 +1,63 @@
+use {
+    super::{
+        scheduler_error::SchedulerError, transaction_state::TransactionBundle,
+        transaction_state_container::StateContainer,
+    },
+    solana_runtime_transaction::transaction_with_meta::TransactionWithMeta,
+};
+
+/// A scheduling policy for the central scheduler. Implementations decide which
+/// transactions from the `StateContainer` are sent to the worker threads, and
+/// in which order.
+pub(crate) trait Scheduler<Tx: TransactionWithMeta> {
+    /// Schedule transactions from the given `StateContainer` to be
+    /// consumed by the worker threads. Returns summary of scheduling, or an
+    /// error.
+    /// `pre_graph_filter` is used to filter out transactions that should be
+    /// skipped and dropped before they are considered for scheduling. This fn
+    /// should set `false` for transactions that should be dropped, and `true`
+    /// otherwise.
+    /// `pre_lock_filter` is used to filter out transactions immediately before
+    /// locks are checked and taken. This fn should return `true` for
+    /// transactions that should be scheduled, and `false` otherwise.
+    fn schedule<S: StateContainer<Tx>>(
+        &mut self,
+        container: &mut S,
+        pre_graph_filter: impl Fn(&[&Tx], &mut [bool]),
+        pre_lock_filter: impl Fn(&Tx) -> bool,
+    ) -> Result<SchedulingSummary, SchedulerError>;
+
+    /// Receive completed batches of transactions without blocking.
+    /// Returns (num_transactions, num_retryable_transactions) on success.
+    fn receive_completed(
+        &mut self,
+        container: &mut impl StateContainer<Tx>,
+    ) -> Result<(usize, usize), SchedulerError>;
+
+    /// Try to schedule a bundle onto a single worker thread, locking the
+    /// accounts of all of its transactions. Bundles are not subject to the
+    /// scheduling policy, they are scheduled as soon as their accounts can be
+    /// locked. Returns the bundle if it cannot be scheduled yet because of
+    /// conflicts with in-flight transactions.
+    fn schedule_bundle(
+        &mut self,
+        bundle: TransactionBundle<Tx>,
+    ) -> Result<Option<TransactionBundle<Tx>>, SchedulerError>;
+
+    /// Take the bundles which the workers returned as retryable, received by
+    /// `receive_completed`.
+    fn take_retryable_bundles(&mut self) -> Vec<TransactionBundle<Tx>>;
+}
+
+/// Metrics from scheduling transactions.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct SchedulingSummary {
+    /// Number of transactions scheduled.
+    pub num_scheduled: usize,
+    /// Number of transactions that were not scheduled due to conflicts.
+    pub num_unschedulable: usize,
+    /// Number of transactions that were dropped due to filter.
+    pub num_filtered_out: usize,
+    /// Time spent filtering transactions
+    pub filter_time_us: u64,
+}
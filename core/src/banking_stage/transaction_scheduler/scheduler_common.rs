@@ -0,0 +1,490 @@
+//! State and helpers shared by the `Scheduler` implementations: tracking of
+//! in-flight work and account locks per thread, and communication with the
+//! worker threads.
+
+use {
+    super::{
+        in_flight_tracker::InFlightTracker,
+        scheduler_error::SchedulerError,
+        thread_aware_account_locks::{ThreadAwareAccountLocks, ThreadId, ThreadSet},
+        transaction_state::{SanitizedTransactionTTL, TransactionBundle, TransactionState},
+        transaction_state_container::StateContainer,
+    },
+    crate::banking_stage::{
+        consumer::TARGET_NUM_TRANSACTIONS_PER_BATCH,
+        read_write_account_set::ReadWriteAccountSet,
+        scheduler_messages::{
+            ConsumeWork, FinishedConsumeWork, MaxAge, TransactionBatchId, TransactionId,
+        },
+    },
+    crossbeam_channel::{Receiver, Sender, TryRecvError},
+    itertools::izip,
+    solana_runtime_transaction::transaction_with_meta::TransactionWithMeta,
+    solana_sdk::saturating_add_assign,
+    std::collections::HashMap,
+};
+
+pub(crate) struct SchedulingCommon<Tx> {
+    pub(crate) in_flight_tracker: InFlightTracker,
+    pub(crate) account_locks: ThreadAwareAccountLocks,
+    consume_work_senders: Vec<Sender<ConsumeWork<Tx>>>,
+    finished_consume_work_receiver: Receiver<FinishedConsumeWork<Tx>>,
+    /// Costs of the bundles which are in flight.
+    in_flight_bundle_costs: HashMap<TransactionBatchId, u64>,
+    /// Bundles which were returned by the workers as retryable.
+    retryable_bundles: Vec<TransactionBundle<Tx>>,
+}
+
+impl<Tx: TransactionWithMeta> SchedulingCommon<Tx> {
+    pub(crate) fn new(
+        consume_work_senders: Vec<Sender<ConsumeWork<Tx>>>,
+        finished_consume_work_receiver: Receiver<FinishedConsumeWork<Tx>>,
+    ) -> Self {
+        let num_threads = consume_work_senders.len();
+        Self {
+            in_flight_tracker: InFlightTracker::new(num_threads),
+            account_locks: ThreadAwareAccountLocks::new(num_threads),
+            consume_work_senders,
+            finished_consume_work_receiver,
+            in_flight_bundle_costs: HashMap::new(),
+            retryable_bundles: Vec::new(),
+        }
+    }
+
+    pub(crate) fn num_threads(&self) -> usize {
+        self.consume_work_senders.len()
+    }
+
+    /// Returns the threads which have less than `max_cu_per_thread` compute
+    /// units in flight.
+    pub(crate) fn schedulable_threads(&self, max_cu_per_thread: u64) -> ThreadSet {
+        let num_threads = self.num_threads();
+        let mut schedulable_threads = ThreadSet::any(num_threads);
+        for thread_id in 0..num_threads {
+            if self.in_flight_tracker.cus_in_flight_per_thread()[thread_id] >= max_cu_per_thread {
+                schedulable_threads.remove(thread_id);
+            }
+        }
+        schedulable_threads
+    }
+
+    /// Receive completed batches of transactions without blocking.
+    /// Returns (num_transactions, num_retryable_transactions) on success.
+    pub(crate) fn receive_completed(
+        &mut self,
+        container: &mut impl StateContainer<Tx>,
+    ) -> Result<(usize, usize), SchedulerError> {
+        let mut total_num_transactions: usize = 0;
+        let mut total_num_retryable: usize = 0;
+        loop {
+            let (num_transactions, num_retryable) = self.try_receive_completed(container)?;
+            if num_transactions == 0 {
+                break;
+            }
+            saturating_add_assign!(total_num_transactions, num_transactions);
+            saturating_add_assign!(total_num_retryable, num_retryable);
+        }
+        Ok((total_num_transactions, total_num_retryable))
+    }
+
+    /// Receive completed batches of transactions.
+    /// Returns `Ok((num_transactions, num_retryable))` if a batch was received, `Ok((0, 0))` if no batch was received.
+    fn try_receive_completed(
+        &mut self,
+        container: &mut impl StateContainer<Tx>,
+    ) -> Result<(usize, usize), SchedulerError> {
+        match self.finished_consume_work_receiver.try_recv() {
+            Ok(FinishedConsumeWork {
+                work:
+                    ConsumeWork {
+                        batch_id,
+                        ids,
+                        transactions,
+                        max_ages,
+                        is_bundle,
+                    },
+                retryable_indexes,
+            }) => {
+                if is_bundle {
+                    return Ok(self.complete_bundle(
+                        batch_id,
+                        transactions,
+                        max_ages,
+                        retryable_indexes,
+                    ));
+                }
+
+                let num_transactions = ids.len();
+                let num_retryable = retryable_indexes.len();
+
+                // Free the locks
+                self.complete_batch(batch_id, &transactions);
+
+                // Retryable transactions should be inserted back into the container
+                let mut retryable_iter = retryable_indexes.into_iter().peekable();
+                for (index, (id, transaction, max_age)) in
+                    izip!(ids, transactions, max_ages).enumerate()
+                {
+                    if let Some(retryable_index) = retryable_iter.peek() {
+                        if *retryable_index == index {
+                            container.retry_transaction(
+                                id,
+                                SanitizedTransactionTTL {
+                                    transaction,
+                                    max_age,
+                                },
+                            );
+                            retryable_iter.next();
+                            continue;
+                        }
+                    }
+                    container.remove_by_id(id);
+                }
+
+                Ok((num_transactions, num_retryable))
+            }
+            Err(TryRecvError::Empty) => Ok((0, 0)),
+            Err(TryRecvError::Disconnected) => Err(SchedulerError::DisconnectedRecvChannel(
+                "finished consume work",
+            )),
+        }
+    }
+
+    /// Mark a bundle as completed, freeing its locks. A retryable bundle is
+    /// kept to be scheduled again.
+    /// Returns `(num_transactions, num_retryable)`.
+    fn complete_bundle(
+        &mut self,
+        batch_id: TransactionBatchId,
+        transactions: Vec<Tx>,
+        max_ages: Vec<MaxAge>,
+        retryable_indexes: Vec<usize>,
+    ) -> (usize, usize) {
+        let num_transactions = transactions.len();
+        let num_retryable = retryable_indexes.len();
+        let cost = self
+            .in_flight_bundle_costs
+            .remove(&batch_id)
+            .unwrap_or_default();
+
+        self.complete_batch(batch_id, &transactions);
+
+        // A bundle is processed atomically, so either all of its transactions
+        // are retryable or none are.
+        debug_assert!(num_retryable == 0 || num_retryable == num_transactions);
+        if num_retryable != 0 {
+            self.retryable_bundles.push(TransactionBundle {
+                transactions,
+                max_ages,
+                cost,
+            });
+        }
+
+        (num_transactions, num_retryable)
+    }
+
+    /// Take the bundles which were returned by the workers as retryable.
+    pub(crate) fn take_retryable_bundles(&mut self) -> Vec<TransactionBundle<Tx>> {
+        core::mem::take(&mut self.retryable_bundles)
+    }
+
+    /// Try to schedule a bundle onto a single thread, locking the accounts of
+    /// all of its transactions. Returns the bundle if it cannot be scheduled
+    /// because of conflicts with in-flight transactions.
+    pub(crate) fn schedule_bundle(
+        &mut self,
+        bundle: TransactionBundle<Tx>,
+    ) -> Result<Option<TransactionBundle<Tx>>, SchedulerError> {
+        let mut bundle_thread_id = None;
+        for (index, transaction) in bundle.transactions.iter().enumerate() {
+            let account_keys = transaction.account_keys();
+            let write_account_locks = account_keys
+                .iter()
+                .enumerate()
+                .filter_map(|(index, key)| transaction.is_writable(index).then_some(key));
+            let read_account_locks = account_keys
+                .iter()
+                .enumerate()
+                .filter_map(|(index, key)| (!transaction.is_writable(index)).then_some(key));
+
+            // Once the first transaction is locked, the rest of the bundle must
+            // follow it onto the same thread.
+            let schedulable_threads = bundle_thread_id
+                .map(ThreadSet::only)
+                .unwrap_or_else(|| ThreadSet::any(self.num_threads()));
+            let in_flight_cus_per_thread = self.in_flight_tracker.cus_in_flight_per_thread();
+            let Some(thread_id) = self.account_locks.try_lock_accounts(
+                write_account_locks,
+                read_account_locks,
+                schedulable_threads,
+                |thread_set| {
+                    thread_set
+                        .contained_threads_iter()
+                        .min_by_key(|thread_id| in_flight_cus_per_thread[*thread_id])
+                        .unwrap()
+                },
+            ) else {
+                if let Some(thread_id) = bundle_thread_id {
+                    self.unlock_transactions(&bundle.transactions[..index], thread_id);
+                }
+                return Ok(Some(bundle));
+            };
+            bundle_thread_id = Some(thread_id);
+        }
+        let Some(thread_id) = bundle_thread_id else {
+            // An empty bundle has nothing to schedule
+            return Ok(None);
+        };
+
+        let TransactionBundle {
+            transactions,
+            max_ages,
+            cost,
+        } = bundle;
+        let batch_id = self
+            .in_flight_tracker
+            .track_batch(transactions.len(), cost, thread_id);
+        self.in_flight_bundle_costs.insert(batch_id, cost);
+        let work = ConsumeWork {
+            batch_id,
+            ids: vec![],
+            transactions,
+            max_ages,
+            is_bundle: true,
+        };
+        self.consume_work_senders[thread_id]
+            .send(work)
+            .map_err(|_| SchedulerError::DisconnectedSendChannel("consume work sender"))?;
+
+        Ok(None)
+    }
+
+    /// Mark a given `TransactionBatchId` as completed.
+    /// This will update the internal tracking, including account locks.
+    fn complete_batch(&mut self, batch_id: TransactionBatchId, transactions: &[Tx]) {
+        let thread_id = self.in_flight_tracker.complete_batch(batch_id);
+        self.unlock_transactions(transactions, thread_id);
+    }
+
+    /// Release the account locks held by `transactions` on `thread_id`.
+    fn unlock_transactions(&mut self, transactions: &[Tx], thread_id: ThreadId) {
+        for transaction in transactions {
+            let account_keys = transaction.account_keys();
+            let write_account_locks = account_keys
+                .iter()
+                .enumerate()
+                .filter_map(|(index, key)| transaction.is_writable(index).then_some(key));
+            let read_account_locks = account_keys
+                .iter()
+                .enumerate()
+                .filter_map(|(index, key)| (!transaction.is_writable(index)).then_some(key));
+            self.account_locks
+                .unlock_accounts(write_account_locks, read_account_locks, thread_id);
+        }
+    }
+
+    /// Send all batches of transactions to the worker threads.
+    /// Returns the number of transactions sent.
+    pub(crate) fn send_batches(
+        &mut self,
+        batches: &mut Batches<Tx>,
+    ) -> Result<usize, SchedulerError> {
+        (0..self.num_threads())
+            .map(|thread_index| self.send_batch(batches, thread_index))
+            .sum()
+    }
+
+    /// Send a batch of transactions to the given thread's `ConsumeWork` channel.
+    /// Returns the number of transactions sent.
+    pub(crate) fn send_batch(
+        &mut self,
+        batches: &mut Batches<Tx>,
+        thread_index: usize,
+    ) -> Result<usize, SchedulerError> {
+        if batches.ids[thread_index].is_empty() {
+            return Ok(0);
+        }
+
+        let (ids, transactions, max_ages, total_cus) = batches.take_batch(thread_index);
+
+        let batch_id = self
+            .in_flight_tracker
+            .track_batch(ids.len(), total_cus, thread_index);
+
+        let num_scheduled = ids.len();
+        let work = ConsumeWork {
+            batch_id,
+            ids,
+            transactions,
+            max_ages,
+            is_bundle: false,
+        };
+        self.consume_work_senders[thread_index]
+            .send(work)
+            .map_err(|_| SchedulerError::DisconnectedSendChannel("consume work sender"))?;
+
+        Ok(num_scheduled)
+    }
+}
+
+pub(crate) struct Batches<Tx> {
+    pub(crate) ids: Vec<Vec<TransactionId>>,
+    pub(crate) transactions: Vec<Vec<Tx>>,
+    pub(crate) max_ages: Vec<Vec<MaxAge>>,
+    pub(crate) total_cus: Vec<u64>,
+}
+
+impl<Tx> Batches<Tx> {
+    pub(crate) fn new(num_threads: usize) -> Self {
+        Self {
+            ids: vec![Vec::with_capacity(TARGET_NUM_TRANSACTIONS_PER_BATCH); num_threads],
+
+            transactions: (0..num_threads)
+                .map(|_| Vec::with_capacity(TARGET_NUM_TRANSACTIONS_PER_BATCH))
+                .collect(),
+            max_ages: vec![Vec::with_capacity(TARGET_NUM_TRANSACTIONS_PER_BATCH); num_threads],
+            total_cus: vec![0; num_threads],
+        }
+    }
+
+    /// Add a scheduled transaction to the batch of its thread.
+    pub(crate) fn push(
+        &mut self,
+        id: TransactionId,
+        TransactionSchedulingInfo {
+            thread_id,
+            transaction,
+            max_age,
+            cost,
+        }: TransactionSchedulingInfo<Tx>,
+    ) {
+        self.transactions[thread_id].push(transaction);
+        self.ids[thread_id].push(id);
+        self.max_ages[thread_id].push(max_age);
+        saturating_add_assign!(self.total_cus[thread_id], cost);
+    }
+
+    fn take_batch(
+        &mut self,
+        thread_id: ThreadId,
+    ) -> (Vec<TransactionId>, Vec<Tx>, Vec<MaxAge>, u64) {
+        (
+            core::mem::replace(
+                &mut self.ids[thread_id],
+                Vec::with_capacity(TARGET_NUM_TRANSACTIONS_PER_BATCH),
+            ),
+            core::mem::replace(
+                &mut self.transactions[thread_id],
+                Vec::with_capacity(TARGET_NUM_TRANSACTIONS_PER_BATCH),
+            ),
+            core::mem::replace(
+                &mut self.max_ages[thread_id],
+                Vec::with_capacity(TARGET_NUM_TRANSACTIONS_PER_BATCH),
+            ),
+            core::mem::replace(&mut self.total_cus[thread_id], 0),
+        )
+    }
+}
+
+/// A transaction has been scheduled to a thread.
+pub(crate) struct TransactionSchedulingInfo<Tx> {
+    pub(crate) thread_id: ThreadId,
+    pub(crate) transaction: Tx,
+    pub(crate) max_age: MaxAge,
+    pub(crate) cost: u64,
+}
+
+/// Error type for reasons a transaction could not be scheduled.
+pub(crate) enum TransactionSchedulingError {
+    /// Transaction was filtered out before locking.
+    Filtered,
+    /// Transaction cannot be scheduled due to conflicts, or
+    /// higher priority conflicting transactions are unschedulable.
+    UnschedulableConflicts,
+}
+
+/// Given the schedulable `thread_set`, select the thread with the least amount
+/// of work queued up.
+/// Currently, "work" is just defined as the number of transactions.
+///
+/// If the `chain_thread` is available, this thread will be selected, regardless of
+/// load-balancing.
+///
+/// Panics if the `thread_set` is empty. This should never happen, see comment
+/// on `ThreadAwareAccountLocks::try_lock_accounts`.
+pub(crate) fn select_thread<Tx>(
+    thread_set: ThreadSet,
+    batch_cus_per_thread: &[u64],
+    in_flight_cus_per_thread: &[u64],
+    batches_per_thread: &[Vec<Tx>],
+    in_flight_per_thread: &[usize],
+) -> ThreadId {
+    thread_set
+        .contained_threads_iter()
+        .map(|thread_id| {
+            (
+                thread_id,
+                batch_cus_per_thread[thread_id] + in_flight_cus_per_thread[thread_id],
+                batches_per_thread[thread_id].len() + in_flight_per_thread[thread_id],
+            )
+        })
+        .min_by(|a, b| a.1.cmp(&b.1).then_with(|| a.2.cmp(&b.2)))
+        .map(|(thread_id, _, _)| thread_id)
+        .unwrap()
+}
+
+/// Try to lock the accounts of a transaction onto one of the `schedulable_threads`,
+/// and transition it to pending if successful.
+/// Transactions which cannot be scheduled have their locks added to
+/// `blocking_locks`, so that lower priority transactions which conflict with them
+/// are not scheduled before them.
+pub(crate) fn try_schedule_transaction<Tx: TransactionWithMeta>(
+    transaction_state: &mut TransactionState<Tx>,
+    pre_lock_filter: impl Fn(&Tx) -> bool,
+    blocking_locks: &mut ReadWriteAccountSet,
+    account_locks: &mut ThreadAwareAccountLocks,
+    schedulable_threads: ThreadSet,
+    thread_selector: impl Fn(ThreadSet) -> ThreadId,
+) -> Result<TransactionSchedulingInfo<Tx>, TransactionSchedulingError> {
+    let transaction = &transaction_state.transaction_ttl().transaction;
+    if !pre_lock_filter(transaction) {
+        return Err(TransactionSchedulingError::Filtered);
+    }
+
+    // Check if this transaction conflicts with any blocked transactions
+    if !blocking_locks.check_locks(transaction) {
+        blocking_locks.take_locks(transaction);
+        return Err(TransactionSchedulingError::UnschedulableConflicts);
+    }
+
+    // Schedule the transaction if it can be.
+    let account_keys = transaction.account_keys();
+    let write_account_locks = account_keys
+        .iter()
+        .enumerate()
+        .filter_map(|(index, key)| transaction.is_writable(index).then_some(key));
+    let read_account_locks = account_keys
+        .iter()
+        .enumerate()
+        .filter_map(|(index, key)| (!transaction.is_writable(index)).then_some(key));
+
+    let Some(thread_id) = account_locks.try_lock_accounts(
+        write_account_locks,
+        read_account_locks,
+        schedulable_threads,
+        thread_selector,
+    ) else {
+        blocking_locks.take_locks(transaction);
+        return Err(TransactionSchedulingError::UnschedulableConflicts);
+    };
+
+    let sanitized_transaction_ttl = transaction_state.transition_to_pending();
+    let cost = transaction_state.cost();
+
+    Ok(TransactionSchedulingInfo {
+        thread_id,
+        transaction: sanitized_transaction_ttl.transaction,
+        max_age: sanitized_transaction_ttl.max_age,
+        cost,
+    })
+}
@@ -3,12 +3,13 @@
 
 use {
     super::{
-        prio_graph_scheduler::PrioGraphScheduler,
         receive_and_buffer::ReceiveAndBuffer,
+        scheduler::Scheduler,
         scheduler_error::SchedulerError,
         scheduler_metrics::{
             SchedulerCountMetrics, SchedulerLeaderDetectionMetrics, SchedulerTimingMetrics,
         },
+        transaction_state::TransactionBundle,
     },
     crate::banking_stage::{
         consume_worker::ConsumeWorkerMetrics,
@@ -16,9 +17,11 @@ use {
         decision_maker::{BufferedPacketsDecision, DecisionMaker},
         forwarder::Forwarder,
         transaction_scheduler::transaction_state_container::StateContainer,
-        ForwardOption, LikeClusterInfo, TOTAL_BUFFERED_PACKETS,
+        BundleReceiver, ForwardOption, LikeClusterInfo, TOTAL_BUFFERED_PACKETS,
     },
+    crossbeam_channel::TryRecvError,
     solana_measure::measure_us,
+    solana_metrics::transaction_tracing,
     solana_runtime::{bank::Bank, bank_forks::BankForks},
     solana_sdk::{
         self,
@@ -27,13 +30,17 @@ use {
     },
     solana_svm::transaction_error_metrics::TransactionErrorMetrics,
     std::{
+        collections::VecDeque,
         sync::{Arc, RwLock},
         time::{Duration, Instant},
     },
 };
 
 /// Controls packet and transaction flow into scheduler, and scheduling execution.
-pub(crate) struct SchedulerController<C: LikeClusterInfo, R: ReceiveAndBuffer> {
+pub(crate) struct SchedulerController<C: LikeClusterInfo, R: ReceiveAndBuffer, S>
+where
+    S: Scheduler<R::Transaction>,
+{
     /// Decision maker for determining what should be done with transactions.
     decision_maker: DecisionMaker,
     receive_and_buffer: R,
@@ -42,7 +49,11 @@ pub(crate) struct SchedulerController<C: LikeClusterInfo, R: ReceiveAndBuffer> {
     /// Shared resource between `packet_receiver` and `scheduler`.
     container: R::Container,
     /// State for scheduling and communicating with worker threads.
-    scheduler: PrioGraphScheduler<R::Transaction>,
+    scheduler: S,
+    /// Bundle ingress, if bundles are accepted.
+    bundle_receiver: Option<BundleReceiver>,
+    /// Bundles waiting to be scheduled, in the order they were received.
+    bundles: VecDeque<TransactionBundle<R::Transaction>>,
     /// Metrics tracking time for leader bank detection.
     leader_detection_metrics: SchedulerLeaderDetectionMetrics,
     /// Metrics tracking counts on transactions in different states
@@ -57,12 +68,16 @@ pub(crate) struct SchedulerController<C: LikeClusterInfo, R: ReceiveAndBuffer> {
     forwarder: Option<Forwarder<C>>,
 }
 
-impl<C: LikeClusterInfo, R: ReceiveAndBuffer> SchedulerController<C, R> {
+impl<C: LikeClusterInfo, R: ReceiveAndBuffer, S> SchedulerController<C, R, S>
+where
+    S: Scheduler<R::Transaction>,
+{
     pub fn new(
         decision_maker: DecisionMaker,
         receive_and_buffer: R,
         bank_forks: Arc<RwLock<BankForks>>,
-        scheduler: PrioGraphScheduler<R::Transaction>,
+        scheduler: S,
+        bundle_receiver: Option<BundleReceiver>,
         worker_metrics: Vec<Arc<ConsumeWorkerMetrics>>,
         forwarder: Option<Forwarder<C>>,
     ) -> Self {
@@ -72,6 +87,8 @@ impl<C: LikeClusterInfo, R: ReceiveAndBuffer> SchedulerController<C, R> {
             bank_forks,
             container: R::Container::with_capacity(TOTAL_BUFFERED_PACKETS),
             scheduler,
+            bundle_receiver,
+            bundles: VecDeque::new(),
             leader_detection_metrics: SchedulerLeaderDetectionMetrics::default(),
             count_metrics: SchedulerCountMetrics::default(),
             timing_metrics: SchedulerTimingMetrics::default(),
@@ -137,6 +154,8 @@ impl<C: LikeClusterInfo, R: ReceiveAndBuffer> SchedulerController<C, R> {
         let forwarding_enabled = self.forwarder.is_some();
         match decision {
             BufferedPacketsDecision::Consume(bank_start) => {
+                // Bundles are scheduled ahead of the transactions in the container
+                self.schedule_bundles(&bank_start.working_bank)?;
                 let (scheduling_summary, schedule_time_us) = measure_us!(self.scheduler.schedule(
                     &mut self.container,
                     |txs, results| {
@@ -174,6 +193,8 @@ impl<C: LikeClusterInfo, R: ReceiveAndBuffer> SchedulerController<C, R> {
                 });
             }
             BufferedPacketsDecision::Forward => {
+                // Bundles are not forwarded, they are only consumed by this leader
+                self.clear_bundles();
                 if forwarding_enabled {
                     let (_, forward_time_us) = measure_us!(self.forward_packets(false));
                     self.timing_metrics.update(|timing_metrics| {
@@ -205,6 +226,50 @@ impl<C: LikeClusterInfo, R: ReceiveAndBuffer> SchedulerController<C, R> {
         Ok(())
     }
 
+    /// Schedule the buffered bundles, in order. Bundles which fail the age
+    /// and status checks are dropped, and bundles which conflict with
+    /// in-flight transactions are kept for the next attempt.
+    fn schedule_bundles(&mut self, bank: &Bank) -> Result<(), SchedulerError> {
+        let mut num_bundles_scheduled: usize = 0;
+        let mut num_bundles_dropped: usize = 0;
+        let mut error_counters = TransactionErrorMetrics::default();
+        for _ in 0..self.bundles.len() {
+            let bundle = self.bundles.pop_front().expect("bundle must exist");
+            let transactions: Vec<_> = bundle.transactions.iter().collect();
+            let lock_results = vec![Ok(()); transactions.len()];
+            let check_results = bank.check_transactions::<R::Transaction>(
+                &transactions,
+                &lock_results,
+                MAX_PROCESSING_AGE,
+                &mut error_counters,
+            );
+            if check_results.iter().any(|result| result.is_err()) {
+                saturating_add_assign!(num_bundles_dropped, 1);
+                continue;
+            }
+
+            match self.scheduler.schedule_bundle(bundle)? {
+                None => saturating_add_assign!(num_bundles_scheduled, 1),
+                Some(bundle) => self.bundles.push_back(bundle),
+            }
+        }
+
+        self.count_metrics.update(|count_metrics| {
+            saturating_add_assign!(count_metrics.num_bundles_scheduled, num_bundles_scheduled);
+            saturating_add_assign!(count_metrics.num_bundles_dropped, num_bundles_dropped);
+        });
+        Ok(())
+    }
+
+    /// Drops all buffered bundles.
+    fn clear_bundles(&mut self) {
+        let num_bundles_dropped = self.bundles.len();
+        self.bundles.clear();
+        self.count_metrics.update(|count_metrics| {
+            saturating_add_assign!(count_metrics.num_bundles_dropped, num_bundles_dropped);
+        });
+    }
+
     fn pre_graph_filter(
         transactions: &[&R::Transaction],
         results: &mut [bool],
@@ -225,9 +290,18 @@ impl<C: LikeClusterInfo, R: ReceiveAndBuffer> SchedulerController<C, R> {
             .zip(transactions)
             .zip(results.iter_mut())
         {
-            *result = check_result
-                .and_then(|_| Consumer::check_fee_payer_unlocked(bank, *tx, &mut error_counters))
-                .is_ok();
+            let check_result = check_result
+                .and_then(|_| Consumer::check_fee_payer_unlocked(bank, *tx, &mut error_counters));
+            if let Err(err) = &check_result {
+                if transaction_tracing::is_enabled() {
+                    transaction_tracing::record_event(
+                        tx.signature().as_ref(),
+                        "scheduler_filtered",
+                        Some(&err.to_string()),
+                    );
+                }
+            }
+            *result = check_result.is_ok();
         }
     }
 
@@ -384,7 +458,19 @@ impl<C: LikeClusterInfo, R: ReceiveAndBuffer> SchedulerController<C, R> {
             );
 
             for (result, id) in check_results.into_iter().zip(chunk.iter()) {
-                if result.is_err() {
+                if let Err(err) = result {
+                    if transaction_tracing::is_enabled() {
+                        let transaction = &self
+                            .container
+                            .get_transaction_ttl(id.id)
+                            .expect("transaction must exist")
+                            .transaction;
+                        transaction_tracing::record_event(
+                            transaction.signature().as_ref(),
+                            "scheduler_cleaned",
+                            Some(&err.to_string()),
+                        );
+                    }
                     saturating_add_assign!(num_dropped_on_age_and_status, 1);
                     self.container.remove_by_id(id.id);
                 } else {
@@ -405,6 +491,7 @@ impl<C: LikeClusterInfo, R: ReceiveAndBuffer> SchedulerController<C, R> {
     fn receive_completed(&mut self) -> Result<(), SchedulerError> {
         let ((num_transactions, num_retryable), receive_completed_time_us) =
             measure_us!(self.scheduler.receive_completed(&mut self.container)?);
+        self.bundles.extend(self.scheduler.take_retryable_bundles());
 
         self.count_metrics.update(|count_metrics| {
             saturating_add_assign!(count_metrics.num_finished, num_transactions);
@@ -420,8 +507,46 @@ impl<C: LikeClusterInfo, R: ReceiveAndBuffer> SchedulerController<C, R> {
         Ok(())
     }
 
+    /// Receive and sanitize bundles without blocking. Bundles are not
+    /// buffered if they could not be consumed by this node.
+    fn receive_bundles(&mut self, decision: &BufferedPacketsDecision) {
+        let Some(bundle_receiver) = &self.bundle_receiver else {
+            return;
+        };
+
+        let mut num_bundles_received: usize = 0;
+        let mut num_bundles_dropped: usize = 0;
+        loop {
+            match bundle_receiver.try_recv() {
+                Ok(packet_batch) => {
+                    saturating_add_assign!(num_bundles_received, 1);
+                    if matches!(decision, BufferedPacketsDecision::Forward) {
+                        saturating_add_assign!(num_bundles_dropped, 1);
+                        continue;
+                    }
+                    match self.receive_and_buffer.sanitize_bundle(&packet_batch) {
+                        Some(bundle) => self.bundles.push_back(bundle),
+                        None => saturating_add_assign!(num_bundles_dropped, 1),
+                    }
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    // Keep scheduling transactions without bundles
+                    self.bundle_receiver = None;
+                    break;
+                }
+            }
+        }
+
+        self.count_metrics.update(|count_metrics| {
+            saturating_add_assign!(count_metrics.num_bundles_received, num_bundles_received);
+            saturating_add_assign!(count_metrics.num_bundles_dropped, num_bundles_dropped);
+        });
+    }
+
     /// Returns whether the packet receiver is still connected.
     fn receive_and_buffer_packets(&mut self, decision: &BufferedPacketsDecision) -> bool {
+        self.receive_bundles(decision);
         self.receive_and_buffer.receive_and_buffer_packets(
             &mut self.container,
             &mut self.timing_metrics,
@@ -441,7 +566,10 @@ mod tests {
                 packet_deserializer::PacketDeserializer,
                 scheduler_messages::{ConsumeWork, FinishedConsumeWork, TransactionBatchId},
                 tests::create_slow_genesis_config,
-                transaction_scheduler::receive_and_buffer::SanitizedTransactionReceiveAndBuffer,
+                transaction_scheduler::{
+                    prio_graph_scheduler::PrioGraphScheduler,
+                    receive_and_buffer::SanitizedTransactionReceiveAndBuffer,
+                },
             },
             banking_trace::BankingPacketBatch,
             sigverify::SigverifyTracerPacketStats,
@@ -487,6 +615,7 @@ mod tests {
         _record_receiver: Receiver<Record>,
         poh_recorder: Arc<RwLock<PohRecorder>>,
         banking_packet_sender: Sender<Arc<(Vec<PacketBatch>, Option<SigverifyTracerPacketStats>)>>,
+        bundle_sender: Sender<PacketBatch>,
 
         consume_work_receivers:
             Vec<Receiver<ConsumeWork<RuntimeTransaction<SanitizedTransaction>>>>,
@@ -499,7 +628,11 @@ mod tests {
         num_threads: usize,
     ) -> (
         TestFrame,
-        SchedulerController<Arc<ClusterInfo>, SanitizedTransactionReceiveAndBuffer>,
+        SchedulerController<
+            Arc<ClusterInfo>,
+            SanitizedTransactionReceiveAndBuffer,
+            PrioGraphScheduler<RuntimeTransaction<SanitizedTransaction>>,
+        >,
     ) {
         let GenesisConfigInfo {
             mut genesis_config,
@@ -528,6 +661,7 @@ mod tests {
 
         let (banking_packet_sender, banking_packet_receiver) = unbounded();
         let packet_deserializer = PacketDeserializer::new(banking_packet_receiver);
+        let (bundle_sender, bundle_receiver) = unbounded();
 
         let (consume_work_senders, consume_work_receivers) = create_channels(num_threads);
         let (finished_consume_work_sender, finished_consume_work_receiver) = unbounded();
@@ -540,6 +674,7 @@ mod tests {
             _record_receiver: record_receiver,
             poh_recorder,
             banking_packet_sender,
+            bundle_sender,
             consume_work_receivers,
             finished_consume_work_sender,
         };
@@ -555,6 +690,7 @@ mod tests {
             receive_and_buffer,
             bank_forks,
             PrioGraphScheduler::new(consume_work_senders, finished_consume_work_receiver),
+            Some(bundle_receiver),
             vec![], // no actual workers with metrics to report, this can be empty
             None,
         );
@@ -604,6 +740,7 @@ mod tests {
         scheduler_controller: &mut SchedulerController<
             Arc<ClusterInfo>,
             SanitizedTransactionReceiveAndBuffer,
+            PrioGraphScheduler<RuntimeTransaction<SanitizedTransaction>>,
         >,
     ) {
         let decision = scheduler_controller
@@ -631,6 +768,7 @@ mod tests {
                     ids: vec![],
                     transactions: vec![],
                     max_ages: vec![],
+                    is_bundle: false,
                 },
                 retryable_indexes: vec![],
             })
@@ -961,4 +1099,174 @@ mod tests {
             .collect_vec();
         assert_eq!(message_hashes, vec![&tx1_hash]);
     }
+
+    #[test]
+    fn test_schedule_consume_bundle() {
+        let (test_frame, mut scheduler_controller) = create_test_frame(1);
+        let TestFrame {
+            bank,
+            mint_keypair,
+            poh_recorder,
+            bundle_sender,
+            consume_work_receivers,
+            finished_consume_work_sender,
+            ..
+        } = &test_frame;
+
+        poh_recorder
+            .write()
+            .unwrap()
+            .set_bank_for_test(bank.clone());
+
+        // Transactions within a bundle keep their order, regardless of priority.
+        let tx1 = create_and_fund_prioritized_transfer(
+            bank,
+            mint_keypair,
+            &Keypair::new(),
+            &Pubkey::new_unique(),
+            1,
+            2000,
+            bank.last_blockhash(),
+        );
+        let tx2 = create_and_fund_prioritized_transfer(
+            bank,
+            mint_keypair,
+            &Keypair::new(),
+            &Pubkey::new_unique(),
+            1,
+            1000,
+            bank.last_blockhash(),
+        );
+        let tx1_hash = tx1.message().hash();
+        let tx2_hash = tx2.message().hash();
+
+        let txs = vec![tx1, tx2];
+        bundle_sender
+            .send(to_packet_batches(&txs, NUM_PACKETS).remove(0))
+            .unwrap();
+
+        test_receive_then_schedule(&mut scheduler_controller);
+        let consume_work = consume_work_receivers[0].try_recv().unwrap();
+        assert!(consume_work.is_bundle);
+        assert!(consume_work.ids.is_empty());
+        let message_hashes = consume_work
+            .transactions
+            .iter()
+            .map(|tx| tx.message_hash())
+            .collect_vec();
+        assert_eq!(message_hashes, vec![&tx1_hash, &tx2_hash]);
+
+        // Complete the bundle as retryable - it should be rescheduled as a whole
+        finished_consume_work_sender
+            .send(FinishedConsumeWork {
+                work: consume_work,
+                retryable_indexes: vec![0, 1],
+            })
+            .unwrap();
+
+        test_receive_then_schedule(&mut scheduler_controller);
+        let consume_work = consume_work_receivers[0].try_recv().unwrap();
+        assert!(consume_work.is_bundle);
+        assert_eq!(consume_work.transactions.len(), 2);
+    }
+
+    #[test]
+    fn test_schedule_consume_conflicting_bundle() {
+        let (test_frame, mut scheduler_controller) = create_test_frame(2);
+        let TestFrame {
+            bank,
+            mint_keypair,
+            poh_recorder,
+            bundle_sender,
+            consume_work_receivers,
+            ..
+        } = &test_frame;
+
+        poh_recorder
+            .write()
+            .unwrap()
+            .set_bank_for_test(bank.clone());
+
+        // The bundle's transactions are executed sequentially, so they may
+        // conflict with each other. They are all scheduled on the same thread.
+        let pk = Pubkey::new_unique();
+        let txs = (0..2)
+            .map(|_| {
+                create_and_fund_prioritized_transfer(
+                    bank,
+                    mint_keypair,
+                    &Keypair::new(),
+                    &pk,
+                    1,
+                    1000,
+                    bank.last_blockhash(),
+                )
+            })
+            .collect_vec();
+        bundle_sender
+            .send(to_packet_batches(&txs, NUM_PACKETS).remove(0))
+            .unwrap();
+
+        test_receive_then_schedule(&mut scheduler_controller);
+        let consume_works = consume_work_receivers
+            .iter()
+            .filter_map(|receiver| receiver.try_recv().ok())
+            .collect_vec();
+        assert_eq!(consume_works.len(), 1);
+        assert!(consume_works[0].is_bundle);
+        assert_eq!(consume_works[0].transactions.len(), 2);
+    }
+
+    #[test]
+    fn test_drop_bundle_with_duplicate_transaction() {
+        let (test_frame, mut scheduler_controller) = create_test_frame(1);
+        let TestFrame {
+            bank,
+            mint_keypair,
+            poh_recorder,
+            bundle_sender,
+            consume_work_receivers,
+            ..
+        } = &test_frame;
+
+        poh_recorder
+            .write()
+            .unwrap()
+            .set_bank_for_test(bank.clone());
+
+        // The same transaction twice in a bundle would be committed twice, so
+        // the bundle is dropped.
+        let tx = create_and_fund_prioritized_transfer(
+            bank,
+            mint_keypair,
+            &Keypair::new(),
+            &Pubkey::new_unique(),
+            1,
+            1000,
+            bank.last_blockhash(),
+        );
+        let tx_hash = tx.message().hash();
+        let txs = vec![tx.clone(), tx.clone()];
+        bundle_sender
+            .send(to_packet_batches(&txs, NUM_PACKETS).remove(0))
+            .unwrap();
+
+        test_receive_then_schedule(&mut scheduler_controller);
+        assert!(consume_work_receivers[0].try_recv().is_err());
+
+        // The transaction on its own is scheduled
+        bundle_sender
+            .send(to_packet_batches(&[tx], NUM_PACKETS).remove(0))
+            .unwrap();
+
+        test_receive_then_schedule(&mut scheduler_controller);
+        let consume_work = consume_work_receivers[0].try_recv().unwrap();
+        assert!(consume_work.is_bundle);
+        let message_hashes = consume_work
+            .transactions
+            .iter()
+            .map(|tx| tx.message_hash())
+            .collect_vec();
+        assert_eq!(message_hashes, vec![&tx_hash]);
+    }
 }
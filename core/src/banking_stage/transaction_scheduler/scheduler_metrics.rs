@@ -77,6 +77,14 @@ pub struct SchedulerCountMetricsInner {
     pub num_dropped_on_age_and_status: usize,
     /// Number of transactions that were dropped due to exceeded capacity.
     pub num_dropped_on_capacity: usize,
+
+    /// Number of bundles received.
+    pub num_bundles_received: usize,
+    /// Number of bundles scheduled.
+    pub num_bundles_scheduled: usize,
+    /// Number of bundles that were dropped, during sanitization, checks, or
+    /// clearing.
+    pub num_bundles_dropped: usize,
     /// Min prioritization fees in the transaction container
     pub min_prioritization_fees: u64,
     /// Max prioritization fees in the transaction container
@@ -148,6 +156,9 @@ impl SchedulerCountMetricsInner {
                 i64
             ),
             ("num_dropped_on_capacity", self.num_dropped_on_capacity, i64),
+            ("num_bundles_received", self.num_bundles_received, i64),
+            ("num_bundles_scheduled", self.num_bundles_scheduled, i64),
+            ("num_bundles_dropped", self.num_bundles_dropped, i64),
             ("min_priority", self.get_min_priority(), i64),
             ("max_priority", self.get_max_priority(), i64)
         );
@@ -173,6 +184,9 @@ impl SchedulerCountMetricsInner {
             || self.num_dropped_on_clear != 0
             || self.num_dropped_on_age_and_status != 0
             || self.num_dropped_on_capacity != 0
+            || self.num_bundles_received != 0
+            || self.num_bundles_scheduled != 0
+            || self.num_bundles_dropped != 0
     }
 
     fn reset(&mut self) {
@@ -191,6 +205,9 @@ impl SchedulerCountMetricsInner {
         self.num_dropped_on_clear = 0;
         self.num_dropped_on_age_and_status = 0;
         self.num_dropped_on_capacity = 0;
+        self.num_bundles_received = 0;
+        self.num_bundles_scheduled = 0;
+        self.num_bundles_dropped = 0;
         self.min_prioritization_fees = u64::MAX;
         self.max_prioritization_fees = 0;
     }
@@ -11,6 +11,16 @@ pub(crate) struct SanitizedTransactionTTL<Tx> {
     pub(crate) max_age: MaxAge,
 }
 
+/// An ordered group of transactions which must be committed atomically and
+/// contiguously: either all of them are committed, in consecutive entries, or
+/// none of them are.
+pub(crate) struct TransactionBundle<Tx> {
+    pub(crate) transactions: Vec<Tx>,
+    pub(crate) max_ages: Vec<MaxAge>,
+    /// Total cost of the transactions.
+    pub(crate) cost: u64,
+}
+
 /// TransactionState is used to track the state of a transaction in the transaction scheduler
 /// and banking stage as a whole.
 ///
@@ -10,6 +10,7 @@ use {
     itertools::MinMaxResult,
     min_max_heap::MinMaxHeap,
     slab::Slab,
+    solana_metrics::transaction_tracing,
     solana_runtime_transaction::transaction_with_meta::TransactionWithMeta,
     std::sync::Arc,
 };
@@ -197,6 +198,16 @@ impl<Tx: TransactionWithMeta> TransactionStateContainer<Tx> {
     ) -> bool {
         if remaining_capacity == 0 {
             let popped_id = self.priority_queue.push_pop_min(priority_id);
+            if transaction_tracing::is_enabled() {
+                let transaction = &self.id_to_transaction_state[popped_id.id]
+                    .transaction_ttl()
+                    .transaction;
+                transaction_tracing::record_event(
+                    transaction.signature().as_ref(),
+                    "scheduler_evicted",
+                    Some("dropped, the scheduler buffer is full"),
+                );
+            }
             self.remove_by_id(popped_id.id);
             true
         } else {
@@ -203,6 +203,7 @@ impl TowerVersions {
                     last_timestamp: tower.last_timestamp,
                     stray_restored_slot: tower.stray_restored_slot,
                     last_switch_threshold_check: tower.last_switch_threshold_check,
+                    unix_timestamp_offset: UnixTimestamp::default(),
                 }
             }
             TowerVersions::V1_14_11(tower) => Tower {
@@ -216,6 +217,7 @@ impl TowerVersions {
                 last_timestamp: tower.last_timestamp,
                 stray_restored_slot: tower.stray_restored_slot,
                 last_switch_threshold_check: tower.last_switch_threshold_check,
+                unix_timestamp_offset: UnixTimestamp::default(),
             },
             TowerVersions::Current(tower) => tower,
         }
@@ -266,6 +268,10 @@ pub struct Tower {
     stray_restored_slot: Option<Slot>,
     #[serde(skip)]
     pub last_switch_threshold_check: Option<(Slot, SwitchForkDecision)>,
+    #[serde(skip)]
+    // Added to the wallclock time of vote timestamps, used to move the cluster clock on validators
+    // with time control enabled
+    unix_timestamp_offset: UnixTimestamp,
 }
 
 impl Default for Tower {
@@ -280,6 +286,7 @@ impl Default for Tower {
             last_vote_tx_blockhash: BlockhashStatus::default(),
             stray_restored_slot: Option::default(),
             last_switch_threshold_check: Option::default(),
+            unix_timestamp_offset: UnixTimestamp::default(),
         };
         // VoteState::root_slot is ensured to be Some in Tower
         tower.vote_state.root_slot = Some(Slot::default());
@@ -708,11 +715,22 @@ impl Tower {
         self.last_vote.clone()
     }
 
+    pub fn unix_timestamp_offset(&self) -> UnixTimestamp {
+        self.unix_timestamp_offset
+    }
+
+    /// Sets the offset added to the wallclock time when timestamping votes
+    pub fn set_unix_timestamp_offset(&mut self, unix_timestamp_offset: UnixTimestamp) {
+        self.unix_timestamp_offset = unix_timestamp_offset;
+    }
+
     fn maybe_timestamp(&mut self, current_slot: Slot) -> Option<UnixTimestamp> {
         if current_slot > self.last_timestamp.slot
             || self.last_timestamp.slot == 0 && current_slot == self.last_timestamp.slot
         {
-            let timestamp = Utc::now().timestamp();
+            let timestamp = Utc::now()
+                .timestamp()
+                .saturating_add(self.unix_timestamp_offset);
             if timestamp >= self.last_timestamp.timestamp {
                 self.last_timestamp = BlockTimestamp {
                     slot: current_slot,
@@ -2,7 +2,7 @@
 
 use {
     crossbeam_channel::Receiver,
-    solana_ledger::blockstore::Blockstore,
+    solana_ledger::{blockstore::Blockstore, blockstore_meta::ContendedAccount},
     solana_runtime::bank::Bank,
     std::{
         sync::Arc,
@@ -25,6 +25,8 @@ pub struct CostUpdateService {
 const MAX_LOOP_COUNT: usize = 25;
 // Throttle checking the count to avoid excessive polling
 const LOOP_LIMITER: Duration = Duration::from_millis(10);
+// The number of most write-lock contended accounts recorded in the Blockstore for each slot
+const MAX_CONTENDED_ACCOUNTS: usize = 32;
 
 impl CostUpdateService {
     pub fn new(blockstore: Arc<Blockstore>, cost_update_receiver: CostUpdateReceiver) -> Self {
@@ -42,10 +44,11 @@ impl CostUpdateService {
         self.thread_hdl.join()
     }
 
-    fn service_loop(_blockstore: Arc<Blockstore>, cost_update_receiver: CostUpdateReceiver) {
+    fn service_loop(blockstore: Arc<Blockstore>, cost_update_receiver: CostUpdateReceiver) {
         for cost_update in cost_update_receiver.iter() {
             match cost_update {
                 CostUpdate::FrozenBank { bank } => {
+                    let mut contended_accounts = vec![];
                     for loop_count in 1..=MAX_LOOP_COUNT {
                         {
                             // Release the lock so that the thread that will
@@ -63,11 +66,25 @@ impl CostUpdateService {
                                      for slot {slot} after {loop_count} iteration(s)"
                                 );
                                 cost_tracker.report_stats(slot);
+                                contended_accounts = cost_tracker
+                                    .contended_accounts(MAX_CONTENDED_ACCOUNTS)
+                                    .into_iter()
+                                    .map(ContendedAccount::from)
+                                    .collect();
                                 break;
                             }
                         }
                         std::thread::sleep(LOOP_LIMITER);
                     }
+
+                    if !contended_accounts.is_empty() {
+                        let slot = bank.slot();
+                        if let Err(err) =
+                            blockstore.write_contended_accounts(slot, &contended_accounts)
+                        {
+                            warn!("Failed to write contended accounts for slot {slot}: {err:?}");
+                        }
+                    }
                 }
             }
         }
@@ -3,7 +3,7 @@
 use {
     crate::result::{Error, Result},
     crossbeam_channel::{unbounded, RecvTimeoutError},
-    solana_metrics::{inc_new_counter_debug, inc_new_counter_info},
+    solana_metrics::{inc_new_counter_debug, inc_new_counter_info, transaction_tracing},
     solana_perf::{packet::PacketBatchRecycler, recycler::Recycler},
     solana_poh::poh_recorder::PohRecorder,
     solana_sdk::{
@@ -14,6 +14,7 @@ use {
         self, PacketBatchReceiver, PacketBatchSender, StreamerReceiveStats,
     },
     solana_tpu_client::tpu_client::DEFAULT_TPU_ENABLE_UDP,
+    solana_transaction_metrics_tracker::get_signature_from_packet,
     std::{
         net::UdpSocket,
         sync::{
@@ -132,6 +133,19 @@ impl FetchStage {
             }
         } else {
             inc_new_counter_info!("fetch_stage-discard_forwards", num_packets);
+            if transaction_tracing::is_enabled() {
+                packet_batches
+                    .iter()
+                    .flat_map(|packet_batch| packet_batch.iter())
+                    .filter_map(|packet| get_signature_from_packet(packet).ok())
+                    .for_each(|signature| {
+                        transaction_tracing::record_event(
+                            signature,
+                            "fetch_forwarded",
+                            Some("forwarded packet discarded, not the upcoming leader"),
+                        )
+                    });
+            }
         }
 
         Ok(())
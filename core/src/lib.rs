@@ -39,6 +39,7 @@ pub mod snapshot_packager_service;
 pub mod staked_nodes_updater_service;
 pub mod stats_reporter_service;
 pub mod system_monitor_service;
+pub mod time_control;
 pub mod tpu;
 mod tpu_entry_notifier;
 pub mod tracer_packet_stats;
@@ -27,13 +27,14 @@ use {
                 AncestorDuplicateSlotsReceiver, DumpedSlotsSender, PopularPrunedForksReceiver,
             },
         },
+        time_control::{TimeControlError, WarpRequest, WarpRequestReceiver, WarpTarget},
         unfrozen_gossip_verified_vote_hashes::UnfrozenGossipVerifiedVoteHashes,
         voting_service::VoteOp,
         window_service::DuplicateSlotReceiver,
     },
     crossbeam_channel::{Receiver, RecvTimeoutError, Sender},
     rayon::{prelude::*, ThreadPool},
-    solana_accounts_db::contains::Contains,
+    solana_accounts_db::{accounts_db::CalcAccountsHashDataSource, contains::Contains},
     solana_entry::entry::VerifyRecyclers,
     solana_geyser_plugin_manager::block_metadata_notifier_interface::BlockMetadataNotifierArc,
     solana_gossip::cluster_info::ClusterInfo,
@@ -66,7 +67,7 @@ use {
         vote_sender_types::ReplayVoteSender,
     },
     solana_sdk::{
-        clock::{BankId, Slot, MAX_PROCESSING_AGE, NUM_CONSECUTIVE_LEADER_SLOTS},
+        clock::{BankId, Slot, UnixTimestamp, MAX_PROCESSING_AGE, NUM_CONSECUTIVE_LEADER_SLOTS},
         hash::Hash,
         pubkey::Pubkey,
         saturating_add_assign,
@@ -298,6 +299,7 @@ pub struct ReplayReceivers {
     pub duplicate_confirmed_slots_receiver: Receiver<Vec<(u64, Hash)>>,
     pub gossip_verified_vote_hash_receiver: Receiver<(Pubkey, u64, Hash)>,
     pub popular_pruned_forks_receiver: Receiver<Vec<u64>>,
+    pub warp_request_receiver: Option<WarpRequestReceiver>,
 }
 
 /// Timing information for the ReplayStage main processing loop
@@ -590,6 +592,7 @@ impl ReplayStage {
             duplicate_confirmed_slots_receiver,
             gossip_verified_vote_hash_receiver,
             popular_pruned_forks_receiver,
+            warp_request_receiver,
         } = receivers;
 
         trace!("replay stage");
@@ -700,6 +703,49 @@ impl ReplayStage {
                     break;
                 }
 
+                if let Some(warp_request_receiver) = &warp_request_receiver {
+                    for WarpRequest {
+                        target,
+                        unix_timestamp,
+                        result_sender,
+                    } in warp_request_receiver.try_iter()
+                    {
+                        let result = Self::warp(
+                            target,
+                            unix_timestamp,
+                            &my_pubkey,
+                            &vote_account,
+                            &bank_forks,
+                            &blockstore,
+                            &leader_schedule_cache,
+                            &poh_recorder,
+                            &block_commitment_cache,
+                            &accounts_background_request_sender,
+                            &rpc_subscriptions,
+                            &mut tower,
+                            &mut progress,
+                            &mut heaviest_subtree_fork_choice,
+                            &mut duplicate_slots_tracker,
+                            &mut duplicate_confirmed_slots,
+                            &mut unfrozen_gossip_verified_vote_hashes,
+                            &mut has_new_vote_been_rooted,
+                            &mut voted_signatures,
+                            &mut epoch_slots_frozen_slots,
+                            &drop_bank_sender,
+                            &slot_status_notifier,
+                        )
+                        .map(|warp_bank| {
+                            last_reset = warp_bank.last_blockhash();
+                            last_reset_bank_descendants = vec![];
+                            warp_bank.slot()
+                        });
+                        if let Err(err) = &result {
+                            warn!("Unable to warp: {err}");
+                        }
+                        let _ = result_sender.send(result);
+                    }
+                }
+
                 let mut generate_new_bank_forks_time =
                     Measure::start("generate_new_bank_forks_time");
                 Self::generate_new_bank_forks(
@@ -1008,6 +1054,7 @@ impl ReplayStage {
                         &block_commitment_cache,
                         &mut heaviest_subtree_fork_choice,
                         &bank_notification_sender,
+                        &slot_status_notifier,
                         &mut duplicate_slots_tracker,
                         &mut duplicate_confirmed_slots,
                         &mut unfrozen_gossip_verified_vote_hashes,
@@ -1071,7 +1118,11 @@ impl ReplayStage {
                                 &vote_account,
                                 &bank_forks,
                             ) {
-                                Ok(tower) => tower,
+                                Ok(mut new_tower) => {
+                                    new_tower
+                                        .set_unix_timestamp_offset(tower.unix_timestamp_offset());
+                                    new_tower
+                                }
                                 Err(err) => {
                                     error!(
                                         "Unable to load new tower when attempting to change \
@@ -2323,10 +2374,12 @@ impl ReplayStage {
         let err = format!("error: {err:?}");
 
         if let Some(slot_status_notifier) = slot_status_notifier {
-            slot_status_notifier
-                .read()
-                .unwrap()
-                .notify_slot_dead(slot, err.clone());
+            slot_status_notifier.read().unwrap().notify_slot_dead(
+                slot,
+                bank.parent().map(|parent| parent.slot()),
+                &Self::sorted_proper_ancestors(bank),
+                err.clone(),
+            );
         }
 
         rpc_subscriptions.notify_slot_update(SlotUpdate::Dead {
@@ -2398,6 +2451,7 @@ impl ReplayStage {
         block_commitment_cache: &Arc<RwLock<BlockCommitmentCache>>,
         heaviest_subtree_fork_choice: &mut HeaviestSubtreeForkChoice,
         bank_notification_sender: &Option<BankNotificationSenderConfig>,
+        slot_status_notifier: &Option<SlotStatusNotifier>,
         duplicate_slots_tracker: &mut DuplicateSlotsTracker,
         duplicate_confirmed_slots: &mut DuplicateConfirmedSlots,
         unfrozen_gossip_verified_vote_hashes: &mut UnfrozenGossipVerifiedVoteHashes,
@@ -2464,6 +2518,7 @@ impl ReplayStage {
                 vote_signatures,
                 epoch_slots_frozen_slots,
                 drop_bank_sender,
+                slot_status_notifier,
             )?;
 
             blockstore.slots_stats.mark_rooted(new_root);
@@ -3985,14 +4040,37 @@ impl ReplayStage {
         voted_signatures: &mut Vec<Signature>,
         epoch_slots_frozen_slots: &mut EpochSlotsFrozenSlots,
         drop_bank_sender: &Sender<Vec<BankWithScheduler>>,
+        slot_status_notifier: &Option<SlotStatusNotifier>,
     ) -> Result<(), SetRootError> {
         bank_forks.read().unwrap().prune_program_cache(new_root);
+        // Ancestors of the new root are removed from bank forks as well, but
+        // those are rooted rather than abandoned.
+        let new_root_ancestors = slot_status_notifier
+            .is_some()
+            .then(|| bank_forks.read().unwrap()[new_root].proper_ancestors_set());
         let removed_banks = bank_forks.write().unwrap().set_root(
             new_root,
             accounts_background_request_sender,
             highest_super_majority_root,
         )?;
 
+        if let (Some(slot_status_notifier), Some(new_root_ancestors)) =
+            (slot_status_notifier, new_root_ancestors)
+        {
+            let slot_status_notifier = slot_status_notifier.read().unwrap();
+            // Dead slots were already reported as abandoned when they were marked dead
+            for bank in removed_banks.iter().filter(|bank| {
+                !new_root_ancestors.contains(&bank.slot())
+                    && progress.is_dead(bank.slot()) != Some(true)
+            }) {
+                slot_status_notifier.notify_slot_pruned(
+                    bank.slot(),
+                    bank.parent().map(|parent| parent.slot()),
+                    &Self::sorted_proper_ancestors(bank),
+                );
+            }
+        }
+
         drop_bank_sender
             .send(removed_banks)
             .unwrap_or_else(|err| warn!("bank drop failed: {:?}", err));
@@ -4026,6 +4104,190 @@ impl ReplayStage {
         // epoch_slots_frozen_slots now only contains entries >= `new_root`
     }
 
+    /// Warps to the slot requested through time control
+    ///
+    /// The heaviest bank is rooted and a frozen bank is created from it at the warp slot, as is
+    /// done for `warp_slot` at startup. The warp bank becomes the new root, so any other fork,
+    /// including a leader block in progress, is discarded and the tower is rebuilt from it.
+    #[allow(clippy::too_many_arguments)]
+    fn warp(
+        target: Option<WarpTarget>,
+        unix_timestamp: Option<UnixTimestamp>,
+        my_pubkey: &Pubkey,
+        vote_account: &Pubkey,
+        bank_forks: &RwLock<BankForks>,
+        blockstore: &Blockstore,
+        leader_schedule_cache: &Arc<LeaderScheduleCache>,
+        poh_recorder: &RwLock<PohRecorder>,
+        block_commitment_cache: &RwLock<BlockCommitmentCache>,
+        accounts_background_request_sender: &AbsRequestSender,
+        rpc_subscriptions: &Arc<RpcSubscriptions>,
+        tower: &mut Tower,
+        progress: &mut ProgressMap,
+        heaviest_subtree_fork_choice: &mut HeaviestSubtreeForkChoice,
+        duplicate_slots_tracker: &mut DuplicateSlotsTracker,
+        duplicate_confirmed_slots: &mut DuplicateConfirmedSlots,
+        unfrozen_gossip_verified_vote_hashes: &mut UnfrozenGossipVerifiedVoteHashes,
+        has_new_vote_been_rooted: &mut bool,
+        voted_signatures: &mut Vec<Signature>,
+        epoch_slots_frozen_slots: &mut EpochSlotsFrozenSlots,
+        drop_bank_sender: &Sender<Vec<BankWithScheduler>>,
+        slot_status_notifier: &Option<SlotStatusNotifier>,
+    ) -> Result<Arc<Bank>, TimeControlError> {
+        let (heaviest_slot, heaviest_hash) = heaviest_subtree_fork_choice.best_overall_slot();
+        let (heaviest_bank, root, highest_slot) = {
+            let r_bank_forks = bank_forks.read().unwrap();
+            (
+                r_bank_forks
+                    .get(heaviest_slot)
+                    .expect("heaviest bank must exist in bank forks"),
+                r_bank_forks.root(),
+                r_bank_forks.highest_slot(),
+            )
+        };
+        let warp_slot = match target {
+            Some(WarpTarget::Slot(slot)) => slot,
+            Some(WarpTarget::Epoch(epoch)) => heaviest_bank
+                .epoch_schedule()
+                .get_first_slot_in_epoch(epoch),
+            None => highest_slot + 1,
+        };
+        if warp_slot <= highest_slot {
+            return Err(TimeControlError::WarpSlotNotInFuture {
+                warp_slot,
+                highest_slot,
+            });
+        }
+        let current_unix_timestamp = heaviest_bank.clock().unix_timestamp;
+        if let Some(unix_timestamp) = unix_timestamp {
+            if unix_timestamp < current_unix_timestamp {
+                return Err(TimeControlError::UnixTimestampInPast {
+                    unix_timestamp,
+                    current_unix_timestamp,
+                });
+            }
+        }
+        info!("warping from slot {heaviest_slot} to slot {warp_slot}");
+
+        if heaviest_slot != root {
+            let mut rooted_banks = heaviest_bank.parents();
+            rooted_banks.push(heaviest_bank.clone());
+            let rooted_slots: Vec<_> = rooted_banks.iter().map(|bank| bank.slot()).collect();
+            leader_schedule_cache.set_root(&heaviest_bank);
+            blockstore
+                .set_roots(rooted_slots.iter())
+                .expect("Ledger set roots failed");
+            Self::handle_new_root(
+                heaviest_slot,
+                bank_forks,
+                progress,
+                accounts_background_request_sender,
+                Some(heaviest_slot),
+                heaviest_subtree_fork_choice,
+                duplicate_slots_tracker,
+                duplicate_confirmed_slots,
+                unfrozen_gossip_verified_vote_hashes,
+                has_new_vote_been_rooted,
+                voted_signatures,
+                epoch_slots_frozen_slots,
+                drop_bank_sender,
+                slot_status_notifier,
+            )?;
+            rpc_subscriptions.notify_roots(rooted_slots);
+        }
+
+        // Warping calculates the accounts hash of the parent from storages, which requires that
+        // the accounts cache has been flushed
+        heaviest_bank.force_flush_accounts_cache();
+        let warp_bank = Bank::warp_from_parent_with_unix_timestamp(
+            heaviest_bank,
+            my_pubkey,
+            warp_slot,
+            CalcAccountsHashDataSource::Storages,
+            unix_timestamp.unwrap_or(current_unix_timestamp),
+        );
+        let warp_bank = bank_forks
+            .write()
+            .unwrap()
+            .insert(warp_bank)
+            .clone_without_scheduler();
+        let prev_leader_slot = progress.get_bank_prev_leader_slot(&warp_bank);
+        progress.insert(
+            warp_slot,
+            ForkProgress::new_from_bank(
+                &warp_bank,
+                my_pubkey,
+                vote_account,
+                prev_leader_slot,
+                0,
+                0,
+            ),
+        );
+        heaviest_subtree_fork_choice.add_new_leaf_slot(
+            (warp_slot, warp_bank.hash()),
+            Some((heaviest_slot, heaviest_hash)),
+        );
+
+        leader_schedule_cache.set_root(&warp_bank);
+        blockstore
+            .mark_slots_as_if_rooted_normally_at_startup(
+                vec![(warp_slot, Some(warp_bank.hash()))],
+                true,
+            )
+            .expect("Ledger set roots failed");
+        blockstore
+            .set_and_chain_connected_on_root_and_next_slots(warp_slot)
+            .expect("Ledger set connected failed");
+        Self::handle_new_root(
+            warp_slot,
+            bank_forks,
+            progress,
+            accounts_background_request_sender,
+            Some(warp_slot),
+            heaviest_subtree_fork_choice,
+            duplicate_slots_tracker,
+            duplicate_confirmed_slots,
+            unfrozen_gossip_verified_vote_hashes,
+            has_new_vote_been_rooted,
+            voted_signatures,
+            epoch_slots_frozen_slots,
+            drop_bank_sender,
+            slot_status_notifier,
+        )?;
+        rpc_subscriptions.notify_roots(vec![warp_slot]);
+        block_commitment_cache
+            .write()
+            .unwrap()
+            .set_all_slots(warp_slot, warp_slot);
+
+        // Votes for slots before the warp can no longer land, so start over with a tower rooted
+        // at the warp slot. Vote timestamps are offset so the clock continues from the warp bank.
+        let unix_timestamp_offset = match unix_timestamp {
+            Some(unix_timestamp) => {
+                unix_timestamp.saturating_sub((timestamp() / 1000) as UnixTimestamp)
+            }
+            None => tower.unix_timestamp_offset(),
+        };
+        *tower = Tower::new_from_bankforks(&bank_forks.read().unwrap(), my_pubkey, vote_account);
+        tower.set_unix_timestamp_offset(unix_timestamp_offset);
+
+        Self::reset_poh_recorder(
+            my_pubkey,
+            blockstore,
+            warp_bank.clone(),
+            poh_recorder,
+            leader_schedule_cache,
+        );
+        Ok(warp_bank)
+    }
+
+    /// Returns the ancestors of `bank`, excluding itself, from newest to oldest
+    fn sorted_proper_ancestors(bank: &Bank) -> Vec<Slot> {
+        let mut ancestors: Vec<_> = bank.proper_ancestors_set().into_iter().collect();
+        ancestors.sort_unstable_by(|a, b| b.cmp(a));
+        ancestors
+    }
+
     fn generate_new_bank_forks(
         blockstore: &Blockstore,
         bank_forks: &RwLock<BankForks>,
@@ -4596,6 +4858,7 @@ pub(crate) mod tests {
             &mut Vec::new(),
             &mut epoch_slots_frozen_slots,
             &drop_bank_sender,
+            &None,
         )
         .unwrap();
         assert_eq!(bank_forks.read().unwrap().root(), root);
@@ -4627,6 +4890,63 @@ pub(crate) mod tests {
         );
     }
 
+    #[test]
+    fn test_handle_new_root_notifies_pruned_slots() {
+        let genesis_config = create_genesis_config(10_000).genesis_config;
+        let bank0 = Bank::new_for_tests(&genesis_config);
+        let bank_forks = BankForks::new_rw_arc(bank0);
+
+        // Slots 1 and 2 fork off of slot 0 and are pruned once slot 3 is rooted. Slot 2 was
+        // already marked dead.
+        //
+        //   0 - 1 - 2
+        //    \
+        //     3
+        let root = 3;
+        for (slot, parent) in [(1, 0), (2, 1), (root, 0)] {
+            let bank = Bank::new_from_parent(
+                bank_forks.read().unwrap().get(parent).unwrap(),
+                &Pubkey::default(),
+                slot,
+            );
+            bank.freeze();
+            bank_forks.write().unwrap().insert(bank);
+        }
+        let root_hash = bank_forks.read().unwrap().get(root).unwrap().hash();
+        let mut heaviest_subtree_fork_choice = HeaviestSubtreeForkChoice::new((root, root_hash));
+
+        let mut progress = ProgressMap::default();
+        for i in 0..=root {
+            progress.insert(i, ForkProgress::new(Hash::default(), None, None, 0, 0));
+        }
+        progress.get_mut(&2).unwrap().is_dead = true;
+
+        let slot_status_notifier = SlotStatusNotifierForTest::new(Arc::default());
+        let pruned_slots = slot_status_notifier.pruned_slots.clone();
+        let slot_status_notifier: SlotStatusNotifier = Arc::new(RwLock::new(slot_status_notifier));
+        let (drop_bank_sender, _drop_bank_receiver) = unbounded();
+        ReplayStage::handle_new_root(
+            root,
+            &bank_forks,
+            &mut progress,
+            &AbsRequestSender::default(),
+            None,
+            &mut heaviest_subtree_fork_choice,
+            &mut DuplicateSlotsTracker::default(),
+            &mut DuplicateConfirmedSlots::default(),
+            &mut UnfrozenGossipVerifiedVoteHashes::default(),
+            &mut true,
+            &mut Vec::new(),
+            &mut EpochSlotsFrozenSlots::default(),
+            &drop_bank_sender,
+            &Some(slot_status_notifier),
+        )
+        .unwrap();
+        assert_eq!(bank_forks.read().unwrap().root(), root);
+        // Slot 0 is an ancestor of the new root and slot 2 was reported dead already
+        assert_eq!(*pruned_slots.lock().unwrap(), vec![(1, Some(0), vec![0])]);
+    }
+
     #[test]
     fn test_handle_new_root_ahead_of_highest_super_majority_root() {
         let genesis_config = create_genesis_config(10_000).genesis_config;
@@ -4675,6 +4995,7 @@ pub(crate) mod tests {
             &mut Vec::new(),
             &mut EpochSlotsFrozenSlots::default(),
             &drop_bank_sender,
+            &None,
         )
         .unwrap();
         assert_eq!(bank_forks.read().unwrap().root(), root);
@@ -4918,11 +5239,15 @@ pub(crate) mod tests {
 
     struct SlotStatusNotifierForTest {
         dead_slots: Arc<Mutex<HashSet<Slot>>>,
+        pruned_slots: Arc<Mutex<Vec<(Slot, Option<Slot>, Vec<Slot>)>>>,
     }
 
     impl SlotStatusNotifierForTest {
         pub fn new(dead_slots: Arc<Mutex<HashSet<Slot>>>) -> Self {
-            Self { dead_slots }
+            Self {
+                dead_slots,
+                pruned_slots: Arc::default(),
+            }
         }
     }
 
@@ -4939,9 +5264,22 @@ pub(crate) mod tests {
 
         fn notify_created_bank(&self, _slot: Slot, _parent: Slot) {}
 
-        fn notify_slot_dead(&self, slot: Slot, _error: String) {
+        fn notify_slot_dead(
+            &self,
+            slot: Slot,
+            _parent: Option<Slot>,
+            _ancestors: &[Slot],
+            _error: String,
+        ) {
             self.dead_slots.lock().unwrap().insert(slot);
         }
+
+        fn notify_slot_pruned(&self, slot: Slot, parent: Option<Slot>, ancestors: &[Slot]) {
+            self.pruned_slots
+                .lock()
+                .unwrap()
+                .push((slot, parent, ancestors.to_vec()));
+        }
     }
 
     // Given a shred and a fatal expected error, check that replaying that shred causes causes the fork to be
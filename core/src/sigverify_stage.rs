@@ -11,6 +11,7 @@ use {
     crossbeam_channel::{Receiver, RecvTimeoutError, SendError},
     itertools::Itertools,
     solana_measure::measure::Measure,
+    solana_metrics::transaction_tracing::{self, TransactionSpan},
     solana_perf::{
         deduper::{self, Deduper},
         packet::{Packet, PacketBatch},
@@ -20,7 +21,9 @@ use {
     },
     solana_sdk::timing,
     solana_streamer::streamer::{self, StreamerError},
+    solana_transaction_metrics_tracker::get_signature_from_packet,
     std::{
+        collections::HashMap,
         thread::{self, Builder, JoinHandle},
         time::Instant,
     },
@@ -312,6 +315,12 @@ impl SigVerifyStage {
             num_packets,
         );
 
+        let spans = if transaction_tracing::is_enabled() {
+            Self::start_spans(&batches)
+        } else {
+            HashMap::new()
+        };
+
         let mut discard_random_time = Measure::start("sigverify_discard_random_time");
         let non_discarded_packets = solana_perf::discard::discard_batches_randomly(
             &mut batches,
@@ -351,6 +360,8 @@ impl SigVerifyStage {
         let excess_fail = num_unique.saturating_sub(MAX_SIGVERIFY_BATCH);
         discard_time.stop();
 
+        let spans = Self::end_discarded_spans(spans, &batches, "discarded before verification");
+
         // Pre-shrink packet batches if many packets are discarded from dedup / discard
         let (pre_shrink_time_us, pre_shrink_total) = Self::maybe_shrink_batches(&mut batches);
 
@@ -363,6 +374,10 @@ impl SigVerifyStage {
         );
         verify_time.stop();
 
+        Self::end_discarded_spans(spans, &batches, "signature verification failed")
+            .into_values()
+            .for_each(TransactionSpan::end);
+
         // Post-shrink packet batches if many packets are discarded from sigverify
         let (post_shrink_time_us, post_shrink_total) = Self::maybe_shrink_batches(&mut batches);
 
@@ -411,6 +426,43 @@ impl SigVerifyStage {
         Ok(())
     }
 
+    /// Starts a span for each sampled packet in `batches` that hasn't been discarded yet.
+    fn start_spans(batches: &[PacketBatch]) -> HashMap<[u8; 64], TransactionSpan> {
+        batches
+            .iter()
+            .flatten()
+            .filter(|packet| !packet.meta().discard())
+            .filter_map(|packet| {
+                let signature = get_signature_from_packet(packet).ok()?;
+                Some((*signature, TransactionSpan::start(signature, "sigverify")?))
+            })
+            .collect()
+    }
+
+    /// Ends the spans of the packets that are no longer in `batches`, or have been discarded,
+    /// failing them with `error`. Returns the remaining spans.
+    fn end_discarded_spans(
+        mut spans: HashMap<[u8; 64], TransactionSpan>,
+        batches: &[PacketBatch],
+        error: &str,
+    ) -> HashMap<[u8; 64], TransactionSpan> {
+        if spans.is_empty() {
+            return spans;
+        }
+        let remaining = batches
+            .iter()
+            .flatten()
+            .filter(|packet| !packet.meta().discard())
+            .filter_map(|packet| get_signature_from_packet(packet).ok())
+            .filter_map(|signature| Some((*signature, spans.remove(signature)?)))
+            .collect();
+        for mut span in spans.into_values() {
+            span.set_error(error);
+            span.end();
+        }
+        remaining
+    }
+
     fn verifier_service<T: SigVerifier + 'static + Send>(
         packet_receiver: Receiver<PacketBatch>,
         mut verifier: T,
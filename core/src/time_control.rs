@@ -0,0 +1,198 @@
+//! The `time_control` module lets a running validator warp forward to a later slot or epoch, set
+//! the cluster clock and pause slot production. It is meant for single node development clusters,
+//! such as `solana-test-validator`, where waiting in real time for vesting schedules, epoch
+//! boundaries or timeouts would make tests impractically slow.
+use {
+    crossbeam_channel::{bounded, unbounded, Receiver, RecvTimeoutError, Sender},
+    solana_poh::poh_recorder::PohRecorder,
+    solana_runtime::bank_forks::SetRootError,
+    solana_sdk::clock::{Epoch, Slot, UnixTimestamp},
+    std::{
+        sync::{Arc, RwLock},
+        time::Duration,
+    },
+    thiserror::Error,
+};
+
+// Warping calculates the accounts hash of the bank being warped from, which can take a while
+const WARP_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Error, Debug)]
+pub enum TimeControlError {
+    #[error("cannot warp to slot {warp_slot}, the highest bank is already at slot {highest_slot}")]
+    WarpSlotNotInFuture { warp_slot: Slot, highest_slot: Slot },
+
+    #[error(
+        "cannot set the clock to {unix_timestamp}, it must not be earlier than the current \
+         timestamp {current_unix_timestamp}"
+    )]
+    UnixTimestampInPast {
+        unix_timestamp: UnixTimestamp,
+        current_unix_timestamp: UnixTimestamp,
+    },
+
+    #[error("failed to set the warp slot as root: {0}")]
+    SetRoot(#[from] SetRootError),
+
+    #[error("replay stage is not running")]
+    ReplayStageUnavailable,
+
+    #[error("timed out waiting for the warp to complete")]
+    Timeout,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarpTarget {
+    Slot(Slot),
+    /// The first slot of the epoch
+    Epoch(Epoch),
+}
+
+/// A request for the replay stage to warp, handled in between replaying and producing blocks
+pub struct WarpRequest {
+    /// Where to warp to, or the slot after the highest bank if `None`
+    pub target: Option<WarpTarget>,
+    /// The `Clock` unix timestamp of the warped bank, or the current timestamp if `None`
+    pub unix_timestamp: Option<UnixTimestamp>,
+    /// Receives the warped slot
+    pub result_sender: Sender<Result<Slot, TimeControlError>>,
+}
+
+pub type WarpRequestSender = Sender<WarpRequest>;
+pub type WarpRequestReceiver = Receiver<WarpRequest>;
+
+/// Controls the passage of slots and time on a running validator
+///
+/// Warping roots the current heaviest bank and creates a frozen bank at the warp slot on top of
+/// it, like the `warp_slot` validator option does at startup. Unrooted banks on other forks,
+/// including any leader block in progress, are discarded.
+pub struct TimeControl {
+    poh_recorder: Arc<RwLock<PohRecorder>>,
+    warp_request_sender: WarpRequestSender,
+}
+
+impl TimeControl {
+    pub fn new(poh_recorder: Arc<RwLock<PohRecorder>>) -> (Self, WarpRequestReceiver) {
+        let (warp_request_sender, warp_request_receiver) = unbounded();
+        (
+            Self {
+                poh_recorder,
+                warp_request_sender,
+            },
+            warp_request_receiver,
+        )
+    }
+
+    /// Warps to `slot`, returning once the warped bank is the root
+    pub fn warp_to_slot(&self, slot: Slot) -> Result<Slot, TimeControlError> {
+        self.warp(Some(WarpTarget::Slot(slot)), None)
+    }
+
+    /// Warps to the first slot of `epoch`, returning the warped slot
+    pub fn warp_to_epoch(&self, epoch: Epoch) -> Result<Slot, TimeControlError> {
+        self.warp(Some(WarpTarget::Epoch(epoch)), None)
+    }
+
+    /// Sets the `Clock` unix timestamp by warping to the next slot, returning the warped slot
+    ///
+    /// Vote timestamps are offset to match, so the clock keeps advancing from `unix_timestamp`
+    /// afterwards.
+    pub fn set_clock_unix_timestamp(
+        &self,
+        unix_timestamp: UnixTimestamp,
+    ) -> Result<Slot, TimeControlError> {
+        self.warp(None, Some(unix_timestamp))
+    }
+
+    pub fn warp(
+        &self,
+        target: Option<WarpTarget>,
+        unix_timestamp: Option<UnixTimestamp>,
+    ) -> Result<Slot, TimeControlError> {
+        let (result_sender, result_receiver) = bounded(1);
+        self.warp_request_sender
+            .send(WarpRequest {
+                target,
+                unix_timestamp,
+                result_sender,
+            })
+            .map_err(|_| TimeControlError::ReplayStageUnavailable)?;
+        match result_receiver.recv_timeout(WARP_TIMEOUT) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => Err(TimeControlError::Timeout),
+            Err(RecvTimeoutError::Disconnected) => Err(TimeControlError::ReplayStageUnavailable),
+        }
+    }
+
+    /// Stops PoH from ticking, so no new slots are started until resumed
+    ///
+    /// Transactions can still be recorded in a leader block that is already in progress, until the
+    /// next tick is due. Past that point, they are rejected as retryable until resumed.
+    pub fn pause_slot_production(&self) {
+        self.poh_recorder.write().unwrap().set_paused(true);
+    }
+
+    pub fn resume_slot_production(&self) {
+        self.poh_recorder.write().unwrap().set_paused(false);
+    }
+
+    pub fn is_slot_production_paused(&self) -> bool {
+        self.poh_recorder.read().unwrap().is_paused()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_ledger::{
+            blockstore::Blockstore, genesis_utils::create_genesis_config,
+            get_tmp_ledger_path_auto_delete,
+        },
+        solana_poh::poh_recorder::create_test_recorder,
+        solana_runtime::bank::Bank,
+        std::{sync::atomic::Ordering, thread},
+    };
+
+    #[test]
+    fn test_time_control() {
+        let genesis_config = create_genesis_config(2).genesis_config;
+        let (bank, _bank_forks) = Bank::new_no_wallclock_throttle_for_tests(&genesis_config);
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
+        let (exit, poh_recorder, poh_service, _entry_receiver) =
+            create_test_recorder(bank, blockstore, None, None);
+        let (time_control, warp_request_receiver) = TimeControl::new(poh_recorder);
+
+        assert!(!time_control.is_slot_production_paused());
+        time_control.pause_slot_production();
+        assert!(time_control.is_slot_production_paused());
+        time_control.resume_slot_production();
+        assert!(!time_control.is_slot_production_paused());
+
+        let replay = thread::spawn(move || {
+            let request = warp_request_receiver.recv().unwrap();
+            assert_eq!(request.target, Some(WarpTarget::Epoch(3)));
+            assert_eq!(request.unix_timestamp, None);
+            request.result_sender.send(Ok(96)).unwrap();
+
+            let request = warp_request_receiver.recv().unwrap();
+            assert_eq!(request.target, None);
+            assert_eq!(request.unix_timestamp, Some(1_000));
+            // Dropped without a reply
+        });
+        assert_eq!(time_control.warp_to_epoch(3).unwrap(), 96);
+        assert!(matches!(
+            time_control.set_clock_unix_timestamp(1_000),
+            Err(TimeControlError::ReplayStageUnavailable)
+        ));
+        replay.join().unwrap();
+        assert!(matches!(
+            time_control.warp_to_slot(100),
+            Err(TimeControlError::ReplayStageUnavailable)
+        ));
+
+        exit.store(true, Ordering::Relaxed);
+        poh_service.join().unwrap();
+    }
+}
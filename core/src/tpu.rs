@@ -4,7 +4,7 @@
 pub use solana_sdk::net::DEFAULT_TPU_COALESCE;
 use {
     crate::{
-        banking_stage::BankingStage,
+        banking_stage::{BankingStage, BundleReceiver},
         banking_trace::{BankingTracer, TracerThread},
         cluster_info_vote_listener::{
             ClusterInfoVoteListener, DuplicateConfirmedSlotsSender, GossipVerifiedVoteHashSender,
@@ -119,6 +119,7 @@ impl Tpu {
         prioritization_fee_cache: &Arc<PrioritizationFeeCache>,
         block_production_method: BlockProductionMethod,
         enable_block_production_forwarding: bool,
+        bundle_receiver: Option<BundleReceiver>,
         _generator_config: Option<GeneratorConfig>, /* vestigial code for replay invalidator */
     ) -> (Self, Vec<Arc<dyn NotifyKeyUpdate + Sync + Send>>) {
         let TpuSockets {
@@ -278,6 +279,7 @@ impl Tpu {
             bank_forks.clone(),
             prioritization_fee_cache,
             enable_block_production_forwarding,
+            bundle_receiver,
         );
 
         let (entry_receiver, tpu_entry_notifier) =
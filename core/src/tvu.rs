@@ -17,6 +17,7 @@ use {
         repair::repair_service::{OutstandingShredRepairs, RepairInfo},
         replay_stage::{ReplayReceivers, ReplaySenders, ReplayStage, ReplayStageConfig},
         shred_fetch_stage::ShredFetchStage,
+        time_control::WarpRequestReceiver,
         voting_service::VotingService,
         warm_quic_cache_service::WarmQuicCacheService,
         window_service::WindowService,
@@ -91,6 +92,8 @@ pub struct TvuConfig {
     pub replay_forks_threads: NonZeroUsize,
     pub replay_transactions_threads: NonZeroUsize,
     pub shred_sigverify_threads: NonZeroUsize,
+    // Warp requests from time control, if enabled
+    pub warp_request_receiver: Option<WarpRequestReceiver>,
 }
 
 impl Default for TvuConfig {
@@ -104,6 +107,7 @@ impl Default for TvuConfig {
             replay_forks_threads: NonZeroUsize::new(1).expect("1 is non-zero"),
             replay_transactions_threads: NonZeroUsize::new(1).expect("1 is non-zero"),
             shred_sigverify_threads: NonZeroUsize::new(1).expect("1 is non-zero"),
+            warp_request_receiver: None,
         }
     }
 }
@@ -301,6 +305,7 @@ impl Tvu {
             duplicate_confirmed_slots_receiver,
             gossip_verified_vote_hash_receiver,
             popular_pruned_forks_receiver,
+            warp_request_receiver: tvu_config.warp_request_receiver,
         };
 
         let replay_stage_config = ReplayStageConfig {
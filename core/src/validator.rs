@@ -5,6 +5,7 @@ use {
     crate::{
         accounts_hash_verifier::AccountsHashVerifier,
         admin_rpc_post_init::AdminRpcRequestMetadataPostInit,
+        banking_stage::BUNDLE_CHANNEL_CAPACITY,
         banking_trace::{self, BankingTracer, TraceError},
         cache_block_meta_service::{CacheBlockMetaSender, CacheBlockMetaService},
         cluster_info_vote_listener::VoteTracker,
@@ -29,6 +30,7 @@ use {
         system_monitor_service::{
             verify_net_stats_access, SystemMonitorService, SystemMonitorStatsReportConfig,
         },
+        time_control::TimeControl,
         tpu::{Tpu, TpuSockets, DEFAULT_TPU_COALESCE},
         tvu::{Tvu, TvuConfig, TvuSockets},
     },
@@ -189,6 +191,7 @@ impl BlockVerificationMethod {
 pub enum BlockProductionMethod {
     #[default]
     CentralScheduler,
+    CentralSchedulerGreedy,
 }
 
 impl BlockProductionMethod {
@@ -286,6 +289,9 @@ pub struct ValidatorConfig {
     pub replay_transactions_threads: NonZeroUsize,
     pub tvu_shred_sigverify_threads: NonZeroUsize,
     pub delay_leader_block_for_pending_fork: bool,
+    /// Allows warping, setting the clock and pausing slot production while running. Only meant
+    /// for single node development clusters.
+    pub enable_time_control: bool,
 }
 
 impl Default for ValidatorConfig {
@@ -358,6 +364,7 @@ impl Default for ValidatorConfig {
             replay_transactions_threads: NonZeroUsize::new(1).expect("1 is non-zero"),
             tvu_shred_sigverify_threads: NonZeroUsize::new(1).expect("1 is non-zero"),
             delay_leader_block_for_pending_fork: false,
+            enable_time_control: false,
         }
     }
 }
@@ -512,6 +519,7 @@ pub struct Validator {
     repair_quic_endpoints: Option<[Endpoint; 3]>,
     repair_quic_endpoints_runtime: Option<TokioRuntime>,
     repair_quic_endpoints_join_handle: Option<repair::quic_endpoint::AsyncTryJoinHandle>,
+    time_control: Option<Arc<TimeControl>>,
 }
 
 impl Validator {
@@ -987,6 +995,12 @@ impl Validator {
             )
         };
         let poh_recorder = Arc::new(RwLock::new(poh_recorder));
+        let (time_control, warp_request_receiver) = if config.enable_time_control {
+            let (time_control, warp_request_receiver) = TimeControl::new(poh_recorder.clone());
+            (Some(Arc::new(time_control)), Some(warp_request_receiver))
+        } else {
+            (None, None)
+        };
 
         let staked_nodes = Arc::new(RwLock::new(StakedNodes::default()));
 
@@ -1025,6 +1039,7 @@ impl Validator {
             rpc_completed_slots_service,
             optimistically_confirmed_bank_tracker,
             bank_notification_sender,
+            bundle_receiver,
         ) = if let Some((rpc_addr, rpc_pubsub_addr)) = config.rpc_addrs {
             assert_eq!(
                 node.info
@@ -1043,6 +1058,12 @@ impl Validator {
                 None
             };
 
+            let (bundle_sender, bundle_receiver) = if config.rpc_config.enable_send_bundle {
+                let (sender, receiver) = bounded(BUNDLE_CHANNEL_CAPACITY);
+                (Some(sender), Some(receiver))
+            } else {
+                (None, None)
+            };
             let json_rpc_service = JsonRpcService::new(
                 rpc_addr,
                 config.rpc_config.clone(),
@@ -1066,6 +1087,7 @@ impl Validator {
                 max_complete_transaction_status_slot,
                 max_complete_rewards_slot,
                 prioritization_fee_cache.clone(),
+                bundle_sender,
             )
             .map_err(ValidatorError::Other)?;
 
@@ -1143,9 +1165,10 @@ impl Validator {
                 rpc_completed_slots_service,
                 optimistically_confirmed_bank_tracker,
                 bank_notification_sender_config,
+                bundle_receiver,
             )
         } else {
-            (None, None, None, None, None, None, None)
+            (None, None, None, None, None, None, None, None)
         };
 
         if config.halt_at_slot.is_some() {
@@ -1409,6 +1432,7 @@ impl Validator {
                 replay_forks_threads: config.replay_forks_threads,
                 replay_transactions_threads: config.replay_transactions_threads,
                 shred_sigverify_threads: config.tvu_shred_sigverify_threads,
+                warp_request_receiver,
             },
             &max_slots,
             block_metadata_notifier,
@@ -1494,6 +1518,7 @@ impl Validator {
             &prioritization_fee_cache,
             config.block_production_method.clone(),
             config.enable_block_production_forwarding,
+            bundle_receiver,
             config.generator_config.clone(),
         );
 
@@ -1519,6 +1544,7 @@ impl Validator {
             repair_socket: Arc::new(node.sockets.repair),
             outstanding_repair_requests,
             cluster_slots,
+            time_control: time_control.clone(),
         });
 
         Ok(Self {
@@ -1557,9 +1583,15 @@ impl Validator {
             repair_quic_endpoints,
             repair_quic_endpoints_runtime,
             repair_quic_endpoints_join_handle,
+            time_control,
         })
     }
 
+    /// Returns the time control of this validator, if enabled with `enable_time_control`
+    pub fn time_control(&self) -> Option<&Arc<TimeControl>> {
+        self.time_control.as_ref()
+    }
+
     // Used for notifying many nodes in parallel to exit
     pub fn exit(&mut self) {
         self.validator_exit.write().unwrap().exit();
@@ -58,6 +58,18 @@ pub struct UpdatedCosts {
     pub updated_costliest_account_cost: u64,
 }
 
+/// Write-lock contention on an account, see `CostTracker::contended_accounts()`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ContendedAccount {
+    pub pubkey: Pubkey,
+    /// Cost accumulated by the transactions write-locking the account
+    pub compute_units: u64,
+    /// Number of transactions write-locking the account
+    pub transaction_count: u64,
+    /// Number of transactions rejected because they would exceed the account cost limit
+    pub rejected_transaction_count: u64,
+}
+
 #[cfg_attr(feature = "frozen-abi", derive(AbiExample))]
 #[derive(Debug)]
 pub struct CostTracker {
@@ -65,6 +77,10 @@ pub struct CostTracker {
     block_cost_limit: u64,
     vote_cost_limit: u64,
     cost_by_writable_accounts: HashMap<Pubkey, u64, ahash::RandomState>,
+    transaction_count_by_writable_accounts: HashMap<Pubkey, u64, ahash::RandomState>,
+    /// Number of transactions rejected with `WouldExceedAccountMaxLimit`, per account that
+    /// would have exceeded the limit
+    rejected_transaction_count_by_writable_accounts: HashMap<Pubkey, u64, ahash::RandomState>,
     block_cost: u64,
     vote_cost: u64,
     transaction_count: u64,
@@ -94,6 +110,11 @@ impl Default for CostTracker {
                 WRITABLE_ACCOUNTS_PER_BLOCK,
                 ahash::RandomState::new(),
             ),
+            transaction_count_by_writable_accounts: HashMap::with_capacity_and_hasher(
+                WRITABLE_ACCOUNTS_PER_BLOCK,
+                ahash::RandomState::new(),
+            ),
+            rejected_transaction_count_by_writable_accounts: HashMap::default(),
             block_cost: 0,
             vote_cost: 0,
             transaction_count: 0,
@@ -119,6 +140,8 @@ impl CostTracker {
 
     pub fn reset(&mut self) {
         self.cost_by_writable_accounts.clear();
+        self.transaction_count_by_writable_accounts.clear();
+        self.rejected_transaction_count_by_writable_accounts.clear();
         self.block_cost = 0;
         self.vote_cost = 0;
         self.transaction_count = 0;
@@ -162,7 +185,11 @@ impl CostTracker {
         &mut self,
         tx_cost: &TransactionCost<impl TransactionWithMeta>,
     ) -> Result<UpdatedCosts, CostTrackerError> {
-        self.would_fit(tx_cost)?;
+        self.would_fit(tx_cost).inspect_err(|err| {
+            if *err == CostTrackerError::WouldExceedAccountMaxLimit {
+                self.record_account_limit_rejection(tx_cost);
+            }
+        })?;
         let updated_costliest_account_cost = self.add_transaction_cost(tx_cost);
         Ok(UpdatedCosts {
             updated_block_cost: self.block_cost,
@@ -206,6 +233,10 @@ impl CostTracker {
         self.block_cost
     }
 
+    pub fn block_cost_limit(&self) -> u64 {
+        self.block_cost_limit
+    }
+
     pub fn vote_cost(&self) -> u64 {
         self.vote_cost
     }
@@ -259,6 +290,51 @@ impl CostTracker {
         );
     }
 
+    /// Returns up to `limit` write-locked accounts with the highest cost in the block, along
+    /// with the number of transactions that were rejected for exceeding their account cost
+    /// limit. Accounts which only saw rejections are included as well.
+    pub fn contended_accounts(&self, limit: usize) -> Vec<ContendedAccount> {
+        let mut contended_accounts: Vec<_> = self
+            .cost_by_writable_accounts
+            .keys()
+            .chain(
+                self.rejected_transaction_count_by_writable_accounts
+                    .keys()
+                    .filter(|pubkey| !self.cost_by_writable_accounts.contains_key(pubkey)),
+            )
+            .map(|pubkey| ContendedAccount {
+                pubkey: *pubkey,
+                compute_units: self
+                    .cost_by_writable_accounts
+                    .get(pubkey)
+                    .copied()
+                    .unwrap_or_default(),
+                transaction_count: self
+                    .transaction_count_by_writable_accounts
+                    .get(pubkey)
+                    .copied()
+                    .unwrap_or_default(),
+                rejected_transaction_count: self
+                    .rejected_transaction_count_by_writable_accounts
+                    .get(pubkey)
+                    .copied()
+                    .unwrap_or_default(),
+            })
+            .filter(|account| account.compute_units > 0 || account.rejected_transaction_count > 0)
+            .collect();
+        contended_accounts.sort_unstable_by(|a, b| {
+            b.compute_units
+                .cmp(&a.compute_units)
+                .then(
+                    b.rejected_transaction_count
+                        .cmp(&a.rejected_transaction_count),
+                )
+                .then(a.pubkey.cmp(&b.pubkey))
+        });
+        contended_accounts.truncate(limit);
+        contended_accounts
+    }
+
     fn find_costliest_account(&self) -> (Pubkey, u64) {
         self.cost_by_writable_accounts
             .iter()
@@ -315,6 +391,29 @@ impl CostTracker {
         Ok(())
     }
 
+    /// Count a transaction rejected with `WouldExceedAccountMaxLimit` against the write-locked
+    /// accounts which would have exceeded the limit
+    fn record_account_limit_rejection(
+        &mut self,
+        tx_cost: &TransactionCost<impl TransactionWithMeta>,
+    ) {
+        let cost = tx_cost.sum();
+        for account_key in tx_cost.writable_accounts() {
+            let chained_cost = self
+                .cost_by_writable_accounts
+                .get(account_key)
+                .copied()
+                .unwrap_or_default();
+            if chained_cost.saturating_add(cost) > self.account_cost_limit {
+                let rejected_transaction_count = self
+                    .rejected_transaction_count_by_writable_accounts
+                    .entry(*account_key)
+                    .or_insert(0);
+                *rejected_transaction_count = rejected_transaction_count.saturating_add(1);
+            }
+        }
+    }
+
     // Returns the highest account cost for all write-lock accounts `TransactionCost` updated
     fn add_transaction_cost(&mut self, tx_cost: &TransactionCost<impl TransactionWithMeta>) -> u64 {
         saturating_add_assign!(
@@ -322,6 +421,13 @@ impl CostTracker {
             tx_cost.allocated_accounts_data_size()
         );
         saturating_add_assign!(self.transaction_count, 1);
+        for account_key in tx_cost.writable_accounts() {
+            let transaction_count = self
+                .transaction_count_by_writable_accounts
+                .entry(*account_key)
+                .or_insert(0);
+            *transaction_count = transaction_count.saturating_add(1);
+        }
         saturating_add_assign!(
             self.transaction_signature_count,
             tx_cost.num_transaction_signatures()
@@ -344,6 +450,14 @@ impl CostTracker {
             .allocated_accounts_data_size
             .saturating_sub(tx_cost.allocated_accounts_data_size());
         self.transaction_count = self.transaction_count.saturating_sub(1);
+        for account_key in tx_cost.writable_accounts() {
+            if let Some(transaction_count) = self
+                .transaction_count_by_writable_accounts
+                .get_mut(account_key)
+            {
+                *transaction_count = transaction_count.saturating_sub(1);
+            }
+        }
         self.transaction_signature_count = self
             .transaction_signature_count
             .saturating_sub(tx_cost.num_transaction_signatures());
@@ -734,6 +848,60 @@ mod tests {
         assert!(testee.try_add(&tx_cost1).is_err());
     }
 
+    #[test]
+    fn test_cost_tracker_contended_accounts() {
+        let hot_account = Pubkey::new_unique();
+        let cold_account = Pubkey::new_unique();
+        let tx1 = WritableKeysTransaction(vec![hot_account, cold_account]);
+        let tx_cost1 = simple_transaction_cost(&tx1, 5);
+        let tx2 = WritableKeysTransaction(vec![hot_account]);
+        let tx_cost2 = simple_transaction_cost(&tx2, 5);
+        let cost = tx_cost1.sum();
+
+        // room for two transactions per account
+        let mut testee = CostTracker::new(2 * cost, 10 * cost, 10 * cost);
+        assert!(testee.try_add(&tx_cost1).is_ok());
+        assert!(testee.try_add(&tx_cost2).is_ok());
+        // rejected, only the hot account would exceed its limit
+        assert_eq!(
+            testee.try_add(&tx_cost1).unwrap_err(),
+            CostTrackerError::WouldExceedAccountMaxLimit
+        );
+        assert_eq!(
+            testee.contended_accounts(usize::MAX),
+            vec![
+                ContendedAccount {
+                    pubkey: hot_account,
+                    compute_units: 2 * cost,
+                    transaction_count: 2,
+                    rejected_transaction_count: 1,
+                },
+                ContendedAccount {
+                    pubkey: cold_account,
+                    compute_units: cost,
+                    transaction_count: 1,
+                    rejected_transaction_count: 0,
+                },
+            ]
+        );
+        assert_eq!(testee.contended_accounts(1).len(), 1);
+
+        // removed transactions are no longer counted, but rejections are
+        testee.remove(&tx_cost1);
+        assert_eq!(
+            testee.contended_accounts(usize::MAX),
+            vec![ContendedAccount {
+                pubkey: hot_account,
+                compute_units: cost,
+                transaction_count: 1,
+                rejected_transaction_count: 1,
+            }]
+        );
+
+        testee.reset();
+        assert!(testee.contended_accounts(usize::MAX).is_empty());
+    }
+
     #[test]
     fn test_cost_tracker_try_add_is_atomic() {
         let acct1 = Pubkey::new_unique();
@@ -87,6 +87,21 @@ impl Poh {
         })
     }
 
+    /// Records all of `mixins` as consecutive entries, or none of them if they do not fit
+    /// before the next tick.
+    pub fn record_batches(&mut self, mixins: &[Hash]) -> Option<Vec<PohEntry>> {
+        if self.remaining_hashes <= mixins.len() as u64 {
+            return None; // Caller needs to `tick()` first
+        }
+
+        Some(
+            mixins
+                .iter()
+                .map(|mixin| self.record(*mixin).expect("remaining hashes were checked"))
+                .collect(),
+        )
+    }
+
     pub fn tick(&mut self) -> Option<PohEntry> {
         self.hash = hash(self.hash.as_ref());
         self.num_hashes += 1;
@@ -328,4 +343,27 @@ mod tests {
         );
         assert_eq!(poh.remaining_hashes, 9);
     }
+
+    #[test]
+    fn test_poh_record_batches() {
+        let mut poh = Poh::new(Hash::default(), Some(10));
+        assert!(poh.hash(6));
+        assert_eq!(poh.remaining_hashes, 4);
+        let mixins = [Hash::new_unique(), Hash::new_unique(), Hash::new_unique()];
+        let entries = poh.record_batches(&mixins).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].num_hashes, 7);
+        assert_eq!(entries[1].num_hashes, 1);
+        assert_eq!(entries[2].num_hashes, 1);
+        assert_eq!(poh.remaining_hashes, 1);
+
+        // Nothing is recorded if the batches do not all fit before the next tick
+        let mut poh = Poh::new(Hash::default(), Some(10));
+        assert!(!poh.hash(7));
+        assert_eq!(poh.remaining_hashes, 3);
+        let hash = poh.hash;
+        assert!(poh.record_batches(&mixins).is_none());
+        assert_eq!(poh.remaining_hashes, 3);
+        assert_eq!(poh.hash, hash);
+    }
 }
@@ -13,6 +13,34 @@ use {
     thiserror::Error,
 };
 
+/// The version of the plugin interface. This is bumped whenever a change to the
+/// `GeyserPlugin` trait or the types passed through it breaks compatibility with
+/// plugins built against an earlier version.
+pub const GEYSER_PLUGIN_INTERFACE_VERSION: u32 = 1;
+
+/// Exports the `_geyser_plugin_interface_version` function, which the validator calls
+/// before creating the plugin to reject plugins built against an incompatible interface.
+/// Plugins should invoke this once, next to their `_create_plugin` function.
+/// # Examples
+///
+/// ```
+/// agave_geyser_plugin_interface::declare_geyser_plugin_interface_version!();
+///
+/// assert_eq!(
+///     _geyser_plugin_interface_version(),
+///     agave_geyser_plugin_interface::geyser_plugin_interface::GEYSER_PLUGIN_INTERFACE_VERSION,
+/// );
+/// ```
+#[macro_export]
+macro_rules! declare_geyser_plugin_interface_version {
+    () => {
+        #[no_mangle]
+        pub extern "C" fn _geyser_plugin_interface_version() -> u32 {
+            $crate::geyser_plugin_interface::GEYSER_PLUGIN_INTERFACE_VERSION
+        }
+    };
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[repr(C)]
 /// Information about an account being updated
@@ -274,6 +302,52 @@ pub enum ReplicaBlockInfoVersions<'a> {
     V0_0_4(&'a ReplicaBlockInfoV4<'a>),
 }
 
+/// The reason a slot was abandoned by the validator
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum SlotAbandonedReason {
+    /// The slot failed replay and was marked dead
+    Dead(String),
+
+    /// The slot is on a fork which does not descend from the new root and was pruned.
+    /// Each abandoned slot is reported exactly once, so a slot already reported as dead is not
+    /// reported again when it is pruned.
+    Pruned,
+}
+
+impl SlotAbandonedReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SlotAbandonedReason::Dead(_error) => "dead",
+            SlotAbandonedReason::Pruned => "pruned",
+        }
+    }
+}
+
+/// Information about a slot which will never be rooted. Any account, transaction
+/// or entry notifications received for this slot should be rolled back.
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct ReplicaAbandonedSlotInfo<'a> {
+    /// The abandoned slot
+    pub slot: Slot,
+
+    /// The parent of the abandoned slot
+    pub parent_slot: Option<Slot>,
+
+    /// The ancestors of the abandoned slot, excluding the slot itself,
+    /// in descending order
+    pub ancestors: &'a [Slot],
+
+    /// Why the slot was abandoned
+    pub reason: &'a SlotAbandonedReason,
+}
+
+#[repr(u32)]
+pub enum ReplicaAbandonedSlotInfoVersions<'a> {
+    V0_0_1(&'a ReplicaAbandonedSlotInfo<'a>),
+}
+
 /// Errors returned by plugin calls
 #[derive(Error, Debug)]
 #[repr(u32)]
@@ -450,6 +524,13 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
         Ok(())
     }
 
+    /// Called when a slot is abandoned, either because it was marked dead during
+    /// replay or because its fork was pruned when a new root was set.
+    #[allow(unused_variables)]
+    fn notify_slot_abandoned(&self, slot_info: ReplicaAbandonedSlotInfoVersions) -> Result<()> {
+        Ok(())
+    }
+
     /// Check if the plugin is interested in account data
     /// Default is true -- if the plugin is not interested in
     /// account data, please return false.
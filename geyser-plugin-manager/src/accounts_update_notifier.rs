@@ -1,6 +1,8 @@
 /// Module responsible for notifying plugins of account updates
 use {
-    crate::geyser_plugin_manager::GeyserPluginManager,
+    crate::{
+        geyser_plugin_manager::GeyserPluginManager, notification_buffer::BufferedNotification,
+    },
     agave_geyser_plugin_interface::geyser_plugin_interface::{
         ReplicaAccountInfoV3, ReplicaAccountInfoVersions,
     },
@@ -66,7 +68,7 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
 
     fn notify_end_of_restore_from_snapshot(&self) {
         let plugin_manager = self.plugin_manager.read().unwrap();
-        if plugin_manager.plugins.is_empty() {
+        if plugin_manager.is_empty() {
             return;
         }
 
@@ -93,6 +95,7 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
                 measure.as_us() as usize
             );
         }
+        plugin_manager.buffer_notification(|| BufferedNotification::EndOfStartup);
     }
 }
 
@@ -152,7 +155,7 @@ impl AccountsUpdateNotifierImpl {
         let mut measure2 = Measure::start("geyser-plugin-notify_plugins_of_account_update");
         let plugin_manager = self.plugin_manager.read().unwrap();
 
-        if plugin_manager.plugins.is_empty() {
+        if plugin_manager.is_empty() {
             return;
         }
         for plugin in plugin_manager.plugins.iter() {
@@ -188,6 +191,9 @@ impl AccountsUpdateNotifierImpl {
                 100000
             );
         }
+        plugin_manager.buffer_notification(|| {
+            BufferedNotification::from_account_info(&account, slot, is_startup)
+        });
         measure2.stop();
         inc_new_counter_debug!(
             "geyser-plugin-notify_plugins_of_account_update-us",
@@ -1,7 +1,7 @@
 use {
     crate::{
         block_metadata_notifier_interface::BlockMetadataNotifier,
-        geyser_plugin_manager::GeyserPluginManager,
+        geyser_plugin_manager::GeyserPluginManager, notification_buffer::BufferedNotification,
     },
     agave_geyser_plugin_interface::geyser_plugin_interface::{
         ReplicaBlockInfoV4, ReplicaBlockInfoVersions,
@@ -34,7 +34,7 @@ impl BlockMetadataNotifier for BlockMetadataNotifierImpl {
         entry_count: u64,
     ) {
         let plugin_manager = self.plugin_manager.read().unwrap();
-        if plugin_manager.plugins.is_empty() {
+        if plugin_manager.is_empty() {
             return;
         }
 
@@ -79,6 +79,7 @@ impl BlockMetadataNotifier for BlockMetadataNotifierImpl {
                 1000
             );
         }
+        plugin_manager.buffer_notification(|| BufferedNotification::from_block_info(&block_info));
     }
 }
 
@@ -1,6 +1,8 @@
 /// Module responsible for notifying plugins about entries
 use {
-    crate::geyser_plugin_manager::GeyserPluginManager,
+    crate::{
+        geyser_plugin_manager::GeyserPluginManager, notification_buffer::BufferedNotification,
+    },
     agave_geyser_plugin_interface::geyser_plugin_interface::{
         ReplicaEntryInfoV2, ReplicaEntryInfoVersions,
     },
@@ -28,7 +30,7 @@ impl EntryNotifier for EntryNotifierImpl {
         let mut measure = Measure::start("geyser-plugin-notify_plugins_of_entry_info");
 
         let plugin_manager = self.plugin_manager.read().unwrap();
-        if plugin_manager.plugins.is_empty() {
+        if plugin_manager.is_empty() {
             return;
         }
 
@@ -52,6 +54,7 @@ impl EntryNotifier for EntryNotifierImpl {
                 }
             }
         }
+        plugin_manager.buffer_notification(|| BufferedNotification::from_entry_info(&entry_info));
         measure.stop();
         inc_new_counter_debug!(
             "geyser-plugin-notify_plugins_of_entry_info-us",
@@ -1,11 +1,17 @@
 use {
-    agave_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin,
+    crate::notification_buffer::{BufferedNotification, NotificationBuffer},
+    agave_geyser_plugin_interface::geyser_plugin_interface::{
+        GeyserPlugin, GEYSER_PLUGIN_INTERFACE_VERSION,
+    },
     jsonrpc_core::{ErrorCode, Result as JsonRpcResult},
     libloading::Library,
     log::*,
+    solana_measure::measure::Measure,
+    solana_metrics::datapoint_info,
     std::{
         ops::{Deref, DerefMut},
         path::Path,
+        sync::RwLock,
     },
     tokio::sync::oneshot::Sender as OneShotSender,
 };
@@ -57,12 +63,33 @@ impl DerefMut for LoadedGeyserPlugin {
 #[derive(Default, Debug)]
 pub struct GeyserPluginManager {
     pub plugins: Vec<LoadedGeyserPlugin>,
+    /// Notifications for plugins which are in the middle of being reloaded
+    reloading: Vec<NotificationBuffer>,
 }
 
 impl GeyserPluginManager {
     pub fn new() -> Self {
         GeyserPluginManager {
             plugins: Vec::default(),
+            reloading: Vec::default(),
+        }
+    }
+
+    /// Check if there are neither loaded plugins nor plugins being reloaded,
+    /// in which case there is nobody to notify.
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty() && self.reloading.is_empty()
+    }
+
+    /// Hold on to a notification for every plugin which is being reloaded, so
+    /// it can be replayed once the new instance of the plugin is loaded.
+    pub(crate) fn buffer_notification(&self, notification: impl FnOnce() -> BufferedNotification) {
+        if self.reloading.is_empty() {
+            return;
+        }
+        let notification = notification();
+        for buffer in self.reloading.iter() {
+            buffer.push(&notification);
         }
     }
 
@@ -82,7 +109,9 @@ impl GeyserPluginManager {
                 return true;
             }
         }
-        false
+        self.reloading
+            .iter()
+            .any(|buffer| buffer.account_data_notifications_enabled())
     }
 
     /// Check if there is any plugin interested in transaction data
@@ -92,7 +121,9 @@ impl GeyserPluginManager {
                 return true;
             }
         }
-        false
+        self.reloading
+            .iter()
+            .any(|buffer| buffer.transaction_notifications_enabled())
     }
 
     /// Check if there is any plugin interested in entry data
@@ -102,12 +133,19 @@ impl GeyserPluginManager {
                 return true;
             }
         }
-        false
+        self.reloading
+            .iter()
+            .any(|buffer| buffer.entry_notifications_enabled())
     }
 
     /// Admin RPC request handler
     pub(crate) fn list_plugins(&self) -> JsonRpcResult<Vec<String>> {
-        Ok(self.plugins.iter().map(|p| p.name().to_owned()).collect())
+        Ok(self
+            .plugins
+            .iter()
+            .map(|p| p.name().to_owned())
+            .chain(self.reloading.iter().map(|b| b.name().to_owned()))
+            .collect())
     }
 
     /// Admin RPC request handler
@@ -191,36 +229,96 @@ impl GeyserPluginManager {
     /// Checks for a plugin with a given `name`.
     /// If it exists, first unload it.
     /// Then, attempt to load a new plugin
-    pub(crate) fn reload_plugin(&mut self, name: &str, config_file: &str) -> JsonRpcResult<()> {
-        // Check if any plugin names match this one
-        let Some(idx) = self
-            .plugins
-            .iter()
-            .position(|plugin| plugin.name().eq(name))
-        else {
-            // If we don't find one return an error
-            return Err(jsonrpc_core::error::Error {
-                code: ErrorCode::InvalidRequest,
-                message: String::from("The plugin you requested to reload is not loaded"),
-                data: None,
-            });
+    ///
+    /// The manager is only locked while swapping the plugins out and in, so notifications
+    /// are not held up while the new plugin starts. Notifications received in the meantime
+    /// are buffered and replayed to the new plugin before it receives any new ones.
+    pub(crate) fn reload_plugin(
+        plugin_manager: &RwLock<Self>,
+        name: &str,
+        config_file: &str,
+    ) -> JsonRpcResult<()> {
+        let mut measure = Measure::start("geyser-plugin-reload");
+        // Check if any plugin names match this one, and start buffering notifications for it
+        let mut current_plugin = {
+            let mut plugin_manager = plugin_manager.write().unwrap();
+            let Some(idx) = plugin_manager
+                .plugins
+                .iter()
+                .position(|plugin| plugin.name().eq(name))
+            else {
+                // If we don't find one return an error
+                return Err(jsonrpc_core::error::Error {
+                    code: ErrorCode::InvalidRequest,
+                    message: String::from("The plugin you requested to reload is not loaded"),
+                    data: None,
+                });
+            };
+            let current_plugin = plugin_manager.plugins.remove(idx);
+            plugin_manager
+                .reloading
+                .push(NotificationBuffer::new(&current_plugin));
+            current_plugin
         };
 
         // Unload and drop current plugin first in case plugin requires exclusive access to resource,
         // such as a particular port or database.
-        self._drop_plugin(idx);
+        current_plugin.on_unload();
+        drop(current_plugin);
+        info!("Unloaded plugin {name} for reload");
+
+        let new_plugin = Self::start_reloaded_plugin(plugin_manager, name, config_file);
 
+        // Swap in the new plugin, replaying everything it missed while starting
+        let mut plugin_manager = plugin_manager.write().unwrap();
+        let idx = plugin_manager
+            .reloading
+            .iter()
+            .position(|buffer| buffer.name().eq(name))
+            .expect("reloading plugin is buffered");
+        let buffer = plugin_manager.reloading.remove(idx);
+        let new_plugin = new_plugin?;
+        let (num_replayed, num_dropped) = buffer.replay(&new_plugin);
+        if num_dropped > 0 {
+            error!(
+                "Dropped {num_dropped} notifications while reloading plugin {name}, \
+                 the buffer was full"
+            );
+        }
+        let new_name = new_plugin.name().to_string();
+        plugin_manager.plugins.push(new_plugin);
+        measure.stop();
+
+        datapoint_info!(
+            "geyser-plugin-reload",
+            ("name", new_name, String),
+            ("num_replayed", num_replayed, i64),
+            ("num_dropped", num_dropped, i64),
+            ("reload_us", measure.as_us(), i64),
+        );
+        Ok(())
+    }
+
+    /// Loads the library for a plugin being reloaded and calls its `on_load` method,
+    /// without holding the manager lock
+    fn start_reloaded_plugin(
+        plugin_manager: &RwLock<Self>,
+        name: &str,
+        config_file: &str,
+    ) -> JsonRpcResult<LoadedGeyserPlugin> {
         // Try to load plugin, library
         // SAFETY: It is up to the validator to ensure this is a valid plugin library.
         let (mut new_plugin, new_parsed_config_file) =
             load_plugin_from_config(config_file.as_ref()).map_err(|err| jsonrpc_core::Error {
                 code: ErrorCode::InvalidRequest,
-                message: err.to_string(),
+                message: format!("Failed to load new plugin (previous plugin was dropped!): {err}"),
                 data: None,
             })?;
 
         // Then see if a plugin with this name already exists. If so, abort
-        if self
+        if plugin_manager
+            .read()
+            .unwrap()
             .plugins
             .iter()
             .any(|plugin| plugin.name().eq(new_plugin.name()))
@@ -238,25 +336,17 @@ impl GeyserPluginManager {
         setup_logger_for_plugin(&*new_plugin.plugin)?;
 
         // Attempt to on_load with new plugin
-        match new_plugin.on_load(new_parsed_config_file, true) {
-            // On success, push plugin and library
-            Ok(()) => {
-                self.plugins.push(new_plugin);
-            }
-
-            // On failure, return error
-            Err(err) => {
-                return Err(jsonrpc_core::error::Error {
-                    code: ErrorCode::InvalidRequest,
-                    message: format!(
-                        "Failed to start new plugin (previous plugin was dropped!): {err}"
-                    ),
-                    data: None,
-                });
-            }
-        }
+        new_plugin
+            .on_load(new_parsed_config_file, true)
+            .map_err(|err| jsonrpc_core::error::Error {
+                code: ErrorCode::InvalidRequest,
+                message: format!(
+                    "Failed to start new plugin (previous plugin was dropped!): {err}"
+                ),
+                data: None,
+            })?;
 
-        Ok(())
+        Ok(new_plugin)
     }
 
     fn _drop_plugin(&mut self, idx: usize) {
@@ -326,6 +416,36 @@ pub enum GeyserPluginManagerError {
 
     #[error("The GeyserPlugin on_load method failed (error: {0})")]
     PluginStartError(String),
+
+    #[error(
+        "The plugin was built against geyser plugin interface version {found}, expected version {expected}"
+    )]
+    IncompatibleInterfaceVersion { expected: u32, found: u32 },
+}
+
+/// Checks the interface version exported by a plugin library against the version
+/// of the interface the validator was built with. Plugins which do not export a
+/// version predate the handshake and are loaded as before.
+fn check_plugin_interface_version(
+    plugin_version: Option<u32>,
+    libpath: &Path,
+) -> Result<(), GeyserPluginManagerError> {
+    match plugin_version {
+        Some(found) if found != GEYSER_PLUGIN_INTERFACE_VERSION => {
+            Err(GeyserPluginManagerError::IncompatibleInterfaceVersion {
+                expected: GEYSER_PLUGIN_INTERFACE_VERSION,
+                found,
+            })
+        }
+        Some(_) => Ok(()),
+        None => {
+            warn!(
+                "Plugin library {libpath:?} does not export _geyser_plugin_interface_version, \
+                 unable to check its interface version"
+            );
+            Ok(())
+        }
+    }
 }
 
 /// # Safety
@@ -341,6 +461,7 @@ pub(crate) fn load_plugin_from_config(
 ) -> Result<(LoadedGeyserPlugin, &str), GeyserPluginManagerError> {
     use std::{fs::File, io::Read, path::PathBuf};
     type PluginConstructor = unsafe fn() -> *mut dyn GeyserPlugin;
+    type PluginInterfaceVersion = unsafe extern "C" fn() -> u32;
     use libloading::Symbol;
 
     let mut file = match File::open(geyser_plugin_config_file) {
@@ -389,8 +510,14 @@ pub(crate) fn load_plugin_from_config(
         .ok_or(GeyserPluginManagerError::InvalidPluginPath)?;
 
     let (plugin, lib) = unsafe {
-        let lib = Library::new(libpath)
+        let lib = Library::new(&libpath)
             .map_err(|e| GeyserPluginManagerError::PluginLoadError(e.to_string()))?;
+        // Reject plugins built against an incompatible interface before calling into them
+        let plugin_version = lib
+            .get::<PluginInterfaceVersion>(b"_geyser_plugin_interface_version")
+            .ok()
+            .map(|version| version());
+        check_plugin_interface_version(plugin_version, &libpath)?;
         let constructor: Symbol<PluginConstructor> = lib
             .get(b"_create_plugin")
             .map_err(|e| GeyserPluginManagerError::PluginLoadError(e.to_string()))?;
@@ -437,12 +564,23 @@ pub(crate) fn load_plugin_from_config(
 #[cfg(test)]
 mod tests {
     use {
-        crate::geyser_plugin_manager::{
-            GeyserPluginManager, LoadedGeyserPlugin, TESTPLUGIN2_CONFIG, TESTPLUGIN_CONFIG,
+        crate::{
+            geyser_plugin_manager::{
+                check_plugin_interface_version, GeyserPluginManager, GeyserPluginManagerError,
+                LoadedGeyserPlugin, TESTPLUGIN2_CONFIG, TESTPLUGIN_CONFIG,
+            },
+            notification_buffer::{BufferedNotification, NotificationBuffer},
+        },
+        agave_geyser_plugin_interface::geyser_plugin_interface::{
+            GeyserPlugin, ReplicaAccountInfoV3, ReplicaAccountInfoVersions, Result as PluginResult,
+            SlotStatus, GEYSER_PLUGIN_INTERFACE_VERSION,
         },
-        agave_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin,
         libloading::Library,
-        std::sync::{Arc, RwLock},
+        solana_sdk::{clock::Slot, pubkey::Pubkey},
+        std::{
+            path::Path,
+            sync::{Arc, Mutex, RwLock},
+        },
     };
 
     pub(super) fn dummy_plugin_and_library<P: GeyserPlugin>(
@@ -487,8 +625,8 @@ mod tests {
         let plugin_manager = Arc::new(RwLock::new(GeyserPluginManager::new()));
 
         // No plugins are loaded, this should fail
-        let mut plugin_manager_lock = plugin_manager.write().unwrap();
-        let reload_result = plugin_manager_lock.reload_plugin(DUMMY_NAME, DUMMY_CONFIG);
+        let reload_result =
+            GeyserPluginManager::reload_plugin(&plugin_manager, DUMMY_NAME, DUMMY_CONFIG);
         assert_eq!(
             reload_result.unwrap_err().message,
             "The plugin you requested to reload is not loaded"
@@ -497,27 +635,171 @@ mod tests {
         // Mock having loaded plugin (TestPlugin)
         let (mut plugin, config) = dummy_plugin_and_library(TestPlugin, DUMMY_CONFIG);
         plugin.on_load(config, false).unwrap();
-        plugin_manager_lock.plugins.push(plugin);
-        assert_eq!(plugin_manager_lock.plugins[0].name(), DUMMY_NAME);
-        plugin_manager_lock.plugins[0].name();
+        plugin_manager.write().unwrap().plugins.push(plugin);
+        assert_eq!(plugin_manager.read().unwrap().plugins[0].name(), DUMMY_NAME);
 
         // Try wrong name (same error)
         const WRONG_NAME: &str = "wrong_name";
-        let reload_result = plugin_manager_lock.reload_plugin(WRONG_NAME, DUMMY_CONFIG);
+        let reload_result =
+            GeyserPluginManager::reload_plugin(&plugin_manager, WRONG_NAME, DUMMY_CONFIG);
         assert_eq!(
             reload_result.unwrap_err().message,
             "The plugin you requested to reload is not loaded"
         );
 
         // Now try a (dummy) reload, replacing TestPlugin with TestPlugin2
-        let reload_result = plugin_manager_lock.reload_plugin(DUMMY_NAME, TESTPLUGIN2_CONFIG);
+        let reload_result =
+            GeyserPluginManager::reload_plugin(&plugin_manager, DUMMY_NAME, TESTPLUGIN2_CONFIG);
         assert!(reload_result.is_ok());
 
         // The plugin is now replaced with ANOTHER_DUMMY_NAME
+        let plugin_manager_lock = plugin_manager.read().unwrap();
         let plugins = plugin_manager_lock.list_plugins().unwrap();
         assert!(plugins.iter().any(|name| name.eq(ANOTHER_DUMMY_NAME)));
         // DUMMY_NAME should no longer be present.
         assert!(!plugins.iter().any(|name| name.eq(DUMMY_NAME)));
+        // Nothing is left buffering
+        assert!(plugin_manager_lock.reloading.is_empty());
+    }
+
+    #[test]
+    fn test_geyser_reload_failure() {
+        let plugin_manager = Arc::new(RwLock::new(GeyserPluginManager::new()));
+        let (mut plugin, config) = dummy_plugin_and_library(TestPlugin, DUMMY_CONFIG);
+        plugin.on_load(config, false).unwrap();
+        plugin_manager.write().unwrap().plugins.push(plugin);
+
+        // The new plugin fails to load, the previous one is gone and nothing is left buffering
+        let reload_result =
+            GeyserPluginManager::reload_plugin(&plugin_manager, DUMMY_NAME, DUMMY_CONFIG);
+        assert!(reload_result.is_err());
+        let plugin_manager_lock = plugin_manager.read().unwrap();
+        assert!(plugin_manager_lock.is_empty());
+        assert!(plugin_manager_lock.list_plugins().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_check_plugin_interface_version() {
+        let libpath = Path::new("libdummy.so");
+        assert!(check_plugin_interface_version(None, libpath).is_ok());
+        assert!(
+            check_plugin_interface_version(Some(GEYSER_PLUGIN_INTERFACE_VERSION), libpath).is_ok()
+        );
+        assert!(matches!(
+            check_plugin_interface_version(Some(GEYSER_PLUGIN_INTERFACE_VERSION + 1), libpath),
+            Err(GeyserPluginManagerError::IncompatibleInterfaceVersion { expected, found })
+                if expected == GEYSER_PLUGIN_INTERFACE_VERSION
+                    && found == GEYSER_PLUGIN_INTERFACE_VERSION + 1
+        ));
+    }
+
+    #[derive(Debug, Default)]
+    struct SlotRecordingPlugin {
+        slots: Arc<Mutex<Vec<Slot>>>,
+    }
+
+    impl GeyserPlugin for SlotRecordingPlugin {
+        fn name(&self) -> &'static str {
+            DUMMY_NAME
+        }
+
+        fn update_slot_status(
+            &self,
+            slot: Slot,
+            _parent: Option<u64>,
+            _status: &SlotStatus,
+        ) -> PluginResult<()> {
+            self.slots.lock().unwrap().push(slot);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_buffered_notifications_replayed() {
+        let mut plugin_manager = GeyserPluginManager::new();
+        let (old_plugin, _config) = dummy_plugin_and_library(TestPlugin, DUMMY_CONFIG);
+
+        // Nothing is buffered while no plugin is reloading
+        plugin_manager.buffer_notification(|| panic!("no plugin is reloading"));
+
+        // Notifications arriving while the plugin is reloading are buffered, in order
+        plugin_manager
+            .reloading
+            .push(NotificationBuffer::new(&old_plugin));
+        assert!(!plugin_manager.is_empty());
+        for slot in [3, 1, 2] {
+            plugin_manager.buffer_notification(|| BufferedNotification::SlotStatus {
+                slot,
+                parent: None,
+                status: SlotStatus::Processed,
+            });
+        }
+
+        let slots = Arc::<Mutex<Vec<Slot>>>::default();
+        let (new_plugin, _config) = dummy_plugin_and_library(
+            SlotRecordingPlugin {
+                slots: slots.clone(),
+            },
+            DUMMY_CONFIG,
+        );
+        let buffer = plugin_manager.reloading.pop().unwrap();
+        assert_eq!(buffer.replay(&new_plugin), (3, 0));
+        assert_eq!(*slots.lock().unwrap(), vec![3, 1, 2]);
+    }
+
+    #[derive(Debug, Default)]
+    struct AccountRecordingPlugin {
+        slots: Arc<Mutex<Vec<Slot>>>,
+    }
+
+    impl GeyserPlugin for AccountRecordingPlugin {
+        fn name(&self) -> &'static str {
+            DUMMY_NAME
+        }
+
+        fn update_account(
+            &self,
+            _account: ReplicaAccountInfoVersions,
+            slot: Slot,
+            _is_startup: bool,
+        ) -> PluginResult<()> {
+            self.slots.lock().unwrap().push(slot);
+            Ok(())
+        }
+
+        fn account_data_notifications_enabled(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_buffered_account_notifications_not_replayed_when_disabled() {
+        let (old_plugin, _config) = dummy_plugin_and_library(TestPlugin, DUMMY_CONFIG);
+        assert!(old_plugin.account_data_notifications_enabled());
+        let buffer = NotificationBuffer::new(&old_plugin);
+        let pubkey = Pubkey::new_unique();
+        let account = ReplicaAccountInfoV3 {
+            pubkey: pubkey.as_ref(),
+            lamports: 1,
+            owner: pubkey.as_ref(),
+            executable: false,
+            rent_epoch: 0,
+            data: &[],
+            write_version: 0,
+            txn: None,
+        };
+        buffer.push(&BufferedNotification::from_account_info(&account, 1, false));
+
+        // The new instance no longer wants account data, so the buffered update is skipped
+        let slots = Arc::<Mutex<Vec<Slot>>>::default();
+        let (new_plugin, _config) = dummy_plugin_and_library(
+            AccountRecordingPlugin {
+                slots: slots.clone(),
+            },
+            DUMMY_CONFIG,
+        );
+        assert_eq!(buffer.replay(&new_plugin), (1, 0));
+        assert!(slots.lock().unwrap().is_empty());
     }
 
     #[test]
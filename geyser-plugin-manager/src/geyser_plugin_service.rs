@@ -222,10 +222,11 @@ impl GeyserPluginService {
                             ref config_file,
                             response_sender,
                         } => {
-                            let reload_result = plugin_manager
-                                .write()
-                                .unwrap()
-                                .reload_plugin(name, config_file);
+                            let reload_result = GeyserPluginManager::reload_plugin(
+                                &plugin_manager,
+                                name,
+                                config_file,
+                            );
                             response_sender
                                 .send(reload_result)
                                 .expect("Admin rpc service will be waiting for response");
@@ -4,6 +4,7 @@ pub mod block_metadata_notifier_interface;
 pub mod entry_notifier;
 pub mod geyser_plugin_manager;
 pub mod geyser_plugin_service;
+pub mod notification_buffer;
 pub mod slot_status_notifier;
 pub mod slot_status_observer;
 pub mod transaction_notifier;
@@ -0,0 +1,459 @@
+/// Module responsible for holding on to notifications destined for a plugin which
+/// is being reloaded, so they can be replayed to the new instance once it is loaded.
+use {
+    crate::geyser_plugin_manager::LoadedGeyserPlugin,
+    agave_geyser_plugin_interface::geyser_plugin_interface::{
+        ReplicaAbandonedSlotInfo, ReplicaAbandonedSlotInfoVersions, ReplicaAccountInfoV3,
+        ReplicaAccountInfoVersions, ReplicaBlockInfoV4, ReplicaBlockInfoVersions,
+        ReplicaEntryInfoV2, ReplicaEntryInfoVersions, ReplicaTransactionInfoV2,
+        ReplicaTransactionInfoVersions, Result, SlotAbandonedReason, SlotStatus,
+    },
+    log::*,
+    solana_sdk::{
+        clock::{Slot, UnixTimestamp},
+        hash::Hash,
+        pubkey::Pubkey,
+        signature::Signature,
+        transaction::SanitizedTransaction,
+    },
+    solana_transaction_status::{Reward, RewardsAndNumPartitions, TransactionStatusMeta},
+    std::{mem::size_of, sync::Mutex},
+};
+
+/// The maximum number of bytes of notifications buffered for a single plugin while
+/// it is being reloaded. Any notifications beyond this are dropped and counted.
+pub const MAX_BUFFERED_NOTIFICATION_BYTES: usize = 1024 * 1024 * 1024;
+
+/// An owned copy of a notification, see the corresponding `GeyserPlugin` method
+/// for the meaning of each field.
+#[derive(Clone, Debug)]
+pub(crate) enum BufferedNotification {
+    Account {
+        slot: Slot,
+        is_startup: bool,
+        pubkey: Pubkey,
+        lamports: u64,
+        owner: Pubkey,
+        executable: bool,
+        rent_epoch: u64,
+        data: Vec<u8>,
+        write_version: u64,
+        txn: Option<SanitizedTransaction>,
+    },
+    EndOfStartup,
+    SlotStatus {
+        slot: Slot,
+        parent: Option<Slot>,
+        status: SlotStatus,
+    },
+    SlotAbandoned {
+        slot: Slot,
+        parent: Option<Slot>,
+        ancestors: Vec<Slot>,
+        reason: SlotAbandonedReason,
+    },
+    Transaction {
+        slot: Slot,
+        index: usize,
+        signature: Signature,
+        is_vote: bool,
+        transaction: SanitizedTransaction,
+        transaction_status_meta: TransactionStatusMeta,
+    },
+    Entry {
+        slot: Slot,
+        index: usize,
+        num_hashes: u64,
+        hash: Hash,
+        executed_transaction_count: u64,
+        starting_transaction_index: usize,
+    },
+    BlockMetadata {
+        parent_slot: Slot,
+        parent_blockhash: String,
+        slot: Slot,
+        blockhash: String,
+        rewards: RewardsAndNumPartitions,
+        block_time: Option<UnixTimestamp>,
+        block_height: Option<u64>,
+        executed_transaction_count: u64,
+        entry_count: u64,
+    },
+}
+
+impl BufferedNotification {
+    pub(crate) fn from_account_info(
+        account: &ReplicaAccountInfoV3,
+        slot: Slot,
+        is_startup: bool,
+    ) -> Self {
+        Self::Account {
+            slot,
+            is_startup,
+            pubkey: Pubkey::try_from(account.pubkey).unwrap_or_default(),
+            lamports: account.lamports,
+            owner: Pubkey::try_from(account.owner).unwrap_or_default(),
+            executable: account.executable,
+            rent_epoch: account.rent_epoch,
+            data: account.data.to_vec(),
+            write_version: account.write_version,
+            txn: account.txn.cloned(),
+        }
+    }
+
+    pub(crate) fn from_transaction_info(
+        transaction_info: &ReplicaTransactionInfoV2,
+        slot: Slot,
+    ) -> Self {
+        Self::Transaction {
+            slot,
+            index: transaction_info.index,
+            signature: *transaction_info.signature,
+            is_vote: transaction_info.is_vote,
+            transaction: transaction_info.transaction.clone(),
+            transaction_status_meta: transaction_info.transaction_status_meta.clone(),
+        }
+    }
+
+    pub(crate) fn from_entry_info(entry_info: &ReplicaEntryInfoV2) -> Self {
+        Self::Entry {
+            slot: entry_info.slot,
+            index: entry_info.index,
+            num_hashes: entry_info.num_hashes,
+            hash: Hash::new(entry_info.hash),
+            executed_transaction_count: entry_info.executed_transaction_count,
+            starting_transaction_index: entry_info.starting_transaction_index,
+        }
+    }
+
+    pub(crate) fn from_block_info(block_info: &ReplicaBlockInfoV4) -> Self {
+        Self::BlockMetadata {
+            parent_slot: block_info.parent_slot,
+            parent_blockhash: block_info.parent_blockhash.to_string(),
+            slot: block_info.slot,
+            blockhash: block_info.blockhash.to_string(),
+            rewards: block_info.rewards.clone(),
+            block_time: block_info.block_time,
+            block_height: block_info.block_height,
+            executed_transaction_count: block_info.executed_transaction_count,
+            entry_count: block_info.entry_count,
+        }
+    }
+
+    /// An estimate of the memory held by this notification, used to bound the buffer
+    fn size(&self) -> usize {
+        let heap_size = match self {
+            Self::Account { data, txn, .. } => data
+                .len()
+                .saturating_add(txn.as_ref().map_or(0, transaction_size)),
+            Self::EndOfStartup | Self::SlotStatus { .. } | Self::Entry { .. } => 0,
+            Self::SlotAbandoned { ancestors, .. } => {
+                ancestors.len().saturating_mul(size_of::<Slot>())
+            }
+            Self::Transaction {
+                transaction,
+                transaction_status_meta,
+                ..
+            } => transaction_size(transaction)
+                .saturating_add(transaction_status_meta_size(transaction_status_meta)),
+            Self::BlockMetadata {
+                parent_blockhash,
+                blockhash,
+                rewards,
+                ..
+            } => parent_blockhash
+                .len()
+                .saturating_add(blockhash.len())
+                .saturating_add(rewards.rewards.len().saturating_mul(size_of::<Reward>())),
+        };
+        size_of::<Self>().saturating_add(heap_size)
+    }
+
+    /// Whether a plugin with the given notification settings is interested in this notification
+    fn is_enabled(
+        &self,
+        account_data_notifications_enabled: bool,
+        transaction_notifications_enabled: bool,
+        entry_notifications_enabled: bool,
+    ) -> bool {
+        match self {
+            Self::Account { .. } | Self::EndOfStartup => account_data_notifications_enabled,
+            Self::Transaction { .. } => transaction_notifications_enabled,
+            Self::Entry { .. } => entry_notifications_enabled,
+            Self::SlotStatus { .. } | Self::SlotAbandoned { .. } | Self::BlockMetadata { .. } => {
+                true
+            }
+        }
+    }
+
+    /// Delivers this notification to `plugin`
+    fn replay(&self, plugin: &LoadedGeyserPlugin) -> Result<()> {
+        match self {
+            Self::Account {
+                slot,
+                is_startup,
+                pubkey,
+                lamports,
+                owner,
+                executable,
+                rent_epoch,
+                data,
+                write_version,
+                txn,
+            } => {
+                if !plugin.account_data_notifications_enabled() {
+                    return Ok(());
+                }
+                let account = ReplicaAccountInfoV3 {
+                    pubkey: pubkey.as_ref(),
+                    lamports: *lamports,
+                    owner: owner.as_ref(),
+                    executable: *executable,
+                    rent_epoch: *rent_epoch,
+                    data,
+                    write_version: *write_version,
+                    txn: txn.as_ref(),
+                };
+                plugin.update_account(
+                    ReplicaAccountInfoVersions::V0_0_3(&account),
+                    *slot,
+                    *is_startup,
+                )
+            }
+            Self::EndOfStartup => plugin.notify_end_of_startup(),
+            Self::SlotStatus {
+                slot,
+                parent,
+                status,
+            } => plugin.update_slot_status(*slot, *parent, status),
+            Self::SlotAbandoned {
+                slot,
+                parent,
+                ancestors,
+                reason,
+            } => {
+                let slot_info = ReplicaAbandonedSlotInfo {
+                    slot: *slot,
+                    parent_slot: *parent,
+                    ancestors,
+                    reason,
+                };
+                plugin.notify_slot_abandoned(ReplicaAbandonedSlotInfoVersions::V0_0_1(&slot_info))
+            }
+            Self::Transaction {
+                slot,
+                index,
+                signature,
+                is_vote,
+                transaction,
+                transaction_status_meta,
+            } => {
+                if !plugin.transaction_notifications_enabled() {
+                    return Ok(());
+                }
+                let transaction_info = ReplicaTransactionInfoV2 {
+                    signature,
+                    is_vote: *is_vote,
+                    transaction,
+                    transaction_status_meta,
+                    index: *index,
+                };
+                plugin.notify_transaction(
+                    ReplicaTransactionInfoVersions::V0_0_2(&transaction_info),
+                    *slot,
+                )
+            }
+            Self::Entry {
+                slot,
+                index,
+                num_hashes,
+                hash,
+                executed_transaction_count,
+                starting_transaction_index,
+            } => {
+                if !plugin.entry_notifications_enabled() {
+                    return Ok(());
+                }
+                let entry_info = ReplicaEntryInfoV2 {
+                    slot: *slot,
+                    index: *index,
+                    num_hashes: *num_hashes,
+                    hash: hash.as_ref(),
+                    executed_transaction_count: *executed_transaction_count,
+                    starting_transaction_index: *starting_transaction_index,
+                };
+                plugin.notify_entry(ReplicaEntryInfoVersions::V0_0_2(&entry_info))
+            }
+            Self::BlockMetadata {
+                parent_slot,
+                parent_blockhash,
+                slot,
+                blockhash,
+                rewards,
+                block_time,
+                block_height,
+                executed_transaction_count,
+                entry_count,
+            } => {
+                let block_info = ReplicaBlockInfoV4 {
+                    parent_slot: *parent_slot,
+                    parent_blockhash,
+                    slot: *slot,
+                    blockhash,
+                    rewards,
+                    block_time: *block_time,
+                    block_height: *block_height,
+                    executed_transaction_count: *executed_transaction_count,
+                    entry_count: *entry_count,
+                };
+                plugin.notify_block_metadata(ReplicaBlockInfoVersions::V0_0_4(&block_info))
+            }
+        }
+    }
+}
+
+/// An estimate of the heap memory held by `transaction`
+fn transaction_size(transaction: &SanitizedTransaction) -> usize {
+    let message = transaction.message();
+    let instructions_size: usize = message
+        .instructions()
+        .iter()
+        .map(|instruction| {
+            instruction
+                .data
+                .len()
+                .saturating_add(instruction.accounts.len())
+        })
+        .sum();
+    transaction
+        .signatures()
+        .len()
+        .saturating_mul(size_of::<Signature>())
+        .saturating_add(
+            message
+                .account_keys()
+                .len()
+                .saturating_mul(size_of::<Pubkey>()),
+        )
+        .saturating_add(instructions_size)
+}
+
+/// An estimate of the heap memory held by `meta`, leaving out the small fields
+fn transaction_status_meta_size(meta: &TransactionStatusMeta) -> usize {
+    let balances_size = meta
+        .pre_balances
+        .len()
+        .saturating_add(meta.post_balances.len())
+        .saturating_mul(size_of::<u64>());
+    let inner_instructions_size: usize = meta
+        .inner_instructions
+        .iter()
+        .flatten()
+        .flat_map(|inner_instructions| inner_instructions.instructions.iter())
+        .map(|inner_instruction| {
+            let instruction = &inner_instruction.instruction;
+            instruction
+                .data
+                .len()
+                .saturating_add(instruction.accounts.len())
+        })
+        .sum();
+    let log_messages_size: usize = meta
+        .log_messages
+        .iter()
+        .flatten()
+        .map(|log_message| log_message.len())
+        .sum();
+    let return_data_size = meta
+        .return_data
+        .as_ref()
+        .map_or(0, |return_data| return_data.data.len());
+    balances_size
+        .saturating_add(inner_instructions_size)
+        .saturating_add(log_messages_size)
+        .saturating_add(return_data_size)
+}
+
+#[derive(Debug, Default)]
+struct NotificationBufferInner {
+    notifications: Vec<BufferedNotification>,
+    num_bytes: usize,
+    num_dropped: usize,
+}
+
+/// Notifications received for a plugin while it is being reloaded. The notification
+/// settings of the previous instance decide which notifications are kept.
+#[derive(Debug)]
+pub(crate) struct NotificationBuffer {
+    name: String,
+    account_data_notifications_enabled: bool,
+    transaction_notifications_enabled: bool,
+    entry_notifications_enabled: bool,
+    inner: Mutex<NotificationBufferInner>,
+}
+
+impl NotificationBuffer {
+    pub(crate) fn new(plugin: &LoadedGeyserPlugin) -> Self {
+        Self {
+            name: plugin.name().to_string(),
+            account_data_notifications_enabled: plugin.account_data_notifications_enabled(),
+            transaction_notifications_enabled: plugin.transaction_notifications_enabled(),
+            entry_notifications_enabled: plugin.entry_notifications_enabled(),
+            inner: Mutex::default(),
+        }
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn account_data_notifications_enabled(&self) -> bool {
+        self.account_data_notifications_enabled
+    }
+
+    pub(crate) fn transaction_notifications_enabled(&self) -> bool {
+        self.transaction_notifications_enabled
+    }
+
+    pub(crate) fn entry_notifications_enabled(&self) -> bool {
+        self.entry_notifications_enabled
+    }
+
+    pub(crate) fn push(&self, notification: &BufferedNotification) {
+        if !notification.is_enabled(
+            self.account_data_notifications_enabled,
+            self.transaction_notifications_enabled,
+            self.entry_notifications_enabled,
+        ) {
+            return;
+        }
+        let size = notification.size();
+        let mut inner = self.inner.lock().unwrap();
+        if inner.num_bytes.saturating_add(size) > MAX_BUFFERED_NOTIFICATION_BYTES {
+            inner.num_dropped = inner.num_dropped.saturating_add(1);
+        } else {
+            inner.num_bytes = inner.num_bytes.saturating_add(size);
+            inner.notifications.push(notification.clone());
+        }
+    }
+
+    /// Delivers all buffered notifications to `plugin` in the order they were received.
+    /// Returns the number of notifications replayed and the number which had been dropped.
+    pub(crate) fn replay(self, plugin: &LoadedGeyserPlugin) -> (usize, usize) {
+        let NotificationBufferInner {
+            notifications,
+            num_bytes: _,
+            num_dropped,
+        } = self.inner.into_inner().unwrap();
+        for notification in notifications.iter() {
+            if let Err(err) = notification.replay(plugin) {
+                error!(
+                    "Failed to replay buffered notification to plugin {}, error: {}",
+                    plugin.name(),
+                    err
+                );
+            }
+        }
+        (notifications.len(), num_dropped)
+    }
+}
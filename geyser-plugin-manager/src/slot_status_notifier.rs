@@ -1,6 +1,10 @@
 use {
-    crate::geyser_plugin_manager::GeyserPluginManager,
-    agave_geyser_plugin_interface::geyser_plugin_interface::SlotStatus,
+    crate::{
+        geyser_plugin_manager::GeyserPluginManager, notification_buffer::BufferedNotification,
+    },
+    agave_geyser_plugin_interface::geyser_plugin_interface::{
+        ReplicaAbandonedSlotInfo, ReplicaAbandonedSlotInfoVersions, SlotAbandonedReason, SlotStatus,
+    },
     log::*,
     solana_measure::measure::Measure,
     solana_metrics::*,
@@ -38,8 +42,19 @@ impl SlotStatusNotifierInterface for SlotStatusNotifierImpl {
         self.notify_slot_status(slot, Some(parent), SlotStatus::CreatedBank);
     }
 
-    fn notify_slot_dead(&self, slot: Slot, error: String) {
-        self.notify_slot_status(slot, None, SlotStatus::Dead(error));
+    fn notify_slot_dead(
+        &self,
+        slot: Slot,
+        parent: Option<Slot>,
+        ancestors: &[Slot],
+        error: String,
+    ) {
+        self.notify_slot_status(slot, parent, SlotStatus::Dead(error.clone()));
+        self.notify_slot_abandoned(slot, parent, ancestors, SlotAbandonedReason::Dead(error));
+    }
+
+    fn notify_slot_pruned(&self, slot: Slot, parent: Option<Slot>, ancestors: &[Slot]) {
+        self.notify_slot_abandoned(slot, parent, ancestors, SlotAbandonedReason::Pruned);
     }
 }
 
@@ -50,7 +65,7 @@ impl SlotStatusNotifierImpl {
 
     pub fn notify_slot_status(&self, slot: Slot, parent: Option<Slot>, slot_status: SlotStatus) {
         let plugin_manager = self.plugin_manager.read().unwrap();
-        if plugin_manager.plugins.is_empty() {
+        if plugin_manager.is_empty() {
             return;
         }
 
@@ -81,5 +96,67 @@ impl SlotStatusNotifierImpl {
                 1000
             );
         }
+        plugin_manager.buffer_notification(|| BufferedNotification::SlotStatus {
+            slot,
+            parent,
+            status: slot_status,
+        });
+    }
+
+    pub fn notify_slot_abandoned(
+        &self,
+        slot: Slot,
+        parent: Option<Slot>,
+        ancestors: &[Slot],
+        reason: SlotAbandonedReason,
+    ) {
+        let plugin_manager = self.plugin_manager.read().unwrap();
+        if plugin_manager.is_empty() {
+            return;
+        }
+
+        let slot_info = ReplicaAbandonedSlotInfo {
+            slot,
+            parent_slot: parent,
+            ancestors,
+            reason: &reason,
+        };
+
+        for plugin in plugin_manager.plugins.iter() {
+            let mut measure = Measure::start("geyser-plugin-slot-abandoned");
+            let slot_info = ReplicaAbandonedSlotInfoVersions::V0_0_1(&slot_info);
+            match plugin.notify_slot_abandoned(slot_info) {
+                Err(err) => {
+                    error!(
+                        "Failed to notify {} slot {}, error: {} to plugin {}",
+                        reason.as_str(),
+                        slot,
+                        err,
+                        plugin.name()
+                    )
+                }
+                Ok(_) => {
+                    trace!(
+                        "Successfully notified {} slot {} to plugin {}",
+                        reason.as_str(),
+                        slot,
+                        plugin.name()
+                    );
+                }
+            }
+            measure.stop();
+            inc_new_counter_debug!(
+                "geyser-plugin-slot-abandoned-us",
+                measure.as_us() as usize,
+                1000,
+                1000
+            );
+        }
+        plugin_manager.buffer_notification(|| BufferedNotification::SlotAbandoned {
+            slot,
+            parent,
+            ancestors: ancestors.to_vec(),
+            reason,
+        });
     }
 }
@@ -1,6 +1,8 @@
 /// Module responsible for notifying plugins of transactions
 use {
-    crate::geyser_plugin_manager::GeyserPluginManager,
+    crate::{
+        geyser_plugin_manager::GeyserPluginManager, notification_buffer::BufferedNotification,
+    },
     agave_geyser_plugin_interface::geyser_plugin_interface::{
         ReplicaTransactionInfoV2, ReplicaTransactionInfoVersions,
     },
@@ -40,7 +42,7 @@ impl TransactionNotifier for TransactionNotifierImpl {
 
         let plugin_manager = self.plugin_manager.read().unwrap();
 
-        if plugin_manager.plugins.is_empty() {
+        if plugin_manager.is_empty() {
             return;
         }
 
@@ -67,6 +69,9 @@ impl TransactionNotifier for TransactionNotifierImpl {
                 }
             }
         }
+        plugin_manager.buffer_notification(|| {
+            BufferedNotification::from_transaction_info(&transaction_log_info, slot)
+        });
         measure.stop();
         inc_new_counter_debug!(
             "geyser-plugin-notify_plugins_of_transaction_info-us",
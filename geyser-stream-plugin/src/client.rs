@@ -0,0 +1,28 @@
+use {
+    crate::message::{read_frame, write_frame, FrameError, StreamMessage, SubscribeRequest},
+    std::{
+        io::BufReader,
+        net::{SocketAddr, TcpStream},
+    },
+};
+
+/// A blocking client for the stream plugin
+pub struct StreamClient {
+    reader: BufReader<TcpStream>,
+}
+
+impl StreamClient {
+    /// Connects to the plugin at `addr` and subscribes with `request`
+    pub fn connect(addr: SocketAddr, request: &SubscribeRequest) -> Result<Self, FrameError> {
+        let mut stream = TcpStream::connect(addr)?;
+        write_frame(&mut stream, request)?;
+        Ok(Self {
+            reader: BufReader::new(stream),
+        })
+    }
+
+    /// Waits for the next message from the plugin
+    pub fn recv(&mut self) -> Result<StreamMessage, FrameError> {
+        read_frame(&mut self.reader)
+    }
+}
@@ -0,0 +1,125 @@
+use {
+    agave_geyser_plugin_interface::geyser_plugin_interface::{GeyserPluginError, Result},
+    serde_derive::Deserialize,
+    std::{fs, net::SocketAddr, path::Path},
+};
+
+const DEFAULT_MAX_CLIENTS: usize = 64;
+const DEFAULT_CLIENT_QUEUE_CAPACITY: usize = 100_000;
+const DEFAULT_REPLAY_WINDOW_SLOTS: u64 = 32;
+const DEFAULT_REPLAY_WINDOW_MAX_BYTES: usize = 0;
+const DEFAULT_BROADCAST_QUEUE_CAPACITY: usize = 100_000;
+const DEFAULT_MAX_FILTER_KEYS: usize = 10_000;
+
+/// The plugin configuration, read from the same JSON5 file that holds the `libpath`
+/// used by the validator to load the plugin.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamPluginConfig {
+    /// The address to accept client connections on
+    pub bind_address: SocketAddr,
+
+    /// Connections beyond this number are refused
+    #[serde(default = "default_max_clients")]
+    pub max_clients: usize,
+
+    /// The number of updates queued for a client before it is considered too slow
+    /// and is disconnected
+    #[serde(default = "default_client_queue_capacity")]
+    pub client_queue_capacity: usize,
+
+    /// Updates for this many of the most recent slots are kept so that reconnecting
+    /// clients can catch up
+    #[serde(default = "default_replay_window_slots")]
+    pub replay_window_slots: u64,
+
+    /// Upper bound on the encoded size of the updates kept in the replay window. Zero, the
+    /// default, disables the replay window.
+    #[serde(default = "default_replay_window_max_bytes")]
+    pub replay_window_max_bytes: usize,
+
+    /// The number of updates queued for encoding and fan-out. Updates arriving while the
+    /// queue is full are dropped and every client is disconnected.
+    #[serde(default = "default_broadcast_queue_capacity")]
+    pub broadcast_queue_capacity: usize,
+
+    /// Upper bound on the number of keys in a client's filter
+    #[serde(default = "default_max_filter_keys")]
+    pub max_filter_keys: usize,
+
+    /// Whether to stream the accounts restored from the snapshot at startup
+    #[serde(default)]
+    pub notify_startup_accounts: bool,
+}
+
+fn default_max_clients() -> usize {
+    DEFAULT_MAX_CLIENTS
+}
+
+fn default_client_queue_capacity() -> usize {
+    DEFAULT_CLIENT_QUEUE_CAPACITY
+}
+
+fn default_replay_window_slots() -> u64 {
+    DEFAULT_REPLAY_WINDOW_SLOTS
+}
+
+fn default_replay_window_max_bytes() -> usize {
+    DEFAULT_REPLAY_WINDOW_MAX_BYTES
+}
+
+fn default_broadcast_queue_capacity() -> usize {
+    DEFAULT_BROADCAST_QUEUE_CAPACITY
+}
+
+fn default_max_filter_keys() -> usize {
+    DEFAULT_MAX_FILTER_KEYS
+}
+
+impl StreamPluginConfig {
+    pub fn new(bind_address: SocketAddr) -> Self {
+        Self {
+            bind_address,
+            max_clients: DEFAULT_MAX_CLIENTS,
+            client_queue_capacity: DEFAULT_CLIENT_QUEUE_CAPACITY,
+            replay_window_slots: DEFAULT_REPLAY_WINDOW_SLOTS,
+            replay_window_max_bytes: DEFAULT_REPLAY_WINDOW_MAX_BYTES,
+            broadcast_queue_capacity: DEFAULT_BROADCAST_QUEUE_CAPACITY,
+            max_filter_keys: DEFAULT_MAX_FILTER_KEYS,
+            notify_startup_accounts: false,
+        }
+    }
+
+    pub fn load(config_file: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(config_file)?;
+        Self::parse(&contents)
+    }
+
+    fn parse(contents: &str) -> Result<Self> {
+        json5::from_str(contents).map_err(|err| GeyserPluginError::ConfigFileReadError {
+            msg: format!("Invalid stream plugin config: {err}"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config = StreamPluginConfig::parse(
+            r#"{
+                libpath: "libagave_geyser_stream_plugin.so",
+                bindAddress: "127.0.0.1:10000",
+                replayWindowSlots: 8,
+            }"#,
+        )
+        .unwrap();
+        let mut expected = StreamPluginConfig::new("127.0.0.1:10000".parse().unwrap());
+        expected.replay_window_slots = 8;
+        assert_eq!(config, expected);
+
+        assert!(StreamPluginConfig::parse(r#"{ libpath: "lib.so" }"#).is_err());
+    }
+}
@@ -0,0 +1,201 @@
+use {
+    crate::message::StreamMessage,
+    serde_derive::{Deserialize, Serialize},
+    solana_sdk::pubkey::Pubkey,
+    std::collections::HashSet,
+};
+
+/// Selects the updates streamed to a client. An account update is sent if either its
+/// address is in `accounts` or its owner is in `owners`. A transaction is sent if any
+/// of its signers is in `transaction_signers`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct StreamFilter {
+    pub accounts: Vec<Pubkey>,
+    pub owners: Vec<Pubkey>,
+    pub transaction_signers: Vec<Pubkey>,
+    /// Whether vote transactions signed by `transaction_signers` are sent
+    pub include_vote_transactions: bool,
+    /// Whether slot status updates are sent
+    pub slots: bool,
+}
+
+impl StreamFilter {
+    pub fn num_keys(&self) -> usize {
+        self.accounts
+            .len()
+            .saturating_add(self.owners.len())
+            .saturating_add(self.transaction_signers.len())
+    }
+}
+
+/// A `StreamFilter` prepared for matching updates
+#[derive(Debug, Default)]
+pub(crate) struct ClientFilter {
+    accounts: HashSet<Pubkey>,
+    owners: HashSet<Pubkey>,
+    transaction_signers: HashSet<Pubkey>,
+    include_vote_transactions: bool,
+    slots: bool,
+}
+
+impl From<StreamFilter> for ClientFilter {
+    fn from(filter: StreamFilter) -> Self {
+        Self {
+            accounts: filter.accounts.into_iter().collect(),
+            owners: filter.owners.into_iter().collect(),
+            transaction_signers: filter.transaction_signers.into_iter().collect(),
+            include_vote_transactions: filter.include_vote_transactions,
+            slots: filter.slots,
+        }
+    }
+}
+
+/// The parts of an update which filters match on, kept alongside its encoded frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum MessageKeys {
+    Subscribed,
+    Account { pubkey: Pubkey, owner: Pubkey },
+    Transaction { is_vote: bool, signers: Vec<Pubkey> },
+    Slot,
+}
+
+impl From<&StreamMessage> for MessageKeys {
+    fn from(message: &StreamMessage) -> Self {
+        match message {
+            StreamMessage::Subscribed { .. } => Self::Subscribed,
+            StreamMessage::Account(account) => Self::Account {
+                pubkey: account.pubkey,
+                owner: account.owner,
+            },
+            StreamMessage::Transaction(transaction) => {
+                let message = &transaction.transaction.message;
+                let num_signers = usize::from(message.header().num_required_signatures);
+                Self::Transaction {
+                    is_vote: transaction.is_vote,
+                    signers: message
+                        .static_account_keys()
+                        .iter()
+                        .take(num_signers)
+                        .copied()
+                        .collect(),
+                }
+            }
+            StreamMessage::Slot(_) => Self::Slot,
+        }
+    }
+}
+
+impl ClientFilter {
+    pub(crate) fn matches(&self, keys: &MessageKeys) -> bool {
+        match keys {
+            MessageKeys::Subscribed => true,
+            MessageKeys::Account { pubkey, owner } => {
+                self.accounts.contains(pubkey) || self.owners.contains(owner)
+            }
+            MessageKeys::Transaction { is_vote, signers } => {
+                if *is_vote && !self.include_vote_transactions {
+                    return false;
+                }
+                signers
+                    .iter()
+                    .any(|signer| self.transaction_signers.contains(signer))
+            }
+            MessageKeys::Slot => self.slots,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::message::{
+            AccountUpdate, SlotUpdate, SlotUpdateStatus, TransactionUpdate, TransactionUpdateMeta,
+        },
+        solana_sdk::{
+            signature::{Keypair, Signer},
+            system_transaction,
+            transaction::VersionedTransaction,
+        },
+    };
+
+    fn account_update(pubkey: Pubkey, owner: Pubkey) -> MessageKeys {
+        MessageKeys::from(&StreamMessage::Account(AccountUpdate {
+            slot: 1,
+            pubkey,
+            lamports: 1,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+            data: vec![],
+            write_version: 0,
+            txn_signature: None,
+        }))
+    }
+
+    fn transaction_update(payer: &Keypair, to: &Pubkey, is_vote: bool) -> MessageKeys {
+        let transaction = system_transaction::transfer(payer, to, 1, Default::default());
+        MessageKeys::from(&StreamMessage::Transaction(TransactionUpdate {
+            slot: 1,
+            index: 0,
+            signature: transaction.signatures[0],
+            is_vote,
+            transaction: VersionedTransaction::from(transaction),
+            meta: TransactionUpdateMeta::default(),
+        }))
+    }
+
+    #[test]
+    fn test_filter_accounts() {
+        let account = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+
+        let filter = ClientFilter::from(StreamFilter {
+            accounts: vec![account],
+            owners: vec![owner],
+            ..StreamFilter::default()
+        });
+        assert!(filter.matches(&account_update(account, other)));
+        assert!(filter.matches(&account_update(other, owner)));
+        assert!(!filter.matches(&account_update(other, other)));
+        assert!(!ClientFilter::default().matches(&account_update(account, owner)));
+    }
+
+    #[test]
+    fn test_filter_transactions() {
+        let payer = Keypair::new();
+        let to = Pubkey::new_unique();
+
+        let filter = ClientFilter::from(StreamFilter {
+            transaction_signers: vec![payer.pubkey()],
+            ..StreamFilter::default()
+        });
+        assert!(filter.matches(&transaction_update(&payer, &to, false)));
+        assert!(!filter.matches(&transaction_update(&payer, &to, true)));
+        assert!(!filter.matches(&transaction_update(&Keypair::new(), &payer.pubkey(), false)));
+
+        // Only signers are matched, not every account referenced by the transaction
+        let filter = ClientFilter::from(StreamFilter {
+            transaction_signers: vec![to],
+            include_vote_transactions: true,
+            ..StreamFilter::default()
+        });
+        assert!(!filter.matches(&transaction_update(&payer, &to, false)));
+    }
+
+    #[test]
+    fn test_filter_slots() {
+        let slot_update = MessageKeys::from(&StreamMessage::Slot(SlotUpdate {
+            slot: 1,
+            parent: None,
+            status: SlotUpdateStatus::Rooted,
+        }));
+        assert!(!ClientFilter::default().matches(&slot_update));
+        let filter = ClientFilter::from(StreamFilter {
+            slots: true,
+            ..StreamFilter::default()
+        });
+        assert!(filter.matches(&slot_update));
+    }
+}
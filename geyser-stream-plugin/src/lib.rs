@@ -0,0 +1,15 @@
+//! A Geyser plugin which streams account, transaction and slot updates to clients
+//! over TCP. Each client subscribes with a filter on accounts, owners and transaction
+//! signers, and may ask for recent updates to be replayed from a given slot.
+//!
+//! See the [`message`] module for the wire protocol and [`client::StreamClient`] for
+//! a client implementation.
+
+pub mod client;
+pub mod config;
+pub mod filter;
+pub mod message;
+pub mod plugin;
+pub mod server;
+
+agave_geyser_plugin_interface::declare_geyser_plugin_interface_version!();
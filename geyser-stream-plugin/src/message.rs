@@ -0,0 +1,208 @@
+//! The wire protocol spoken between the plugin and its clients.
+//!
+//! Every message is a frame made of a little-endian `u32` length followed by that many
+//! bytes of the bincode encoded message. After connecting, a client sends a single
+//! [`SubscribeRequest`]. The server answers with [`StreamMessage::Subscribed`] followed
+//! by any replayed updates, and then streams live updates until either side disconnects.
+
+use {
+    crate::filter::StreamFilter,
+    serde::de::DeserializeOwned,
+    serde_derive::{Deserialize, Serialize},
+    solana_sdk::{
+        clock::Slot,
+        pubkey::Pubkey,
+        signature::Signature,
+        transaction::{TransactionError, VersionedTransaction},
+    },
+    std::io::{self, Read, Write},
+    thiserror::Error,
+};
+
+/// Frames larger than this are rejected. Accounts are at most 10MiB.
+pub const MAX_FRAME_SIZE: usize = 32 * 1024 * 1024;
+
+#[derive(Error, Debug)]
+pub enum FrameError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] bincode::Error),
+
+    #[error("Frame of {0} bytes exceeds the maximum frame size")]
+    FrameTooLarge(usize),
+}
+
+/// Sent by a client once, right after connecting
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubscribeRequest {
+    pub filter: StreamFilter,
+
+    /// Replay the updates kept in the replay window starting at this slot, before
+    /// streaming live updates
+    pub from_slot: Option<Slot>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum StreamMessage {
+    /// The subscription was accepted. `first_available_slot` is the oldest slot kept in
+    /// the replay window, a client which asked for an older slot has missed updates.
+    Subscribed {
+        first_available_slot: Option<Slot>,
+    },
+    Account(AccountUpdate),
+    Transaction(TransactionUpdate),
+    Slot(SlotUpdate),
+}
+
+impl StreamMessage {
+    /// The slot this update belongs to
+    pub fn slot(&self) -> Option<Slot> {
+        match self {
+            Self::Subscribed { .. } => None,
+            Self::Account(account) => Some(account.slot),
+            Self::Transaction(transaction) => Some(transaction.slot),
+            Self::Slot(slot) => Some(slot.slot),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AccountUpdate {
+    pub slot: Slot,
+    pub pubkey: Pubkey,
+    pub lamports: u64,
+    pub owner: Pubkey,
+    pub executable: bool,
+    pub rent_epoch: u64,
+    pub data: Vec<u8>,
+    /// Orders updates to the same account within a slot
+    pub write_version: u64,
+    /// The transaction which caused the update, if any
+    pub txn_signature: Option<Signature>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TransactionUpdate {
+    pub slot: Slot,
+    /// The index of the transaction in the block
+    pub index: u64,
+    pub signature: Signature,
+    pub is_vote: bool,
+    pub transaction: VersionedTransaction,
+    pub meta: TransactionUpdateMeta,
+}
+
+/// The subset of the transaction status meta sent to clients
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionUpdateMeta {
+    pub err: Option<TransactionError>,
+    pub fee: u64,
+    pub pre_balances: Vec<u64>,
+    pub post_balances: Vec<u64>,
+    pub log_messages: Option<Vec<String>>,
+    pub compute_units_consumed: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SlotUpdate {
+    pub slot: Slot,
+    pub parent: Option<Slot>,
+    pub status: SlotUpdateStatus,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum SlotUpdateStatus {
+    FirstShredReceived,
+    Completed,
+    CreatedBank,
+    Processed,
+    Confirmed,
+    Rooted,
+    Dead(String),
+    /// The slot will never be rooted and updates received for it should be rolled back
+    Abandoned {
+        /// The ancestors of the slot, from newest to oldest
+        ancestors: Vec<Slot>,
+        reason: AbandonedReason,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum AbandonedReason {
+    Dead(String),
+    Pruned,
+}
+
+/// Serializes `value` into a complete frame, including the length prefix
+pub fn encode_frame<T: serde::Serialize>(value: &T) -> Result<Vec<u8>, FrameError> {
+    let len = bincode::serialized_size(value)? as usize;
+    if len > MAX_FRAME_SIZE {
+        return Err(FrameError::FrameTooLarge(len));
+    }
+    let mut frame = Vec::with_capacity(len + 4);
+    frame.extend_from_slice(&(len as u32).to_le_bytes());
+    bincode::serialize_into(&mut frame, value)?;
+    Ok(frame)
+}
+
+pub fn write_frame<T: serde::Serialize>(
+    writer: &mut impl Write,
+    value: &T,
+) -> Result<(), FrameError> {
+    writer.write_all(&encode_frame(value)?)?;
+    Ok(())
+}
+
+pub fn read_frame<T: DeserializeOwned>(reader: &mut impl Read) -> Result<T, FrameError> {
+    let mut len = [0u8; 4];
+    reader.read_exact(&mut len)?;
+    let len = u32::from_le_bytes(len) as usize;
+    if len > MAX_FRAME_SIZE {
+        return Err(FrameError::FrameTooLarge(len));
+    }
+    let mut buf = vec![0u8; len];
+    reader.read_exact(&mut buf)?;
+    Ok(bincode::deserialize(&buf)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::io::Cursor};
+
+    #[test]
+    fn test_frame_roundtrip() {
+        let message = StreamMessage::Slot(SlotUpdate {
+            slot: 42,
+            parent: Some(41),
+            status: SlotUpdateStatus::Abandoned {
+                ancestors: vec![41, 40],
+                reason: AbandonedReason::Pruned,
+            },
+        });
+        let mut buf = vec![];
+        write_frame(&mut buf, &message).unwrap();
+        write_frame(&mut buf, &SubscribeRequest::default()).unwrap();
+
+        let mut reader = Cursor::new(buf);
+        assert_eq!(read_frame::<StreamMessage>(&mut reader).unwrap(), message);
+        assert_eq!(
+            read_frame::<SubscribeRequest>(&mut reader).unwrap(),
+            SubscribeRequest::default()
+        );
+        assert!(matches!(
+            read_frame::<StreamMessage>(&mut reader),
+            Err(FrameError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_frame_too_large() {
+        let frame = (MAX_FRAME_SIZE as u32 + 1).to_le_bytes();
+        assert!(matches!(
+            read_frame::<StreamMessage>(&mut Cursor::new(frame)),
+            Err(FrameError::FrameTooLarge(_))
+        ));
+    }
+}
@@ -0,0 +1,192 @@
+use {
+    crate::{
+        config::StreamPluginConfig,
+        message::{
+            AbandonedReason, AccountUpdate, SlotUpdate, SlotUpdateStatus, StreamMessage,
+            TransactionUpdate, TransactionUpdateMeta,
+        },
+        server::StreamServer,
+    },
+    agave_geyser_plugin_interface::geyser_plugin_interface::{
+        GeyserPlugin, GeyserPluginError, ReplicaAbandonedSlotInfoVersions,
+        ReplicaAccountInfoVersions, ReplicaTransactionInfoVersions, Result, SlotAbandonedReason,
+        SlotStatus,
+    },
+    log::*,
+    solana_sdk::{clock::Slot, pubkey::Pubkey},
+    std::path::Path,
+};
+
+/// Streams account, transaction and slot updates to TCP clients, see [`StreamServer`]
+#[derive(Debug, Default)]
+pub struct GeyserStreamPlugin {
+    server: Option<StreamServer>,
+    notify_startup_accounts: bool,
+}
+
+impl GeyserStreamPlugin {
+    /// The server, unless it is not running or has no use for updates right now
+    fn active_server(&self) -> Option<&StreamServer> {
+        self.server.as_ref().filter(|server| server.is_active())
+    }
+}
+
+impl GeyserPlugin for GeyserStreamPlugin {
+    fn name(&self) -> &'static str {
+        "GeyserStreamPlugin"
+    }
+
+    fn on_load(&mut self, config_file: &str, _is_reload: bool) -> Result<()> {
+        let config = StreamPluginConfig::load(Path::new(config_file))?;
+        info!("Loading {} with {config:?}", self.name());
+        self.notify_startup_accounts = config.notify_startup_accounts;
+        let server =
+            StreamServer::new(config).map_err(|err| GeyserPluginError::Custom(err.into()))?;
+        self.server = Some(server);
+        Ok(())
+    }
+
+    fn on_unload(&mut self) {
+        if let Some(server) = self.server.take() {
+            server.join();
+        }
+    }
+
+    fn update_account(
+        &self,
+        account: ReplicaAccountInfoVersions,
+        slot: Slot,
+        is_startup: bool,
+    ) -> Result<()> {
+        if is_startup && !self.notify_startup_accounts {
+            return Ok(());
+        }
+        let Some(server) = self.active_server() else {
+            return Ok(());
+        };
+        let account = match account {
+            ReplicaAccountInfoVersions::V0_0_1(_) | ReplicaAccountInfoVersions::V0_0_2(_) => {
+                return Err(GeyserPluginError::AccountsUpdateError {
+                    msg: "unsupported account info version".to_string(),
+                });
+            }
+            ReplicaAccountInfoVersions::V0_0_3(account) => account,
+        };
+        let to_pubkey = |bytes: &[u8]| {
+            Pubkey::try_from(bytes).map_err(|_| GeyserPluginError::AccountsUpdateError {
+                msg: "invalid pubkey".to_string(),
+            })
+        };
+        server.broadcast(StreamMessage::Account(AccountUpdate {
+            slot,
+            pubkey: to_pubkey(account.pubkey)?,
+            lamports: account.lamports,
+            owner: to_pubkey(account.owner)?,
+            executable: account.executable,
+            rent_epoch: account.rent_epoch,
+            data: account.data.to_vec(),
+            write_version: account.write_version,
+            txn_signature: account.txn.map(|txn| *txn.signature()),
+        }));
+        Ok(())
+    }
+
+    fn update_slot_status(
+        &self,
+        slot: Slot,
+        parent: Option<u64>,
+        status: &SlotStatus,
+    ) -> Result<()> {
+        let Some(server) = self.active_server() else {
+            return Ok(());
+        };
+        let status = match status {
+            SlotStatus::FirstShredReceived => SlotUpdateStatus::FirstShredReceived,
+            SlotStatus::Completed => SlotUpdateStatus::Completed,
+            SlotStatus::CreatedBank => SlotUpdateStatus::CreatedBank,
+            SlotStatus::Processed => SlotUpdateStatus::Processed,
+            SlotStatus::Confirmed => SlotUpdateStatus::Confirmed,
+            SlotStatus::Rooted => SlotUpdateStatus::Rooted,
+            SlotStatus::Dead(err) => SlotUpdateStatus::Dead(err.clone()),
+        };
+        server.broadcast(StreamMessage::Slot(SlotUpdate {
+            slot,
+            parent,
+            status,
+        }));
+        Ok(())
+    }
+
+    fn notify_slot_abandoned(&self, slot_info: ReplicaAbandonedSlotInfoVersions) -> Result<()> {
+        let Some(server) = self.active_server() else {
+            return Ok(());
+        };
+        let ReplicaAbandonedSlotInfoVersions::V0_0_1(slot_info) = slot_info;
+        let reason = match slot_info.reason {
+            SlotAbandonedReason::Dead(err) => AbandonedReason::Dead(err.clone()),
+            SlotAbandonedReason::Pruned => AbandonedReason::Pruned,
+        };
+        server.broadcast(StreamMessage::Slot(SlotUpdate {
+            slot: slot_info.slot,
+            parent: slot_info.parent_slot,
+            status: SlotUpdateStatus::Abandoned {
+                ancestors: slot_info.ancestors.to_vec(),
+                reason,
+            },
+        }));
+        Ok(())
+    }
+
+    fn notify_transaction(
+        &self,
+        transaction: ReplicaTransactionInfoVersions,
+        slot: Slot,
+    ) -> Result<()> {
+        let Some(server) = self.active_server() else {
+            return Ok(());
+        };
+        let transaction = match transaction {
+            ReplicaTransactionInfoVersions::V0_0_1(_) => {
+                return Err(GeyserPluginError::TransactionUpdateError {
+                    msg: "unsupported transaction info version".to_string(),
+                });
+            }
+            ReplicaTransactionInfoVersions::V0_0_2(transaction) => transaction,
+        };
+        let meta = transaction.transaction_status_meta;
+        server.broadcast(StreamMessage::Transaction(TransactionUpdate {
+            slot,
+            index: transaction.index as u64,
+            signature: *transaction.signature,
+            is_vote: transaction.is_vote,
+            transaction: transaction.transaction.to_versioned_transaction(),
+            meta: TransactionUpdateMeta {
+                err: meta.status.clone().err(),
+                fee: meta.fee,
+                pre_balances: meta.pre_balances.clone(),
+                post_balances: meta.post_balances.clone(),
+                log_messages: meta.log_messages.clone(),
+                compute_units_consumed: meta.compute_units_consumed,
+            },
+        }));
+        Ok(())
+    }
+
+    fn account_data_notifications_enabled(&self) -> bool {
+        true
+    }
+
+    fn transaction_notifications_enabled(&self) -> bool {
+        true
+    }
+}
+
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+/// # Safety
+///
+/// This function returns the GeyserStreamPlugin pointer as trait GeyserPlugin.
+pub unsafe extern "C" fn _create_plugin() -> *mut dyn GeyserPlugin {
+    let plugin: Box<dyn GeyserPlugin> = Box::<GeyserStreamPlugin>::default();
+    Box::into_raw(plugin)
+}
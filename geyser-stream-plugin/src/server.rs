@@ -0,0 +1,685 @@
+//! Accepts client connections and fans updates out to them.
+//!
+//! Updates are handed to a broadcast thread which encodes them once and fans them out.
+//! Every client is served by its own thread, fed through a bounded queue. Updates are
+//! never allowed to block the validator: a client whose queue is full is disconnected,
+//! and may reconnect asking for the updates it missed from the replay window. If the
+//! broadcast thread itself falls behind, updates are dropped and every client is
+//! disconnected.
+
+use {
+    crate::{
+        config::StreamPluginConfig,
+        filter::{ClientFilter, MessageKeys},
+        message::{encode_frame, read_frame, FrameError, StreamMessage, SubscribeRequest},
+    },
+    crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender, TrySendError},
+    log::*,
+    solana_sdk::clock::Slot,
+    std::{
+        collections::VecDeque,
+        io::{self, BufWriter, Write},
+        net::{Shutdown, SocketAddr, TcpListener, TcpStream},
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+        thread::{self, sleep, JoinHandle},
+        time::Duration,
+    },
+};
+
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const CLIENT_RECV_TIMEOUT: Duration = Duration::from_millis(500);
+const SUBSCRIBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// An encoded update and the keys clients filter it on
+#[derive(Debug)]
+struct Frame {
+    slot: Slot,
+    keys: MessageKeys,
+    bytes: Vec<u8>,
+}
+
+/// The most recent updates, kept so that reconnecting clients can catch up
+#[derive(Debug, Default)]
+struct ReplayWindow {
+    frames: VecDeque<Arc<Frame>>,
+    num_bytes: usize,
+    highest_slot: Slot,
+}
+
+impl ReplayWindow {
+    fn push(&mut self, frame: Arc<Frame>, window_slots: u64, max_bytes: usize) {
+        self.highest_slot = self.highest_slot.max(frame.slot);
+        self.num_bytes = self.num_bytes.saturating_add(frame.bytes.len());
+        self.frames.push_back(frame);
+        let lowest_slot = self
+            .highest_slot
+            .saturating_add(1)
+            .saturating_sub(window_slots);
+        while let Some(front) = self.frames.front() {
+            if front.slot >= lowest_slot && self.num_bytes <= max_bytes {
+                break;
+            }
+            self.num_bytes = self.num_bytes.saturating_sub(front.bytes.len());
+            self.frames.pop_front();
+        }
+    }
+
+    fn clear(&mut self) {
+        self.frames.clear();
+        self.num_bytes = 0;
+    }
+
+    fn first_available_slot(&self) -> Option<Slot> {
+        self.frames.front().map(|frame| frame.slot)
+    }
+
+    fn frames_since(&self, from_slot: Slot, filter: &ClientFilter) -> Vec<Arc<Frame>> {
+        self.frames
+            .iter()
+            .filter(|frame| frame.slot >= from_slot && filter.matches(&frame.keys))
+            .cloned()
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+struct Client {
+    addr: SocketAddr,
+    filter: ClientFilter,
+    sender: Sender<Arc<Frame>>,
+    stream: TcpStream,
+}
+
+#[derive(Debug, Default)]
+struct BroadcastState {
+    clients: Vec<Client>,
+    replay_window: ReplayWindow,
+}
+
+#[derive(Debug)]
+struct Shared {
+    config: StreamPluginConfig,
+    state: Mutex<BroadcastState>,
+    /// The number of subscribed clients, readable without taking the broadcast lock
+    num_clients: AtomicUsize,
+    num_connections: AtomicUsize,
+    client_threads: Mutex<Vec<JoinHandle<()>>>,
+    /// Set when an update was dropped because the broadcast queue was full
+    lagged: AtomicBool,
+    exit: AtomicBool,
+}
+
+/// Streams updates to every connected client whose filter matches
+#[derive(Debug)]
+pub struct StreamServer {
+    shared: Arc<Shared>,
+    local_addr: SocketAddr,
+    broadcast_sender: Sender<StreamMessage>,
+    accept_thread: Option<JoinHandle<()>>,
+    broadcast_thread: Option<JoinHandle<()>>,
+}
+
+impl StreamServer {
+    pub fn new(config: StreamPluginConfig) -> io::Result<Self> {
+        let listener = TcpListener::bind(config.bind_address)?;
+        listener.set_nonblocking(true)?;
+        let local_addr = listener.local_addr()?;
+        let (broadcast_sender, broadcast_receiver) = bounded(config.broadcast_queue_capacity);
+        let shared = Arc::new(Shared {
+            config,
+            state: Mutex::default(),
+            num_clients: AtomicUsize::default(),
+            num_connections: AtomicUsize::default(),
+            client_threads: Mutex::default(),
+            lagged: AtomicBool::default(),
+            exit: AtomicBool::default(),
+        });
+        let broadcast_thread = {
+            let shared = shared.clone();
+            thread::Builder::new()
+                .name("solGeyserStrBcs".to_string())
+                .spawn(move || Self::broadcast_loop(broadcast_receiver, shared))?
+        };
+        let accept_thread = {
+            let shared = shared.clone();
+            thread::Builder::new()
+                .name("solGeyserStrAcc".to_string())
+                .spawn(move || Self::accept_loop(listener, shared))?
+        };
+        info!("Geyser stream plugin listening on {local_addr}");
+        Ok(Self {
+            shared,
+            local_addr,
+            broadcast_sender,
+            accept_thread: Some(accept_thread),
+            broadcast_thread: Some(broadcast_thread),
+        })
+    }
+
+    /// The address clients connect to
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Whether an update would be used, either by a subscribed client or to fill the
+    /// replay window. Callers check this before building an update, so that nothing is
+    /// copied while no client is subscribed and the replay window is disabled.
+    pub fn is_active(&self) -> bool {
+        self.shared.num_clients.load(Ordering::Relaxed) > 0
+            || self.shared.config.replay_window_max_bytes > 0
+    }
+
+    /// Queues `message` to be sent to every client whose filter matches, and kept for
+    /// replay. Encoding and fan-out happen on the broadcast thread; if it has fallen
+    /// behind, the update is dropped and every client is disconnected.
+    pub fn broadcast(&self, message: StreamMessage) {
+        if !self.is_active() {
+            return;
+        }
+        match self.broadcast_sender.try_send(message) {
+            Ok(()) | Err(TrySendError::Disconnected(_)) => {}
+            Err(TrySendError::Full(_)) => {
+                if !self.shared.lagged.swap(true, Ordering::Relaxed) {
+                    warn!("Geyser stream broadcast queue is full, dropping updates");
+                }
+            }
+        }
+    }
+
+    /// The number of clients currently subscribed
+    pub fn num_clients(&self) -> usize {
+        self.shared.num_clients.load(Ordering::Relaxed)
+    }
+
+    fn broadcast_loop(receiver: Receiver<StreamMessage>, shared: Arc<Shared>) {
+        while !shared.exit.load(Ordering::Relaxed) {
+            let message = match receiver.recv_timeout(CLIENT_RECV_TIMEOUT) {
+                Ok(message) => message,
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+            if shared.lagged.swap(false, Ordering::Relaxed) {
+                Self::disconnect_all(&shared);
+            }
+            Self::send_to_clients(message, &shared);
+        }
+    }
+
+    /// Disconnects every client and empties the replay window after updates were
+    /// dropped, so that no client carries on with a gap it does not know about
+    fn disconnect_all(shared: &Shared) {
+        let mut state = shared.state.lock().unwrap();
+        for client in state.clients.drain(..) {
+            warn!(
+                "Disconnecting geyser stream client {}, updates were dropped",
+                client.addr
+            );
+            let _ = client.stream.shutdown(Shutdown::Both);
+        }
+        shared.num_clients.store(0, Ordering::Relaxed);
+        state.replay_window.clear();
+    }
+
+    /// Encodes `message` and sends it to every client whose filter matches, and keeps it
+    /// for replay
+    fn send_to_clients(message: StreamMessage, shared: &Shared) {
+        let Some(slot) = message.slot() else {
+            return;
+        };
+        let bytes = match encode_frame(&message) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                warn!("Failed to encode update for slot {slot}: {err}");
+                return;
+            }
+        };
+        let frame = Arc::new(Frame {
+            slot,
+            keys: MessageKeys::from(&message),
+            bytes,
+        });
+        drop(message);
+
+        let config = &shared.config;
+        let mut state = shared.state.lock().unwrap();
+        state.clients.retain(|client| {
+            if !client.filter.matches(&frame.keys) {
+                return true;
+            }
+            match client.sender.try_send(frame.clone()) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    warn!(
+                        "Disconnecting geyser stream client {}, it is not keeping up",
+                        client.addr
+                    );
+                    let _ = client.stream.shutdown(Shutdown::Both);
+                    false
+                }
+                Err(TrySendError::Disconnected(_)) => false,
+            }
+        });
+        shared
+            .num_clients
+            .store(state.clients.len(), Ordering::Relaxed);
+        state.replay_window.push(
+            frame,
+            config.replay_window_slots,
+            config.replay_window_max_bytes,
+        );
+    }
+
+    fn accept_loop(listener: TcpListener, shared: Arc<Shared>) {
+        while !shared.exit.load(Ordering::Relaxed) {
+            let (stream, addr) = match listener.accept() {
+                Ok(connection) => connection,
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                    sleep(ACCEPT_POLL_INTERVAL);
+                    continue;
+                }
+                Err(err) => {
+                    warn!("Failed to accept geyser stream client: {err}");
+                    sleep(ACCEPT_POLL_INTERVAL);
+                    continue;
+                }
+            };
+            if shared.num_connections.load(Ordering::Relaxed) >= shared.config.max_clients {
+                warn!("Refusing geyser stream client {addr}, too many clients");
+                continue;
+            }
+            shared.num_connections.fetch_add(1, Ordering::Relaxed);
+            let client_thread = {
+                let shared = shared.clone();
+                thread::Builder::new()
+                    .name("solGeyserStrCli".to_string())
+                    .spawn(move || {
+                        if let Err(err) = Self::serve_client(stream, addr, &shared) {
+                            debug!("Geyser stream client {addr} disconnected: {err}");
+                        }
+                        shared.num_connections.fetch_sub(1, Ordering::Relaxed);
+                    })
+            };
+            match client_thread {
+                Ok(client_thread) => {
+                    let mut client_threads = shared.client_threads.lock().unwrap();
+                    client_threads.retain(|thread| !thread.is_finished());
+                    client_threads.push(client_thread);
+                }
+                Err(err) => {
+                    warn!("Failed to spawn thread for geyser stream client {addr}: {err}");
+                    shared.num_connections.fetch_sub(1, Ordering::Relaxed);
+                }
+            }
+        }
+    }
+
+    fn serve_client(
+        mut stream: TcpStream,
+        addr: SocketAddr,
+        shared: &Shared,
+    ) -> Result<(), FrameError> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(SUBSCRIBE_TIMEOUT))?;
+        stream.set_nodelay(true)?;
+        let request: SubscribeRequest = read_frame(&mut stream)?;
+        if request.filter.num_keys() > shared.config.max_filter_keys {
+            return Err(FrameError::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "too many keys in filter",
+            )));
+        }
+        let (replay, receiver) = Self::subscribe(request, addr, stream.try_clone()?, shared);
+        info!("Geyser stream client {addr} subscribed");
+
+        let mut writer = BufWriter::new(stream);
+        for frame in replay.iter() {
+            writer.write_all(&frame.bytes)?;
+        }
+        writer.flush()?;
+        Self::stream_to_client(writer, receiver, shared)
+    }
+
+    /// Registers the client and returns the frames to replay to it. Both happen under
+    /// the broadcast lock, so the client sees every update exactly once.
+    fn subscribe(
+        request: SubscribeRequest,
+        addr: SocketAddr,
+        stream: TcpStream,
+        shared: &Shared,
+    ) -> (Vec<Arc<Frame>>, Receiver<Arc<Frame>>) {
+        let filter = ClientFilter::from(request.filter);
+        let (sender, receiver) = bounded(shared.config.client_queue_capacity);
+        let mut state = shared.state.lock().unwrap();
+
+        let first_available_slot = state.replay_window.first_available_slot();
+        let subscribed = StreamMessage::Subscribed {
+            first_available_slot,
+        };
+        let mut replay = vec![Arc::new(Frame {
+            slot: 0,
+            keys: MessageKeys::Subscribed,
+            bytes: encode_frame(&subscribed).expect("subscribed message is small"),
+        })];
+        if let Some(from_slot) = request.from_slot {
+            replay.extend(state.replay_window.frames_since(from_slot, &filter));
+        }
+        state.clients.push(Client {
+            addr,
+            filter,
+            sender,
+            stream,
+        });
+        shared
+            .num_clients
+            .store(state.clients.len(), Ordering::Relaxed);
+        (replay, receiver)
+    }
+
+    fn stream_to_client(
+        mut writer: BufWriter<TcpStream>,
+        receiver: Receiver<Arc<Frame>>,
+        shared: &Shared,
+    ) -> Result<(), FrameError> {
+        while !shared.exit.load(Ordering::Relaxed) {
+            let frame = match receiver.recv_timeout(CLIENT_RECV_TIMEOUT) {
+                Ok(frame) => frame,
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+            writer.write_all(&frame.bytes)?;
+            // Batch up whatever else is already queued before flushing
+            for frame in receiver.try_iter() {
+                writer.write_all(&frame.bytes)?;
+            }
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Disconnects all clients and waits for every thread to finish
+    pub fn join(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.shared.exit.store(true, Ordering::Relaxed);
+        if let Some(accept_thread) = self.accept_thread.take() {
+            let _ = accept_thread.join();
+        }
+        if let Some(broadcast_thread) = self.broadcast_thread.take() {
+            let _ = broadcast_thread.join();
+        }
+        for client in self.shared.state.lock().unwrap().clients.drain(..) {
+            let _ = client.stream.shutdown(Shutdown::Both);
+        }
+        self.shared.num_clients.store(0, Ordering::Relaxed);
+        let client_threads = std::mem::take(&mut *self.shared.client_threads.lock().unwrap());
+        for client_thread in client_threads {
+            let _ = client_thread.join();
+        }
+    }
+}
+
+impl Drop for StreamServer {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            client::StreamClient,
+            filter::StreamFilter,
+            message::{AccountUpdate, SlotUpdate, SlotUpdateStatus},
+        },
+        solana_sdk::pubkey::Pubkey,
+        std::time::Instant,
+    };
+
+    fn account_update(slot: Slot, pubkey: Pubkey, owner: Pubkey) -> StreamMessage {
+        StreamMessage::Account(AccountUpdate {
+            slot,
+            pubkey,
+            lamports: slot,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+            data: vec![1, 2, 3],
+            write_version: slot,
+            txn_signature: None,
+        })
+    }
+
+    fn slot_update(slot: Slot) -> StreamMessage {
+        StreamMessage::Slot(SlotUpdate {
+            slot,
+            parent: slot.checked_sub(1),
+            status: SlotUpdateStatus::Processed,
+        })
+    }
+
+    fn start_server(config: impl FnOnce(&mut StreamPluginConfig)) -> StreamServer {
+        let mut stream_config = StreamPluginConfig::new("127.0.0.1:0".parse().unwrap());
+        config(&mut stream_config);
+        StreamServer::new(stream_config).unwrap()
+    }
+
+    fn wait_for_clients(server: &StreamServer, num_clients: usize) {
+        let start = Instant::now();
+        while server.num_clients() != num_clients {
+            assert!(start.elapsed() < Duration::from_secs(10));
+            sleep(Duration::from_millis(10));
+        }
+    }
+
+    /// Waits for the broadcast thread to have handled the updates up to `slot`
+    fn wait_for_replay_window(server: &StreamServer, slot: Slot) {
+        let start = Instant::now();
+        while server
+            .shared
+            .state
+            .lock()
+            .unwrap()
+            .replay_window
+            .highest_slot
+            != slot
+        {
+            assert!(start.elapsed() < Duration::from_secs(10));
+            sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_stream_filtered_updates() {
+        let server = start_server(|_| {});
+        let owner = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+
+        let mut client = StreamClient::connect(
+            server.local_addr(),
+            &SubscribeRequest {
+                filter: StreamFilter {
+                    owners: vec![owner],
+                    slots: true,
+                    ..StreamFilter::default()
+                },
+                from_slot: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            client.recv().unwrap(),
+            StreamMessage::Subscribed {
+                first_available_slot: None
+            }
+        );
+        wait_for_clients(&server, 1);
+
+        let owned = Pubkey::new_unique();
+        server.broadcast(account_update(1, Pubkey::new_unique(), other));
+        server.broadcast(account_update(1, owned, owner));
+        server.broadcast(slot_update(1));
+        assert_eq!(client.recv().unwrap(), account_update(1, owned, owner));
+        assert_eq!(client.recv().unwrap(), slot_update(1));
+
+        // A client which went away is dropped on a later update
+        drop(client);
+        let start = Instant::now();
+        while server.num_clients() != 0 {
+            assert!(start.elapsed() < Duration::from_secs(10));
+            server.broadcast(slot_update(2));
+            sleep(Duration::from_millis(10));
+        }
+        server.join();
+    }
+
+    #[test]
+    fn test_stream_replay_window() {
+        let server = start_server(|config| {
+            config.replay_window_slots = 2;
+            config.replay_window_max_bytes = 1024 * 1024;
+        });
+        for slot in 1..=5 {
+            server.broadcast(slot_update(slot));
+        }
+        wait_for_replay_window(&server, 5);
+
+        // Slots older than the window are gone
+        let mut client = StreamClient::connect(
+            server.local_addr(),
+            &SubscribeRequest {
+                filter: StreamFilter {
+                    slots: true,
+                    ..StreamFilter::default()
+                },
+                from_slot: Some(1),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            client.recv().unwrap(),
+            StreamMessage::Subscribed {
+                first_available_slot: Some(4)
+            }
+        );
+        for slot in 4..=5 {
+            assert_eq!(client.recv().unwrap(), slot_update(slot));
+        }
+
+        // Live updates follow the replayed ones
+        wait_for_clients(&server, 1);
+        server.broadcast(slot_update(6));
+        assert_eq!(client.recv().unwrap(), slot_update(6));
+        server.join();
+    }
+
+    #[test]
+    fn test_stream_disconnects_slow_client() {
+        let server = start_server(|config| config.client_queue_capacity = 1);
+        let pubkey = Pubkey::new_unique();
+        let client = StreamClient::connect(
+            server.local_addr(),
+            &SubscribeRequest {
+                filter: StreamFilter {
+                    accounts: vec![pubkey],
+                    ..StreamFilter::default()
+                },
+                from_slot: None,
+            },
+        )
+        .unwrap();
+        wait_for_clients(&server, 1);
+
+        // The client never reads, so its queue eventually fills up
+        let update = account_update(1, pubkey, Pubkey::new_unique());
+        let start = Instant::now();
+        while server.num_clients() != 0 {
+            assert!(start.elapsed() < Duration::from_secs(10));
+            server.broadcast(update.clone());
+        }
+        drop(client);
+        server.join();
+    }
+
+    fn slot_frame(slot: Slot) -> Arc<Frame> {
+        let message = slot_update(slot);
+        Arc::new(Frame {
+            slot,
+            keys: MessageKeys::from(&message),
+            bytes: encode_frame(&message).unwrap(),
+        })
+    }
+
+    #[test]
+    fn test_replay_window_slots() {
+        // Exactly `window_slots` slots are kept, up to and including the highest one
+        for window_slots in 1..=3 {
+            let mut replay_window = ReplayWindow::default();
+            for slot in 1..=5 {
+                replay_window.push(slot_frame(slot), window_slots, usize::MAX);
+            }
+            assert_eq!(replay_window.first_available_slot(), Some(6 - window_slots));
+            assert_eq!(replay_window.frames.len(), window_slots as usize);
+        }
+
+        // Slots from before the window is full are kept
+        let mut replay_window = ReplayWindow::default();
+        for slot in 0..=1 {
+            replay_window.push(slot_frame(slot), 2, usize::MAX);
+        }
+        assert_eq!(replay_window.first_available_slot(), Some(0));
+
+        // A window of zero slots keeps nothing
+        let mut replay_window = ReplayWindow::default();
+        replay_window.push(slot_frame(1), 0, usize::MAX);
+        assert_eq!(replay_window.first_available_slot(), None);
+    }
+
+    #[test]
+    fn test_replay_window_max_bytes() {
+        let frame_size = slot_frame(1).bytes.len();
+
+        // Only as many frames as fit in the byte limit are kept, newest first
+        let mut replay_window = ReplayWindow::default();
+        for slot in 1..=5 {
+            replay_window.push(slot_frame(slot), 32, 2 * frame_size);
+        }
+        assert_eq!(replay_window.first_available_slot(), Some(4));
+        assert_eq!(replay_window.num_bytes, 2 * frame_size);
+
+        // A limit of zero keeps nothing
+        let mut replay_window = ReplayWindow::default();
+        replay_window.push(slot_frame(1), 32, 0);
+        assert_eq!(replay_window.first_available_slot(), None);
+        assert_eq!(replay_window.num_bytes, 0);
+    }
+
+    #[test]
+    fn test_stream_inactive_without_clients() {
+        // The replay window is disabled by default
+        let server = start_server(|_| {});
+        assert!(!server.is_active());
+
+        let client = StreamClient::connect(
+            server.local_addr(),
+            &SubscribeRequest {
+                filter: StreamFilter {
+                    slots: true,
+                    ..StreamFilter::default()
+                },
+                from_slot: None,
+            },
+        )
+        .unwrap();
+        wait_for_clients(&server, 1);
+        assert!(server.is_active());
+        drop(client);
+        server.join();
+    }
+}
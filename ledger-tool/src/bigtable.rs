@@ -41,7 +41,7 @@ use {
     std::{
         cmp::min,
         collections::HashSet,
-        path::Path,
+        path::{Path, PathBuf},
         process::exit,
         result::Result,
         str::FromStr,
@@ -879,6 +879,7 @@ async fn get_bigtable(
                 instance_name: args.instance_name,
                 app_profile_id: args.app_profile_id,
                 max_message_size: solana_storage_bigtable::DEFAULT_MAX_MESSAGE_SIZE,
+                local_storage_path: None,
             },
         )
         .await
@@ -914,6 +915,17 @@ impl BigTableSubCommand for App<'_, '_> {
                         .default_value(solana_storage_bigtable::DEFAULT_APP_PROFILE_ID)
                         .help("Bigtable application profile id to use in requests"),
                 )
+                .arg(
+                    Arg::with_name("local_storage_path")
+                        .global(true)
+                        .long("local-storage")
+                        .takes_value(true)
+                        .value_name("DIR")
+                        .help(
+                            "Use a local database at this path instead of a Bigtable instance, \
+                             for storing ledger data on a single machine",
+                        ),
+                )
                 .subcommand(
                     SubCommand::with_name("upload")
                         .about("Upload the ledger to BigTable")
@@ -1366,6 +1378,10 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
         "rpc_bigtable_app_profile_id",
         solana_storage_bigtable::DEFAULT_APP_PROFILE_ID,
     );
+    let local_storage_path = matches
+        .value_of("local_storage_path")
+        .or_else(|| sub_matches.and_then(|sub_matches| sub_matches.value_of("local_storage_path")))
+        .map(PathBuf::from);
 
     let future = match (subcommand, sub_matches) {
         ("upload", Some(arg_matches)) => {
@@ -1381,6 +1397,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: false,
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(upload(
@@ -1397,6 +1414,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: !arg_matches.is_present("force"),
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(delete_slots(slots, config))
@@ -1406,6 +1424,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(first_available_block(config))
@@ -1417,6 +1436,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(block(slot, output_format, show_entries, config))
@@ -1427,6 +1447,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(entries(slot, output_format, config))
@@ -1471,6 +1492,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
@@ -1489,6 +1511,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
@@ -1501,6 +1524,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
@@ -1534,6 +1558,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
@@ -1554,6 +1579,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                local_storage_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
@@ -2,6 +2,7 @@
 
 use {
     crate::{
+        blockstore_export::export_blocks,
         error::{LedgerToolError, Result},
         ledger_path::canonicalize_ledger_path,
         ledger_utils::get_program_ids,
@@ -134,7 +135,8 @@ fn analyze_storage(database: &Database) -> Result<()> {
     analyze_column::<PerfSamples>(database, "PerfSamples")?;
     analyze_column::<BlockHeight>(database, "BlockHeight")?;
     analyze_column::<ProgramCosts>(database, "ProgramCosts")?;
-    analyze_column::<OptimisticSlots>(database, "OptimisticSlots")
+    analyze_column::<OptimisticSlots>(database, "OptimisticSlots")?;
+    analyze_column::<ContendedAccounts>(database, "ContendedAccounts")
 }
 
 fn raw_key_to_slot(key: &[u8], column_name: &str) -> Option<Slot> {
@@ -166,6 +168,9 @@ fn raw_key_to_slot(key: &[u8], column_name: &str) -> Option<Slot> {
         cf::OptimisticSlots::NAME => {
             Some(cf::OptimisticSlots::slot(cf::OptimisticSlots::index(key)))
         }
+        cf::ContendedAccounts::NAME => Some(cf::ContendedAccounts::slot(
+            cf::ContendedAccounts::index(key),
+        )),
         &_ => None,
     }
 }
@@ -347,6 +352,25 @@ pub fn blockstore_subcommands<'a, 'b>(hidden: bool) -> Vec<App<'a, 'b>> {
             .about("Print all the duplicate slots in the ledger")
             .settings(&hidden)
             .arg(&starting_slot_arg),
+        SubCommand::with_name("export")
+            .about(
+                "Export the rooted blocks in a slot range, including their transactions, \
+                 balances, inner instructions and rewards, into Parquet files",
+            )
+            .arg(&starting_slot_arg)
+            .arg(&ending_slot_arg)
+            .arg(
+                Arg::with_name("output_dir")
+                    .long("output-dir")
+                    .value_name("DIR")
+                    .takes_value(true)
+                    .required(true)
+                    .help(
+                        "Directory to write the files to, one file per table: blocks, \
+                         transactions, balances, token_balances, inner_instructions and \
+                         rewards",
+                    ),
+            ),
         SubCommand::with_name("latest-optimistic-slots")
             .about(
                 "Output up to the most recent <num-slots> optimistic slots with their hashes \
@@ -704,6 +728,16 @@ fn do_blockstore_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) -
                 }
             }
         }
+        ("export", Some(arg_matches)) => {
+            let blockstore =
+                crate::open_blockstore(&ledger_path, arg_matches, AccessType::Secondary);
+            let starting_slot = value_t_or_exit!(arg_matches, "starting_slot", Slot);
+            let ending_slot = value_t!(arg_matches, "ending_slot", Slot).unwrap_or(Slot::MAX);
+            let output_dir = PathBuf::from(value_t_or_exit!(arg_matches, "output_dir", String));
+
+            let stats = export_blocks(&blockstore, starting_slot, ending_slot, &output_dir)?;
+            println!("Exported to {}: {stats:?}", output_dir.display());
+        }
         ("latest-optimistic-slots", Some(arg_matches)) => {
             let blockstore =
                 crate::open_blockstore(&ledger_path, arg_matches, AccessType::Secondary);
@@ -0,0 +1,749 @@
+//! Export of rooted blocks into Parquet files, one file per table.
+//!
+//! The schema of each table is part of the interface of `agave-ledger-tool blockstore export`;
+//! columns may be appended but must not be removed, renamed or change type.
+
+use {
+    crate::error::Result,
+    arrow_array::{
+        builder::{ListBuilder, StringBuilder},
+        ArrayRef, BinaryArray, BooleanArray, Int64Array, RecordBatch, StringArray, UInt32Array,
+        UInt64Array, UInt8Array,
+    },
+    arrow_schema::{DataType, Field, Schema, SchemaRef},
+    log::*,
+    parquet::{
+        arrow::ArrowWriter,
+        basic::{Compression, ZstdLevel},
+        file::properties::WriterProperties,
+    },
+    solana_ledger::blockstore::{Blockstore, BlockstoreError},
+    solana_sdk::{clock::Slot, transaction::TransactionVersion},
+    solana_transaction_status::{VersionedConfirmedBlock, VersionedTransactionWithStatusMeta},
+    std::{collections::BTreeMap, fs::File, path::Path, sync::Arc},
+};
+
+/// Rows are buffered up to this many per table before being written out as a row group
+const ROW_GROUP_SIZE: usize = 64 * 1024;
+
+fn list_of_strings() -> DataType {
+    DataType::List(Arc::new(Field::new("item", DataType::Utf8, true)))
+}
+
+/// The columns of a table, buffered in memory until written out
+trait Columns: Default {
+    const NAME: &'static str;
+
+    fn schema() -> Schema;
+
+    fn len(&self) -> usize;
+
+    fn into_arrays(self) -> Vec<ArrayRef>;
+}
+
+#[derive(Default)]
+struct BlockColumns {
+    slot: Vec<u64>,
+    parent_slot: Vec<u64>,
+    blockhash: Vec<String>,
+    previous_blockhash: Vec<String>,
+    block_time: Vec<Option<i64>>,
+    block_height: Vec<Option<u64>>,
+    transaction_count: Vec<u64>,
+    reward_count: Vec<u64>,
+    num_reward_partitions: Vec<Option<u64>>,
+}
+
+impl Columns for BlockColumns {
+    const NAME: &'static str = "blocks";
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            Field::new("slot", DataType::UInt64, false),
+            Field::new("parent_slot", DataType::UInt64, false),
+            Field::new("blockhash", DataType::Utf8, false),
+            Field::new("previous_blockhash", DataType::Utf8, false),
+            Field::new("block_time", DataType::Int64, true),
+            Field::new("block_height", DataType::UInt64, true),
+            Field::new("transaction_count", DataType::UInt64, false),
+            Field::new("reward_count", DataType::UInt64, false),
+            Field::new("num_reward_partitions", DataType::UInt64, true),
+        ])
+    }
+
+    fn len(&self) -> usize {
+        self.slot.len()
+    }
+
+    fn into_arrays(self) -> Vec<ArrayRef> {
+        vec![
+            Arc::new(UInt64Array::from(self.slot)),
+            Arc::new(UInt64Array::from(self.parent_slot)),
+            Arc::new(StringArray::from(self.blockhash)),
+            Arc::new(StringArray::from(self.previous_blockhash)),
+            Arc::new(Int64Array::from(self.block_time)),
+            Arc::new(UInt64Array::from(self.block_height)),
+            Arc::new(UInt64Array::from(self.transaction_count)),
+            Arc::new(UInt64Array::from(self.reward_count)),
+            Arc::new(UInt64Array::from(self.num_reward_partitions)),
+        ]
+    }
+}
+
+#[derive(Default)]
+struct TransactionColumns {
+    slot: Vec<u64>,
+    transaction_index: Vec<u32>,
+    signature: Vec<String>,
+    version: Vec<String>,
+    recent_blockhash: Vec<String>,
+    num_required_signatures: Vec<u8>,
+    success: Vec<bool>,
+    error: Vec<Option<String>>,
+    fee: Vec<u64>,
+    compute_units_consumed: Vec<Option<u64>>,
+    log_messages: ListBuilder<StringBuilder>,
+}
+
+impl Columns for TransactionColumns {
+    const NAME: &'static str = "transactions";
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            Field::new("slot", DataType::UInt64, false),
+            Field::new("transaction_index", DataType::UInt32, false),
+            Field::new("signature", DataType::Utf8, false),
+            Field::new("version", DataType::Utf8, false),
+            Field::new("recent_blockhash", DataType::Utf8, false),
+            Field::new("num_required_signatures", DataType::UInt8, false),
+            Field::new("success", DataType::Boolean, false),
+            Field::new("error", DataType::Utf8, true),
+            Field::new("fee", DataType::UInt64, false),
+            Field::new("compute_units_consumed", DataType::UInt64, true),
+            Field::new("log_messages", list_of_strings(), true),
+        ])
+    }
+
+    fn len(&self) -> usize {
+        self.slot.len()
+    }
+
+    fn into_arrays(mut self) -> Vec<ArrayRef> {
+        vec![
+            Arc::new(UInt64Array::from(self.slot)),
+            Arc::new(UInt32Array::from(self.transaction_index)),
+            Arc::new(StringArray::from(self.signature)),
+            Arc::new(StringArray::from(self.version)),
+            Arc::new(StringArray::from(self.recent_blockhash)),
+            Arc::new(UInt8Array::from(self.num_required_signatures)),
+            Arc::new(BooleanArray::from(self.success)),
+            Arc::new(StringArray::from(self.error)),
+            Arc::new(UInt64Array::from(self.fee)),
+            Arc::new(UInt64Array::from(self.compute_units_consumed)),
+            Arc::new(self.log_messages.finish()),
+        ]
+    }
+}
+
+#[derive(Default)]
+struct BalanceColumns {
+    slot: Vec<u64>,
+    transaction_index: Vec<u32>,
+    signature: Vec<String>,
+    account_index: Vec<u32>,
+    account: Vec<String>,
+    pre_balance: Vec<u64>,
+    post_balance: Vec<u64>,
+}
+
+impl Columns for BalanceColumns {
+    const NAME: &'static str = "balances";
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            Field::new("slot", DataType::UInt64, false),
+            Field::new("transaction_index", DataType::UInt32, false),
+            Field::new("signature", DataType::Utf8, false),
+            Field::new("account_index", DataType::UInt32, false),
+            Field::new("account", DataType::Utf8, false),
+            Field::new("pre_balance", DataType::UInt64, false),
+            Field::new("post_balance", DataType::UInt64, false),
+        ])
+    }
+
+    fn len(&self) -> usize {
+        self.slot.len()
+    }
+
+    fn into_arrays(self) -> Vec<ArrayRef> {
+        vec![
+            Arc::new(UInt64Array::from(self.slot)),
+            Arc::new(UInt32Array::from(self.transaction_index)),
+            Arc::new(StringArray::from(self.signature)),
+            Arc::new(UInt32Array::from(self.account_index)),
+            Arc::new(StringArray::from(self.account)),
+            Arc::new(UInt64Array::from(self.pre_balance)),
+            Arc::new(UInt64Array::from(self.post_balance)),
+        ]
+    }
+}
+
+#[derive(Default)]
+struct TokenBalanceColumns {
+    slot: Vec<u64>,
+    transaction_index: Vec<u32>,
+    signature: Vec<String>,
+    account_index: Vec<u32>,
+    account: Vec<Option<String>>,
+    mint: Vec<String>,
+    owner: Vec<String>,
+    program_id: Vec<String>,
+    decimals: Vec<u8>,
+    pre_amount: Vec<Option<String>>,
+    post_amount: Vec<Option<String>>,
+}
+
+impl Columns for TokenBalanceColumns {
+    const NAME: &'static str = "token_balances";
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            Field::new("slot", DataType::UInt64, false),
+            Field::new("transaction_index", DataType::UInt32, false),
+            Field::new("signature", DataType::Utf8, false),
+            Field::new("account_index", DataType::UInt32, false),
+            Field::new("account", DataType::Utf8, true),
+            Field::new("mint", DataType::Utf8, false),
+            Field::new("owner", DataType::Utf8, false),
+            Field::new("program_id", DataType::Utf8, false),
+            Field::new("decimals", DataType::UInt8, false),
+            Field::new("pre_amount", DataType::Utf8, true),
+            Field::new("post_amount", DataType::Utf8, true),
+        ])
+    }
+
+    fn len(&self) -> usize {
+        self.slot.len()
+    }
+
+    fn into_arrays(self) -> Vec<ArrayRef> {
+        vec![
+            Arc::new(UInt64Array::from(self.slot)),
+            Arc::new(UInt32Array::from(self.transaction_index)),
+            Arc::new(StringArray::from(self.signature)),
+            Arc::new(UInt32Array::from(self.account_index)),
+            Arc::new(StringArray::from(self.account)),
+            Arc::new(StringArray::from(self.mint)),
+            Arc::new(StringArray::from(self.owner)),
+            Arc::new(StringArray::from(self.program_id)),
+            Arc::new(UInt8Array::from(self.decimals)),
+            Arc::new(StringArray::from(self.pre_amount)),
+            Arc::new(StringArray::from(self.post_amount)),
+        ]
+    }
+}
+
+#[derive(Default)]
+struct InnerInstructionColumns {
+    slot: Vec<u64>,
+    transaction_index: Vec<u32>,
+    signature: Vec<String>,
+    instruction_index: Vec<u32>,
+    inner_instruction_index: Vec<u32>,
+    program_id: Vec<Option<String>>,
+    accounts: ListBuilder<StringBuilder>,
+    data: Vec<Vec<u8>>,
+    stack_height: Vec<Option<u32>>,
+}
+
+impl Columns for InnerInstructionColumns {
+    const NAME: &'static str = "inner_instructions";
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            Field::new("slot", DataType::UInt64, false),
+            Field::new("transaction_index", DataType::UInt32, false),
+            Field::new("signature", DataType::Utf8, false),
+            Field::new("instruction_index", DataType::UInt32, false),
+            Field::new("inner_instruction_index", DataType::UInt32, false),
+            Field::new("program_id", DataType::Utf8, true),
+            Field::new("accounts", list_of_strings(), true),
+            Field::new("data", DataType::Binary, false),
+            Field::new("stack_height", DataType::UInt32, true),
+        ])
+    }
+
+    fn len(&self) -> usize {
+        self.slot.len()
+    }
+
+    fn into_arrays(mut self) -> Vec<ArrayRef> {
+        let data: Vec<&[u8]> = self.data.iter().map(Vec::as_slice).collect();
+        vec![
+            Arc::new(UInt64Array::from(self.slot)),
+            Arc::new(UInt32Array::from(self.transaction_index)),
+            Arc::new(StringArray::from(self.signature)),
+            Arc::new(UInt32Array::from(self.instruction_index)),
+            Arc::new(UInt32Array::from(self.inner_instruction_index)),
+            Arc::new(StringArray::from(self.program_id)),
+            Arc::new(self.accounts.finish()),
+            Arc::new(BinaryArray::from(data)),
+            Arc::new(UInt32Array::from(self.stack_height)),
+        ]
+    }
+}
+
+#[derive(Default)]
+struct RewardColumns {
+    slot: Vec<u64>,
+    pubkey: Vec<String>,
+    lamports: Vec<i64>,
+    post_balance: Vec<u64>,
+    reward_type: Vec<Option<String>>,
+    commission: Vec<Option<u8>>,
+}
+
+impl Columns for RewardColumns {
+    const NAME: &'static str = "rewards";
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            Field::new("slot", DataType::UInt64, false),
+            Field::new("pubkey", DataType::Utf8, false),
+            Field::new("lamports", DataType::Int64, false),
+            Field::new("post_balance", DataType::UInt64, false),
+            Field::new("reward_type", DataType::Utf8, true),
+            Field::new("commission", DataType::UInt8, true),
+        ])
+    }
+
+    fn len(&self) -> usize {
+        self.slot.len()
+    }
+
+    fn into_arrays(self) -> Vec<ArrayRef> {
+        vec![
+            Arc::new(UInt64Array::from(self.slot)),
+            Arc::new(StringArray::from(self.pubkey)),
+            Arc::new(Int64Array::from(self.lamports)),
+            Arc::new(UInt64Array::from(self.post_balance)),
+            Arc::new(StringArray::from(self.reward_type)),
+            Arc::new(UInt8Array::from(self.commission)),
+        ]
+    }
+}
+
+/// Writes the rows of one table into `<output_dir>/<table>.parquet`
+struct TableWriter<C: Columns> {
+    schema: SchemaRef,
+    writer: ArrowWriter<File>,
+    columns: C,
+    num_rows: usize,
+}
+
+impl<C: Columns> TableWriter<C> {
+    fn new(output_dir: &Path) -> Result<Self> {
+        let schema = Arc::new(C::schema());
+        let file = File::create(output_dir.join(format!("{}.parquet", C::NAME)))?;
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::ZSTD(ZstdLevel::default()))
+            .set_max_row_group_size(ROW_GROUP_SIZE)
+            .build();
+        let writer = ArrowWriter::try_new(file, schema.clone(), Some(properties))?;
+        Ok(Self {
+            schema,
+            writer,
+            columns: C::default(),
+            num_rows: 0,
+        })
+    }
+
+    /// Writes out the buffered rows once there are enough for a row group
+    fn maybe_flush(&mut self) -> Result<()> {
+        if self.columns.len() >= ROW_GROUP_SIZE {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        let columns = std::mem::take(&mut self.columns);
+        if columns.len() == 0 {
+            return Ok(());
+        }
+        self.num_rows += columns.len();
+        let batch = RecordBatch::try_new(self.schema.clone(), columns.into_arrays())?;
+        self.writer.write(&batch)?;
+        Ok(())
+    }
+
+    /// Writes out any buffered rows and the file footer, returning the number of rows written
+    fn finish(mut self) -> Result<usize> {
+        self.flush()?;
+        self.writer.close()?;
+        Ok(self.num_rows)
+    }
+}
+
+/// The number of rows written to each table
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ExportStats {
+    pub blocks: usize,
+    pub transactions: usize,
+    pub balances: usize,
+    pub token_balances: usize,
+    pub inner_instructions: usize,
+    pub rewards: usize,
+}
+
+pub struct BlockExporter {
+    blocks: TableWriter<BlockColumns>,
+    transactions: TableWriter<TransactionColumns>,
+    balances: TableWriter<BalanceColumns>,
+    token_balances: TableWriter<TokenBalanceColumns>,
+    inner_instructions: TableWriter<InnerInstructionColumns>,
+    rewards: TableWriter<RewardColumns>,
+}
+
+impl BlockExporter {
+    pub fn new(output_dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(output_dir)?;
+        Ok(Self {
+            blocks: TableWriter::new(output_dir)?,
+            transactions: TableWriter::new(output_dir)?,
+            balances: TableWriter::new(output_dir)?,
+            token_balances: TableWriter::new(output_dir)?,
+            inner_instructions: TableWriter::new(output_dir)?,
+            rewards: TableWriter::new(output_dir)?,
+        })
+    }
+
+    pub fn add_block(&mut self, slot: Slot, block: &VersionedConfirmedBlock) -> Result<()> {
+        let blocks = &mut self.blocks.columns;
+        blocks.slot.push(slot);
+        blocks.parent_slot.push(block.parent_slot);
+        blocks.blockhash.push(block.blockhash.clone());
+        blocks
+            .previous_blockhash
+            .push(block.previous_blockhash.clone());
+        blocks.block_time.push(block.block_time);
+        blocks.block_height.push(block.block_height);
+        blocks
+            .transaction_count
+            .push(block.transactions.len() as u64);
+        blocks.reward_count.push(block.rewards.len() as u64);
+        blocks.num_reward_partitions.push(block.num_partitions);
+
+        for (index, transaction) in block.transactions.iter().enumerate() {
+            self.add_transaction(slot, index as u32, transaction);
+        }
+
+        let rewards = &mut self.rewards.columns;
+        for reward in block.rewards.iter() {
+            rewards.slot.push(slot);
+            rewards.pubkey.push(reward.pubkey.clone());
+            rewards.lamports.push(reward.lamports);
+            rewards.post_balance.push(reward.post_balance);
+            rewards.reward_type.push(
+                reward
+                    .reward_type
+                    .map(|reward_type| reward_type.to_string()),
+            );
+            rewards.commission.push(reward.commission);
+        }
+
+        self.blocks.maybe_flush()?;
+        self.transactions.maybe_flush()?;
+        self.balances.maybe_flush()?;
+        self.token_balances.maybe_flush()?;
+        self.inner_instructions.maybe_flush()?;
+        self.rewards.maybe_flush()
+    }
+
+    fn add_transaction(
+        &mut self,
+        slot: Slot,
+        transaction_index: u32,
+        transaction_with_meta: &VersionedTransactionWithStatusMeta,
+    ) {
+        let VersionedTransactionWithStatusMeta { transaction, meta } = transaction_with_meta;
+        let signature = transaction.signatures[0].to_string();
+        let account_keys = transaction_with_meta.account_keys();
+
+        let transactions = &mut self.transactions.columns;
+        transactions.slot.push(slot);
+        transactions.transaction_index.push(transaction_index);
+        transactions.signature.push(signature.clone());
+        transactions.version.push(match transaction.version() {
+            TransactionVersion::Legacy(_) => "legacy".to_string(),
+            TransactionVersion::Number(version) => version.to_string(),
+        });
+        transactions
+            .recent_blockhash
+            .push(transaction.message.recent_blockhash().to_string());
+        transactions
+            .num_required_signatures
+            .push(transaction.message.header().num_required_signatures);
+        transactions.success.push(meta.status.is_ok());
+        transactions
+            .error
+            .push(meta.status.as_ref().err().map(|err| err.to_string()));
+        transactions.fee.push(meta.fee);
+        transactions
+            .compute_units_consumed
+            .push(meta.compute_units_consumed);
+        match &meta.log_messages {
+            Some(log_messages) => {
+                for log_message in log_messages {
+                    transactions.log_messages.values().append_value(log_message);
+                }
+                transactions.log_messages.append(true);
+            }
+            None => transactions.log_messages.append(false),
+        }
+
+        let balances = &mut self.balances.columns;
+        for (account_index, (pre_balance, post_balance)) in meta
+            .pre_balances
+            .iter()
+            .zip(meta.post_balances.iter())
+            .enumerate()
+        {
+            balances.slot.push(slot);
+            balances.transaction_index.push(transaction_index);
+            balances.signature.push(signature.clone());
+            balances.account_index.push(account_index as u32);
+            balances.account.push(
+                account_keys
+                    .get(account_index)
+                    .map(ToString::to_string)
+                    .unwrap_or_default(),
+            );
+            balances.pre_balance.push(*pre_balance);
+            balances.post_balance.push(*post_balance);
+        }
+
+        // Pair up the pre and post token balances of each account
+        let mut token_balances = BTreeMap::new();
+        for pre_token_balance in meta.pre_token_balances.iter().flatten() {
+            token_balances
+                .entry(pre_token_balance.account_index)
+                .or_insert((None, None))
+                .0 = Some(pre_token_balance);
+        }
+        for post_token_balance in meta.post_token_balances.iter().flatten() {
+            token_balances
+                .entry(post_token_balance.account_index)
+                .or_insert((None, None))
+                .1 = Some(post_token_balance);
+        }
+        let columns = &mut self.token_balances.columns;
+        for (account_index, (pre, post)) in token_balances {
+            let Some(token_balance) = post.or(pre) else {
+                continue;
+            };
+            columns.slot.push(slot);
+            columns.transaction_index.push(transaction_index);
+            columns.signature.push(signature.clone());
+            columns.account_index.push(u32::from(account_index));
+            columns.account.push(
+                account_keys
+                    .get(usize::from(account_index))
+                    .map(ToString::to_string),
+            );
+            columns.mint.push(token_balance.mint.clone());
+            columns.owner.push(token_balance.owner.clone());
+            columns.program_id.push(token_balance.program_id.clone());
+            columns
+                .decimals
+                .push(token_balance.ui_token_amount.decimals);
+            columns
+                .pre_amount
+                .push(pre.map(|pre| pre.ui_token_amount.amount.clone()));
+            columns
+                .post_amount
+                .push(post.map(|post| post.ui_token_amount.amount.clone()));
+        }
+
+        let columns = &mut self.inner_instructions.columns;
+        for inner_instructions in meta.inner_instructions.iter().flatten() {
+            for (inner_instruction_index, inner_instruction) in
+                inner_instructions.instructions.iter().enumerate()
+            {
+                let instruction = &inner_instruction.instruction;
+                columns.slot.push(slot);
+                columns.transaction_index.push(transaction_index);
+                columns.signature.push(signature.clone());
+                columns
+                    .instruction_index
+                    .push(u32::from(inner_instructions.index));
+                columns
+                    .inner_instruction_index
+                    .push(inner_instruction_index as u32);
+                columns.program_id.push(
+                    account_keys
+                        .get(usize::from(instruction.program_id_index))
+                        .map(ToString::to_string),
+                );
+                for account_index in instruction.accounts.iter() {
+                    columns.accounts.values().append_option(
+                        account_keys
+                            .get(usize::from(*account_index))
+                            .map(ToString::to_string),
+                    );
+                }
+                columns.accounts.append(true);
+                columns.data.push(instruction.data.clone());
+                columns.stack_height.push(inner_instruction.stack_height);
+            }
+        }
+    }
+
+    pub fn finish(self) -> Result<ExportStats> {
+        Ok(ExportStats {
+            blocks: self.blocks.finish()?,
+            transactions: self.transactions.finish()?,
+            balances: self.balances.finish()?,
+            token_balances: self.token_balances.finish()?,
+            inner_instructions: self.inner_instructions.finish()?,
+            rewards: self.rewards.finish()?,
+        })
+    }
+}
+
+/// Exports the rooted blocks between `starting_slot` and `ending_slot`, inclusive, into
+/// Parquet files in `output_dir`
+pub fn export_blocks(
+    blockstore: &Blockstore,
+    starting_slot: Slot,
+    ending_slot: Slot,
+    output_dir: &Path,
+) -> Result<ExportStats> {
+    let mut exporter = BlockExporter::new(output_dir)?;
+    for slot in blockstore.rooted_slot_iterator(starting_slot)? {
+        if slot > ending_slot {
+            break;
+        }
+        let block = match blockstore.get_rooted_block(slot, false) {
+            Ok(block) => block,
+            Err(BlockstoreError::SlotUnavailable) | Err(BlockstoreError::SlotNotRooted) => {
+                warn!("Block for slot {slot} is not available, skipping");
+                continue;
+            }
+            Err(err) => return Err(err.into()),
+        };
+        exporter.add_block(slot, &block)?;
+    }
+    exporter.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder,
+        solana_sdk::{
+            hash::Hash,
+            pubkey::Pubkey,
+            reward_type::RewardType,
+            signature::{Keypair, Signer},
+            system_transaction,
+            transaction::VersionedTransaction,
+        },
+        solana_transaction_status::{
+            InnerInstruction, InnerInstructions, Reward, TransactionStatusMeta,
+        },
+    };
+
+    fn read_table(output_dir: &Path, name: &str) -> Vec<RecordBatch> {
+        let file = File::open(output_dir.join(format!("{name}.parquet"))).unwrap();
+        ParquetRecordBatchReaderBuilder::try_new(file)
+            .unwrap()
+            .build()
+            .unwrap()
+            .map(|batch| batch.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_export_block() {
+        let output_dir = tempfile::tempdir().unwrap();
+        let payer = Keypair::new();
+        let to = Pubkey::new_unique();
+        let transaction = system_transaction::transfer(&payer, &to, 42, Hash::new_unique());
+        let inner_instruction = transaction.message.instructions[0].clone();
+        let block = VersionedConfirmedBlock {
+            previous_blockhash: Hash::new_unique().to_string(),
+            blockhash: Hash::new_unique().to_string(),
+            parent_slot: 4,
+            transactions: vec![VersionedTransactionWithStatusMeta {
+                transaction: VersionedTransaction::from(transaction),
+                meta: TransactionStatusMeta {
+                    fee: 5000,
+                    pre_balances: vec![100_000, 0, 1],
+                    post_balances: vec![94_958, 42, 1],
+                    inner_instructions: Some(vec![InnerInstructions {
+                        index: 0,
+                        instructions: vec![InnerInstruction {
+                            instruction: inner_instruction,
+                            stack_height: Some(2),
+                        }],
+                    }]),
+                    log_messages: Some(vec!["Program log: hello".to_string()]),
+                    ..TransactionStatusMeta::default()
+                },
+            }],
+            rewards: vec![Reward {
+                pubkey: Pubkey::new_unique().to_string(),
+                lamports: 2500,
+                post_balance: 1_000_000,
+                reward_type: Some(RewardType::Fee),
+                commission: None,
+            }],
+            num_partitions: None,
+            block_time: Some(1_700_000_000),
+            block_height: Some(3),
+        };
+
+        let mut exporter = BlockExporter::new(output_dir.path()).unwrap();
+        exporter.add_block(5, &block).unwrap();
+        assert_eq!(
+            exporter.finish().unwrap(),
+            ExportStats {
+                blocks: 1,
+                transactions: 1,
+                balances: 3,
+                token_balances: 0,
+                inner_instructions: 1,
+                rewards: 1,
+            }
+        );
+
+        let blocks = read_table(output_dir.path(), "blocks");
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].schema(), Arc::new(BlockColumns::schema()));
+        let slots = blocks[0]
+            .column(0)
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .unwrap();
+        assert_eq!(slots.value(0), 5);
+
+        let balances = read_table(output_dir.path(), "balances");
+        let accounts = balances[0]
+            .column_by_name("account")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(accounts.value(0), payer.pubkey().to_string());
+        assert_eq!(accounts.value(1), to.to_string());
+
+        for name in ["transactions", "inner_instructions", "rewards"] {
+            let batches = read_table(output_dir.path(), name);
+            assert_eq!(batches.iter().map(RecordBatch::num_rows).sum::<usize>(), 1);
+        }
+        // Tables without rows are still written, with their schema
+        assert!(read_table(output_dir.path(), "token_balances").is_empty());
+    }
+}
@@ -11,6 +11,7 @@ use {
             SlotBankHash,
         },
         program::*,
+        transaction_replay::*,
     },
     clap::{
         crate_description, crate_name, value_t, value_t_or_exit, values_t_or_exit, App,
@@ -103,11 +104,13 @@ use {
 mod args;
 mod bigtable;
 mod blockstore;
+mod blockstore_export;
 mod error;
 mod ledger_path;
 mod ledger_utils;
 mod output;
 mod program;
+mod transaction_replay;
 
 fn parse_encoding_format(matches: &ArgMatches<'_>) -> UiAccountEncoding {
     match matches.value_of("encoding") {
@@ -1637,6 +1640,7 @@ fn main() {
                 .arg(&allow_dead_slots_arg),
         )
         .program_subcommand()
+        .replay_transaction_subcommand()
         .get_matches();
 
     info!("{} {}", crate_name!(), solana_version::version!());
@@ -1654,6 +1658,7 @@ fn main() {
         ("bigtable", Some(arg_matches)) => bigtable_process_command(&ledger_path, arg_matches),
         ("blockstore", Some(arg_matches)) => blockstore_process_command(&ledger_path, arg_matches),
         ("program", Some(arg_matches)) => program(&ledger_path, arg_matches),
+        ("replay-transaction", Some(arg_matches)) => replay_transaction(&ledger_path, arg_matches),
         // This match case provides legacy support for commands that were previously top level
         // subcommands of the binary, but have been moved under the blockstore subcommand.
         ("analyze-storage", Some(_))
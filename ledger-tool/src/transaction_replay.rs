@@ -0,0 +1,346 @@
+//! The `replay-transaction` subcommand: re-executes a single transaction from the ledger with
+//! instruction level tracing and writes a JSON report of its execution.
+
+use {
+    crate::{
+        args::*,
+        canonicalize_ledger_path,
+        error::{LedgerToolError, Result},
+        ledger_utils::*,
+    },
+    base64::{prelude::BASE64_STANDARD, Engine},
+    clap::{value_t_or_exit, App, Arg, ArgMatches, SubCommand},
+    log::*,
+    serde_derive::Serialize,
+    solana_bpf_loader_program::syscalls::{
+        create_program_runtime_environment_v1, create_program_runtime_environment_v2,
+    },
+    solana_clap_utils::input_validators::{is_parsable, is_slot},
+    solana_ledger::{blockstore_options::AccessType, leader_schedule_cache::LeaderScheduleCache},
+    solana_program_runtime::invoke_context::InstructionTrace,
+    solana_runtime::bank::{Bank, LoadAndExecuteTransactionsOutput},
+    solana_sdk::{
+        clock::{Slot, MAX_PROCESSING_AGE},
+        signature::Signature,
+        transaction::TransactionVerificationMode,
+    },
+    solana_svm::{
+        transaction_error_metrics::TransactionErrorMetrics,
+        transaction_processing_result::ProcessedTransaction,
+        transaction_processor::{ExecutionRecordingConfig, TransactionProcessingConfig},
+        transaction_state_diff::{AccountStateDiff, DataRangeDiff},
+    },
+    solana_timings::ExecuteTimings,
+    std::{
+        fs::File,
+        io::{self, Write},
+        path::Path,
+        process::exit,
+        sync::Arc,
+    },
+};
+
+pub trait ReplayTransactionSubCommand {
+    fn replay_transaction_subcommand(self) -> Self;
+}
+
+impl ReplayTransactionSubCommand for App<'_, '_> {
+    fn replay_transaction_subcommand(self) -> Self {
+        self.subcommand(
+            SubCommand::with_name("replay-transaction")
+                .about(
+                    "Replay the block containing a transaction up to that transaction, then \
+                     re-execute it with instruction tracing and write a JSON report",
+                )
+                .arg(
+                    Arg::with_name("signature")
+                        .index(1)
+                        .value_name("SIGNATURE")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_parsable::<Signature>)
+                        .help("Signature of the transaction to replay"),
+                )
+                .arg(
+                    Arg::with_name("slot")
+                        .long("slot")
+                        .value_name("SLOT")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_slot)
+                        .help("Slot of the block containing the transaction"),
+                )
+                .arg(
+                    Arg::with_name("output_file")
+                        .long("output")
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .help("Write the report to FILE instead of stdout"),
+                )
+                .arg(&load_genesis_arg())
+                .args(&snapshot_args())
+                .args(&accounts_db_args()),
+        )
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TransactionReplayReport {
+    signature: String,
+    slot: Slot,
+    parent_slot: Slot,
+    /// Position of the transaction in the block
+    transaction_index: usize,
+    err: Option<String>,
+    compute_units_consumed: u64,
+    logs: Vec<String>,
+    instructions: Vec<InstructionReport>,
+    account_diffs: Vec<AccountDiffReport>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InstructionReport {
+    index_in_trace: usize,
+    stack_height: usize,
+    program_id: String,
+    compute_units_consumed: u64,
+    /// The registers of the program's VM before each executed instruction, empty for builtin
+    /// programs
+    register_trace: Vec<[u64; 12]>,
+}
+
+impl From<InstructionTrace> for InstructionReport {
+    fn from(trace: InstructionTrace) -> Self {
+        Self {
+            index_in_trace: trace.index_in_trace,
+            stack_height: trace.stack_height,
+            program_id: trace.program_id.to_string(),
+            compute_units_consumed: trace.compute_units_consumed,
+            register_trace: trace.register_trace,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AccountDiffReport {
+    pubkey: String,
+    pre_lamports: u64,
+    post_lamports: u64,
+    pre_owner: String,
+    post_owner: String,
+    pre_executable: bool,
+    post_executable: bool,
+    pre_data_len: usize,
+    post_data_len: usize,
+    data_changes: Vec<DataChangeReport>,
+}
+
+impl From<AccountStateDiff> for AccountDiffReport {
+    fn from(diff: AccountStateDiff) -> Self {
+        Self {
+            pubkey: diff.pubkey.to_string(),
+            pre_lamports: diff.pre_lamports,
+            post_lamports: diff.post_lamports,
+            pre_owner: diff.pre_owner.to_string(),
+            post_owner: diff.post_owner.to_string(),
+            pre_executable: diff.pre_executable,
+            post_executable: diff.post_executable,
+            pre_data_len: diff.pre_data_len,
+            post_data_len: diff.post_data_len,
+            data_changes: diff
+                .data_changes
+                .into_iter()
+                .map(DataChangeReport::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DataChangeReport {
+    offset: usize,
+    /// Base64 encoded
+    pre_bytes: String,
+    /// Base64 encoded
+    post_bytes: String,
+}
+
+impl From<DataRangeDiff> for DataChangeReport {
+    fn from(diff: DataRangeDiff) -> Self {
+        Self {
+            offset: diff.offset,
+            pre_bytes: BASE64_STANDARD.encode(diff.pre_bytes),
+            post_bytes: BASE64_STANDARD.encode(diff.post_bytes),
+        }
+    }
+}
+
+pub fn replay_transaction(ledger_path: &Path, matches: &ArgMatches<'_>) {
+    do_replay_transaction(ledger_path, matches).unwrap_or_else(|err| {
+        eprintln!("Failed to replay transaction: {err}");
+        exit(1);
+    });
+}
+
+fn do_replay_transaction(ledger_path: &Path, matches: &ArgMatches<'_>) -> Result<()> {
+    let ledger_path = canonicalize_ledger_path(ledger_path);
+    let signature = value_t_or_exit!(matches, "signature", Signature);
+    let slot = value_t_or_exit!(matches, "slot", Slot);
+
+    let blockstore = open_blockstore(&ledger_path, matches, AccessType::Secondary);
+    let parent_slot = blockstore
+        .meta(slot)?
+        .and_then(|meta| meta.parent_slot)
+        .ok_or_else(|| LedgerToolError::BadArgument(format!("slot {slot} has no parent")))?;
+    let entries = blockstore.get_slot_entries(slot, 0)?;
+    let Some((entry_index, transaction_index_in_entry)) =
+        entries.iter().enumerate().find_map(|(entry_index, entry)| {
+            entry
+                .transactions
+                .iter()
+                .position(|tx| tx.signatures.first() == Some(&signature))
+                .map(|index| (entry_index, index))
+        })
+    else {
+        return Err(LedgerToolError::BadArgument(format!(
+            "transaction {signature} not found in slot {slot}"
+        )));
+    };
+
+    let mut process_options = parse_process_options(&ledger_path, matches);
+    process_options.halt_at_slot = Some(parent_slot);
+    let genesis_config = open_genesis_config_by(&ledger_path, matches);
+    let LoadAndProcessLedgerOutput { bank_forks, .. } = load_and_process_ledger_or_exit(
+        matches,
+        &genesis_config,
+        Arc::new(blockstore),
+        process_options,
+        None,
+    );
+    let parent_bank = bank_forks.read().unwrap().working_bank();
+    if parent_bank.slot() != parent_slot {
+        return Err(LedgerToolError::Generic(format!(
+            "failed to load the bank at parent slot {parent_slot}, loaded slot {} instead",
+            parent_bank.slot()
+        )));
+    }
+
+    let leader = LeaderScheduleCache::new_from_bank(&parent_bank)
+        .slot_leader_at(slot, Some(&parent_bank))
+        .unwrap_or_else(|| *parent_bank.collector_id());
+    let bank = bank_forks
+        .write()
+        .unwrap()
+        .insert(Bank::new_from_parent(parent_bank, &leader, slot))
+        .clone_without_scheduler();
+
+    // Commit the transactions which precede the target, an entry at a time as the block was
+    // originally replayed
+    let mut transaction_index = 0;
+    let mut target_entry_transactions = entries[entry_index].transactions.clone();
+    let target_transaction = target_entry_transactions.remove(transaction_index_in_entry);
+    target_entry_transactions.truncate(transaction_index_in_entry);
+    let preceding_entries = entries[..entry_index]
+        .iter()
+        .map(|entry| entry.transactions.clone())
+        .chain(std::iter::once(target_entry_transactions));
+    for transactions in preceding_entries.filter(|transactions| !transactions.is_empty()) {
+        transaction_index += transactions.len();
+        bank.try_process_entry_transactions(transactions)
+            .map_err(|err| {
+                LedgerToolError::Generic(format!("failed to replay slot {slot}: {err}"))
+            })?;
+    }
+    info!("Replayed {transaction_index} transactions preceding {signature}");
+
+    // Programs are only able to record register traces when built with debugging features, so
+    // replace the bank's loaders and let the program cache recompile them
+    let compute_budget = bank.compute_budget().unwrap_or_default();
+    let program_runtime_environment_v1 =
+        create_program_runtime_environment_v1(&bank.feature_set, &compute_budget, false, true)
+            .map_err(|err| LedgerToolError::Generic(err.to_string()))?;
+    let program_runtime_environment_v2 =
+        create_program_runtime_environment_v2(&compute_budget, true);
+    bank.get_transaction_processor()
+        .configure_program_runtime_environments(
+            Some(Arc::new(program_runtime_environment_v1)),
+            Some(Arc::new(program_runtime_environment_v2)),
+        );
+
+    let sanitized_transaction = bank
+        .verify_transaction(target_transaction, TransactionVerificationMode::HashOnly)
+        .map_err(|err| {
+            LedgerToolError::Generic(format!("failed to sanitize transaction: {err}"))
+        })?;
+    let batch = bank.prepare_sanitized_batch(std::slice::from_ref(&sanitized_transaction));
+    let LoadAndExecuteTransactionsOutput {
+        mut processing_results,
+        mut state_diffs,
+        ..
+    } = bank.load_and_execute_transactions(
+        &batch,
+        MAX_PROCESSING_AGE,
+        &mut ExecuteTimings::default(),
+        &mut TransactionErrorMetrics::default(),
+        TransactionProcessingConfig {
+            compute_budget: bank.compute_budget(),
+            log_messages_bytes_limit: None,
+            recording_config: ExecutionRecordingConfig::new_single_setting(true),
+            transaction_account_lock_limit: Some(bank.get_transaction_account_lock_limit()),
+            record_state_diffs: true,
+            record_instruction_traces: true,
+            ..TransactionProcessingConfig::default()
+        },
+    );
+
+    let mut report = TransactionReplayReport {
+        signature: signature.to_string(),
+        slot,
+        parent_slot: bank.parent_slot(),
+        transaction_index,
+        err: None,
+        compute_units_consumed: 0,
+        logs: vec![],
+        instructions: vec![],
+        account_diffs: state_diffs
+            .pop()
+            .map(|state_diff| {
+                state_diff
+                    .accounts
+                    .into_iter()
+                    .map(AccountDiffReport::from)
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+    match processing_results.pop().unwrap() {
+        Ok(ProcessedTransaction::Executed(executed_transaction)) => {
+            let details = executed_transaction.execution_details;
+            report.err = details.status.err().map(|err| err.to_string());
+            report.compute_units_consumed = details.executed_units;
+            report.logs = details.log_messages.unwrap_or_default();
+            report.instructions = details
+                .instruction_traces
+                .unwrap_or_default()
+                .into_iter()
+                .map(InstructionReport::from)
+                .collect();
+        }
+        Ok(ProcessedTransaction::FeesOnly(fees_only_transaction)) => {
+            report.err = Some(fees_only_transaction.load_error.to_string());
+        }
+        Err(err) => report.err = Some(err.to_string()),
+    }
+
+    let mut output: Box<dyn Write> = match matches.value_of("output_file") {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout()),
+    };
+    serde_json::to_writer_pretty(&mut output, &report)?;
+    writeln!(output)?;
+    Ok(())
+}
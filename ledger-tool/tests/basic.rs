@@ -1,9 +1,16 @@
 use {
     assert_cmd::prelude::*,
+    solana_entry::entry::next_entry,
     solana_ledger::{
         blockstore, blockstore::Blockstore, create_new_tmp_ledger_auto_delete,
         genesis_utils::create_genesis_config, get_tmp_ledger_path_auto_delete,
     },
+    solana_sdk::{
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+        signature::{Signature, Signer},
+        system_program, system_transaction,
+    },
     std::{
         path::Path,
         process::{Command, Output},
@@ -87,3 +94,83 @@ fn ledger_tool_copy_test() {
         assert!(!src_slot_output.stdout.is_empty());
     }
 }
+
+#[test]
+fn ledger_tool_replay_transaction_test() {
+    let genesis_config_info = create_genesis_config(100 * LAMPORTS_PER_SOL);
+    let genesis_config = &genesis_config_info.genesis_config;
+    let (ledger_path, blockhash) = create_new_tmp_ledger_auto_delete!(genesis_config);
+
+    // Slot 1 holds a single transfer from the mint
+    let recipient = Pubkey::new_unique();
+    let transaction = system_transaction::transfer(
+        &genesis_config_info.mint_keypair,
+        &recipient,
+        LAMPORTS_PER_SOL,
+        genesis_config.hash(),
+    );
+    let signature = transaction.signatures[0];
+    let entries = vec![next_entry(&blockhash, 1, vec![transaction])];
+    let shreds = blockstore::entries_to_test_shreds(
+        &entries, /*slot:*/ 1, /*parent_slot:*/ 0, /*is_full_slot:*/ true,
+        /*version:*/ 0, /*merkle_variant:*/ true,
+    );
+    Blockstore::open(ledger_path.path())
+        .unwrap()
+        .insert_shreds(shreds, None, false)
+        .unwrap();
+
+    let ledger_path = ledger_path.path().to_str().unwrap();
+    let output = run_ledger_tool(&[
+        "-l",
+        ledger_path,
+        "replay-transaction",
+        &signature.to_string(),
+        "--slot",
+        "1",
+    ]);
+    assert!(output.status.success());
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["signature"], signature.to_string());
+    assert_eq!(report["slot"], 1);
+    assert_eq!(report["parentSlot"], 0);
+    assert_eq!(report["transactionIndex"], 0);
+    assert!(report["err"].is_null());
+
+    // The transfer is a single builtin instruction, which records no registers
+    let instructions = report["instructions"].as_array().unwrap();
+    assert_eq!(instructions.len(), 1);
+    assert_eq!(instructions[0]["indexInTrace"], 0);
+    assert_eq!(instructions[0]["stackHeight"], 1);
+    assert_eq!(
+        instructions[0]["programId"],
+        system_program::id().to_string()
+    );
+    assert!(instructions[0]["registerTrace"]
+        .as_array()
+        .unwrap()
+        .is_empty());
+
+    let account_diffs = report["accountDiffs"].as_array().unwrap();
+    let recipient_diff = account_diffs
+        .iter()
+        .find(|diff| diff["pubkey"] == recipient.to_string())
+        .unwrap();
+    assert_eq!(recipient_diff["preLamports"], 0);
+    assert_eq!(recipient_diff["postLamports"], LAMPORTS_PER_SOL);
+    assert!(account_diffs
+        .iter()
+        .any(|diff| diff["pubkey"] == genesis_config_info.mint_keypair.pubkey().to_string()));
+
+    // A signature which is not in the slot is an error
+    let output = run_ledger_tool(&[
+        "-l",
+        ledger_path,
+        "replay-transaction",
+        &Signature::default().to_string(),
+        "--slot",
+        "1",
+    ]);
+    assert!(!output.status.success());
+}
@@ -239,6 +239,7 @@ pub struct Blockstore {
     block_height_cf: LedgerColumn<cf::BlockHeight>,
     blocktime_cf: LedgerColumn<cf::Blocktime>,
     code_shred_cf: LedgerColumn<cf::ShredCode>,
+    contended_accounts_cf: LedgerColumn<cf::ContendedAccounts>,
     data_shred_cf: LedgerColumn<cf::ShredData>,
     dead_slots_cf: LedgerColumn<cf::DeadSlots>,
     duplicate_slots_cf: LedgerColumn<cf::DuplicateSlots>,
@@ -392,6 +393,7 @@ impl Blockstore {
         let block_height_cf = db.column();
         let blocktime_cf = db.column();
         let code_shred_cf = db.column();
+        let contended_accounts_cf = db.column();
         let data_shred_cf = db.column();
         let dead_slots_cf = db.column();
         let duplicate_slots_cf = db.column();
@@ -429,6 +431,7 @@ impl Blockstore {
             block_height_cf,
             blocktime_cf,
             code_shred_cf,
+            contended_accounts_cf,
             data_shred_cf,
             dead_slots_cf,
             duplicate_slots_cf,
@@ -881,6 +884,7 @@ impl Blockstore {
         self.index_cf.submit_rocksdb_cf_metrics();
         self.data_shred_cf.submit_rocksdb_cf_metrics();
         self.code_shred_cf.submit_rocksdb_cf_metrics();
+        self.contended_accounts_cf.submit_rocksdb_cf_metrics();
         self.transaction_status_cf.submit_rocksdb_cf_metrics();
         self.address_signatures_cf.submit_rocksdb_cf_metrics();
         self.transaction_memos_cf.submit_rocksdb_cf_metrics();
@@ -2658,6 +2662,25 @@ impl Blockstore {
         self.block_height_cf.put(slot, &block_height)
     }
 
+    /// Returns the most write-lock contended accounts of `slot`, if they were recorded
+    pub fn get_contended_accounts(&self, slot: Slot) -> Result<Option<Vec<ContendedAccount>>> {
+        self.rpc_api_metrics
+            .num_get_contended_accounts
+            .fetch_add(1, Ordering::Relaxed);
+        let _lock = self.check_lowest_cleanup_slot(slot)?;
+
+        self.contended_accounts_cf.get(slot)
+    }
+
+    pub fn write_contended_accounts(
+        &self,
+        slot: Slot,
+        contended_accounts: &[ContendedAccount],
+    ) -> Result<()> {
+        let bytes = serialize(contended_accounts)?;
+        self.contended_accounts_cf.put_bytes(slot, &bytes)
+    }
+
     /// The first complete block that is available in the Blockstore ledger
     pub fn get_first_available_block(&self) -> Result<Slot> {
         let mut root_iterator = self.rooted_slot_iterator(self.lowest_slot_with_genesis())?;
@@ -10254,6 +10277,40 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_write_contended_accounts() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+
+        let slot = 5;
+        assert_eq!(blockstore.get_contended_accounts(slot).unwrap(), None);
+
+        let contended_accounts = vec![
+            ContendedAccount {
+                pubkey: Pubkey::new_unique(),
+                compute_units: 2_000_000,
+                transaction_count: 20,
+                rejected_transaction_count: 3,
+            },
+            ContendedAccount {
+                pubkey: Pubkey::new_unique(),
+                compute_units: 500_000,
+                transaction_count: 5,
+                rejected_transaction_count: 0,
+            },
+        ];
+        blockstore
+            .write_contended_accounts(slot, &contended_accounts)
+            .unwrap();
+        assert_eq!(
+            blockstore.get_contended_accounts(slot).unwrap(),
+            Some(contended_accounts)
+        );
+
+        blockstore.purge_and_compact_slots(0, slot);
+        assert_eq!(blockstore.get_contended_accounts(slot).unwrap(), None);
+    }
+
     #[test]
     fn test_lowest_slot() {
         let ledger_path = get_tmp_ledger_path_auto_delete!();
@@ -102,6 +102,8 @@ const PROGRAM_COSTS_CF: &str = "program_costs";
 const OPTIMISTIC_SLOTS_CF: &str = "optimistic_slots";
 /// Column family for merkle roots
 const MERKLE_ROOT_META_CF: &str = "merkle_root_meta";
+/// Column family for ContendedAccounts
+const CONTENDED_ACCOUNTS_CF: &str = "contended_accounts";
 
 #[derive(Error, Debug)]
 pub enum BlockstoreError {
@@ -361,6 +363,16 @@ pub mod columns {
     /// * value type: [`blockstore_meta::MerkleRootMeta`]`
     pub struct MerkleRootMeta;
 
+    #[derive(Debug)]
+    /// The contended accounts column
+    ///
+    /// Stores the writable accounts with the highest compute unit usage in
+    /// each slot, in descending order of compute units.
+    ///
+    /// * index type: `u64` (see [`SlotColumn`])
+    /// * value type: `Vec<`[`blockstore_meta::ContendedAccount`]`>`
+    pub struct ContendedAccounts;
+
     // When adding a new column ...
     // - Add struct below and implement `Column` and `ColumnName` traits
     // - Add descriptor in Rocks::cf_descriptors() and name in Rocks::columns()
@@ -498,6 +510,7 @@ impl Rocks {
             new_cf_descriptor::<ProgramCosts>(options, oldest_slot),
             new_cf_descriptor::<OptimisticSlots>(options, oldest_slot),
             new_cf_descriptor::<MerkleRootMeta>(options, oldest_slot),
+            new_cf_descriptor::<ContendedAccounts>(options, oldest_slot),
         ];
 
         // If the access type is Secondary, we don't need to open all of the
@@ -571,6 +584,7 @@ impl Rocks {
             ProgramCosts::NAME,
             OptimisticSlots::NAME,
             MerkleRootMeta::NAME,
+            ContendedAccounts::NAME,
         ]
     }
 
@@ -1116,6 +1130,14 @@ impl TypedColumn for columns::BlockHeight {
     type Type = u64;
 }
 
+impl SlotColumn for columns::ContendedAccounts {}
+impl ColumnName for columns::ContendedAccounts {
+    const NAME: &'static str = CONTENDED_ACCOUNTS_CF;
+}
+impl TypedColumn for columns::ContendedAccounts {
+    type Type = Vec<blockstore_meta::ContendedAccount>;
+}
+
 impl ColumnName for columns::ProgramCosts {
     const NAME: &'static str = PROGRAM_COSTS_CF;
 }
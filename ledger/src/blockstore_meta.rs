@@ -5,6 +5,7 @@ use {
     solana_sdk::{
         clock::{Slot, UnixTimestamp},
         hash::Hash,
+        pubkey::Pubkey,
     },
     std::{
         collections::BTreeSet,
@@ -557,6 +558,31 @@ pub struct ProgramCost {
     pub cost: u64,
 }
 
+/// Write-lock contention on an account during a slot, as tracked by the
+/// bank's cost tracker.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ContendedAccount {
+    pub pubkey: Pubkey,
+    /// Compute units consumed by the transactions write-locking the account
+    pub compute_units: u64,
+    /// Number of transactions write-locking the account
+    pub transaction_count: u64,
+    /// Number of transactions which were not included because they would have
+    /// exceeded the account's compute unit limit
+    pub rejected_transaction_count: u64,
+}
+
+impl From<solana_cost_model::cost_tracker::ContendedAccount> for ContendedAccount {
+    fn from(account: solana_cost_model::cost_tracker::ContendedAccount) -> Self {
+        Self {
+            pubkey: account.pubkey,
+            compute_units: account.compute_units,
+            transaction_count: account.transaction_count,
+            rejected_transaction_count: account.rejected_transaction_count,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
 pub struct OptimisticSlotMetaV0 {
     pub hash: Hash,
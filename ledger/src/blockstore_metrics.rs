@@ -148,6 +148,7 @@ impl BlockstoreInsertionMetrics {
 pub(crate) struct BlockstoreRpcApiMetrics {
     pub num_get_block_height: AtomicU64,
     pub num_get_complete_transaction: AtomicU64,
+    pub num_get_contended_accounts: AtomicU64,
     pub num_get_confirmed_signatures_for_address: AtomicU64,
     pub num_get_confirmed_signatures_for_address2: AtomicU64,
     pub num_get_rooted_block: AtomicU64,
@@ -163,6 +164,7 @@ impl BlockstoreRpcApiMetrics {
         let num_get_block_height = self.num_get_block_height.swap(0, Ordering::Relaxed);
         let num_get_complete_transaction =
             self.num_get_complete_transaction.swap(0, Ordering::Relaxed);
+        let num_get_contended_accounts = self.num_get_contended_accounts.swap(0, Ordering::Relaxed);
         let num_get_confirmed_signatures_for_address = self
             .num_get_confirmed_signatures_for_address
             .swap(0, Ordering::Relaxed);
@@ -182,6 +184,7 @@ impl BlockstoreRpcApiMetrics {
 
         let total_num_queries = num_get_block_height
             .saturating_add(num_get_complete_transaction)
+            .saturating_add(num_get_contended_accounts)
             .saturating_add(num_get_confirmed_signatures_for_address)
             .saturating_add(num_get_confirmed_signatures_for_address2)
             .saturating_add(num_get_rooted_block)
@@ -200,6 +203,11 @@ impl BlockstoreRpcApiMetrics {
                     num_get_complete_transaction as i64,
                     i64
                 ),
+                (
+                    "num_get_contended_accounts",
+                    num_get_contended_accounts as i64,
+                    i64
+                ),
                 (
                     "num_get_confirmed_signatures_for_address",
                     num_get_confirmed_signatures_for_address as i64,
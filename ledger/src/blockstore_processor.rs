@@ -24,7 +24,10 @@ use {
         self, create_ticks, Entry, EntrySlice, EntryType, EntryVerificationStatus, VerifyRecyclers,
     },
     solana_measure::{measure::Measure, measure_us},
-    solana_metrics::datapoint_error,
+    solana_metrics::{
+        datapoint_error,
+        transaction_tracing::{self, TransactionSpan},
+    },
     solana_rayon_threadlimit::{get_max_thread_count, get_thread_count},
     solana_runtime::{
         accounts_background_service::{AbsRequestSender, SnapshotRequestKind},
@@ -159,6 +162,16 @@ pub fn execute_batch(
 
     let mut mint_decimals: HashMap<Pubkey, u8> = HashMap::new();
 
+    let spans: Vec<_> = if transaction_tracing::is_enabled() {
+        batch
+            .sanitized_transactions()
+            .iter()
+            .map(|tx| TransactionSpan::start(tx.signature().as_ref(), "replay"))
+            .collect()
+    } else {
+        vec![]
+    };
+
     let pre_token_balances = if record_token_balances {
         collect_token_balances(bank, batch, &mut mint_decimals)
     } else {
@@ -174,6 +187,21 @@ pub fn execute_batch(
         log_messages_bytes_limit,
     );
 
+    for (span, commit_result) in spans.into_iter().zip(&commit_results) {
+        let Some(mut span) = span else {
+            continue;
+        };
+        span.add_attribute("slot", bank.slot());
+        match commit_result {
+            Ok(committed_tx) => match &committed_tx.status {
+                Ok(()) => span.add_attribute("status", "ok"),
+                Err(err) => span.add_attribute("status", err),
+            },
+            Err(err) => span.set_error(err),
+        }
+        span.end();
+    }
+
     bank_utils::find_and_send_votes(
         batch.sanitized_transactions(),
         &commit_results,
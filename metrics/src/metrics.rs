@@ -1,7 +1,7 @@
 //! The `metrics` module enables sending measurements to an `InfluxDB` instance
 
 use {
-    crate::{counter::CounterPoint, datapoint::DataPoint},
+    crate::{counter::CounterPoint, datapoint::DataPoint, prometheus::PrometheusMetricsWriter},
     crossbeam_channel::{unbounded, Receiver, RecvTimeoutError, Sender},
     gethostname::gethostname,
     lazy_static::lazy_static,
@@ -35,6 +35,10 @@ pub enum MetricsError {
     ConfigIncomplete,
     #[error("SOLANA_METRICS_CONFIG database mismatch: {0}")]
     DbMismatch(String),
+    #[error("SOLANA_METRICS_PROMETHEUS_ADDR is invalid: '{0}'")]
+    PrometheusAddrInvalid(String),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
 }
 
 impl From<MetricsError> for String {
@@ -67,6 +71,17 @@ pub trait MetricsWriter {
     // Write the points and empty the vector.  Called on the internal
     // MetricsAgent worker thread.
     fn write(&self, points: Vec<DataPoint>);
+
+    // Whether counters are passed to `write_counters()`, rather than being
+    // written as points with a `count` field.
+    fn aggregates_counters(&self) -> bool {
+        false
+    }
+
+    // Write the counters submitted since the last write, each with the count
+    // accumulated in its bucket.  Called on the internal MetricsAgent worker
+    // thread, before `write()`, if `aggregates_counters()` is true.
+    fn write_counters(&self, _counters: Vec<CounterPoint>) {}
 }
 
 struct InfluxDbMetricsWriter {
@@ -180,14 +195,32 @@ impl Default for MetricsAgent {
             })
             .unwrap_or(4000);
 
-        Self::new(
-            Arc::new(InfluxDbMetricsWriter::new()),
-            Duration::from_secs(10),
-            max_points_per_sec,
-        )
+        // Serve the metrics for Prometheus to scrape instead of pushing them to InfluxDB
+        let prometheus_writer = env::var("SOLANA_METRICS_PROMETHEUS_ADDR")
+            .ok()
+            .and_then(|addr| {
+                build_prometheus_writer(&addr)
+                    .map_err(|err| warn!("prometheus metrics disabled: {}", err))
+                    .ok()
+            });
+        let writer: Arc<dyn MetricsWriter + Send + Sync> = match prometheus_writer {
+            Some(writer) => Arc::new(writer),
+            None => Arc::new(InfluxDbMetricsWriter::new()),
+        };
+
+        Self::new(writer, Duration::from_secs(10), max_points_per_sec)
     }
 }
 
+fn build_prometheus_writer(addr: &str) -> Result<PrometheusMetricsWriter, MetricsError> {
+    let addr = addr
+        .parse()
+        .map_err(|_| MetricsError::PrometheusAddrInvalid(addr.to_string()))?;
+    let writer = PrometheusMetricsWriter::new(addr)?;
+    info!("serving prometheus metrics on {}", writer.local_addr());
+    Ok(writer)
+}
+
 impl MetricsAgent {
     pub fn new(
         writer: Arc<dyn MetricsWriter + Send + Sync>,
@@ -272,6 +305,9 @@ impl MetricsAgent {
         let now = Instant::now();
         let secs_since_last_write = now.duration_since(last_write_time).as_secs();
 
+        if writer.aggregates_counters() {
+            writer.write_counters(counters.drain().map(|(_, counter)| counter).collect());
+        }
         writer.write(Self::combine_points(
             max_points,
             max_points_per_sec,
@@ -398,7 +434,7 @@ fn get_singleton_agent() -> &'static MetricsAgent {
 }
 
 lazy_static! {
-    static ref HOST_ID: Arc<RwLock<String>> = {
+    pub(crate) static ref HOST_ID: Arc<RwLock<String>> = {
         Arc::new(RwLock::new({
             let hostname: String = gethostname()
                 .into_string()
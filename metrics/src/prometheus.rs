@@ -0,0 +1,442 @@
+//! The `prometheus` module serves metrics over HTTP in the Prometheus text exposition format,
+//! as an alternative to pushing them to an `InfluxDB` instance.
+//!
+//! Submitted metrics are aggregated as follows:
+//! - each numeric field of a `DataPoint` is a gauge holding the last submitted value, except for
+//!   timing fields (suffixed with `_us`) which are histograms of the submitted values
+//! - each `CounterPoint` is a counter of the total count
+//!
+//! Tags, as well as the host id, are exported as labels.
+
+use {
+    crate::{counter::CounterPoint, datapoint::DataPoint, metrics::MetricsWriter},
+    log::*,
+    std::{
+        collections::BTreeMap,
+        fmt::Write as _,
+        io::{self, Read, Write},
+        net::{SocketAddr, TcpListener, TcpStream},
+        sync::{Arc, RwLock, Weak},
+        thread,
+        time::Duration,
+    },
+};
+
+const METRIC_NAME_PREFIX: &str = "solana_";
+const TIMING_FIELD_SUFFIX: &str = "_us";
+/// Upper bounds of the histogram buckets of timing fields, in microseconds
+const TIMING_BUCKETS_US: [f64; 16] = [
+    10.0,
+    50.0,
+    100.0,
+    250.0,
+    500.0,
+    1_000.0,
+    2_500.0,
+    5_000.0,
+    10_000.0,
+    25_000.0,
+    50_000.0,
+    100_000.0,
+    250_000.0,
+    500_000.0,
+    1_000_000.0,
+    10_000_000.0,
+];
+const MAX_REQUEST_HEADER_LEN: usize = 8 * 1024;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+type Labels = Vec<(String, String)>;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Histogram {
+    /// Number of observed values less than or equal to each bound of `TIMING_BUCKETS_US`
+    cumulative_bucket_counts: [u64; TIMING_BUCKETS_US.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self {
+            cumulative_bucket_counts: [0; TIMING_BUCKETS_US.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        for (bound, bucket_count) in TIMING_BUCKETS_US
+            .iter()
+            .zip(self.cumulative_bucket_counts.iter_mut())
+        {
+            if value <= *bound {
+                *bucket_count = bucket_count.saturating_add(1);
+            }
+        }
+        self.sum += value;
+        self.count = self.count.saturating_add(1);
+    }
+}
+
+/// The metrics aggregated from submitted points and counters, keyed by metric name and labels
+#[derive(Debug, Default)]
+struct Registry {
+    gauges: BTreeMap<String, BTreeMap<Labels, f64>>,
+    counters: BTreeMap<String, BTreeMap<Labels, f64>>,
+    histograms: BTreeMap<String, BTreeMap<Labels, Histogram>>,
+}
+
+impl Registry {
+    fn record_point(&mut self, point: &DataPoint, host_id: &str) {
+        let labels = labels(host_id, &point.tags);
+        for (field, value) in &point.fields {
+            // string fields are not exported
+            let Some(value) = parse_field_value(value) else {
+                continue;
+            };
+            let name = metric_name(&format!("{}_{}", point.name, field));
+            if field.ends_with(TIMING_FIELD_SUFFIX) {
+                self.histograms
+                    .entry(name)
+                    .or_default()
+                    .entry(labels.clone())
+                    .or_default()
+                    .observe(value);
+            } else {
+                self.gauges
+                    .entry(name)
+                    .or_default()
+                    .insert(labels.clone(), value);
+            }
+        }
+    }
+
+    fn record_counter(&mut self, counter: &CounterPoint, host_id: &str) {
+        let total = self
+            .counters
+            .entry(metric_name(&format!("{}_total", counter.name)))
+            .or_default()
+            .entry(labels(host_id, &[]))
+            .or_default();
+        *total += counter.count.max(0) as f64;
+    }
+
+    /// Renders the metrics in the Prometheus text exposition format
+    fn render(&self) -> String {
+        let mut text = String::new();
+        for (name, series) in &self.gauges {
+            let _ = writeln!(text, "# TYPE {name} gauge");
+            for (labels, value) in series {
+                let _ = writeln!(
+                    text,
+                    "{name}{} {}",
+                    format_labels(labels, None),
+                    format_value(*value)
+                );
+            }
+        }
+        for (name, series) in &self.counters {
+            let _ = writeln!(text, "# TYPE {name} counter");
+            for (labels, value) in series {
+                let _ = writeln!(
+                    text,
+                    "{name}{} {}",
+                    format_labels(labels, None),
+                    format_value(*value)
+                );
+            }
+        }
+        for (name, series) in &self.histograms {
+            let _ = writeln!(text, "# TYPE {name} histogram");
+            for (labels, histogram) in series {
+                for (bound, bucket_count) in TIMING_BUCKETS_US
+                    .iter()
+                    .zip(histogram.cumulative_bucket_counts)
+                {
+                    let _ = writeln!(
+                        text,
+                        "{name}_bucket{} {bucket_count}",
+                        format_labels(labels, Some(&format_value(*bound)))
+                    );
+                }
+                let _ = writeln!(
+                    text,
+                    "{name}_bucket{} {}",
+                    format_labels(labels, Some("+Inf")),
+                    histogram.count
+                );
+                let _ = writeln!(
+                    text,
+                    "{name}_sum{} {}",
+                    format_labels(labels, None),
+                    format_value(histogram.sum)
+                );
+                let _ = writeln!(
+                    text,
+                    "{name}_count{} {}",
+                    format_labels(labels, None),
+                    histogram.count
+                );
+            }
+        }
+        text
+    }
+}
+
+/// A `MetricsWriter` which aggregates the written metrics and serves them on an HTTP endpoint for
+/// Prometheus to scrape
+pub struct PrometheusMetricsWriter {
+    registry: Arc<RwLock<Registry>>,
+    local_addr: SocketAddr,
+}
+
+impl PrometheusMetricsWriter {
+    /// Serves the metrics on `addr`, on any request path
+    pub fn new(addr: SocketAddr) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let local_addr = listener.local_addr()?;
+        let registry = Arc::new(RwLock::new(Registry::default()));
+
+        thread::Builder::new()
+            .name("solMetricsProm".into())
+            .spawn({
+                let registry = Arc::downgrade(&registry);
+                move || Self::serve(listener, registry)
+            })?;
+
+        Ok(Self {
+            registry,
+            local_addr,
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    fn serve(listener: TcpListener, registry: Weak<RwLock<Registry>>) {
+        for stream in listener.incoming() {
+            // Stop serving once the writer is dropped
+            let Some(registry) = registry.upgrade() else {
+                break;
+            };
+            let result = stream.and_then(|stream| Self::respond(stream, &registry));
+            if let Err(err) = result {
+                debug!("prometheus metrics request failed: {}", err);
+            }
+        }
+    }
+
+    fn respond(mut stream: TcpStream, registry: &RwLock<Registry>) -> io::Result<()> {
+        stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+        stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+
+        // The request is not needed beyond its method, consume its header so that closing the
+        // connection does not reset it
+        let mut request = Vec::new();
+        let mut buf = [0; 1024];
+        while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+            let len = stream.read(&mut buf)?;
+            if len == 0 || request.len() + len > MAX_REQUEST_HEADER_LEN {
+                break;
+            }
+            request.extend_from_slice(&buf[..len]);
+        }
+
+        let response = if request.starts_with(b"GET ") {
+            let body = registry.read().unwrap().render();
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+        } else {
+            "HTTP/1.1 405 Method Not Allowed\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                .to_string()
+        };
+        stream.write_all(response.as_bytes())?;
+        stream.flush()
+    }
+}
+
+impl MetricsWriter for PrometheusMetricsWriter {
+    fn write(&self, points: Vec<DataPoint>) {
+        let host_id = crate::metrics::HOST_ID.read().unwrap();
+        let mut registry = self.registry.write().unwrap();
+        for point in &points {
+            registry.record_point(point, &host_id);
+        }
+    }
+
+    fn aggregates_counters(&self) -> bool {
+        true
+    }
+
+    fn write_counters(&self, counters: Vec<CounterPoint>) {
+        let host_id = crate::metrics::HOST_ID.read().unwrap();
+        let mut registry = self.registry.write().unwrap();
+        for counter in &counters {
+            registry.record_counter(counter, &host_id);
+        }
+    }
+}
+
+/// Parses a field value serialized by `DataPoint`, returns `None` for string fields
+fn parse_field_value(value: &str) -> Option<f64> {
+    if let Some(value) = value.strip_suffix('i') {
+        return value.parse::<i64>().ok().map(|value| value as f64);
+    }
+    match value {
+        "true" => Some(1.0),
+        "false" => Some(0.0),
+        _ => value.parse().ok(),
+    }
+}
+
+fn labels(host_id: &str, tags: &[(&'static str, String)]) -> Labels {
+    std::iter::once(("host_id".to_string(), host_id.to_string()))
+        .chain(
+            tags.iter()
+                .map(|(name, value)| (sanitize_name(name), value.clone())),
+        )
+        .collect()
+}
+
+fn metric_name(name: &str) -> String {
+    sanitize_name(&format!("{METRIC_NAME_PREFIX}{name}"))
+}
+
+/// Replaces the characters which are not allowed in metric and label names
+fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn format_labels(labels: &Labels, le: Option<&str>) -> String {
+    let mut text = String::from("{");
+    let le = le.map(|le| ("le", le));
+    for (i, (name, value)) in labels
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .chain(le)
+        .enumerate()
+    {
+        if i > 0 {
+            text.push(',');
+        }
+        let value = value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n");
+        let _ = write!(text, "{name}=\"{value}\"");
+    }
+    text.push('}');
+    text
+}
+
+fn format_value(value: f64) -> String {
+    if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, std::time::SystemTime};
+
+    #[test]
+    fn test_parse_field_value() {
+        let point = DataPoint::new("measurement")
+            .add_field_i64("i", -42)
+            .add_field_f64("f", 1.5)
+            .add_field_bool("b", true)
+            .add_field_str("s", "123")
+            .to_owned();
+        let values: Vec<_> = point
+            .fields
+            .iter()
+            .map(|(_, value)| parse_field_value(value))
+            .collect();
+        assert_eq!(values, vec![Some(-42.0), Some(1.5), Some(1.0), None]);
+    }
+
+    #[test]
+    fn test_render() {
+        let mut registry = Registry::default();
+        for (count, elapsed_us) in [(1, 20), (3, 700)] {
+            registry.record_point(
+                DataPoint::new("bank-stats")
+                    .add_tag("kind", "a\"b")
+                    .add_field_i64("count", count)
+                    .add_field_i64("elapsed_us", elapsed_us)
+                    .add_field_str("leader", "unused"),
+                "host",
+            );
+        }
+        for count in [4, 3] {
+            registry.record_counter(
+                &CounterPoint {
+                    name: "packets",
+                    count,
+                    timestamp: SystemTime::now(),
+                },
+                "host",
+            );
+        }
+
+        let text = registry.render();
+        let labels = r#"host_id="host",kind="a\"b""#;
+        assert!(text.contains("# TYPE solana_bank_stats_count gauge\n"));
+        assert!(text.contains(&format!("solana_bank_stats_count{{{labels}}} 3\n")));
+        assert!(!text.contains("leader"));
+        assert!(text.contains("# TYPE solana_packets_total counter\n"));
+        assert!(text.contains("solana_packets_total{host_id=\"host\"} 7\n"));
+        assert!(text.contains("# TYPE solana_bank_stats_elapsed_us histogram\n"));
+        assert!(text.contains(&format!(
+            "solana_bank_stats_elapsed_us_bucket{{{labels},le=\"10\"}} 0\n"
+        )));
+        assert!(text.contains(&format!(
+            "solana_bank_stats_elapsed_us_bucket{{{labels},le=\"50\"}} 1\n"
+        )));
+        assert!(text.contains(&format!(
+            "solana_bank_stats_elapsed_us_bucket{{{labels},le=\"1000\"}} 2\n"
+        )));
+        assert!(text.contains(&format!(
+            "solana_bank_stats_elapsed_us_bucket{{{labels},le=\"+Inf\"}} 2\n"
+        )));
+        assert!(text.contains(&format!(
+            "solana_bank_stats_elapsed_us_sum{{{labels}}} 720\n"
+        )));
+        assert!(text.contains(&format!(
+            "solana_bank_stats_elapsed_us_count{{{labels}}} 2\n"
+        )));
+    }
+
+    #[test]
+    fn test_scrape() {
+        let writer = PrometheusMetricsWriter::new("127.0.0.1:0".parse().unwrap()).unwrap();
+        writer.write(vec![DataPoint::new("measurement")
+            .add_field_i64("value", 42)
+            .to_owned()]);
+
+        let mut stream = TcpStream::connect(writer.local_addr()).unwrap();
+        stream
+            .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("\r\n\r\n# TYPE solana_measurement_value gauge\n"));
+        assert!(response.contains("solana_measurement_value{host_id="));
+        assert!(response.ends_with("} 42\n"));
+    }
+}
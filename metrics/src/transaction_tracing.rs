@@ -0,0 +1,630 @@
+//! The `transaction_tracing` module records sampled, per-transaction spans of the time spent in
+//! each stage of the transaction pipeline, and exports them to an OpenTelemetry collector over
+//! OTLP/HTTP (JSON encoding).
+//!
+//! Tracing is disabled unless `SOLANA_TRACING_OTLP_ENDPOINT` is set. The decision whether a
+//! transaction is sampled is derived from its signature, so every stage (and every validator)
+//! samples the same transactions. The trace id of a transaction is the first 16 bytes of its
+//! signature, and the full signature is recorded in the `transaction.signature` attribute.
+//!
+//! The spans of the stages a transaction went through on this validator are the children of a
+//! root `transaction` span, which covers all of them. The exporter emits the root span once no
+//! stage span of the transaction has ended for a while.
+//!
+//! Spans are sent to a background thread through a bounded channel; if the channel is full the
+//! span is dropped rather than blocking the pipeline.
+
+use {
+    crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender, TrySendError},
+    lazy_static::lazy_static,
+    log::*,
+    solana_sha256_hasher::hashv,
+    std::{
+        collections::{HashMap, HashSet, VecDeque},
+        env,
+        fmt::Write,
+        sync::atomic::{AtomicU64, Ordering},
+        thread,
+        time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+    },
+};
+
+const SIGNATURE_BYTES: usize = 64;
+const TRACE_ID_BYTES: usize = 16;
+const SPAN_ID_BYTES: usize = 8;
+const DEFAULT_SAMPLE_RATIO: f64 = 0.001;
+const MAX_PENDING_SPANS: usize = 10_000;
+const MAX_SPANS_PER_EXPORT: usize = 1_000;
+const EXPORT_INTERVAL: Duration = Duration::from_secs(1);
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(5);
+// How long after the last stage span of a transaction ended its root span is exported
+const ROOT_SPAN_IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+// Number of exported root spans remembered, so that a late stage span doesn't export one twice
+const MAX_EXPORTED_ROOT_SPANS: usize = 100_000;
+const ROOT_SPAN_NAME: &str = "transaction";
+const SIGNATURE_ATTRIBUTE: &str = "transaction.signature";
+const SERVICE_NAME: &str = "solana-validator";
+const SCOPE_NAME: &str = "solana-transaction-tracing";
+
+/// A span that has ended and is waiting to be exported.
+#[derive(Debug, Clone, PartialEq)]
+struct FinishedSpan {
+    trace_id: [u8; TRACE_ID_BYTES],
+    span_id: [u8; SPAN_ID_BYTES],
+    parent_span_id: Option<[u8; SPAN_ID_BYTES]>,
+    name: &'static str,
+    start_time: SystemTime,
+    end_time: SystemTime,
+    attributes: Vec<(&'static str, String)>,
+    error: Option<String>,
+}
+
+/// The root span of a transaction, spanning its stage spans seen so far.
+#[derive(Debug)]
+struct PendingRootSpan {
+    start_time: SystemTime,
+    end_time: SystemTime,
+    signature: Option<String>,
+    last_update: Instant,
+}
+
+/// The root spans of the transactions traced on this validator.
+#[derive(Debug, Default)]
+struct RootSpans {
+    pending: HashMap<[u8; TRACE_ID_BYTES], PendingRootSpan>,
+    exported: HashSet<[u8; TRACE_ID_BYTES]>,
+    // Trace ids of the exported root spans, oldest first
+    exported_order: VecDeque<[u8; TRACE_ID_BYTES]>,
+}
+
+impl RootSpans {
+    /// Makes `span` a child of the root span of its transaction, and extends the root span to
+    /// cover it.
+    fn add_child(&mut self, span: &mut FinishedSpan, host_id: &str, now: Instant) {
+        span.parent_span_id = Some(root_span_id(&span.trace_id, host_id));
+        if self.exported.contains(&span.trace_id) {
+            return;
+        }
+        let signature = span
+            .attributes
+            .iter()
+            .find(|(key, _)| *key == SIGNATURE_ATTRIBUTE)
+            .map(|(_, value)| value.clone());
+        let root = self
+            .pending
+            .entry(span.trace_id)
+            .or_insert_with(|| PendingRootSpan {
+                start_time: span.start_time,
+                end_time: span.end_time,
+                signature: None,
+                last_update: now,
+            });
+        root.start_time = root.start_time.min(span.start_time);
+        root.end_time = root.end_time.max(span.end_time);
+        root.signature = root.signature.take().or(signature);
+        root.last_update = now;
+    }
+
+    /// Removes and returns the root spans which haven't been extended for `idle_timeout`.
+    fn take_idle(
+        &mut self,
+        host_id: &str,
+        now: Instant,
+        idle_timeout: Duration,
+    ) -> Vec<FinishedSpan> {
+        let idle_trace_ids: Vec<_> = self
+            .pending
+            .iter()
+            .filter(|(_, root)| now.saturating_duration_since(root.last_update) >= idle_timeout)
+            .map(|(trace_id, _)| *trace_id)
+            .collect();
+        idle_trace_ids
+            .into_iter()
+            .map(|trace_id| {
+                let root = self.pending.remove(&trace_id).unwrap();
+                if self.exported_order.len() >= MAX_EXPORTED_ROOT_SPANS {
+                    let oldest = self.exported_order.pop_front().unwrap();
+                    self.exported.remove(&oldest);
+                }
+                self.exported.insert(trace_id);
+                self.exported_order.push_back(trace_id);
+                FinishedSpan {
+                    trace_id,
+                    span_id: root_span_id(&trace_id, host_id),
+                    parent_span_id: None,
+                    name: ROOT_SPAN_NAME,
+                    start_time: root.start_time,
+                    end_time: root.end_time,
+                    attributes: root
+                        .signature
+                        .map(|signature| vec![(SIGNATURE_ATTRIBUTE, signature)])
+                        .unwrap_or_default(),
+                    error: None,
+                }
+            })
+            .collect()
+    }
+}
+
+struct Tracer {
+    sender: Sender<FinishedSpan>,
+    sample_threshold: u64,
+    next_span_id: AtomicU64,
+}
+
+impl Tracer {
+    fn from_env() -> Option<Self> {
+        let endpoint = env::var("SOLANA_TRACING_OTLP_ENDPOINT").ok()?;
+        let sample_ratio = env::var("SOLANA_TRACING_SAMPLE_RATIO")
+            .map(|x| {
+                x.parse()
+                    .expect("Failed to parse SOLANA_TRACING_SAMPLE_RATIO")
+            })
+            .unwrap_or(DEFAULT_SAMPLE_RATIO);
+        let url = format!("{}/v1/traces", endpoint.trim_end_matches('/'));
+        info!(
+            "exporting transaction traces to {} with a sample ratio of {}",
+            url, sample_ratio
+        );
+
+        let (sender, receiver) = bounded(MAX_PENDING_SPANS);
+        thread::Builder::new()
+            .name("solTxTracing".into())
+            .spawn(move || export_loop(&receiver, &url))
+            .unwrap();
+
+        Some(Self {
+            sender,
+            sample_threshold: sample_threshold(sample_ratio),
+            next_span_id: AtomicU64::default(),
+        })
+    }
+}
+
+lazy_static! {
+    static ref TRACER: Option<Tracer> = Tracer::from_env();
+}
+
+/// Returns true if transaction tracing is enabled.
+pub fn is_enabled() -> bool {
+    TRACER.is_some()
+}
+
+/// Returns true if tracing is enabled and the transaction with `signature` is sampled.
+pub fn is_sampled(signature: &[u8]) -> bool {
+    TRACER
+        .as_ref()
+        .is_some_and(|tracer| is_sampled_with_threshold(signature, tracer.sample_threshold))
+}
+
+fn sample_threshold(sample_ratio: f64) -> u64 {
+    if sample_ratio >= 1.0 {
+        u64::MAX
+    } else if sample_ratio > 0.0 {
+        (sample_ratio * u64::MAX as f64) as u64
+    } else {
+        0
+    }
+}
+
+fn is_sampled_with_threshold(signature: &[u8], sample_threshold: u64) -> bool {
+    if signature.len() != SIGNATURE_BYTES || sample_threshold == 0 {
+        return false;
+    }
+    let value = u64::from_le_bytes(signature[..8].try_into().unwrap());
+    sample_threshold == u64::MAX || value < sample_threshold
+}
+
+/// A span of time a sampled transaction spent in a stage of the pipeline. The span is exported
+/// when `end()` is called; dropping it without ending it discards it.
+#[derive(Debug)]
+pub struct TransactionSpan {
+    trace_id: [u8; TRACE_ID_BYTES],
+    name: &'static str,
+    start_time: SystemTime,
+    attributes: Vec<(&'static str, String)>,
+    error: Option<String>,
+}
+
+impl TransactionSpan {
+    /// Starts a span named `name` now, if the transaction with `signature` is sampled.
+    pub fn start(signature: &[u8], name: &'static str) -> Option<Self> {
+        Self::start_at(signature, name, SystemTime::now())
+    }
+
+    /// Starts a span named `name` at `start_time`, if the transaction with `signature` is sampled.
+    pub fn start_at(signature: &[u8], name: &'static str, start_time: SystemTime) -> Option<Self> {
+        if !is_sampled(signature) {
+            return None;
+        }
+        Some(Self {
+            trace_id: signature[..TRACE_ID_BYTES].try_into().unwrap(),
+            name,
+            start_time,
+            attributes: vec![(SIGNATURE_ATTRIBUTE, to_hex(signature))],
+            error: None,
+        })
+    }
+
+    /// Starts a span named `name` at the wall-clock time corresponding to `start`, if the
+    /// transaction with `signature` is sampled.
+    pub fn start_at_instant(signature: &[u8], name: &'static str, start: Instant) -> Option<Self> {
+        let start_time = SystemTime::now()
+            .checked_sub(start.elapsed())
+            .unwrap_or(UNIX_EPOCH);
+        Self::start_at(signature, name, start_time)
+    }
+
+    pub fn add_attribute(&mut self, key: &'static str, value: impl ToString) {
+        self.attributes.push((key, value.to_string()));
+    }
+
+    /// Marks the span as failed, e.g. because the transaction was dropped in this stage.
+    pub fn set_error(&mut self, message: impl ToString) {
+        self.error = Some(message.to_string());
+    }
+
+    pub fn end(self) {
+        let Some(tracer) = TRACER.as_ref() else {
+            return;
+        };
+        let span_id = span_id(
+            &self.trace_id,
+            self.name,
+            tracer.next_span_id.fetch_add(1, Ordering::Relaxed),
+        );
+        let span = FinishedSpan {
+            trace_id: self.trace_id,
+            span_id,
+            parent_span_id: None,
+            name: self.name,
+            start_time: self.start_time,
+            end_time: SystemTime::now(),
+            attributes: self.attributes,
+            error: self.error,
+        };
+        if let Err(TrySendError::Full(_)) = tracer.sender.try_send(span) {
+            debug!("transaction tracing queue is full, dropping span");
+        }
+    }
+}
+
+/// Records a zero-length span named `name`, failed with `error` if set, if the transaction with
+/// `signature` is sampled.
+pub fn record_event(signature: &[u8], name: &'static str, error: Option<&str>) {
+    if let Some(mut span) = TransactionSpan::start(signature, name) {
+        if let Some(error) = error {
+            span.set_error(error);
+        }
+        span.end();
+    }
+}
+
+fn span_id(trace_id: &[u8], name: &str, sequence: u64) -> [u8; SPAN_ID_BYTES] {
+    let hash = hashv(&[trace_id, name.as_bytes(), &sequence.to_le_bytes()]);
+    hash.as_ref()[..SPAN_ID_BYTES].try_into().unwrap()
+}
+
+/// The span id of the root span of a transaction on the validator `host_id`. Every validator
+/// tracing the transaction exports its own root span in the same trace.
+fn root_span_id(trace_id: &[u8], host_id: &str) -> [u8; SPAN_ID_BYTES] {
+    let hash = hashv(&[trace_id, ROOT_SPAN_NAME.as_bytes(), host_id.as_bytes()]);
+    hash.as_ref()[..SPAN_ID_BYTES].try_into().unwrap()
+}
+
+fn export_loop(receiver: &Receiver<FinishedSpan>, url: &str) {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(EXPORT_TIMEOUT)
+        .build()
+        .unwrap();
+    let mut spans = Vec::with_capacity(MAX_SPANS_PER_EXPORT);
+    let mut root_spans = RootSpans::default();
+    let mut last_export = Instant::now();
+    loop {
+        let disconnected = match receiver.recv_timeout(EXPORT_INTERVAL) {
+            Ok(span) => {
+                spans.push(span);
+                spans.extend(
+                    receiver
+                        .try_iter()
+                        .take(MAX_SPANS_PER_EXPORT.saturating_sub(spans.len())),
+                );
+                false
+            }
+            Err(RecvTimeoutError::Timeout) => false,
+            Err(RecvTimeoutError::Disconnected) => true,
+        };
+
+        if disconnected
+            || spans.len() >= MAX_SPANS_PER_EXPORT
+            || last_export.elapsed() >= EXPORT_INTERVAL
+        {
+            let host_id = crate::metrics::HOST_ID.read().unwrap().clone();
+            let now = Instant::now();
+            for span in spans.iter_mut() {
+                root_spans.add_child(span, &host_id, now);
+            }
+            // Export the remaining root spans when shutting down
+            let root_span_idle_timeout = if disconnected {
+                Duration::ZERO
+            } else {
+                ROOT_SPAN_IDLE_TIMEOUT
+            };
+            spans.extend(root_spans.take_idle(&host_id, now, root_span_idle_timeout));
+
+            if !spans.is_empty() {
+                let body = render_spans(&host_id, &spans);
+                match client
+                    .post(url)
+                    .header(reqwest::header::CONTENT_TYPE, "application/json")
+                    .body(body)
+                    .send()
+                {
+                    Ok(response) if !response.status().is_success() => {
+                        warn!("trace export unsuccessful: {}", response.status());
+                    }
+                    Ok(_) => {}
+                    Err(err) => warn!("trace export error: {}", err),
+                }
+                spans.clear();
+            }
+            last_export = Instant::now();
+        }
+
+        if disconnected {
+            return;
+        }
+    }
+}
+
+/// Renders `spans` as an OTLP `ExportTraceServiceRequest` in the JSON encoding.
+fn render_spans(host_id: &str, spans: &[FinishedSpan]) -> String {
+    let mut out = String::new();
+    out.push_str(r#"{"resourceSpans":[{"resource":{"attributes":["#);
+    write_attribute(&mut out, "service.name", SERVICE_NAME);
+    out.push(',');
+    write_attribute(&mut out, "host.id", host_id);
+    out.push_str(r#"]},"scopeSpans":[{"scope":{"name":"#);
+    write_json_string(&mut out, SCOPE_NAME);
+    out.push_str(r#"},"spans":["#);
+    for (i, span) in spans.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_span(&mut out, span);
+    }
+    out.push_str("]}]}]}");
+    out
+}
+
+fn write_span(out: &mut String, span: &FinishedSpan) {
+    let _ = write!(
+        out,
+        r#"{{"traceId":"{}","spanId":"{}","#,
+        to_hex(&span.trace_id),
+        to_hex(&span.span_id),
+    );
+    if let Some(parent_span_id) = &span.parent_span_id {
+        let _ = write!(out, r#""parentSpanId":"{}","#, to_hex(parent_span_id));
+    }
+    out.push_str(r#""name":"#);
+    write_json_string(out, span.name);
+    let _ = write!(
+        out,
+        r#","kind":1,"startTimeUnixNano":"{}","endTimeUnixNano":"{}","attributes":["#,
+        unix_nanos(span.start_time),
+        unix_nanos(span.end_time),
+    );
+    for (i, (key, value)) in span.attributes.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_attribute(out, key, value);
+    }
+    out.push(']');
+    if let Some(error) = &span.error {
+        out.push_str(r#","status":{"code":2,"message":"#);
+        write_json_string(out, error);
+        out.push('}');
+    }
+    out.push('}');
+}
+
+fn write_attribute(out: &mut String, key: &str, value: &str) {
+    out.push_str(r#"{"key":"#);
+    write_json_string(out, key);
+    out.push_str(r#","value":{"stringValue":"#);
+    write_json_string(out, value);
+    out.push_str("}}");
+}
+
+fn write_json_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn unix_nanos(time: SystemTime) -> u128 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or_default()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut out, byte| {
+        let _ = write!(out, "{byte:02x}");
+        out
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_sampled_with_threshold() {
+        let mut signature = [0u8; SIGNATURE_BYTES];
+        assert!(!is_sampled_with_threshold(
+            &signature,
+            sample_threshold(0.0)
+        ));
+        assert!(is_sampled_with_threshold(
+            &signature,
+            sample_threshold(0.001)
+        ));
+        assert!(!is_sampled_with_threshold(
+            &signature[..32],
+            sample_threshold(1.0)
+        ));
+
+        signature[..8].copy_from_slice(&(u64::MAX / 2).to_le_bytes());
+        assert!(is_sampled_with_threshold(&signature, sample_threshold(0.6)));
+        assert!(!is_sampled_with_threshold(
+            &signature,
+            sample_threshold(0.4)
+        ));
+
+        signature[..8].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(!is_sampled_with_threshold(
+            &signature,
+            sample_threshold(0.999)
+        ));
+        assert!(is_sampled_with_threshold(&signature, sample_threshold(1.0)));
+        assert!(is_sampled_with_threshold(&signature, sample_threshold(2.0)));
+    }
+
+    #[test]
+    fn test_span_id() {
+        let trace_id = [1u8; TRACE_ID_BYTES];
+        assert_eq!(span_id(&trace_id, "a", 0), span_id(&trace_id, "a", 0));
+        assert_ne!(span_id(&trace_id, "a", 0), span_id(&trace_id, "a", 1));
+        assert_ne!(span_id(&trace_id, "a", 0), span_id(&trace_id, "b", 0));
+    }
+
+    #[test]
+    fn test_write_json_string() {
+        let mut out = String::new();
+        write_json_string(&mut out, "a\"b\\c\nd\u{1}");
+        assert_eq!(out, r#""a\"b\\c\nd\u0001""#);
+    }
+
+    #[test]
+    fn test_render_spans() {
+        let spans = vec![
+            FinishedSpan {
+                trace_id: [0xab; TRACE_ID_BYTES],
+                span_id: [0x01; SPAN_ID_BYTES],
+                parent_span_id: Some([0x03; SPAN_ID_BYTES]),
+                name: "sigverify",
+                start_time: UNIX_EPOCH + Duration::from_nanos(1_000),
+                end_time: UNIX_EPOCH + Duration::from_nanos(2_500),
+                attributes: vec![("slot", "42".to_string())],
+                error: Some("signature verification failed".to_string()),
+            },
+            FinishedSpan {
+                trace_id: [0xab; TRACE_ID_BYTES],
+                span_id: [0x02; SPAN_ID_BYTES],
+                parent_span_id: None,
+                name: "banking_stage",
+                start_time: UNIX_EPOCH,
+                end_time: UNIX_EPOCH,
+                attributes: vec![],
+                error: None,
+            },
+        ];
+        let expected = concat!(
+            r#"{"resourceSpans":[{"resource":{"attributes":["#,
+            r#"{"key":"service.name","value":{"stringValue":"solana-validator"}},"#,
+            r#"{"key":"host.id","value":{"stringValue":"host"}}]},"#,
+            r#""scopeSpans":[{"scope":{"name":"solana-transaction-tracing"},"spans":["#,
+            r#"{"traceId":"abababababababababababababababab","spanId":"0101010101010101","#,
+            r#""parentSpanId":"0303030303030303","name":"sigverify","kind":1,"startTimeUnixNano":"1000","endTimeUnixNano":"2500","#,
+            r#""attributes":[{"key":"slot","value":{"stringValue":"42"}}],"#,
+            r#""status":{"code":2,"message":"signature verification failed"}},"#,
+            r#"{"traceId":"abababababababababababababababab","spanId":"0202020202020202","#,
+            r#""name":"banking_stage","kind":1,"startTimeUnixNano":"0","endTimeUnixNano":"0","#,
+            r#""attributes":[]}]}]}]}"#,
+        );
+        assert_eq!(render_spans("host", &spans), expected);
+    }
+
+    fn stage_span(
+        trace_id: [u8; TRACE_ID_BYTES],
+        name: &'static str,
+        start: u64,
+        end: u64,
+    ) -> FinishedSpan {
+        FinishedSpan {
+            trace_id,
+            span_id: span_id(&trace_id, name, 0),
+            parent_span_id: None,
+            name,
+            start_time: UNIX_EPOCH + Duration::from_nanos(start),
+            end_time: UNIX_EPOCH + Duration::from_nanos(end),
+            attributes: vec![(SIGNATURE_ATTRIBUTE, to_hex(&trace_id))],
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_root_spans() {
+        let mut root_spans = RootSpans::default();
+        let trace_id = [0xab; TRACE_ID_BYTES];
+        let other_trace_id = [0xcd; TRACE_ID_BYTES];
+        let now = Instant::now();
+
+        let mut sigverify = stage_span(trace_id, "sigverify", 2_000, 3_000);
+        let mut banking_stage = stage_span(trace_id, "banking_stage", 5_000, 8_000);
+        let mut other_sigverify = stage_span(other_trace_id, "sigverify", 1_000, 1_500);
+        root_spans.add_child(&mut sigverify, "host", now);
+        root_spans.add_child(&mut banking_stage, "host", now);
+        root_spans.add_child(&mut other_sigverify, "host", now + Duration::from_secs(5));
+
+        // Stage spans of a transaction share their parent, which is unique to the host
+        let root_id = root_span_id(&trace_id, "host");
+        assert_eq!(sigverify.parent_span_id, Some(root_id));
+        assert_eq!(banking_stage.parent_span_id, Some(root_id));
+        assert_ne!(other_sigverify.parent_span_id, Some(root_id));
+        assert_ne!(root_span_id(&trace_id, "other_host"), root_id);
+
+        // Root spans are only exported once idle, and cover all of their stage spans
+        assert!(root_spans
+            .take_idle("host", now + Duration::from_secs(1), Duration::from_secs(2))
+            .is_empty());
+        let exported =
+            root_spans.take_idle("host", now + Duration::from_secs(3), Duration::from_secs(2));
+        assert_eq!(
+            exported,
+            vec![FinishedSpan {
+                trace_id,
+                span_id: root_id,
+                parent_span_id: None,
+                name: ROOT_SPAN_NAME,
+                start_time: UNIX_EPOCH + Duration::from_nanos(2_000),
+                end_time: UNIX_EPOCH + Duration::from_nanos(8_000),
+                attributes: vec![(SIGNATURE_ATTRIBUTE, to_hex(&trace_id))],
+                error: None,
+            }]
+        );
+
+        // A late stage span is parented to the exported root span without exporting it again
+        let mut replay = stage_span(trace_id, "replay", 9_000, 9_500);
+        root_spans.add_child(&mut replay, "host", now + Duration::from_secs(3));
+        assert_eq!(replay.parent_span_id, Some(root_id));
+        let exported = root_spans.take_idle("host", now + Duration::from_secs(20), Duration::ZERO);
+        assert_eq!(exported.len(), 1);
+        assert_eq!(exported[0].trace_id, other_trace_id);
+    }
+}
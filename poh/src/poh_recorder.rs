@@ -92,21 +92,33 @@ impl BankStart {
 type RecordResultSender = Sender<Result<Option<usize>>>;
 
 pub struct Record {
-    pub mixin: Hash,
-    pub transactions: Vec<VersionedTransaction>,
+    /// One mixin per batch of transactions, each batch being recorded as its own entry
+    pub mixins: Vec<Hash>,
+    pub transaction_batches: Vec<Vec<VersionedTransaction>>,
     pub slot: Slot,
     pub sender: RecordResultSender,
 }
 impl Record {
+    /// Creates a record of a single entry
     pub fn new(
         mixin: Hash,
         transactions: Vec<VersionedTransaction>,
         slot: Slot,
         sender: RecordResultSender,
+    ) -> Self {
+        Self::new_batches(vec![mixin], vec![transactions], slot, sender)
+    }
+
+    /// Creates a record of one entry per batch, all recorded together or not at all
+    pub fn new_batches(
+        mixins: Vec<Hash>,
+        transaction_batches: Vec<Vec<VersionedTransaction>>,
+        slot: Slot,
+        sender: RecordResultSender,
     ) -> Self {
         Self {
-            mixin,
-            transactions,
+            mixins,
+            transaction_batches,
             slot,
             sender,
         }
@@ -161,15 +173,31 @@ impl TransactionRecorder {
         &self,
         bank_slot: Slot,
         transactions: Vec<VersionedTransaction>,
+    ) -> RecordTransactionsSummary {
+        self.record_transaction_batches(bank_slot, vec![transactions])
+    }
+
+    /// Like `record_transactions`, but records each of `transaction_batches` as its own entry.
+    /// The entries are consecutive in the PoH stream, and either all of them are recorded or none
+    /// of them are.
+    pub fn record_transaction_batches(
+        &self,
+        bank_slot: Slot,
+        mut transaction_batches: Vec<Vec<VersionedTransaction>>,
     ) -> RecordTransactionsSummary {
         let mut record_transactions_timings = RecordTransactionsTimings::default();
         let mut starting_transaction_index = None;
 
-        if !transactions.is_empty() {
-            let (hash, hash_us) = measure_us!(hash_transactions(&transactions));
+        transaction_batches.retain(|transactions| !transactions.is_empty());
+        if !transaction_batches.is_empty() {
+            let (mixins, hash_us) = measure_us!(transaction_batches
+                .iter()
+                .map(|transactions| hash_transactions(transactions))
+                .collect::<Vec<_>>());
             record_transactions_timings.hash_us = hash_us;
 
-            let (res, poh_record_us) = measure_us!(self.record(bank_slot, hash, transactions));
+            let (res, poh_record_us) =
+                measure_us!(self.record_batches(bank_slot, mixins, transaction_batches));
             record_transactions_timings.poh_record_us = poh_record_us;
 
             match res {
@@ -207,12 +235,26 @@ impl TransactionRecorder {
         bank_slot: Slot,
         mixin: Hash,
         transactions: Vec<VersionedTransaction>,
+    ) -> Result<Option<usize>> {
+        self.record_batches(bank_slot, vec![mixin], vec![transactions])
+    }
+
+    // Returns the index of the first transaction of `transaction_batches` in the slot, if being
+    // tracked by WorkingBank
+    pub fn record_batches(
+        &self,
+        bank_slot: Slot,
+        mixins: Vec<Hash>,
+        transaction_batches: Vec<Vec<VersionedTransaction>>,
     ) -> Result<Option<usize>> {
         // create a new channel so that there is only 1 sender and when it goes out of scope, the receiver fails
         let (result_sender, result_receiver) = bounded(1);
-        let res =
-            self.record_sender
-                .send(Record::new(mixin, transactions, bank_slot, result_sender));
+        let res = self.record_sender.send(Record::new_batches(
+            mixins,
+            transaction_batches,
+            bank_slot,
+            result_sender,
+        ));
         if res.is_err() {
             // If the channel is dropped, then the validator is shutting down so return that we are hitting
             //  the max tick height to stop transaction processing and flush any transactions in the pipeline.
@@ -311,6 +353,8 @@ pub struct PohRecorder {
     delay_leader_block_for_pending_fork: bool,
     last_reported_slot_for_pending_fork: Arc<Mutex<Slot>>,
     pub is_exited: Arc<AtomicBool>,
+    // Ticks are not produced by the PoH service while paused
+    is_paused: bool,
 }
 
 impl PohRecorder {
@@ -428,6 +472,15 @@ impl PohRecorder {
         self.working_bank.is_some()
     }
 
+    /// Stops the PoH service from producing ticks, and so slots, until `set_paused(false)`
+    pub fn set_paused(&mut self, is_paused: bool) {
+        self.is_paused = is_paused;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.is_paused
+    }
+
     pub fn tick_height(&self) -> u64 {
         self.tick_height
     }
@@ -957,13 +1010,39 @@ impl PohRecorder {
         mixin: Hash,
         transactions: Vec<VersionedTransaction>,
     ) -> Result<Option<usize>> {
+        self.record_batches(bank_slot, vec![mixin], vec![transactions])
+    }
+
+    /// Records each of `transaction_batches` as its own entry, mixing in the corresponding hash
+    /// of `mixins`. The entries are consecutive, with no tick in between, and either all of them
+    /// are recorded or none of them are.
+    ///
+    /// Returns the index of the first transaction in the slot, if being tracked by WorkingBank
+    pub fn record_batches(
+        &mut self,
+        bank_slot: Slot,
+        mixins: Vec<Hash>,
+        transaction_batches: Vec<Vec<VersionedTransaction>>,
+    ) -> Result<Option<usize>> {
+        assert_eq!(mixins.len(), transaction_batches.len());
         // Entries without transactions are used to track real-time passing in the ledger and
         // cannot be generated by `record()`
-        assert!(!transactions.is_empty(), "No transactions provided");
+        assert!(
+            !transaction_batches.is_empty()
+                && transaction_batches
+                    .iter()
+                    .all(|transactions| !transactions.is_empty()),
+            "No transactions provided"
+        );
 
         let ((), report_metrics_us) = measure_us!(self.report_metrics(bank_slot));
         self.report_metrics_us += report_metrics_us;
 
+        // The entries must all fit in between two ticks
+        if mixins.len() as u64 >= self.poh.lock().unwrap().hashes_per_tick() {
+            return Err(PohRecorderError::MaxHeightReached);
+        }
+
         loop {
             let (flush_cache_res, flush_cache_us) = measure_us!(self.flush_cache(false));
             self.flush_cache_no_tick_us += flush_cache_us;
@@ -980,22 +1059,25 @@ impl PohRecorder {
             let (mut poh_lock, poh_lock_us) = measure_us!(self.poh.lock().unwrap());
             self.record_lock_contention_us += poh_lock_us;
 
-            let (record_mixin_res, record_mixin_us) = measure_us!(poh_lock.record(mixin));
+            let (record_mixin_res, record_mixin_us) = measure_us!(poh_lock.record_batches(&mixins));
             self.record_us += record_mixin_us;
 
             drop(poh_lock);
 
-            if let Some(poh_entry) = record_mixin_res {
-                let num_transactions = transactions.len();
-                let (send_entry_res, send_entry_us) = measure_us!({
-                    let entry = Entry {
-                        num_hashes: poh_entry.num_hashes,
-                        hash: poh_entry.hash,
-                        transactions,
-                    };
-                    let bank_clone = working_bank.bank.clone();
-                    self.sender.send((bank_clone, (entry, self.tick_height)))
-                });
+            if let Some(poh_entries) = record_mixin_res {
+                let num_transactions: usize = transaction_batches.iter().map(Vec::len).sum();
+                let (send_entry_res, send_entry_us) = measure_us!(poh_entries
+                    .into_iter()
+                    .zip(transaction_batches)
+                    .try_for_each(|(poh_entry, transactions)| {
+                        let entry = Entry {
+                            num_hashes: poh_entry.num_hashes,
+                            hash: poh_entry.hash,
+                            transactions,
+                        };
+                        let bank_clone = working_bank.bank.clone();
+                        self.sender.send((bank_clone, (entry, self.tick_height)))
+                    }));
                 self.send_entry_us += send_entry_us;
                 send_entry_res?;
                 let starting_transaction_index =
@@ -1008,7 +1090,10 @@ impl PohRecorder {
             }
 
             // record() might fail if the next PoH hash needs to be a tick.  But that's ok, tick()
-            // and re-record()
+            // and re-record(), unless ticking is paused, in which case the slot must not advance
+            if self.is_paused {
+                return Err(PohRecorderError::MaxHeightReached);
+            }
             self.ticks_from_record += 1;
             self.tick();
         }
@@ -1078,6 +1163,7 @@ impl PohRecorder {
                 delay_leader_block_for_pending_fork,
                 last_reported_slot_for_pending_fork: Arc::default(),
                 is_exited,
+                is_paused: false,
             },
             receiver,
             record_receiver,
@@ -1642,6 +1728,122 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_poh_recorder_record_batches() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path())
+            .expect("Expected to be able to open database ledger");
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(2);
+        let bank = Arc::new(Bank::new_for_tests(&genesis_config));
+        let prev_hash = bank.last_blockhash();
+        let (mut poh_recorder, entry_receiver, _record_receiver) = PohRecorder::new(
+            0,
+            prev_hash,
+            bank.clone(),
+            Some((4, 4)),
+            bank.ticks_per_slot(),
+            Arc::new(blockstore),
+            &Arc::new(LeaderScheduleCache::new_from_bank(&bank)),
+            &PohConfig::default(),
+            Arc::new(AtomicBool::default()),
+        );
+
+        poh_recorder.set_bank_with_transaction_index_for_test(bank.clone());
+        poh_recorder.tick();
+        let _tick = entry_receiver.recv().unwrap();
+
+        let tx0 = VersionedTransaction::from(test_tx());
+        let tx1 = VersionedTransaction::from(test_tx());
+        let tx2 = VersionedTransaction::from(test_tx());
+        let record_result = poh_recorder
+            .record_batches(
+                bank.slot(),
+                vec![hash(b"hello"), hash(b"world")],
+                vec![vec![tx0.clone(), tx1.clone()], vec![tx2.clone()]],
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(record_result, 0);
+
+        // Each batch is its own entry, in order
+        let (_bank, (entry, _tick_height)) = entry_receiver.recv().unwrap();
+        assert_eq!(entry.transactions, vec![tx0, tx1]);
+        let (_bank, (entry, _tick_height)) = entry_receiver.recv().unwrap();
+        assert_eq!(entry.transactions, vec![tx2]);
+        assert_eq!(entry.num_hashes, 1);
+        assert!(entry_receiver.try_recv().is_err());
+        assert_eq!(
+            poh_recorder
+                .working_bank
+                .as_ref()
+                .unwrap()
+                .transaction_index
+                .unwrap(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_poh_recorder_record_batches_while_paused() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path())
+            .expect("Expected to be able to open database ledger");
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(2);
+        let bank = Arc::new(Bank::new_for_tests(&genesis_config));
+        let prev_hash = bank.last_blockhash();
+        let (mut poh_recorder, entry_receiver, _record_receiver) = PohRecorder::new(
+            0,
+            prev_hash,
+            bank.clone(),
+            Some((4, 4)),
+            bank.ticks_per_slot(),
+            Arc::new(blockstore),
+            &Arc::new(LeaderScheduleCache::new_from_bank(&bank)),
+            &PohConfig {
+                hashes_per_tick: Some(2),
+                ..PohConfig::default()
+            },
+            Arc::new(AtomicBool::default()),
+        );
+
+        poh_recorder.set_bank_for_test(bank.clone());
+        poh_recorder.set_paused(true);
+
+        // The first entry fits before the next tick
+        assert!(poh_recorder
+            .record_batches(
+                bank.slot(),
+                vec![hash(b"hello")],
+                vec![vec![test_tx().into()]]
+            )
+            .is_ok());
+        let (_bank, (entry, _tick_height)) = entry_receiver.recv().unwrap();
+        assert!(!entry.is_tick());
+
+        // The next one would need a tick, which must not happen while paused
+        assert!(matches!(
+            poh_recorder.record_batches(
+                bank.slot(),
+                vec![hash(b"world")],
+                vec![vec![test_tx().into()]]
+            ),
+            Err(PohRecorderError::MaxHeightReached)
+        ));
+        assert_eq!(poh_recorder.tick_height(), 0);
+        assert!(entry_receiver.try_recv().is_err());
+
+        // Once resumed, the recorder ticks and records again
+        poh_recorder.set_paused(false);
+        assert!(poh_recorder
+            .record_batches(
+                bank.slot(),
+                vec![hash(b"world")],
+                vec![vec![test_tx().into()]]
+            )
+            .is_ok());
+        assert_eq!(poh_recorder.tick_height(), 1);
+    }
+
     #[test]
     fn test_poh_cache_on_disconnect() {
         let ledger_path = get_tmp_ledger_path_auto_delete!();
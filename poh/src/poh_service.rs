@@ -33,6 +33,9 @@ pub const DEFAULT_PINNED_CPU_CORE: usize = 0;
 
 const TARGET_SLOT_ADJUSTMENT_NS: u64 = 50_000_000;
 
+// How often a paused tick producer checks whether it has been resumed
+const PAUSED_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
 #[derive(Debug)]
 struct PohTiming {
     num_ticks: u64,
@@ -178,7 +181,10 @@ impl PohService {
             );
             if remaining_tick_time.is_zero() {
                 last_tick = Instant::now();
-                poh_recorder.write().unwrap().tick();
+                let mut poh_recorder = poh_recorder.write().unwrap();
+                if !poh_recorder.is_paused() {
+                    poh_recorder.tick();
+                }
             }
         }
     }
@@ -192,10 +198,10 @@ impl PohService {
         if let Ok(record) = record {
             if record
                 .sender
-                .send(poh_recorder.write().unwrap().record(
+                .send(poh_recorder.write().unwrap().record_batches(
                     record.slot,
-                    record.mixin,
-                    record.transactions,
+                    record.mixins,
+                    record.transaction_batches,
                 ))
                 .is_err()
             {
@@ -255,16 +261,17 @@ impl PohService {
                 timing.total_lock_time_ns += lock_time.as_ns();
                 let mut record_time = Measure::start("record");
                 loop {
-                    let res = poh_recorder_l.record(
+                    let num_mixins = record.mixins.len() as u64;
+                    let res = poh_recorder_l.record_batches(
                         record.slot,
-                        record.mixin,
-                        std::mem::take(&mut record.transactions),
+                        std::mem::take(&mut record.mixins),
+                        std::mem::take(&mut record.transaction_batches),
                     );
                     let (send_res, send_record_result_us) = measure_us!(record.sender.send(res));
                     debug_assert!(send_res.is_ok(), "Record wasn't sent.");
 
                     timing.total_send_record_result_us += send_record_result_us;
-                    timing.num_hashes += 1; // note: may have also ticked inside record
+                    timing.num_hashes += num_mixins; // note: may have also ticked inside record
                     if let Ok(new_record) = record_receiver.try_recv() {
                         // we already have second request to record, so record again while we still have the mutex
                         record = new_record;
@@ -347,15 +354,27 @@ impl PohService {
             );
             if should_tick {
                 // Lock PohRecorder only for the final hash. record_or_hash will lock PohRecorder for record calls but not for hashing.
-                {
+                let is_paused = {
                     let mut lock_time = Measure::start("lock");
                     let mut poh_recorder_l = poh_recorder.write().unwrap();
                     lock_time.stop();
                     timing.total_lock_time_ns += lock_time.as_ns();
-                    let mut tick_time = Measure::start("tick");
-                    poh_recorder_l.tick();
-                    tick_time.stop();
-                    timing.total_tick_time_ns += tick_time.as_ns();
+                    let is_paused = poh_recorder_l.is_paused();
+                    if !is_paused {
+                        let mut tick_time = Measure::start("tick");
+                        poh_recorder_l.tick();
+                        tick_time.stop();
+                        timing.total_tick_time_ns += tick_time.as_ns();
+                    }
+                    is_paused
+                };
+                if is_paused {
+                    // The tick is still due, so hashing can't continue until resumed
+                    if poh_exit.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    thread::sleep(PAUSED_POLL_INTERVAL);
+                    continue;
                 }
                 timing.num_ticks += 1;
 
@@ -181,6 +181,22 @@ pub struct SyscallContext {
     pub trace_log: Vec<[u64; 12]>,
 }
 
+/// Debugging record of an executed instruction
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InstructionTrace {
+    /// Index of the instruction in the instruction trace of the transaction
+    pub index_in_trace: usize,
+    /// Height of the instruction in the invocation stack, top level instructions are at
+    /// `TRANSACTION_LEVEL_STACK_HEIGHT`
+    pub stack_height: usize,
+    pub program_id: Pubkey,
+    /// Compute units consumed by the instruction, including the instructions it invoked
+    pub compute_units_consumed: u64,
+    /// The registers before each executed SBF instruction. Only recorded if the program
+    /// runtime environment enables instruction tracing, and always empty for builtins.
+    pub register_trace: Vec<[u64; 12]>,
+}
+
 #[derive(Debug, Clone)]
 pub struct SerializedAccountMetadata {
     pub original_data_len: usize,
@@ -209,6 +225,10 @@ pub struct InvokeContext<'a> {
     pub timings: ExecuteDetailsTimings,
     pub syscall_context: Vec<Option<SyscallContext>>,
     traces: Vec<Vec<[u64; 12]>>,
+    /// Completed instruction traces, `None` unless instruction tracing is enabled
+    instruction_traces: Option<Vec<InstructionTrace>>,
+    /// Traces of the instructions on the invocation stack
+    pending_instruction_traces: Vec<InstructionTrace>,
 }
 
 impl<'a> InvokeContext<'a> {
@@ -231,6 +251,8 @@ impl<'a> InvokeContext<'a> {
             timings: ExecuteDetailsTimings::default(),
             syscall_context: Vec::new(),
             traces: Vec::new(),
+            instruction_traces: None,
+            pending_instruction_traces: Vec::new(),
         }
     }
 
@@ -287,14 +309,42 @@ impl<'a> InvokeContext<'a> {
             }
         }
 
+        let index_in_trace = self.transaction_context.get_instruction_trace_length();
+        let program_id = *program_id;
         self.syscall_context.push(None);
-        self.transaction_context.push()
+        self.transaction_context.push()?;
+        if self.instruction_traces.is_some() {
+            self.pending_instruction_traces.push(InstructionTrace {
+                index_in_trace,
+                stack_height: self.get_stack_height(),
+                program_id,
+                // Replaced by the consumed units once the instruction is popped
+                compute_units_consumed: self.get_remaining(),
+                register_trace: Vec::new(),
+            });
+        }
+        Ok(())
     }
 
     /// Pop a stack frame from the invocation stack
     pub fn pop(&mut self) -> Result<(), InstructionError> {
-        if let Some(Some(syscall_context)) = self.syscall_context.pop() {
-            self.traces.push(syscall_context.trace_log);
+        let register_trace = match self.syscall_context.pop() {
+            Some(Some(syscall_context)) => Some(syscall_context.trace_log),
+            _ => None,
+        };
+        if self.instruction_traces.is_some() {
+            let remaining = self.get_remaining();
+            if let Some(mut instruction_trace) = self.pending_instruction_traces.pop() {
+                instruction_trace.compute_units_consumed = instruction_trace
+                    .compute_units_consumed
+                    .saturating_sub(remaining);
+                instruction_trace.register_trace = register_trace.unwrap_or_default();
+                if let Some(instruction_traces) = self.instruction_traces.as_mut() {
+                    instruction_traces.push(instruction_trace);
+                }
+            }
+        } else if let Some(register_trace) = register_trace {
+            self.traces.push(register_trace);
         }
         self.transaction_context.pop()
     }
@@ -714,6 +764,18 @@ impl<'a> InvokeContext<'a> {
     pub fn get_traces(&self) -> &Vec<Vec<[u64; 12]>> {
         &self.traces
     }
+
+    /// Record an [`InstructionTrace`] for every instruction executed from now on
+    pub fn enable_instruction_tracing(&mut self) {
+        self.instruction_traces.get_or_insert_with(Vec::new);
+    }
+
+    /// Take the traces of the executed instructions, in the order of the instruction trace
+    pub fn take_instruction_traces(&mut self) -> Option<Vec<InstructionTrace>> {
+        let mut instruction_traces = self.instruction_traces.take()?;
+        instruction_traces.sort_by_key(|instruction_trace| instruction_trace.index_in_trace);
+        Some(instruction_traces)
+    }
 }
 
 #[macro_export]
@@ -1185,6 +1247,83 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_instruction_tracing() {
+        let callee_program_id = solana_sdk::pubkey::new_rand();
+        let loader_key = solana_sdk::pubkey::new_rand();
+        let mut program_account = AccountSharedData::new(1, 1, &native_loader::id());
+        program_account.set_executable(true);
+        let transaction_accounts = vec![
+            (
+                solana_sdk::pubkey::new_rand(),
+                AccountSharedData::new(42, 1, &callee_program_id),
+            ),
+            (callee_program_id, program_account),
+            (
+                loader_key,
+                AccountSharedData::new(0, 1, &native_loader::id()),
+            ),
+        ];
+        let metas = vec![AccountMeta::new(transaction_accounts[0].0, false)];
+        let instruction_accounts = vec![InstructionAccount {
+            index_in_transaction: 0,
+            index_in_caller: 0,
+            index_in_callee: 0,
+            is_signer: false,
+            is_writable: true,
+        }];
+        with_mock_invoke_context!(invoke_context, transaction_context, transaction_accounts);
+        let mut program_cache_for_tx_batch = ProgramCacheForTxBatch::default();
+        program_cache_for_tx_batch.replenish(
+            callee_program_id,
+            Arc::new(ProgramCacheEntry::new_builtin(0, 1, MockBuiltin::vm)),
+        );
+        invoke_context.program_cache_for_tx_batch = &mut program_cache_for_tx_batch;
+        invoke_context.enable_instruction_tracing();
+
+        invoke_context
+            .transaction_context
+            .get_next_instruction_context()
+            .unwrap()
+            .configure(&[2], &instruction_accounts, &[]);
+        invoke_context.push().unwrap();
+        let compute_units_to_consume = 10;
+        let inner_instruction = Instruction::new_with_bincode(
+            callee_program_id,
+            &MockInstruction::ConsumeComputeUnits {
+                compute_units_to_consume,
+                desired_result: Ok(()),
+            },
+            metas,
+        );
+        invoke_context
+            .native_invoke(inner_instruction.into(), &[])
+            .unwrap();
+        invoke_context.pop().unwrap();
+
+        let consumed = compute_units_to_consume.saturating_add(MOCK_BUILTIN_COMPUTE_UNIT_COST);
+        assert_eq!(
+            invoke_context.take_instruction_traces().unwrap(),
+            vec![
+                InstructionTrace {
+                    index_in_trace: 0,
+                    stack_height: 1,
+                    program_id: loader_key,
+                    compute_units_consumed: consumed,
+                    register_trace: vec![],
+                },
+                InstructionTrace {
+                    index_in_trace: 1,
+                    stack_height: 2,
+                    program_id: callee_program_id,
+                    compute_units_consumed: consumed,
+                    register_trace: vec![],
+                },
+            ]
+        );
+        assert!(invoke_context.get_traces().is_empty());
+    }
+
     #[test]
     fn test_invoke_context_compute_budget() {
         let transaction_accounts =
@@ -102,6 +102,11 @@ impl SysvarCache {
         }
     }
 
+    /// Overwrite the clock, e.g. to simulate transactions at another point in time.
+    pub fn set_clock(&mut self, clock: &Clock) {
+        self.clock = Some(bincode::serialize(clock).expect("Failed to serialize Clock sysvar."));
+    }
+
     // this is exposed for SyscallGetSysvar and should not otherwise be used
     pub fn sysvar_id_to_buffer(&self, sysvar_id: &Pubkey) -> &Option<Vec<u8>> {
         if Clock::check_id(sysvar_id) {
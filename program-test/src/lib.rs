@@ -84,8 +84,15 @@ pub enum ProgramTestError {
     /// The chosen warp slot is not in the future, so warp is not performed
     #[error("Warp slot not in the future")]
     InvalidWarpSlot,
+    /// The snapshot's bank is no longer available, e.g. because a warp rooted a later slot
+    #[error("Snapshot {0:?} is no longer available")]
+    SnapshotUnavailable(SnapshotId),
 }
 
+/// Identifies a checkpoint of the bank state taken with `ProgramTestContext::snapshot()`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SnapshotId(Slot);
+
 thread_local! {
     static INVOKE_CONTEXT: RefCell<Option<usize>> = const { RefCell::new(None) };
 }
@@ -1257,6 +1264,77 @@ impl ProgramTestContext {
         Ok(())
     }
 
+    /// Checkpoint the current bank state, so that it can be restored later with `restore()`.
+    ///
+    /// The working bank is frozen and a new working bank is created at the next slot, so
+    /// taking a snapshot is cheap. Snapshots are invalidated by warping, since warping roots
+    /// the working bank and discards all other forks.
+    pub fn snapshot(&mut self) -> SnapshotId {
+        let mut bank_forks = self.bank_forks.write().unwrap();
+        let bank = bank_forks.working_bank();
+
+        // Fill ticks until a new blockhash is recorded, otherwise retried transactions will have
+        // the same signature
+        bank.fill_bank_with_ticks_for_tests();
+        bank.freeze();
+        let snapshot_slot = bank.slot();
+
+        let working_slot = snapshot_slot + 1;
+        bank_forks.insert(Bank::new_from_parent(
+            bank,
+            &Pubkey::default(),
+            working_slot,
+        ));
+        self.update_working_slot(&bank_forks, working_slot);
+        SnapshotId(snapshot_slot)
+    }
+
+    /// Roll the bank state back to a checkpoint taken with `snapshot()`.
+    ///
+    /// A new working bank is created as a child of the snapshot's bank on a new fork, at the
+    /// slot after the current working slot, so all changes made since the snapshot was taken
+    /// are discarded. The same snapshot can be restored any number of times, but snapshots
+    /// taken after it are discarded along with the rest of the abandoned forks.
+    pub fn restore(&mut self, snapshot_id: SnapshotId) -> Result<(), ProgramTestError> {
+        let mut bank_forks = self.bank_forks.write().unwrap();
+        let SnapshotId(snapshot_slot) = snapshot_id;
+        let snapshot_bank = bank_forks
+            .get(snapshot_slot)
+            .filter(|bank| bank.is_frozen())
+            .ok_or(ProgramTestError::SnapshotUnavailable(snapshot_id))?;
+
+        let working_slot = bank_forks.highest_slot() + 1;
+        // Drop the forks built on top of the snapshot, otherwise every restore keeps them alive
+        let mut abandoned_slots: Vec<_> = bank_forks
+            .descendants()
+            .remove(&snapshot_slot)
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        abandoned_slots.sort_unstable_by(|a, b| b.cmp(a));
+        for slot in abandoned_slots {
+            bank_forks.remove(slot);
+        }
+
+        bank_forks.insert(Bank::new_from_parent(
+            snapshot_bank,
+            &Pubkey::default(),
+            working_slot,
+        ));
+        self.update_working_slot(&bank_forks, working_slot);
+        Ok(())
+    }
+
+    fn update_working_slot(&mut self, bank_forks: &BankForks, working_slot: Slot) {
+        // Update block commitment cache, otherwise banks server will poll at
+        // the wrong slot
+        self.block_commitment_cache
+            .write()
+            .unwrap()
+            .set_all_slots(working_slot, working_slot);
+        self.last_blockhash = bank_forks.working_bank().last_blockhash();
+    }
+
     /// Get a new latest blockhash, similar in spirit to RpcClient::get_latest_blockhash()
     pub async fn get_new_latest_blockhash(&mut self) -> io::Result<Hash> {
         let blockhash = self
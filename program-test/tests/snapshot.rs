@@ -0,0 +1,88 @@
+use {
+    solana_program_test::{ProgramTest, ProgramTestError},
+    solana_sdk::{
+        account::AccountSharedData, pubkey::Pubkey, signature::Signer, system_instruction,
+        system_program, transaction::Transaction,
+    },
+};
+
+#[tokio::test]
+async fn restore_snapshot() {
+    let mut context = ProgramTest::default().start_with_context().await;
+    let recipient = Pubkey::new_unique();
+    let fixture = Pubkey::new_unique();
+    let lamports = 1_000_000_000;
+
+    context.set_account(
+        &fixture,
+        &AccountSharedData::new(lamports, 0, &system_program::id()),
+    );
+    let snapshot_id = context.snapshot();
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &context.payer.pubkey(),
+            &recipient,
+            lamports,
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction.clone())
+        .await
+        .unwrap();
+    context.set_account(&fixture, &AccountSharedData::default());
+    assert_eq!(
+        context.banks_client.get_balance(recipient).await.unwrap(),
+        lamports
+    );
+    assert_eq!(context.banks_client.get_balance(fixture).await.unwrap(), 0);
+
+    // Changes made since the snapshot are discarded, including the processed transaction, so it
+    // can be processed again
+    context.restore(snapshot_id).unwrap();
+    assert_eq!(
+        context.banks_client.get_balance(recipient).await.unwrap(),
+        0
+    );
+    assert_eq!(
+        context.banks_client.get_balance(fixture).await.unwrap(),
+        lamports
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+    assert_eq!(
+        context.banks_client.get_balance(recipient).await.unwrap(),
+        lamports
+    );
+
+    // The same snapshot can be restored again
+    context.restore(snapshot_id).unwrap();
+    assert_eq!(
+        context.banks_client.get_balance(recipient).await.unwrap(),
+        0
+    );
+
+    // Restoring a snapshot discards the forks built on top of it, including later snapshots
+    let later_snapshot_id = context.snapshot();
+    context.restore(snapshot_id).unwrap();
+    assert_eq!(
+        context.restore(later_snapshot_id),
+        Err(ProgramTestError::SnapshotUnavailable(later_snapshot_id))
+    );
+    context.restore(snapshot_id).unwrap();
+
+    // Warping discards all snapshots
+    let working_slot = context.banks_client.get_root_slot().await.unwrap();
+    context.warp_to_slot(working_slot + 10).unwrap();
+    assert_eq!(
+        context.restore(snapshot_id),
+        Err(ProgramTestError::SnapshotUnavailable(snapshot_id))
+    );
+}
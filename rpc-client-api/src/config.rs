@@ -1,9 +1,10 @@
 use {
     crate::filter::RpcFilterType,
-    solana_account_decoder_client_types::{UiAccountEncoding, UiDataSliceConfig},
-    solana_clock::{Epoch, Slot},
+    solana_account_decoder_client_types::{UiAccountData, UiAccountEncoding, UiDataSliceConfig},
+    solana_clock::{Epoch, Slot, UnixTimestamp},
     solana_commitment_config::{CommitmentConfig, CommitmentLevel},
     solana_transaction_status_client_types::{TransactionDetails, UiTransactionEncoding},
+    std::collections::HashMap,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -44,6 +45,73 @@ pub struct RpcSimulateTransactionConfig {
     pub min_context_slot: Option<Slot>,
     #[serde(default)]
     pub inner_instructions: bool,
+    /// Replacement state for accounts, keyed by base-58 encoded address. Sysvar accounts
+    /// can't be overridden, except the clock with `clock_override`.
+    pub account_overrides: Option<HashMap<String, RpcAccountOverride>>,
+    pub clock_override: Option<RpcClockOverride>,
+    /// Return the accounts changed by the transaction
+    #[serde(default)]
+    pub state_diffs: bool,
+}
+
+/// The accounts to estimate a prioritization fee for are the writable accounts of `transaction`
+/// together with `account_keys`
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPriorityFeeEstimateConfig {
+    pub transaction: Option<String>,
+    pub encoding: Option<UiTransactionEncoding>,
+    pub account_keys: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub commitment: Option<CommitmentConfig>,
+    pub min_context_slot: Option<Slot>,
+}
+
+/// Fields which aren't set keep the current state of the account
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountOverride {
+    pub lamports: Option<u64>,
+    pub owner: Option<String>, // base-58 encoded Pubkey
+    pub executable: Option<bool>,
+    pub data: Option<UiAccountData>,
+}
+
+/// Fields which aren't set keep the current value of the clock sysvar
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcClockOverride {
+    pub slot: Option<Slot>,
+    pub epoch_start_timestamp: Option<UnixTimestamp>,
+    pub epoch: Option<Epoch>,
+    pub leader_schedule_epoch: Option<Epoch>,
+    pub unix_timestamp: Option<UnixTimestamp>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSendBundleConfig {
+    pub encoding: Option<UiTransactionEncoding>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSimulateBundleConfig {
+    #[serde(default)]
+    pub sig_verify: bool,
+    #[serde(default)]
+    pub replace_recent_blockhash: bool,
+    #[serde(flatten)]
+    pub commitment: Option<CommitmentConfig>,
+    pub encoding: Option<UiTransactionEncoding>,
+    /// Accounts to return after each transaction of the bundle
+    pub accounts: Option<RpcSimulateTransactionAccountsConfig>,
+    pub min_context_slot: Option<Slot>,
+    #[serde(default)]
+    pub inner_instructions: bool,
+    /// Return the accounts changed by each transaction of the bundle
+    #[serde(default)]
+    pub state_diffs: bool,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -164,6 +232,10 @@ pub struct RpcProgramAccountsConfig {
     pub account_config: RpcAccountInfoConfig,
     pub with_context: Option<bool>,
     pub sort_results: Option<bool>,
+    /// Maximum number of accounts to return; paginates the results when set
+    pub limit: Option<usize>,
+    /// Opaque cursor returned with the previous page
+    pub cursor: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -188,6 +260,17 @@ pub enum RpcTokenAccountsFilter {
     ProgramId(String),
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTokenAccountsConfig {
+    #[serde(flatten)]
+    pub account_config: RpcAccountInfoConfig,
+    /// Maximum number of accounts to return; paginates the results when set
+    pub limit: Option<usize>,
+    /// Opaque cursor returned with the previous page
+    pub cursor: Option<String>,
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcSignatureSubscribeConfig {
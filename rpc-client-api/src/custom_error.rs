@@ -27,6 +27,9 @@ pub const JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED: i64 = -32016;
 pub const JSON_RPC_SERVER_ERROR_EPOCH_REWARDS_PERIOD_ACTIVE: i64 = -32017;
 pub const JSON_RPC_SERVER_ERROR_SLOT_NOT_EPOCH_BOUNDARY: i64 = -32018;
 pub const JSON_RPC_SERVER_ERROR_LONG_TERM_STORAGE_UNREACHABLE: i64 = -32019;
+pub const JSON_RPC_SERVER_ERROR_ACCOUNTS_CURSOR_EXPIRED: i64 = -32020;
+pub const JSON_RPC_SERVER_ERROR_BUNDLES_NOT_ACCEPTED: i64 = -32021;
+pub const JSON_RPC_SERVER_ERROR_BUNDLE_QUEUE_FULL: i64 = -32022;
 
 #[derive(Error, Debug)]
 pub enum RpcCustomError {
@@ -78,6 +81,12 @@ pub enum RpcCustomError {
     SlotNotEpochBoundary { slot: Slot },
     #[error("LongTermStorageUnreachable")]
     LongTermStorageUnreachable,
+    #[error("AccountsCursorExpired")]
+    AccountsCursorExpired { slot: Slot },
+    #[error("BundlesNotAccepted")]
+    BundlesNotAccepted,
+    #[error("BundleQueueFull")]
+    BundleQueueFull,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -252,6 +261,24 @@ impl From<RpcCustomError> for Error {
                 message: "Failed to query long-term storage; please try again".to_string(),
                 data: None,
             },
+            RpcCustomError::AccountsCursorExpired { slot } => Self {
+                code: ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_ACCOUNTS_CURSOR_EXPIRED),
+                message: format!(
+                    "Cursor expired: slot {slot} is no longer available; restart the scan without \
+                     a cursor"
+                ),
+                data: None,
+            },
+            RpcCustomError::BundlesNotAccepted => Self {
+                code: ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_BUNDLES_NOT_ACCEPTED),
+                message: "Bundles are not accepted by this node".to_string(),
+                data: None,
+            },
+            RpcCustomError::BundleQueueFull => Self {
+                code: ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_BUNDLE_QUEUE_FULL),
+                message: "Bundle queue is full; please try again".to_string(),
+                data: None,
+            },
         }
     }
 }
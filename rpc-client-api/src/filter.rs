@@ -17,13 +17,19 @@ pub enum RpcFilterType {
     DataSize(u64),
     Memcmp(Memcmp),
     TokenAccountState,
+    /// Matches accounts whose data does not match the given bytes
+    NotMemcmp(Memcmp),
+    /// Matches accounts whose data holds an integer within the given range
+    DataRange(DataRange),
+    /// Matches accounts whose lamports are within the given range
+    Lamports(RangeComparison),
 }
 
 impl RpcFilterType {
     pub fn verify(&self) -> Result<(), RpcFilterError> {
         match self {
             RpcFilterType::DataSize(_) => Ok(()),
-            RpcFilterType::Memcmp(compare) => {
+            RpcFilterType::Memcmp(compare) | RpcFilterType::NotMemcmp(compare) => {
                 use MemcmpEncodedBytes::*;
                 match &compare.bytes {
                     Base58(bytes) => {
@@ -57,6 +63,8 @@ impl RpcFilterType {
                 }
             }
             RpcFilterType::TokenAccountState => Ok(()),
+            RpcFilterType::DataRange(range) => range.verify(),
+            RpcFilterType::Lamports(comparison) => comparison.verify(u64::MAX as u128),
         }
     }
 
@@ -69,6 +77,9 @@ impl RpcFilterType {
             RpcFilterType::DataSize(size) => account.data().len() as u64 == *size,
             RpcFilterType::Memcmp(compare) => compare.bytes_match(account.data()),
             RpcFilterType::TokenAccountState => Account::valid_account_data(account.data()),
+            RpcFilterType::NotMemcmp(compare) => !compare.bytes_match(account.data()),
+            RpcFilterType::DataRange(range) => range.data_match(account.data()),
+            RpcFilterType::Lamports(comparison) => comparison.matches(account.lamports() as u128),
         }
     }
 }
@@ -81,6 +92,10 @@ pub enum RpcFilterError {
     Base58DecodeError(#[from] bs58::decode::Error),
     #[error("base64 decode error")]
     Base64DecodeError(#[from] base64::DecodeError),
+    #[error("range value does not fit the compared integer type")]
+    RangeValueTooLarge,
+    #[error("range lower bound is greater than its upper bound")]
+    InvalidRangeBounds,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
@@ -222,6 +237,174 @@ impl Memcmp {
     }
 }
 
+/// The comparison applied by a range filter.
+///
+/// The bounds are serialized as decimal strings, as JSON numbers above
+/// `u64::MAX` do not round-trip through serde_json.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RangeComparison {
+    /// Greater than the value
+    Gt(#[serde(with = "range_bound")] u128),
+    /// Less than the value
+    Lt(#[serde(with = "range_bound")] u128),
+    /// Between `min` and `max`, inclusive
+    Between {
+        #[serde(with = "range_bound")]
+        min: u128,
+        #[serde(with = "range_bound")]
+        max: u128,
+    },
+}
+
+/// Serde codec for the bounds of a `RangeComparison`, which are written as
+/// decimal strings.  Integer bounds that fit in a `u64` are accepted as well.
+mod range_bound {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(value: &u128, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<u128, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum RangeBound {
+            Integer(u64),
+            Decimal(String),
+        }
+
+        match RangeBound::deserialize(deserializer)? {
+            RangeBound::Integer(value) => Ok(value.into()),
+            RangeBound::Decimal(value) => value.parse().map_err(D::Error::custom),
+        }
+    }
+}
+
+impl RangeComparison {
+    /// Verifies that the bounds are consistent and do not exceed `max_value`.
+    fn verify(&self, max_value: u128) -> Result<(), RpcFilterError> {
+        match *self {
+            RangeComparison::Gt(value) | RangeComparison::Lt(value) => {
+                if value > max_value {
+                    return Err(RpcFilterError::RangeValueTooLarge);
+                }
+            }
+            RangeComparison::Between { min, max } => {
+                if max > max_value {
+                    return Err(RpcFilterError::RangeValueTooLarge);
+                }
+                if min > max {
+                    return Err(RpcFilterError::InvalidRangeBounds);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn matches(&self, value: u128) -> bool {
+        match *self {
+            RangeComparison::Gt(bound) => value > bound,
+            RangeComparison::Lt(bound) => value < bound,
+            RangeComparison::Between { min, max } => (min..=max).contains(&value),
+        }
+    }
+}
+
+/// The unsigned integer types that a `DataRange` filter can read from
+/// account data.  All integers are read in little-endian order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DataRangeType {
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+}
+
+impl DataRangeType {
+    /// Returns the size of the integer type in bytes
+    pub fn size(&self) -> usize {
+        match self {
+            DataRangeType::U8 => 1,
+            DataRangeType::U16 => 2,
+            DataRangeType::U32 => 4,
+            DataRangeType::U64 => 8,
+            DataRangeType::U128 => 16,
+        }
+    }
+
+    /// Returns the largest value of the integer type
+    pub fn max_value(&self) -> u128 {
+        match self {
+            DataRangeType::U8 => u8::MAX as u128,
+            DataRangeType::U16 => u16::MAX as u128,
+            DataRangeType::U32 => u32::MAX as u128,
+            DataRangeType::U64 => u64::MAX as u128,
+            DataRangeType::U128 => u128::MAX,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataRange {
+    /// Data offset of the integer
+    offset: usize,
+    /// Type of the integer
+    integer_type: DataRangeType,
+    /// Comparison applied to the integer
+    comparison: RangeComparison,
+}
+
+impl DataRange {
+    pub fn new(offset: usize, integer_type: DataRangeType, comparison: RangeComparison) -> Self {
+        Self {
+            offset,
+            integer_type,
+            comparison,
+        }
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn integer_type(&self) -> DataRangeType {
+        self.integer_type
+    }
+
+    pub fn comparison(&self) -> RangeComparison {
+        self.comparison
+    }
+
+    fn verify(&self) -> Result<(), RpcFilterError> {
+        self.comparison.verify(self.integer_type.max_value())
+    }
+
+    /// Reads the little-endian integer at the offset of `data`, or returns
+    /// None if `data` is too short.
+    pub fn read_value(&self, data: &[u8]) -> Option<u128> {
+        let bytes = data.get(self.offset..self.offset.checked_add(self.integer_type.size())?)?;
+        let mut buf = [0u8; 16];
+        buf[..bytes.len()].copy_from_slice(bytes);
+        Some(u128::from_le_bytes(buf))
+    }
+
+    pub fn data_match(&self, data: &[u8]) -> bool {
+        self.read_value(data)
+            .map(|value| self.comparison.matches(value))
+            .unwrap_or(false)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -323,6 +506,163 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_data_range_match() {
+        let mut data = vec![0u8; 4];
+        data.extend_from_slice(&1_000u64.to_le_bytes());
+
+        let data_range = |comparison| DataRange::new(4, DataRangeType::U64, comparison);
+        assert_eq!(
+            data_range(RangeComparison::Gt(0)).read_value(&data),
+            Some(1_000)
+        );
+        assert!(data_range(RangeComparison::Gt(999)).data_match(&data));
+        assert!(!data_range(RangeComparison::Gt(1_000)).data_match(&data));
+        assert!(data_range(RangeComparison::Lt(1_001)).data_match(&data));
+        assert!(!data_range(RangeComparison::Lt(1_000)).data_match(&data));
+        assert!(data_range(RangeComparison::Between {
+            min: 1_000,
+            max: 1_000
+        })
+        .data_match(&data));
+        assert!(!data_range(RangeComparison::Between { min: 0, max: 999 }).data_match(&data));
+
+        // Integers are read in little-endian order
+        assert_eq!(
+            DataRange::new(4, DataRangeType::U16, RangeComparison::Gt(0)).read_value(&data),
+            Some(1_000)
+        );
+        assert_eq!(
+            DataRange::new(5, DataRangeType::U8, RangeComparison::Gt(0)).read_value(&data),
+            Some(3)
+        );
+
+        // Integer overrun data fails
+        assert!(
+            !DataRange::new(5, DataRangeType::U64, RangeComparison::Lt(u128::MAX))
+                .data_match(&data)
+        );
+        assert!(
+            !DataRange::new(0, DataRangeType::U128, RangeComparison::Lt(u128::MAX))
+                .data_match(&data)
+        );
+
+        // Offset outside data fails
+        assert!(!DataRange::new(
+            usize::MAX,
+            DataRangeType::U8,
+            RangeComparison::Lt(u128::MAX)
+        )
+        .data_match(&data));
+    }
+
+    #[test]
+    fn test_verify_range() {
+        assert_eq!(
+            RpcFilterType::DataRange(DataRange::new(
+                0,
+                DataRangeType::U8,
+                RangeComparison::Gt(u8::MAX as u128)
+            ))
+            .verify(),
+            Ok(())
+        );
+        assert_eq!(
+            RpcFilterType::DataRange(DataRange::new(
+                0,
+                DataRangeType::U8,
+                RangeComparison::Gt(u8::MAX as u128 + 1)
+            ))
+            .verify(),
+            Err(RpcFilterError::RangeValueTooLarge)
+        );
+        assert_eq!(
+            RpcFilterType::DataRange(DataRange::new(
+                0,
+                DataRangeType::U128,
+                RangeComparison::Between { min: 2, max: 1 }
+            ))
+            .verify(),
+            Err(RpcFilterError::InvalidRangeBounds)
+        );
+        assert_eq!(
+            RpcFilterType::Lamports(RangeComparison::Lt(u64::MAX as u128)).verify(),
+            Ok(())
+        );
+        assert_eq!(
+            RpcFilterType::Lamports(RangeComparison::Lt(u64::MAX as u128 + 1)).verify(),
+            Err(RpcFilterError::RangeValueTooLarge)
+        );
+    }
+
+    #[test]
+    fn test_range_filter_deserialize() {
+        let filter: RpcFilterType = serde_json::from_str(
+            r#"{"dataRange":{"offset":64,"integerType":"u64","comparison":{"gt":100}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            filter,
+            RpcFilterType::DataRange(DataRange::new(
+                64,
+                DataRangeType::U64,
+                RangeComparison::Gt(100)
+            ))
+        );
+
+        let filter: RpcFilterType =
+            serde_json::from_str(r#"{"lamports":{"between":{"min":1,"max":5}}}"#).unwrap();
+        assert_eq!(
+            filter,
+            RpcFilterType::Lamports(RangeComparison::Between { min: 1, max: 5 })
+        );
+
+        let filter: RpcFilterType =
+            serde_json::from_str(r#"{"notMemcmp":{"offset":0,"bytes":[0,1,2,3]}}"#).unwrap();
+        assert_eq!(
+            filter,
+            RpcFilterType::NotMemcmp(Memcmp::new_raw_bytes(0, BYTES.to_vec()))
+        );
+    }
+
+    #[test]
+    fn test_range_comparison_serde() {
+        let above_u64_max = u64::MAX as u128 + 1;
+        for comparison in [
+            RangeComparison::Gt(above_u64_max),
+            RangeComparison::Lt(u128::MAX),
+            RangeComparison::Between {
+                min: above_u64_max,
+                max: u128::MAX,
+            },
+        ] {
+            let filter =
+                RpcFilterType::DataRange(DataRange::new(0, DataRangeType::U128, comparison));
+            let serialized = serde_json::to_string(&filter).unwrap();
+            assert_eq!(
+                serde_json::from_str::<RpcFilterType>(&serialized).unwrap(),
+                filter
+            );
+        }
+
+        assert_eq!(
+            serde_json::to_value(RangeComparison::Between {
+                min: 1,
+                max: u128::MAX
+            })
+            .unwrap(),
+            json!({"between": {"min": "1", "max": u128::MAX.to_string()}})
+        );
+        assert_eq!(
+            serde_json::from_str::<RangeComparison>(r#"{"gt":"18446744073709551616"}"#).unwrap(),
+            RangeComparison::Gt(above_u64_max)
+        );
+
+        // Numbers above u64::MAX would lose precision, and are rejected
+        assert!(serde_json::from_str::<RangeComparison>(r#"{"gt":18446744073709551616}"#).is_err());
+        assert!(serde_json::from_str::<RangeComparison>(r#"{"gt":"-1"}"#).is_err());
+    }
+
     const BASE58_STR: &str = "Bpf4ERpEvSFmCSTNh1PzTWTkALrKXvMXEdthxHuwCQcf";
     const BASE64_STR: &str = "oMoycDvJzrjQpCfukbO4VW/FLGLfnbqBEc9KUEVgj2g=";
     const BYTES: [u8; 4] = [0, 1, 2, 3];
@@ -166,6 +166,22 @@ pub struct RpcKeyedAccount {
     pub account: UiAccount,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcKeyedAccountsPage {
+    pub accounts: Vec<RpcKeyedAccount>,
+    /// Opaque cursor to request the following page with; None on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Accounts returned by an accounts scan, paginated if the request set a `limit`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum RpcKeyedAccounts {
+    Page(RpcKeyedAccountsPage),
+    All(Vec<RpcKeyedAccount>),
+}
+
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
 pub struct SlotInfo {
     pub slot: Slot,
@@ -407,6 +423,42 @@ pub struct RpcSimulateTransactionResult {
     pub return_data: Option<UiTransactionReturnData>,
     pub inner_instructions: Option<Vec<UiInnerInstructions>>,
     pub replacement_blockhash: Option<RpcBlockhash>,
+    /// The accounts changed by the transaction, if requested with `stateDiffs`
+    pub state_diffs: Option<Vec<RpcAccountStateDiff>>,
+}
+
+/// The changes a simulated transaction made to an account. Accounts without lamports are
+/// reported with a default state.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountStateDiff {
+    pub pubkey: String, // base-58 encoded
+    pub pre_lamports: u64,
+    pub post_lamports: u64,
+    pub pre_owner: String,  // base-58 encoded
+    pub post_owner: String, // base-58 encoded
+    pub pre_executable: bool,
+    pub post_executable: bool,
+    pub pre_data_len: u64,
+    pub post_data_len: u64,
+    pub data_changes: Vec<RpcDataRangeDiff>,
+}
+
+/// A range of changed account data. The bytes only differ in length when the range extends
+/// past the end of the shorter data.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcDataRangeDiff {
+    pub offset: u64,
+    pub pre_bytes: String,  // base-64 encoded
+    pub post_bytes: String, // base-64 encoded
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSimulateBundleResult {
+    /// One result per transaction, in bundle order
+    pub transaction_results: Vec<RpcSimulateTransactionResult>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -530,6 +582,26 @@ pub struct RpcPrioritizationFee {
     pub prioritization_fee: u64,
 }
 
+/// Percentiles of the prioritization fees, in micro-lamports per compute unit, needed to land a
+/// transaction in recent blocks
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPriorityFeeEstimate {
+    pub p25: u64,
+    pub p50: u64,
+    pub p75: u64,
+    pub p95: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcContendedAccount {
+    pub pubkey: String,
+    pub compute_units: u64,
+    pub transaction_count: u64,
+    pub rejected_transaction_count: u64,
+}
+
 #[cfg(test)]
 pub mod tests {
 
@@ -307,7 +307,8 @@ impl RpcSender for MockSender {
                     units_consumed: None,
                     return_data: None,
                     inner_instructions: None,
-                    replacement_blockhash: None
+                    replacement_blockhash: None,
+                    state_diffs: None,
                 },
             })?,
             "getMinimumBalanceForRentExemption" => json![20],
@@ -2666,6 +2666,31 @@ impl RpcClient {
         self.invoke((self.rpc_client.as_ref()).get_recent_performance_samples(limit))
     }
 
+    /// Returns percentiles of the prioritization fees needed to land a transaction in recent
+    /// blocks, given the transaction or the accounts it write-locks. Returns `None` if the node
+    /// has no recent blocks in its prioritization-fee cache.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `getPriorityFeeEstimate` RPC method.
+    pub fn get_priority_fee_estimate(
+        &self,
+        config: RpcPriorityFeeEstimateConfig,
+    ) -> ClientResult<Option<RpcPriorityFeeEstimate>> {
+        self.invoke((self.rpc_client.as_ref()).get_priority_fee_estimate(config))
+    }
+
+    /// Returns the accounts with the most write-lock contention in a slot, ordered by the
+    /// compute units consumed by transactions write-locking them. Slots without recorded
+    /// contention return an empty list.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `getContendedAccounts` RPC method.
+    pub fn get_contended_accounts(&self, slot: Slot) -> ClientResult<Vec<RpcContendedAccount>> {
+        self.invoke((self.rpc_client.as_ref()).get_contended_accounts(slot))
+    }
+
     /// Returns a list of minimum prioritization fees from recent blocks.
     /// Takes an optional vector of addresses; if any addresses are provided, the response will
     /// reflect the minimum prioritization fee to land a transaction locking all of the provided
@@ -3370,6 +3395,8 @@ impl RpcClient {
     ///     },
     ///     with_context: Some(false),
     ///     sort_results: Some(true),
+    ///     limit: None,
+    ///     cursor: None,
     /// };
     /// let accounts = rpc_client.get_program_accounts_with_config(
     ///     &alice.pubkey(),
@@ -4028,6 +4055,8 @@ mod tests {
                         },
                         with_context: None,
                         sort_results: None,
+                        limit: None,
+                        cursor: None,
                     },
                 )
                 .unwrap();
@@ -4063,6 +4092,8 @@ mod tests {
                         },
                         with_context: Some(true),
                         sort_results: None,
+                        limit: None,
+                        cursor: None,
                     },
                 )
                 .unwrap();
@@ -7,6 +7,7 @@ use {
     serde_json::{json, Value},
     solana_account_decoder::UiAccount,
     solana_client::connection_cache::ConnectionCache,
+    solana_core::time_control::TimeControlError,
     solana_net_utils::bind_to_unspecified,
     solana_pubsub_client::nonblocking::pubsub_client::PubsubClient,
     solana_rpc_client::rpc_client::RpcClient,
@@ -17,12 +18,13 @@ use {
         response::{Response as RpcResponse, RpcSignatureResult, SlotUpdate},
     },
     solana_sdk::{
+        clock::Clock,
         commitment_config::CommitmentConfig,
         hash::Hash,
         pubkey::Pubkey,
         rent::Rent,
         signature::{Keypair, Signature, Signer},
-        system_transaction,
+        system_transaction, sysvar,
         transaction::Transaction,
     },
     solana_streamer::socket::SocketAddrSpace,
@@ -181,6 +183,72 @@ fn test_simulation_replaced_blockhash() -> ClientResult<()> {
     Ok(())
 }
 
+#[test]
+fn test_time_control() {
+    solana_logger::setup();
+
+    let alice = Keypair::new();
+    let validator = TestValidator::with_no_fees(alice.pubkey(), None, SocketAddrSpace::Unspecified);
+    let rpc_client =
+        RpcClient::new_with_commitment(validator.rpc_url(), CommitmentConfig::processed());
+    let time_control = validator.time_control();
+    let get_clock = || -> Clock {
+        bincode::deserialize(&rpc_client.get_account_data(&sysvar::clock::id()).unwrap()).unwrap()
+    };
+
+    // Warp to an epoch
+    let epoch = rpc_client.get_epoch_info().unwrap().epoch + 2;
+    let warp_slot = time_control.warp_to_epoch(epoch).unwrap();
+    assert_eq!(
+        warp_slot,
+        rpc_client
+            .get_epoch_schedule()
+            .unwrap()
+            .get_first_slot_in_epoch(epoch)
+    );
+    assert!(rpc_client.get_slot().unwrap() >= warp_slot);
+    assert!(rpc_client.get_epoch_info().unwrap().epoch >= epoch);
+    assert!(matches!(
+        time_control.warp_to_slot(warp_slot),
+        Err(TimeControlError::WarpSlotNotInFuture { .. })
+    ));
+
+    // Set the clock a day ahead
+    let unix_timestamp = get_clock().unix_timestamp + 86_400;
+    let warp_slot = time_control
+        .set_clock_unix_timestamp(unix_timestamp)
+        .unwrap();
+    let clock = get_clock();
+    assert!(clock.slot >= warp_slot);
+    assert!(clock.unix_timestamp >= unix_timestamp);
+    assert!(matches!(
+        time_control.set_clock_unix_timestamp(clock.unix_timestamp - 1),
+        Err(TimeControlError::UnixTimestampInPast { .. })
+    ));
+
+    // Slots stop advancing while paused, even while transactions are submitted
+    time_control.pause_slot_production();
+    sleep(Duration::from_secs(1));
+    let paused_slot = rpc_client.get_slot().unwrap();
+    let blockhash = rpc_client.get_latest_blockhash().unwrap();
+    let lamports = rpc_client
+        .get_minimum_balance_for_rent_exemption(0)
+        .unwrap();
+    for _ in 0..100 {
+        let tx = system_transaction::transfer(&alice, &Pubkey::new_unique(), lamports, blockhash);
+        rpc_client.send_transaction(&tx).unwrap();
+    }
+    sleep(Duration::from_secs(2));
+    assert_eq!(rpc_client.get_slot().unwrap(), paused_slot);
+
+    time_control.resume_slot_production();
+    let timeout = Instant::now() + Duration::from_secs(10);
+    while rpc_client.get_slot().unwrap() <= paused_slot {
+        assert!(Instant::now() < timeout, "slot production did not resume");
+        sleep(Duration::from_millis(100));
+    }
+}
+
 #[test]
 fn test_rpc_invalid_requests() {
     solana_logger::setup();
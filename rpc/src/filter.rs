@@ -9,5 +9,8 @@ pub fn filter_allows(filter: &RpcFilterType, account: &AccountSharedData) -> boo
         RpcFilterType::DataSize(size) => account.data().len() as u64 == *size,
         RpcFilterType::Memcmp(compare) => compare.bytes_match(account.data()),
         RpcFilterType::TokenAccountState => Account::valid_account_data(account.data()),
+        RpcFilterType::NotMemcmp(compare) => !compare.bytes_match(account.data()),
+        RpcFilterType::DataRange(range) => range.data_match(account.data()),
+        RpcFilterType::Lamports(comparison) => comparison.matches(account.lamports() as u128),
     }
 }
@@ -7,7 +7,7 @@ use {
     },
     base64::{prelude::BASE64_STANDARD, Engine},
     bincode::{config::Options, serialize},
-    crossbeam_channel::{unbounded, Receiver, Sender},
+    crossbeam_channel::{unbounded, Receiver, Sender, TrySendError},
     jsonrpc_core::{futures::future, types::error, BoxFuture, Error, Metadata, Result},
     jsonrpc_derive::rpc,
     solana_account_decoder::{
@@ -19,6 +19,7 @@ use {
     solana_accounts_db::{
         accounts::AccountAddressFilter,
         accounts_index::{AccountIndex, AccountSecondaryIndexes, IndexKey, ScanConfig},
+        secondary_index::CustomIndexSpec,
     },
     solana_client::connection_cache::Protocol,
     solana_entry::entry::Entry,
@@ -36,17 +37,18 @@ use {
         leader_schedule_cache::LeaderScheduleCache,
     },
     solana_metrics::inc_new_counter_info,
-    solana_perf::packet::PACKET_DATA_SIZE,
+    solana_perf::packet::{Packet, PacketBatch, PACKET_DATA_SIZE},
     solana_rpc_client_api::{
         config::*,
         custom_error::RpcCustomError,
         filter::{Memcmp, RpcFilterType},
         request::{
-            TokenAccountsFilter, DELINQUENT_VALIDATOR_SLOT_DISTANCE,
+            TokenAccountsFilter, DELINQUENT_VALIDATOR_SLOT_DISTANCE, MAX_GET_ACCOUNTS_PAGE_LIMIT,
             MAX_GET_CONFIRMED_BLOCKS_RANGE, MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT,
             MAX_GET_PROGRAM_ACCOUNT_FILTERS, MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS,
             MAX_GET_SLOT_LEADERS, MAX_MULTIPLE_ACCOUNTS,
-            MAX_RPC_VOTE_ACCOUNT_INFO_EPOCH_CREDITS_HISTORY, NUM_LARGEST_ACCOUNTS,
+            MAX_RPC_VOTE_ACCOUNT_INFO_EPOCH_CREDITS_HISTORY, MAX_SEND_BUNDLE_TRANSACTIONS,
+            MAX_SIMULATE_BUNDLE_TRANSACTIONS, NUM_LARGEST_ACCOUNTS,
         },
         response::{Response as RpcResponse, *},
     },
@@ -56,15 +58,15 @@ use {
         commitment::{BlockCommitmentArray, BlockCommitmentCache},
         installed_scheduler_pool::BankWithScheduler,
         non_circulating_supply::calculate_non_circulating_supply,
-        prioritization_fee_cache::PrioritizationFeeCache,
+        prioritization_fee_cache::{PrioritizationFeeCache, PrioritizationFeeEstimate},
         snapshot_config::SnapshotConfig,
         snapshot_utils,
         verify_precompiles::verify_precompiles,
     },
     solana_runtime_transaction::runtime_transaction::RuntimeTransaction,
     solana_sdk::{
-        account::{AccountSharedData, ReadableAccount},
-        clock::{Slot, UnixTimestamp, MAX_PROCESSING_AGE},
+        account::{to_account, AccountSharedData, ReadableAccount, WritableAccount},
+        clock::{Clock, Slot, UnixTimestamp, MAX_PROCESSING_AGE},
         commitment_config::{CommitmentConfig, CommitmentLevel},
         epoch_info::EpochInfo,
         epoch_rewards_hasher::EpochRewardsHasher,
@@ -75,6 +77,7 @@ use {
         pubkey::{Pubkey, PUBKEY_BYTES},
         signature::{Keypair, Signature, Signer},
         system_instruction,
+        sysvar::{self, Sysvar},
         transaction::{
             self, AddressLoader, MessageHash, SanitizedTransaction, TransactionError,
             VersionedTransaction, MAX_TX_ACCOUNT_LOCKS,
@@ -83,6 +86,9 @@ use {
     solana_send_transaction_service::send_transaction_service::TransactionInfo,
     solana_stake_program,
     solana_storage_bigtable::Error as StorageError,
+    solana_svm::{
+        account_overrides::AccountOverrides, transaction_state_diff::TransactionStateDiff,
+    },
     solana_transaction_status::{
         map_inner_instructions, BlockEncodingOptions, ConfirmedBlock,
         ConfirmedTransactionStatusWithSignature, ConfirmedTransactionWithStatusMeta,
@@ -167,6 +173,8 @@ pub struct JsonRpcConfig {
     pub max_request_body_size: Option<usize>,
     /// Disable the health check, used for tests and TestValidator
     pub disable_health_check: bool,
+    /// Accept atomic transaction bundles through `sendBundle`
+    pub enable_send_bundle: bool,
 }
 
 impl JsonRpcConfig {
@@ -186,6 +194,8 @@ pub struct RpcBigtableConfig {
     pub bigtable_app_profile_id: String,
     pub timeout: Option<Duration>,
     pub max_message_size: usize,
+    /// Keep the ledger data in a local database instead of BigTable
+    pub local_storage_path: Option<PathBuf>,
 }
 
 impl Default for RpcBigtableConfig {
@@ -198,6 +208,7 @@ impl Default for RpcBigtableConfig {
             bigtable_app_profile_id,
             timeout: None,
             max_message_size: solana_storage_bigtable::DEFAULT_MAX_MESSAGE_SIZE,
+            local_storage_path: None,
         }
     }
 }
@@ -223,6 +234,8 @@ pub struct JsonRpcRequestProcessor {
     max_complete_transaction_status_slot: Arc<AtomicU64>,
     max_complete_rewards_slot: Arc<AtomicU64>,
     prioritization_fee_cache: Arc<PrioritizationFeeCache>,
+    /// Sends the bundles received by `sendBundle` to banking stage, if bundles are accepted
+    bundle_sender: Option<Sender<PacketBatch>>,
 }
 impl Metadata for JsonRpcRequestProcessor {}
 
@@ -253,6 +266,28 @@ impl JsonRpcRequestProcessor {
         Ok(bank)
     }
 
+    /// Returns the bank to load a page of an accounts scan from: the bank of the first page when
+    /// continuing from a cursor, so every page sees the same state, otherwise the bank selected
+    /// by `config`.
+    ///
+    /// The cursor expires once its bank is pruned from bank forks, at which point the scan has to
+    /// be restarted without a cursor.
+    fn get_bank_for_page(
+        &self,
+        page: Option<&AccountsPage>,
+        config: RpcContextConfig,
+    ) -> Result<Arc<Bank>> {
+        match page.and_then(|page| page.cursor) {
+            Some(AccountsCursor { slot, .. }) => self
+                .bank_forks
+                .read()
+                .unwrap()
+                .get(slot)
+                .ok_or_else(|| RpcCustomError::AccountsCursorExpired { slot }.into()),
+            None => self.get_bank_with_config(config),
+        }
+    }
+
     #[allow(deprecated)]
     fn bank(&self, commitment: Option<CommitmentConfig>) -> Arc<Bank> {
         debug!("RPC commitment_config: {:?}", commitment);
@@ -329,6 +364,7 @@ impl JsonRpcRequestProcessor {
         max_complete_transaction_status_slot: Arc<AtomicU64>,
         max_complete_rewards_slot: Arc<AtomicU64>,
         prioritization_fee_cache: Arc<PrioritizationFeeCache>,
+        bundle_sender: Option<Sender<PacketBatch>>,
     ) -> (Self, Receiver<TransactionInfo>) {
         let (transaction_sender, transaction_receiver) = unbounded();
         (
@@ -351,6 +387,7 @@ impl JsonRpcRequestProcessor {
                 max_complete_transaction_status_slot,
                 max_complete_rewards_slot,
                 prioritization_fee_cache,
+                bundle_sender,
             },
             transaction_receiver,
         )
@@ -430,6 +467,7 @@ impl JsonRpcRequestProcessor {
             max_complete_transaction_status_slot: Arc::new(AtomicU64::default()),
             max_complete_rewards_slot: Arc::new(AtomicU64::default()),
             prioritization_fee_cache: Arc::new(PrioritizationFeeCache::default()),
+            bundle_sender: None,
         }
     }
 
@@ -494,20 +532,24 @@ impl JsonRpcRequestProcessor {
         mut filters: Vec<RpcFilterType>,
         with_context: bool,
         sort_results: bool,
-    ) -> Result<OptionalContext<Vec<RpcKeyedAccount>>> {
+        page: Option<AccountsPage>,
+    ) -> Result<OptionalContext<RpcKeyedAccounts>> {
         let RpcAccountInfoConfig {
             encoding,
             data_slice: data_slice_config,
             commitment,
             min_context_slot,
         } = config.unwrap_or_default();
-        let bank = self.get_bank_with_config(RpcContextConfig {
-            commitment,
-            min_context_slot,
-        })?;
+        let bank = self.get_bank_for_page(
+            page.as_ref(),
+            RpcContextConfig {
+                commitment,
+                min_context_slot,
+            },
+        )?;
         let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
         optimize_filters(&mut filters);
-        let keyed_accounts = {
+        let (keyed_accounts, has_more) = {
             if let Some(owner) = get_spl_token_owner_filter(program_id, &filters) {
                 self.get_filtered_spl_token_accounts_by_owner(
                     &bank,
@@ -515,6 +557,7 @@ impl JsonRpcRequestProcessor {
                     &owner,
                     filters,
                     sort_results,
+                    page.as_ref(),
                 )?
             } else if let Some(mint) = get_spl_token_mint_filter(program_id, &filters) {
                 self.get_filtered_spl_token_accounts_by_mint(
@@ -523,11 +566,31 @@ impl JsonRpcRequestProcessor {
                     &mint,
                     filters,
                     sort_results,
+                    page.as_ref(),
+                )?
+            } else if let Some((spec, index_key)) =
+                get_custom_index_filter(program_id, &filters, &self.config.account_indexes)
+            {
+                self.get_filtered_custom_indexed_accounts(
+                    &bank,
+                    program_id,
+                    spec,
+                    &index_key,
+                    filters,
+                    sort_results,
+                    page.as_ref(),
                 )?
             } else {
-                self.get_filtered_program_accounts(&bank, program_id, filters, sort_results)?
+                self.get_filtered_program_accounts(
+                    &bank,
+                    program_id,
+                    filters,
+                    sort_results,
+                    page.as_ref(),
+                )?
             }
         };
+        let next_cursor = next_accounts_cursor(&bank, &keyed_accounts, has_more);
         let accounts = if is_known_spl_token_id(program_id)
             && encoding == UiAccountEncoding::JsonParsed
         {
@@ -543,6 +606,7 @@ impl JsonRpcRequestProcessor {
                 })
                 .collect::<Result<Vec<_>>>()?
         };
+        let accounts = paginate_keyed_accounts(page.as_ref(), accounts, next_cursor);
         Ok(match with_context {
             true => OptionalContext::Context(new_response(&bank, accounts)),
             false => OptionalContext::NoContext(accounts),
@@ -1901,9 +1965,15 @@ impl JsonRpcRequestProcessor {
 
         let mut token_balances =
             BinaryHeap::<Reverse<(u64, Pubkey)>>::with_capacity(NUM_LARGEST_ACCOUNTS);
-        for (address, account) in
-            self.get_filtered_spl_token_accounts_by_mint(&bank, &mint_owner, mint, vec![], true)?
-        {
+        let (keyed_accounts, _) = self.get_filtered_spl_token_accounts_by_mint(
+            &bank,
+            &mint_owner,
+            mint,
+            vec![],
+            true,
+            None,
+        )?;
+        for (address, account) in keyed_accounts {
             let amount = StateWithExtensions::<TokenAccount>::unpack(account.data())
                 .map(|account| account.base.amount)
                 .unwrap_or(0);
@@ -1941,17 +2011,21 @@ impl JsonRpcRequestProcessor {
         token_account_filter: TokenAccountsFilter,
         config: Option<RpcAccountInfoConfig>,
         sort_results: bool,
-    ) -> Result<RpcResponse<Vec<RpcKeyedAccount>>> {
+        page: Option<AccountsPage>,
+    ) -> Result<RpcResponse<RpcKeyedAccounts>> {
         let RpcAccountInfoConfig {
             encoding,
             data_slice: data_slice_config,
             commitment,
             min_context_slot,
         } = config.unwrap_or_default();
-        let bank = self.get_bank_with_config(RpcContextConfig {
-            commitment,
-            min_context_slot,
-        })?;
+        let bank = self.get_bank_for_page(
+            page.as_ref(),
+            RpcContextConfig {
+                commitment,
+                min_context_slot,
+            },
+        )?;
         let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
         let (token_program_id, mint) = get_token_program_id_and_mint(&bank, token_account_filter)?;
 
@@ -1964,13 +2038,15 @@ impl JsonRpcRequestProcessor {
             )));
         }
 
-        let keyed_accounts = self.get_filtered_spl_token_accounts_by_owner(
+        let (keyed_accounts, has_more) = self.get_filtered_spl_token_accounts_by_owner(
             &bank,
             &token_program_id,
             owner,
             filters,
             sort_results,
+            page.as_ref(),
         )?;
+        let next_cursor = next_accounts_cursor(&bank, &keyed_accounts, has_more);
         let accounts = if encoding == UiAccountEncoding::JsonParsed {
             get_parsed_token_accounts(bank.clone(), keyed_accounts.into_iter()).collect()
         } else {
@@ -1984,7 +2060,10 @@ impl JsonRpcRequestProcessor {
                 })
                 .collect::<Result<Vec<_>>>()?
         };
-        Ok(new_response(&bank, accounts))
+        Ok(new_response(
+            &bank,
+            paginate_keyed_accounts(page.as_ref(), accounts, next_cursor),
+        ))
     }
 
     pub fn get_token_accounts_by_delegate(
@@ -2017,18 +2096,25 @@ impl JsonRpcRequestProcessor {
             RpcFilterType::Memcmp(Memcmp::new_raw_bytes(76, delegate.to_bytes().into())),
         ];
         // Optional filter on Mint address, uses mint account index for scan
-        let keyed_accounts = if let Some(mint) = mint {
+        let (keyed_accounts, _) = if let Some(mint) = mint {
             self.get_filtered_spl_token_accounts_by_mint(
                 &bank,
                 &token_program_id,
                 &mint,
                 filters,
                 sort_results,
+                None,
             )?
         } else {
             // Filter on Token Account state
             filters.push(RpcFilterType::TokenAccountState);
-            self.get_filtered_program_accounts(&bank, &token_program_id, filters, sort_results)?
+            self.get_filtered_program_accounts(
+                &bank,
+                &token_program_id,
+                filters,
+                sort_results,
+                None,
+            )?
         };
         let accounts = if encoding == UiAccountEncoding::JsonParsed {
             get_parsed_token_accounts(bank.clone(), keyed_accounts.into_iter()).collect()
@@ -2053,7 +2139,8 @@ impl JsonRpcRequestProcessor {
         program_id: &Pubkey,
         mut filters: Vec<RpcFilterType>,
         sort_results: bool,
-    ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
+        page: Option<&AccountsPage>,
+    ) -> RpcCustomResult<(Vec<(Pubkey, AccountSharedData)>, bool)> {
         optimize_filters(&mut filters);
         let filter_closure = |account: &AccountSharedData| {
             filters
@@ -2070,34 +2157,24 @@ impl JsonRpcRequestProcessor {
                     index_key: program_id.to_string(),
                 });
             }
-            Ok(bank
-                .get_filtered_indexed_accounts(
-                    &IndexKey::ProgramId(*program_id),
-                    |account| {
-                        // The program-id account index checks for Account owner on inclusion. However, due
-                        // to the current AccountsDb implementation, an account may remain in storage as a
-                        // zero-lamport AccountSharedData::Default() after being wiped and reinitialized in later
-                        // updates. We include the redundant filters here to avoid returning these
-                        // accounts.
-                        account.owner() == program_id && filter_closure(account)
-                    },
-                    &ScanConfig::new(!sort_results),
-                    bank.byte_limit_for_scans(),
-                )
-                .map_err(|e| RpcCustomError::ScanError {
-                    message: e.to_string(),
-                })?)
+            load_filtered_accounts(
+                bank,
+                Some(IndexKey::ProgramId(*program_id)),
+                program_id,
+                |account| {
+                    // The program-id account index checks for Account owner on inclusion. However, due
+                    // to the current AccountsDb implementation, an account may remain in storage as a
+                    // zero-lamport AccountSharedData::Default() after being wiped and reinitialized in later
+                    // updates. We include the redundant filters here to avoid returning these
+                    // accounts.
+                    account.owner() == program_id && filter_closure(account)
+                },
+                sort_results,
+                page,
+            )
         } else {
             // this path does not need to provide a mb limit because we only want to support secondary indexes
-            Ok(bank
-                .get_filtered_program_accounts(
-                    program_id,
-                    filter_closure,
-                    &ScanConfig::new(!sort_results),
-                )
-                .map_err(|e| RpcCustomError::ScanError {
-                    message: e.to_string(),
-                })?)
+            load_filtered_accounts(bank, None, program_id, filter_closure, sort_results, page)
         }
     }
 
@@ -2109,7 +2186,8 @@ impl JsonRpcRequestProcessor {
         owner_key: &Pubkey,
         mut filters: Vec<RpcFilterType>,
         sort_results: bool,
-    ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
+        page: Option<&AccountsPage>,
+    ) -> RpcCustomResult<(Vec<(Pubkey, AccountSharedData)>, bool)> {
         // The by-owner accounts index checks for Token Account state and Owner address on
         // inclusion. However, due to the current AccountsDb implementation, an account may remain
         // in storage as a zero-lamport AccountSharedData::Default() after being wiped and reinitialized in
@@ -2133,23 +2211,21 @@ impl JsonRpcRequestProcessor {
                     index_key: owner_key.to_string(),
                 });
             }
-            Ok(bank
-                .get_filtered_indexed_accounts(
-                    &IndexKey::SplTokenOwner(*owner_key),
-                    |account| {
-                        account.owner() == program_id
-                            && filters
-                                .iter()
-                                .all(|filter_type| filter_allows(filter_type, account))
-                    },
-                    &ScanConfig::new(!sort_results),
-                    bank.byte_limit_for_scans(),
-                )
-                .map_err(|e| RpcCustomError::ScanError {
-                    message: e.to_string(),
-                })?)
+            load_filtered_accounts(
+                bank,
+                Some(IndexKey::SplTokenOwner(*owner_key)),
+                program_id,
+                |account| {
+                    account.owner() == program_id
+                        && filters
+                            .iter()
+                            .all(|filter_type| filter_allows(filter_type, account))
+                },
+                sort_results,
+                page,
+            )
         } else {
-            self.get_filtered_program_accounts(bank, program_id, filters, sort_results)
+            self.get_filtered_program_accounts(bank, program_id, filters, sort_results, page)
         }
     }
 
@@ -2161,7 +2237,8 @@ impl JsonRpcRequestProcessor {
         mint_key: &Pubkey,
         mut filters: Vec<RpcFilterType>,
         sort_results: bool,
-    ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
+        page: Option<&AccountsPage>,
+    ) -> RpcCustomResult<(Vec<(Pubkey, AccountSharedData)>, bool)> {
         // The by-mint accounts index checks for Token Account state and Mint address on inclusion.
         // However, due to the current AccountsDb implementation, an account may remain in storage
         // as be zero-lamport AccountSharedData::Default() after being wiped and reinitialized in later
@@ -2184,24 +2261,55 @@ impl JsonRpcRequestProcessor {
                     index_key: mint_key.to_string(),
                 });
             }
-            Ok(bank
-                .get_filtered_indexed_accounts(
-                    &IndexKey::SplTokenMint(*mint_key),
-                    |account| {
-                        account.owner() == program_id
-                            && filters
-                                .iter()
-                                .all(|filter_type| filter_allows(filter_type, account))
-                    },
-                    &ScanConfig::new(!sort_results),
-                    bank.byte_limit_for_scans(),
-                )
-                .map_err(|e| RpcCustomError::ScanError {
-                    message: e.to_string(),
-                })?)
+            load_filtered_accounts(
+                bank,
+                Some(IndexKey::SplTokenMint(*mint_key)),
+                program_id,
+                |account| {
+                    account.owner() == program_id
+                        && filters
+                            .iter()
+                            .all(|filter_type| filter_allows(filter_type, account))
+                },
+                sort_results,
+                page,
+            )
         } else {
-            self.get_filtered_program_accounts(bank, program_id, filters, sort_results)
+            self.get_filtered_program_accounts(bank, program_id, filters, sort_results, page)
+        }
+    }
+
+    /// Get an iterator of program accounts through a custom secondary index
+    fn get_filtered_custom_indexed_accounts(
+        &self,
+        bank: &Bank,
+        program_id: &Pubkey,
+        spec: CustomIndexSpec,
+        index_key: &Pubkey,
+        filters: Vec<RpcFilterType>,
+        sort_results: bool,
+        page: Option<&AccountsPage>,
+    ) -> RpcCustomResult<(Vec<(Pubkey, AccountSharedData)>, bool)> {
+        if !self.config.account_indexes.include_key(index_key) {
+            return Err(RpcCustomError::KeyExcludedFromSecondaryIndex {
+                index_key: index_key.to_string(),
+            });
         }
+        // The filters still include the memcmp on the indexed key, which excludes accounts whose
+        // data changed after they were added to the index
+        load_filtered_accounts(
+            bank,
+            Some(IndexKey::Custom(spec, *index_key)),
+            program_id,
+            |account| {
+                account.owner() == program_id
+                    && filters
+                        .iter()
+                        .all(|filter_type| filter_allows(filter_type, account))
+            },
+            sort_results,
+            page,
+        )
     }
 
     fn get_latest_blockhash(&self, config: RpcContextConfig) -> Result<RpcResponse<RpcBlockhash>> {
@@ -2250,11 +2358,148 @@ impl JsonRpcRequestProcessor {
             })
             .collect())
     }
+
+    fn get_priority_fee_estimate(&self, account_keys: &[Pubkey]) -> Option<RpcPriorityFeeEstimate> {
+        self.prioritization_fee_cache
+            .get_prioritization_fee_estimate(account_keys)
+            .map(
+                |PrioritizationFeeEstimate { p25, p50, p75, p95 }| RpcPriorityFeeEstimate {
+                    p25,
+                    p50,
+                    p75,
+                    p95,
+                },
+            )
+    }
+
+    fn get_contended_accounts(&self, slot: Slot) -> Result<Vec<RpcContendedAccount>> {
+        let result = self.blockstore.get_contended_accounts(slot);
+        self.check_slot_cleaned_up(&result, slot)?;
+        Ok(result
+            .map_err(|err| {
+                warn!("get_contended_accounts failed: {:?}", err);
+                Error::invalid_request()
+            })?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|account| RpcContendedAccount {
+                pubkey: account.pubkey.to_string(),
+                compute_units: account.compute_units,
+                transaction_count: account.transaction_count,
+                rejected_transaction_count: account.rejected_transaction_count,
+            })
+            .collect())
+    }
+}
+
+/// Position of a paginated accounts scan, handed to clients as an opaque cursor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct AccountsCursor {
+    /// Slot of the bank every page of the scan is loaded from
+    slot: Slot,
+    /// Last account returned so far
+    last: Pubkey,
+}
+
+impl AccountsCursor {
+    const SERIALIZED_SIZE: usize = std::mem::size_of::<Slot>() + PUBKEY_BYTES;
+
+    fn encode(&self) -> String {
+        let mut bytes = Vec::with_capacity(Self::SERIALIZED_SIZE);
+        bytes.extend_from_slice(&self.slot.to_le_bytes());
+        bytes.extend_from_slice(self.last.as_ref());
+        bs58::encode(bytes).into_string()
+    }
+
+    fn decode(cursor: &str) -> Option<Self> {
+        let bytes = bs58::decode(cursor).into_vec().ok()?;
+        if bytes.len() != Self::SERIALIZED_SIZE {
+            return None;
+        }
+        let (slot, last) = bytes.split_at(std::mem::size_of::<Slot>());
+        Some(Self {
+            slot: Slot::from_le_bytes(slot.try_into().ok()?),
+            last: Pubkey::try_from(last).ok()?,
+        })
+    }
+}
+
+/// A page requested from an accounts scan
+#[derive(Debug)]
+pub struct AccountsPage {
+    limit: usize,
+    cursor: Option<AccountsCursor>,
+}
+
+/// Loads the accounts of `bank` passing `filter`, through the secondary index of `index_key` if
+/// it is Some. If `page` is Some, only that page is loaded, and the returned flag is whether more
+/// accounts follow it. Pages are always sorted by pubkey, regardless of `sort_results`.
+fn load_filtered_accounts(
+    bank: &Bank,
+    index_key: Option<IndexKey>,
+    program_id: &Pubkey,
+    filter: impl Fn(&AccountSharedData) -> bool,
+    sort_results: bool,
+    page: Option<&AccountsPage>,
+) -> RpcCustomResult<(Vec<(Pubkey, AccountSharedData)>, bool)> {
+    let scan_result = match (page, index_key) {
+        (Some(page), index_key) => bank.get_filtered_accounts_page(
+            index_key.as_ref(),
+            |account| account.owner() == program_id && filter(account),
+            page.cursor.as_ref().map(|cursor| &cursor.last),
+            page.limit,
+            &ScanConfig::default(),
+            bank.byte_limit_for_scans(),
+        ),
+        (None, Some(index_key)) => bank
+            .get_filtered_indexed_accounts(
+                &index_key,
+                filter,
+                &ScanConfig::new(!sort_results),
+                bank.byte_limit_for_scans(),
+            )
+            .map(|accounts| (accounts, false)),
+        (None, None) => bank
+            .get_filtered_program_accounts(program_id, filter, &ScanConfig::new(!sort_results))
+            .map(|accounts| (accounts, false)),
+    };
+    scan_result.map_err(|e| RpcCustomError::ScanError {
+        message: e.to_string(),
+    })
+}
+
+/// Returns the cursor to the page following `keyed_accounts`, if there is one
+fn next_accounts_cursor(
+    bank: &Bank,
+    keyed_accounts: &[(Pubkey, AccountSharedData)],
+    has_more: bool,
+) -> Option<String> {
+    keyed_accounts.last().filter(|_| has_more).map(|(last, _)| {
+        AccountsCursor {
+            slot: bank.slot(),
+            last: *last,
+        }
+        .encode()
+    })
+}
+
+fn paginate_keyed_accounts(
+    page: Option<&AccountsPage>,
+    accounts: Vec<RpcKeyedAccount>,
+    next_cursor: Option<String>,
+) -> RpcKeyedAccounts {
+    match page {
+        Some(_) => RpcKeyedAccounts::Page(RpcKeyedAccountsPage {
+            accounts,
+            next_cursor,
+        }),
+        None => RpcKeyedAccounts::All(accounts),
+    }
 }
 
 fn optimize_filters(filters: &mut [RpcFilterType]) {
     filters.iter_mut().for_each(|filter_type| {
-        if let RpcFilterType::Memcmp(compare) = filter_type {
+        if let RpcFilterType::Memcmp(compare) | RpcFilterType::NotMemcmp(compare) = filter_type {
             if let Err(err) = compare.convert_to_raw_bytes() {
                 // All filters should have been previously verified
                 warn!("Invalid filter: bytes could not be decoded, {err}");
@@ -2283,7 +2528,7 @@ fn verify_transaction(
     Ok(())
 }
 
-fn verify_filter(input: &RpcFilterType) -> Result<()> {
+pub(crate) fn verify_filter(input: &RpcFilterType) -> Result<()> {
     input
         .verify()
         .map_err(|e| Error::invalid_params(format!("Invalid param: {e:?}")))
@@ -2307,6 +2552,32 @@ fn verify_signature(input: &str) -> Result<Signature> {
         .map_err(|e| Error::invalid_params(format!("Invalid param: {e:?}")))
 }
 
+fn verify_accounts_page(
+    limit: Option<usize>,
+    cursor: Option<String>,
+) -> Result<Option<AccountsPage>> {
+    let Some(limit) = limit else {
+        if cursor.is_some() {
+            return Err(Error::invalid_params(
+                "Invalid param: cursor requires a limit".to_string(),
+            ));
+        }
+        return Ok(None);
+    };
+    if limit == 0 || limit > MAX_GET_ACCOUNTS_PAGE_LIMIT {
+        return Err(Error::invalid_params(format!(
+            "Invalid limit; max {MAX_GET_ACCOUNTS_PAGE_LIMIT}"
+        )));
+    }
+    let cursor = cursor
+        .map(|cursor| {
+            AccountsCursor::decode(&cursor)
+                .ok_or_else(|| Error::invalid_params("Invalid param: cursor".to_string()))
+        })
+        .transpose()?;
+    Ok(Some(AccountsPage { limit, cursor }))
+}
+
 fn verify_token_account_filter(
     token_account_filter: RpcTokenAccountsFilter,
 ) -> Result<TokenAccountsFilter> {
@@ -2375,6 +2646,103 @@ fn get_encoded_account(
     }
 }
 
+/// Applies the overrides of a simulation to the current state of the accounts
+fn get_simulation_account_overrides(
+    bank: &Bank,
+    account_overrides: Option<HashMap<String, RpcAccountOverride>>,
+    clock_override: Option<RpcClockOverride>,
+) -> Result<HashMap<Pubkey, AccountSharedData>> {
+    let account_overrides = account_overrides.unwrap_or_default();
+    if account_overrides.len() > MAX_MULTIPLE_ACCOUNTS {
+        return Err(Error::invalid_params(format!(
+            "Too many account overrides provided; max {MAX_MULTIPLE_ACCOUNTS}"
+        )));
+    }
+
+    let mut overridden_accounts = account_overrides
+        .into_iter()
+        .map(|(address, account_override)| {
+            let pubkey = verify_pubkey(&address)?;
+            // Programs read most sysvars from the sysvar cache rather than from the accounts,
+            // so only the clock can be overridden, with `clockOverride`
+            if sysvar::is_sysvar_id(&pubkey) {
+                return Err(Error::invalid_params(format!(
+                    "Sysvar account {pubkey} can't be overridden"
+                )));
+            }
+            let mut account = bank.get_account(&pubkey).unwrap_or_default();
+            if let Some(lamports) = account_override.lamports {
+                account.set_lamports(lamports);
+            }
+            if let Some(owner) = account_override.owner {
+                account.set_owner(verify_pubkey(&owner)?);
+            }
+            if let Some(executable) = account_override.executable {
+                account.set_executable(executable);
+            }
+            if let Some(data) = account_override.data {
+                let data = data.decode().ok_or_else(|| {
+                    Error::invalid_params(format!("Invalid data override for account {pubkey}"))
+                })?;
+                account.set_data(data);
+            }
+            Ok((pubkey, account))
+        })
+        .collect::<Result<HashMap<_, _>>>()?;
+
+    if let Some(clock_override) = clock_override {
+        let RpcClockOverride {
+            slot,
+            epoch_start_timestamp,
+            epoch,
+            leader_schedule_epoch,
+            unix_timestamp,
+        } = clock_override;
+        let clock = bank.clock();
+        let clock = Clock {
+            slot: slot.unwrap_or(clock.slot),
+            epoch_start_timestamp: epoch_start_timestamp.unwrap_or(clock.epoch_start_timestamp),
+            epoch: epoch.unwrap_or(clock.epoch),
+            leader_schedule_epoch: leader_schedule_epoch.unwrap_or(clock.leader_schedule_epoch),
+            unix_timestamp: unix_timestamp.unwrap_or(clock.unix_timestamp),
+        };
+        let mut account = bank
+            .get_account(&sysvar::clock::id())
+            .unwrap_or_else(|| AccountSharedData::new(1, Clock::size_of(), &sysvar::id()));
+        to_account(&clock, &mut account).ok_or_else(Error::internal_error)?;
+        overridden_accounts.insert(sysvar::clock::id(), account);
+    }
+
+    Ok(overridden_accounts)
+}
+
+fn encode_state_diff(state_diff: TransactionStateDiff) -> Vec<RpcAccountStateDiff> {
+    state_diff
+        .accounts
+        .into_iter()
+        .map(|account_diff| RpcAccountStateDiff {
+            pubkey: account_diff.pubkey.to_string(),
+            pre_lamports: account_diff.pre_lamports,
+            post_lamports: account_diff.post_lamports,
+            pre_owner: account_diff.pre_owner.to_string(),
+            post_owner: account_diff.post_owner.to_string(),
+            pre_executable: account_diff.pre_executable,
+            post_executable: account_diff.post_executable,
+            pre_data_len: account_diff.pre_data_len as u64,
+            post_data_len: account_diff.post_data_len as u64,
+            data_changes: account_diff
+                .data_changes
+                .into_iter()
+                .map(|data_diff| RpcDataRangeDiff {
+                    offset: data_diff.offset as u64,
+                    pre_bytes: BASE64_STANDARD.encode(data_diff.pre_bytes),
+                    post_bytes: BASE64_STANDARD.encode(data_diff.post_bytes),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
 fn encode_account<T: ReadableAccount>(
     account: &T,
     pubkey: &Pubkey,
@@ -2432,6 +2800,9 @@ fn get_spl_token_owner_filter(program_id: &Pubkey, filters: &[RpcFilterType]) ->
                 }
             }
             RpcFilterType::TokenAccountState => token_account_state_filter = true,
+            RpcFilterType::NotMemcmp(_)
+            | RpcFilterType::DataRange(_)
+            | RpcFilterType::Lamports(_) => {}
         }
     }
     if data_size_filter == Some(account_packed_len as u64)
@@ -2483,6 +2854,9 @@ fn get_spl_token_mint_filter(program_id: &Pubkey, filters: &[RpcFilterType]) ->
                 }
             }
             RpcFilterType::TokenAccountState => token_account_state_filter = true,
+            RpcFilterType::NotMemcmp(_)
+            | RpcFilterType::DataRange(_)
+            | RpcFilterType::Lamports(_) => {}
         }
     }
     if data_size_filter == Some(account_packed_len as u64)
@@ -2502,6 +2876,31 @@ fn get_spl_token_mint_filter(program_id: &Pubkey, filters: &[RpcFilterType]) ->
     }
 }
 
+/// Analyze custom filters to determine if the result will be a subset of the accounts in an
+/// enabled custom secondary index, and if so, return the index and the key to scan it by.
+/// NOTE: `optimize_filters()` should almost always be called before using this method because of
+/// the requirement that `Memcmp::raw_bytes_as_ref().is_some()`.
+fn get_custom_index_filter(
+    program_id: &Pubkey,
+    filters: &[RpcFilterType],
+    account_indexes: &AccountSecondaryIndexes,
+) -> Option<(CustomIndexSpec, Pubkey)> {
+    filters.iter().find_map(|filter| {
+        let RpcFilterType::Memcmp(memcmp) = filter else {
+            return None;
+        };
+        let spec = CustomIndexSpec {
+            owner: *program_id,
+            offset: memcmp.offset(),
+        };
+        if !account_indexes.contains(&AccountIndex::Custom(spec)) {
+            return None;
+        }
+        let index_key = Pubkey::try_from(memcmp.raw_bytes_as_ref()?).ok()?;
+        Some((spec, index_key))
+    })
+}
+
 /// Analyze a passed Pubkey that may be a Token program id or Mint address to determine the program
 /// id and optional Mint
 fn get_token_program_id_and_mint(
@@ -3151,7 +3550,7 @@ pub mod rpc_accounts_scan {
             meta: Self::Metadata,
             program_id_str: String,
             config: Option<RpcProgramAccountsConfig>,
-        ) -> Result<OptionalContext<Vec<RpcKeyedAccount>>>;
+        ) -> Result<OptionalContext<RpcKeyedAccounts>>;
 
         #[rpc(meta, name = "getLargestAccounts")]
         fn get_largest_accounts(
@@ -3185,8 +3584,8 @@ pub mod rpc_accounts_scan {
             meta: Self::Metadata,
             owner_str: String,
             token_account_filter: RpcTokenAccountsFilter,
-            config: Option<RpcAccountInfoConfig>,
-        ) -> Result<RpcResponse<Vec<RpcKeyedAccount>>>;
+            config: Option<RpcTokenAccountsConfig>,
+        ) -> Result<RpcResponse<RpcKeyedAccounts>>;
 
         #[rpc(meta, name = "getTokenAccountsByDelegate")]
         fn get_token_accounts_by_delegate(
@@ -3207,21 +3606,22 @@ pub mod rpc_accounts_scan {
             meta: Self::Metadata,
             program_id_str: String,
             config: Option<RpcProgramAccountsConfig>,
-        ) -> Result<OptionalContext<Vec<RpcKeyedAccount>>> {
+        ) -> Result<OptionalContext<RpcKeyedAccounts>> {
             debug!(
                 "get_program_accounts rpc request received: {:?}",
                 program_id_str
             );
             let program_id = verify_pubkey(&program_id_str)?;
-            let (config, filters, with_context, sort_results) = if let Some(config) = config {
+            let (config, filters, with_context, sort_results, page) = if let Some(config) = config {
                 (
                     Some(config.account_config),
                     config.filters.unwrap_or_default(),
                     config.with_context.unwrap_or_default(),
                     config.sort_results.unwrap_or(true),
+                    verify_accounts_page(config.limit, config.cursor)?,
                 )
             } else {
-                (None, vec![], false, true)
+                (None, vec![], false, true, None)
             };
             if filters.len() > MAX_GET_PROGRAM_ACCOUNT_FILTERS {
                 return Err(Error::invalid_params(format!(
@@ -3231,7 +3631,14 @@ pub mod rpc_accounts_scan {
             for filter in &filters {
                 verify_filter(filter)?;
             }
-            meta.get_program_accounts(&program_id, config, filters, with_context, sort_results)
+            meta.get_program_accounts(
+                &program_id,
+                config,
+                filters,
+                with_context,
+                sort_results,
+                page,
+            )
         }
 
         fn get_largest_accounts(
@@ -3271,15 +3678,23 @@ pub mod rpc_accounts_scan {
             meta: Self::Metadata,
             owner_str: String,
             token_account_filter: RpcTokenAccountsFilter,
-            config: Option<RpcAccountInfoConfig>,
-        ) -> Result<RpcResponse<Vec<RpcKeyedAccount>>> {
+            config: Option<RpcTokenAccountsConfig>,
+        ) -> Result<RpcResponse<RpcKeyedAccounts>> {
             debug!(
                 "get_token_accounts_by_owner rpc request received: {:?}",
                 owner_str
             );
             let owner = verify_pubkey(&owner_str)?;
             let token_account_filter = verify_token_account_filter(token_account_filter)?;
-            meta.get_token_accounts_by_owner(&owner, token_account_filter, config, true)
+            let (config, page) = if let Some(config) = config {
+                (
+                    Some(config.account_config),
+                    verify_accounts_page(config.limit, config.cursor)?,
+                )
+            } else {
+                (None, None)
+            };
+            meta.get_token_accounts_by_owner(&owner, token_account_filter, config, true, page)
         }
 
         fn get_token_accounts_by_delegate(
@@ -3361,6 +3776,14 @@ pub mod rpc_full {
             config: Option<RpcSendTransactionConfig>,
         ) -> Result<String>;
 
+        #[rpc(meta, name = "sendBundle")]
+        fn send_bundle(
+            &self,
+            meta: Self::Metadata,
+            data: Vec<String>,
+            config: Option<RpcSendBundleConfig>,
+        ) -> Result<Vec<String>>;
+
         #[rpc(meta, name = "simulateTransaction")]
         fn simulate_transaction(
             &self,
@@ -3369,6 +3792,14 @@ pub mod rpc_full {
             config: Option<RpcSimulateTransactionConfig>,
         ) -> Result<RpcResponse<RpcSimulateTransactionResult>>;
 
+        #[rpc(meta, name = "simulateBundle")]
+        fn simulate_bundle(
+            &self,
+            meta: Self::Metadata,
+            data: Vec<String>,
+            config: Option<RpcSimulateBundleConfig>,
+        ) -> Result<RpcResponse<RpcSimulateBundleResult>>;
+
         #[rpc(meta, name = "minimumLedgerSlot")]
         fn minimum_ledger_slot(&self, meta: Self::Metadata) -> Result<Slot>;
 
@@ -3460,6 +3891,20 @@ pub mod rpc_full {
             meta: Self::Metadata,
             pubkey_strs: Option<Vec<String>>,
         ) -> Result<Vec<RpcPrioritizationFee>>;
+
+        #[rpc(meta, name = "getPriorityFeeEstimate")]
+        fn get_priority_fee_estimate(
+            &self,
+            meta: Self::Metadata,
+            config: Option<RpcPriorityFeeEstimateConfig>,
+        ) -> Result<Option<RpcPriorityFeeEstimate>>;
+
+        #[rpc(meta, name = "getContendedAccounts")]
+        fn get_contended_accounts(
+            &self,
+            meta: Self::Metadata,
+            slot: Slot,
+        ) -> Result<Vec<RpcContendedAccount>>;
     }
 
     pub struct FullImpl;
@@ -3746,6 +4191,7 @@ pub mod rpc_full {
                     units_consumed,
                     return_data,
                     inner_instructions: _, // Always `None` due to `enable_cpi_recording = false`
+                    state_diff: _,
                 } = preflight_bank.simulate_transaction(&transaction, false)
                 {
                     match err {
@@ -3766,6 +4212,7 @@ pub mod rpc_full {
                             return_data: return_data.map(|return_data| return_data.into()),
                             inner_instructions: None,
                             replacement_blockhash: None,
+                            state_diffs: None,
                         },
                     }
                     .into());
@@ -3782,6 +4229,69 @@ pub mod rpc_full {
             )
         }
 
+        fn send_bundle(
+            &self,
+            meta: Self::Metadata,
+            data: Vec<String>,
+            config: Option<RpcSendBundleConfig>,
+        ) -> Result<Vec<String>> {
+            debug!("send_bundle rpc request received");
+            let Some(bundle_sender) = &meta.bundle_sender else {
+                return Err(RpcCustomError::BundlesNotAccepted.into());
+            };
+            let RpcSendBundleConfig { encoding } = config.unwrap_or_default();
+            if data.is_empty() {
+                return Err(Error::invalid_params("bundle must contain a transaction"));
+            }
+            if data.len() > MAX_SEND_BUNDLE_TRANSACTIONS {
+                return Err(Error::invalid_params(format!(
+                    "Too many transactions provided; max {MAX_SEND_BUNDLE_TRANSACTIONS}"
+                )));
+            }
+            let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
+            let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
+                Error::invalid_params(format!(
+                    "unsupported encoding: {tx_encoding}. Supported encodings: base58, base64"
+                ))
+            })?;
+
+            // Banking stage expects the bundle's signatures to have been verified already
+            let bank = &*meta.bank(Some(CommitmentConfig::processed()));
+            let mut signatures = Vec::with_capacity(data.len());
+            let mut unique_signatures = HashSet::with_capacity(data.len());
+            let mut message_hashes = HashSet::with_capacity(data.len());
+            let mut packets = Vec::with_capacity(data.len());
+            for data in data {
+                let (_, unsanitized_tx) =
+                    decode_and_deserialize::<VersionedTransaction>(data, binary_encoding)?;
+                let transaction =
+                    sanitize_transaction(unsanitized_tx, bank, bank.get_reserved_account_keys())?;
+                verify_transaction(&transaction, &bank.feature_set)?;
+                // A transaction repeated within the bundle would be committed twice
+                if !unique_signatures.insert(*transaction.signature())
+                    || !message_hashes.insert(*transaction.message_hash())
+                {
+                    return Err(Error::invalid_params(format!(
+                        "transaction {} is repeated in the bundle",
+                        transaction.signature()
+                    )));
+                }
+                signatures.push(transaction.signature().to_string());
+                packets.push(
+                    Packet::from_data(None, transaction.to_versioned_transaction())
+                        .map_err(|_| Error::internal_error())?,
+                );
+            }
+
+            match bundle_sender.try_send(PacketBatch::new(packets)) {
+                Ok(()) => Ok(signatures),
+                Err(TrySendError::Full(_)) => Err(RpcCustomError::BundleQueueFull.into()),
+                Err(TrySendError::Disconnected(_)) => {
+                    Err(RpcCustomError::BundlesNotAccepted.into())
+                }
+            }
+        }
+
         fn simulate_transaction(
             &self,
             meta: Self::Metadata,
@@ -3797,6 +4307,9 @@ pub mod rpc_full {
                 accounts: config_accounts,
                 min_context_slot,
                 inner_instructions: enable_cpi_recording,
+                account_overrides,
+                clock_override,
+                state_diffs: record_state_diffs,
             } = config.unwrap_or_default();
             let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
             let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
@@ -3811,6 +4324,8 @@ pub mod rpc_full {
                 commitment,
                 min_context_slot,
             })?;
+            let overridden_accounts =
+                get_simulation_account_overrides(bank, account_overrides, clock_override)?;
             let mut blockhash: Option<RpcBlockhash> = None;
             if replace_recent_blockhash {
                 if sig_verify {
@@ -3844,7 +4359,19 @@ pub mod rpc_full {
                 units_consumed,
                 return_data,
                 inner_instructions,
-            } = bank.simulate_transaction(&transaction, enable_cpi_recording);
+                state_diff,
+            } = {
+                let mut account_overrides = AccountOverrides::default();
+                for (pubkey, account) in &overridden_accounts {
+                    account_overrides.set_account(pubkey, Some(account.clone()));
+                }
+                bank.simulate_transaction_with_overrides(
+                    &transaction,
+                    enable_cpi_recording,
+                    record_state_diffs,
+                    account_overrides,
+                )
+            };
 
             let account_keys = transaction.message().account_keys();
             let number_of_accounts = account_keys.len();
@@ -3869,7 +4396,7 @@ pub mod rpc_full {
                 if result.is_err() {
                     Some(vec![None; config_accounts.addresses.len()])
                 } else {
-                    let mut post_simulation_accounts_map = HashMap::new();
+                    let mut post_simulation_accounts_map = overridden_accounts;
                     for (pubkey, data) in post_simulation_accounts {
                         post_simulation_accounts_map.insert(pubkey, data);
                     }
@@ -3911,29 +4438,224 @@ pub mod rpc_full {
                     return_data: return_data.map(|return_data| return_data.into()),
                     inner_instructions,
                     replacement_blockhash: blockhash,
+                    state_diffs: state_diff.map(encode_state_diff),
                 },
             ))
         }
 
-        fn minimum_ledger_slot(&self, meta: Self::Metadata) -> Result<Slot> {
-            debug!("minimum_ledger_slot rpc request received");
-            meta.minimum_ledger_slot()
-        }
-
-        fn get_block(
-            &self,
-            meta: Self::Metadata,
-            slot: Slot,
-            config: Option<RpcEncodingConfigWrapper<RpcBlockConfig>>,
-        ) -> BoxFuture<Result<Option<UiConfirmedBlock>>> {
-            debug!("get_block rpc request received: {:?}", slot);
-            Box::pin(async move { meta.get_block(slot, config).await })
-        }
-
-        fn get_blocks(
+        fn simulate_bundle(
             &self,
             meta: Self::Metadata,
-            start_slot: Slot,
+            data: Vec<String>,
+            config: Option<RpcSimulateBundleConfig>,
+        ) -> Result<RpcResponse<RpcSimulateBundleResult>> {
+            debug!("simulate_bundle rpc request received");
+            let RpcSimulateBundleConfig {
+                sig_verify,
+                replace_recent_blockhash,
+                commitment,
+                encoding,
+                accounts: config_accounts,
+                min_context_slot,
+                inner_instructions: enable_cpi_recording,
+                state_diffs: record_state_diffs,
+            } = config.unwrap_or_default();
+            if data.is_empty() {
+                return Err(Error::invalid_params("bundle must contain a transaction"));
+            }
+            if data.len() > MAX_SIMULATE_BUNDLE_TRANSACTIONS {
+                return Err(Error::invalid_params(format!(
+                    "Too many transactions provided; max {MAX_SIMULATE_BUNDLE_TRANSACTIONS}"
+                )));
+            }
+            let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
+            let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
+                Error::invalid_params(format!(
+                    "unsupported encoding: {tx_encoding}. Supported encodings: base58, base64"
+                ))
+            })?;
+            let unsanitized_txs = data
+                .into_iter()
+                .map(|data| {
+                    decode_and_deserialize::<VersionedTransaction>(data, binary_encoding)
+                        .map(|(_, unsanitized_tx)| unsanitized_tx)
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let accounts_config = config_accounts
+                .map(|config_accounts| {
+                    let accounts_encoding = config_accounts
+                        .encoding
+                        .unwrap_or(UiAccountEncoding::Base64);
+
+                    if accounts_encoding == UiAccountEncoding::Binary
+                        || accounts_encoding == UiAccountEncoding::Base58
+                    {
+                        return Err(Error::invalid_params("base58 encoding not supported"));
+                    }
+
+                    if config_accounts.addresses.len() > MAX_MULTIPLE_ACCOUNTS {
+                        return Err(Error::invalid_params(format!(
+                            "Too many accounts provided; max {MAX_MULTIPLE_ACCOUNTS}"
+                        )));
+                    }
+
+                    let addresses = config_accounts
+                        .addresses
+                        .iter()
+                        .map(|address_str| verify_pubkey(address_str))
+                        .collect::<Result<Vec<_>>>()?;
+                    Ok((addresses, accounts_encoding))
+                })
+                .transpose()?;
+
+            let bank = &*meta.get_bank_with_config(RpcContextConfig {
+                commitment,
+                min_context_slot,
+            })?;
+            let mut blockhash: Option<RpcBlockhash> = None;
+            if replace_recent_blockhash {
+                if sig_verify {
+                    return Err(Error::invalid_params(
+                        "sigVerify may not be used with replaceRecentBlockhash",
+                    ));
+                }
+                let recent_blockhash = bank.last_blockhash();
+                let last_valid_block_height = bank
+                    .get_blockhash_last_valid_block_height(&recent_blockhash)
+                    .expect("bank blockhash queue should contain blockhash");
+                blockhash.replace(RpcBlockhash {
+                    blockhash: recent_blockhash.to_string(),
+                    last_valid_block_height,
+                });
+            }
+
+            let transactions = unsanitized_txs
+                .into_iter()
+                .map(|mut unsanitized_tx| {
+                    if replace_recent_blockhash {
+                        unsanitized_tx
+                            .message
+                            .set_recent_blockhash(bank.last_blockhash());
+                    }
+                    let transaction = sanitize_transaction(
+                        unsanitized_tx,
+                        bank,
+                        bank.get_reserved_account_keys(),
+                    )?;
+                    if sig_verify {
+                        verify_transaction(&transaction, &bank.feature_set)?;
+                    }
+                    Ok(transaction)
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            // Each transaction is simulated against the state left by the transactions
+            // before it. The accounts are tracked here so that the snapshot returned for
+            // each transaction reflects the bundle up to and including that transaction.
+            let mut bundle_accounts = HashMap::new();
+            let transaction_results = transactions
+                .iter()
+                .zip(bank.simulate_transactions(
+                    &transactions,
+                    enable_cpi_recording,
+                    record_state_diffs,
+                ))
+                .map(|(transaction, simulation)| {
+                    let TransactionSimulationResult {
+                        result,
+                        logs,
+                        post_simulation_accounts,
+                        units_consumed,
+                        return_data,
+                        inner_instructions,
+                        state_diff,
+                    } = simulation;
+
+                    if result.is_ok() {
+                        let message = transaction.message();
+                        bundle_accounts.extend(
+                            post_simulation_accounts
+                                .into_iter()
+                                .enumerate()
+                                .filter(|(index, _)| message.is_writable(*index))
+                                .map(|(_, account)| account),
+                        );
+                    } else {
+                        // Failed transactions still pay fees and advance nonces
+                        bundle_accounts.extend(post_simulation_accounts);
+                    }
+
+                    let accounts = if let Some((addresses, accounts_encoding)) = &accounts_config {
+                        if result.is_err() {
+                            Some(vec![None; addresses.len()])
+                        } else {
+                            Some(
+                                addresses
+                                    .iter()
+                                    .map(|pubkey| {
+                                        get_encoded_account(
+                                            bank,
+                                            pubkey,
+                                            *accounts_encoding,
+                                            None,
+                                            Some(&bundle_accounts),
+                                        )
+                                    })
+                                    .collect::<Result<Vec<_>>>()?,
+                            )
+                        }
+                    } else {
+                        None
+                    };
+
+                    let account_keys = transaction.message().account_keys();
+                    let inner_instructions = inner_instructions.map(|info| {
+                        map_inner_instructions(info)
+                            .map(|converted| parse_ui_inner_instructions(converted, &account_keys))
+                            .collect()
+                    });
+
+                    Ok(RpcSimulateTransactionResult {
+                        err: result.err(),
+                        logs: Some(logs),
+                        accounts,
+                        units_consumed: Some(units_consumed),
+                        return_data: return_data.map(|return_data| return_data.into()),
+                        inner_instructions,
+                        replacement_blockhash: blockhash.clone(),
+                        state_diffs: state_diff.map(encode_state_diff),
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            Ok(new_response(
+                bank,
+                RpcSimulateBundleResult {
+                    transaction_results,
+                },
+            ))
+        }
+
+        fn minimum_ledger_slot(&self, meta: Self::Metadata) -> Result<Slot> {
+            debug!("minimum_ledger_slot rpc request received");
+            meta.minimum_ledger_slot()
+        }
+
+        fn get_block(
+            &self,
+            meta: Self::Metadata,
+            slot: Slot,
+            config: Option<RpcEncodingConfigWrapper<RpcBlockConfig>>,
+        ) -> BoxFuture<Result<Option<UiConfirmedBlock>>> {
+            debug!("get_block rpc request received: {:?}", slot);
+            Box::pin(async move { meta.get_block(slot, config).await })
+        }
+
+        fn get_blocks(
+            &self,
+            meta: Self::Metadata,
+            start_slot: Slot,
             wrapper: Option<RpcBlocksConfigWrapper>,
             config: Option<RpcContextConfig>,
         ) -> BoxFuture<Result<Vec<Slot>>> {
@@ -4124,6 +4846,72 @@ pub mod rpc_full {
                 .collect::<Result<Vec<_>>>()?;
             meta.get_recent_prioritization_fees(pubkeys)
         }
+
+        fn get_priority_fee_estimate(
+            &self,
+            meta: Self::Metadata,
+            config: Option<RpcPriorityFeeEstimateConfig>,
+        ) -> Result<Option<RpcPriorityFeeEstimate>> {
+            debug!("get_priority_fee_estimate rpc request received");
+            let RpcPriorityFeeEstimateConfig {
+                transaction,
+                encoding,
+                account_keys,
+                commitment,
+                min_context_slot,
+            } = config.unwrap_or_default();
+
+            let account_key_strs = account_keys.unwrap_or_default();
+            if account_key_strs.len() > MAX_TX_ACCOUNT_LOCKS {
+                return Err(Error::invalid_params(format!(
+                    "Too many inputs provided; max {MAX_TX_ACCOUNT_LOCKS}"
+                )));
+            }
+            let mut account_keys = account_key_strs
+                .iter()
+                .map(|pubkey_str| verify_pubkey(pubkey_str))
+                .collect::<Result<Vec<_>>>()?;
+
+            if let Some(data) = transaction {
+                let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
+                let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
+                    Error::invalid_params(format!(
+                        "unsupported encoding: {tx_encoding}. Supported encodings: base58, base64"
+                    ))
+                })?;
+                let (_, unsanitized_tx) =
+                    decode_and_deserialize::<VersionedTransaction>(data, binary_encoding)?;
+                // Address lookup tables are resolved against the bank
+                let bank = &*meta.get_bank_with_config(RpcContextConfig {
+                    commitment,
+                    min_context_slot,
+                })?;
+                let transaction =
+                    sanitize_transaction(unsanitized_tx, bank, bank.get_reserved_account_keys())?;
+                let message = transaction.message();
+                account_keys.extend(
+                    message
+                        .account_keys()
+                        .iter()
+                        .enumerate()
+                        .filter(|(index, _)| message.is_writable(*index))
+                        .map(|(_, key)| *key),
+                );
+            }
+            account_keys.sort_unstable();
+            account_keys.dedup();
+
+            Ok(meta.get_priority_fee_estimate(&account_keys))
+        }
+
+        fn get_contended_accounts(
+            &self,
+            meta: Self::Metadata,
+            slot: Slot,
+        ) -> Result<Vec<RpcContendedAccount>> {
+            debug!("get_contended_accounts rpc request received: {:?}", slot);
+            meta.get_contended_accounts(slot)
+        }
     }
 }
 
@@ -4352,7 +5140,10 @@ pub mod tests {
         },
         solana_rpc_client_api::{
             custom_error::{
+                JSON_RPC_SERVER_ERROR_ACCOUNTS_CURSOR_EXPIRED,
                 JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE,
+                JSON_RPC_SERVER_ERROR_BUNDLES_NOT_ACCEPTED,
+                JSON_RPC_SERVER_ERROR_BUNDLE_QUEUE_FULL,
                 JSON_RPC_SERVER_ERROR_TRANSACTION_HISTORY_NOT_AVAILABLE,
                 JSON_RPC_SERVER_ERROR_UNSUPPORTED_TRANSACTION_VERSION,
             },
@@ -4531,6 +5322,7 @@ pub mod tests {
                 max_complete_transaction_status_slot.clone(),
                 max_complete_rewards_slot,
                 Arc::new(PrioritizationFeeCache::default()),
+                None,
             )
             .0;
 
@@ -4934,6 +5726,39 @@ pub mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_rpc_get_contended_accounts() {
+        let rpc = RpcHandler::start();
+
+        let slot = 0;
+        let request = create_test_request("getContendedAccounts", Some(json!([slot])));
+        let result: Value = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result, json!([]));
+
+        let pubkey = Pubkey::new_unique();
+        rpc.blockstore
+            .write_contended_accounts(
+                slot,
+                &[solana_ledger::blockstore_meta::ContendedAccount {
+                    pubkey,
+                    compute_units: 12_000_000,
+                    transaction_count: 40,
+                    rejected_transaction_count: 7,
+                }],
+            )
+            .expect("write to blockstore");
+
+        let request = create_test_request("getContendedAccounts", Some(json!([slot])));
+        let result: Value = parse_success_result(rpc.handle_request_sync(request));
+        let expected = json!([{
+            "pubkey": pubkey.to_string(),
+            "computeUnits": 12_000_000,
+            "transactionCount": 40,
+            "rejectedTransactionCount": 7,
+        }]);
+        assert_eq!(result, expected);
+    }
+
     #[test]
     fn test_rpc_get_recent_performance_samples_invalid_limit() {
         let rpc = RpcHandler::start();
@@ -5534,127 +6359,721 @@ pub mod tests {
         let request = create_test_request(
             "getProgramAccounts",
             Some(json!([
-                new_program_id.to_string(),
-                {"withContext": true},
+                new_program_id.to_string(),
+                {"withContext": true},
+            ])),
+        );
+        let result: RpcResponse<Vec<RpcKeyedAccount>> =
+            parse_success_result(rpc.handle_request_sync(request));
+        let expected = RpcResponse {
+            context: RpcResponseContext::new(0),
+            value: expected_value,
+        };
+        assert_eq!(result, expected);
+
+        // Set up nonce accounts to test filters
+        let nonce_authorities = (0..2)
+            .map(|_| {
+                let pubkey = Pubkey::new_unique();
+                let authority = Pubkey::new_unique();
+                let account = AccountSharedData::new_data(
+                    42,
+                    &nonce::state::Versions::new(nonce::State::new_initialized(
+                        &authority,
+                        DurableNonce::default(),
+                        1000,
+                    )),
+                    &system_program::id(),
+                )
+                .unwrap();
+                bank.store_account(&pubkey, &account);
+                authority
+            })
+            .collect::<Vec<_>>();
+
+        // Test memcmp filter; filter on Initialized state
+        let request = create_test_request(
+            "getProgramAccounts",
+            Some(json!([
+                system_program::id().to_string(),
+                {"filters": [{
+                    "memcmp": {
+                        "offset": 4,
+                        "bytes": bs58::encode(vec![1, 0, 0, 0]).into_string(),
+                    },
+                }]},
+            ])),
+        );
+        let result: Vec<RpcKeyedAccount> = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.len(), 2);
+
+        let request = create_test_request(
+            "getProgramAccounts",
+            Some(json!([
+                system_program::id().to_string(),
+                {"filters": [{
+                    "memcmp": {
+                        "offset": 4,
+                        "bytes": bs58::encode(vec![0, 0, 0, 0]).into_string(),
+                    },
+                }]},
+            ])),
+        );
+        let result: Vec<RpcKeyedAccount> = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.len(), 0);
+
+        // Test dataSize filter
+        let request = create_test_request(
+            "getProgramAccounts",
+            Some(json!([
+                system_program::id().to_string(),
+                {"filters": [{"dataSize": nonce::State::size()}]},
+            ])),
+        );
+        let result: Vec<RpcKeyedAccount> = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.len(), 2);
+
+        let request = create_test_request(
+            "getProgramAccounts",
+            Some(json!([
+                system_program::id().to_string(),
+                {"filters": [{"dataSize": 1}]},
+            ])),
+        );
+        let result: Vec<RpcKeyedAccount> = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.len(), 0);
+
+        // Test multiple filters
+        let request = create_test_request(
+            "getProgramAccounts",
+            Some(json!([
+                system_program::id().to_string(),
+                {"filters": [{
+                    "memcmp": {
+                        "offset": 4,
+                        "bytes": bs58::encode(vec![1, 0, 0, 0]).into_string(),
+                    },
+                }, {
+                    "memcmp": {
+                        "offset": 8,
+                        "bytes": nonce_authorities[0].to_string(),
+                    },
+                }]}, // Filter on Initialized and Nonce authority
+            ])),
+        );
+        let result: Vec<RpcKeyedAccount> = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.len(), 1);
+
+        let request = create_test_request(
+            "getProgramAccounts",
+            Some(json!([
+                system_program::id().to_string(),
+                {"filters": [{
+                    "memcmp": {
+                        "offset": 4,
+                        "bytes": bs58::encode(vec![1, 0, 0, 0]).into_string(),
+                    },
+                }, {
+                    "dataSize": 1,
+                }]}, // Filter on Initialized and non-matching data size
+            ])),
+        );
+        let result: Vec<RpcKeyedAccount> = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.len(), 0);
+
+        // Test dataRange filter; filter on lamports_per_signature of the
+        // nonce fee calculator
+        let request = create_test_request(
+            "getProgramAccounts",
+            Some(json!([
+                system_program::id().to_string(),
+                {"filters": [{
+                    "dataRange": {
+                        "offset": 72,
+                        "integerType": "u64",
+                        "comparison": {"gt": 999},
+                    },
+                }]},
+            ])),
+        );
+        let result: Vec<RpcKeyedAccount> = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.len(), 2);
+
+        let request = create_test_request(
+            "getProgramAccounts",
+            Some(json!([
+                system_program::id().to_string(),
+                {"filters": [{
+                    "dataRange": {
+                        "offset": 72,
+                        "integerType": "u64",
+                        "comparison": {"between": {"min": 0, "max": 999}},
+                    },
+                }]},
+            ])),
+        );
+        let result: Vec<RpcKeyedAccount> = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.len(), 0);
+
+        // Test notMemcmp filter; filter on Initialized and not Nonce authority
+        let request = create_test_request(
+            "getProgramAccounts",
+            Some(json!([
+                system_program::id().to_string(),
+                {"filters": [{
+                    "memcmp": {
+                        "offset": 4,
+                        "bytes": bs58::encode(vec![1, 0, 0, 0]).into_string(),
+                    },
+                }, {
+                    "notMemcmp": {
+                        "offset": 8,
+                        "bytes": nonce_authorities[0].to_string(),
+                    },
+                }]},
+            ])),
+        );
+        let result: Vec<RpcKeyedAccount> = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.len(), 1);
+
+        // Test lamports filter
+        let request = create_test_request(
+            "getProgramAccounts",
+            Some(json!([
+                system_program::id().to_string(),
+                {"filters": [{
+                    "lamports": {"between": {"min": 42, "max": 42}},
+                }, {
+                    "dataSize": nonce::State::size(),
+                }]},
+            ])),
+        );
+        let result: Vec<RpcKeyedAccount> = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.len(), 2);
+
+        let request = create_test_request(
+            "getProgramAccounts",
+            Some(json!([
+                system_program::id().to_string(),
+                {"filters": [{
+                    "lamports": {"lt": 42},
+                }, {
+                    "dataSize": nonce::State::size(),
+                }]},
+            ])),
+        );
+        let result: Vec<RpcKeyedAccount> = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.len(), 0);
+
+        // Test invalid range filter
+        let request = create_test_request(
+            "getProgramAccounts",
+            Some(json!([
+                system_program::id().to_string(),
+                {"filters": [{
+                    "dataRange": {
+                        "offset": 0,
+                        "integerType": "u8",
+                        "comparison": {"gt": 256},
+                    },
+                }]},
+            ])),
+        );
+        let (code, _) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+    }
+
+    #[test]
+    fn test_rpc_get_program_accounts_custom_index() {
+        let program_id = Pubkey::new_unique();
+        let spec = CustomIndexSpec {
+            owner: program_id,
+            offset: 8,
+        };
+        let account_indexes = AccountSecondaryIndexes {
+            keys: None,
+            indexes: HashSet::from([AccountIndex::Custom(spec)]),
+        };
+        let rpc = RpcHandler::start_with_config(JsonRpcConfig {
+            account_indexes: account_indexes.clone(),
+            ..JsonRpcConfig::default()
+        });
+        let bank = rpc.working_bank();
+
+        let market = Pubkey::new_unique();
+        let other_market = Pubkey::new_unique();
+        let mut expected_keys = vec![];
+        for (index_key, owner) in [
+            (market, program_id),
+            (market, program_id),
+            (other_market, program_id),
+            (market, Pubkey::new_unique()),
+        ] {
+            let mut data = vec![0; 40];
+            data[8..40].copy_from_slice(index_key.as_ref());
+            let pubkey = Pubkey::new_unique();
+            bank.store_account(
+                &pubkey,
+                &AccountSharedData::create(42, data, owner, false, 0),
+            );
+            if index_key == market && owner == program_id {
+                expected_keys.push(pubkey.to_string());
+            }
+        }
+        expected_keys.sort_by_key(|key| Pubkey::from_str(key).unwrap());
+
+        // A memcmp on the indexed offset is served by the custom index
+        let mut filters = vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            8,
+            market.as_ref(),
+        ))];
+        optimize_filters(&mut filters);
+        assert_eq!(
+            get_custom_index_filter(&program_id, &filters, &account_indexes),
+            Some((spec, market))
+        );
+        filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            9,
+            market.as_ref(),
+        )));
+        optimize_filters(&mut filters);
+        assert_eq!(
+            get_custom_index_filter(&Pubkey::new_unique(), &filters, &account_indexes),
+            None
+        );
+
+        let request = create_test_request(
+            "getProgramAccounts",
+            Some(json!([
+                program_id.to_string(),
+                {"filters": [{
+                    "memcmp": {
+                        "offset": 8,
+                        "bytes": market.to_string(),
+                    },
+                }]},
+            ])),
+        );
+        let result: Vec<RpcKeyedAccount> = parse_success_result(rpc.handle_request_sync(request));
+        let mut keys = result
+            .into_iter()
+            .map(|account| account.pubkey)
+            .collect::<Vec<_>>();
+        keys.sort_by_key(|key| Pubkey::from_str(key).unwrap());
+        assert_eq!(keys, expected_keys);
+    }
+
+    #[test]
+    fn test_rpc_get_program_accounts_paginated() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+
+        let program_id = Pubkey::new_unique();
+        let mut expected_keys = (0..5)
+            .map(|_| {
+                let pubkey = Pubkey::new_unique();
+                bank.store_account(&pubkey, &AccountSharedData::new(42, 0, &program_id));
+                pubkey.to_string()
+            })
+            .collect::<Vec<_>>();
+        expected_keys.sort_by_key(|key| Pubkey::from_str(key).unwrap());
+
+        // Page through all the accounts, two at a time
+        let mut keys = vec![];
+        let mut cursor = None;
+        let mut num_pages = 0;
+        loop {
+            let request = create_test_request(
+                "getProgramAccounts",
+                Some(json!([
+                    program_id.to_string(),
+                    {"limit": 2, "cursor": cursor, "withContext": true},
+                ])),
+            );
+            let result: RpcResponse<RpcKeyedAccountsPage> =
+                parse_success_result(rpc.handle_request_sync(request));
+            assert_eq!(result.context.slot, bank.slot());
+            num_pages += 1;
+            keys.extend(
+                result
+                    .value
+                    .accounts
+                    .into_iter()
+                    .map(|account| account.pubkey),
+            );
+            cursor = result.value.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_eq!(num_pages, 3);
+        assert_eq!(keys, expected_keys);
+
+        // Test a limit larger than the number of accounts
+        let request = create_test_request(
+            "getProgramAccounts",
+            Some(json!([program_id.to_string(), {"limit": 10}])),
+        );
+        let result: RpcKeyedAccountsPage = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.accounts.len(), expected_keys.len());
+        assert_eq!(result.next_cursor, None);
+
+        // Test invalid pagination params
+        let cursor = AccountsCursor {
+            slot: bank.slot(),
+            last: Pubkey::default(),
+        }
+        .encode();
+        for config in [
+            json!({"cursor": cursor}),
+            json!({"limit": 0}),
+            json!({"limit": MAX_GET_ACCOUNTS_PAGE_LIMIT + 1}),
+            json!({"limit": 2, "cursor": "not a cursor"}),
+        ] {
+            let request = create_test_request(
+                "getProgramAccounts",
+                Some(json!([program_id.to_string(), config])),
+            );
+            let (code, _) = parse_failure_response(rpc.handle_request_sync(request));
+            assert_eq!(code, ErrorCode::InvalidParams.code());
+        }
+
+        // Test a cursor whose slot is no longer in bank forks
+        let cursor = AccountsCursor {
+            slot: bank.slot() + 100,
+            last: Pubkey::default(),
+        }
+        .encode();
+        let request = create_test_request(
+            "getProgramAccounts",
+            Some(json!([
+                program_id.to_string(),
+                {"limit": 2, "cursor": cursor},
+            ])),
+        );
+        let (code, _) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, JSON_RPC_SERVER_ERROR_ACCOUNTS_CURSOR_EXPIRED);
+
+        // Test accounts changed after the first page don't affect the following pages
+        let request = create_test_request(
+            "getProgramAccounts",
+            Some(json!([program_id.to_string(), {"limit": 4}])),
+        );
+        let first_page: RpcKeyedAccountsPage =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(first_page.accounts.len(), 4);
+        let child_bank = rpc.advance_bank_to_confirmed_slot(bank.slot() + 1);
+        let changed_pubkey = Pubkey::from_str(&expected_keys[4]).unwrap();
+        child_bank.store_account(&changed_pubkey, &AccountSharedData::new(43, 0, &program_id));
+        child_bank.store_account(
+            &Pubkey::new_unique(),
+            &AccountSharedData::new(42, 0, &program_id),
+        );
+        let request = create_test_request(
+            "getProgramAccounts",
+            Some(json!([
+                program_id.to_string(),
+                {"limit": 4, "cursor": first_page.next_cursor, "withContext": true},
+            ])),
+        );
+        let second_page: RpcResponse<RpcKeyedAccountsPage> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(second_page.context.slot, bank.slot());
+        assert_eq!(second_page.value.accounts.len(), 1);
+        assert_eq!(second_page.value.accounts[0].pubkey, expected_keys[4]);
+        assert_eq!(second_page.value.accounts[0].account.lamports, 42);
+        assert_eq!(second_page.value.next_cursor, None);
+
+        // Test the cursor expires once its bank is pruned from bank forks
+        rpc.bank_forks
+            .write()
+            .unwrap()
+            .set_root(child_bank.slot(), &AbsRequestSender::default(), None)
+            .unwrap();
+        let request = create_test_request(
+            "getProgramAccounts",
+            Some(json!([
+                program_id.to_string(),
+                {"limit": 4, "cursor": first_page.next_cursor},
             ])),
         );
-        let result: RpcResponse<Vec<RpcKeyedAccount>> =
-            parse_success_result(rpc.handle_request_sync(request));
-        let expected = RpcResponse {
-            context: RpcResponseContext::new(0),
-            value: expected_value,
-        };
-        assert_eq!(result, expected);
+        let (code, _) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, JSON_RPC_SERVER_ERROR_ACCOUNTS_CURSOR_EXPIRED);
+    }
 
-        // Set up nonce accounts to test filters
-        let nonce_authorities = (0..2)
-            .map(|_| {
-                let pubkey = Pubkey::new_unique();
-                let authority = Pubkey::new_unique();
-                let account = AccountSharedData::new_data(
-                    42,
-                    &nonce::state::Versions::new(nonce::State::new_initialized(
-                        &authority,
-                        DurableNonce::default(),
-                        1000,
-                    )),
-                    &system_program::id(),
-                )
-                .unwrap();
-                bank.store_account(&pubkey, &account);
-                authority
-            })
-            .collect::<Vec<_>>();
+    #[test]
+    fn test_rpc_simulate_bundle() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let recent_blockhash = bank.confirmed_last_blockhash();
+
+        let bob = Keypair::new();
+        let carol_pubkey = solana_sdk::pubkey::new_rand();
+        let fund_bob = system_transaction::transfer(
+            &rpc.mint_keypair,
+            &bob.pubkey(),
+            2 * rent_exempt_amount,
+            recent_blockhash,
+        );
+        // Bob's account only exists once the first transaction has been executed
+        let fund_carol = Transaction::new_signed_with_payer(
+            &[system_instruction::transfer(
+                &bob.pubkey(),
+                &carol_pubkey,
+                rent_exempt_amount,
+            )],
+            Some(&rpc.mint_keypair.pubkey()),
+            &[&rpc.mint_keypair, &bob],
+            recent_blockhash,
+        );
+        let encode = |tx: &Transaction| BASE64_STANDARD.encode(serialize(tx).unwrap());
+
+        // Simulation bank must be frozen
+        bank.freeze();
 
-        // Test memcmp filter; filter on Initialized state
         let request = create_test_request(
-            "getProgramAccounts",
+            "simulateBundle",
             Some(json!([
-                system_program::id().to_string(),
-                {"filters": [{
-                    "memcmp": {
-                        "offset": 4,
-                        "bytes": bs58::encode(vec![1, 0, 0, 0]).into_string(),
+                [encode(&fund_bob), encode(&fund_carol)],
+                {
+                    "sigVerify": true,
+                    "encoding": "base64",
+                    "accounts": {
+                        "addresses": [bob.pubkey().to_string(), carol_pubkey.to_string()]
                     },
-                }]},
+                    "stateDiffs": true
+                }
             ])),
         );
-        let result: Vec<RpcKeyedAccount> = parse_success_result(rpc.handle_request_sync(request));
-        assert_eq!(result.len(), 2);
+        let result: RpcResponse<RpcSimulateBundleResult> =
+            parse_success_result(rpc.handle_request_sync(request));
+        let results = result.value.transaction_results;
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|result| result.err.is_none()));
+        let lamports = |result: &RpcSimulateTransactionResult| {
+            result
+                .accounts
+                .as_ref()
+                .unwrap()
+                .iter()
+                .map(|account| account.as_ref().map(|account| account.lamports))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            lamports(&results[0]),
+            vec![Some(2 * rent_exempt_amount), None]
+        );
+        assert_eq!(
+            lamports(&results[1]),
+            vec![Some(rent_exempt_amount), Some(rent_exempt_amount)]
+        );
+        let carol_diff = results[1]
+            .state_diffs
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|account_diff| account_diff.pubkey == carol_pubkey.to_string())
+            .unwrap();
+        assert_eq!(carol_diff.pre_lamports, 0);
+        assert_eq!(carol_diff.post_lamports, rent_exempt_amount);
+        assert_eq!(carol_diff.post_owner, system_program::id().to_string());
 
+        // Nothing was committed
+        assert_eq!(bank.get_balance(&bob.pubkey()), 0);
+        assert_eq!(bank.get_balance(&carol_pubkey), 0);
+
+        // Out of order, the first transaction fails
         let request = create_test_request(
-            "getProgramAccounts",
+            "simulateBundle",
             Some(json!([
-                system_program::id().to_string(),
-                {"filters": [{
-                    "memcmp": {
-                        "offset": 4,
-                        "bytes": bs58::encode(vec![0, 0, 0, 0]).into_string(),
-                    },
-                }]},
+                [encode(&fund_carol), encode(&fund_bob)],
+                {"encoding": "base64"}
             ])),
         );
-        let result: Vec<RpcKeyedAccount> = parse_success_result(rpc.handle_request_sync(request));
-        assert_eq!(result.len(), 0);
+        let result: RpcResponse<RpcSimulateBundleResult> =
+            parse_success_result(rpc.handle_request_sync(request));
+        let results = result.value.transaction_results;
+        assert!(results[0].err.is_some());
+        assert_eq!(results[1].err, None);
+        assert_eq!(results[1].state_diffs, None);
 
-        // Test dataSize filter
+        let request = create_test_request("simulateBundle", Some(json!([[]])));
+        let (code, message) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+        assert_eq!(message, "bundle must contain a transaction");
+    }
+
+    #[test]
+    fn test_rpc_send_bundle() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let recent_blockhash = bank.confirmed_last_blockhash();
+        let tx = system_transaction::transfer(
+            &rpc.mint_keypair,
+            &solana_sdk::pubkey::new_rand(),
+            1,
+            recent_blockhash,
+        );
         let request = create_test_request(
-            "getProgramAccounts",
+            "sendBundle",
             Some(json!([
-                system_program::id().to_string(),
-                {"filters": [{"dataSize": nonce::State::size()}]},
+                [BASE64_STANDARD.encode(serialize(&tx).unwrap())],
+                {"encoding": "base64"}
             ])),
         );
-        let result: Vec<RpcKeyedAccount> = parse_success_result(rpc.handle_request_sync(request));
-        assert_eq!(result.len(), 2);
 
+        let (code, _) = parse_failure_response(rpc.handle_request_sync(request.clone()));
+        assert_eq!(code, JSON_RPC_SERVER_ERROR_BUNDLES_NOT_ACCEPTED);
+
+        let (bundle_sender, bundle_receiver) = crossbeam_channel::bounded(1);
+        let meta = JsonRpcRequestProcessor {
+            bundle_sender: Some(bundle_sender),
+            ..rpc.meta.clone()
+        };
+        let handle_request = |request: &serde_json::Value| {
+            let response = rpc
+                .io
+                .handle_request_sync(&request.to_string(), meta.clone())
+                .expect("no response");
+            serde_json::from_str::<Response>(&response).unwrap()
+        };
+
+        let signatures: Vec<String> = parse_success_result(handle_request(&request));
+        assert_eq!(signatures, vec![tx.signatures[0].to_string()]);
+        let bundle = bundle_receiver.try_recv().unwrap();
+        assert_eq!(bundle.len(), 1);
+        assert_eq!(
+            bundle[0]
+                .deserialize_slice::<VersionedTransaction, _>(..)
+                .unwrap(),
+            VersionedTransaction::from(tx),
+        );
+
+        // The bundle is rejected rather than queued when banking stage falls behind
+        let _: Vec<String> = parse_success_result(handle_request(&request));
+        let (code, _) = parse_failure_response(handle_request(&request));
+        assert_eq!(code, JSON_RPC_SERVER_ERROR_BUNDLE_QUEUE_FULL);
+
+        let request = create_test_request("sendBundle", Some(json!([[]])));
+        let (code, message) = parse_failure_response(handle_request(&request));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+        assert_eq!(message, "bundle must contain a transaction");
+
+        // A transaction can't be repeated within a bundle
+        let tx = system_transaction::transfer(
+            &rpc.mint_keypair,
+            &solana_sdk::pubkey::new_rand(),
+            1,
+            recent_blockhash,
+        );
+        let tx_encoded = BASE64_STANDARD.encode(serialize(&tx).unwrap());
         let request = create_test_request(
-            "getProgramAccounts",
-            Some(json!([
-                system_program::id().to_string(),
-                {"filters": [{"dataSize": 1}]},
-            ])),
+            "sendBundle",
+            Some(json!([[tx_encoded, tx_encoded], {"encoding": "base64"}])),
         );
-        let result: Vec<RpcKeyedAccount> = parse_success_result(rpc.handle_request_sync(request));
-        assert_eq!(result.len(), 0);
+        let (code, message) = parse_failure_response(handle_request(&request));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+        assert_eq!(
+            message,
+            format!("transaction {} is repeated in the bundle", tx.signatures[0])
+        );
+    }
+
+    #[test]
+    fn test_rpc_simulate_transaction_with_overrides() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let recent_blockhash = bank.confirmed_last_blockhash();
+
+        let alice = Keypair::new();
+        let bob_pubkey = solana_sdk::pubkey::new_rand();
+        let tx =
+            system_transaction::transfer(&alice, &bob_pubkey, rent_exempt_amount, recent_blockhash);
+        let tx_serialized_encoded = BASE64_STANDARD.encode(serialize(&tx).unwrap());
+
+        // Simulation bank must be frozen
+        bank.freeze();
 
-        // Test multiple filters
         let request = create_test_request(
-            "getProgramAccounts",
+            "simulateTransaction",
+            Some(json!([tx_serialized_encoded, {"encoding": "base64"}])),
+        );
+        let result: RpcResponse<RpcSimulateTransactionResult> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.value.err, Some(TransactionError::AccountNotFound));
+
+        let request = create_test_request(
+            "simulateTransaction",
             Some(json!([
-                system_program::id().to_string(),
-                {"filters": [{
-                    "memcmp": {
-                        "offset": 4,
-                        "bytes": bs58::encode(vec![1, 0, 0, 0]).into_string(),
+                tx_serialized_encoded,
+                {
+                    "encoding": "base64",
+                    "accounts": {
+                        "addresses": [alice.pubkey().to_string(), bob_pubkey.to_string()]
                     },
-                }, {
-                    "memcmp": {
-                        "offset": 8,
-                        "bytes": nonce_authorities[0].to_string(),
+                    "accountOverrides": {
+                        alice.pubkey().to_string(): {"lamports": 10 * rent_exempt_amount}
                     },
-                }]}, // Filter on Initialized and Nonce authority
+                    "clockOverride": {"unixTimestamp": 42}
+                }
             ])),
         );
-        let result: Vec<RpcKeyedAccount> = parse_success_result(rpc.handle_request_sync(request));
-        assert_eq!(result.len(), 1);
+        let result: RpcResponse<RpcSimulateTransactionResult> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.value.err, None);
+        let accounts = result.value.accounts.unwrap();
+        assert!(accounts[0].as_ref().unwrap().lamports < 9 * rent_exempt_amount);
+        assert_eq!(accounts[1].as_ref().unwrap().lamports, rent_exempt_amount);
+
+        // Nothing was committed
+        assert_eq!(bank.get_balance(&alice.pubkey()), 0);
+        assert_eq!(bank.get_balance(&bob_pubkey), 0);
 
         let request = create_test_request(
-            "getProgramAccounts",
+            "simulateTransaction",
             Some(json!([
-                system_program::id().to_string(),
-                {"filters": [{
-                    "memcmp": {
-                        "offset": 4,
-                        "bytes": bs58::encode(vec![1, 0, 0, 0]).into_string(),
-                    },
-                }, {
-                    "dataSize": 1,
-                }]}, // Filter on Initialized and non-matching data size
+                tx_serialized_encoded,
+                {
+                    "encoding": "base64",
+                    "accountOverrides": {
+                        alice.pubkey().to_string(): {"data": ["!", "base64"]}
+                    }
+                }
             ])),
         );
-        let result: Vec<RpcKeyedAccount> = parse_success_result(rpc.handle_request_sync(request));
-        assert_eq!(result.len(), 0);
+        let (code, message) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+        assert_eq!(
+            message,
+            format!("Invalid data override for account {}", alice.pubkey())
+        );
+
+        for sysvar_id in [
+            sysvar::rent::id(),
+            sysvar::slot_hashes::id(),
+            sysvar::clock::id(),
+        ] {
+            let request = create_test_request(
+                "simulateTransaction",
+                Some(json!([
+                    tx_serialized_encoded,
+                    {
+                        "encoding": "base64",
+                        "accountOverrides": {
+                            sysvar_id.to_string(): {"lamports": 1}
+                        }
+                    }
+                ])),
+            );
+            let (code, message) = parse_failure_response(rpc.handle_request_sync(request));
+            assert_eq!(code, ErrorCode::InvalidParams.code());
+            assert_eq!(
+                message,
+                format!("Sysvar account {sysvar_id} can't be overridden")
+            );
+        }
     }
 
     #[test]
@@ -5727,6 +7146,7 @@ pub mod tests {
                         "Program 11111111111111111111111111111111 success"
                     ],
                     "replacementBlockhash": null,
+                    "stateDiffs": null,
                     "returnData":null,
                     "unitsConsumed":150,
                 }
@@ -5813,6 +7233,7 @@ pub mod tests {
                         "Program 11111111111111111111111111111111 success"
                     ],
                     "replacementBlockhash": null,
+                    "stateDiffs": null,
                     "returnData":null,
                     "unitsConsumed":150,
                 }
@@ -5843,6 +7264,7 @@ pub mod tests {
                         "Program 11111111111111111111111111111111 success"
                     ],
                     "replacementBlockhash": null,
+                    "stateDiffs": null,
                     "returnData": null,
                     "unitsConsumed":150,
                 }
@@ -5894,6 +7316,7 @@ pub mod tests {
                     "innerInstructions":null,
                     "logs":[],
                     "replacementBlockhash": null,
+                    "stateDiffs": null,
                     "returnData": null,
                     "unitsConsumed":0,
                 }
@@ -5934,6 +7357,7 @@ pub mod tests {
                         "lastValidBlockHeight": expiry_slot
                     },
                     "returnData":null,
+                    "stateDiffs":null,
                     "unitsConsumed":150,
                 }
             },
@@ -6078,6 +7502,7 @@ pub mod tests {
                         "Program 11111111111111111111111111111111 success"
                     ],
                     "replacementBlockhash": null,
+                    "stateDiffs": null,
                     "returnData": null,
                     "unitsConsumed": 150,
                 }
@@ -6159,6 +7584,7 @@ pub mod tests {
                         "Program AddressLookupTab1e1111111111111111111111111 success"
                     ],
                     "replacementBlockhash": null,
+                    "stateDiffs": null,
                     "returnData":null,
                     "unitsConsumed":1200,
                 }
@@ -6203,6 +7629,7 @@ pub mod tests {
                         "Program AddressLookupTab1e1111111111111111111111111 success"
                     ],
                     "replacementBlockhash": null,
+                    "stateDiffs": null,
                     "returnData":null,
                     "unitsConsumed":1200,
                 }
@@ -6290,6 +7717,7 @@ pub mod tests {
                         "Program AddressLookupTab1e1111111111111111111111111 success"
                     ],
                     "replacementBlockhash": null,
+                    "stateDiffs": null,
                     "returnData":null,
                     "unitsConsumed":1200,
                 }
@@ -6497,6 +7925,7 @@ pub mod tests {
             Arc::new(AtomicU64::default()),
             Arc::new(AtomicU64::default()),
             Arc::new(PrioritizationFeeCache::default()),
+            None,
         );
         let client = ConnectionCacheClient::<NullTpuInfo>::new(
             connection_cache.clone(),
@@ -6523,7 +7952,7 @@ pub mod tests {
         assert_eq!(
             res,
             Some(
-                r#"{"jsonrpc":"2.0","error":{"code":-32002,"message":"Transaction simulation failed: Blockhash not found","data":{"accounts":null,"err":"BlockhashNotFound","innerInstructions":null,"logs":[],"replacementBlockhash":null,"returnData":null,"unitsConsumed":0}},"id":1}"#.to_string(),
+                r#"{"jsonrpc":"2.0","error":{"code":-32002,"message":"Transaction simulation failed: Blockhash not found","data":{"accounts":null,"err":"BlockhashNotFound","innerInstructions":null,"logs":[],"replacementBlockhash":null,"returnData":null,"stateDiffs":null,"unitsConsumed":0}},"id":1}"#.to_string(),
             )
         );
 
@@ -6769,6 +8198,7 @@ pub mod tests {
             Arc::new(AtomicU64::default()),
             Arc::new(AtomicU64::default()),
             Arc::new(PrioritizationFeeCache::default()),
+            None,
         );
         let client = ConnectionCacheClient::<NullTpuInfo>::new(
             connection_cache.clone(),
@@ -7705,6 +9135,47 @@ pub mod tests {
                 serde_json::from_value(result["result"]["value"].clone()).unwrap();
             assert_eq!(accounts.len(), 3);
 
+            // Test getTokenAccountsByOwner pages through the same accounts
+            let req = format!(
+                r#"{{
+                    "jsonrpc":"2.0",
+                    "id":1,
+                    "method":"getTokenAccountsByOwner",
+                    "params":["{owner}", {{"programId": "{program_id}"}}, {{"encoding":"base64", "limit": 2}}]
+                }}"#,
+            );
+            let res = io.handle_request_sync(&req, meta.clone());
+            let result: Value = serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization");
+            let first_page: RpcKeyedAccountsPage =
+                serde_json::from_value(result["result"]["value"].clone()).unwrap();
+            assert_eq!(first_page.accounts.len(), 2);
+            let cursor = first_page.next_cursor.unwrap();
+            let req = format!(
+                r#"{{
+                    "jsonrpc":"2.0",
+                    "id":1,
+                    "method":"getTokenAccountsByOwner",
+                    "params":["{owner}", {{"programId": "{program_id}"}}, {{"encoding":"base64", "limit": 2, "cursor": "{cursor}"}}]
+                }}"#,
+            );
+            let res = io.handle_request_sync(&req, meta.clone());
+            let result: Value = serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization");
+            let second_page: RpcKeyedAccountsPage =
+                serde_json::from_value(result["result"]["value"].clone()).unwrap();
+            assert_eq!(second_page.accounts.len(), 1);
+            assert_eq!(second_page.next_cursor, None);
+            let mut paged_accounts = first_page.accounts;
+            paged_accounts.extend(second_page.accounts);
+            let mut sorted_accounts = accounts.clone();
+            sorted_accounts.sort_by(|a, b| {
+                Pubkey::from_str(&a.pubkey)
+                    .unwrap()
+                    .cmp(&Pubkey::from_str(&b.pubkey).unwrap())
+            });
+            assert_eq!(paged_accounts, sorted_accounts);
+
             // Test getTokenAccountsByOwner with jsonParsed encoding doesn't return accounts with invalid mints
             let req = format!(
                 r#"{{
@@ -8418,6 +9889,7 @@ pub mod tests {
             max_complete_transaction_status_slot,
             max_complete_rewards_slot,
             Arc::new(PrioritizationFeeCache::default()),
+            None,
         );
 
         let mut io = MetaIoHandler::default();
@@ -8734,6 +10206,80 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_rpc_get_priority_fee_estimate() {
+        let rpc = RpcHandler::start();
+        let request = create_test_request("getPriorityFeeEstimate", None);
+        let result: Value = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result, Value::Null);
+
+        let slot0 = rpc.working_bank().slot();
+        let bank0_id = rpc.working_bank().bank_id();
+        let account0 = Pubkey::new_unique();
+        let account1 = Pubkey::new_unique();
+        let price = 42;
+        let transaction = Transaction::new_unsigned(Message::new(
+            &[
+                system_instruction::transfer(&account0, &account1, 1),
+                ComputeBudgetInstruction::set_compute_unit_price(price),
+            ],
+            Some(&account0),
+        ));
+        rpc.update_prioritization_fee_cache(vec![
+            transaction.clone(),
+            Transaction::new_unsigned(Message::new(
+                &[system_instruction::transfer(
+                    &Pubkey::new_unique(),
+                    &Pubkey::new_unique(),
+                    1,
+                )],
+                None,
+            )),
+        ]);
+        let cache = rpc.get_prioritization_fee_cache();
+        cache.finalize_priority_fee(slot0, bank0_id);
+        while cache.available_block_count() < 1 {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+
+        let request = create_test_request("getPriorityFeeEstimate", None);
+        let result: RpcPriorityFeeEstimate = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(
+            result,
+            RpcPriorityFeeEstimate {
+                p25: 0,
+                p50: 0,
+                p75: 0,
+                p95: 0,
+            }
+        );
+
+        let request = create_test_request(
+            "getPriorityFeeEstimate",
+            Some(json!([{ "accountKeys": [account1.to_string()] }])),
+        );
+        let result: RpcPriorityFeeEstimate = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(
+            result,
+            RpcPriorityFeeEstimate {
+                p25: price,
+                p50: price,
+                p75: price,
+                p95: price,
+            }
+        );
+
+        let request = create_test_request(
+            "getPriorityFeeEstimate",
+            Some(json!([{
+                "transaction": BASE64_STANDARD.encode(serialize(&transaction).unwrap()),
+                "encoding": "base64",
+            }])),
+        );
+        let result: RpcPriorityFeeEstimate = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.p50, price);
+    }
+
     #[test]
     fn test_rpc_get_recent_prioritization_fees() {
         fn wait_for_cache_blocks(cache: &PrioritizationFeeCache, num_blocks: usize) {
@@ -23,7 +23,10 @@ pub trait SlotStatusNotifierInterface {
     fn notify_created_bank(&self, slot: Slot, parent: Slot);
 
     /// Notified when the slot is marked "Dead"
-    fn notify_slot_dead(&self, slot: Slot, error: String);
+    fn notify_slot_dead(&self, slot: Slot, parent: Option<Slot>, ancestors: &[Slot], error: String);
+
+    /// Notified when a slot not descending from the new root is pruned from bank forks.
+    fn notify_slot_pruned(&self, slot: Slot, parent: Option<Slot>, ancestors: &[Slot]);
 }
 
 pub type SlotStatusNotifier = Arc<RwLock<dyn SlotStatusNotifierInterface + Sync + Send>>;
@@ -134,7 +134,7 @@ use {
         incinerator,
         inflation::Inflation,
         inner_instruction::InnerInstructions,
-        message::{AccountKeys, SanitizedMessage},
+        message::SanitizedMessage,
         native_loader,
         native_token::LAMPORTS_PER_SOL,
         packet::PACKET_DATA_SIZE,
@@ -164,6 +164,7 @@ use {
     solana_svm::{
         account_loader::{collect_rent_from_account, LoadedTransaction},
         account_overrides::AccountOverrides,
+        rollback_accounts::RollbackAccounts,
         transaction_commit_result::{CommittedTransaction, TransactionCommitResult},
         transaction_error_metrics::TransactionErrorMetrics,
         transaction_execution_result::{
@@ -178,6 +179,7 @@ use {
             ExecutionRecordingConfig, TransactionBatchProcessor, TransactionLogMessages,
             TransactionProcessingConfig, TransactionProcessingEnvironment,
         },
+        transaction_state_diff::TransactionStateDiff,
     },
     solana_svm_transaction::svm_message::SVMMessage,
     solana_timings::{ExecuteTimingType, ExecuteTimings},
@@ -338,6 +340,9 @@ pub struct LoadAndExecuteTransactionsOutput {
     // Processed transaction counts used to update bank transaction counts and
     // for metrics reporting.
     pub processed_counts: ProcessedTransactionCounts,
+    // The accounts changed by each processed transaction, only recorded when
+    // `record_state_diffs` is set in the processing config.
+    pub state_diffs: Vec<TransactionStateDiff>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -348,6 +353,8 @@ pub struct TransactionSimulationResult {
     pub units_consumed: u64,
     pub return_data: Option<TransactionReturnData>,
     pub inner_instructions: Option<Vec<InnerInstructions>>,
+    /// The accounts changed by the transaction, only set when state diffs were requested
+    pub state_diff: Option<TransactionStateDiff>,
 }
 
 #[derive(Clone)]
@@ -1693,6 +1700,25 @@ impl Bank {
         collector_id: &Pubkey,
         slot: Slot,
         data_source: CalcAccountsHashDataSource,
+    ) -> Self {
+        let parent_timestamp = parent.clock().unix_timestamp;
+        Self::warp_from_parent_with_unix_timestamp(
+            parent,
+            collector_id,
+            slot,
+            data_source,
+            parent_timestamp,
+        )
+    }
+
+    /// Like `warp_from_parent` but sets the new bank's `Clock` unix timestamp, and the epoch start
+    /// timestamp, to `unix_timestamp` instead of the parent's timestamp
+    pub fn warp_from_parent_with_unix_timestamp(
+        parent: Arc<Bank>,
+        collector_id: &Pubkey,
+        slot: Slot,
+        data_source: CalcAccountsHashDataSource,
+        unix_timestamp: UnixTimestamp,
     ) -> Self {
         parent.freeze();
         parent
@@ -1710,15 +1736,14 @@ impl Bank {
             .epoch_accounts_hash_manager
             .set_valid(epoch_accounts_hash, parent.slot());
 
-        let parent_timestamp = parent.clock().unix_timestamp;
         let mut new = Bank::new_from_parent(parent, collector_id, slot);
         new.apply_feature_activations(ApplyFeatureActivationsCaller::WarpFromParent, false);
         new.update_epoch_stakes(new.epoch_schedule().get_epoch(slot));
         new.tick_height.store(new.max_tick_height(), Relaxed);
 
         let mut clock = new.clock();
-        clock.epoch_start_timestamp = parent_timestamp;
-        clock.unix_timestamp = parent_timestamp;
+        clock.epoch_start_timestamp = unix_timestamp;
+        clock.unix_timestamp = unix_timestamp;
         new.update_sysvar_account(&sysvar::clock::id(), |account| {
             create_account(
                 &clock,
@@ -3689,6 +3714,26 @@ impl Bank {
         TransactionBatch::new(lock_results, self, OwnedOrBorrowed::Borrowed(transactions))
     }
 
+    /// Prepare a locked transaction batch for a bundle, which must be executed and committed
+    /// atomically. The accounts are only locked if `bundle_result`, the result of any checks
+    /// done on the bundle beforehand, is `Ok`. The transactions of the bundle may conflict with
+    /// each other, as they are executed sequentially, but not with any other locked transaction.
+    /// Either all of the transactions are locked, or none of them are, in which case every
+    /// transaction carries the same error.
+    pub fn prepare_locked_bundle_batch<'a, 'b, Tx: SVMMessage>(
+        &'a self,
+        transactions: &'b [Tx],
+        bundle_result: Result<()>,
+    ) -> TransactionBatch<'a, 'b, Tx> {
+        let tx_account_lock_limit = self.get_transaction_account_lock_limit();
+        let lock_result = bundle_result.and_then(|()| {
+            self.rc
+                .accounts
+                .lock_bundle_accounts(transactions.iter(), tx_account_lock_limit)
+        });
+        TransactionBatch::new_bundle(lock_result, self, OwnedOrBorrowed::Borrowed(transactions))
+    }
+
     /// Prepare a transaction batch from a single transaction without locking accounts
     pub fn prepare_unlocked_batch_from_single_tx<'a, Tx: SVMMessage>(
         &'a self,
@@ -3705,6 +3750,22 @@ impl Bank {
         batch
     }
 
+    /// Prepare a transaction batch without locking accounts
+    pub fn prepare_unlocked_batch_from_txs<'a, Tx: SVMMessage>(
+        &'a self,
+        transactions: &'a [Tx],
+    ) -> TransactionBatch<'a, 'a, Tx> {
+        let tx_account_lock_limit = self.get_transaction_account_lock_limit();
+        let lock_results = transactions
+            .iter()
+            .map(|tx| validate_account_locks(tx.account_keys(), tx_account_lock_limit))
+            .collect();
+        let mut batch =
+            TransactionBatch::new(lock_results, self, OwnedOrBorrowed::Borrowed(transactions));
+        batch.set_needs_unlock(false);
+        batch
+    }
+
     /// Run transactions against a frozen bank without committing the results
     pub fn simulate_transaction(
         &self,
@@ -3722,15 +3783,56 @@ impl Bank {
         &self,
         transaction: &impl TransactionWithMeta,
         enable_cpi_recording: bool,
+    ) -> TransactionSimulationResult {
+        self.simulate_transaction_unchecked_with_overrides(
+            transaction,
+            enable_cpi_recording,
+            false,
+            AccountOverrides::default(),
+        )
+    }
+
+    /// Run transactions against a frozen bank without committing the results, replacing the
+    /// stored state of the overridden accounts
+    pub fn simulate_transaction_with_overrides(
+        &self,
+        transaction: &impl TransactionWithMeta,
+        enable_cpi_recording: bool,
+        record_state_diffs: bool,
+        account_overrides: AccountOverrides,
+    ) -> TransactionSimulationResult {
+        assert!(self.is_frozen(), "simulation bank must be frozen");
+
+        self.simulate_transaction_unchecked_with_overrides(
+            transaction,
+            enable_cpi_recording,
+            record_state_diffs,
+            account_overrides,
+        )
+    }
+
+    /// Run transactions against a bank without committing the results, replacing the stored
+    /// state of the overridden accounts; does not check if the bank is frozen
+    pub fn simulate_transaction_unchecked_with_overrides(
+        &self,
+        transaction: &impl TransactionWithMeta,
+        enable_cpi_recording: bool,
+        record_state_diffs: bool,
+        account_overrides: AccountOverrides,
     ) -> TransactionSimulationResult {
         let account_keys = transaction.account_keys();
         let number_of_accounts = account_keys.len();
-        let account_overrides = self.get_account_overrides_for_simulation(&account_keys);
+        let slot_history_id = sysvar::slot_history::id();
+        let account_overrides = self.add_account_overrides_for_simulation(
+            account_overrides,
+            account_keys.iter().any(|pubkey| *pubkey == slot_history_id),
+        );
         let batch = self.prepare_unlocked_batch_from_single_tx(transaction);
         let mut timings = ExecuteTimings::default();
 
         let LoadAndExecuteTransactionsOutput {
             mut processing_results,
+            mut state_diffs,
             ..
         } = self.load_and_execute_transactions(
             &batch,
@@ -3752,6 +3854,8 @@ impl Bank {
                     enable_return_data_recording: true,
                 },
                 transaction_account_lock_limit: Some(self.get_transaction_account_lock_limit()),
+                record_state_diffs,
+                record_instruction_traces: false,
             },
         );
 
@@ -3804,13 +3908,160 @@ impl Bank {
             units_consumed,
             return_data,
             inner_instructions,
+            state_diff: state_diffs.pop(),
         }
     }
 
-    fn get_account_overrides_for_simulation(&self, account_keys: &AccountKeys) -> AccountOverrides {
-        let mut account_overrides = AccountOverrides::default();
+    /// Run a bundle of transactions against a frozen bank without committing the results. The
+    /// transactions are executed in order, each one seeing the accounts written by the previous
+    /// ones.
+    pub fn simulate_transactions(
+        &self,
+        transactions: &[impl TransactionWithMeta],
+        enable_cpi_recording: bool,
+        record_state_diffs: bool,
+    ) -> Vec<TransactionSimulationResult> {
+        assert!(self.is_frozen(), "simulation bank must be frozen");
+
+        self.simulate_transactions_unchecked(transactions, enable_cpi_recording, record_state_diffs)
+    }
+
+    /// Run a bundle of transactions against a bank without committing the results; does not
+    /// check if the bank is frozen.
+    ///
+    /// The `post_simulation_accounts` of a transaction which failed are the accounts it
+    /// would have committed, its fee payer and nonce accounts.
+    pub fn simulate_transactions_unchecked(
+        &self,
+        transactions: &[impl TransactionWithMeta],
+        enable_cpi_recording: bool,
+        record_state_diffs: bool,
+    ) -> Vec<TransactionSimulationResult> {
         let slot_history_id = sysvar::slot_history::id();
-        if account_keys.iter().any(|pubkey| *pubkey == slot_history_id) {
+        let account_overrides = self.add_account_overrides_for_simulation(
+            AccountOverrides::default(),
+            transactions.iter().any(|tx| {
+                tx.account_keys()
+                    .iter()
+                    .any(|pubkey| *pubkey == slot_history_id)
+            }),
+        );
+        let batch = self.prepare_unlocked_batch_from_txs(transactions);
+        let mut timings = ExecuteTimings::default();
+
+        let LoadAndExecuteTransactionsOutput {
+            processing_results,
+            state_diffs,
+            ..
+        } = self.load_and_execute_transactions(
+            &batch,
+            MAX_PROCESSING_AGE - MAX_TRANSACTION_FORWARDING_DELAY,
+            &mut timings,
+            &mut TransactionErrorMetrics::default(),
+            TransactionProcessingConfig {
+                account_overrides: Some(&account_overrides),
+                check_program_modification_slot: self.check_program_modification_slot,
+                compute_budget: self.compute_budget(),
+                log_messages_bytes_limit: None,
+                limit_to_load_programs: true,
+                recording_config: ExecutionRecordingConfig {
+                    enable_cpi_recording,
+                    enable_log_recording: true,
+                    enable_return_data_recording: true,
+                },
+                transaction_account_lock_limit: Some(self.get_transaction_account_lock_limit()),
+                record_state_diffs,
+                record_instruction_traces: false,
+            },
+        );
+
+        debug!("simulate_transactions: {:?}", timings);
+
+        let mut state_diffs = state_diffs.into_iter();
+        transactions
+            .iter()
+            .zip(processing_results)
+            .map(|(transaction, processing_result)| match processing_result {
+                Ok(ProcessedTransaction::Executed(executed_tx)) => {
+                    let details = executed_tx.execution_details;
+                    let post_simulation_accounts = if details.status.is_ok() {
+                        executed_tx
+                            .loaded_transaction
+                            .accounts
+                            .into_iter()
+                            .take(transaction.account_keys().len())
+                            .collect()
+                    } else {
+                        Self::rollback_transaction_accounts(
+                            transaction.fee_payer(),
+                            executed_tx.loaded_transaction.rollback_accounts,
+                        )
+                    };
+                    TransactionSimulationResult {
+                        result: details.status,
+                        logs: details.log_messages.unwrap_or_default(),
+                        post_simulation_accounts,
+                        units_consumed: details.executed_units,
+                        return_data: details.return_data,
+                        inner_instructions: details.inner_instructions,
+                        state_diff: state_diffs.next(),
+                    }
+                }
+                Ok(ProcessedTransaction::FeesOnly(fees_only_tx)) => TransactionSimulationResult {
+                    result: Err(fees_only_tx.load_error),
+                    logs: vec![],
+                    post_simulation_accounts: Self::rollback_transaction_accounts(
+                        transaction.fee_payer(),
+                        fees_only_tx.rollback_accounts,
+                    ),
+                    units_consumed: 0,
+                    return_data: None,
+                    inner_instructions: None,
+                    state_diff: state_diffs.next(),
+                },
+                Err(error) => TransactionSimulationResult {
+                    result: Err(error),
+                    logs: vec![],
+                    post_simulation_accounts: vec![],
+                    units_consumed: 0,
+                    return_data: None,
+                    inner_instructions: None,
+                    state_diff: state_diffs.next(),
+                },
+            })
+            .collect()
+    }
+
+    fn rollback_transaction_accounts(
+        fee_payer: &Pubkey,
+        rollback_accounts: RollbackAccounts,
+    ) -> Vec<TransactionAccount> {
+        match rollback_accounts {
+            RollbackAccounts::FeePayerOnly { fee_payer_account } => {
+                vec![(*fee_payer, fee_payer_account)]
+            }
+            RollbackAccounts::SameNonceAndFeePayer { nonce } => {
+                vec![(*nonce.address(), nonce.account().clone())]
+            }
+            RollbackAccounts::SeparateNonceAndFeePayer {
+                nonce,
+                fee_payer_account,
+            } => vec![
+                (*fee_payer, fee_payer_account),
+                (*nonce.address(), nonce.account().clone()),
+            ],
+        }
+    }
+
+    /// Adds the accounts which must be overridden for simulation, unless the caller already
+    /// overrides them
+    fn add_account_overrides_for_simulation(
+        &self,
+        mut account_overrides: AccountOverrides,
+        uses_slot_history: bool,
+    ) -> AccountOverrides {
+        let slot_history_id = sysvar::slot_history::id();
+        if uses_slot_history && account_overrides.get(&slot_history_id).is_none() {
             let current_account = self.get_account_with_fixed_root(&slot_history_id);
             let slot_history = current_account
                 .as_ref()
@@ -3835,6 +4086,13 @@ impl Bank {
         self.rc.accounts.unlock_accounts(txs_and_results)
     }
 
+    pub fn unlock_bundle_accounts<'a, Tx: SVMMessage + 'a>(
+        &self,
+        txs: impl Iterator<Item = &'a Tx>,
+    ) {
+        self.rc.accounts.unlock_bundle_accounts(txs)
+    }
+
     pub fn remove_unrooted_slots(&self, slots: &[(Slot, BankId)]) {
         self.rc.accounts.accounts_db.remove_unrooted_slots(slots)
     }
@@ -3963,6 +4221,7 @@ impl Bank {
         LoadAndExecuteTransactionsOutput {
             processing_results: sanitized_output.processing_results,
             processed_counts,
+            state_diffs: sanitized_output.state_diffs,
         }
     }
 
@@ -5032,6 +5291,59 @@ impl Bank {
         timings: &mut ExecuteTimings,
         log_messages_bytes_limit: Option<usize>,
     ) -> (Vec<TransactionCommitResult>, TransactionBalancesSet) {
+        let (commit_results, balances, _) = self.do_load_execute_and_commit_transactions(
+            batch,
+            max_age,
+            collect_balances,
+            recording_config,
+            timings,
+            log_messages_bytes_limit,
+            false,
+        );
+        (commit_results, balances)
+    }
+
+    /// Process a batch of transactions, also returning the accounts changed by each of them
+    #[must_use]
+    pub fn load_execute_and_commit_transactions_with_state_diffs(
+        &self,
+        batch: &TransactionBatch<impl TransactionWithMeta>,
+        max_age: usize,
+        collect_balances: bool,
+        recording_config: ExecutionRecordingConfig,
+        timings: &mut ExecuteTimings,
+        log_messages_bytes_limit: Option<usize>,
+    ) -> (
+        Vec<TransactionCommitResult>,
+        TransactionBalancesSet,
+        Vec<TransactionStateDiff>,
+    ) {
+        self.do_load_execute_and_commit_transactions(
+            batch,
+            max_age,
+            collect_balances,
+            recording_config,
+            timings,
+            log_messages_bytes_limit,
+            true,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn do_load_execute_and_commit_transactions(
+        &self,
+        batch: &TransactionBatch<impl TransactionWithMeta>,
+        max_age: usize,
+        collect_balances: bool,
+        recording_config: ExecutionRecordingConfig,
+        timings: &mut ExecuteTimings,
+        log_messages_bytes_limit: Option<usize>,
+        record_state_diffs: bool,
+    ) -> (
+        Vec<TransactionCommitResult>,
+        TransactionBalancesSet,
+        Vec<TransactionStateDiff>,
+    ) {
         let pre_balances = if collect_balances {
             self.collect_balances(batch)
         } else {
@@ -5041,6 +5353,7 @@ impl Bank {
         let LoadAndExecuteTransactionsOutput {
             processing_results,
             processed_counts,
+            state_diffs,
         } = self.load_and_execute_transactions(
             batch,
             max_age,
@@ -5054,6 +5367,8 @@ impl Bank {
                 limit_to_load_programs: false,
                 recording_config,
                 transaction_account_lock_limit: Some(self.get_transaction_account_lock_limit()),
+                record_state_diffs,
+                record_instruction_traces: false,
             },
         );
 
@@ -5071,6 +5386,7 @@ impl Bank {
         (
             commit_results,
             TransactionBalancesSet::new(pre_balances, post_balances),
+            state_diffs,
         )
     }
 
@@ -5492,6 +5808,30 @@ impl Bank {
         )
     }
 
+    /// Returns up to `limit` accounts passing `filter` in pubkey order, starting after `after`,
+    /// and whether more matching accounts follow. If `index_key` is Some, only the accounts in
+    /// that secondary index are considered.
+    pub fn get_filtered_accounts_page<F: Fn(&AccountSharedData) -> bool>(
+        &self,
+        index_key: Option<&IndexKey>,
+        filter: F,
+        after: Option<&Pubkey>,
+        limit: usize,
+        config: &ScanConfig,
+        byte_limit_for_scan: Option<usize>,
+    ) -> ScanResult<(Vec<TransactionAccount>, bool)> {
+        self.rc.accounts.load_page_with_filter(
+            &self.ancestors,
+            self.bank_id,
+            index_key,
+            filter,
+            after,
+            limit,
+            config,
+            byte_limit_for_scan,
+        )
+    }
+
     pub fn account_indexes_include_key(&self, key: &Pubkey) -> bool {
         self.rc.accounts.account_indexes_include_key(key)
     }
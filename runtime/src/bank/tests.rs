@@ -102,6 +102,7 @@ use {
     solana_stake_program::stake_state::{self, StakeStateV2},
     solana_svm::{
         account_loader::{FeesOnlyTransaction, LoadedTransaction},
+        account_overrides::AccountOverrides,
         rollback_accounts::RollbackAccounts,
         transaction_commit_result::TransactionCommitResultExtensions,
         transaction_execution_result::ExecutedTransaction,
@@ -252,6 +253,7 @@ fn new_executed_processing_result(
                 return_data: None,
                 executed_units: 0,
                 accounts_data_len_delta: 0,
+                instruction_traces: None,
             },
             programs_modified_by_tx: HashMap::new(),
         },
@@ -13029,10 +13031,193 @@ fn test_failed_simulation_load_error() {
             units_consumed: 0,
             return_data: None,
             inner_instructions: None,
+            state_diff: None,
         }
     );
 }
 
+/// Test that the transactions of a simulated bundle see the changes of the previous ones
+#[test]
+fn test_simulate_transactions() {
+    let (genesis_config, mint_keypair) = create_genesis_config(LAMPORTS_PER_SOL);
+    let bank = Bank::new_for_tests(&genesis_config);
+    let (bank, _bank_forks) = bank.wrap_with_bank_forks_for_tests();
+    let alice = Keypair::new();
+    let bob = Pubkey::new_unique();
+    let blockhash = bank.last_blockhash();
+
+    let transactions = [
+        system_transaction::transfer(&mint_keypair, &alice.pubkey(), 100_000_000, blockhash),
+        system_transaction::transfer(&alice, &bob, 10_000_000, blockhash),
+        system_transaction::transfer(&alice, &bob, LAMPORTS_PER_SOL, blockhash),
+    ]
+    .map(RuntimeTransaction::from_transaction_for_tests);
+
+    bank.freeze();
+    let simulations = bank.simulate_transactions(&transactions, false, true);
+    assert_eq!(simulations.len(), 3);
+    assert_eq!(simulations[0].result, Ok(()));
+    assert_eq!(simulations[1].result, Ok(()));
+    let (pubkey, account) = &simulations[0].post_simulation_accounts[0];
+    assert_eq!(*pubkey, mint_keypair.pubkey());
+    let fee = LAMPORTS_PER_SOL - 100_000_000 - account.lamports();
+    let (pubkey, account) = &simulations[1].post_simulation_accounts[0];
+    assert_eq!(*pubkey, alice.pubkey());
+    assert_eq!(account.lamports(), 100_000_000 - 10_000_000 - fee);
+    assert!(!simulations[1].logs.is_empty());
+    assert!(simulations[1].units_consumed > 0);
+    let state_diff = simulations[1].state_diff.as_ref().unwrap();
+    assert_eq!(state_diff.accounts.len(), 2);
+    let bob_diff = state_diff.get(&bob).unwrap();
+    assert!(bob_diff.was_created());
+    assert_eq!(bob_diff.post_lamports, 10_000_000);
+
+    // A failed transaction only commits its fee payer
+    assert_eq!(
+        simulations[2].result,
+        Err(TransactionError::InstructionError(
+            0,
+            SystemError::ResultWithNegativeLamports.into()
+        ))
+    );
+    let [(pubkey, account)] = simulations[2].post_simulation_accounts.as_slice() else {
+        panic!("expected only the fee payer");
+    };
+    assert_eq!(*pubkey, alice.pubkey());
+    assert_eq!(account.lamports(), 100_000_000 - 10_000_000 - 2 * fee);
+    assert!(simulations[2]
+        .state_diff
+        .as_ref()
+        .unwrap()
+        .get(&bob)
+        .is_none());
+
+    // Nothing was committed
+    assert_eq!(bank.get_balance(&alice.pubkey()), 0);
+    assert_eq!(bank.get_balance(&bob), 0);
+}
+
+/// Test that simulations run against the overridden accounts and clock
+#[test]
+fn test_simulate_transaction_with_overrides() {
+    const UNIX_TIMESTAMP: UnixTimestamp = 1_234;
+    declare_process_instruction!(MockBuiltin, 1, |invoke_context| {
+        let clock = invoke_context.get_sysvar_cache().get_clock()?;
+        if clock.unix_timestamp != UNIX_TIMESTAMP {
+            return Err(InstructionError::InvalidArgument);
+        }
+        Ok(())
+    });
+
+    let (genesis_config, _mint_keypair) = create_genesis_config(LAMPORTS_PER_SOL);
+    let mut bank = Bank::new_for_tests(&genesis_config);
+    let program_id = Pubkey::new_unique();
+    bank.add_mockup_builtin(program_id, MockBuiltin::vm);
+    let (bank, _bank_forks) = bank.wrap_with_bank_forks_for_tests();
+    let alice = Keypair::new();
+    let bob = Pubkey::new_unique();
+
+    let transaction =
+        RuntimeTransaction::from_transaction_for_tests(Transaction::new_signed_with_payer(
+            &[
+                system_instruction::transfer(&alice.pubkey(), &bob, 10_000_000),
+                Instruction::new_with_bytes(program_id, &[], vec![]),
+            ],
+            Some(&alice.pubkey()),
+            &[&alice],
+            bank.last_blockhash(),
+        ));
+    bank.freeze();
+    assert_eq!(
+        bank.simulate_transaction(&transaction, false).result,
+        Err(TransactionError::AccountNotFound)
+    );
+
+    let mut account_overrides = AccountOverrides::default();
+    account_overrides.set_account(
+        &alice.pubkey(),
+        Some(AccountSharedData::new(
+            LAMPORTS_PER_SOL,
+            0,
+            &system_program::id(),
+        )),
+    );
+    let simulation =
+        bank.simulate_transaction_with_overrides(&transaction, false, false, account_overrides);
+    assert_eq!(
+        simulation.result,
+        Err(TransactionError::InstructionError(
+            1,
+            InstructionError::InvalidArgument
+        ))
+    );
+
+    let mut account_overrides = AccountOverrides::default();
+    account_overrides.set_account(
+        &alice.pubkey(),
+        Some(AccountSharedData::new(
+            LAMPORTS_PER_SOL,
+            0,
+            &system_program::id(),
+        )),
+    );
+    let clock = sysvar::clock::Clock {
+        unix_timestamp: UNIX_TIMESTAMP,
+        ..bank.clock()
+    };
+    account_overrides.set_clock(Some(create_account(&clock, (1, INITIAL_RENT_EPOCH))));
+    let simulation =
+        bank.simulate_transaction_with_overrides(&transaction, false, true, account_overrides);
+    assert_eq!(simulation.result, Ok(()));
+    let (_, bob_account) = simulation
+        .post_simulation_accounts
+        .iter()
+        .find(|(pubkey, _)| *pubkey == bob)
+        .unwrap();
+    assert_eq!(bob_account.lamports(), 10_000_000);
+    // The overridden state is the state before the transaction
+    let alice_diff = simulation
+        .state_diff
+        .as_ref()
+        .unwrap()
+        .get(&alice.pubkey())
+        .unwrap();
+    assert_eq!(alice_diff.pre_lamports, LAMPORTS_PER_SOL);
+    assert!(!alice_diff.was_created());
+
+    // Nothing was committed
+    assert_eq!(bank.get_balance(&alice.pubkey()), 0);
+    assert_ne!(bank.clock().unix_timestamp, UNIX_TIMESTAMP);
+}
+
+#[test]
+fn test_load_execute_and_commit_transactions_with_state_diffs() {
+    let (genesis_config, mint_keypair) = create_genesis_config(LAMPORTS_PER_SOL);
+    let (bank, _bank_forks) = Bank::new_with_bank_forks_for_tests(&genesis_config);
+    let bob = Pubkey::new_unique();
+    let batch = bank.prepare_batch_for_tests(vec![system_transaction::transfer(
+        &mint_keypair,
+        &bob,
+        10_000_000,
+        bank.last_blockhash(),
+    )]);
+
+    let (commit_results, _, state_diffs) = bank
+        .load_execute_and_commit_transactions_with_state_diffs(
+            &batch,
+            MAX_PROCESSING_AGE,
+            false,
+            ExecutionRecordingConfig::new_single_setting(false),
+            &mut ExecuteTimings::default(),
+            None,
+        );
+    assert!(commit_results[0].is_ok());
+    assert_eq!(state_diffs.len(), 1);
+    let bob_diff = state_diffs[0].get(&bob).unwrap();
+    assert!(bob_diff.was_created());
+    assert_eq!(bob_diff.post_lamports, bank.get_balance(&bob));
+}
+
 #[test]
 fn test_filter_program_errors_and_collect_fee_details() {
     // TX  | PROCESSING RESULT           | COLLECT            | COLLECT
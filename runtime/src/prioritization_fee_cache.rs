@@ -30,6 +30,16 @@ const MAX_UNFINALIZED_SLOTS: u64 = 128;
 
 type UnfinalizedPrioritizationFees = BTreeMap<Slot, HashMap<BankId, PrioritizationFee>>;
 
+/// Percentiles of the prioritization fees needed to land a transaction in recent blocks, see
+/// `PrioritizationFeeCache::get_prioritization_fee_estimate()`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PrioritizationFeeEstimate {
+    pub p25: u64,
+    pub p50: u64,
+    pub p75: u64,
+    pub p95: u64,
+}
+
 #[derive(Debug, Default)]
 struct PrioritizationFeeCacheMetrics {
     // Count of transactions that successfully updated each slot's prioritization fee cache.
@@ -429,6 +439,62 @@ impl PrioritizationFeeCache {
             })
             .collect()
     }
+
+    /// Estimates the prioritization fee needed to land a transaction write-locking
+    /// `account_keys`, from the fees needed to land it in each cached block. Blocks in which more
+    /// of the accounts were contended, ie. had a minimum fee above the block's minimum, are
+    /// weighted more heavily. Returns `None` if no block is available.
+    pub fn get_prioritization_fee_estimate(
+        &self,
+        account_keys: &[Pubkey],
+    ) -> Option<PrioritizationFeeEstimate> {
+        let mut weighted_fees: Vec<_> = self
+            .cache
+            .read()
+            .unwrap()
+            .values()
+            .map(|slot_prioritization_fee| {
+                let mut fee = slot_prioritization_fee
+                    .get_min_transaction_fee()
+                    .unwrap_or_default();
+                let mut weight = 1;
+                for account_key in account_keys {
+                    if let Some(account_fee) =
+                        slot_prioritization_fee.get_writable_account_fee(account_key)
+                    {
+                        fee = std::cmp::max(fee, account_fee);
+                        weight += 1;
+                    }
+                }
+                (fee, weight)
+            })
+            .collect();
+        if weighted_fees.is_empty() {
+            return None;
+        }
+
+        weighted_fees.sort_unstable();
+        Some(PrioritizationFeeEstimate {
+            p25: weighted_percentile(&weighted_fees, 25),
+            p50: weighted_percentile(&weighted_fees, 50),
+            p75: weighted_percentile(&weighted_fees, 75),
+            p95: weighted_percentile(&weighted_fees, 95),
+        })
+    }
+}
+
+/// Returns the smallest fee of the non-empty, sorted `(fee, weight)` list such that the fees up
+/// to and including it hold at least `percentile` percent of the total weight.
+fn weighted_percentile(sorted_weighted_fees: &[(u64, u64)], percentile: u64) -> u64 {
+    let total_weight: u64 = sorted_weighted_fees.iter().map(|(_, weight)| weight).sum();
+    let mut cumulative_weight = 0;
+    for (fee, weight) in sorted_weighted_fees {
+        cumulative_weight += weight;
+        if cumulative_weight * 100 >= percentile * total_weight {
+            return *fee;
+        }
+    }
+    sorted_weighted_fees.last().unwrap().0
 }
 
 #[cfg(test)]
@@ -859,6 +925,96 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_weighted_percentile() {
+        let weighted_fees = [(1, 1), (2, 1), (3, 1), (4, 1)];
+        assert_eq!(weighted_percentile(&weighted_fees, 25), 1);
+        assert_eq!(weighted_percentile(&weighted_fees, 50), 2);
+        assert_eq!(weighted_percentile(&weighted_fees, 75), 3);
+        assert_eq!(weighted_percentile(&weighted_fees, 95), 4);
+
+        // the heavier fee dominates the higher percentiles
+        let weighted_fees = [(1, 1), (2, 1), (10, 6)];
+        assert_eq!(weighted_percentile(&weighted_fees, 25), 2);
+        assert_eq!(weighted_percentile(&weighted_fees, 50), 10);
+        assert_eq!(weighted_percentile(&weighted_fees, 95), 10);
+
+        assert_eq!(weighted_percentile(&[(7, 1)], 50), 7);
+    }
+
+    #[test]
+    fn test_get_prioritization_fee_estimate() {
+        solana_logger::setup();
+        let write_account_a = Pubkey::new_unique();
+        let write_account_b = Pubkey::new_unique();
+
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let bank0 = Bank::new_for_benches(&genesis_config);
+        let bank_forks = BankForks::new_rw_arc(bank0);
+        let bank = bank_forks.read().unwrap().working_bank();
+        let collector = solana_sdk::pubkey::new_rand();
+        let banks: Vec<_> = (1..=4)
+            .map(|slot| Arc::new(Bank::new_from_parent(bank.clone(), &collector, slot)))
+            .collect();
+
+        let prioritization_fee_cache = PrioritizationFeeCache::default();
+        assert_eq!(
+            prioritization_fee_cache.get_prioritization_fee_estimate(&[]),
+            None
+        );
+
+        // Every block lands a transaction at fee 1, slot 4 also lands account_a at fee 100
+        for bank in &banks {
+            let mut txs = vec![build_sanitized_transaction_for_test(
+                1,
+                &Pubkey::new_unique(),
+                &Pubkey::new_unique(),
+            )];
+            if bank.slot() == 4 {
+                txs.push(build_sanitized_transaction_for_test(
+                    100,
+                    &write_account_a,
+                    &Pubkey::new_unique(),
+                ));
+            }
+            sync_update(&prioritization_fee_cache, bank.clone(), txs.iter());
+            sync_finalize_priority_fee_for_test(
+                &prioritization_fee_cache,
+                bank.slot(),
+                bank.bank_id(),
+            );
+        }
+
+        assert_eq!(
+            prioritization_fee_cache.get_prioritization_fee_estimate(&[]),
+            Some(PrioritizationFeeEstimate {
+                p25: 1,
+                p50: 1,
+                p75: 1,
+                p95: 1,
+            })
+        );
+        assert_eq!(
+            prioritization_fee_cache.get_prioritization_fee_estimate(&[write_account_b]),
+            Some(PrioritizationFeeEstimate {
+                p25: 1,
+                p50: 1,
+                p75: 1,
+                p95: 1,
+            })
+        );
+        // slot 4 weighs twice as much as each other block for account_a
+        assert_eq!(
+            prioritization_fee_cache.get_prioritization_fee_estimate(&[write_account_a]),
+            Some(PrioritizationFeeEstimate {
+                p25: 1,
+                p50: 1,
+                p75: 100,
+                p95: 100,
+            })
+        );
+    }
+
     #[test]
     fn test_purge_duplicated_bank() {
         // duplicated bank can exists for same slot before OC.
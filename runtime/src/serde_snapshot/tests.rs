@@ -19,7 +19,7 @@ mod serde_snapshot_tests {
                 get_temp_accounts_paths, test_utils::create_test_accounts, AccountStorageEntry,
                 AccountsDb, AtomicAccountsFileId, VerifyAccountsHashAndLamportsConfig,
             },
-            accounts_file::{AccountsFile, AccountsFileError, StorageAccess},
+            accounts_file::{AccountsFile, AccountsFileError, AccountsFileProvider, StorageAccess},
             accounts_hash::AccountsHash,
             ancestors::Ancestors,
         },
@@ -310,6 +310,40 @@ mod serde_snapshot_tests {
             .is_none());
     }
 
+    #[test_case(AccountsFileProvider::HotStorage)]
+    #[test_case(AccountsFileProvider::ColdStorage)]
+    #[test_case(AccountsFileProvider::ColdStorageZstd)]
+    fn test_accounts_db_serialize_tiered_storage(provider: AccountsFileProvider) {
+        solana_logger::setup();
+        let db = AccountsDb::new_single_for_tests_with_provider(provider);
+        let slot = 1;
+        let accounts: Vec<_> = (1..=10)
+            .map(|lamports| {
+                (
+                    solana_sdk::pubkey::new_rand(),
+                    AccountSharedData::new(lamports, 0, &solana_sdk::pubkey::new_rand()),
+                )
+            })
+            .collect();
+        let account_refs: Vec<_> = accounts
+            .iter()
+            .map(|(pubkey, account)| (pubkey, account))
+            .collect();
+        db.store_for_tests(slot, &account_refs);
+        db.add_root_and_flush_write_cache(slot);
+
+        db.calculate_accounts_delta_hash(slot);
+        db.update_accounts_hash_for_tests(slot, &linear_ancestors(slot), false, false);
+
+        // Simulate reconstruction from snapshot, which must reopen the tiered storage
+        let db = reconstruct_accounts_db_via_serialization(&db, slot, StorageAccess::Mmap);
+        let storage = db.storage.get_slot_storage_entry(slot).unwrap();
+        assert!(matches!(storage.accounts, AccountsFile::TieredStorage(_)));
+        for (pubkey, account) in &accounts {
+            db.assert_load_account(slot, *pubkey, account.lamports());
+        }
+    }
+
     #[test_case(StorageAccess::Mmap)]
     #[test_case(StorageAccess::File)]
     fn test_accounts_db_serialize1(storage_access: StorageAccess) {
@@ -25,6 +25,8 @@ pub struct TransactionBatch<'a, 'b, Tx: SVMMessage> {
     bank: &'a Bank,
     sanitized_txs: OwnedOrBorrowed<'b, Tx>,
     needs_unlock: bool,
+    /// The transactions are a bundle, whose accounts are locked together
+    is_bundle: bool,
 }
 
 impl<'a, 'b, Tx: SVMMessage> TransactionBatch<'a, 'b, Tx> {
@@ -39,6 +41,23 @@ impl<'a, 'b, Tx: SVMMessage> TransactionBatch<'a, 'b, Tx> {
             bank,
             sanitized_txs,
             needs_unlock: true,
+            is_bundle: false,
+        }
+    }
+
+    /// Create a batch for a bundle, whose accounts were locked together with
+    /// `Accounts::lock_bundle_accounts` if `lock_result` is `Ok`.
+    pub fn new_bundle(
+        lock_result: Result<()>,
+        bank: &'a Bank,
+        sanitized_txs: OwnedOrBorrowed<'b, Tx>,
+    ) -> Self {
+        Self {
+            lock_results: vec![lock_result; sanitized_txs.len()],
+            bank,
+            sanitized_txs,
+            needs_unlock: true,
+            is_bundle: true,
         }
     }
 
@@ -71,6 +90,11 @@ impl<'a, 'b, Tx: SVMMessage> TransactionBatch<'a, 'b, Tx> {
         if !self.needs_unlock() {
             return;
         }
+        // A bundle's accounts are locked together, so they are held until the
+        // batch is dropped.
+        if self.is_bundle {
+            return;
+        }
 
         let txs_and_results = transaction_results
             .iter()
@@ -101,11 +125,18 @@ impl<'a, 'b, Tx: SVMMessage> Drop for TransactionBatch<'a, 'b, Tx> {
     fn drop(&mut self) {
         if self.needs_unlock() {
             self.set_needs_unlock(false);
-            self.bank.unlock_accounts(
-                self.sanitized_transactions()
-                    .iter()
-                    .zip(self.lock_results()),
-            )
+            if self.is_bundle {
+                if self.lock_results.iter().all(|result| result.is_ok()) {
+                    self.bank
+                        .unlock_bundle_accounts(self.sanitized_transactions().iter());
+                }
+            } else {
+                self.bank.unlock_accounts(
+                    self.sanitized_transactions()
+                        .iter()
+                        .zip(self.lock_results()),
+                )
+            }
         }
     }
 }
@@ -192,6 +223,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bundle_batch() {
+        let (bank, txs) = setup(true);
+
+        // The bundle's transactions conflict with each other, which is allowed
+        let batch = bank.prepare_locked_bundle_batch(&txs, Ok(()));
+        assert!(batch.lock_results().iter().all(|x| x.is_ok()));
+
+        // But not with other transactions
+        let batch2 = bank.prepare_locked_bundle_batch(&txs[2..], Ok(()));
+        assert_eq!(
+            batch2.lock_results(),
+            &vec![Err(TransactionError::AccountInUse)]
+        );
+        drop(batch2);
+        let batch2 = bank.prepare_sanitized_batch(&txs);
+        assert!(batch2.lock_results().iter().all(|x| x.is_err()));
+        drop(batch2);
+
+        // Dropping the bundle batch releases all of its locks
+        drop(batch);
+        let batch2 = bank.prepare_sanitized_batch(&txs);
+        assert_eq!(
+            batch2.lock_results,
+            vec![Ok(()), Err(TransactionError::AccountInUse), Ok(())]
+        );
+        drop(batch2);
+
+        // A bundle which failed its checks is not locked
+        let batch = bank.prepare_locked_bundle_batch(
+            &txs[..1],
+            Err(TransactionError::WouldExceedMaxBlockCostLimit),
+        );
+        assert_eq!(
+            batch.lock_results(),
+            &vec![Err(TransactionError::WouldExceedMaxBlockCostLimit)]
+        );
+        let batch2 = bank.prepare_sanitized_batch(&txs[..1]);
+        assert_eq!(batch2.lock_results(), &vec![Ok(())]);
+    }
+
     fn setup(insert_conflicting_tx: bool) -> (Bank, Vec<RuntimeTransaction<SanitizedTransaction>>) {
         let dummy_leader_pubkey = solana_sdk::pubkey::new_rand();
         let GenesisConfigInfo {
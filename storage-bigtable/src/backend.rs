@@ -0,0 +1,215 @@
+//! The storage behind `LedgerStorage`.
+//!
+//! Ledger data is kept in a handful of tables, each made of rows addressed by a string key.
+//! Rows hold named cells and are ordered by key, which is what lets `LedgerStorage` list blocks
+//! and signatures by scanning key ranges. Any store offering that model can stand in for BigTable.
+
+pub use crate::bigtable::{CellName, CellValue, Error, Result, RowData, RowKey};
+use {
+    crate::{
+        bigtable::{
+            deserialize_bincode_cell_data, deserialize_protobuf_cell_data,
+            deserialize_protobuf_or_bincode_cell_data, CellData,
+        },
+        compression::compress_best,
+    },
+    async_trait::async_trait,
+};
+
+#[async_trait]
+pub trait LedgerStorageBackend: Send + Sync {
+    /// List the keys of `table_name`, starting at `start_at` (inclusive) or the start of the
+    /// table, and ending at `end_at` (inclusive) or once `rows_limit` keys have been listed.
+    /// If `rows_limit` is zero, returns an empty list.
+    async fn get_row_keys(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<RowKey>>;
+
+    /// Check whether a row key exists in `table_name`
+    async fn row_key_exists(&self, table_name: &str, row_key: RowKey) -> Result<bool>;
+
+    /// Get the rows of `table_name` within the same range as `get_row_keys`
+    async fn get_row_data(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<(RowKey, RowData)>>;
+
+    /// Get the rows of `table_name` with the given keys, skipping rows that don't exist
+    async fn get_multi_row_data(
+        &self,
+        table_name: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, RowData)>>;
+
+    /// Same as `get_multi_row_data`, retrying failures a backend considers transient
+    async fn get_multi_row_data_with_retry(
+        &self,
+        table_name: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        self.get_multi_row_data(table_name, row_keys).await
+    }
+
+    /// Get a single row of `table_name`, returning `Error::RowNotFound` if it doesn't exist
+    async fn get_single_row_data(&self, table_name: &str, row_key: RowKey) -> Result<RowData>;
+
+    /// Store cells in the `family_name` column family of one or more rows of `table_name`.
+    /// Existing cells with the same name are replaced, other cells of the row are kept.
+    async fn put_row_data(
+        &self,
+        table_name: &str,
+        family_name: &str,
+        row_data: &[(&RowKey, RowData)],
+    ) -> Result<()>;
+
+    /// Delete one or more rows of `table_name`
+    async fn delete_rows(&self, table_name: &str, row_keys: &[RowKey]) -> Result<()>;
+}
+
+/// Typed access to the cells of a backend, shared by all backends so they store identical data
+impl dyn LedgerStorageBackend {
+    pub(crate) async fn get_bincode_cell<T>(&self, table: &str, key: RowKey) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let row_data = self.get_single_row_data(table, key.clone()).await?;
+        deserialize_bincode_cell_data(&row_data, table, key)
+    }
+
+    pub(crate) async fn get_bincode_cells<T>(
+        &self,
+        table: &str,
+        keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, Result<T>)>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let rows = self.get_multi_row_data(table, keys).await?;
+        Ok(deserialize_bincode_rows(table, rows))
+    }
+
+    pub(crate) async fn get_bincode_cells_with_retry<T>(
+        &self,
+        table: &str,
+        keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, Result<T>)>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let rows = self.get_multi_row_data_with_retry(table, keys).await?;
+        Ok(deserialize_bincode_rows(table, rows))
+    }
+
+    pub(crate) async fn get_protobuf_cell<P>(&self, table: &str, key: RowKey) -> Result<P>
+    where
+        P: prost::Message + Default,
+    {
+        let row_data = self.get_single_row_data(table, key.clone()).await?;
+        deserialize_protobuf_cell_data(&row_data, table, key)
+    }
+
+    pub(crate) async fn get_protobuf_or_bincode_cell<B, P>(
+        &self,
+        table: &str,
+        key: RowKey,
+    ) -> Result<CellData<B, P>>
+    where
+        B: serde::de::DeserializeOwned,
+        P: prost::Message + Default,
+    {
+        let row_data = self.get_single_row_data(table, key.clone()).await?;
+        deserialize_protobuf_or_bincode_cell_data(&row_data, table, key)
+    }
+
+    pub(crate) async fn get_protobuf_or_bincode_cells<'a, B, P>(
+        &self,
+        table: &'a str,
+        row_keys: impl IntoIterator<Item = RowKey>,
+    ) -> Result<impl Iterator<Item = (RowKey, CellData<B, P>)> + 'a>
+    where
+        B: serde::de::DeserializeOwned,
+        P: prost::Message + Default,
+    {
+        Ok(self
+            .get_multi_row_data(
+                table,
+                row_keys.into_iter().collect::<Vec<RowKey>>().as_slice(),
+            )
+            .await?
+            .into_iter()
+            .map(|(key, row_data)| {
+                let key_str = key.to_string();
+                (
+                    key,
+                    deserialize_protobuf_or_bincode_cell_data(&row_data, table, key_str).unwrap(),
+                )
+            }))
+    }
+
+    pub(crate) async fn put_bincode_cells<T>(
+        &self,
+        table: &str,
+        cells: &[(RowKey, T)],
+    ) -> Result<usize>
+    where
+        T: serde::ser::Serialize,
+    {
+        let mut bytes_written = 0;
+        let mut new_row_data = vec![];
+        for (row_key, data) in cells {
+            let data = compress_best(&bincode::serialize(&data).unwrap())?;
+            bytes_written += data.len();
+            new_row_data.push((row_key, vec![("bin".to_string(), data)]));
+        }
+
+        self.put_row_data(table, "x", &new_row_data).await?;
+        Ok(bytes_written)
+    }
+
+    pub(crate) async fn put_protobuf_cells<T>(
+        &self,
+        table: &str,
+        cells: &[(RowKey, T)],
+    ) -> Result<usize>
+    where
+        T: prost::Message,
+    {
+        let mut bytes_written = 0;
+        let mut new_row_data = vec![];
+        for (row_key, data) in cells {
+            let mut buf = Vec::with_capacity(data.encoded_len());
+            data.encode(&mut buf).unwrap();
+            let data = compress_best(&buf)?;
+            bytes_written += data.len();
+            new_row_data.push((row_key, vec![("proto".to_string(), data)]));
+        }
+
+        self.put_row_data(table, "x", &new_row_data).await?;
+        Ok(bytes_written)
+    }
+}
+
+fn deserialize_bincode_rows<T>(
+    table: &str,
+    rows: Vec<(RowKey, RowData)>,
+) -> Vec<(RowKey, Result<T>)>
+where
+    T: serde::de::DeserializeOwned,
+{
+    rows.into_iter()
+        .map(|(key, row_data)| {
+            let key_str = key.to_string();
+            (
+                key,
+                deserialize_bincode_cell_data(&row_data, table, key_str),
+            )
+        })
+        .collect()
+}
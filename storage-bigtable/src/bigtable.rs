@@ -3,9 +3,11 @@
 use {
     crate::{
         access_token::{AccessToken, Scope},
-        compression::{compress_best, decompress},
+        backend::LedgerStorageBackend,
+        compression::decompress,
         root_ca_certificate, CredentialType,
     },
+    async_trait::async_trait,
     backoff::{future::retry, Error as BackoffError, ExponentialBackoff},
     log::*,
     std::{
@@ -82,6 +84,9 @@ pub enum Error {
 
     #[error("Timeout")]
     Timeout,
+
+    #[error("Local storage: {0}")]
+    LocalStorage(String),
 }
 
 fn to_backoff_err(err: Error) -> BackoffError<Error> {
@@ -276,58 +281,80 @@ impl BigTableConnection {
             timeout: self.timeout,
         }
     }
+}
 
-    pub async fn put_bincode_cells_with_retry<T>(
+#[async_trait]
+impl LedgerStorageBackend for BigTableConnection {
+    async fn get_row_keys(
         &self,
-        table: &str,
-        cells: &[(RowKey, T)],
-    ) -> Result<usize>
-    where
-        T: serde::ser::Serialize,
-    {
-        retry(ExponentialBackoff::default(), || async {
-            let mut client = self.client();
-            let result = client.put_bincode_cells(table, cells).await;
-            result.map_err(to_backoff_err)
-        })
-        .await
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<RowKey>> {
+        self.client()
+            .get_row_keys(table_name, start_at, end_at, rows_limit)
+            .await
+    }
+
+    async fn row_key_exists(&self, table_name: &str, row_key: RowKey) -> Result<bool> {
+        self.client().row_key_exists(table_name, row_key).await
+    }
+
+    async fn get_row_data(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        self.client()
+            .get_row_data(table_name, start_at, end_at, rows_limit)
+            .await
+    }
+
+    async fn get_multi_row_data(
+        &self,
+        table_name: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        self.client().get_multi_row_data(table_name, row_keys).await
     }
 
-    pub async fn delete_rows_with_retry(&self, table: &str, row_keys: &[RowKey]) -> Result<()> {
+    async fn get_multi_row_data_with_retry(
+        &self,
+        table_name: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, RowData)>> {
         retry(ExponentialBackoff::default(), || async {
             let mut client = self.client();
-            Ok(client.delete_rows(table, row_keys).await?)
+            Ok(client.get_multi_row_data(table_name, row_keys).await?)
         })
         .await
     }
 
-    pub async fn get_bincode_cells_with_retry<T>(
+    async fn get_single_row_data(&self, table_name: &str, row_key: RowKey) -> Result<RowData> {
+        self.client().get_single_row_data(table_name, row_key).await
+    }
+
+    async fn put_row_data(
         &self,
-        table: &str,
-        row_keys: &[RowKey],
-    ) -> Result<Vec<(RowKey, Result<T>)>>
-    where
-        T: serde::de::DeserializeOwned,
-    {
+        table_name: &str,
+        family_name: &str,
+        row_data: &[(&RowKey, RowData)],
+    ) -> Result<()> {
         retry(ExponentialBackoff::default(), || async {
             let mut client = self.client();
-            Ok(client.get_bincode_cells(table, row_keys).await?)
+            let result = client.put_row_data(table_name, family_name, row_data).await;
+            result.map_err(to_backoff_err)
         })
         .await
     }
 
-    pub async fn put_protobuf_cells_with_retry<T>(
-        &self,
-        table: &str,
-        cells: &[(RowKey, T)],
-    ) -> Result<usize>
-    where
-        T: prost::Message,
-    {
+    async fn delete_rows(&self, table_name: &str, row_keys: &[RowKey]) -> Result<()> {
         retry(ExponentialBackoff::default(), || async {
             let mut client = self.client();
-            let result = client.put_protobuf_cells(table, cells).await;
-            result.map_err(to_backoff_err)
+            Ok(client.delete_rows(table_name, row_keys).await?)
         })
         .await
     }
@@ -751,124 +778,6 @@ impl<F: FnMut(Request<()>) -> InterceptedRequestResult> BigTable<F> {
         Ok(())
     }
 
-    pub async fn get_bincode_cell<T>(&mut self, table: &str, key: RowKey) -> Result<T>
-    where
-        T: serde::de::DeserializeOwned,
-    {
-        let row_data = self.get_single_row_data(table, key.clone()).await?;
-        deserialize_bincode_cell_data(&row_data, table, key.to_string())
-    }
-
-    pub async fn get_bincode_cells<T>(
-        &mut self,
-        table: &str,
-        keys: &[RowKey],
-    ) -> Result<Vec<(RowKey, Result<T>)>>
-    where
-        T: serde::de::DeserializeOwned,
-    {
-        Ok(self
-            .get_multi_row_data(table, keys)
-            .await?
-            .into_iter()
-            .map(|(key, row_data)| {
-                let key_str = key.to_string();
-                (
-                    key,
-                    deserialize_bincode_cell_data(&row_data, table, key_str),
-                )
-            })
-            .collect())
-    }
-
-    pub async fn get_protobuf_cell<P>(&mut self, table: &str, key: RowKey) -> Result<P>
-    where
-        P: prost::Message + Default,
-    {
-        let row_data = self.get_single_row_data(table, key.clone()).await?;
-        deserialize_protobuf_cell_data(&row_data, table, key.to_string())
-    }
-
-    pub async fn get_protobuf_or_bincode_cell<B, P>(
-        &mut self,
-        table: &str,
-        key: RowKey,
-    ) -> Result<CellData<B, P>>
-    where
-        B: serde::de::DeserializeOwned,
-        P: prost::Message + Default,
-    {
-        let row_data = self.get_single_row_data(table, key.clone()).await?;
-        deserialize_protobuf_or_bincode_cell_data(&row_data, table, key)
-    }
-
-    pub async fn get_protobuf_or_bincode_cells<'a, B, P>(
-        &mut self,
-        table: &'a str,
-        row_keys: impl IntoIterator<Item = RowKey>,
-    ) -> Result<impl Iterator<Item = (RowKey, CellData<B, P>)> + 'a>
-    where
-        B: serde::de::DeserializeOwned,
-        P: prost::Message + Default,
-    {
-        Ok(self
-            .get_multi_row_data(
-                table,
-                row_keys.into_iter().collect::<Vec<RowKey>>().as_slice(),
-            )
-            .await?
-            .into_iter()
-            .map(|(key, row_data)| {
-                let key_str = key.to_string();
-                (
-                    key,
-                    deserialize_protobuf_or_bincode_cell_data(&row_data, table, key_str).unwrap(),
-                )
-            }))
-    }
-
-    pub async fn put_bincode_cells<T>(
-        &mut self,
-        table: &str,
-        cells: &[(RowKey, T)],
-    ) -> Result<usize>
-    where
-        T: serde::ser::Serialize,
-    {
-        let mut bytes_written = 0;
-        let mut new_row_data = vec![];
-        for (row_key, data) in cells {
-            let data = compress_best(&bincode::serialize(&data).unwrap())?;
-            bytes_written += data.len();
-            new_row_data.push((row_key, vec![("bin".to_string(), data)]));
-        }
-
-        self.put_row_data(table, "x", &new_row_data).await?;
-        Ok(bytes_written)
-    }
-
-    pub async fn put_protobuf_cells<T>(
-        &mut self,
-        table: &str,
-        cells: &[(RowKey, T)],
-    ) -> Result<usize>
-    where
-        T: prost::Message,
-    {
-        let mut bytes_written = 0;
-        let mut new_row_data = vec![];
-        for (row_key, data) in cells {
-            let mut buf = Vec::with_capacity(data.encoded_len());
-            data.encode(&mut buf).unwrap();
-            let data = compress_best(&buf)?;
-            bytes_written += data.len();
-            new_row_data.push((row_key, vec![("proto".to_string(), data)]));
-        }
-
-        self.put_row_data(table, "x", &new_row_data).await?;
-        Ok(bytes_written)
-    }
-
     async fn read_rows(
         &mut self,
         table_name: &str,
@@ -964,7 +873,7 @@ where
 mod tests {
     use {
         super::*,
-        crate::StoredConfirmedBlock,
+        crate::{compression::compress_best, StoredConfirmedBlock},
         prost::Message,
         solana_sdk::{
             hash::Hash, message::v0::LoadedAddresses, signature::Keypair, system_transaction,
@@ -1,7 +1,7 @@
 #![allow(clippy::arithmetic_side_effects)]
 
 use {
-    crate::bigtable::RowKey,
+    crate::{backend::LedgerStorageBackend, bigtable::RowKey},
     log::*,
     serde::{Deserialize, Serialize},
     solana_metrics::datapoint_info,
@@ -26,6 +26,7 @@ use {
     std::{
         collections::{HashMap, HashSet},
         convert::TryInto,
+        path::PathBuf,
         sync::{
             atomic::{AtomicUsize, Ordering},
             Arc,
@@ -43,10 +44,14 @@ extern crate solana_metrics;
 extern crate serde_derive;
 
 mod access_token;
+pub mod backend;
 mod bigtable;
 mod compression;
+mod local_storage;
 mod root_ca_certificate;
 
+pub use local_storage::LocalStorage;
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("BigTable: {0}")]
@@ -399,6 +404,9 @@ pub struct LedgerStorageConfig {
     pub instance_name: String,
     pub app_profile_id: String,
     pub max_message_size: usize,
+    /// Store the ledger data in a local database at this path instead of BigTable, in which
+    /// case the BigTable settings above are ignored
+    pub local_storage_path: Option<PathBuf>,
 }
 
 impl Default for LedgerStorageConfig {
@@ -410,6 +418,7 @@ impl Default for LedgerStorageConfig {
             instance_name: DEFAULT_INSTANCE_NAME.to_string(),
             app_profile_id: DEFAULT_APP_PROFILE_ID.to_string(),
             max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            local_storage_path: None,
         }
     }
 }
@@ -444,7 +453,7 @@ impl LedgerStorageStats {
 
 #[derive(Clone)]
 pub struct LedgerStorage {
-    connection: bigtable::BigTableConnection,
+    backend: Arc<dyn LedgerStorageBackend>,
     stats: Arc<LedgerStorageStats>,
 }
 
@@ -469,21 +478,17 @@ impl LedgerStorage {
         endpoint: &str,
         timeout: Option<Duration>,
     ) -> Result<Self> {
-        let stats = Arc::new(LedgerStorageStats::default());
-        Ok(Self {
-            connection: bigtable::BigTableConnection::new_for_emulator(
-                instance_name,
-                app_profile_id,
-                endpoint,
-                timeout,
-                LedgerStorageConfig::default().max_message_size,
-            )?,
-            stats,
-        })
+        let connection = bigtable::BigTableConnection::new_for_emulator(
+            instance_name,
+            app_profile_id,
+            endpoint,
+            timeout,
+            LedgerStorageConfig::default().max_message_size,
+        )?;
+        Ok(Self::new_with_backend(Arc::new(connection)))
     }
 
     pub async fn new_with_config(config: LedgerStorageConfig) -> Result<Self> {
-        let stats = Arc::new(LedgerStorageStats::default());
         let LedgerStorageConfig {
             read_only,
             timeout,
@@ -491,7 +496,16 @@ impl LedgerStorage {
             app_profile_id,
             credential_type,
             max_message_size,
+            local_storage_path,
         } = config;
+        if let Some(local_storage_path) = local_storage_path {
+            info!(
+                "Using local ledger storage at {}",
+                local_storage_path.display()
+            );
+            let local_storage = LocalStorage::open(&local_storage_path, read_only)?;
+            return Ok(Self::new_with_backend(Arc::new(local_storage)));
+        }
         let connection = bigtable::BigTableConnection::new(
             instance_name.as_str(),
             app_profile_id.as_str(),
@@ -501,7 +515,15 @@ impl LedgerStorage {
             max_message_size,
         )
         .await?;
-        Ok(Self { stats, connection })
+        Ok(Self::new_with_backend(Arc::new(connection)))
+    }
+
+    /// Use `backend` to store the ledger data
+    pub fn new_with_backend(backend: Arc<dyn LedgerStorageBackend>) -> Self {
+        Self {
+            backend,
+            stats: Arc::new(LedgerStorageStats::default()),
+        }
     }
 
     pub async fn new_with_stringified_credential(credential: String) -> Result<Self> {
@@ -516,8 +538,7 @@ impl LedgerStorage {
     pub async fn get_first_available_block(&self) -> Result<Option<Slot>> {
         trace!("LedgerStorage::get_first_available_block request received");
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-        let blocks = bigtable.get_row_keys("blocks", None, None, 1).await?;
+        let blocks = self.backend.get_row_keys("blocks", None, None, 1).await?;
         if blocks.is_empty() {
             return Ok(None);
         }
@@ -535,8 +556,8 @@ impl LedgerStorage {
             limit
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-        let blocks = bigtable
+        let blocks = self
+            .backend
             .get_row_keys(
                 "blocks",
                 Some(slot_to_blocks_key(start_slot)),
@@ -557,9 +578,9 @@ impl LedgerStorage {
             slots
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
         let row_keys = slots.iter().copied().map(slot_to_blocks_key);
-        let data = bigtable
+        let data = self
+            .backend
             .get_protobuf_or_bincode_cells("blocks", row_keys)
             .await?
             .filter_map(
@@ -584,8 +605,8 @@ impl LedgerStorage {
             slot
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-        let block_cell_data = bigtable
+        let block_cell_data = self
+            .backend
             .get_protobuf_or_bincode_cell::<StoredConfirmedBlock, generated::ConfirmedBlock>(
                 "blocks",
                 slot_to_blocks_key(slot),
@@ -610,9 +631,9 @@ impl LedgerStorage {
             slot
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
 
-        let block_exists = bigtable
+        let block_exists = self
+            .backend
             .row_key_exists("blocks", slot_to_blocks_key(slot))
             .await?;
 
@@ -626,8 +647,8 @@ impl LedgerStorage {
             slot
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-        let entry_cell_data = bigtable
+        let entry_cell_data = self
+            .backend
             .get_protobuf_cell::<entries::Entries>("entries", slot_to_entries_key(slot))
             .await
             .map_err(|err| match err {
@@ -644,8 +665,8 @@ impl LedgerStorage {
             signature
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-        let transaction_info = bigtable
+        let transaction_info = self
+            .backend
             .get_bincode_cell::<TransactionInfo>("tx", signature.to_string())
             .await
             .map_err(|err| match err {
@@ -665,11 +686,11 @@ impl LedgerStorage {
             signatures
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
 
         // Fetch transactions info
         let keys = signatures.iter().map(|s| s.to_string()).collect::<Vec<_>>();
-        let cells = bigtable
+        let cells = self
+            .backend
             .get_bincode_cells::<TransactionInfo>("tx", &keys)
             .await?;
 
@@ -727,10 +748,10 @@ impl LedgerStorage {
             signature
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
 
         // Figure out which block the transaction is located in
-        let TransactionInfo { slot, index, .. } = bigtable
+        let TransactionInfo { slot, index, .. } = self
+            .backend
             .get_bincode_cell("tx", signature.to_string())
             .await
             .map_err(|err| match err {
@@ -787,14 +808,14 @@ impl LedgerStorage {
             address
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
         let address_prefix = format!("{address}/");
 
         // Figure out where to start listing from based on `before_signature`
         let (first_slot, before_transaction_index) = match before_signature {
             None => (Slot::MAX, 0),
             Some(before_signature) => {
-                let TransactionInfo { slot, index, .. } = bigtable
+                let TransactionInfo { slot, index, .. } = self
+                    .backend
                     .get_bincode_cell("tx", before_signature.to_string())
                     .await
                     .map_err(|err| match err {
@@ -810,7 +831,8 @@ impl LedgerStorage {
         let (last_slot, until_transaction_index) = match until_signature {
             None => (0, u32::MAX),
             Some(until_signature) => {
-                let TransactionInfo { slot, index, .. } = bigtable
+                let TransactionInfo { slot, index, .. } = self
+                    .backend
                     .get_bincode_cell("tx", until_signature.to_string())
                     .await
                     .map_err(|err| match err {
@@ -824,7 +846,7 @@ impl LedgerStorage {
 
         let mut infos = vec![];
 
-        let starting_slot_tx_len = bigtable
+        let starting_slot_tx_len = self.backend
             .get_protobuf_or_bincode_cell::<Vec<LegacyTransactionByAddrInfo>, tx_by_addr::TransactionByAddr>(
                 "tx-by-addr",
                 format!("{}{}", address_prefix, slot_to_tx_by_addr_key(first_slot)),
@@ -840,7 +862,8 @@ impl LedgerStorage {
 
         // Return the next tx-by-addr data of amount `limit` plus extra to account for the largest
         // number that might be filtered out
-        let tx_by_addr_data = bigtable
+        let tx_by_addr_data = self
+            .backend
             .get_row_data(
                 "tx-by-addr",
                 Some(format!(
@@ -1013,28 +1036,31 @@ impl LedgerStorage {
         let mut tasks = vec![];
 
         if !tx_cells.is_empty() {
-            let conn = self.connection.clone();
+            let backend = self.backend.clone();
             tasks.push(tokio::spawn(async move {
-                conn.put_bincode_cells_with_retry::<TransactionInfo>("tx", &tx_cells)
+                backend
+                    .put_bincode_cells::<TransactionInfo>("tx", &tx_cells)
                     .await
             }));
         }
 
         if !tx_by_addr_cells.is_empty() {
-            let conn = self.connection.clone();
+            let backend = self.backend.clone();
             tasks.push(tokio::spawn(async move {
-                conn.put_protobuf_cells_with_retry::<tx_by_addr::TransactionByAddr>(
-                    "tx-by-addr",
-                    &tx_by_addr_cells,
-                )
-                .await
+                backend
+                    .put_protobuf_cells::<tx_by_addr::TransactionByAddr>(
+                        "tx-by-addr",
+                        &tx_by_addr_cells,
+                    )
+                    .await
             }));
         }
 
         if num_entries > 0 {
-            let conn = self.connection.clone();
+            let backend = self.backend.clone();
             tasks.push(tokio::spawn(async move {
-                conn.put_protobuf_cells_with_retry::<entries::Entries>("entries", &[entry_cell])
+                backend
+                    .put_protobuf_cells::<entries::Entries>("entries", &[entry_cell])
                     .await
             }));
         }
@@ -1072,8 +1098,8 @@ impl LedgerStorage {
         // `get_confirmed_block()` and `get_confirmed_blocks()`
         let blocks_cells = [(slot_to_blocks_key(slot), confirmed_block.into())];
         bytes_written += self
-            .connection
-            .put_protobuf_cells_with_retry::<generated::ConfirmedBlock>("blocks", &blocks_cells)
+            .backend
+            .put_protobuf_cells::<generated::ConfirmedBlock>("blocks", &blocks_cells)
             .await?;
         datapoint_info!(
             "storage-bigtable-upload-block",
@@ -1144,7 +1170,7 @@ impl LedgerStorage {
         let tx_deletion_rows = if !expected_tx_infos.is_empty() {
             let signatures = expected_tx_infos.keys().cloned().collect::<Vec<_>>();
             let fetched_tx_infos: HashMap<String, std::result::Result<UploadedTransaction, _>> =
-                self.connection
+                self.backend
                     .get_bincode_cells_with_retry::<TransactionInfo>("tx", &signatures)
                     .await?
                     .into_iter()
@@ -1182,33 +1208,30 @@ impl LedgerStorage {
         };
 
         let entries_exist = self
-            .connection
-            .client()
+            .backend
             .row_key_exists("entries", slot_to_entries_key(slot))
             .await
             .is_ok_and(|x| x);
 
         if !dry_run {
             if !address_slot_rows.is_empty() {
-                self.connection
-                    .delete_rows_with_retry("tx-by-addr", &address_slot_rows)
+                self.backend
+                    .delete_rows("tx-by-addr", &address_slot_rows)
                     .await?;
             }
 
             if !tx_deletion_rows.is_empty() {
-                self.connection
-                    .delete_rows_with_retry("tx", &tx_deletion_rows)
-                    .await?;
+                self.backend.delete_rows("tx", &tx_deletion_rows).await?;
             }
 
             if entries_exist {
-                self.connection
-                    .delete_rows_with_retry("entries", &[slot_to_entries_key(slot)])
+                self.backend
+                    .delete_rows("entries", &[slot_to_entries_key(slot)])
                     .await?;
             }
 
-            self.connection
-                .delete_rows_with_retry("blocks", &[slot_to_blocks_key(slot)])
+            self.backend
+                .delete_rows("blocks", &[slot_to_blocks_key(slot)])
                 .await?;
         }
 
@@ -1227,11 +1250,130 @@ impl LedgerStorage {
 
 #[cfg(test)]
 mod test {
-    use super::*;
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash,
+            signature::{Keypair, Signer},
+            system_transaction,
+        },
+    };
 
     #[test]
     fn test_slot_to_key() {
         assert_eq!(slot_to_key(0), "0000000000000000");
         assert_eq!(slot_to_key(!0), "ffffffffffffffff");
     }
+
+    fn confirmed_block(parent_slot: Slot, payer: &Keypair, to: &Pubkey) -> VersionedConfirmedBlock {
+        let transaction = system_transaction::transfer(payer, to, 42, Hash::new_unique());
+        VersionedConfirmedBlock {
+            previous_blockhash: Hash::new_unique().to_string(),
+            blockhash: Hash::new_unique().to_string(),
+            parent_slot,
+            transactions: vec![VersionedTransactionWithStatusMeta {
+                transaction: VersionedTransaction::from(transaction),
+                meta: TransactionStatusMeta {
+                    fee: 5000,
+                    pre_balances: vec![100_000, 0, 1],
+                    post_balances: vec![94_958, 42, 1],
+                    ..TransactionStatusMeta::default()
+                },
+            }],
+            rewards: vec![],
+            num_partitions: None,
+            block_time: Some(1_700_000_000 + parent_slot as UnixTimestamp),
+            block_height: Some(parent_slot + 1),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_local_ledger_storage() {
+        let ledger_path = tempfile::tempdir().unwrap();
+        let storage = LedgerStorage::new_with_config(LedgerStorageConfig {
+            read_only: false,
+            local_storage_path: Some(ledger_path.path().to_path_buf()),
+            ..LedgerStorageConfig::default()
+        })
+        .await
+        .unwrap();
+
+        let payer = Keypair::new();
+        let to = Pubkey::new_unique();
+        let mut signatures = vec![];
+        for slot in [2, 3, 5] {
+            let block = confirmed_block(slot - 1, &payer, &to);
+            signatures.push(block.transactions[0].transaction.signatures[0]);
+            storage.upload_confirmed_block(slot, block).await.unwrap();
+        }
+
+        assert_eq!(storage.get_first_available_block().await.unwrap(), Some(2));
+        assert_eq!(
+            storage.get_confirmed_blocks(3, 10).await.unwrap(),
+            vec![3, 5]
+        );
+        assert!(storage.confirmed_block_exists(5).await.unwrap());
+        assert!(!storage.confirmed_block_exists(4).await.unwrap());
+        assert!(matches!(
+            storage.get_confirmed_block(4).await,
+            Err(Error::BlockNotFound(4))
+        ));
+        let block = storage.get_confirmed_block(3).await.unwrap();
+        assert_eq!(block.parent_slot, 2);
+        assert_eq!(
+            block.transactions[0].transaction_signature(),
+            &signatures[1]
+        );
+
+        let transaction = storage
+            .get_confirmed_transaction(&signatures[2])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(transaction.slot, 5);
+
+        // Signatures are listed from newest to oldest
+        let listed_signatures = |infos: Vec<(ConfirmedTransactionStatusWithSignature, u32)>| {
+            infos
+                .into_iter()
+                .map(|(info, _index)| (info.slot, info.signature))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            listed_signatures(
+                storage
+                    .get_confirmed_signatures_for_address(&to, None, None, 10)
+                    .await
+                    .unwrap()
+            ),
+            vec![(5, signatures[2]), (3, signatures[1]), (2, signatures[0])]
+        );
+        assert_eq!(
+            listed_signatures(
+                storage
+                    .get_confirmed_signatures_for_address(&to, Some(&signatures[2]), None, 1)
+                    .await
+                    .unwrap()
+            ),
+            vec![(3, signatures[1])]
+        );
+
+        storage.delete_confirmed_block(3, false).await.unwrap();
+        assert_eq!(
+            storage.get_confirmed_blocks(0, 10).await.unwrap(),
+            vec![2, 5]
+        );
+        assert!(matches!(
+            storage.get_signature_status(&signatures[1]).await,
+            Err(Error::SignatureNotFound)
+        ));
+        assert_eq!(
+            storage
+                .get_confirmed_signatures_for_address(&payer.pubkey(), None, None, 10)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
 }
@@ -0,0 +1,477 @@
+//! A `LedgerStorageBackend` keeping the tables in a local RocksDB database, for running
+//! long-term ledger storage on a single machine without access to BigTable.
+//!
+//! Each table is a column family. Row keys are stored as is, so rows are ordered the same way
+//! as in BigTable, and each row is stored as the bincode serialized list of its cells.
+//!
+//! RocksDB calls block, so they run on tokio's blocking thread pool. A read-only instance opens
+//! the database as a secondary instance, which can follow a primary instance held by another
+//! process, such as an upload running in parallel.
+
+use {
+    crate::backend::{Error, LedgerStorageBackend, Result, RowData, RowKey},
+    async_trait::async_trait,
+    log::*,
+    rocksdb::{ColumnFamily, Direction, IteratorMode, Options, WriteBatch, DB},
+    std::{
+        collections::{hash_map::Entry, HashMap},
+        path::Path,
+        sync::{Arc, Mutex},
+    },
+    tokio::task::spawn_blocking,
+};
+
+/// The tables used by `LedgerStorage`
+const TABLES: &[&str] = &["blocks", "entries", "tx", "tx-by-addr"];
+
+/// Where a read-only instance keeps its own files, within the database directory
+const SECONDARY_DIRECTORY: &str = "secondary";
+
+impl std::convert::From<rocksdb::Error> for Error {
+    fn from(err: rocksdb::Error) -> Self {
+        Self::LocalStorage(err.to_string())
+    }
+}
+
+pub struct LocalStorage {
+    inner: Arc<LocalStorageInner>,
+}
+
+struct LocalStorageInner {
+    db: DB,
+    /// Whether `db` is a secondary instance, which has to catch up with the primary to see
+    /// its writes
+    secondary: bool,
+    /// Held across the read-modify-write of `put_row_data`, so concurrent writes to the same
+    /// row don't lose cells
+    write_lock: Mutex<()>,
+}
+
+impl LocalStorage {
+    /// Open the database at `path`, creating it unless `read_only` is set
+    pub fn open(path: &Path, read_only: bool) -> Result<Self> {
+        let db = if read_only {
+            let secondary_path = path.join(SECONDARY_DIRECTORY);
+            info!(
+                "Opening local ledger storage with secondary (read only) access at {}",
+                secondary_path.display()
+            );
+            let mut options = Options::default();
+            // Required for a secondary instance
+            options.set_max_open_files(-1);
+            DB::open_cf_as_secondary(&options, path, secondary_path.as_path(), TABLES)?
+        } else {
+            let mut options = Options::default();
+            options.create_if_missing(true);
+            options.create_missing_column_families(true);
+            DB::open_cf(&options, path, TABLES)?
+        };
+        Ok(Self {
+            inner: Arc::new(LocalStorageInner {
+                db,
+                secondary: read_only,
+                write_lock: Mutex::default(),
+            }),
+        })
+    }
+
+    /// Run `f` on the blocking thread pool
+    async fn run<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&LocalStorageInner) -> Result<T> + Send + 'static,
+    {
+        let inner = self.inner.clone();
+        spawn_blocking(move || f(&inner))
+            .await
+            .map_err(|err| Error::LocalStorage(err.to_string()))?
+    }
+}
+
+impl LocalStorageInner {
+    fn table(&self, table_name: &str) -> Result<&ColumnFamily> {
+        self.db
+            .cf_handle(table_name)
+            .ok_or_else(|| Error::LocalStorage(format!("Unknown table: {table_name}")))
+    }
+
+    /// Pick up the writes made by the primary instance since the last read
+    fn catch_up(&self) -> Result<()> {
+        if self.secondary {
+            self.db.try_catch_up_with_primary()?;
+        }
+        Ok(())
+    }
+
+    fn get_row(&self, table_name: &str, row_key: &RowKey) -> Result<Option<RowData>> {
+        self.db
+            .get_pinned_cf(self.table(table_name)?, row_key)?
+            .map(|value| deserialize_row(table_name, row_key, &value))
+            .transpose()
+    }
+
+    /// Visit the rows of `table_name` within the range described by `get_row_keys`, until
+    /// `visit` returns false
+    fn scan_rows(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        mut visit: impl FnMut(RowKey, &[u8]) -> Result<bool>,
+    ) -> Result<()> {
+        let mode = match &start_at {
+            Some(start_at) => IteratorMode::From(start_at.as_bytes(), Direction::Forward),
+            None => IteratorMode::Start,
+        };
+        for item in self.db.iterator_cf(self.table(table_name)?, mode) {
+            let (key, value) = item?;
+            if let Some(end_at) = &end_at {
+                if key.as_ref() > end_at.as_bytes() {
+                    break;
+                }
+            }
+            let row_key = String::from_utf8(key.into_vec()).map_err(|_| {
+                Error::ObjectCorrupt(format!("{table_name}: row key is not valid UTF-8"))
+            })?;
+            if !visit(row_key, &value)? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn get_row_keys(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<RowKey>> {
+        let mut row_keys = vec![];
+        if rows_limit <= 0 {
+            return Ok(row_keys);
+        }
+        self.catch_up()?;
+        self.scan_rows(table_name, start_at, end_at, |row_key, _value| {
+            row_keys.push(row_key);
+            Ok((row_keys.len() as i64) < rows_limit)
+        })?;
+        Ok(row_keys)
+    }
+
+    fn row_key_exists(&self, table_name: &str, row_key: &RowKey) -> Result<bool> {
+        self.catch_up()?;
+        Ok(self
+            .db
+            .get_pinned_cf(self.table(table_name)?, row_key)?
+            .is_some())
+    }
+
+    fn get_row_data(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        let mut rows = vec![];
+        if rows_limit <= 0 {
+            return Ok(rows);
+        }
+        self.catch_up()?;
+        self.scan_rows(table_name, start_at, end_at, |row_key, value| {
+            let row_data = deserialize_row(table_name, &row_key, value)?;
+            rows.push((row_key, row_data));
+            Ok((rows.len() as i64) < rows_limit)
+        })?;
+        Ok(rows)
+    }
+
+    fn get_multi_row_data(
+        &self,
+        table_name: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        self.catch_up()?;
+        let mut rows = vec![];
+        for row_key in row_keys {
+            if let Some(row_data) = self.get_row(table_name, row_key)? {
+                rows.push((row_key.clone(), row_data));
+            }
+        }
+        Ok(rows)
+    }
+
+    fn get_single_row_data(&self, table_name: &str, row_key: &RowKey) -> Result<RowData> {
+        self.catch_up()?;
+        self.get_row(table_name, row_key)?.ok_or(Error::RowNotFound)
+    }
+
+    fn put_row_data(&self, table_name: &str, row_data: Vec<(RowKey, RowData)>) -> Result<()> {
+        let table = self.table(table_name)?;
+        let _write_lock = self.write_lock.lock().unwrap();
+        // Merge into the rows as already updated by this call, should a row appear twice
+        let mut rows: HashMap<RowKey, RowData> = HashMap::with_capacity(row_data.len());
+        for (row_key, new_cells) in row_data {
+            let cells = match rows.entry(row_key) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let cells = self.get_row(table_name, entry.key())?.unwrap_or_default();
+                    entry.insert(cells)
+                }
+            };
+            for (name, value) in new_cells {
+                match cells.iter_mut().find(|(cell_name, _)| *cell_name == name) {
+                    Some((_, cell_value)) => *cell_value = value,
+                    None => cells.push((name, value)),
+                }
+            }
+        }
+        // All rows are written at once, or none are
+        let mut batch = WriteBatch::default();
+        for (row_key, cells) in rows {
+            let value =
+                bincode::serialize(&cells).map_err(|err| Error::LocalStorage(err.to_string()))?;
+            batch.put_cf(table, row_key.as_bytes(), value);
+        }
+        self.db.write(batch)?;
+        Ok(())
+    }
+
+    fn delete_rows(&self, table_name: &str, row_keys: &[RowKey]) -> Result<()> {
+        let table = self.table(table_name)?;
+        let mut batch = WriteBatch::default();
+        for row_key in row_keys {
+            batch.delete_cf(table, row_key.as_bytes());
+        }
+        self.db.write(batch)?;
+        Ok(())
+    }
+}
+
+fn deserialize_row(table_name: &str, row_key: &RowKey, value: &[u8]) -> Result<RowData> {
+    bincode::deserialize(value).map_err(|_| Error::ObjectCorrupt(format!("{table_name}/{row_key}")))
+}
+
+#[async_trait]
+impl LedgerStorageBackend for LocalStorage {
+    async fn get_row_keys(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<RowKey>> {
+        let table_name = table_name.to_string();
+        self.run(move |inner| inner.get_row_keys(&table_name, start_at, end_at, rows_limit))
+            .await
+    }
+
+    async fn row_key_exists(&self, table_name: &str, row_key: RowKey) -> Result<bool> {
+        let table_name = table_name.to_string();
+        self.run(move |inner| inner.row_key_exists(&table_name, &row_key))
+            .await
+    }
+
+    async fn get_row_data(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        let table_name = table_name.to_string();
+        self.run(move |inner| inner.get_row_data(&table_name, start_at, end_at, rows_limit))
+            .await
+    }
+
+    async fn get_multi_row_data(
+        &self,
+        table_name: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        let table_name = table_name.to_string();
+        let row_keys = row_keys.to_vec();
+        self.run(move |inner| inner.get_multi_row_data(&table_name, &row_keys))
+            .await
+    }
+
+    async fn get_single_row_data(&self, table_name: &str, row_key: RowKey) -> Result<RowData> {
+        let table_name = table_name.to_string();
+        self.run(move |inner| inner.get_single_row_data(&table_name, &row_key))
+            .await
+    }
+
+    async fn put_row_data(
+        &self,
+        table_name: &str,
+        _family_name: &str,
+        row_data: &[(&RowKey, RowData)],
+    ) -> Result<()> {
+        let table_name = table_name.to_string();
+        let row_data: Vec<_> = row_data
+            .iter()
+            .map(|(row_key, cells)| ((*row_key).clone(), cells.clone()))
+            .collect();
+        self.run(move |inner| inner.put_row_data(&table_name, row_data))
+            .await
+    }
+
+    async fn delete_rows(&self, table_name: &str, row_keys: &[RowKey]) -> Result<()> {
+        let table_name = table_name.to_string();
+        let row_keys = row_keys.to_vec();
+        self.run(move |inner| inner.delete_rows(&table_name, &row_keys))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(value: u8) -> RowData {
+        vec![("bin".to_string(), vec![value])]
+    }
+
+    #[tokio::test]
+    async fn test_local_storage_rows() {
+        let ledger_path = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::open(ledger_path.path(), false).unwrap();
+
+        let keys: Vec<RowKey> = ["a", "b/1", "b/2", "c"]
+            .iter()
+            .map(|k| k.to_string())
+            .collect();
+        let rows: Vec<_> = keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| (key, row(i as u8)))
+            .collect();
+        storage.put_row_data("tx", "x", &rows).await.unwrap();
+
+        assert_eq!(
+            storage.get_row_keys("tx", None, None, 10).await.unwrap(),
+            keys
+        );
+        assert_eq!(
+            storage
+                .get_row_keys("tx", Some("b".to_string()), Some("b/2".to_string()), 10)
+                .await
+                .unwrap(),
+            &keys[1..3]
+        );
+        assert!(storage
+            .get_row_keys("tx", None, None, 0)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            storage
+                .get_row_data("tx", Some("b/2".to_string()), None, 1)
+                .await
+                .unwrap(),
+            vec![(keys[2].clone(), row(2))]
+        );
+        assert_eq!(
+            storage
+                .get_multi_row_data("tx", &[keys[3].clone(), "missing".to_string()])
+                .await
+                .unwrap(),
+            vec![(keys[3].clone(), row(3))]
+        );
+        assert!(storage.row_key_exists("tx", keys[0].clone()).await.unwrap());
+        assert!(!storage
+            .row_key_exists("blocks", keys[0].clone())
+            .await
+            .unwrap());
+
+        // Cells are replaced by name, other cells of the row are kept
+        let proto = vec![("proto".to_string(), vec![7])];
+        storage
+            .put_row_data("tx", "x", &[(&keys[0], proto), (&keys[1], row(9))])
+            .await
+            .unwrap();
+        assert_eq!(
+            storage
+                .get_single_row_data("tx", keys[0].clone())
+                .await
+                .unwrap(),
+            vec![("bin".to_string(), vec![0]), ("proto".to_string(), vec![7])]
+        );
+        assert_eq!(
+            storage
+                .get_single_row_data("tx", keys[1].clone())
+                .await
+                .unwrap(),
+            row(9)
+        );
+
+        storage.delete_rows("tx", &keys[..2]).await.unwrap();
+        assert!(matches!(
+            storage.get_single_row_data("tx", keys[0].clone()).await,
+            Err(Error::RowNotFound)
+        ));
+        assert_eq!(
+            storage.get_row_keys("tx", None, None, 10).await.unwrap(),
+            &keys[2..]
+        );
+        assert!(matches!(
+            storage.get_row_keys("unknown", None, None, 10).await,
+            Err(Error::LocalStorage(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_local_storage_put_same_row_twice() {
+        let ledger_path = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::open(ledger_path.path(), false).unwrap();
+
+        // Both updates of the row are kept
+        let key = "a".to_string();
+        let proto = vec![("proto".to_string(), vec![7])];
+        storage
+            .put_row_data("tx", "x", &[(&key, row(1)), (&key, proto)])
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.get_single_row_data("tx", key).await.unwrap(),
+            vec![("bin".to_string(), vec![1]), ("proto".to_string(), vec![7])]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_local_storage_read_only() {
+        let ledger_path = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::open(ledger_path.path(), false).unwrap();
+        let keys: Vec<RowKey> = ["a", "b"].iter().map(|k| k.to_string()).collect();
+        storage
+            .put_row_data("blocks", "x", &[(&keys[0], row(0))])
+            .await
+            .unwrap();
+
+        // A read-only instance opens while the primary is still open, and follows its writes
+        let read_only_storage = LocalStorage::open(ledger_path.path(), true).unwrap();
+        assert_eq!(
+            read_only_storage
+                .get_row_keys("blocks", None, None, 10)
+                .await
+                .unwrap(),
+            &keys[..1]
+        );
+        storage
+            .put_row_data("blocks", "x", &[(&keys[1], row(1))])
+            .await
+            .unwrap();
+        assert_eq!(
+            read_only_storage
+                .get_single_row_data("blocks", keys[1].clone())
+                .await
+                .unwrap(),
+            row(1)
+        );
+        assert!(read_only_storage
+            .delete_rows("blocks", &keys)
+            .await
+            .is_err());
+    }
+}
@@ -24,6 +24,7 @@ use {
     smallvec::SmallVec,
     solana_keypair::Keypair,
     solana_measure::measure::Measure,
+    solana_metrics::transaction_tracing::{self, TransactionSpan},
     solana_packet::{Meta, PACKET_DATA_SIZE},
     solana_perf::packet::{PacketBatch, PACKETS_PER_BATCH},
     solana_pubkey::Pubkey,
@@ -36,7 +37,9 @@ use {
     solana_signature::Signature,
     solana_time_utils as timing,
     solana_tls_utils::get_pubkey_from_tls_certificate,
-    solana_transaction_metrics_tracker::signature_if_should_track_packet,
+    solana_transaction_metrics_tracker::{
+        get_signature_from_packet, signature_if_should_track_packet,
+    },
     std::{
         array,
         fmt,
@@ -892,6 +895,7 @@ async fn packet_batch_sender(
     let mut batch_start_time = Instant::now();
     loop {
         let mut packet_perf_measure: Vec<([u8; 64], Instant)> = Vec::default();
+        let mut fetch_spans: Vec<TransactionSpan> = Vec::default();
         let mut packet_batch = PacketBatch::with_capacity(PACKETS_PER_BATCH);
         let mut total_bytes: usize = 0;
 
@@ -913,7 +917,15 @@ async fn packet_batch_sender(
                 let len = packet_batch.len();
                 track_streamer_fetch_packet_performance(&packet_perf_measure, &stats);
 
-                if let Err(e) = packet_sender.send(packet_batch) {
+                let send_result = packet_sender.send(packet_batch);
+                for mut span in fetch_spans.drain(..) {
+                    if send_result.is_err() {
+                        span.set_error("failed to send packet batch");
+                    }
+                    span.end();
+                }
+
+                if let Err(e) = send_result {
                     stats
                         .total_packet_batch_send_err
                         .fetch_add(1, Ordering::Relaxed);
@@ -980,6 +992,21 @@ async fn packet_batch_sender(
                     // we set the PERF_TRACK_PACKET on
                     packet_batch[i].meta_mut().set_track_performance(true);
                 }
+                if transaction_tracing::is_enabled() {
+                    if let Some(span) =
+                        get_signature_from_packet(&packet_batch[i])
+                            .ok()
+                            .and_then(|signature| {
+                                TransactionSpan::start_at_instant(
+                                    signature,
+                                    "fetch",
+                                    packet_accumulator.start_time,
+                                )
+                            })
+                    {
+                        fetch_spans.push(span);
+                    }
+                }
                 stats
                     .total_chunks_processed_by_batcher
                     .fetch_add(num_chunks, Ordering::Relaxed);
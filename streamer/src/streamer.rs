@@ -10,9 +10,11 @@ use {
     crossbeam_channel::{Receiver, RecvTimeoutError, SendError, Sender},
     histogram::Histogram,
     itertools::Itertools,
+    solana_metrics::transaction_tracing,
     solana_packet::Packet,
     solana_pubkey::Pubkey,
     solana_time_utils::timestamp,
+    solana_transaction_metrics_tracker::get_signature_from_packet,
     std::{
         cmp::Reverse,
         collections::HashMap,
@@ -153,6 +155,14 @@ fn recv_loop(
                     packet_batch
                         .iter_mut()
                         .for_each(|p| p.meta_mut().set_from_staked_node(is_staked_service));
+                    if transaction_tracing::is_enabled() {
+                        packet_batch
+                            .iter()
+                            .filter_map(|packet| get_signature_from_packet(packet).ok())
+                            .for_each(|signature| {
+                                transaction_tracing::record_event(signature, "fetch", None)
+                            });
+                    }
                     packet_batch_sender.send(packet_batch)?;
                 }
                 break;
@@ -333,6 +333,8 @@ impl JsonRpcRequestProcessor {
                     enable_return_data_recording: true,
                 },
                 transaction_account_lock_limit: Some(64),
+                record_state_diffs: false,
+                record_instruction_traces: false,
             },
         );
 
@@ -659,6 +661,9 @@ pub mod rpc {
                 accounts: config_accounts,
                 min_context_slot: _,
                 inner_instructions: enable_cpi_recording,
+                account_overrides: _,
+                clock_override: _,
+                state_diffs: _,
             } = config.unwrap_or_default();
             let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
             let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
@@ -749,6 +754,7 @@ pub mod rpc {
                     return_data: return_data.map(|return_data| return_data.into()),
                     inner_instructions,
                     replacement_blockhash: None,
+                    state_diffs: None,
                 },
             ))
         }
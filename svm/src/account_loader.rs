@@ -24,7 +24,6 @@ use {
         sysvar::{
             self,
             instructions::{construct_instructions_data, BorrowedAccountMeta, BorrowedInstruction},
-            slot_history,
         },
         transaction::{Result, TransactionError},
         transaction_context::{IndexOfAccount, TransactionAccount},
@@ -117,12 +116,13 @@ impl<'a, CB: TransactionProcessingCallback> AccountLoader<'a, CB> {
     ) -> AccountLoader<'a, CB> {
         let mut account_cache = AHashMap::with_capacity(capacity);
 
-        // SlotHistory may be overridden for simulation.
-        // No other uses of AccountOverrides are expected.
-        if let Some(slot_history) =
-            account_overrides.and_then(|overrides| overrides.get(&slot_history::id()))
-        {
-            account_cache.insert(slot_history::id(), slot_history.clone());
+        // Accounts, such as SlotHistory, may be overridden for simulation.
+        if let Some(account_overrides) = account_overrides {
+            account_cache.extend(
+                account_overrides
+                    .iter()
+                    .map(|(pubkey, account)| (*pubkey, account.clone())),
+            );
         }
 
         Self {
@@ -193,6 +193,21 @@ impl<'a, CB: TransactionProcessingCallback> AccountLoader<'a, CB> {
         })
     }
 
+    /// Get the current state of an account, including the changes made by the transactions
+    /// processed so far, without inspecting it. Missing accounts are returned as default.
+    pub(crate) fn peek_account(&mut self, account_key: &Pubkey) -> AccountSharedData {
+        if let Some(account) = self.account_cache.get(account_key) {
+            return account.clone();
+        }
+        match self.callbacks.get_account_shared_data(account_key) {
+            Some(account) => {
+                self.account_cache.insert(*account_key, account.clone());
+                account
+            }
+            None => AccountSharedData::default(),
+        }
+    }
+
     pub fn update_accounts_for_executed_tx(
         &mut self,
         message: &impl SVMMessage,
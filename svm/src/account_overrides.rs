@@ -1,11 +1,17 @@
 use {
-    solana_sdk::{account::AccountSharedData, pubkey::Pubkey, sysvar},
+    crate::transaction_processing_callback::TransactionProcessingCallback,
+    solana_sdk::{
+        account::{from_account, AccountSharedData, ReadableAccount},
+        clock::Clock,
+        pubkey::Pubkey,
+        sysvar,
+    },
     std::collections::HashMap,
 };
 
 /// Encapsulates overridden accounts, typically used for transaction
-/// simulations. Account overrides are currently not used when loading the
-/// durable nonce account or when constructing the instructions sysvar account.
+/// simulations. Account overrides are currently not used when constructing
+/// the instructions sysvar account.
 #[derive(Default)]
 pub struct AccountOverrides {
     accounts: HashMap<Pubkey, AccountSharedData>,
@@ -13,7 +19,12 @@ pub struct AccountOverrides {
 
 impl AccountOverrides {
     /// Insert or remove an account with a given pubkey to/from the list of overrides.
-    fn set_account(&mut self, pubkey: &Pubkey, account: Option<AccountSharedData>) {
+    ///
+    /// Overridden programs are loaded for the transaction batch only, and the
+    /// clock account also overrides the clock sysvar seen by programs. Other
+    /// sysvars are read by programs from the sysvar cache, so overriding their
+    /// accounts doesn't change them.
+    pub fn set_account(&mut self, pubkey: &Pubkey, account: Option<AccountSharedData>) {
         match account {
             Some(account) => self.accounts.insert(*pubkey, account),
             None => self.accounts.remove(pubkey),
@@ -27,18 +38,76 @@ impl AccountOverrides {
         self.set_account(&sysvar::slot_history::id(), slot_history);
     }
 
+    /// Sets in the clock
+    ///
+    /// Note: no checks are performed on the correctness of the contained data
+    pub fn set_clock(&mut self, clock: Option<AccountSharedData>) {
+        self.set_account(&sysvar::clock::id(), clock);
+    }
+
     /// Gets the account if it's found in the list of overrides
     pub fn get(&self, pubkey: &Pubkey) -> Option<&AccountSharedData> {
         self.accounts.get(pubkey)
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Pubkey, &AccountSharedData)> {
+        self.accounts.iter()
+    }
+
+    /// Gets the overridden clock, if it can be deserialized
+    pub fn clock(&self) -> Option<Clock> {
+        self.get(&sysvar::clock::id()).and_then(from_account)
+    }
+}
+
+/// Wraps the callbacks of a transaction batch so that overridden accounts are
+/// returned in place of the stored ones.
+pub(crate) struct AccountOverridesCallback<'a, CB: TransactionProcessingCallback> {
+    account_overrides: &'a AccountOverrides,
+    callbacks: &'a CB,
+}
+
+impl<'a, CB: TransactionProcessingCallback> AccountOverridesCallback<'a, CB> {
+    pub(crate) fn new(account_overrides: &'a AccountOverrides, callbacks: &'a CB) -> Self {
+        Self {
+            account_overrides,
+            callbacks,
+        }
+    }
+}
+
+impl<CB: TransactionProcessingCallback> TransactionProcessingCallback
+    for AccountOverridesCallback<'_, CB>
+{
+    fn account_matches_owners(&self, account: &Pubkey, owners: &[Pubkey]) -> Option<usize> {
+        match self.account_overrides.get(account) {
+            Some(account) if account.lamports() == 0 => None,
+            Some(account) => owners.iter().position(|owner| account.owner() == owner),
+            None => self.callbacks.account_matches_owners(account, owners),
+        }
+    }
+
+    fn get_account_shared_data(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        match self.account_overrides.get(pubkey) {
+            Some(account) if account.lamports() == 0 => None,
+            Some(account) => Some(account.clone()),
+            None => self.callbacks.get_account_shared_data(pubkey),
+        }
+    }
+
+    fn get_current_epoch_vote_account_stake(&self, vote_address: &Pubkey) -> u64 {
+        self.callbacks
+            .get_current_epoch_vote_account_stake(vote_address)
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use {
-        crate::account_overrides::AccountOverrides,
-        solana_sdk::{account::AccountSharedData, pubkey::Pubkey, sysvar},
-    };
+    use {super::*, solana_sdk::account::create_account_shared_data_for_test};
 
     #[test]
     fn test_set_account() {
@@ -62,4 +131,71 @@ mod test {
 
         assert_eq!(accounts.get(&sysvar::slot_history::id()), Some(&data));
     }
+
+    #[test]
+    fn test_clock() {
+        let mut accounts = AccountOverrides::default();
+        assert_eq!(accounts.clock(), None);
+
+        let clock = Clock {
+            slot: 42,
+            unix_timestamp: 1_700_000_000,
+            ..Clock::default()
+        };
+        accounts.set_clock(Some(create_account_shared_data_for_test(&clock)));
+        assert_eq!(accounts.clock(), Some(clock));
+    }
+
+    #[test]
+    fn test_account_overrides_callback() {
+        struct MockCallback(HashMap<Pubkey, AccountSharedData>);
+        impl TransactionProcessingCallback for MockCallback {
+            fn account_matches_owners(&self, account: &Pubkey, owners: &[Pubkey]) -> Option<usize> {
+                let account = self.0.get(account)?;
+                owners.iter().position(|owner| account.owner() == owner)
+            }
+
+            fn get_account_shared_data(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+                self.0.get(pubkey).cloned()
+            }
+        }
+
+        let owner = Pubkey::new_unique();
+        let stored_key = Pubkey::new_unique();
+        let overridden_key = Pubkey::new_unique();
+        let closed_key = Pubkey::new_unique();
+        let callbacks = MockCallback(HashMap::from([
+            (stored_key, AccountSharedData::new(1, 0, &owner)),
+            (overridden_key, AccountSharedData::new(1, 0, &owner)),
+            (closed_key, AccountSharedData::new(1, 0, &owner)),
+        ]));
+        let mut accounts = AccountOverrides::default();
+        let overridden_account = AccountSharedData::new(2, 1, &Pubkey::new_unique());
+        accounts.set_account(&overridden_key, Some(overridden_account.clone()));
+        accounts.set_account(&closed_key, Some(AccountSharedData::default()));
+        let callbacks = AccountOverridesCallback::new(&accounts, &callbacks);
+
+        assert_eq!(
+            callbacks.get_account_shared_data(&stored_key),
+            Some(AccountSharedData::new(1, 0, &owner))
+        );
+        assert_eq!(
+            callbacks.get_account_shared_data(&overridden_key),
+            Some(overridden_account)
+        );
+        assert_eq!(callbacks.get_account_shared_data(&closed_key), None);
+
+        assert_eq!(
+            callbacks.account_matches_owners(&stored_key, &[owner]),
+            Some(0)
+        );
+        assert_eq!(
+            callbacks.account_matches_owners(&overridden_key, &[owner]),
+            None
+        );
+        assert_eq!(
+            callbacks.account_matches_owners(&closed_key, &[owner]),
+            None
+        );
+    }
 }
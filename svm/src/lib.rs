@@ -8,6 +8,7 @@ pub mod nonce_info;
 pub mod program_loader;
 pub mod rollback_accounts;
 pub mod runtime_config;
+pub mod svm_host;
 pub mod transaction_account_state_info;
 pub mod transaction_commit_result;
 pub mod transaction_error_metrics;
@@ -15,6 +16,7 @@ pub mod transaction_execution_result;
 pub mod transaction_processing_callback;
 pub mod transaction_processing_result;
 pub mod transaction_processor;
+pub mod transaction_state_diff;
 
 #[cfg_attr(feature = "frozen-abi", macro_use)]
 #[cfg(feature = "frozen-abi")]
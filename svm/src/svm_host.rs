@@ -0,0 +1,974 @@
+//! A self-contained host for the SVM.
+//!
+//! `SVMHost` plays the part `Bank` plays in the validator, for users which want to run
+//! transactions outside of a validator, such as simulators or rollup prototypes. It keeps
+//! accounts in memory, registers the builtin programs which live in the SVM's dependencies,
+//! maintains the sysvars as slots advance, checks transactions for recent blockhashes and
+//! replays, and commits the results of processed transactions back to its accounts. Address
+//! lookup tables of versioned transactions are resolved from the accounts in memory.
+//!
+//! The host only knows a single chain of slots. There are no forks, votes or stakes, and
+//! durable nonce transactions are not supported.
+
+#[allow(deprecated)]
+use solana_sdk::sysvar::recent_blockhashes::{IterItem, RecentBlockhashes, MAX_ENTRIES};
+use {
+    crate::{
+        account_loader::{CheckedTransactionDetails, TransactionCheckResult},
+        rollback_accounts::RollbackAccounts,
+        transaction_processing_callback::TransactionProcessingCallback,
+        transaction_processing_result::{ProcessedTransaction, TransactionProcessingResult},
+        transaction_processor::{
+            ExecutionRecordingConfig, LoadAndExecuteSanitizedTransactionsOutput,
+            TransactionBatchProcessor, TransactionProcessingConfig,
+            TransactionProcessingEnvironment,
+        },
+    },
+    solana_bpf_loader_program::syscalls::create_program_runtime_environment_v1,
+    solana_compute_budget::compute_budget::ComputeBudget,
+    solana_feature_set::{
+        enable_program_runtime_v2_and_loader_v4, move_precompile_verification_to_svm, FeatureSet,
+    },
+    solana_program_runtime::{
+        invoke_context::BuiltinFunctionWithContext,
+        loaded_programs::{BlockRelation, ForkGraph, ProgramCacheEntry},
+    },
+    solana_sdk::{
+        account::{
+            create_account_shared_data_with_fields, AccountSharedData, ReadableAccount,
+            WritableAccount,
+        },
+        address_lookup_table::{self, error::AddressLookupError, state::AddressLookupTable},
+        bpf_loader, bpf_loader_deprecated,
+        bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+        clock::{Clock, Epoch, Slot, UnixTimestamp, DEFAULT_MS_PER_SLOT, MAX_PROCESSING_AGE},
+        compute_budget,
+        epoch_schedule::EpochSchedule,
+        hash::{hashv, Hash},
+        loader_v4,
+        message::{
+            v0::{LoadedAddresses, MessageAddressTableLookup},
+            AddressLoaderError,
+        },
+        native_loader,
+        pubkey::Pubkey,
+        rent::Rent,
+        rent_collector::{RentCollector, RENT_EXEMPT_RENT_EPOCH},
+        reserved_account_keys::ReservedAccountKeys,
+        signature::Signature,
+        slot_hashes::SlotHashes,
+        system_program,
+        sysvar::Sysvar,
+        transaction::{
+            self, AddressLoader, MessageHash, SanitizedTransaction, TransactionError,
+            VersionedTransaction,
+        },
+    },
+    solana_svm_transaction::svm_message::SVMMessage,
+    solana_type_overrides::sync::{Arc, RwLock},
+    std::{
+        cmp::Ordering,
+        collections::{HashMap, HashSet, VecDeque},
+        time::{SystemTime, UNIX_EPOCH},
+    },
+};
+
+/// The slot the host starts at. Programs present when the host is created are deployed at
+/// slot 0 and only become visible in the following slot.
+pub const SVM_HOST_INITIAL_SLOT: Slot = 1;
+
+/// The default fee charged per signature
+pub const DEFAULT_LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Accounts kept in memory, keyed by address
+#[derive(Debug, Default)]
+pub struct InMemoryAccounts {
+    accounts: RwLock<HashMap<Pubkey, AccountSharedData>>,
+}
+
+impl InMemoryAccounts {
+    pub fn get_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        self.accounts.read().unwrap().get(pubkey).cloned()
+    }
+
+    /// Store `account` at `pubkey`. Accounts without lamports are removed.
+    pub fn set_account(&self, pubkey: Pubkey, account: AccountSharedData) {
+        let mut accounts = self.accounts.write().unwrap();
+        if account.lamports() == 0 {
+            accounts.remove(&pubkey);
+        } else {
+            accounts.insert(pubkey, account);
+        }
+    }
+
+    /// A copy of all the accounts
+    pub fn snapshot(&self) -> HashMap<Pubkey, AccountSharedData> {
+        self.accounts.read().unwrap().clone()
+    }
+}
+
+impl TransactionProcessingCallback for InMemoryAccounts {
+    fn account_matches_owners(&self, account: &Pubkey, owners: &[Pubkey]) -> Option<usize> {
+        self.accounts
+            .read()
+            .unwrap()
+            .get(account)
+            .filter(|account| account.lamports() != 0)
+            .and_then(|account| owners.iter().position(|owner| account.owner() == owner))
+    }
+
+    fn get_account_shared_data(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        self.get_account(pubkey)
+    }
+
+    fn add_builtin_account(&self, name: &str, program_id: &Pubkey) {
+        let mut accounts = self.accounts.write().unwrap();
+        if !accounts.contains_key(program_id) {
+            accounts.insert(
+                *program_id,
+                native_loader::create_loadable_account_with_fields(name, (1, 0)),
+            );
+        }
+    }
+}
+
+/// The host only knows a single chain of slots, so a slot is an ancestor of every
+/// slot after it
+pub struct SVMHostForkGraph;
+
+impl ForkGraph for SVMHostForkGraph {
+    fn relationship(&self, a: Slot, b: Slot) -> BlockRelation {
+        match a.cmp(&b) {
+            Ordering::Less => BlockRelation::Ancestor,
+            Ordering::Equal => BlockRelation::Equal,
+            Ordering::Greater => BlockRelation::Descendant,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SVMHostConfig {
+    pub feature_set: FeatureSet,
+    pub compute_budget: ComputeBudget,
+    pub rent: Rent,
+    pub epoch_schedule: EpochSchedule,
+    pub lamports_per_signature: u64,
+    /// The unix timestamp of slot 0, later slots are `DEFAULT_MS_PER_SLOT` apart
+    pub genesis_unix_timestamp: UnixTimestamp,
+}
+
+impl Default for SVMHostConfig {
+    fn default() -> Self {
+        Self {
+            feature_set: FeatureSet::all_enabled(),
+            compute_budget: ComputeBudget::default(),
+            rent: Rent::default(),
+            epoch_schedule: EpochSchedule::default(),
+            lamports_per_signature: DEFAULT_LAMPORTS_PER_SIGNATURE,
+            genesis_unix_timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs() as UnixTimestamp,
+        }
+    }
+}
+
+pub struct SVMHost {
+    accounts: InMemoryAccounts,
+    processor: TransactionBatchProcessor<SVMHostForkGraph>,
+    /// Referenced weakly by the program cache, so it must live as long as the processor
+    _fork_graph: Arc<RwLock<SVMHostForkGraph>>,
+    feature_set: Arc<FeatureSet>,
+    compute_budget: ComputeBudget,
+    reserved_account_keys: HashSet<Pubkey>,
+    rent_collector: RentCollector,
+    lamports_per_signature: u64,
+    genesis_unix_timestamp: UnixTimestamp,
+    clock: Clock,
+    slot_hashes: SlotHashes,
+    /// The blockhashes transactions may refer to, oldest first, along with the signatures
+    /// of the transactions committed with them
+    blockhash_queue: VecDeque<(Hash, HashSet<Signature>)>,
+}
+
+impl Default for SVMHost {
+    fn default() -> Self {
+        Self::new(SVMHostConfig::default())
+    }
+}
+
+impl SVMHost {
+    pub fn new(config: SVMHostConfig) -> Self {
+        let SVMHostConfig {
+            feature_set,
+            compute_budget,
+            rent,
+            epoch_schedule,
+            lamports_per_signature,
+            genesis_unix_timestamp,
+        } = config;
+
+        let slot = SVM_HOST_INITIAL_SLOT;
+        let epoch = epoch_schedule.get_epoch(slot);
+        let fork_graph = Arc::new(RwLock::new(SVMHostForkGraph));
+        let processor = TransactionBatchProcessor::new(
+            slot,
+            epoch,
+            Arc::downgrade(&fork_graph),
+            Some(Arc::new(
+                create_program_runtime_environment_v1(&feature_set, &compute_budget, false, false)
+                    .unwrap(),
+            )),
+            None,
+        );
+        let mut reserved_account_keys = ReservedAccountKeys::default();
+        reserved_account_keys.update_active_set(&feature_set);
+
+        let genesis_hash = hashv(&[&0u64.to_le_bytes()]);
+        let blockhash = next_blockhash(&genesis_hash, slot);
+        let host = Self {
+            accounts: InMemoryAccounts::default(),
+            processor,
+            _fork_graph: fork_graph,
+            feature_set: Arc::new(feature_set),
+            compute_budget,
+            reserved_account_keys: reserved_account_keys.active,
+            rent_collector: RentCollector {
+                epoch,
+                epoch_schedule: epoch_schedule.clone(),
+                rent,
+                ..RentCollector::default()
+            },
+            lamports_per_signature,
+            genesis_unix_timestamp,
+            clock: Clock {
+                slot,
+                epoch_start_timestamp: genesis_unix_timestamp,
+                epoch,
+                leader_schedule_epoch: epoch_schedule.get_leader_schedule_epoch(slot),
+                unix_timestamp: slot_unix_timestamp(genesis_unix_timestamp, slot),
+            },
+            slot_hashes: SlotHashes::new(&[(0, genesis_hash)]),
+            blockhash_queue: VecDeque::from([
+                (genesis_hash, HashSet::new()),
+                (blockhash, HashSet::new()),
+            ]),
+        };
+        host.register_builtins();
+        host.update_sysvars();
+        host
+    }
+
+    fn register_builtins(&self) {
+        self.add_builtin(
+            system_program::id(),
+            "system_program",
+            solana_system_program::system_processor::Entrypoint::vm,
+        );
+        self.add_builtin(
+            bpf_loader_deprecated::id(),
+            "solana_bpf_loader_deprecated_program",
+            solana_bpf_loader_program::Entrypoint::vm,
+        );
+        self.add_builtin(
+            bpf_loader::id(),
+            "solana_bpf_loader_program",
+            solana_bpf_loader_program::Entrypoint::vm,
+        );
+        self.add_builtin(
+            bpf_loader_upgradeable::id(),
+            "solana_bpf_loader_upgradeable_program",
+            solana_bpf_loader_program::Entrypoint::vm,
+        );
+        self.add_builtin(
+            compute_budget::id(),
+            "compute_budget_program",
+            solana_compute_budget_program::Entrypoint::vm,
+        );
+        self.add_builtin(
+            address_lookup_table::program::id(),
+            "address_lookup_table_program",
+            solana_address_lookup_table_program::processor::Entrypoint::vm,
+        );
+        if self
+            .feature_set
+            .is_active(&enable_program_runtime_v2_and_loader_v4::id())
+        {
+            self.add_builtin(
+                loader_v4::id(),
+                "loader_v4",
+                solana_loader_v4_program::Entrypoint::vm,
+            );
+        }
+    }
+
+    /// Register a builtin program, in addition to the default ones
+    pub fn add_builtin(
+        &self,
+        program_id: Pubkey,
+        name: &str,
+        builtin_function: BuiltinFunctionWithContext,
+    ) {
+        self.processor.add_builtin(
+            &self.accounts,
+            program_id,
+            name,
+            ProgramCacheEntry::new_builtin(0, name.len(), builtin_function),
+        );
+    }
+
+    pub fn accounts(&self) -> &InMemoryAccounts {
+        &self.accounts
+    }
+
+    pub fn processor(&self) -> &TransactionBatchProcessor<SVMHostForkGraph> {
+        &self.processor
+    }
+
+    pub fn feature_set(&self) -> &Arc<FeatureSet> {
+        &self.feature_set
+    }
+
+    pub fn slot(&self) -> Slot {
+        self.clock.slot
+    }
+
+    pub fn epoch(&self) -> Epoch {
+        self.clock.epoch
+    }
+
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    pub fn rent(&self) -> &Rent {
+        &self.rent_collector.rent
+    }
+
+    pub fn lamports_per_signature(&self) -> u64 {
+        self.lamports_per_signature
+    }
+
+    /// The blockhash of the current slot
+    pub fn latest_blockhash(&self) -> Hash {
+        self.blockhash_queue.back().unwrap().0
+    }
+
+    pub fn is_blockhash_valid(&self, blockhash: &Hash) -> bool {
+        self.blockhash_queue
+            .iter()
+            .any(|(hash, _)| hash == blockhash)
+    }
+
+    pub fn get_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        self.accounts.get_account(pubkey)
+    }
+
+    pub fn set_account(&self, pubkey: Pubkey, account: AccountSharedData) {
+        self.accounts.set_account(pubkey, account);
+    }
+
+    /// Credit `lamports` to `pubkey`, creating a system account if it doesn't exist
+    pub fn airdrop(&self, pubkey: &Pubkey, lamports: u64) {
+        let mut account = self
+            .get_account(pubkey)
+            .unwrap_or_else(|| AccountSharedData::new(0, 0, &system_program::id()));
+        account.checked_add_lamports(lamports).unwrap();
+        self.set_account(*pubkey, account);
+    }
+
+    /// Deploy `elf` as an upgradeable program without an upgrade authority. As in the
+    /// runtime, the program can be invoked starting from the next slot.
+    pub fn add_program(&self, program_id: &Pubkey, elf: &[u8]) {
+        let rent = self.rent();
+        let programdata_address = bpf_loader_upgradeable::get_program_data_address(program_id);
+
+        let mut program_account = AccountSharedData::new_data(
+            rent.minimum_balance(UpgradeableLoaderState::size_of_program()),
+            &UpgradeableLoaderState::Program {
+                programdata_address,
+            },
+            &bpf_loader_upgradeable::id(),
+        )
+        .unwrap();
+        program_account.set_executable(true);
+        self.set_account(*program_id, program_account);
+
+        let metadata_len = UpgradeableLoaderState::size_of_programdata_metadata();
+        let programdata_len = metadata_len + elf.len();
+        let mut programdata_account = AccountSharedData::new(
+            rent.minimum_balance(programdata_len),
+            programdata_len,
+            &bpf_loader_upgradeable::id(),
+        );
+        programdata_account
+            .serialize_data(&UpgradeableLoaderState::ProgramData {
+                slot: self.slot(),
+                upgrade_authority_address: None,
+            })
+            .unwrap();
+        programdata_account.data_as_mut_slice()[metadata_len..].copy_from_slice(elf);
+        self.set_account(programdata_address, programdata_account);
+    }
+
+    /// Move to the next slot
+    pub fn advance_slot(&mut self) {
+        self.warp_to_slot(self.slot() + 1);
+    }
+
+    /// Move to `slot`, which must be after the current slot. The sysvars are updated as if
+    /// the slots in between had been skipped.
+    pub fn warp_to_slot(&mut self, slot: Slot) {
+        assert!(slot > self.slot(), "cannot warp back to slot {slot}");
+        let parent_slot = self.slot();
+        let parent_hash = self.latest_blockhash();
+        let epoch_schedule = &self.rent_collector.epoch_schedule;
+        let epoch = epoch_schedule.get_epoch(slot);
+        let unix_timestamp = slot_unix_timestamp(self.genesis_unix_timestamp, slot);
+
+        self.processor = self.processor.new_from(slot, epoch);
+        self.processor
+            .program_cache
+            .write()
+            .unwrap()
+            .prune(parent_slot, self.clock.epoch);
+
+        self.clock = Clock {
+            slot,
+            epoch_start_timestamp: if epoch != self.clock.epoch {
+                unix_timestamp
+            } else {
+                self.clock.epoch_start_timestamp
+            },
+            epoch,
+            leader_schedule_epoch: epoch_schedule.get_leader_schedule_epoch(slot),
+            unix_timestamp,
+        };
+        self.rent_collector.epoch = epoch;
+        self.slot_hashes.add(parent_slot, parent_hash);
+
+        self.blockhash_queue
+            .push_back((next_blockhash(&parent_hash, slot), HashSet::new()));
+        while self.blockhash_queue.len() > MAX_PROCESSING_AGE + 1 {
+            self.blockhash_queue.pop_front();
+        }
+
+        self.update_sysvars();
+    }
+
+    fn update_sysvars(&self) {
+        self.set_sysvar(&self.clock);
+        self.set_sysvar(&self.rent_collector.rent);
+        self.set_sysvar(&self.rent_collector.epoch_schedule);
+        self.set_sysvar(&self.slot_hashes);
+        #[allow(deprecated)]
+        {
+            let recent_blockhashes: RecentBlockhashes = self
+                .blockhash_queue
+                .iter()
+                .enumerate()
+                .rev()
+                .take(MAX_ENTRIES)
+                .map(|(height, (hash, _))| {
+                    IterItem(height as u64, hash, self.lamports_per_signature)
+                })
+                .collect();
+            self.set_sysvar(&recent_blockhashes);
+        }
+
+        self.processor.reset_sysvar_cache();
+        self.processor
+            .fill_missing_sysvar_cache_entries(&self.accounts);
+    }
+
+    fn set_sysvar<S: Sysvar>(&self, sysvar: &S) {
+        let lamports = self.rent().minimum_balance(S::size_of()).max(1);
+        self.set_account(
+            S::id(),
+            create_account_shared_data_with_fields(sysvar, (lamports, RENT_EXEMPT_RENT_EPOCH)),
+        );
+    }
+
+    /// Verify the signatures of `tx` and sanitize it
+    pub fn sanitize_transaction(
+        &self,
+        tx: VersionedTransaction,
+    ) -> transaction::Result<SanitizedTransaction> {
+        let message_hash = tx.verify_and_hash_message()?;
+        let sanitized_tx = SanitizedTransaction::try_create(
+            tx,
+            MessageHash::Precomputed(message_hash),
+            None,
+            self,
+            &self.reserved_account_keys,
+        )?;
+        if !self
+            .feature_set
+            .is_active(&move_precompile_verification_to_svm::id())
+        {
+            sanitized_tx.verify_precompiles(&self.feature_set)?;
+        }
+        Ok(sanitized_tx)
+    }
+
+    /// Check that the transactions use a recent blockhash and haven't been committed yet
+    pub fn check_transactions(&self, txs: &[SanitizedTransaction]) -> Vec<TransactionCheckResult> {
+        let mut batch_signatures = HashSet::new();
+        txs.iter()
+            .map(|tx| {
+                let signatures = self
+                    .blockhash_queue
+                    .iter()
+                    .find(|(hash, _)| hash == tx.message().recent_blockhash())
+                    .map(|(_, signatures)| signatures)
+                    .ok_or(TransactionError::BlockhashNotFound)?;
+                if signatures.contains(tx.signature()) || !batch_signatures.insert(tx.signature()) {
+                    return Err(TransactionError::AlreadyProcessed);
+                }
+                Ok(CheckedTransactionDetails {
+                    nonce: None,
+                    lamports_per_signature: self.lamports_per_signature,
+                })
+            })
+            .collect()
+    }
+
+    /// Check, load and execute the transactions without committing the results
+    pub fn load_and_execute_transactions(
+        &self,
+        txs: &[SanitizedTransaction],
+        config: &TransactionProcessingConfig,
+    ) -> LoadAndExecuteSanitizedTransactionsOutput {
+        let environment = TransactionProcessingEnvironment {
+            blockhash: self.latest_blockhash(),
+            blockhash_lamports_per_signature: self.lamports_per_signature,
+            epoch_total_stake: 0,
+            feature_set: Arc::clone(&self.feature_set),
+            fee_lamports_per_signature: self.lamports_per_signature,
+            rent_collector: Some(&self.rent_collector),
+        };
+        self.processor.load_and_execute_sanitized_transactions(
+            &self.accounts,
+            txs,
+            self.check_transactions(txs),
+            &environment,
+            config,
+        )
+    }
+
+    /// Store the accounts modified by processed transactions and record their signatures.
+    /// Transactions which failed only pay their fees.
+    pub fn commit_transactions(
+        &mut self,
+        txs: &[SanitizedTransaction],
+        processing_results: &[TransactionProcessingResult],
+    ) {
+        for (tx, processing_result) in txs.iter().zip(processing_results) {
+            let Ok(processed_tx) = processing_result else {
+                continue;
+            };
+            match processed_tx {
+                ProcessedTransaction::Executed(executed_tx) if executed_tx.was_successful() => {
+                    for (i, (address, account)) in
+                        executed_tx.loaded_transaction.accounts.iter().enumerate()
+                    {
+                        if !tx.is_writable(i) || (tx.is_invoked(i) && !tx.is_instruction_account(i))
+                        {
+                            continue;
+                        }
+                        self.set_account(*address, account.clone());
+                    }
+                    if !executed_tx.programs_modified_by_tx.is_empty() {
+                        self.processor
+                            .program_cache
+                            .write()
+                            .unwrap()
+                            .merge(&executed_tx.programs_modified_by_tx);
+                    }
+                }
+                ProcessedTransaction::Executed(executed_tx) => self.commit_rollback_accounts(
+                    tx,
+                    &executed_tx.loaded_transaction.rollback_accounts,
+                ),
+                ProcessedTransaction::FeesOnly(fees_only_tx) => {
+                    self.commit_rollback_accounts(tx, &fees_only_tx.rollback_accounts)
+                }
+            }
+            if let Some((_, signatures)) = self
+                .blockhash_queue
+                .iter_mut()
+                .find(|(hash, _)| hash == tx.message().recent_blockhash())
+            {
+                signatures.insert(*tx.signature());
+            }
+        }
+    }
+
+    fn commit_rollback_accounts(
+        &self,
+        tx: &SanitizedTransaction,
+        rollback_accounts: &RollbackAccounts,
+    ) {
+        match rollback_accounts {
+            RollbackAccounts::FeePayerOnly { fee_payer_account } => {
+                self.set_account(*tx.fee_payer(), fee_payer_account.clone());
+            }
+            RollbackAccounts::SameNonceAndFeePayer { nonce } => {
+                self.set_account(*nonce.address(), nonce.account().clone());
+            }
+            RollbackAccounts::SeparateNonceAndFeePayer {
+                nonce,
+                fee_payer_account,
+            } => {
+                self.set_account(*tx.fee_payer(), fee_payer_account.clone());
+                self.set_account(*nonce.address(), nonce.account().clone());
+            }
+        }
+    }
+
+    /// Sanitize, execute and commit the transactions in the current slot, recording their
+    /// logs, inner instructions and return data
+    pub fn process_transactions(
+        &mut self,
+        txs: Vec<VersionedTransaction>,
+    ) -> Vec<TransactionProcessingResult> {
+        let mut results: Vec<Option<TransactionProcessingResult>> = vec![];
+        let mut sanitized_txs = vec![];
+        for tx in txs {
+            match self.sanitize_transaction(tx) {
+                Ok(sanitized_tx) => {
+                    sanitized_txs.push(sanitized_tx);
+                    results.push(None);
+                }
+                Err(err) => results.push(Some(Err(err))),
+            }
+        }
+
+        let config = TransactionProcessingConfig {
+            check_program_modification_slot: true,
+            compute_budget: Some(self.compute_budget),
+            recording_config: ExecutionRecordingConfig::new_single_setting(true),
+            ..TransactionProcessingConfig::default()
+        };
+        let output = self.load_and_execute_transactions(&sanitized_txs, &config);
+        self.commit_transactions(&sanitized_txs, &output.processing_results);
+
+        let mut processing_results = output.processing_results.into_iter();
+        results
+            .into_iter()
+            .map(|result| result.unwrap_or_else(|| processing_results.next().unwrap()))
+            .collect()
+    }
+
+    pub fn process_transaction(
+        &mut self,
+        tx: impl Into<VersionedTransaction>,
+    ) -> TransactionProcessingResult {
+        self.process_transactions(vec![tx.into()]).pop().unwrap()
+    }
+}
+
+impl AddressLoader for &SVMHost {
+    fn load_addresses(
+        self,
+        address_table_lookups: &[MessageAddressTableLookup],
+    ) -> Result<LoadedAddresses, AddressLoaderError> {
+        let mut loaded_addresses = LoadedAddresses::default();
+        for address_table_lookup in address_table_lookups {
+            let table_account = self
+                .get_account(&address_table_lookup.account_key)
+                .ok_or(AddressLoaderError::LookupTableAccountNotFound)?;
+            if table_account.owner() != &address_lookup_table::program::id() {
+                return Err(AddressLoaderError::InvalidAccountOwner);
+            }
+            let lookup_table = AddressLookupTable::deserialize(table_account.data())
+                .map_err(|_| AddressLoaderError::InvalidAccountData)?;
+            let lookup = |indexes: &[u8]| {
+                lookup_table
+                    .lookup(self.slot(), indexes, &self.slot_hashes)
+                    .map_err(into_address_loader_error)
+            };
+            loaded_addresses
+                .writable
+                .extend(lookup(&address_table_lookup.writable_indexes)?);
+            loaded_addresses
+                .readonly
+                .extend(lookup(&address_table_lookup.readonly_indexes)?);
+        }
+        Ok(loaded_addresses)
+    }
+}
+
+fn into_address_loader_error(err: AddressLookupError) -> AddressLoaderError {
+    match err {
+        AddressLookupError::LookupTableAccountNotFound => {
+            AddressLoaderError::LookupTableAccountNotFound
+        }
+        AddressLookupError::InvalidAccountOwner => AddressLoaderError::InvalidAccountOwner,
+        AddressLookupError::InvalidAccountData => AddressLoaderError::InvalidAccountData,
+        AddressLookupError::InvalidLookupIndex => AddressLoaderError::InvalidLookupIndex,
+    }
+}
+
+fn next_blockhash(parent_hash: &Hash, slot: Slot) -> Hash {
+    hashv(&[parent_hash.as_ref(), &slot.to_le_bytes()])
+}
+
+fn slot_unix_timestamp(genesis_unix_timestamp: UnixTimestamp, slot: Slot) -> UnixTimestamp {
+    genesis_unix_timestamp + (slot * DEFAULT_MS_PER_SLOT / 1_000) as UnixTimestamp
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            transaction_processing_result::TransactionProcessingResultExtensions,
+            transaction_state_diff::DataRangeDiff,
+        },
+        solana_sdk::{
+            address_lookup_table::instruction::{create_lookup_table, extend_lookup_table},
+            instruction::InstructionError,
+            message::{v0, AddressLookupTableAccount, VersionedMessage},
+            native_token::LAMPORTS_PER_SOL,
+            signature::{Keypair, Signer},
+            system_instruction, system_transaction,
+            transaction::Transaction,
+        },
+    };
+
+    #[test]
+    fn test_svm_host_transfer() {
+        let mut host = SVMHost::default();
+        let fee = host.lamports_per_signature();
+        let payer = Keypair::new();
+        let recipient = Pubkey::new_unique();
+        host.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL);
+
+        let tx =
+            system_transaction::transfer(&payer, &recipient, 1_000_000, host.latest_blockhash());
+        let result = host.process_transaction(tx.clone());
+        assert_eq!(result.flattened_result(), Ok(()));
+        assert_eq!(host.get_account(&recipient).unwrap().lamports(), 1_000_000);
+        assert_eq!(
+            host.get_account(&payer.pubkey()).unwrap().lamports(),
+            LAMPORTS_PER_SOL - 1_000_000 - fee
+        );
+
+        // The same transaction can't be committed twice
+        assert_eq!(
+            host.process_transaction(tx).flattened_result(),
+            Err(TransactionError::AlreadyProcessed)
+        );
+
+        // A failed transaction only pays the fee
+        let tx = system_transaction::transfer(
+            &payer,
+            &recipient,
+            LAMPORTS_PER_SOL,
+            host.latest_blockhash(),
+        );
+        assert_eq!(
+            host.process_transaction(tx).flattened_result(),
+            Err(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(1)
+            ))
+        );
+        assert_eq!(host.get_account(&recipient).unwrap().lamports(), 1_000_000);
+        assert_eq!(
+            host.get_account(&payer.pubkey()).unwrap().lamports(),
+            LAMPORTS_PER_SOL - 1_000_000 - 2 * fee
+        );
+
+        // Unknown blockhashes and bad signatures are rejected
+        let tx = system_transaction::transfer(&payer, &recipient, 1, Hash::new_unique());
+        assert_eq!(
+            host.process_transaction(tx).flattened_result(),
+            Err(TransactionError::BlockhashNotFound)
+        );
+        let mut tx = system_transaction::transfer(&payer, &recipient, 2, host.latest_blockhash());
+        tx.signatures[0] = Signature::default();
+        assert_eq!(
+            host.process_transaction(tx).flattened_result(),
+            Err(TransactionError::SignatureFailure)
+        );
+    }
+
+    #[test]
+    fn test_svm_host_state_diffs() {
+        let host = SVMHost::default();
+        let fee = host.lamports_per_signature();
+        let payer = Keypair::new();
+        let recipient = Keypair::new();
+        host.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL);
+
+        let blockhash = host.latest_blockhash();
+        let txs = [
+            system_transaction::transfer(&payer, &recipient.pubkey(), 1_000_000, blockhash),
+            system_transaction::allocate(&payer, &recipient, blockhash, 8),
+            system_transaction::transfer(&payer, &recipient.pubkey(), LAMPORTS_PER_SOL, blockhash),
+        ]
+        .map(|tx| host.sanitize_transaction(tx.into()).unwrap());
+        let config = TransactionProcessingConfig {
+            record_state_diffs: true,
+            ..TransactionProcessingConfig::default()
+        };
+        let output = host.load_and_execute_transactions(&txs, &config);
+        assert_eq!(output.state_diffs.len(), txs.len());
+
+        // The transfer creates the recipient and charges the fee payer
+        let state_diff = &output.state_diffs[0];
+        assert_eq!(state_diff.accounts.len(), 2);
+        let payer_diff = state_diff.get(&payer.pubkey()).unwrap();
+        assert_eq!(payer_diff.pre_lamports, LAMPORTS_PER_SOL);
+        assert_eq!(payer_diff.post_lamports, LAMPORTS_PER_SOL - 1_000_000 - fee);
+        assert!(state_diff.get(&recipient.pubkey()).unwrap().was_created());
+
+        // Diffs follow the changes made by earlier transactions in the batch
+        let recipient_diff = output.state_diffs[1].get(&recipient.pubkey()).unwrap();
+        assert_eq!(recipient_diff.pre_lamports, 1_000_000);
+        assert_eq!(recipient_diff.pre_data_len, 0);
+        assert_eq!(recipient_diff.post_data_len, 8);
+        assert_eq!(
+            recipient_diff.data_changes,
+            vec![DataRangeDiff {
+                offset: 0,
+                pre_bytes: vec![],
+                post_bytes: vec![0; 8],
+            }]
+        );
+
+        // Failed transactions only pay the fee
+        let state_diff = &output.state_diffs[2];
+        assert_eq!(state_diff.accounts.len(), 1);
+        let payer_diff = state_diff.get(&payer.pubkey()).unwrap();
+        assert_eq!(payer_diff.pre_lamports - payer_diff.post_lamports, fee);
+
+        // Nothing was committed
+        assert!(host.get_account(&recipient.pubkey()).is_none());
+        let output =
+            host.load_and_execute_transactions(&txs, &TransactionProcessingConfig::default());
+        assert!(output.state_diffs.is_empty());
+    }
+
+    #[test]
+    fn test_svm_host_advance_slot() {
+        let mut host = SVMHost::new(SVMHostConfig {
+            genesis_unix_timestamp: 1_000,
+            ..SVMHostConfig::default()
+        });
+        assert_eq!(host.slot(), SVM_HOST_INITIAL_SLOT);
+        let payer = Keypair::new();
+        host.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL);
+        let blockhash = host.latest_blockhash();
+
+        host.advance_slot();
+        assert_eq!(host.slot(), 2);
+        assert_ne!(host.latest_blockhash(), blockhash);
+        assert!(host.is_blockhash_valid(&blockhash));
+        let sysvar_clock = host.processor().sysvar_cache().get_clock().unwrap();
+        assert_eq!(*sysvar_clock, *host.clock());
+        let slot_hashes = host.processor().sysvar_cache().get_slot_hashes().unwrap();
+        assert_eq!(slot_hashes.get(&SVM_HOST_INITIAL_SLOT), Some(&blockhash));
+
+        let tx = system_transaction::transfer(&payer, &Pubkey::new_unique(), 1_000_000, blockhash);
+        assert_eq!(host.process_transaction(tx).flattened_result(), Ok(()));
+
+        let slots_per_epoch = host.rent_collector.epoch_schedule.get_slots_in_epoch(0);
+        host.warp_to_slot(10 * slots_per_epoch);
+        assert_eq!(host.slot(), 10 * slots_per_epoch);
+        assert_eq!(
+            host.clock().unix_timestamp,
+            1_000 + (host.slot() * DEFAULT_MS_PER_SLOT / 1_000) as UnixTimestamp
+        );
+        assert_eq!(
+            host.clock().epoch_start_timestamp,
+            host.clock().unix_timestamp
+        );
+        assert_eq!(
+            host.processor().sysvar_cache().get_clock().unwrap().epoch,
+            host.epoch()
+        );
+
+        for _ in 0..MAX_PROCESSING_AGE {
+            host.advance_slot();
+        }
+        assert!(!host.is_blockhash_valid(&blockhash));
+        let tx = system_transaction::transfer(&payer, &Pubkey::new_unique(), 1, blockhash);
+        assert_eq!(
+            host.process_transaction(tx).flattened_result(),
+            Err(TransactionError::BlockhashNotFound)
+        );
+    }
+
+    #[test]
+    fn test_svm_host_address_lookup_table() {
+        let mut host = SVMHost::default();
+        let payer = Keypair::new();
+        host.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL);
+        let recipient = Pubkey::new_unique();
+
+        // The table is created and extended through the builtin program
+        let (create_ix, table_address) =
+            create_lookup_table(payer.pubkey(), payer.pubkey(), host.slot() - 1);
+        let extend_ix = extend_lookup_table(
+            table_address,
+            payer.pubkey(),
+            Some(payer.pubkey()),
+            vec![recipient],
+        );
+        let tx = Transaction::new_signed_with_payer(
+            &[create_ix, extend_ix],
+            Some(&payer.pubkey()),
+            &[&payer],
+            host.latest_blockhash(),
+        );
+        assert_eq!(host.process_transaction(tx).flattened_result(), Ok(()));
+        let table_account = host.get_account(&table_address).unwrap();
+        assert_eq!(table_account.owner(), &address_lookup_table::program::id());
+
+        // Addresses can only be looked up after the slot they were added in
+        let transfer_message = |host: &SVMHost| {
+            v0::Message::try_compile(
+                &payer.pubkey(),
+                &[system_instruction::transfer(
+                    &payer.pubkey(),
+                    &recipient,
+                    1_000_000,
+                )],
+                &[AddressLookupTableAccount {
+                    key: table_address,
+                    addresses: vec![recipient],
+                }],
+                host.latest_blockhash(),
+            )
+            .unwrap()
+        };
+        let sign = |message: v0::Message| {
+            VersionedTransaction::try_new(VersionedMessage::V0(message), &[&payer]).unwrap()
+        };
+        assert_eq!(
+            host.process_transaction(sign(transfer_message(&host)))
+                .flattened_result(),
+            Err(TransactionError::InvalidAddressLookupTableIndex)
+        );
+
+        host.advance_slot();
+        let message = transfer_message(&host);
+        assert_eq!(message.address_table_lookups.len(), 1);
+        assert_eq!(
+            host.process_transaction(sign(message)).flattened_result(),
+            Ok(())
+        );
+        assert_eq!(host.get_account(&recipient).unwrap().lamports(), 1_000_000);
+
+        // Lookups in accounts which aren't tables are rejected
+        let mut message = transfer_message(&host);
+        message.address_table_lookups[0].account_key = payer.pubkey();
+        assert_eq!(
+            host.sanitize_transaction(sign(message)).unwrap_err(),
+            TransactionError::InvalidAddressLookupTableOwner
+        );
+    }
+}
@@ -6,7 +6,9 @@
 pub use solana_sdk::inner_instruction::{InnerInstruction, InnerInstructionsList};
 use {
     crate::account_loader::LoadedTransaction,
-    solana_program_runtime::loaded_programs::ProgramCacheEntry,
+    solana_program_runtime::{
+        invoke_context::InstructionTrace, loaded_programs::ProgramCacheEntry,
+    },
     solana_sdk::{pubkey::Pubkey, transaction, transaction_context::TransactionReturnData},
     std::{collections::HashMap, sync::Arc},
 };
@@ -40,6 +42,8 @@ pub struct TransactionExecutionDetails {
     /// The change in accounts data len for this transaction.
     /// NOTE: This value is valid IFF `status` is `Ok`.
     pub accounts_data_len_delta: i64,
+    /// The executed instructions, in the order of the instruction trace.
+    pub instruction_traces: Option<Vec<InstructionTrace>>,
 }
 
 impl TransactionExecutionDetails {
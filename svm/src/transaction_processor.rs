@@ -7,7 +7,7 @@ use {
             AccountUsagePattern, CheckedTransactionDetails, LoadedTransaction,
             TransactionCheckResult, TransactionLoadResult, ValidatedTransactionDetails,
         },
-        account_overrides::AccountOverrides,
+        account_overrides::{AccountOverrides, AccountOverridesCallback},
         message_processor::MessageProcessor,
         nonce_info::NonceInfo,
         program_loader::{get_program_modification_slot, load_program_with_pubkey},
@@ -17,6 +17,7 @@ use {
         transaction_execution_result::{ExecutedTransaction, TransactionExecutionDetails},
         transaction_processing_callback::TransactionProcessingCallback,
         transaction_processing_result::{ProcessedTransaction, TransactionProcessingResult},
+        transaction_state_diff::TransactionStateDiff,
     },
     log::debug,
     percentage::Percentage,
@@ -46,6 +47,7 @@ use {
     solana_sdk::{
         account::{AccountSharedData, ReadableAccount, PROGRAM_OWNERS},
         account_utils::StateMut,
+        bpf_loader_upgradeable,
         clock::{Epoch, Slot},
         fee::{FeeBudgetLimits, FeeStructure},
         hash::Hash,
@@ -85,6 +87,9 @@ pub struct LoadAndExecuteSanitizedTransactionsOutput {
     /// could not be processed. Note processed transactions can still have a
     /// failure result meaning that the transaction will be rolled back.
     pub processing_results: Vec<TransactionProcessingResult>,
+    /// The accounts changed by each transaction, if `record_state_diffs` was set in the
+    /// config. Otherwise empty.
+    pub state_diffs: Vec<TransactionStateDiff>,
 }
 
 /// Configuration of the recording capabilities for transaction execution
@@ -125,6 +130,10 @@ pub struct TransactionProcessingConfig<'a> {
     pub recording_config: ExecutionRecordingConfig,
     /// The max number of accounts that a transaction may lock.
     pub transaction_account_lock_limit: Option<usize>,
+    /// Whether to record the accounts changed by each transaction.
+    pub record_state_diffs: bool,
+    /// Whether to record an instruction trace of each executed transaction.
+    pub record_instruction_traces: bool,
 }
 
 /// Runtime environment for transaction batch processing.
@@ -349,23 +358,49 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
         let mut error_metrics = TransactionErrorMetrics::default();
         let mut execute_timings = ExecuteTimings::default();
         let mut processing_results = Vec::with_capacity(sanitized_txs.len());
+        let mut state_diffs = Vec::with_capacity(if config.record_state_diffs {
+            sanitized_txs.len()
+        } else {
+            0
+        });
 
         let native_loader = native_loader::id();
-        let (program_accounts_map, filter_executable_us) = measure_us!({
-            let mut program_accounts_map = Self::filter_executable_program_accounts(
-                callbacks,
-                sanitized_txs,
-                &check_results,
-                PROGRAM_OWNERS,
-            );
-            for builtin_program in self.builtin_program_ids.read().unwrap().iter() {
-                program_accounts_map.insert(*builtin_program, (&native_loader, 0));
-            }
-            program_accounts_map
-        });
+        let overridden_callbacks = config
+            .account_overrides
+            .filter(|account_overrides| !account_overrides.is_empty())
+            .map(|account_overrides| AccountOverridesCallback::new(account_overrides, callbacks));
+        let ((mut program_accounts_map, overridden_programs), filter_executable_us) =
+            measure_us!({
+                let (mut program_accounts_map, overridden_programs) =
+                    if let Some(overridden_callbacks) = &overridden_callbacks {
+                        let mut program_accounts_map = Self::filter_executable_program_accounts(
+                            overridden_callbacks,
+                            sanitized_txs,
+                            &check_results,
+                            PROGRAM_OWNERS,
+                        );
+                        let overridden_programs = Self::take_overridden_programs(
+                            config.account_overrides.unwrap(),
+                            &mut program_accounts_map,
+                        );
+                        (program_accounts_map, overridden_programs)
+                    } else {
+                        let program_accounts_map = Self::filter_executable_program_accounts(
+                            callbacks,
+                            sanitized_txs,
+                            &check_results,
+                            PROGRAM_OWNERS,
+                        );
+                        (program_accounts_map, vec![])
+                    };
+                for builtin_program in self.builtin_program_ids.read().unwrap().iter() {
+                    program_accounts_map.insert(*builtin_program, (&native_loader, 0));
+                }
+                (program_accounts_map, overridden_programs)
+            });
 
         let (program_cache_for_tx_batch, program_cache_us) = measure_us!({
-            let program_cache_for_tx_batch = self.replenish_program_cache(
+            let mut program_cache_for_tx_batch = self.replenish_program_cache(
                 callbacks,
                 &program_accounts_map,
                 &mut execute_timings,
@@ -380,9 +415,32 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
                     processing_results: (0..sanitized_txs.len())
                         .map(|_| Err(TransactionError::ProgramCacheHitMaxLimit))
                         .collect(),
+                    state_diffs: if config.record_state_diffs {
+                        vec![TransactionStateDiff::default(); sanitized_txs.len()]
+                    } else {
+                        vec![]
+                    },
                 };
             }
 
+            // Overridden programs are only loaded into the cache of this batch, so that they
+            // never reach the global program cache.
+            if let Some(overridden_callbacks) = &overridden_callbacks {
+                for (program_id, program_account) in overridden_programs {
+                    if let Some(program) = load_program_with_pubkey(
+                        overridden_callbacks,
+                        &program_cache_for_tx_batch.environments,
+                        &program_id,
+                        self.slot,
+                        &mut execute_timings,
+                        false,
+                    ) {
+                        program_cache_for_tx_batch.replenish(program_id, program);
+                    }
+                    program_accounts_map.insert(program_id, program_account);
+                }
+            }
+
             program_cache_for_tx_batch
         });
 
@@ -412,6 +470,10 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
         // in the same batch may modify the same accounts. Transaction order is
         // preserved within entries written to the ledger.
         for (tx, check_result) in sanitized_txs.iter().zip(check_results) {
+            let pre_accounts = config
+                .record_state_diffs
+                .then(|| Self::writable_accounts(&mut account_loader, tx));
+
             let (validate_result, single_validate_fees_us) =
                 measure_us!(check_result.and_then(|tx_details| {
                     Self::validate_transaction_nonce_and_fee_payer(
@@ -474,6 +536,20 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
             });
             execution_us = execution_us.saturating_add(single_execution_us);
 
+            if let Some(pre_accounts) = pre_accounts {
+                let state_diff = if processing_result.is_ok() {
+                    let post_accounts = Self::writable_accounts(&mut account_loader, tx);
+                    TransactionStateDiff::new(
+                        pre_accounts
+                            .iter()
+                            .zip(&post_accounts)
+                            .map(|((pubkey, pre), (_, post))| (*pubkey, pre, post)),
+                    )
+                } else {
+                    TransactionStateDiff::default()
+                };
+                state_diffs.push(state_diff);
+            }
             processing_results.push(processing_result);
         }
 
@@ -514,9 +590,24 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
             error_metrics,
             execute_timings,
             processing_results,
+            state_diffs,
         }
     }
 
+    /// The current state of the writable accounts of `tx`, including the changes made by the
+    /// transactions before it in the batch
+    fn writable_accounts<CB: TransactionProcessingCallback>(
+        account_loader: &mut AccountLoader<CB>,
+        tx: &impl SVMMessage,
+    ) -> Vec<(Pubkey, AccountSharedData)> {
+        tx.account_keys()
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| tx.is_writable(*index))
+            .map(|(_, pubkey)| (*pubkey, account_loader.peek_account(pubkey)))
+            .collect()
+    }
+
     fn validate_transaction_nonce_and_fee_payer<CB: TransactionProcessingCallback>(
         account_loader: &mut AccountLoader<CB>,
         message: &impl SVMMessage,
@@ -695,6 +786,35 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
         }
     }
 
+    /// Removes the programs whose accounts are overridden from `program_accounts_map`. For
+    /// upgradeable programs, overriding the program data account overrides the program.
+    fn take_overridden_programs<'a>(
+        account_overrides: &AccountOverrides,
+        program_accounts_map: &mut HashMap<Pubkey, (&'a Pubkey, u64)>,
+    ) -> Vec<(Pubkey, (&'a Pubkey, u64))> {
+        let overridden_program_ids: Vec<Pubkey> = program_accounts_map
+            .iter()
+            .filter(|(program_id, (owner, _))| {
+                account_overrides.get(program_id).is_some()
+                    || (bpf_loader_upgradeable::check_id(owner)
+                        && account_overrides
+                            .get(&bpf_loader_upgradeable::get_program_data_address(
+                                program_id,
+                            ))
+                            .is_some())
+            })
+            .map(|(program_id, _)| *program_id)
+            .collect();
+        overridden_program_ids
+            .into_iter()
+            .filter_map(|program_id| {
+                program_accounts_map
+                    .remove(&program_id)
+                    .map(|program_account| (program_id, program_account))
+            })
+            .collect()
+    }
+
     /// Returns a map from executable program accounts (all accounts owned by any loader)
     /// to their usage counters, for the transactions with a valid blockhash or nonce.
     fn filter_executable_program_accounts<'a, CB: TransactionProcessingCallback>(
@@ -980,7 +1100,19 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
         };
 
         let mut executed_units = 0u64;
-        let sysvar_cache = &self.sysvar_cache.read().unwrap();
+        let sysvar_cache_guard = self.sysvar_cache.read().unwrap();
+        // Simulations may run against an overridden clock
+        let overridden_sysvar_cache = config
+            .account_overrides
+            .and_then(|account_overrides| account_overrides.clock())
+            .map(|clock| {
+                let mut sysvar_cache = SysvarCache::clone(&sysvar_cache_guard);
+                sysvar_cache.set_clock(&clock);
+                sysvar_cache
+            });
+        let sysvar_cache = overridden_sysvar_cache
+            .as_ref()
+            .unwrap_or(&*sysvar_cache_guard);
         let epoch_vote_account_stake_callback =
             |pubkey| callback.get_current_epoch_vote_account_stake(pubkey);
 
@@ -998,6 +1130,9 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
             log_collector.clone(),
             compute_budget,
         );
+        if config.record_instruction_traces {
+            invoke_context.enable_instruction_tracing();
+        }
 
         let mut process_message_time = Measure::start("process_message_time");
         let process_result = MessageProcessor::process_message(
@@ -1009,6 +1144,7 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
         );
         process_message_time.stop();
 
+        let instruction_traces = invoke_context.take_instruction_traces();
         drop(invoke_context);
 
         saturating_add_assign!(
@@ -1101,6 +1237,7 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
                 return_data,
                 executed_units,
                 accounts_data_len_delta,
+                instruction_traces,
             },
             loaded_transaction,
             programs_modified_by_tx: program_cache_for_tx_batch.drain_modified_entries(),
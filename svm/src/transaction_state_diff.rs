@@ -0,0 +1,228 @@
+use {
+    solana_sdk::{
+        account::{AccountSharedData, ReadableAccount},
+        pubkey::Pubkey,
+    },
+    std::ops::Range,
+};
+
+/// The accounts changed by a transaction, as they would be committed. Transactions which
+/// failed only change their fee payer and nonce accounts.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TransactionStateDiff {
+    pub accounts: Vec<AccountStateDiff>,
+}
+
+impl TransactionStateDiff {
+    /// Build the diff from the state of the writable accounts of a transaction before and
+    /// after processing it. Accounts without lamports are treated as nonexistent.
+    pub fn new<'a>(
+        accounts: impl IntoIterator<Item = (Pubkey, &'a AccountSharedData, &'a AccountSharedData)>,
+    ) -> Self {
+        Self {
+            accounts: accounts
+                .into_iter()
+                .filter_map(|(pubkey, pre, post)| AccountStateDiff::new(pubkey, pre, post))
+                .collect(),
+        }
+    }
+
+    pub fn get(&self, pubkey: &Pubkey) -> Option<&AccountStateDiff> {
+        self.accounts.iter().find(|diff| diff.pubkey == *pubkey)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountStateDiff {
+    pub pubkey: Pubkey,
+    pub pre_lamports: u64,
+    pub post_lamports: u64,
+    pub pre_owner: Pubkey,
+    pub post_owner: Pubkey,
+    pub pre_executable: bool,
+    pub post_executable: bool,
+    pub pre_data_len: usize,
+    pub post_data_len: usize,
+    /// The byte ranges of the data which changed, including bytes added or removed by
+    /// resizing the account
+    pub data_changes: Vec<DataRangeDiff>,
+}
+
+impl AccountStateDiff {
+    /// Returns `None` if the account didn't change
+    pub fn new(pubkey: Pubkey, pre: &AccountSharedData, post: &AccountSharedData) -> Option<Self> {
+        let empty_account = AccountSharedData::default();
+        let pre = if pre.lamports() == 0 {
+            &empty_account
+        } else {
+            pre
+        };
+        let post = if post.lamports() == 0 {
+            &empty_account
+        } else {
+            post
+        };
+
+        let data_changes = if pre.data() == post.data() {
+            vec![]
+        } else {
+            DataRangeDiff::diff(pre.data(), post.data())
+        };
+        if pre.lamports() == post.lamports()
+            && pre.owner() == post.owner()
+            && pre.executable() == post.executable()
+            && data_changes.is_empty()
+        {
+            return None;
+        }
+        Some(Self {
+            pubkey,
+            pre_lamports: pre.lamports(),
+            post_lamports: post.lamports(),
+            pre_owner: *pre.owner(),
+            post_owner: *post.owner(),
+            pre_executable: pre.executable(),
+            post_executable: post.executable(),
+            pre_data_len: pre.data().len(),
+            post_data_len: post.data().len(),
+            data_changes,
+        })
+    }
+
+    pub fn was_created(&self) -> bool {
+        self.pre_lamports == 0 && self.post_lamports != 0
+    }
+
+    pub fn was_closed(&self) -> bool {
+        self.pre_lamports != 0 && self.post_lamports == 0
+    }
+}
+
+/// Changed ranges separated by at most this many unchanged bytes are merged, so that scattered
+/// writes to a struct don't produce one range per field
+const MAX_COALESCED_GAP: usize = 8;
+
+/// A range of changed bytes, which may include short runs of unchanged bytes between changes.
+/// `pre_bytes` and `post_bytes` only differ in length when the range extends past the end of
+/// the shorter data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataRangeDiff {
+    pub offset: usize,
+    pub pre_bytes: Vec<u8>,
+    pub post_bytes: Vec<u8>,
+}
+
+impl DataRangeDiff {
+    fn diff(pre: &[u8], post: &[u8]) -> Vec<Self> {
+        let len = pre.len().max(post.len());
+        let mut ranges: Vec<Range<usize>> = vec![];
+        for offset in (0..len).filter(|offset| pre.get(*offset) != post.get(*offset)) {
+            match ranges.last_mut() {
+                Some(range) if offset - range.end <= MAX_COALESCED_GAP => range.end = offset + 1,
+                _ => ranges.push(offset..offset + 1),
+            }
+        }
+        ranges
+            .into_iter()
+            .map(|range| Self::new(pre, post, range))
+            .collect()
+    }
+
+    fn new(pre: &[u8], post: &[u8], range: Range<usize>) -> Self {
+        let bytes = |data: &[u8]| {
+            let len = data.len();
+            data[range.start.min(len)..range.end.min(len)].to_vec()
+        };
+        Self {
+            offset: range.start,
+            pre_bytes: bytes(pre),
+            post_bytes: bytes(post),
+        }
+    }
+
+    pub fn range(&self) -> Range<usize> {
+        self.offset..self.offset + self.pre_bytes.len().max(self.post_bytes.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(lamports: u64, data: &[u8]) -> AccountSharedData {
+        let mut account = AccountSharedData::new(lamports, data.len(), &Pubkey::default());
+        account.set_data_from_slice(data);
+        account
+    }
+
+    #[test]
+    fn test_data_range_diff() {
+        assert!(DataRangeDiff::diff(&[1, 2, 3], &[1, 2, 3]).is_empty());
+        // Changes separated by a few unchanged bytes are reported as a single range
+        assert_eq!(
+            DataRangeDiff::diff(&[1, 2, 3, 4, 5], &[0, 2, 4, 0, 5, 6, 7]),
+            vec![DataRangeDiff {
+                offset: 0,
+                pre_bytes: vec![1, 2, 3, 4, 5],
+                post_bytes: vec![0, 2, 4, 0, 5, 6, 7],
+            }]
+        );
+        let mut pre = vec![0; 32];
+        pre[1] = 1;
+        pre[10] = 1;
+        pre[20] = 1;
+        let mut post = vec![0; 32];
+        post[1] = 2;
+        post[10] = 2;
+        post[30] = 2;
+        assert_eq!(
+            DataRangeDiff::diff(&pre, &post)
+                .iter()
+                .map(DataRangeDiff::range)
+                .collect::<Vec<_>>(),
+            vec![1..11, 20..21, 30..31]
+        );
+        let shrunk = DataRangeDiff::diff(&[1, 2, 3], &[1]);
+        assert_eq!(
+            shrunk,
+            vec![DataRangeDiff {
+                offset: 1,
+                pre_bytes: vec![2, 3],
+                post_bytes: vec![],
+            }]
+        );
+        assert_eq!(shrunk[0].range(), 1..3);
+    }
+
+    #[test]
+    fn test_account_state_diff() {
+        let pubkey = Pubkey::new_unique();
+        assert_eq!(
+            AccountStateDiff::new(pubkey, &account(1, &[1]), &account(1, &[1])),
+            None
+        );
+        // Accounts without lamports don't exist, whatever their data
+        assert_eq!(
+            AccountStateDiff::new(pubkey, &account(0, &[1]), &AccountSharedData::default()),
+            None
+        );
+
+        let closed =
+            AccountStateDiff::new(pubkey, &account(10, &[1, 2]), &account(0, &[1, 2])).unwrap();
+        assert!(closed.was_closed());
+        assert_eq!(closed.post_data_len, 0);
+        assert_eq!(closed.data_changes[0].range(), 0..2);
+
+        let created =
+            AccountStateDiff::new(pubkey, &AccountSharedData::default(), &account(5, &[])).unwrap();
+        assert!(created.was_created());
+        assert!(created.data_changes.is_empty());
+
+        let state_diff = TransactionStateDiff::new([
+            (pubkey, &account(1, &[1]), &account(1, &[2])),
+            (Pubkey::new_unique(), &account(1, &[1]), &account(1, &[1])),
+        ]);
+        assert_eq!(state_diff.accounts.len(), 1);
+        assert_eq!(state_diff.get(&pubkey).unwrap().data_changes.len(), 1);
+    }
+}
@@ -3,12 +3,13 @@
 
 use {
     crate::mock_bank::{
-        create_custom_loader, deploy_program_with_upgrade_authority, program_address,
-        program_data_size, register_builtins, MockBankCallback, MockForkGraph, EXECUTION_EPOCH,
-        EXECUTION_SLOT, WALLCLOCK_TIME,
+        create_custom_loader, create_program_data_account, deploy_program_with_upgrade_authority,
+        program_address, program_data_size, register_builtins, MockBankCallback, MockForkGraph,
+        EXECUTION_EPOCH, EXECUTION_SLOT, WALLCLOCK_TIME,
     },
     solana_sdk::{
         account::{AccountSharedData, ReadableAccount, WritableAccount},
+        bpf_loader_upgradeable,
         clock::Slot,
         compute_budget::ComputeBudgetInstruction,
         entrypoint::MAX_PERMITTED_DATA_INCREASE,
@@ -27,6 +28,7 @@ use {
     },
     solana_svm::{
         account_loader::{CheckedTransactionDetails, TransactionCheckResult},
+        account_overrides::AccountOverrides,
         nonce_info::NonceInfo,
         rollback_accounts::RollbackAccounts,
         transaction_execution_result::TransactionExecutionDetails,
@@ -2450,6 +2452,87 @@ fn svm_inspect_account() {
     );
 }
 
+// Overriding the program data account of a program runs the overriding binary, for this
+// batch only.
+#[test]
+fn svm_program_data_override() {
+    let mut test_entry = SvmTestEntry::default();
+    let program_name = "hello-solana";
+    let program_id = program_address(program_name);
+    test_entry.add_initial_program(program_name);
+
+    let fee_payer_keypair = Keypair::new();
+    let fee_payer = fee_payer_keypair.pubkey();
+    let mut fee_payer_data = AccountSharedData::default();
+    fee_payer_data.set_lamports(LAMPORTS_PER_SOL);
+    test_entry.add_initial_account(fee_payer, &fee_payer_data);
+
+    test_entry.push_transaction(Transaction::new_signed_with_payer(
+        &[Instruction::new_with_bytes(program_id, &[], vec![])],
+        Some(&fee_payer),
+        &[&fee_payer_keypair],
+        Hash::default(),
+    ));
+
+    let mut env = SvmTestEnvironment::create(test_entry);
+    let execute = |env: &SvmTestEnvironment| {
+        let (transactions, check_results) = env.test_entry.prepare_transactions();
+        let mut result = env.batch_processor.load_and_execute_sanitized_transactions(
+            &env.mock_bank,
+            &transactions,
+            check_results,
+            &env.processing_environment,
+            &env.processing_config,
+        );
+        match result.processing_results.pop() {
+            Some(Ok(ProcessedTransaction::Executed(executed_transaction))) => {
+                executed_transaction.execution_details
+            }
+            result => panic!("transaction wasn't executed: {result:?}"),
+        }
+    };
+    let says_hello = |details: &TransactionExecutionDetails| {
+        details
+            .log_messages
+            .as_ref()
+            .unwrap()
+            .iter()
+            .any(|log| log.contains("Hello, Solana!"))
+    };
+
+    let details = execute(&env);
+    assert!(details.status.is_ok());
+    assert!(says_hello(&details));
+    assert!(details.return_data.is_none());
+
+    // Replace the binary with one returning the clock
+    let mut account_overrides = AccountOverrides::default();
+    account_overrides.set_account(
+        &bpf_loader_upgradeable::get_program_data_address(&program_id),
+        Some(create_program_data_account(
+            "clock-sysvar".to_string(),
+            DEPLOYMENT_SLOT,
+        )),
+    );
+    env.processing_config.account_overrides = Some(&account_overrides);
+    let details = execute(&env);
+    assert!(details.status.is_ok());
+    assert!(!says_hello(&details));
+    assert_eq!(
+        details.return_data.unwrap(),
+        TransactionReturnData {
+            program_id,
+            data: WALLCLOCK_TIME.to_be_bytes().to_vec(),
+        }
+    );
+
+    // The overriding binary never reaches the global program cache
+    env.processing_config.account_overrides = None;
+    let details = execute(&env);
+    assert!(details.status.is_ok());
+    assert!(says_hello(&details));
+}
+
 // Tests for proper accumulation of metrics across loaded programs in a batch.
 #[test]
 fn svm_metrics_accumulation() {
@@ -213,6 +213,19 @@ pub fn deploy_program_with_upgrade_authority(
         .unwrap()
         .insert(program_account, account_data);
 
+    let account_data = create_program_data_account(name, deployment_slot);
+    mock_bank
+        .account_shared_data
+        .write()
+        .unwrap()
+        .insert(program_data_account, account_data);
+
+    program_account
+}
+
+/// The program data account of an upgradeable program holding the binary of `name`
+pub fn create_program_data_account(name: String, deployment_slot: Slot) -> AccountSharedData {
+    let rent = Rent::default();
     let mut account_data = AccountSharedData::default();
     let state = UpgradeableLoaderState::ProgramData {
         slot: deployment_slot,
@@ -232,13 +245,7 @@ pub fn deploy_program_with_upgrade_authority(
     account_data.set_lamports(rent.minimum_balance(header.len()));
     account_data.set_owner(bpf_loader_upgradeable::id());
     account_data.set_data(header);
-    mock_bank
-        .account_shared_data
-        .write()
-        .unwrap()
-        .insert(program_data_account, account_data);
-
-    program_account
+    account_data
 }
 
 pub fn register_builtins(
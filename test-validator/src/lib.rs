@@ -4,7 +4,9 @@ use {
     crossbeam_channel::Receiver,
     log::*,
     solana_accounts_db::{
-        accounts_db::AccountsDbConfig, accounts_index::AccountsIndexConfig,
+        accounts_db::{AccountStorageEntry, AccountsDbConfig},
+        accounts_file::StorageAccess,
+        accounts_index::AccountsIndexConfig,
         hardened_unpack::MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
         utils::create_accounts_run_and_snapshot_dirs,
     },
@@ -13,6 +15,7 @@ use {
     solana_core::{
         admin_rpc_post_init::AdminRpcRequestMetadataPostInit,
         consensus::tower_storage::TowerStorage,
+        time_control::TimeControl,
         validator::{Validator, ValidatorConfig, ValidatorStartProgress},
     },
     solana_feature_set::FEATURE_NAMES,
@@ -37,7 +40,9 @@ use {
         bank_forks::BankForks,
         genesis_utils::{self, create_genesis_config_with_leader_ex_no_features},
         runtime_config::RuntimeConfig,
+        snapshot_archive_info::{FullSnapshotArchiveInfo, IncrementalSnapshotArchiveInfo},
         snapshot_config::SnapshotConfig,
+        snapshot_utils,
     },
     solana_sdk::{
         account::{Account, AccountSharedData, ReadableAccount, WritableAccount},
@@ -176,6 +181,33 @@ impl Default for TestValidatorGenesis {
     }
 }
 
+/// Returns the latest version of the accounts in `storages` that match `filter`, given their
+/// address and owner, skipping the accounts that have been closed.
+fn load_latest_accounts(
+    storages: &[(Slot, Arc<AccountStorageEntry>)],
+    filter: impl Fn(&Pubkey, &Pubkey) -> bool,
+) -> HashMap<Pubkey, AccountSharedData> {
+    // Scan the storages from the newest to the oldest slot, so that the first version seen of an
+    // account is its latest one
+    let mut storages: Vec<_> = storages.iter().collect();
+    storages.sort_unstable_by(|(slot, _), (other_slot, _)| other_slot.cmp(slot));
+
+    let mut seen = HashSet::new();
+    let mut accounts = HashMap::new();
+    for (_, storage) in storages {
+        storage.accounts.scan_accounts(|stored_account| {
+            let address = stored_account.pubkey();
+            if seen.insert(*address)
+                && stored_account.lamports() != 0
+                && filter(address, stored_account.owner())
+            {
+                accounts.insert(*address, stored_account.to_account_shared_data());
+            }
+        });
+    }
+    accounts
+}
+
 fn try_transform_program_data(
     address: &Pubkey,
     account: &mut AccountSharedData,
@@ -431,6 +463,121 @@ impl TestValidatorGenesis {
         Ok(self)
     }
 
+    /// Clone accounts from a full snapshot archive on disk, and optionally an incremental
+    /// snapshot archive based on it, instead of fetching them over RPC.
+    ///
+    /// The accounts at `addresses` are cloned, as well as all accounts owned by one of the
+    /// `owners` programs. The executable data accounts of the cloned upgradeable programs are
+    /// cloned too. If `skip_missing` is not set, an error is returned if one of the
+    /// `addresses` does not exist in the snapshot.
+    pub fn clone_accounts_from_snapshot_archives<T, U>(
+        &mut self,
+        full_snapshot_archive_path: &Path,
+        incremental_snapshot_archive_path: Option<&Path>,
+        addresses: T,
+        owners: U,
+        skip_missing: bool,
+    ) -> Result<&mut Self, String>
+    where
+        T: IntoIterator<Item = Pubkey>,
+        U: IntoIterator<Item = Pubkey>,
+    {
+        let addresses: HashSet<Pubkey> = addresses.into_iter().collect();
+        let owners: HashSet<Pubkey> = owners.into_iter().collect();
+
+        let full_snapshot_archive_info =
+            FullSnapshotArchiveInfo::new_from_path(full_snapshot_archive_path.to_path_buf())
+                .map_err(|err| {
+                    format!(
+                        "Invalid full snapshot archive {}: {err}",
+                        full_snapshot_archive_path.display()
+                    )
+                })?;
+        let incremental_snapshot_archive_info = incremental_snapshot_archive_path
+            .map(|path| {
+                IncrementalSnapshotArchiveInfo::new_from_path(path.to_path_buf()).map_err(|err| {
+                    format!(
+                        "Invalid incremental snapshot archive {}: {err}",
+                        path.display()
+                    )
+                })
+            })
+            .transpose()?;
+
+        let unpack_dir = tempfile::tempdir()
+            .map_err(|err| format!("Failed to create a directory to unpack snapshots: {err}"))?;
+        let bank_snapshots_dir = unpack_dir.path().join("snapshot");
+        let account_path = unpack_dir.path().join("accounts");
+        for dir in [&bank_snapshots_dir, &account_path] {
+            fs::create_dir_all(dir)
+                .map_err(|err| format!("Failed to create {}: {err}", dir.display()))?;
+        }
+
+        info!(
+            "Unpacking {} and {:?}...",
+            full_snapshot_archive_path.display(),
+            incremental_snapshot_archive_path
+        );
+        let (unarchived_full_snapshot, unarchived_incremental_snapshot, _) =
+            snapshot_utils::verify_and_unarchive_snapshots(
+                &bank_snapshots_dir,
+                &full_snapshot_archive_info,
+                incremental_snapshot_archive_info.as_ref(),
+                &[account_path],
+                StorageAccess::default(),
+            )
+            .map_err(|err| format!("Failed to unpack snapshot archives: {err}"))?;
+        let storages: Vec<_> = unarchived_full_snapshot
+            .storage
+            .iter()
+            .chain(
+                unarchived_incremental_snapshot
+                    .iter()
+                    .flat_map(|unarchived_snapshot| unarchived_snapshot.storage.iter()),
+            )
+            .map(|entry| (*entry.key(), entry.value().storage.clone()))
+            .collect();
+
+        let mut accounts = load_latest_accounts(&storages, |address, owner| {
+            addresses.contains(address) || owners.contains(owner)
+        });
+        let programdata_addresses: HashSet<Pubkey> = accounts
+            .values()
+            .filter(|account| account.owner() == &solana_sdk::bpf_loader_upgradeable::id())
+            .filter_map(|account| match account.deserialize_data() {
+                Ok(UpgradeableLoaderState::Program {
+                    programdata_address,
+                }) => Some(programdata_address),
+                _ => None,
+            })
+            .filter(|programdata_address| !accounts.contains_key(programdata_address))
+            .collect();
+        if !programdata_addresses.is_empty() {
+            accounts.extend(load_latest_accounts(&storages, |address, _| {
+                programdata_addresses.contains(address)
+            }));
+        }
+
+        for address in &addresses {
+            if accounts.contains_key(address) {
+                continue;
+            }
+            if skip_missing {
+                warn!("Could not find {} in the snapshot, skipping.", address);
+            } else {
+                return Err(format!("Failed to find {address} in the snapshot"));
+            }
+        }
+
+        info!("Cloning {} accounts from the snapshot", accounts.len());
+        for (address, mut account) in accounts {
+            // ignore the error
+            try_transform_program_data(&address, &mut account).ok();
+            self.add_account(address, account);
+        }
+        Ok(self)
+    }
+
     pub fn clone_feature_set(&mut self, rpc_client: &RpcClient) -> Result<&mut Self, String> {
         for feature_ids in FEATURE_NAMES
             .keys()
@@ -1027,6 +1174,7 @@ impl TestValidator {
             staked_nodes_overrides: config.staked_nodes_overrides.clone(),
             accounts_db_config,
             runtime_config,
+            enable_time_control: true,
             ..ValidatorConfig::default_for_test()
         };
         if let Some(ref tower_storage) = config.tower_storage {
@@ -1173,6 +1321,17 @@ impl TestValidator {
     pub fn repair_whitelist(&self) -> Arc<RwLock<HashSet<Pubkey>>> {
         Arc::new(RwLock::new(HashSet::default()))
     }
+
+    /// Warps forward, sets the clock and pauses slot production while the validator is running.
+    /// The same controls are available over the admin RPC, see `agave-validator time-control`.
+    pub fn time_control(&self) -> Arc<TimeControl> {
+        self.validator
+            .as_ref()
+            .unwrap()
+            .time_control()
+            .expect("time control is enabled for the test validator")
+            .clone()
+    }
 }
 
 impl Drop for TestValidator {
@@ -1194,7 +1353,19 @@ impl Drop for TestValidator {
 
 #[cfg(test)]
 mod test {
-    use {super::*, solana_sdk::feature::Feature};
+    use {
+        super::*,
+        solana_runtime::{
+            bank::Bank,
+            genesis_utils::GenesisConfigInfo,
+            snapshot_archive_info::SnapshotArchiveInfoGetter,
+            snapshot_bank_utils::{
+                bank_to_full_snapshot_archive, bank_to_incremental_snapshot_archive,
+            },
+            snapshot_utils::ArchiveFormat,
+        },
+        solana_sdk::feature::Feature,
+    };
 
     #[test]
     fn get_health() {
@@ -1300,4 +1471,155 @@ mod test {
         let feature_state: Feature = bincode::deserialize(feature_account.data()).unwrap();
         assert!(feature_state.activated_at.is_some());
     }
+
+    #[test]
+    fn test_clone_accounts_from_snapshot_archives() {
+        let GenesisConfigInfo { genesis_config, .. } =
+            genesis_utils::create_genesis_config(sol_to_lamports(1_000_000.));
+        let (bank0, bank_forks) = Bank::new_with_bank_forks_for_tests(&genesis_config);
+        let collector = Pubkey::new_unique();
+        let lamports = genesis_config.rent.minimum_balance(0);
+        let owner = Pubkey::new_unique();
+        let other_owner = Pubkey::new_unique();
+
+        let address = Pubkey::new_unique();
+        let owned_address = Pubkey::new_unique();
+        let closed_address = Pubkey::new_unique();
+        let reassigned_address = Pubkey::new_unique();
+        let unrelated_address = Pubkey::new_unique();
+        let program_address = Pubkey::new_unique();
+        let programdata_address = Pubkey::new_unique();
+
+        let program_account = AccountSharedData::new_data(
+            1_000_000,
+            &UpgradeableLoaderState::Program {
+                programdata_address,
+            },
+            &solana_sdk::bpf_loader_upgradeable::id(),
+        )
+        .unwrap();
+        let mut programdata_account = AccountSharedData::new(
+            1_000_000,
+            UpgradeableLoaderState::size_of_programdata_metadata() + 3,
+            &solana_sdk::bpf_loader_upgradeable::id(),
+        );
+        bincode::serialize_into(
+            programdata_account.data_as_mut_slice(),
+            &UpgradeableLoaderState::ProgramData {
+                slot: 1,
+                upgrade_authority_address: None,
+            },
+        )
+        .unwrap();
+
+        // Store the accounts in the slot of the full snapshot
+        let bank1 = bank_forks
+            .write()
+            .unwrap()
+            .insert(Bank::new_from_parent(bank0, &collector, 1))
+            .clone_without_scheduler();
+        bank1.store_account(&address, &AccountSharedData::new(lamports, 0, &other_owner));
+        for owned_address in [owned_address, closed_address, reassigned_address] {
+            bank1.store_account(&owned_address, &AccountSharedData::new(lamports, 0, &owner));
+        }
+        bank1.store_account(
+            &unrelated_address,
+            &AccountSharedData::new(lamports, 0, &other_owner),
+        );
+        bank1.store_account(&program_address, &program_account);
+        bank1.store_account(&programdata_address, &programdata_account);
+        bank1.fill_bank_with_ticks_for_tests();
+
+        let bank_snapshots_dir = tempfile::TempDir::new().unwrap();
+        let full_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let incremental_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let full_snapshot_archive_info = bank_to_full_snapshot_archive(
+            bank_snapshots_dir.path(),
+            &bank1,
+            None,
+            full_snapshot_archives_dir.path(),
+            incremental_snapshot_archives_dir.path(),
+            ArchiveFormat::Tar,
+        )
+        .unwrap();
+
+        // Close and reassign accounts in the slot of the incremental snapshot
+        let bank2 = bank_forks
+            .write()
+            .unwrap()
+            .insert(Bank::new_from_parent(bank1.clone(), &collector, 2))
+            .clone_without_scheduler();
+        bank2.store_account(&closed_address, &AccountSharedData::default());
+        bank2.store_account(
+            &reassigned_address,
+            &AccountSharedData::new(lamports, 0, &other_owner),
+        );
+        bank2.fill_bank_with_ticks_for_tests();
+
+        let incremental_snapshot_archive_info = bank_to_incremental_snapshot_archive(
+            bank_snapshots_dir.path(),
+            &bank2,
+            bank1.slot(),
+            None,
+            full_snapshot_archives_dir.path(),
+            incremental_snapshot_archives_dir.path(),
+            ArchiveFormat::Tar,
+        )
+        .unwrap();
+
+        let mut genesis = TestValidatorGenesis::default();
+        genesis
+            .clone_accounts_from_snapshot_archives(
+                full_snapshot_archive_info.path(),
+                Some(incremental_snapshot_archive_info.path()),
+                [address, program_address],
+                [owner],
+                false,
+            )
+            .unwrap();
+
+        let mut cloned_addresses: Vec<_> = genesis.accounts.keys().copied().collect();
+        cloned_addresses.sort();
+        let mut expected_addresses =
+            vec![address, owned_address, program_address, programdata_address];
+        expected_addresses.sort();
+        assert_eq!(cloned_addresses, expected_addresses);
+        assert_eq!(genesis.accounts[&address].owner(), &other_owner);
+        assert_eq!(genesis.accounts[&owned_address].owner(), &owner);
+        assert_eq!(
+            genesis.accounts[&program_address].data(),
+            program_account.data()
+        );
+        assert_eq!(
+            bincode::deserialize::<UpgradeableLoaderState>(
+                genesis.accounts[&programdata_address].data()
+            )
+            .unwrap(),
+            UpgradeableLoaderState::ProgramData {
+                slot: 0,
+                upgrade_authority_address: None,
+            }
+        );
+
+        // Missing addresses are an error unless they are skipped
+        let missing_address = Pubkey::new_unique();
+        assert!(TestValidatorGenesis::default()
+            .clone_accounts_from_snapshot_archives(
+                full_snapshot_archive_info.path(),
+                Some(incremental_snapshot_archive_info.path()),
+                [missing_address],
+                [],
+                false,
+            )
+            .is_err());
+        assert!(TestValidatorGenesis::default()
+            .clone_accounts_from_snapshot_archives(
+                full_snapshot_archive_info.path(),
+                None,
+                [missing_address],
+                [],
+                true,
+            )
+            .is_ok());
+    }
 }
@@ -13,6 +13,7 @@ use {
         admin_rpc_post_init::AdminRpcRequestMetadataPostInit,
         consensus::{tower_storage::TowerStorage, Tower},
         repair::repair_service,
+        time_control::{TimeControl, TimeControlError},
         validator::ValidatorStartProgress,
     },
     solana_geyser_plugin_manager::GeyserPluginManagerRequest,
@@ -20,6 +21,7 @@ use {
     solana_rpc::rpc::verify_pubkey,
     solana_rpc_client_api::{config::RpcAccountIndex, custom_error::RpcCustomError},
     solana_sdk::{
+        clock::{Epoch, Slot, UnixTimestamp},
         exit::Exit,
         pubkey::Pubkey,
         signature::{read_keypair_file, Keypair, Signer},
@@ -243,6 +245,25 @@ pub trait AdminRpc {
         meta: Self::Metadata,
         public_tpu_forwards_addr: SocketAddr,
     ) -> Result<()>;
+
+    #[rpc(meta, name = "warpToSlot")]
+    fn warp_to_slot(&self, meta: Self::Metadata, slot: Slot) -> Result<Slot>;
+
+    #[rpc(meta, name = "warpToEpoch")]
+    fn warp_to_epoch(&self, meta: Self::Metadata, epoch: Epoch) -> Result<Slot>;
+
+    #[rpc(meta, name = "setClockUnixTimestamp")]
+    fn set_clock_unix_timestamp(
+        &self,
+        meta: Self::Metadata,
+        unix_timestamp: UnixTimestamp,
+    ) -> Result<Slot>;
+
+    #[rpc(meta, name = "pauseSlotProduction")]
+    fn pause_slot_production(&self, meta: Self::Metadata) -> Result<()>;
+
+    #[rpc(meta, name = "resumeSlotProduction")]
+    fn resume_slot_production(&self, meta: Self::Metadata) -> Result<()>;
 }
 
 pub struct AdminRpcImpl;
@@ -592,9 +613,10 @@ impl AdminRpc for AdminRpcImpl {
                 .indexes
                 .iter()
                 .filter_map(|index| {
+                    let rpc_account_index = rpc_account_index_from_account_index(index)?;
                     accounts_index
                         .get_index_key_size(index, &index_key)
-                        .map(|size| (rpc_account_index_from_account_index(index), size))
+                        .map(|size| (rpc_account_index, size))
                 })
                 .collect::<HashMap<_, _>>();
 
@@ -679,9 +701,84 @@ impl AdminRpc for AdminRpcImpl {
             Ok(())
         })
     }
+
+    fn warp_to_slot(&self, meta: Self::Metadata, slot: Slot) -> Result<Slot> {
+        debug!("warp_to_slot rpc request received: {slot}");
+
+        Self::with_time_control(&meta, |time_control| {
+            Self::map_time_control_result(time_control.warp_to_slot(slot))
+        })
+    }
+
+    fn warp_to_epoch(&self, meta: Self::Metadata, epoch: Epoch) -> Result<Slot> {
+        debug!("warp_to_epoch rpc request received: {epoch}");
+
+        Self::with_time_control(&meta, |time_control| {
+            Self::map_time_control_result(time_control.warp_to_epoch(epoch))
+        })
+    }
+
+    fn set_clock_unix_timestamp(
+        &self,
+        meta: Self::Metadata,
+        unix_timestamp: UnixTimestamp,
+    ) -> Result<Slot> {
+        debug!("set_clock_unix_timestamp rpc request received: {unix_timestamp}");
+
+        Self::with_time_control(&meta, |time_control| {
+            Self::map_time_control_result(time_control.set_clock_unix_timestamp(unix_timestamp))
+        })
+    }
+
+    fn pause_slot_production(&self, meta: Self::Metadata) -> Result<()> {
+        debug!("pause_slot_production rpc request received");
+
+        Self::with_time_control(&meta, |time_control| {
+            time_control.pause_slot_production();
+            warn!("Slot production paused");
+            Ok(())
+        })
+    }
+
+    fn resume_slot_production(&self, meta: Self::Metadata) -> Result<()> {
+        debug!("resume_slot_production rpc request received");
+
+        Self::with_time_control(&meta, |time_control| {
+            time_control.resume_slot_production();
+            warn!("Slot production resumed");
+            Ok(())
+        })
+    }
 }
 
 impl AdminRpcImpl {
+    fn with_time_control<F, R>(meta: &AdminRpcRequestMetadata, func: F) -> Result<R>
+    where
+        F: FnOnce(&TimeControl) -> Result<R>,
+    {
+        meta.with_post_init(|post_init| match &post_init.time_control {
+            Some(time_control) => func(time_control),
+            None => Err(jsonrpc_core::error::Error::invalid_params(
+                "Time control is not enabled on this validator",
+            )),
+        })
+    }
+
+    fn map_time_control_result(
+        result: std::result::Result<Slot, TimeControlError>,
+    ) -> Result<Slot> {
+        result.map_err(|err| match err {
+            TimeControlError::WarpSlotNotInFuture { .. }
+            | TimeControlError::UnixTimestampInPast { .. } => {
+                jsonrpc_core::error::Error::invalid_params(err.to_string())
+            }
+            err => {
+                error!("Time control request failed: {err}");
+                jsonrpc_core::error::Error::internal_error()
+            }
+        })
+    }
+
     fn add_authorized_voter_keypair(
         meta: AdminRpcRequestMetadata,
         authorized_voter: Keypair,
@@ -734,11 +831,13 @@ impl AdminRpcImpl {
     }
 }
 
-fn rpc_account_index_from_account_index(account_index: &AccountIndex) -> RpcAccountIndex {
+fn rpc_account_index_from_account_index(account_index: &AccountIndex) -> Option<RpcAccountIndex> {
     match account_index {
-        AccountIndex::ProgramId => RpcAccountIndex::ProgramId,
-        AccountIndex::SplTokenOwner => RpcAccountIndex::SplTokenOwner,
-        AccountIndex::SplTokenMint => RpcAccountIndex::SplTokenMint,
+        AccountIndex::ProgramId => Some(RpcAccountIndex::ProgramId),
+        AccountIndex::SplTokenOwner => Some(RpcAccountIndex::SplTokenOwner),
+        AccountIndex::SplTokenMint => Some(RpcAccountIndex::SplTokenMint),
+        // custom indexes cannot be named by a `RpcAccountIndex` key
+        AccountIndex::Custom(_) => None,
     }
 }
 
@@ -961,6 +1060,7 @@ mod tests {
                     cluster_slots: Arc::new(
                         solana_core::cluster_slots_service::cluster_slots::ClusterSlots::default(),
                     ),
+                    time_control: None,
                 }))),
                 staked_nodes_overrides: Arc::new(RwLock::new(HashMap::new())),
                 rpc_to_plugin_manager_sender: None,
@@ -1301,6 +1401,27 @@ mod tests {
 
     // This test checks that the rpc call to `set_identity` works a expected with
     // Bank but without validator.
+    #[test]
+    fn test_time_control_disabled() {
+        let rpc = RpcHandler::start_with_config(TestConfig::default());
+
+        let RpcHandler { io, meta, .. } = rpc;
+
+        for request in [
+            r#"{"jsonrpc":"2.0","id":1,"method":"warpToSlot","params":[1000]}"#,
+            r#"{"jsonrpc":"2.0","id":1,"method":"pauseSlotProduction","params":[]}"#,
+        ] {
+            let response = io.handle_request_sync(request, meta.clone());
+            let actual_parsed_response: Value =
+                serde_json::from_str(&response.expect("actual response"))
+                    .expect("actual response deserialization");
+            assert_eq!(
+                actual_parsed_response["error"]["message"],
+                "Time control is not enabled on this validator"
+            );
+        }
+    }
+
     #[test]
     fn test_set_identity() {
         let rpc = RpcHandler::start_with_config(TestConfig::default());
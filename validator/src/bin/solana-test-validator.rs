@@ -281,6 +281,23 @@ fn main() {
             .map(|v| v.into_iter().collect())
             .unwrap_or_default();
 
+    let clone_snapshot_archive = matches
+        .value_of("clone_snapshot_archive")
+        .map(PathBuf::from);
+    let clone_incremental_snapshot_archive = matches
+        .value_of("clone_incremental_snapshot_archive")
+        .map(PathBuf::from);
+
+    let accounts_to_clone_from_snapshot: HashSet<_> =
+        pubkeys_of(&matches, "clone_account_from_snapshot")
+            .map(|v| v.into_iter().collect())
+            .unwrap_or_default();
+
+    let programs_to_clone_accounts_from_snapshot: HashSet<_> =
+        pubkeys_of(&matches, "clone_program_accounts_from_snapshot")
+            .map(|v| v.into_iter().collect())
+            .unwrap_or_default();
+
     let clone_feature_set = matches.is_present("clone_feature_set");
 
     let warp_slot = if matches.is_present("warp_slot") {
@@ -513,6 +530,19 @@ fn main() {
         }
     }
 
+    if let Some(clone_snapshot_archive) = clone_snapshot_archive {
+        if let Err(e) = genesis.clone_accounts_from_snapshot_archives(
+            &clone_snapshot_archive,
+            clone_incremental_snapshot_archive.as_deref(),
+            accounts_to_clone_from_snapshot,
+            programs_to_clone_accounts_from_snapshot,
+            false,
+        ) {
+            println!("Error: clone_accounts_from_snapshot_archives failed: {e}");
+            exit(1);
+        }
+    }
+
     if clone_feature_set {
         if let Err(e) = genesis.clone_feature_set(
             cluster_rpc_client
@@ -8,6 +8,7 @@ use {
             DEFAULT_ACCOUNTS_SHRINK_OPTIMIZE_TOTAL_SPACE, DEFAULT_ACCOUNTS_SHRINK_RATIO,
         },
         hardened_unpack::MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
+        secondary_index::CustomIndexSpec,
     },
     solana_clap_utils::{
         hidden_unless_forced,
@@ -41,7 +42,10 @@ use {
         },
     },
     solana_sdk::{
-        clock::Slot, epoch_schedule::MINIMUM_SLOTS_PER_EPOCH, hash::Hash, quic::QUIC_PORT_OFFSET,
+        clock::{Epoch, Slot, UnixTimestamp},
+        epoch_schedule::MINIMUM_SLOTS_PER_EPOCH,
+        hash::Hash,
+        quic::QUIC_PORT_OFFSET,
         rpc_port,
     },
     solana_send_transaction_service::send_transaction_service::{
@@ -997,6 +1001,16 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .default_value(&default_args.rpc_bigtable_max_message_size)
                 .help("Max encoding and decoding message size used in Bigtable Grpc client"),
         )
+        .arg(
+            Arg::with_name("rpc_bigtable_local_storage")
+                .long("rpc-bigtable-local-storage")
+                .takes_value(true)
+                .value_name("DIR")
+                .help(
+                    "Keep the long-term ledger history in a local database at this path \
+                     instead of Bigtable",
+                ),
+        )
         .arg(
             Arg::with_name("rpc_pubsub_worker_threads")
                 .long("rpc-pubsub-worker-threads")
@@ -1168,6 +1182,16 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .requires("enable_rpc_transaction_history")
                 .help("Verifies blockstore roots on boot and fixes any gaps"),
         )
+        .arg(
+            Arg::with_name("rpc_enable_send_bundle")
+                .long("rpc-enable-send-bundle")
+                .takes_value(false)
+                .requires("full_rpc_api")
+                .help(
+                    "Accept atomic transaction bundles through the sendBundle JSON RPC method. \
+                     Bundles are only scheduled by the central scheduler",
+                ),
+        )
         .arg(
             Arg::with_name("rpc_max_request_body_size")
                 .long("rpc-max-request-body-size")
@@ -1265,6 +1289,19 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .value_name("INDEX")
                 .help("Enable an accounts index, indexed by the selected account field"),
         )
+        .arg(
+            Arg::with_name("account_index_custom")
+                .long("account-index-custom")
+                .takes_value(true)
+                .multiple(true)
+                .validator(is_parsable::<CustomIndexSpec>)
+                .value_name("PROGRAM_ID:OFFSET")
+                .help(
+                    "Enable an accounts index of the accounts owned by PROGRAM_ID, indexed by \
+                     the 32-byte key at OFFSET in their data. getProgramAccounts uses it for \
+                     memcmp filters on that key.",
+                ),
+        )
         .arg(
             Arg::with_name("account_index_exclude_key")
                 .long(EXCLUDE_KEY)
@@ -1337,6 +1374,18 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .help("Squash multiple account storage files together using this method")
                 .hidden(hidden_unless_forced()),
         )
+        .arg(
+            Arg::with_name("accounts_db_ancient_storage_format")
+                .long("accounts-db-ancient-storage-format")
+                .value_name("FORMAT")
+                .takes_value(true)
+                .possible_values(&["append-vec", "cold", "cold-zstd"])
+                .help(
+                    "Write the account storage files squashed together by packing in this \
+                     format. The cold formats are compressed and can only be written once",
+                )
+                .hidden(hidden_unless_forced()),
+        )
         .arg(
             Arg::with_name("accounts_db_access_storages_method")
                 .long("accounts-db-access-storages-method")
@@ -1986,6 +2035,61 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                         .multiple(true),
                 )
                 .after_help("Note: At least one arg must be used. Using multiple is ok"),
+        )
+        .subcommand(
+            SubCommand::with_name("time-control")
+                .about("Control the passage of time on a validator with time control enabled")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .setting(AppSettings::InferSubcommands)
+                .subcommand(
+                    SubCommand::with_name("warp-to-slot")
+                        .about("Warp forward to a slot")
+                        .arg(
+                            Arg::with_name("slot")
+                                .index(1)
+                                .value_name("SLOT")
+                                .takes_value(true)
+                                .required(true)
+                                .validator(is_slot)
+                                .help("Slot to warp to"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("warp-to-epoch")
+                        .about("Warp forward to the first slot of an epoch")
+                        .arg(
+                            Arg::with_name("epoch")
+                                .index(1)
+                                .value_name("EPOCH")
+                                .takes_value(true)
+                                .required(true)
+                                .validator(is_parsable::<Epoch>)
+                                .help("Epoch to warp to"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("set-clock")
+                        .about("Set the unix timestamp of the Clock sysvar")
+                        .arg(
+                            Arg::with_name("unix_timestamp")
+                                .index(1)
+                                .value_name("UNIX_TIMESTAMP")
+                                .takes_value(true)
+                                .required(true)
+                                .validator(is_parsable::<UnixTimestamp>)
+                                .help("Unix timestamp to set the clock to"),
+                        )
+                        .after_help(
+                            "Note: the clock can only be moved forward. It is set by warping to \
+                             the next slot",
+                        ),
+                )
+                .subcommand(SubCommand::with_name("pause").about("Pause slot production"))
+                .subcommand(SubCommand::with_name("resume").about("Resume slot production"))
+                .after_help(
+                    "Note: warping discards any unrooted forks, including transactions that are \
+                     only processed or confirmed",
+                ),
         );
 }
 
@@ -2774,6 +2878,57 @@ pub fn test_app<'a>(version: &'a str, default_args: &'a DefaultTestArgs) -> App<
                      already exists then this parameter is silently ignored",
                 ),
         )
+        .arg(
+            Arg::with_name("clone_snapshot_archive")
+                .long("clone-snapshot-archive")
+                .value_name("PATH")
+                .takes_value(true)
+                .help(
+                    "Full snapshot archive to copy the accounts selected by --clone-from-snapshot \
+                     and --clone-program-accounts-from-snapshot from, instead of the cluster \
+                     referenced by the --url argument",
+                ),
+        )
+        .arg(
+            Arg::with_name("clone_incremental_snapshot_archive")
+                .long("clone-incremental-snapshot-archive")
+                .value_name("PATH")
+                .takes_value(true)
+                .requires("clone_snapshot_archive")
+                .help(
+                    "Incremental snapshot archive, based on the --clone-snapshot-archive full \
+                     snapshot archive, to copy accounts from",
+                ),
+        )
+        .arg(
+            Arg::with_name("clone_account_from_snapshot")
+                .long("clone-from-snapshot")
+                .value_name("ADDRESS")
+                .takes_value(true)
+                .validator(is_pubkey_or_keypair)
+                .multiple(true)
+                .requires("clone_snapshot_archive")
+                .help(
+                    "Copy an account from the snapshot archive referenced by the \
+                     --clone-snapshot-archive argument into the genesis configuration. If the \
+                     account is an upgradeable program, its executable data is copied too. If \
+                     the ledger already exists then this parameter is silently ignored",
+                ),
+        )
+        .arg(
+            Arg::with_name("clone_program_accounts_from_snapshot")
+                .long("clone-program-accounts-from-snapshot")
+                .value_name("PROGRAM_ID")
+                .takes_value(true)
+                .validator(is_pubkey_or_keypair)
+                .multiple(true)
+                .requires("clone_snapshot_archive")
+                .help(
+                    "Copy all accounts owned by a program from the snapshot archive referenced by \
+                     the --clone-snapshot-archive argument into the genesis configuration. If \
+                     the ledger already exists then this parameter is silently ignored",
+                ),
+        )
         .arg(
             Arg::with_name("warp_slot")
                 .required(false)
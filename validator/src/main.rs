@@ -18,12 +18,13 @@ use {
     rand::{seq::SliceRandom, thread_rng},
     solana_accounts_db::{
         accounts_db::{AccountShrinkThreshold, AccountsDb, AccountsDbConfig, CreateAncientStorage},
-        accounts_file::StorageAccess,
+        accounts_file::{AccountsFileProvider, StorageAccess},
         accounts_index::{
             AccountIndex, AccountSecondaryIndexes, AccountSecondaryIndexesIncludeExclude,
             AccountsIndexConfig, IndexLimitMb, ScanFilter,
         },
         partitioned_rewards::TestPartitionedEpochRewards,
+        secondary_index::CustomIndexSpec,
         utils::{create_all_accounts_run_and_snapshot_dirs, create_and_canonicalize_directories},
     },
     solana_clap_utils::input_parsers::{keypair_of, keypairs_of, pubkey_of, value_of, values_of},
@@ -64,7 +65,7 @@ use {
         snapshot_utils::{self, ArchiveFormat, SnapshotVersion},
     },
     solana_sdk::{
-        clock::{Slot, DEFAULT_S_PER_SLOT},
+        clock::{Epoch, Slot, UnixTimestamp, DEFAULT_S_PER_SLOT},
         commitment_config::CommitmentConfig,
         hash::Hash,
         pubkey::Pubkey,
@@ -892,6 +893,66 @@ pub fn main() {
             );
             return;
         }
+        ("time-control", Some(time_control_subcommand_matches)) => {
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            let runtime = admin_rpc_service::runtime();
+            match time_control_subcommand_matches.subcommand() {
+                ("warp-to-slot", Some(subcommand_matches)) => {
+                    let slot = value_t_or_exit!(subcommand_matches, "slot", Slot);
+                    let warp_slot = runtime
+                        .block_on(async move { admin_client.await?.warp_to_slot(slot).await })
+                        .unwrap_or_else(|err| {
+                            eprintln!("Warp failed: {err}");
+                            exit(1);
+                        });
+                    println!("Warped to slot {warp_slot}");
+                }
+                ("warp-to-epoch", Some(subcommand_matches)) => {
+                    let epoch = value_t_or_exit!(subcommand_matches, "epoch", Epoch);
+                    let warp_slot = runtime
+                        .block_on(async move { admin_client.await?.warp_to_epoch(epoch).await })
+                        .unwrap_or_else(|err| {
+                            eprintln!("Warp failed: {err}");
+                            exit(1);
+                        });
+                    println!("Warped to slot {warp_slot}");
+                }
+                ("set-clock", Some(subcommand_matches)) => {
+                    let unix_timestamp =
+                        value_t_or_exit!(subcommand_matches, "unix_timestamp", UnixTimestamp);
+                    let warp_slot = runtime
+                        .block_on(async move {
+                            admin_client
+                                .await?
+                                .set_clock_unix_timestamp(unix_timestamp)
+                                .await
+                        })
+                        .unwrap_or_else(|err| {
+                            eprintln!("Set clock failed: {err}");
+                            exit(1);
+                        });
+                    println!("Clock set to {unix_timestamp} at slot {warp_slot}");
+                }
+                ("pause", _) => {
+                    runtime
+                        .block_on(async move { admin_client.await?.pause_slot_production().await })
+                        .unwrap_or_else(|err| {
+                            eprintln!("Pause slot production failed: {err}");
+                            exit(1);
+                        });
+                }
+                ("resume", _) => {
+                    runtime
+                        .block_on(async move { admin_client.await?.resume_slot_production().await })
+                        .unwrap_or_else(|err| {
+                            eprintln!("Resume slot production failed: {err}");
+                            exit(1);
+                        });
+                }
+                _ => unreachable!(),
+            }
+            return;
+        }
         _ => unreachable!(),
     };
 
@@ -1305,6 +1366,18 @@ pub fn main() {
             }
         })
         .unwrap_or_default();
+    let ancient_storage_format =
+        matches
+            .value_of("accounts_db_ancient_storage_format")
+            .map(|format| match format {
+                "append-vec" => AccountsFileProvider::AppendVec,
+                "cold" => AccountsFileProvider::ColdStorage,
+                "cold-zstd" => AccountsFileProvider::ColdStorageZstd,
+                _ => {
+                    // clap will enforce one of the above values is given
+                    unreachable!("invalid value given to accounts-db-ancient-storage-format")
+                }
+            });
     let storage_access = matches
         .value_of("accounts_db_access_storages_method")
         .map(|method| match method {
@@ -1357,6 +1430,7 @@ pub fn main() {
         .ok(),
         exhaustively_verify_refcounts: matches.is_present("accounts_db_verify_refcounts"),
         create_ancient_storage,
+        ancient_storage_format,
         test_partitioned_epoch_rewards,
         test_skip_rewrites_but_include_in_bank_hash: matches
             .is_present("accounts_db_test_skip_rewrites"),
@@ -1402,6 +1476,7 @@ pub fn main() {
                 .ok()
                 .map(Duration::from_secs),
             max_message_size: value_t_or_exit!(matches, "rpc_bigtable_max_message_size", usize),
+            local_storage_path: value_t!(matches, "rpc_bigtable_local_storage", PathBuf).ok(),
         })
     } else {
         None
@@ -1496,6 +1571,7 @@ pub fn main() {
                 usize
             )),
             skip_preflight_health_check: matches.is_present("skip_preflight_health_check"),
+            enable_send_bundle: matches.is_present("rpc_enable_send_bundle"),
         },
         on_start_geyser_plugin_config_files,
         geyser_plugin_always_enabled: matches.is_present("geyser_plugin_always_enabled"),
@@ -2117,6 +2193,12 @@ fn process_account_indexes(matches: &ArgMatches) -> AccountSecondaryIndexes {
             "spl-token-owner" => AccountIndex::SplTokenOwner,
             _ => unreachable!(),
         })
+        .chain(
+            values_t!(matches, "account_index_custom", CustomIndexSpec)
+                .unwrap_or_default()
+                .into_iter()
+                .map(AccountIndex::Custom),
+        )
         .collect();
 
     let account_indexes_include_keys: HashSet<Pubkey> =